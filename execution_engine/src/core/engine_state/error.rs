@@ -106,6 +106,9 @@ pub enum Error {
     /// Failed to retrieve accumulation purse from handle payment system contract.
     #[error("Failed to retrieve accumulation purse from the handle payment contract")]
     FailedToRetrieveAccumulationPurse,
+    /// A deploy whose gas was being estimated exceeded the block gas limit.
+    #[error("Exceeds block gas limit of {0}")]
+    ExceedsBlockGasLimit(u64),
 }
 
 impl Error {
@@ -3,7 +3,8 @@
 use std::collections::VecDeque;
 
 use casper_types::{
-    bytesrepr::FromBytes, CLTyped, CLValue, Gas, Key, Motes, StoredValue, TransferAddr,
+    bytesrepr::FromBytes, CLTyped, CLValue, ContractEvent, Gas, Key, Motes, StoredValue,
+    TransferAddr,
 };
 
 use super::error;
@@ -58,6 +59,8 @@ pub enum ExecutionResult {
         cost: Gas,
         /// Journal of execution.
         execution_journal: ExecutionJournal,
+        /// List of events emitted by contracts.
+        events: Vec<ContractEvent>,
     },
 }
 
@@ -67,6 +70,7 @@ impl Default for ExecutionResult {
             execution_journal: Default::default(),
             transfers: Default::default(),
             cost: Default::default(),
+            events: Default::default(),
         }
     }
 }
@@ -144,6 +148,14 @@ impl ExecutionResult {
         }
     }
 
+    /// Returns the events emitted by contracts, which are only retained on success.
+    pub fn events(&self) -> &[ContractEvent] {
+        match self {
+            ExecutionResult::Failure { .. } => &[],
+            ExecutionResult::Success { events, .. } => events,
+        }
+    }
+
     /// The journal of transforms regardless of variant.
     pub fn execution_journal(&self) -> &ExecutionJournal {
         match self {
@@ -176,11 +188,13 @@ impl ExecutionResult {
             ExecutionResult::Success {
                 transfers,
                 execution_journal,
+                events,
                 ..
             } => ExecutionResult::Success {
                 transfers,
                 cost,
                 execution_journal,
+                events,
             },
         }
     }
@@ -205,11 +219,13 @@ impl ExecutionResult {
             ExecutionResult::Success {
                 cost,
                 execution_journal,
+                events,
                 ..
             } => ExecutionResult::Success {
                 transfers,
                 cost,
                 execution_journal,
+                events,
             },
         }
     }
@@ -235,10 +251,12 @@ impl ExecutionResult {
                 transfers,
                 cost,
                 execution_journal: _,
+                events,
             } => ExecutionResult::Success {
                 transfers,
                 cost,
                 execution_journal,
+                events,
             },
         }
     }
@@ -349,10 +367,12 @@ impl From<&ExecutionResult> for casper_types::ExecutionResult {
                 transfers,
                 cost,
                 execution_journal,
+                events,
             } => casper_types::ExecutionResult::Success {
                 effect: execution_journal.into(),
                 transfers: transfers.clone(),
                 cost: cost.value(),
+                events: events.clone(),
             },
             ExecutionResult::Failure {
                 error,
@@ -376,10 +396,12 @@ impl From<ExecutionResult> for casper_types::ExecutionResult {
                 transfers,
                 cost,
                 execution_journal,
+                events,
             } => casper_types::ExecutionResult::Success {
                 effect: execution_journal.into(),
                 transfers,
                 cost: cost.value(),
+                events,
             },
             ExecutionResult::Failure {
                 error,
@@ -484,6 +506,7 @@ impl ExecutionResultBuilder {
     pub fn build(self) -> Result<ExecutionResult, ExecutionResultBuilderError> {
         let mut error: Option<error::Error> = None;
         let mut transfers = self.transfers();
+        let mut events = Vec::new();
         let cost = self.total_cost();
 
         let mut journal = match self.payment_execution_result {
@@ -507,8 +530,13 @@ impl ExecutionResultBuilder {
                 transfers = session_transfers;
             }
             Some(ExecutionResult::Success {
-                execution_journal, ..
-            }) => journal.extend(execution_journal),
+                execution_journal,
+                events: session_events,
+                ..
+            }) => {
+                journal.extend(execution_journal);
+                events = session_events;
+            }
             None => return Err(ExecutionResultBuilderError::MissingSessionExecutionResult),
         };
 
@@ -530,6 +558,7 @@ impl ExecutionResultBuilder {
                 transfers,
                 cost,
                 execution_journal: journal,
+                events,
             }),
             Some(error) => Ok(ExecutionResult::Failure {
                 error,
@@ -0,0 +1,66 @@
+//! Types for gas estimation.
+use casper_types::{Gas, Motes};
+
+use crate::core::engine_state::Error;
+
+/// The gas consumed by a deploy executed with a payment covering up to the block gas limit.
+#[derive(Clone, Debug)]
+pub struct GasEstimate {
+    payment_gas: Gas,
+    session_gas: Gas,
+    gas_price: u64,
+    recommended_payment: Motes,
+    error: Option<Error>,
+}
+
+impl GasEstimate {
+    /// Creates a new [`GasEstimate`].
+    pub fn new(
+        payment_gas: Gas,
+        session_gas: Gas,
+        gas_price: u64,
+        recommended_payment: Motes,
+        error: Option<Error>,
+    ) -> Self {
+        GasEstimate {
+            payment_gas,
+            session_gas,
+            gas_price,
+            recommended_payment,
+            error,
+        }
+    }
+
+    /// Returns the gas consumed by the payment code.
+    pub fn payment_gas(&self) -> Gas {
+        self.payment_gas
+    }
+
+    /// Returns the gas consumed by the session code, including the costs of any system contract
+    /// entry points it called.
+    pub fn session_gas(&self) -> Gas {
+        self.session_gas
+    }
+
+    /// Returns the total gas consumed by the payment and session code.
+    pub fn total_gas(&self) -> Option<Gas> {
+        self.payment_gas.checked_add(self.session_gas)
+    }
+
+    /// Returns the gas price the deploy is charged at.
+    pub fn gas_price(&self) -> u64 {
+        self.gas_price
+    }
+
+    /// Returns the smallest payment amount which covers the gas consumed by the deploy.
+    pub fn recommended_payment(&self) -> Motes {
+        self.recommended_payment
+    }
+
+    /// Returns the error the deploy failed with, if any.
+    ///
+    /// The gas consumed by a failed deploy only covers the execution up to the point of failure.
+    pub fn error(&self) -> Option<&Error> {
+        self.error.as_ref()
+    }
+}
@@ -0,0 +1,80 @@
+//! Support for enumerating the items of a dictionary.
+use casper_hashing::Digest;
+use casper_types::{bytesrepr::Bytes, CLValue, Key, URef};
+
+/// The maximum number of [`Key::Dictionary`] keys read to fill a single page of dictionary items.
+///
+/// Items of all dictionaries share the same key space, so a page may end early, holding fewer
+/// items than requested or none at all, once this many keys have been read.
+pub const MAX_DICTIONARY_KEYS_SCANNED: usize = 10_000;
+
+/// Represents a request to obtain a page of the items of the dictionary with the given seed URef.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GetDictionaryItemsRequest {
+    state_hash: Digest,
+    seed_uref: URef,
+    after: Option<Key>,
+    limit: usize,
+}
+
+impl GetDictionaryItemsRequest {
+    /// Creates new request.
+    ///
+    /// Items are returned in ascending order of their [`Key::Dictionary`] address, starting with
+    /// the first item whose address is greater than `after` if given.  At most `limit` items are
+    /// returned, and fewer if [`MAX_DICTIONARY_KEYS_SCANNED`] keys are read before the page is
+    /// filled.
+    pub fn new(state_hash: Digest, seed_uref: URef, after: Option<Key>, limit: usize) -> Self {
+        GetDictionaryItemsRequest {
+            state_hash,
+            seed_uref,
+            after,
+            limit,
+        }
+    }
+
+    /// Returns state root hash.
+    pub fn state_hash(&self) -> Digest {
+        self.state_hash
+    }
+
+    /// Returns the seed URef of the dictionary.
+    pub fn seed_uref(&self) -> URef {
+        self.seed_uref
+    }
+
+    /// Returns the key after which the page starts, if any.
+    pub fn after(&self) -> Option<&Key> {
+        self.after.as_ref()
+    }
+
+    /// Returns the maximum number of items to return.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+}
+
+/// An item of a dictionary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DictionaryItem {
+    /// The [`Key::Dictionary`] under which the item is stored.
+    pub key: Key,
+    /// The item key with which the item was written, as passed to `dictionary_put`.
+    pub dictionary_item_key: Bytes,
+    /// The value of the item.
+    pub value: CLValue,
+}
+
+/// Represents a result of a `get_dictionary_items` request.
+#[derive(Debug, PartialEq, Eq)]
+pub enum GetDictionaryItemsResult {
+    /// Invalid state root hash.
+    RootNotFound,
+    /// Contains a page of dictionary items.
+    Success {
+        /// The items, in ascending order of their keys.
+        items: Vec<DictionaryItem>,
+        /// The key after which the next page starts, if there may be further items.
+        next: Option<Key>,
+    },
+}
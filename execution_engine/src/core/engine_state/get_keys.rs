@@ -0,0 +1,62 @@
+//! Support for enumerating the keys of a given type under a state root.
+use casper_hashing::Digest;
+use casper_types::{Key, KeyTag};
+
+/// Represents a request to obtain a page of the keys of a given type under a state root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GetKeysRequest {
+    state_hash: Digest,
+    key_tag: KeyTag,
+    after: Option<Key>,
+    limit: usize,
+}
+
+impl GetKeysRequest {
+    /// Creates new request.
+    ///
+    /// Keys are returned in ascending order of their serialized form, starting with the first key
+    /// whose serialized form is greater than that of `after` if given.  At most `limit` keys are
+    /// returned.
+    pub fn new(state_hash: Digest, key_tag: KeyTag, after: Option<Key>, limit: usize) -> Self {
+        GetKeysRequest {
+            state_hash,
+            key_tag,
+            after,
+            limit,
+        }
+    }
+
+    /// Returns state root hash.
+    pub fn state_hash(&self) -> Digest {
+        self.state_hash
+    }
+
+    /// Returns the type of the keys requested.
+    pub fn key_tag(&self) -> KeyTag {
+        self.key_tag
+    }
+
+    /// Returns the key after which the page starts, if any.
+    pub fn after(&self) -> Option<&Key> {
+        self.after.as_ref()
+    }
+
+    /// Returns the maximum number of keys to return.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+}
+
+/// Represents a result of a `get_keys` request.
+#[derive(Debug, PartialEq, Eq)]
+pub enum GetKeysResult {
+    /// Invalid state root hash.
+    RootNotFound,
+    /// Contains a page of keys.
+    Success {
+        /// The keys, in ascending order of their serialized form.
+        keys: Vec<Key>,
+        /// The key after which the next page starts, if there are further keys.
+        next: Option<Key>,
+    },
+}
@@ -10,12 +10,16 @@ pub mod executable_deploy_item;
 pub mod execute_request;
 pub mod execution_effect;
 pub mod execution_result;
+pub mod gas_estimate;
 pub mod genesis;
 pub mod get_bids;
+pub mod get_dictionary_items;
+pub mod get_keys;
 pub mod op;
 mod prune;
 pub mod query;
 pub mod run_genesis_request;
+pub mod state_diff;
 pub mod step;
 pub mod system_contract_registry;
 mod transfer;
@@ -23,6 +27,7 @@ pub mod upgrade;
 
 use std::{
     cell::RefCell,
+    cmp,
     collections::{BTreeMap, BTreeSet},
     convert::TryFrom,
     rc::Rc,
@@ -47,7 +52,7 @@ use casper_types::{
         },
         handle_payment::{self, ACCUMULATION_PURSE_KEY},
         mint::{self, ROUND_SEIGNIORAGE_RATE_KEY},
-        AUCTION, HANDLE_PAYMENT, MINT, STANDARD_PAYMENT,
+        standard_payment, AUCTION, HANDLE_PAYMENT, MINT, STANDARD_PAYMENT,
     },
     AccessRights, ApiError, BlockTime, CLValue, ContractHash, DeployHash, DeployInfo, Gas, Key,
     KeyTag, Motes, Phase, ProtocolVersion, PublicKey, RuntimeArgs, StoredValue, URef, U512,
@@ -68,11 +73,18 @@ pub use self::{
     execute_request::ExecuteRequest,
     execution::Error as ExecError,
     execution_result::{ExecutionResult, ForcedTransferResult},
+    gas_estimate::GasEstimate,
     genesis::{ExecConfig, GenesisAccount, GenesisConfig, GenesisSuccess},
     get_bids::{GetBidsRequest, GetBidsResult},
+    get_dictionary_items::{
+        DictionaryItem, GetDictionaryItemsRequest, GetDictionaryItemsResult,
+        MAX_DICTIONARY_KEYS_SCANNED,
+    },
+    get_keys::{GetKeysRequest, GetKeysResult},
     prune::{PruneConfig, PruneResult},
     query::{QueryRequest, QueryResult},
     run_genesis_request::RunGenesisRequest,
+    state_diff::{StateDiff, StateDiffRequest, StateDiffResult, TrieDiff},
     step::{RewardItem, SlashItem, StepError, StepRequest, StepSuccess},
     system_contract_registry::SystemContractRegistry,
     transfer::{TransferArgs, TransferRuntimeArgsBuilder, TransferTargetMode},
@@ -88,7 +100,8 @@ use crate::{
             upgrade::{ProtocolUpgradeError, SystemUpgrader},
         },
         execution::{self, DirectSystemContractCall, Executor},
-        runtime::RuntimeStack,
+        runtime::{call_trace::CallTrace, RuntimeStack},
+        runtime_context::dictionary::DictionaryValue,
         tracking_copy::{TrackingCopy, TrackingCopyExt},
     },
     shared::{additive_map::AdditiveMap, newtypes::CorrelationId, transform::Transform},
@@ -98,7 +111,7 @@ use crate::{
             StateReader,
         },
         trie::{merkle_proof::TrieMerkleProof, TrieRaw},
-        trie_store::operations::DeleteResult,
+        trie_store::operations::{DeleteResult, DiffResult},
     },
     system::auction,
 };
@@ -167,6 +180,19 @@ impl EngineState<LmdbGlobalState> {
             .put_stored_values(CorrelationId::new(), state_root_hash, stored_values)
             .map_err(Into::into)
     }
+
+    /// Computes the state root hash which would result from writing the state cached in an
+    /// `EngineState<ScratchEngineState>` to LMDB, without writing it.
+    pub fn scratch_state_root_hash(
+        &self,
+        state_root_hash: Digest,
+        scratch_global_state: ScratchGlobalState,
+    ) -> Result<Digest, Error> {
+        let stored_values = scratch_global_state.into_inner();
+        self.state
+            .stored_values_root_hash(CorrelationId::new(), state_root_hash, stored_values)
+            .map_err(Into::into)
+    }
 }
 
 impl<S> EngineState<S>
@@ -558,35 +584,206 @@ where
         let mut results = ExecutionResults::with_capacity(deploys.len());
 
         for deploy_item in deploys {
-            let result = match deploy_item.session {
-                ExecutableDeployItem::Transfer { .. } => self.transfer(
-                    correlation_id,
-                    &executor,
-                    exec_request.protocol_version,
-                    exec_request.parent_state_hash,
-                    BlockTime::new(exec_request.block_time),
-                    deploy_item,
-                    exec_request.proposer.clone(),
-                ),
-                _ => self.deploy(
+            let result =
+                self.execute_deploy_item(correlation_id, &executor, &exec_request, deploy_item)?;
+            results.push_back(result);
+        }
+
+        Ok(results)
+    }
+
+    /// Executes the deploys of an [`ExecuteRequest`] like [`EngineState::run_execute`], also
+    /// recording the call frames entered while executing each of them.
+    ///
+    /// Tracing adds overhead to the execution, so this is intended for debugging contracts rather
+    /// than for executing blocks.
+    pub fn run_execute_with_call_traces(
+        &self,
+        correlation_id: CorrelationId,
+        mut exec_request: ExecuteRequest,
+    ) -> Result<Vec<(ExecutionResult, CallTrace)>, Error> {
+        let executor = Executor::new_with_call_tracer(self.config().clone());
+
+        let deploys = exec_request.take_deploys();
+        let mut results = Vec::with_capacity(deploys.len());
+
+        for deploy_item in deploys {
+            let result =
+                self.execute_deploy_item(correlation_id, &executor, &exec_request, deploy_item)?;
+            let call_trace = executor.take_call_trace().unwrap_or_default();
+            results.push((result, call_trace));
+        }
+
+        Ok(results)
+    }
+
+    /// Estimates the gas consumed by each of the deploys of an [`ExecuteRequest`] without
+    /// committing their effects.
+    ///
+    /// Deploys using standard payment are executed with a payment buying `block_gas_limit` gas at
+    /// the deploy's gas price, funded on top of the balance of the account's main purse, so that
+    /// their session code is not limited by the amount they would have paid.  A deploy running
+    /// out of that gas is reported as [`Error::ExceedsBlockGasLimit`], as it could never be
+    /// included in a block.  Deploys using custom payment code are executed as given.
+    pub fn estimate_gas(
+        &self,
+        correlation_id: CorrelationId,
+        mut exec_request: ExecuteRequest,
+        block_gas_limit: u64,
+    ) -> Result<Vec<GasEstimate>, Error> {
+        let executor = Executor::new_with_call_tracer(self.config().clone());
+
+        let deploys = exec_request.take_deploys();
+        let mut estimates = Vec::with_capacity(deploys.len());
+
+        for deploy_item in deploys {
+            let is_transfer = deploy_item.session.is_transfer();
+            let gas_price = if is_transfer {
+                WASMLESS_TRANSFER_FIXED_GAS_PRICE
+            } else {
+                deploy_item.gas_price
+            };
+
+            let has_capped_payment =
+                !is_transfer && deploy_item.payment.is_standard_payment(Phase::Payment);
+            let execution_result = if has_capped_payment {
+                let payment_amount =
+                    Motes::from_gas(Gas::new(U512::from(block_gas_limit)), gas_price)
+                        .ok_or(Error::GasConversionOverflow)?;
+                self.deploy_with_estimation_payment(
                     correlation_id,
                     &executor,
-                    exec_request.protocol_version,
-                    exec_request.parent_state_hash,
-                    BlockTime::new(exec_request.block_time),
+                    &exec_request,
                     deploy_item,
-                    exec_request.proposer.clone(),
-                ),
+                    payment_amount.value(),
+                )?
+            } else {
+                self.execute_deploy_item(correlation_id, &executor, &exec_request, deploy_item)?
             };
-            match result {
-                Ok(result) => results.push_back(result),
-                Err(error) => {
-                    return Err(error);
+            let call_trace = executor.take_call_trace().unwrap_or_default();
+
+            // Wasmless transfers execute no payment code, so their fixed cost is all attributed
+            // to the session.
+            let payment_gas = call_trace
+                .frames()
+                .iter()
+                .filter(|frame| frame.depth == 0 && frame.phase == Phase::Payment)
+                .try_fold(Gas::default(), |total, frame| {
+                    total.checked_add(frame.gas_used)
+                })
+                .ok_or(Error::GasConversionOverflow)?;
+            let total_gas = execution_result.cost();
+            let session_gas = total_gas.checked_sub(payment_gas).unwrap_or_default();
+
+            let mut recommended_payment =
+                Motes::from_gas(total_gas, gas_price).ok_or(Error::GasConversionOverflow)?;
+            if !is_transfer && session_gas.value().is_zero() {
+                // Deploys whose session code consumes no gas are charged the collateral locked
+                // while executing payment code.
+                recommended_payment = cmp::max(recommended_payment, Motes::new(*MAX_PAYMENT));
+            }
+
+            let error = match execution_result.as_error() {
+                Some(Error::Exec(ExecError::GasLimit)) if has_capped_payment => {
+                    Some(Error::ExceedsBlockGasLimit(block_gas_limit))
                 }
+                maybe_error => maybe_error.cloned(),
             };
+
+            estimates.push(GasEstimate::new(
+                payment_gas,
+                session_gas,
+                gas_price,
+                recommended_payment,
+                error,
+            ));
         }
 
-        Ok(results)
+        Ok(estimates)
+    }
+
+    /// Executes a deploy using standard payment with a payment of `payment_amount` motes, funding
+    /// the account's main purse with the payment on top of its actual balance.
+    ///
+    /// The effects of the returned [`ExecutionResult`] include the funding, so must never be
+    /// committed.
+    fn deploy_with_estimation_payment(
+        &self,
+        correlation_id: CorrelationId,
+        executor: &Executor,
+        exec_request: &ExecuteRequest,
+        mut deploy_item: DeployItem,
+        payment_amount: U512,
+    ) -> Result<ExecutionResult, Error> {
+        let prestate_hash = exec_request.parent_state_hash;
+        let tracking_copy = match self.tracking_copy(prestate_hash) {
+            Err(error) => return Ok(ExecutionResult::precondition_failure(error)),
+            Ok(None) => return Err(Error::RootNotFound(prestate_hash)),
+            Ok(Some(tracking_copy)) => Rc::new(RefCell::new(tracking_copy)),
+        };
+
+        // A missing account is left for `deploy_with_tracking_copy` to report.
+        let maybe_account = tracking_copy
+            .borrow_mut()
+            .get_account(correlation_id, deploy_item.address)
+            .ok();
+        if let Some(account) = maybe_account {
+            let mut tracking_copy = tracking_copy.borrow_mut();
+            let balance_key =
+                tracking_copy.get_purse_balance_key(correlation_id, account.main_purse().into())?;
+            let balance = tracking_copy.get_purse_balance(correlation_id, balance_key)?;
+            let funded_balance = CLValue::from_t(balance.value() + payment_amount)
+                .map_err(|error| Error::from(ExecError::from(error)))?;
+            tracking_copy.write(balance_key, StoredValue::CLValue(funded_balance));
+        }
+
+        let payment_args =
+            RuntimeArgs::try_new(|args| args.insert(standard_payment::ARG_AMOUNT, payment_amount))
+                .map_err(|error| Error::from(ExecError::from(error)))?;
+        deploy_item.payment = ExecutableDeployItem::ModuleBytes {
+            module_bytes: Default::default(),
+            args: payment_args,
+        };
+
+        self.deploy_with_tracking_copy(
+            correlation_id,
+            executor,
+            exec_request.protocol_version,
+            prestate_hash,
+            tracking_copy,
+            BlockTime::new(exec_request.block_time),
+            deploy_item,
+            exec_request.proposer.clone(),
+        )
+    }
+
+    fn execute_deploy_item(
+        &self,
+        correlation_id: CorrelationId,
+        executor: &Executor,
+        exec_request: &ExecuteRequest,
+        deploy_item: DeployItem,
+    ) -> Result<ExecutionResult, Error> {
+        match deploy_item.session {
+            ExecutableDeployItem::Transfer { .. } => self.transfer(
+                correlation_id,
+                executor,
+                exec_request.protocol_version,
+                exec_request.parent_state_hash,
+                BlockTime::new(exec_request.block_time),
+                deploy_item,
+                exec_request.proposer.clone(),
+            ),
+            _ => self.deploy(
+                correlation_id,
+                executor,
+                exec_request.protocol_version,
+                exec_request.parent_state_hash,
+                BlockTime::new(exec_request.block_time),
+                deploy_item,
+                exec_request.proposer.clone(),
+            ),
+        }
     }
 
     fn get_authorized_account(
@@ -1205,6 +1402,32 @@ where
             Ok(Some(tracking_copy)) => Rc::new(RefCell::new(tracking_copy)),
         };
 
+        self.deploy_with_tracking_copy(
+            correlation_id,
+            executor,
+            protocol_version,
+            prestate_hash,
+            tracking_copy,
+            blocktime,
+            deploy_item,
+            proposer,
+        )
+    }
+
+    /// Executes a deploy like [`EngineState::deploy`] on top of the given tracking copy of the
+    /// global state at `prestate_hash`.
+    #[allow(clippy::too_many_arguments)]
+    fn deploy_with_tracking_copy(
+        &self,
+        correlation_id: CorrelationId,
+        executor: &Executor,
+        protocol_version: ProtocolVersion,
+        prestate_hash: Digest,
+        tracking_copy: Rc<RefCell<TrackingCopy<<S as StateProvider>::Reader>>>,
+        blocktime: BlockTime,
+        deploy_item: DeployItem,
+        proposer: PublicKey,
+    ) -> Result<ExecutionResult, Error> {
         // Get addr bytes from `address` (which is actually a Key)
         // validation_spec_3: account validity
 
@@ -1812,12 +2035,9 @@ where
     where
         Error: From<S::Error>,
     {
-        let missing_children = self.state.missing_children(correlation_id, trie_bytes)?;
-        if missing_children.is_empty() {
-            Ok(self.state.put_trie(correlation_id, trie_bytes)?)
-        } else {
-            Err(Error::MissingTrieNodeChildren(missing_children))
-        }
+        self.state
+            .put_trie_if_all_children_present(correlation_id, trie_bytes)?
+            .map_err(Error::MissingTrieNodeChildren)
     }
 
     /// Obtains validator weights for given era.
@@ -1919,6 +2139,161 @@ where
         Ok(GetBidsResult::Success { bids })
     }
 
+    /// Obtains a page of the keys of a given type under a state root.
+    ///
+    /// The trie is read from the requested cursor onwards, and only until the page is filled.
+    pub fn get_keys(
+        &self,
+        correlation_id: CorrelationId,
+        get_keys_request: GetKeysRequest,
+    ) -> Result<GetKeysResult, Error> {
+        let tracking_copy = match self.tracking_copy(get_keys_request.state_hash())? {
+            Some(tracking_copy) => tracking_copy,
+            None => return Ok(GetKeysResult::RootNotFound),
+        };
+
+        let after = match get_keys_request.after() {
+            Some(key) => key.to_bytes()?,
+            None => Vec::new(),
+        };
+        let limit = get_keys_request.limit();
+        // One more key than requested is read to determine whether there are further pages.
+        let mut keys = tracking_copy
+            .reader()
+            .keys_with_prefix_after(
+                correlation_id,
+                &[get_keys_request.key_tag() as u8],
+                &after,
+                limit.saturating_add(1),
+            )
+            .map_err(|err| Error::Exec(err.into()))?;
+        let next = if keys.len() > limit {
+            keys.truncate(limit);
+            keys.last().copied()
+        } else {
+            None
+        };
+
+        Ok(GetKeysResult::Success { keys, next })
+    }
+
+    /// Obtains a page of the items of the dictionary with the given seed URef.
+    ///
+    /// Dictionary item keys are hashes of the seed URef and the item key, so every
+    /// [`Key::Dictionary`] after the requested cursor is read until the page is filled, or until
+    /// [`MAX_DICTIONARY_KEYS_SCANNED`] keys have been read, in which case the page ends early.
+    pub fn get_dictionary_items(
+        &self,
+        correlation_id: CorrelationId,
+        get_dictionary_items_request: GetDictionaryItemsRequest,
+    ) -> Result<GetDictionaryItemsResult, Error> {
+        let mut tracking_copy =
+            match self.tracking_copy(get_dictionary_items_request.state_hash())? {
+                Some(tracking_copy) => tracking_copy,
+                None => return Ok(GetDictionaryItemsResult::RootNotFound),
+            };
+
+        let after = match get_dictionary_items_request.after() {
+            Some(key) => key.to_bytes()?,
+            None => Vec::new(),
+        };
+        // One more key than may be scanned is read to determine whether there are further keys.
+        let dictionary_keys = tracking_copy
+            .reader()
+            .keys_with_prefix_after(
+                correlation_id,
+                &[KeyTag::Dictionary as u8],
+                &after,
+                MAX_DICTIONARY_KEYS_SCANNED + 1,
+            )
+            .map_err(|err| Error::Exec(err.into()))?;
+
+        let seed_uref_addr = get_dictionary_items_request.seed_uref().addr();
+        let limit = get_dictionary_items_request.limit();
+        let mut items: Vec<DictionaryItem> = Vec::new();
+        let mut next = None;
+        for (scanned, key) in dictionary_keys.iter().enumerate() {
+            if items.len() >= limit {
+                next = items.last().map(|item| item.key);
+                break;
+            }
+            if scanned == MAX_DICTIONARY_KEYS_SCANNED {
+                // The page ends early, continuing after the last key scanned.
+                next = Some(dictionary_keys[scanned - 1]);
+                break;
+            }
+            let cl_value = match tracking_copy.get(correlation_id, key).map_err(Into::into)? {
+                Some(StoredValue::CLValue(cl_value)) => cl_value,
+                Some(_) | None => continue,
+            };
+            let dictionary_value: DictionaryValue = cl_value
+                .into_t()
+                .map_err(|error| Error::Exec(execution::Error::CLValue(error)))?;
+            if dictionary_value.seed_uref_addr().as_slice() != seed_uref_addr.as_slice() {
+                continue;
+            }
+            items.push(DictionaryItem {
+                key: *key,
+                dictionary_item_key: dictionary_value.dictionary_item_key_bytes().clone(),
+                value: dictionary_value.into_cl_value(),
+            });
+        }
+
+        Ok(GetDictionaryItemsResult::Success { items, next })
+    }
+
+    /// Obtains the differences between the global state under two state roots.
+    ///
+    /// Subtries which are identical under both roots or precede the requested cursor are skipped
+    /// without being read, and the walk stops once the page is filled, so the cost is proportional
+    /// to the size of the page rather than the size of the global state.
+    pub fn get_state_diff(
+        &self,
+        correlation_id: CorrelationId,
+        state_diff_request: StateDiffRequest,
+    ) -> Result<StateDiffResult, Error>
+    where
+        Error: From<S::Error>,
+    {
+        let pre_state_hash = state_diff_request.pre_state_hash();
+        let post_state_hash = state_diff_request.post_state_hash();
+        for state_hash in [pre_state_hash, post_state_hash] {
+            if self.state.checkout(state_hash)?.is_none() {
+                return Ok(StateDiffResult::RootNotFound(state_hash));
+            }
+        }
+
+        let prefix: Vec<u8> = state_diff_request
+            .key_tag()
+            .map(|key_tag| vec![key_tag as u8])
+            .unwrap_or_default();
+        let after = match state_diff_request.after() {
+            Some(key) => key.to_bytes()?,
+            None => Vec::new(),
+        };
+        let limit = state_diff_request.limit();
+        // One more difference than requested is read to determine whether there are further pages.
+        match self.state.diff(
+            correlation_id,
+            pre_state_hash,
+            post_state_hash,
+            &prefix,
+            &after,
+            limit.saturating_add(1),
+        )? {
+            DiffResult::Success(mut diffs) => {
+                let next = if diffs.len() > limit {
+                    diffs.truncate(limit);
+                    diffs.last().map(|diff| *diff.key())
+                } else {
+                    None
+                };
+                Ok(StateDiffResult::Success { diffs, next })
+            }
+            DiffResult::TrieNotFound(missing) => Err(Error::MissingTrieNodeChildren(vec![missing])),
+        }
+    }
+
     /// Executes a step request.
     pub fn commit_step(
         &self,
@@ -2282,11 +2657,13 @@ fn log_execution_result(preamble: &'static str, result: &ExecutionResult) {
             transfers,
             cost,
             execution_journal,
+            events,
         } => {
             debug!(
                 %cost,
                 transfer_count=%transfers.len(),
                 journal_entries=%execution_journal.len(),
+                event_count=%events.len(),
                 "{}: execution success",
                 preamble
             );
@@ -2394,7 +2771,8 @@ fn should_charge_for_errors_in_wasm(execution_result: &ExecutionResult) -> bool
             | Error::FailedToRetrieveUnbondingDelay
             | Error::FailedToRetrieveEraId
             | Error::MissingTrieNodeChildren(_)
-            | Error::FailedToRetrieveAccumulationPurse => false,
+            | Error::FailedToRetrieveAccumulationPurse
+            | Error::ExceedsBlockGasLimit(_) => false,
         },
         ExecutionResult::Success { .. } => false,
     }
@@ -0,0 +1,83 @@
+//! Support for obtaining the differences between the global state under two state roots.
+use casper_hashing::Digest;
+use casper_types::{Key, KeyTag, StoredValue};
+
+pub use crate::storage::trie_store::operations::TrieDiff;
+
+/// A difference between the values stored under a key at two state roots.
+pub type StateDiff = TrieDiff<Key, StoredValue>;
+
+/// Represents a request to obtain the differences between the global state under two state roots.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateDiffRequest {
+    pre_state_hash: Digest,
+    post_state_hash: Digest,
+    key_tag: Option<KeyTag>,
+    after: Option<Key>,
+    limit: usize,
+}
+
+impl StateDiffRequest {
+    /// Creates new request.
+    ///
+    /// If `key_tag` is given, only the differences under keys of that type are returned.
+    /// Differences are returned in ascending order of serialized key, starting with the first key
+    /// greater than `after` if given, and at most `limit` of them.
+    pub fn new(
+        pre_state_hash: Digest,
+        post_state_hash: Digest,
+        key_tag: Option<KeyTag>,
+        after: Option<Key>,
+        limit: usize,
+    ) -> Self {
+        StateDiffRequest {
+            pre_state_hash,
+            post_state_hash,
+            key_tag,
+            after,
+            limit,
+        }
+    }
+
+    /// Returns the state root hash against which differences are reported.
+    pub fn pre_state_hash(&self) -> Digest {
+        self.pre_state_hash
+    }
+
+    /// Returns the state root hash whose differences are reported.
+    pub fn post_state_hash(&self) -> Digest {
+        self.post_state_hash
+    }
+
+    /// Returns the type of the keys whose differences are requested, if restricted.
+    pub fn key_tag(&self) -> Option<KeyTag> {
+        self.key_tag
+    }
+
+    /// Returns the key after which the page starts, if any.
+    pub fn after(&self) -> Option<&Key> {
+        self.after.as_ref()
+    }
+
+    /// Returns the maximum number of differences to return.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+}
+
+/// Represents a result of a `get_state_diff` request.
+#[derive(Debug, PartialEq, Eq)]
+pub enum StateDiffResult {
+    /// Invalid state root hash.
+    RootNotFound(Digest),
+    /// Contains the differences between the global state under the two state roots.
+    Success {
+        /// The differences, in ascending order of serialized key.
+        ///
+        /// Values under the pre-state root are reported as removed or old values, and values
+        /// under the post-state root as added or new values.
+        diffs: Vec<StateDiff>,
+        /// The key after which the next page starts, if there are further differences.
+        next: Option<Key>,
+    },
+}
@@ -1,4 +1,4 @@
-use std::{cell::RefCell, collections::BTreeSet, rc::Rc};
+use std::{cell::RefCell, collections::BTreeSet, mem, rc::Rc};
 
 use casper_types::{
     account::{Account, AccountHash},
@@ -16,7 +16,10 @@ use crate::{
             ExecError,
         },
         execution::{address_generator::AddressGenerator, Error},
-        runtime::{Runtime, RuntimeStack},
+        runtime::{
+            call_trace::{CallTrace, CallTracer},
+            Runtime, RuntimeStack,
+        },
         runtime_context::RuntimeContext,
         tracking_copy::{TrackingCopy, TrackingCopyExt},
     },
@@ -35,12 +38,31 @@ fn try_get_amount(runtime_args: &RuntimeArgs) -> Result<U512, ExecError> {
 /// Executor object deals with execution of WASM modules.
 pub struct Executor {
     config: EngineConfig,
+    call_tracer: Option<Rc<RefCell<CallTracer>>>,
 }
 
 impl Executor {
     /// Creates new executor object.
     pub fn new(config: EngineConfig) -> Self {
-        Executor { config }
+        Executor {
+            config,
+            call_tracer: None,
+        }
+    }
+
+    /// Creates new executor object which records the call frames entered during execution.
+    pub fn new_with_call_tracer(config: EngineConfig) -> Self {
+        Executor {
+            config,
+            call_tracer: Some(Rc::new(RefCell::new(CallTracer::default()))),
+        }
+    }
+
+    /// Takes the call frames recorded since the previous call, if tracing is enabled.
+    pub fn take_call_trace(&self) -> Option<CallTrace> {
+        self.call_tracer
+            .as_ref()
+            .map(|call_tracer| mem::take(&mut *call_tracer.borrow_mut()).into_trace())
     }
 
     /// Executes a WASM module.
@@ -122,6 +144,7 @@ impl Executor {
                 execution_journal: runtime.context().execution_journal(),
                 transfers: runtime.context().transfers().to_owned(),
                 cost: runtime.context().gas_counter(),
+                events: runtime.context().events().to_owned(),
             },
             Err(error) => ExecutionResult::Failure {
                 error: error.into(),
@@ -197,6 +220,7 @@ impl Executor {
                 execution_journal: runtime.context().execution_journal(),
                 transfers: runtime.context().transfers().to_owned(),
                 cost: runtime.context().gas_counter(),
+                events: runtime.context().events().to_owned(),
             },
             Err(error) => ExecutionResult::Failure {
                 execution_journal,
@@ -322,6 +346,7 @@ impl Executor {
                     execution_journal: runtime.context().execution_journal(),
                     transfers: runtime.context().transfers().to_owned(),
                     cost: runtime.context().gas_counter(),
+                    events: runtime.context().events().to_owned(),
                 }
                 .take_with_ret(ret),
                 Err(error) => ExecutionResult::Failure {
@@ -390,6 +415,7 @@ impl Executor {
             self.config.clone(),
             transfers,
             remaining_spending_limit,
+            self.call_tracer.clone(),
         )
     }
 }
@@ -0,0 +1,171 @@
+//! Opt-in tracing of the call frames entered while executing a deploy.
+//!
+//! Tracing is intended for debugging contracts, e.g. via speculative execution, and is never
+//! enabled while executing blocks.
+use std::collections::BTreeMap;
+
+use casper_types::{system::CallStackElement, CLValue, Gas, Phase};
+
+use crate::core::execution::Error;
+
+/// A call frame entered while executing a deploy.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CallFrame {
+    /// The depth of the frame, zero for the top-level code of each phase.
+    pub depth: usize,
+    /// The phase in which the frame was entered.
+    pub phase: Phase,
+    /// The element pushed onto the call stack for the frame.
+    ///
+    /// `None` if the callee could not be resolved, e.g. because the contract does not exist.
+    pub call_stack_element: Option<CallStackElement>,
+    /// The name of the entry point called.
+    pub entry_point: String,
+    /// The size in bytes of the serialized runtime args passed to the entry point.
+    pub args_size: usize,
+    /// The gas used by the frame, including that used by any frames it entered.
+    pub gas_used: Gas,
+    /// The code passed to `revert` if the frame reverted.
+    pub revert_code: Option<u32>,
+    /// The error the frame failed with, if any.
+    pub error: Option<String>,
+    /// The number of times each host function was invoked directly by the frame.
+    pub host_function_calls: BTreeMap<String, u64>,
+}
+
+/// The call frames entered while executing a deploy, in the order they were entered.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CallTrace {
+    frames: Vec<CallFrame>,
+}
+
+impl CallTrace {
+    /// Returns the call frames in the order they were entered.
+    pub fn frames(&self) -> &[CallFrame] {
+        &self.frames
+    }
+
+    /// Consumes the trace, returning the call frames in the order they were entered.
+    pub fn into_frames(self) -> Vec<CallFrame> {
+        self.frames
+    }
+}
+
+/// Records the call frames entered while executing a deploy.
+///
+/// A single tracer is shared between all the runtimes used to execute a deploy.
+#[derive(Debug, Default)]
+pub struct CallTracer {
+    trace: CallTrace,
+    /// Indices into `trace.frames` of the frames which have been entered but not yet exited,
+    /// along with the gas counter at the point they were entered.
+    open_frames: Vec<(usize, Gas)>,
+}
+
+impl CallTracer {
+    /// Records entry into a new frame nested within the currently open one, if any.
+    pub(crate) fn enter_frame(
+        &mut self,
+        phase: Phase,
+        call_stack_element: Option<CallStackElement>,
+        entry_point: &str,
+        args_size: usize,
+        gas_counter: Gas,
+    ) {
+        let frame = CallFrame {
+            depth: self.open_frames.len(),
+            phase,
+            call_stack_element,
+            entry_point: entry_point.to_string(),
+            args_size,
+            gas_used: Gas::default(),
+            revert_code: None,
+            error: None,
+            host_function_calls: BTreeMap::new(),
+        };
+        self.open_frames
+            .push((self.trace.frames.len(), gas_counter));
+        self.trace.frames.push(frame);
+    }
+
+    /// Sets the call stack element of the currently open frame once the callee is resolved.
+    pub(crate) fn set_call_stack_element(&mut self, call_stack_element: CallStackElement) {
+        if let Some(frame) = self.current_frame_mut() {
+            frame.call_stack_element = Some(call_stack_element);
+        }
+    }
+
+    /// Records an invocation of the given host function by the currently open frame.
+    pub(crate) fn record_host_function_call(&mut self, host_function_name: &str) {
+        if let Some(frame) = self.current_frame_mut() {
+            *frame
+                .host_function_calls
+                .entry(host_function_name.to_string())
+                .or_default() += 1;
+        }
+    }
+
+    /// Records exit from the currently open frame with the given result.
+    pub(crate) fn exit_frame(&mut self, gas_counter: Gas, result: &Result<CLValue, Error>) {
+        let (index, gas_at_entry) = match self.open_frames.pop() {
+            Some(open_frame) => open_frame,
+            None => return,
+        };
+        let frame = &mut self.trace.frames[index];
+        frame.gas_used = gas_counter.checked_sub(gas_at_entry).unwrap_or_default();
+        if let Err(error) = result {
+            if let Error::Revert(api_error) = error {
+                frame.revert_code = Some(u32::from(*api_error));
+            }
+            frame.error = Some(error.to_string());
+        }
+    }
+
+    /// Consumes the tracer, returning the recorded trace.
+    pub fn into_trace(self) -> CallTrace {
+        self.trace
+    }
+
+    fn current_frame_mut(&mut self) -> Option<&mut CallFrame> {
+        let (index, _) = self.open_frames.last()?;
+        self.trace.frames.get_mut(*index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::ApiError;
+
+    use super::*;
+
+    #[test]
+    fn should_record_nested_frames() {
+        let mut tracer = CallTracer::default();
+        tracer.enter_frame(Phase::Session, None, "call", 0, Gas::new(10.into()));
+        tracer.record_host_function_call("casper_call_contract");
+        tracer.enter_frame(Phase::Session, None, "transfer", 42, Gas::new(20.into()));
+        tracer.record_host_function_call("casper_write");
+        tracer.record_host_function_call("casper_write");
+        tracer.exit_frame(Gas::new(50.into()), &Err(Error::Revert(ApiError::User(7))));
+        tracer.exit_frame(Gas::new(60.into()), &Ok(CLValue::unit()));
+
+        let frames = tracer.into_trace().into_frames();
+        assert_eq!(frames.len(), 2);
+
+        assert_eq!(frames[0].depth, 0);
+        assert_eq!(frames[0].entry_point, "call");
+        assert_eq!(frames[0].gas_used, Gas::new(50.into()));
+        assert_eq!(frames[0].revert_code, None);
+        assert_eq!(frames[0].error, None);
+        assert_eq!(frames[0].host_function_calls.len(), 1);
+        assert_eq!(frames[0].host_function_calls["casper_call_contract"], 1);
+
+        assert_eq!(frames[1].depth, 1);
+        assert_eq!(frames[1].entry_point, "transfer");
+        assert_eq!(frames[1].args_size, 42);
+        assert_eq!(frames[1].gas_used, Gas::new(30.into()));
+        assert_eq!(frames[1].revert_code, Some(u32::from(ApiError::User(7))));
+        assert!(frames[1].error.is_some());
+        assert_eq!(frames[1].host_function_calls["casper_write"], 2);
+    }
+}
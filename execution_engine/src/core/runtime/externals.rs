@@ -32,6 +32,12 @@ where
     ) -> Result<Option<RuntimeValue>, Trap> {
         let func = FunctionIndex::try_from(index).expect("unknown function index");
 
+        if let Some(call_tracer) = self.context.call_tracer() {
+            call_tracer
+                .borrow_mut()
+                .record_host_function_call(func.host_function_name());
+        }
+
         let host_function_costs = self.config.wasm_config().take_host_function_costs();
 
         match func {
@@ -934,7 +940,8 @@ where
                 let target: URef = self.t_from_mem(target_ptr, target_size)?;
                 let amount: U512 = self.t_from_mem(amount_ptr, amount_size)?;
                 let id: Option<u64> = self.t_from_mem(id_ptr, id_size)?;
-                self.record_transfer(maybe_to, source, target, amount, id)?;
+                let from = self.context.account().account_hash();
+                self.record_transfer(from, maybe_to, source, target, amount, id)?;
                 Ok(Some(RuntimeValue::I32(0)))
             }
 
@@ -1095,6 +1102,20 @@ where
 
                 let result = self.enable_contract_version(contract_package_hash, contract_hash)?;
 
+                Ok(Some(RuntimeValue::I32(api_error::i32_from(result))))
+            }
+            FunctionIndex::EmitEventFuncIndex => {
+                // args(0) = pointer to event topic in wasm memory
+                // args(1) = size of event topic in wasm memory
+                // args(2) = pointer to serialized event value in wasm memory
+                // args(3) = size of serialized event value in wasm memory
+                let (topic_ptr, topic_size, value_ptr, value_size) = Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.emit_event,
+                    [topic_ptr, topic_size, value_ptr, value_size],
+                )?;
+                let result = self.emit_event(topic_ptr, topic_size, value_ptr, value_size)?;
+
                 Ok(Some(RuntimeValue::I32(api_error::i32_from(result))))
             }
         }
@@ -162,6 +162,38 @@ where
             .metered_add_gs_unsafe(Key::Balance(uref.addr()), StoredValue::CLValue(cl_value))
             .map_err(|exec_error| <Option<Error>>::from(exec_error).unwrap_or(Error::Storage))
     }
+
+    fn read_dictionary_item<T: CLTyped + FromBytes>(
+        &mut self,
+        seed_uref: URef,
+        dictionary_item_key: &str,
+    ) -> Result<Option<T>, Error> {
+        let maybe_value = self
+            .context
+            .dictionary_get(seed_uref, dictionary_item_key)
+            .map_err(|exec_error| <Option<Error>>::from(exec_error).unwrap_or(Error::Storage))?;
+        match maybe_value {
+            Some(value) => {
+                let value = CLValue::into_t(value).map_err(|_| Error::CLValue)?;
+                Ok(Some(value))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn write_dictionary_item<T: CLTyped + ToBytes>(
+        &mut self,
+        seed_uref: URef,
+        dictionary_item_key: &str,
+        value: T,
+    ) -> Result<(), Error> {
+        let cl_value = CLValue::from_t(value).map_err(|_| Error::CLValue)?;
+        self.context
+            .dictionary_put(seed_uref, dictionary_item_key, cl_value)
+            .map_err(|exec_error| {
+                <Option<Error>>::from(exec_error).unwrap_or(Error::WriteDictionary)
+            })
+    }
 }
 
 impl<'a, R> SystemProvider for Runtime<'a, R>
@@ -171,13 +203,14 @@ where
 {
     fn record_transfer(
         &mut self,
+        from: AccountHash,
         maybe_to: Option<AccountHash>,
         source: URef,
         target: URef,
         amount: U512,
         id: Option<u64>,
     ) -> Result<(), Error> {
-        let result = Runtime::record_transfer(self, maybe_to, source, target, amount, id);
+        let result = Runtime::record_transfer(self, from, maybe_to, source, target, amount, id);
         result.map_err(|exec_error| {
             <Option<Error>>::from(exec_error).unwrap_or(Error::RecordTransferFailure)
         })
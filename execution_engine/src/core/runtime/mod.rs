@@ -1,6 +1,7 @@
 //! This module contains executor state of the WASM code.
 mod args;
 mod auction_internal;
+pub mod call_trace;
 mod externals;
 mod handle_payment_internal;
 mod host_function_flag;
@@ -40,10 +41,11 @@ use casper_types::{
         handle_payment, mint, standard_payment, CallStackElement, SystemContractType, AUCTION,
         HANDLE_PAYMENT, MINT, STANDARD_PAYMENT,
     },
-    AccessRights, ApiError, CLTyped, CLValue, ContextAccessRights, ContractHash,
+    AccessRights, ApiError, CLTyped, CLValue, ContextAccessRights, ContractEvent, ContractHash,
     ContractPackageHash, ContractVersionKey, ContractWasm, DeployHash, EntryPointType, Gas,
     GrantedAccess, Key, NamedArg, Parameter, Phase, PublicKey, RuntimeArgs, StoredValue, Transfer,
-    TransferResult, TransferredTo, URef, DICTIONARY_ITEM_KEY_MAX_LENGTH, U512,
+    TransferResult, TransferredTo, URef, DICTIONARY_ITEM_KEY_MAX_LENGTH, EVENT_TOPIC_MAX_LENGTH,
+    U512,
 };
 
 use crate::{
@@ -700,6 +702,39 @@ where
                     mint_runtime.mint_into_existing_purse(existing_purse, amount);
                 CLValue::from_t(result).map_err(Self::reverter)
             })(),
+            // Type: `fn approve(spender: Key, amount: U512) -> Result<(), Error>`
+            mint::METHOD_APPROVE => (|| {
+                mint_runtime.charge_system_contract_call(mint_costs.approve)?;
+
+                let spender: Key = Self::get_named_argument(runtime_args, mint::ARG_SPENDER)?;
+                let amount: U512 = Self::get_named_argument(runtime_args, mint::ARG_AMOUNT)?;
+                let result: Result<(), mint::Error> = mint_runtime.approve(spender, amount);
+                CLValue::from_t(result).map_err(Self::reverter)
+            })(),
+            // Type: `fn allowance(owner: AccountHash, spender: Key) -> U512`
+            mint::METHOD_ALLOWANCE => (|| {
+                mint_runtime.charge_system_contract_call(mint_costs.allowance)?;
+
+                let owner: AccountHash = Self::get_named_argument(runtime_args, mint::ARG_OWNER)?;
+                let spender: Key = Self::get_named_argument(runtime_args, mint::ARG_SPENDER)?;
+                let allowance: U512 = mint_runtime
+                    .allowance(owner, spender)
+                    .map_err(Self::reverter)?;
+                CLValue::from_t(allowance).map_err(Self::reverter)
+            })(),
+            // Type: `fn transfer_from(owner: AccountHash, target: URef, amount: U512, id:
+            // Option<u64>) -> Result<(), Error>`
+            mint::METHOD_TRANSFER_FROM => (|| {
+                mint_runtime.charge_system_contract_call(mint_costs.transfer_from)?;
+
+                let owner: AccountHash = Self::get_named_argument(runtime_args, mint::ARG_OWNER)?;
+                let target: URef = Self::get_named_argument(runtime_args, mint::ARG_TARGET)?;
+                let amount: U512 = Self::get_named_argument(runtime_args, mint::ARG_AMOUNT)?;
+                let id: Option<u64> = Self::get_named_argument(runtime_args, mint::ARG_ID)?;
+                let result: Result<(), mint::Error> =
+                    mint_runtime.transfer_from(owner, target, amount, id);
+                CLValue::from_t(result).map_err(Self::reverter)
+            })(),
 
             _ => CLValue::from_t(()).map_err(Self::reverter),
         };
@@ -826,12 +861,13 @@ where
     pub(crate) fn call_host_standard_payment(&mut self, stack: RuntimeStack) -> Result<(), Error> {
         // NOTE: This method (unlike other call_host_* methods) already runs on its own runtime
         // context.
+        self.trace_enter_frame(stack.current_frame().cloned(), standard_payment::METHOD_PAY);
         self.stack = Some(stack);
         let gas_counter = self.gas_counter();
-        let amount: U512 =
-            Self::get_named_argument(self.context.args(), standard_payment::ARG_AMOUNT)?;
-        let result = self.pay(amount).map_err(Self::reverter);
+        let result = Self::get_named_argument(self.context.args(), standard_payment::ARG_AMOUNT)
+            .and_then(|amount: U512| self.pay(amount).map_err(Self::reverter));
         self.set_gas_counter(gas_counter);
+        self.trace_exit_frame(&result.clone().map(|()| CLValue::unit()));
         result
     }
 
@@ -1066,6 +1102,17 @@ where
         &mut self,
         module_bytes: &Bytes,
         stack: RuntimeStack,
+    ) -> Result<CLValue, Error> {
+        self.trace_enter_frame(stack.current_frame().cloned(), DEFAULT_ENTRY_POINT_NAME);
+        let result = self.execute_module_bytes_frame(module_bytes, stack);
+        self.trace_exit_frame(&result);
+        result
+    }
+
+    fn execute_module_bytes_frame(
+        &mut self,
+        module_bytes: &Bytes,
+        stack: RuntimeStack,
     ) -> Result<CLValue, Error> {
         let protocol_version = self.context.protocol_version();
         let engine_config = self.config.clone();
@@ -1164,6 +1211,33 @@ where
         }
     }
 
+    /// Records entry into a new call frame running with the current runtime args, if tracing is
+    /// enabled.
+    fn trace_enter_frame(
+        &self,
+        call_stack_element: Option<CallStackElement>,
+        entry_point_name: &str,
+    ) {
+        if let Some(call_tracer) = self.context.call_tracer() {
+            call_tracer.borrow_mut().enter_frame(
+                self.context.phase(),
+                call_stack_element,
+                entry_point_name,
+                self.context.args().serialized_length(),
+                self.context.gas_counter(),
+            );
+        }
+    }
+
+    /// Records exit from the current call frame, if tracing is enabled.
+    fn trace_exit_frame(&self, result: &Result<CLValue, Error>) {
+        if let Some(call_tracer) = self.context.call_tracer() {
+            call_tracer
+                .borrow_mut()
+                .exit_frame(self.context.gas_counter(), result);
+        }
+    }
+
     fn try_get_memory(&self) -> Result<&MemoryRef, Error> {
         self.memory.as_ref().ok_or(Error::WasmPreprocessing(
             PreprocessingError::MissingMemorySection,
@@ -1185,6 +1259,26 @@ where
         identifier: CallContractIdentifier,
         entry_point_name: &str,
         args: RuntimeArgs,
+    ) -> Result<CLValue, Error> {
+        if let Some(call_tracer) = self.context.call_tracer() {
+            call_tracer.borrow_mut().enter_frame(
+                self.context.phase(),
+                None,
+                entry_point_name,
+                args.serialized_length(),
+                self.context.gas_counter(),
+            );
+        }
+        let result = self.execute_contract_frame(identifier, entry_point_name, args);
+        self.trace_exit_frame(&result);
+        result
+    }
+
+    fn execute_contract_frame(
+        &mut self,
+        identifier: CallContractIdentifier,
+        entry_point_name: &str,
+        args: RuntimeArgs,
     ) -> Result<CLValue, Error> {
         let (contract, contract_hash, contract_package) = match identifier {
             CallContractIdentifier::Contract { contract_hash } => {
@@ -1322,6 +1416,11 @@ where
                     contract_hash,
                 ),
             };
+            if let Some(call_tracer) = self.context.call_tracer() {
+                call_tracer
+                    .borrow_mut()
+                    .set_call_stack_element(call_stack_element.clone());
+            }
             stack.push(call_stack_element)?;
 
             stack
@@ -1419,6 +1518,11 @@ where
             *transfers = runtime.context.transfers().to_owned();
         }
 
+        {
+            let events = self.context.events_mut();
+            *events = runtime.context.events().to_owned();
+        }
+
         let error = match result {
             Err(error) => error,
             // If `Ok` and the `host_buffer` is `None`, the contract's execution succeeded but did
@@ -1879,9 +1983,35 @@ where
             .map_err(Into::into)
     }
 
-    /// Records a transfer.
+    /// Records an event emitted by the currently executing contract.
+    ///
+    /// As with transfers, only events emitted during the session phase are recorded.
+    fn emit_event(
+        &mut self,
+        topic_ptr: u32,
+        topic_size: u32,
+        value_ptr: u32,
+        value_size: u32,
+    ) -> Result<Result<(), ApiError>, Trap> {
+        let topic = self.string_from_mem(topic_ptr, topic_size)?;
+        if topic.is_empty() || topic.len() > EVENT_TOPIC_MAX_LENGTH {
+            return Ok(Err(ApiError::InvalidArgument));
+        }
+        let value = self.cl_value_from_mem(value_ptr, value_size)?;
+
+        if self.context.phase() != Phase::Session {
+            return Ok(Ok(()));
+        }
+
+        let event = ContractEvent::new(self.context.base_key(), topic, value);
+        self.context.events_mut().push(event);
+        Ok(Ok(()))
+    }
+
+    /// Records a transfer made by the account `from`.
     fn record_transfer(
         &mut self,
+        from: AccountHash,
         maybe_to: Option<AccountHash>,
         source: URef,
         target: URef,
@@ -1899,7 +2029,6 @@ where
         let transfer_addr = self.context.new_transfer_addr()?;
         let transfer = {
             let deploy_hash: DeployHash = self.context.get_deploy_hash();
-            let from: AccountHash = self.context.account().account_hash();
             let fee: U512 = U512::zero(); // TODO
             Transfer::new(deploy_hash, from, maybe_to, source, target, amount, fee, id)
         };
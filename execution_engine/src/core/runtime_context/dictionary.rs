@@ -35,6 +35,16 @@ impl DictionaryValue {
     pub fn into_cl_value(self) -> CLValue {
         self.cl_value
     }
+
+    /// Returns the address of the seed [`URef`](casper_types::URef) of the dictionary.
+    pub fn seed_uref_addr(&self) -> &Bytes {
+        &self.seed_uref_addr
+    }
+
+    /// Returns the original dictionary item key bytes.
+    pub fn dictionary_item_key_bytes(&self) -> &Bytes {
+        &self.dictionary_item_key_bytes
+    }
 }
 
 impl CLTyped for DictionaryValue {
@@ -17,16 +17,18 @@ use casper_types::{
     bytesrepr::ToBytes,
     contracts::NamedKeys,
     system::auction::EraInfo,
-    AccessRights, BlockTime, CLType, CLValue, ContextAccessRights, Contract, ContractHash,
-    ContractPackage, ContractPackageHash, DeployHash, DeployInfo, EntryPointAccess, EntryPointType,
-    Gas, GrantedAccess, Key, KeyTag, Phase, ProtocolVersion, PublicKey, RuntimeArgs, StoredValue,
-    Transfer, TransferAddr, URef, URefAddr, DICTIONARY_ITEM_KEY_MAX_LENGTH, KEY_HASH_LENGTH, U512,
+    AccessRights, BlockTime, CLType, CLValue, ContextAccessRights, Contract, ContractEvent,
+    ContractHash, ContractPackage, ContractPackageHash, DeployHash, DeployInfo, EntryPointAccess,
+    EntryPointType, Gas, GrantedAccess, Key, KeyTag, Phase, ProtocolVersion, PublicKey,
+    RuntimeArgs, StoredValue, Transfer, TransferAddr, URef, URefAddr,
+    DICTIONARY_ITEM_KEY_MAX_LENGTH, KEY_HASH_LENGTH, U512,
 };
 
 use crate::{
     core::{
         engine_state::{execution_effect::ExecutionEffect, EngineConfig, SystemContractRegistry},
         execution::{AddressGenerator, Error},
+        runtime::call_trace::CallTracer,
         runtime_context::dictionary::DictionaryValue,
         tracking_copy::{AddResult, TrackingCopy, TrackingCopyExt},
     },
@@ -103,7 +105,10 @@ pub struct RuntimeContext<'a, R> {
     engine_config: EngineConfig,
     entry_point_type: EntryPointType,
     transfers: Vec<TransferAddr>,
+    events: Vec<ContractEvent>,
     remaining_spending_limit: U512,
+    // Records the call frames entered if tracing is enabled
+    call_tracer: Option<Rc<RefCell<CallTracer>>>,
 }
 
 impl<'a, R> RuntimeContext<'a, R>
@@ -135,6 +140,7 @@ where
         engine_config: EngineConfig,
         transfers: Vec<TransferAddr>,
         remaining_spending_limit: U512,
+        call_tracer: Option<Rc<RefCell<CallTracer>>>,
     ) -> Self {
         RuntimeContext {
             tracking_copy,
@@ -155,7 +161,9 @@ where
             phase,
             engine_config,
             transfers,
+            events: Vec::new(),
             remaining_spending_limit,
+            call_tracer,
         }
     }
 
@@ -183,7 +191,9 @@ where
         let phase = self.phase;
         let engine_config = self.engine_config.clone();
         let transfers = self.transfers.clone();
+        let events = self.events.clone();
         let remaining_spending_limit = self.remaining_spending_limit();
+        let call_tracer = self.call_tracer.clone();
 
         RuntimeContext {
             tracking_copy,
@@ -204,7 +214,9 @@ where
             phase,
             engine_config,
             transfers,
+            events,
             remaining_spending_limit,
+            call_tracer,
         }
     }
 
@@ -655,6 +667,21 @@ where
         &mut self.transfers
     }
 
+    /// Returns list of events emitted by contracts.
+    pub fn events(&self) -> &Vec<ContractEvent> {
+        &self.events
+    }
+
+    /// Returns mutable list of events emitted by contracts.
+    pub fn events_mut(&mut self) -> &mut Vec<ContractEvent> {
+        &mut self.events
+    }
+
+    /// Returns the call tracer if tracing is enabled.
+    pub(crate) fn call_tracer(&self) -> Option<&Rc<RefCell<CallTracer>>> {
+        self.call_tracer.as_ref()
+    }
+
     fn validate_cl_value(&self, cl_value: &CLValue) -> Result<(), Error> {
         match cl_value.cl_type() {
             CLType::Bool
@@ -78,6 +78,16 @@ impl StateReader<Key, StoredValue> for CountingDb {
     ) -> Result<Vec<Key>, Self::Error> {
         Ok(Vec::new())
     }
+
+    fn keys_with_prefix_after(
+        &self,
+        _correlation_id: CorrelationId,
+        _prefix: &[u8],
+        _after: &[u8],
+        _limit: usize,
+    ) -> Result<Vec<Key>, Self::Error> {
+        Ok(Vec::new())
+    }
 }
 
 #[test]
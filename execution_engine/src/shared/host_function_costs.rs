@@ -78,6 +78,10 @@ const DEFAULT_DICTIONARY_PUT_VALUE_SIZE_WEIGHT: u32 = 520;
 
 const DEFAULT_NEW_DICTIONARY_COST: u32 = DEFAULT_NEW_UREF_COST;
 
+const DEFAULT_EMIT_EVENT_COST: u32 = 10_000;
+const DEFAULT_EMIT_EVENT_TOPIC_SIZE_WEIGHT: u32 = 1_800;
+const DEFAULT_EMIT_EVENT_VALUE_SIZE_WEIGHT: u32 = 520;
+
 pub(crate) const DEFAULT_HOST_FUNCTION_NEW_DICTIONARY: HostFunction<[Cost; 1]> =
     HostFunction::new(DEFAULT_NEW_DICTIONARY_COST, [NOT_USED]);
 
@@ -290,6 +294,8 @@ pub struct HostFunctionCosts {
     pub random_bytes: HostFunction<[Cost; 2]>,
     /// Cost of calling the `enable_contract_version` host function.
     pub enable_contract_version: HostFunction<[Cost; 4]>,
+    /// Cost of calling the `emit_event` host function.
+    pub emit_event: HostFunction<[Cost; 4]>,
 }
 
 impl Default for HostFunctionCosts {
@@ -422,6 +428,15 @@ impl Default for HostFunctionCosts {
             blake2b: HostFunction::default(),
             random_bytes: HostFunction::default(),
             enable_contract_version: HostFunction::default(),
+            emit_event: HostFunction::new(
+                DEFAULT_EMIT_EVENT_COST,
+                [
+                    NOT_USED,
+                    DEFAULT_EMIT_EVENT_TOPIC_SIZE_WEIGHT,
+                    NOT_USED,
+                    DEFAULT_EMIT_EVENT_VALUE_SIZE_WEIGHT,
+                ],
+            ),
         }
     }
 }
@@ -473,6 +488,7 @@ impl ToBytes for HostFunctionCosts {
         ret.append(&mut self.blake2b.to_bytes()?);
         ret.append(&mut self.random_bytes.to_bytes()?);
         ret.append(&mut self.enable_contract_version.to_bytes()?);
+        ret.append(&mut self.emit_event.to_bytes()?);
         Ok(ret)
     }
 
@@ -521,6 +537,7 @@ impl ToBytes for HostFunctionCosts {
             + self.blake2b.serialized_length()
             + self.random_bytes.serialized_length()
             + self.enable_contract_version.serialized_length()
+            + self.emit_event.serialized_length()
     }
 }
 
@@ -570,6 +587,7 @@ impl FromBytes for HostFunctionCosts {
         let (blake2b, rem) = FromBytes::from_bytes(rem)?;
         let (random_bytes, rem) = FromBytes::from_bytes(rem)?;
         let (enable_contract_version, rem) = FromBytes::from_bytes(rem)?;
+        let (emit_event, rem) = FromBytes::from_bytes(rem)?;
         Ok((
             HostFunctionCosts {
                 read_value,
@@ -616,6 +634,7 @@ impl FromBytes for HostFunctionCosts {
                 blake2b,
                 random_bytes,
                 enable_contract_version,
+                emit_event,
             },
             rem,
         ))
@@ -669,6 +688,7 @@ impl Distribution<HostFunctionCosts> for Standard {
             blake2b: rng.gen(),
             random_bytes: rng.gen(),
             enable_contract_version: rng.gen(),
+            emit_event: rng.gen(),
         }
     }
 }
@@ -730,6 +750,7 @@ pub mod gens {
             blake2b in host_function_cost_arb(),
             random_bytes in host_function_cost_arb(),
             enable_contract_version in host_function_cost_arb(),
+            emit_event in host_function_cost_arb(),
         ) -> HostFunctionCosts {
             HostFunctionCosts {
                 read_value,
@@ -776,6 +797,7 @@ pub mod gens {
                 blake2b,
                 random_bytes,
                 enable_contract_version,
+                emit_event,
             }
         }
     }
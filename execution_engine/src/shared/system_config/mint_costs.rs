@@ -18,6 +18,12 @@ pub const DEFAULT_TRANSFER_COST: u32 = 10_000;
 pub const DEFAULT_READ_BASE_ROUND_REWARD_COST: u32 = 10_000;
 /// Default cost of the `mint_into_existing_purse` mint entry point.
 pub const DEFAULT_MINT_INTO_EXISTING_PURSE_COST: u32 = 2_500_000_000;
+/// Default cost of the `approve` mint entry point.
+pub const DEFAULT_APPROVE_COST: u32 = 10_000;
+/// Default cost of the `allowance` mint entry point.
+pub const DEFAULT_ALLOWANCE_COST: u32 = 10_000;
+/// Default cost of the `transfer_from` mint entry point.
+pub const DEFAULT_TRANSFER_FROM_COST: u32 = 10_000;
 
 /// Description of the costs of calling mint entry points.
 #[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug, DataSize)]
@@ -37,6 +43,12 @@ pub struct MintCosts {
     pub read_base_round_reward: u32,
     /// Cost of calling the `mint_into_existing_purse` entry point.
     pub mint_into_existing_purse: u32,
+    /// Cost of calling the `approve` entry point.
+    pub approve: u32,
+    /// Cost of calling the `allowance` entry point.
+    pub allowance: u32,
+    /// Cost of calling the `transfer_from` entry point.
+    pub transfer_from: u32,
 }
 
 impl Default for MintCosts {
@@ -49,6 +61,9 @@ impl Default for MintCosts {
             transfer: DEFAULT_TRANSFER_COST,
             read_base_round_reward: DEFAULT_READ_BASE_ROUND_REWARD_COST,
             mint_into_existing_purse: DEFAULT_MINT_INTO_EXISTING_PURSE_COST,
+            approve: DEFAULT_APPROVE_COST,
+            allowance: DEFAULT_ALLOWANCE_COST,
+            transfer_from: DEFAULT_TRANSFER_FROM_COST,
         }
     }
 }
@@ -65,6 +80,9 @@ impl ToBytes for MintCosts {
             transfer,
             read_base_round_reward,
             mint_into_existing_purse,
+            approve,
+            allowance,
+            transfer_from,
         } = self;
 
         ret.append(&mut mint.to_bytes()?);
@@ -74,6 +92,9 @@ impl ToBytes for MintCosts {
         ret.append(&mut transfer.to_bytes()?);
         ret.append(&mut read_base_round_reward.to_bytes()?);
         ret.append(&mut mint_into_existing_purse.to_bytes()?);
+        ret.append(&mut approve.to_bytes()?);
+        ret.append(&mut allowance.to_bytes()?);
+        ret.append(&mut transfer_from.to_bytes()?);
 
         Ok(ret)
     }
@@ -87,6 +108,9 @@ impl ToBytes for MintCosts {
             transfer,
             read_base_round_reward,
             mint_into_existing_purse,
+            approve,
+            allowance,
+            transfer_from,
         } = self;
 
         mint.serialized_length()
@@ -96,6 +120,9 @@ impl ToBytes for MintCosts {
             + transfer.serialized_length()
             + read_base_round_reward.serialized_length()
             + mint_into_existing_purse.serialized_length()
+            + approve.serialized_length()
+            + allowance.serialized_length()
+            + transfer_from.serialized_length()
     }
 }
 
@@ -108,6 +135,9 @@ impl FromBytes for MintCosts {
         let (transfer, rem) = FromBytes::from_bytes(rem)?;
         let (read_base_round_reward, rem) = FromBytes::from_bytes(rem)?;
         let (mint_into_existing_purse, rem) = FromBytes::from_bytes(rem)?;
+        let (approve, rem) = FromBytes::from_bytes(rem)?;
+        let (allowance, rem) = FromBytes::from_bytes(rem)?;
+        let (transfer_from, rem) = FromBytes::from_bytes(rem)?;
 
         Ok((
             Self {
@@ -118,6 +148,9 @@ impl FromBytes for MintCosts {
                 transfer,
                 read_base_round_reward,
                 mint_into_existing_purse,
+                approve,
+                allowance,
+                transfer_from,
             },
             rem,
         ))
@@ -134,6 +167,9 @@ impl Distribution<MintCosts> for Standard {
             transfer: rng.gen(),
             read_base_round_reward: rng.gen(),
             mint_into_existing_purse: rng.gen(),
+            approve: rng.gen(),
+            allowance: rng.gen(),
+            transfer_from: rng.gen(),
         }
     }
 }
@@ -154,6 +190,9 @@ pub mod gens {
             transfer in num::u32::ANY,
             read_base_round_reward in num::u32::ANY,
             mint_into_existing_purse in num::u32::ANY,
+            approve in num::u32::ANY,
+            allowance in num::u32::ANY,
+            transfer_from in num::u32::ANY,
         ) -> MintCosts {
             MintCosts {
                 mint,
@@ -163,6 +202,9 @@ pub mod gens {
                 transfer,
                 read_base_round_reward,
                 mint_into_existing_purse,
+                approve,
+                allowance,
+                transfer_from,
             }
         }
     }
@@ -1,4 +1,8 @@
-use std::{collections::HashMap, ops::Deref, sync::Arc};
+use std::{
+    collections::HashMap,
+    ops::Deref,
+    sync::{Arc, Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
 
 use casper_hashing::Digest;
 use casper_types::{Key, StoredValue};
@@ -8,8 +12,11 @@ use crate::{
     storage::{
         error,
         global_state::{
-            commit, put_stored_values, scratch::ScratchGlobalState, CommitProvider, StateProvider,
-            StateReader,
+            commit,
+            pruning::{PruningProgress, StatePruning},
+            put_stored_values,
+            scratch::ScratchGlobalState,
+            CommitProvider, StateProvider, StateReader,
         },
         store::Store,
         transaction_source::{lmdb::LmdbEnvironment, Transaction, TransactionSource},
@@ -19,8 +26,8 @@ use crate::{
         trie_store::{
             lmdb::{LmdbTrieStore, ScratchTrieStore},
             operations::{
-                delete, keys_with_prefix, missing_children, put_trie, read, read_with_proof,
-                DeleteResult, ReadResult,
+                delete, diff, keys_with_prefix, keys_with_prefix_after, missing_children, put_trie,
+                read, read_with_proof, DeleteResult, DiffResult, ReadResult,
             },
         },
     },
@@ -35,6 +42,11 @@ pub struct LmdbGlobalState {
     // TODO: make this a lazy-static
     /// Empty root hash used for a new trie.
     pub(crate) empty_root_hash: Digest,
+    /// The in-progress pruning of the trie store, if any.
+    ///
+    /// Reads and writes only share the outer lock, so they never wait on one another unless a
+    /// pruning is in progress, in which case they also lock the pruning itself.
+    pruning: RwLock<Option<Mutex<StatePruning>>>,
 }
 
 /// Represents a "view" of global state at a particular root hash.
@@ -49,6 +61,9 @@ pub struct LmdbGlobalStateView {
 
 impl LmdbGlobalState {
     /// Creates an empty state from an existing environment and trie_store.
+    ///
+    /// A pruning interrupted during its sweep phase is resumed, and must be driven to completion
+    /// via [`Self::prune_step`].
     pub fn empty(
         environment: Arc<LmdbEnvironment>,
         trie_store: Arc<LmdbTrieStore>,
@@ -61,7 +76,10 @@ impl LmdbGlobalState {
             environment.env().sync(true)?;
             root_hash
         };
-        Ok(LmdbGlobalState::new(environment, trie_store, root_hash))
+        let pruning = StatePruning::resume(&environment)?;
+        let state = LmdbGlobalState::new(environment, trie_store, root_hash);
+        *state.write_pruning()? = pruning.map(Mutex::new);
+        Ok(state)
     }
 
     /// Creates a state from an existing environment, store, and root_hash.
@@ -75,6 +93,7 @@ impl LmdbGlobalState {
             environment,
             trie_store,
             empty_root_hash,
+            pruning: RwLock::new(None),
         }
     }
 
@@ -94,6 +113,8 @@ impl LmdbGlobalState {
         prestate_hash: Digest,
         stored_values: HashMap<Key, StoredValue>,
     ) -> Result<Digest, error::Error> {
+        let pruning = self.read_pruning()?;
+        let mut state_pruning = lock_state_pruning(&pruning)?;
         let scratch_trie = self.get_scratch_store();
         let new_state_root = put_stored_values::<_, _, error::Error>(
             &scratch_trie,
@@ -103,9 +124,28 @@ impl LmdbGlobalState {
             stored_values,
         )?;
         scratch_trie.write_root_to_db(new_state_root)?;
+        self.register_root(state_pruning.as_deref_mut(), new_state_root)?;
         Ok(new_state_root)
     }
 
+    /// Computes the state root hash which would result from writing the stored values to LMDB,
+    /// without writing them.
+    pub fn stored_values_root_hash(
+        &self,
+        correlation_id: CorrelationId,
+        prestate_hash: Digest,
+        stored_values: HashMap<Key, StoredValue>,
+    ) -> Result<Digest, error::Error> {
+        let scratch_trie = self.get_scratch_store();
+        put_stored_values::<_, _, error::Error>(
+            &scratch_trie,
+            &scratch_trie,
+            correlation_id,
+            prestate_hash,
+            stored_values,
+        )
+    }
+
     /// Gets a scratch trie store.
     fn get_scratch_store(&self) -> ScratchTrieStore {
         ScratchTrieStore::new(Arc::clone(&self.trie_store), Arc::clone(&self.environment))
@@ -127,6 +167,81 @@ impl LmdbGlobalState {
     pub fn empty_state_root_hash(&self) -> Digest {
         self.empty_root_hash
     }
+
+    /// Starts pruning all tries which are not reachable from `retained_roots`, or from any root
+    /// written after this call.
+    ///
+    /// Pruning is performed incrementally via [`Self::prune_step`].  Returns `false` if pruning is
+    /// already in progress, in which case this call has no effect.
+    pub fn start_pruning<I: IntoIterator<Item = Digest>>(
+        &self,
+        retained_roots: I,
+    ) -> Result<bool, error::Error> {
+        let mut pruning = self.write_pruning()?;
+        if pruning.is_some() {
+            return Ok(false);
+        }
+        *pruning = Some(Mutex::new(StatePruning::new(
+            &self.environment,
+            retained_roots,
+        )?));
+        Ok(true)
+    }
+
+    /// Performs a single step of the in-progress pruning, visiting at most `max_tries` tries.
+    ///
+    /// Returns `None` if no pruning is in progress.
+    pub fn prune_step(&self, max_tries: usize) -> Result<Option<PruningProgress>, error::Error> {
+        let mut pruning = self.write_pruning()?;
+        let progress = match pruning.as_mut() {
+            Some(state_pruning) => state_pruning
+                .get_mut()
+                .map_err(|_| error::Error::Poison)?
+                .step(&self.environment, &self.trie_store, max_tries)?,
+            None => return Ok(None),
+        };
+        if let PruningProgress::Complete { .. } = progress {
+            *pruning = None;
+        }
+        Ok(Some(progress))
+    }
+
+    /// Returns `true` if pruning is in progress.
+    pub fn is_pruning(&self) -> Result<bool, error::Error> {
+        Ok(self.read_pruning()?.is_some())
+    }
+
+    fn read_pruning(&self) -> Result<RwLockReadGuard<Option<Mutex<StatePruning>>>, error::Error> {
+        self.pruning.read().map_err(|_| error::Error::Poison)
+    }
+
+    fn write_pruning(&self) -> Result<RwLockWriteGuard<Option<Mutex<StatePruning>>>, error::Error> {
+        self.pruning.write().map_err(|_| error::Error::Poison)
+    }
+
+    /// Registers a newly-written root with the in-progress pruning, if any, so that it is retained.
+    fn register_root(
+        &self,
+        state_pruning: Option<&mut StatePruning>,
+        root: Digest,
+    ) -> Result<(), error::Error> {
+        match state_pruning {
+            Some(state_pruning) => {
+                state_pruning.register_root(&self.environment, &self.trie_store, root)
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+/// Locks the in-progress pruning, if any, so that its steps cannot run concurrently with the caller.
+fn lock_state_pruning(
+    pruning: &Option<Mutex<StatePruning>>,
+) -> Result<Option<MutexGuard<StatePruning>>, error::Error> {
+    pruning
+        .as_ref()
+        .map(|state_pruning| state_pruning.lock().map_err(|_| error::Error::Poison))
+        .transpose()
 }
 
 fn compute_empty_root_hash() -> Result<(Digest, Trie<Key, StoredValue>), error::Error> {
@@ -208,6 +323,33 @@ impl StateReader<Key, StoredValue> for LmdbGlobalStateView {
         txn.commit()?;
         Ok(ret)
     }
+
+    fn keys_with_prefix_after(
+        &self,
+        correlation_id: CorrelationId,
+        prefix: &[u8],
+        after: &[u8],
+        limit: usize,
+    ) -> Result<Vec<Key>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let keys_iter = keys_with_prefix_after::<Key, StoredValue, _, _>(
+            correlation_id,
+            &txn,
+            self.store.deref(),
+            &self.root_hash,
+            prefix,
+            after,
+        );
+        let mut ret = Vec::new();
+        for result in keys_iter.take(limit) {
+            match result {
+                Ok(key) => ret.push(key),
+                Err(error) => return Err(error),
+            }
+        }
+        txn.commit()?;
+        Ok(ret)
+    }
 }
 
 impl CommitProvider for LmdbGlobalState {
@@ -217,14 +359,17 @@ impl CommitProvider for LmdbGlobalState {
         prestate_hash: Digest,
         effects: AdditiveMap<Key, Transform>,
     ) -> Result<Digest, Self::Error> {
-        commit::<LmdbEnvironment, LmdbTrieStore, _, Self::Error>(
+        let pruning = self.read_pruning()?;
+        let mut state_pruning = lock_state_pruning(&pruning)?;
+        let new_state_root = commit::<LmdbEnvironment, LmdbTrieStore, _, Self::Error>(
             &self.environment,
             &self.trie_store,
             correlation_id,
             prestate_hash,
             effects,
-        )
-        .map_err(Into::into)
+        )?;
+        self.register_root(state_pruning.as_deref_mut(), new_state_root)?;
+        Ok(new_state_root)
     }
 }
 
@@ -234,6 +379,13 @@ impl StateProvider for LmdbGlobalState {
     type Reader = LmdbGlobalStateView;
 
     fn checkout(&self, state_hash: Digest) -> Result<Option<Self::Reader>, Self::Error> {
+        let pruning = self.read_pruning()?;
+        if let Some(state_pruning) = lock_state_pruning(&pruning)? {
+            if !state_pruning.is_retained(&self.environment, &state_hash)? {
+                return Ok(None);
+            }
+        }
+        drop(pruning);
         let txn = self.environment.create_read_txn()?;
         let maybe_root: Option<Trie<Key, StoredValue>> = self.trie_store.get(&txn, &state_hash)?;
         let maybe_state = maybe_root.map(|_| LmdbGlobalStateView {
@@ -263,6 +415,8 @@ impl StateProvider for LmdbGlobalState {
     }
 
     fn put_trie(&self, correlation_id: CorrelationId, trie: &[u8]) -> Result<Digest, Self::Error> {
+        let pruning = self.read_pruning()?;
+        let mut state_pruning = lock_state_pruning(&pruning)?;
         let mut txn = self.environment.create_read_write_txn()?;
         let trie_hash = put_trie::<
             Key,
@@ -272,6 +426,7 @@ impl StateProvider for LmdbGlobalState {
             Self::Error,
         >(correlation_id, &mut txn, &self.trie_store, trie)?;
         txn.commit()?;
+        self.register_root(state_pruning.as_deref_mut(), trie_hash)?;
         Ok(trie_hash)
     }
 
@@ -293,6 +448,47 @@ impl StateProvider for LmdbGlobalState {
         Ok(missing_hashes)
     }
 
+    /// Checks for missing children and writes the trie within a single transaction, so that an
+    /// in-progress pruning cannot sweep a child in between.
+    ///
+    /// While pruning is sweeping, a child which is present but not retained may already have lost
+    /// some of its descendants, so it is reported as missing too.
+    fn put_trie_if_all_children_present(
+        &self,
+        correlation_id: CorrelationId,
+        trie_raw: &[u8],
+    ) -> Result<Result<Digest, Vec<Digest>>, Self::Error> {
+        let pruning = self.read_pruning()?;
+        let mut state_pruning = lock_state_pruning(&pruning)?;
+        let mut txn = self.environment.create_read_write_txn()?;
+        let missing_children = match state_pruning.as_deref() {
+            Some(state_pruning) if state_pruning.is_sweeping() => {
+                state_pruning.unretained_children(&txn, trie_raw)?
+            }
+            _ => missing_children::<
+                Key,
+                StoredValue,
+                lmdb::RwTransaction,
+                LmdbTrieStore,
+                Self::Error,
+            >(correlation_id, &txn, self.trie_store.deref(), trie_raw)?,
+        };
+        if !missing_children.is_empty() {
+            txn.commit()?;
+            return Ok(Err(missing_children));
+        }
+        let trie_hash = put_trie::<
+            Key,
+            StoredValue,
+            lmdb::RwTransaction,
+            LmdbTrieStore,
+            Self::Error,
+        >(correlation_id, &mut txn, &self.trie_store, trie_raw)?;
+        txn.commit()?;
+        self.register_root(state_pruning.as_deref_mut(), trie_hash)?;
+        Ok(Ok(trie_hash))
+    }
+
     /// Delete keys.
     fn delete_keys(
         &self,
@@ -300,6 +496,8 @@ impl StateProvider for LmdbGlobalState {
         mut state_root_hash: Digest,
         keys: &[Key],
     ) -> Result<DeleteResult, Self::Error> {
+        let pruning = self.read_pruning()?;
+        let mut state_pruning = lock_state_pruning(&pruning)?;
         let scratch_trie_store = self.get_scratch_store();
 
         let mut txn = scratch_trie_store.create_read_write_txn()?;
@@ -323,8 +521,33 @@ impl StateProvider for LmdbGlobalState {
         txn.commit()?;
 
         scratch_trie_store.write_root_to_db(state_root_hash)?;
+        self.register_root(state_pruning.as_deref_mut(), state_root_hash)?;
         Ok(DeleteResult::Deleted(state_root_hash))
     }
+
+    fn diff(
+        &self,
+        correlation_id: CorrelationId,
+        left_root: Digest,
+        right_root: Digest,
+        prefix: &[u8],
+        after: &[u8],
+        limit: usize,
+    ) -> Result<DiffResult<Key, StoredValue>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let diff_result = diff::<Key, StoredValue, lmdb::RoTransaction, LmdbTrieStore, Self::Error>(
+            correlation_id,
+            &txn,
+            self.trie_store.deref(),
+            &left_root,
+            &right_root,
+            prefix,
+            after,
+            limit,
+        )?;
+        txn.commit()?;
+        Ok(diff_result)
+    }
 }
 
 #[cfg(test)]
@@ -332,8 +555,12 @@ mod tests {
     use lmdb::DatabaseFlags;
     use tempfile::tempdir;
 
-    use casper_hashing::Digest;
-    use casper_types::{account::AccountHash, CLValue};
+    use casper_hashing::{ChunkWithProof, Digest};
+    use casper_types::{
+        account::AccountHash,
+        bytesrepr::{Bytes, ToBytes},
+        CLValue,
+    };
 
     use super::*;
     use crate::storage::{
@@ -505,4 +732,264 @@ mod tests {
                 .unwrap()
         );
     }
+
+    #[test]
+    fn stored_values_root_hash_matches_written_root_without_writing() {
+        let correlation_id = CorrelationId::new();
+        let (state, root_hash) = create_test_state(create_test_pairs);
+        let stored_values: HashMap<Key, StoredValue> = create_test_pairs_updated()
+            .iter()
+            .cloned()
+            .map(|TestPair { key, value }| (key, value))
+            .collect();
+
+        let computed_hash = state
+            .stored_values_root_hash(correlation_id, root_hash, stored_values.clone())
+            .unwrap();
+        assert_ne!(computed_hash, root_hash);
+        assert!(state.checkout(computed_hash).unwrap().is_none());
+
+        let written_hash = state
+            .put_stored_values(correlation_id, root_hash, stored_values)
+            .unwrap();
+        assert_eq!(computed_hash, written_hash);
+        assert!(state.checkout(written_hash).unwrap().is_some());
+    }
+
+    #[test]
+    fn proofs_from_a_checkout_are_verifiable() {
+        let correlation_id = CorrelationId::new();
+        let (state, root_hash) = create_test_state(create_test_pairs);
+        let checkout = state.checkout(root_hash).unwrap().unwrap();
+        for TestPair { key, value } in create_test_pairs() {
+            let proof = checkout
+                .read_with_proof(correlation_id, &key)
+                .unwrap()
+                .unwrap();
+            let merkle_proof = base16::encode_lower(&vec![proof].to_bytes().unwrap());
+            assert_eq!(
+                casper_types::verify_global_state_query(
+                    &root_hash.value(),
+                    &key,
+                    &[],
+                    &value,
+                    &merkle_proof
+                ),
+                Ok(())
+            );
+        }
+    }
+
+    #[test]
+    fn proofs_of_values_hashed_in_chunks_are_verifiable() {
+        fn create_large_test_pairs() -> [TestPair; 2] {
+            // Large enough for the leaf to be hashed as a Merkle tree of three chunks.
+            let large_value = vec![7u8; 2 * ChunkWithProof::CHUNK_SIZE_BYTES + 1];
+            [
+                TestPair {
+                    key: Key::Account(AccountHash::new([1_u8; 32])),
+                    value: StoredValue::CLValue(CLValue::from_t(Bytes::from(large_value)).unwrap()),
+                },
+                TestPair {
+                    key: Key::Account(AccountHash::new([2_u8; 32])),
+                    value: StoredValue::CLValue(CLValue::from_t(2_i32).unwrap()),
+                },
+            ]
+        }
+
+        let correlation_id = CorrelationId::new();
+        let (state, root_hash) = create_test_state(create_large_test_pairs);
+        let checkout = state.checkout(root_hash).unwrap().unwrap();
+        for TestPair { key, value } in create_large_test_pairs() {
+            let proof = checkout
+                .read_with_proof(correlation_id, &key)
+                .unwrap()
+                .unwrap();
+            let merkle_proof = base16::encode_lower(&vec![proof].to_bytes().unwrap());
+            assert_eq!(
+                casper_types::verify_global_state_query(
+                    &root_hash.value(),
+                    &key,
+                    &[],
+                    &value,
+                    &merkle_proof
+                ),
+                Ok(())
+            );
+        }
+    }
+
+    #[test]
+    fn pruning_removes_unretained_roots() {
+        let correlation_id = CorrelationId::new();
+        let test_pairs_updated = create_test_pairs_updated();
+
+        let (state, root_hash) = create_test_state(create_test_pairs);
+
+        let effects: AdditiveMap<Key, Transform> = {
+            let mut tmp = AdditiveMap::new();
+            for TestPair { key, value } in &test_pairs_updated {
+                tmp.insert(*key, Transform::Write(value.to_owned()));
+            }
+            tmp
+        };
+        let updated_hash = state.commit(correlation_id, root_hash, effects).unwrap();
+
+        assert!(state.start_pruning(vec![updated_hash]).unwrap());
+        assert!(!state.start_pruning(vec![root_hash]).unwrap());
+
+        let mut deleted = None;
+        while let Some(progress) = state.prune_step(1).unwrap() {
+            match progress {
+                PruningProgress::Marking => {
+                    // Nothing is deleted while marking.
+                    assert!(state.checkout(root_hash).unwrap().is_some());
+                }
+                PruningProgress::Sweeping => {
+                    assert!(state.checkout(root_hash).unwrap().is_none());
+                }
+                PruningProgress::Complete { deleted_count } => deleted = Some(deleted_count),
+            }
+        }
+        assert!(deleted.unwrap() > 0);
+        assert!(!state.is_pruning().unwrap());
+
+        // The original root and its unshared descendants have been deleted.
+        assert!(state.checkout(root_hash).unwrap().is_none());
+        assert!(state
+            .get_trie_full(correlation_id, &root_hash)
+            .unwrap()
+            .is_none());
+
+        let updated_checkout = state.checkout(updated_hash).unwrap().unwrap();
+        for TestPair { key, value } in test_pairs_updated.iter().cloned() {
+            assert_eq!(
+                Some(value),
+                updated_checkout.read(correlation_id, &key).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn pruning_retains_roots_written_during_pruning() {
+        let correlation_id = CorrelationId::new();
+        let test_pairs_updated = create_test_pairs_updated();
+
+        let (state, root_hash) = create_test_state(create_test_pairs);
+        assert!(state.start_pruning(vec![]).unwrap());
+        assert_eq!(
+            state.prune_step(usize::MAX).unwrap(),
+            Some(PruningProgress::Sweeping)
+        );
+
+        let effects: AdditiveMap<Key, Transform> = {
+            let mut tmp = AdditiveMap::new();
+            for TestPair { key, value } in &test_pairs_updated {
+                tmp.insert(*key, Transform::Write(value.to_owned()));
+            }
+            tmp
+        };
+        let updated_hash = state.commit(correlation_id, root_hash, effects).unwrap();
+
+        while let Some(progress) = state.prune_step(1).unwrap() {
+            assert_ne!(progress, PruningProgress::Marking);
+        }
+
+        assert!(state.checkout(root_hash).unwrap().is_none());
+        let updated_checkout = state.checkout(updated_hash).unwrap().unwrap();
+        for TestPair { key, value } in test_pairs_updated.iter().cloned() {
+            assert_eq!(
+                Some(value),
+                updated_checkout.read(correlation_id, &key).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn tries_written_while_sweeping_should_not_depend_on_unretained_children() {
+        let correlation_id = CorrelationId::new();
+        let (state, root_hash) = create_test_state(create_test_pairs);
+        let root_trie = state
+            .get_trie_full(correlation_id, &root_hash)
+            .unwrap()
+            .unwrap();
+
+        assert!(state.start_pruning(vec![]).unwrap());
+        assert_eq!(
+            state.prune_step(usize::MAX).unwrap(),
+            Some(PruningProgress::Sweeping)
+        );
+
+        // The children are still present, but may already have lost some of their descendants.
+        let missing_children = state
+            .put_trie_if_all_children_present(correlation_id, root_trie.inner())
+            .unwrap()
+            .unwrap_err();
+        assert!(!missing_children.is_empty());
+        assert!(state
+            .missing_children(correlation_id, root_trie.inner())
+            .unwrap()
+            .is_empty());
+
+        while state.prune_step(usize::MAX).unwrap().is_some() {}
+        assert!(state.checkout(root_hash).unwrap().is_none());
+    }
+
+    #[test]
+    fn pruning_should_resume_sweep_after_restart() {
+        let correlation_id = CorrelationId::new();
+        let (state, root_hash) = create_test_state(create_test_pairs);
+        let root_trie = state
+            .get_trie_full(correlation_id, &root_hash)
+            .unwrap()
+            .unwrap();
+
+        assert!(state.start_pruning(vec![]).unwrap());
+        assert_eq!(
+            state.prune_step(usize::MAX).unwrap(),
+            Some(PruningProgress::Sweeping)
+        );
+        assert_eq!(
+            state.prune_step(1).unwrap(),
+            Some(PruningProgress::Sweeping)
+        );
+
+        // Restart part way through the sweep.
+        let restarted = LmdbGlobalState::empty(
+            Arc::clone(&state.environment),
+            Arc::clone(&state.trie_store),
+        )
+        .unwrap();
+        drop(state);
+
+        assert!(restarted.is_pruning().unwrap());
+        assert!(restarted.checkout(root_hash).unwrap().is_none());
+        let missing_children = restarted
+            .put_trie_if_all_children_present(correlation_id, root_trie.inner())
+            .unwrap()
+            .unwrap_err();
+        assert!(!missing_children.is_empty());
+
+        let mut deleted = None;
+        while let Some(progress) = restarted.prune_step(1).unwrap() {
+            match progress {
+                PruningProgress::Marking => panic!("should resume sweeping"),
+                PruningProgress::Sweeping => {}
+                PruningProgress::Complete { deleted_count } => deleted = Some(deleted_count),
+            }
+        }
+        assert!(deleted.unwrap() > 0);
+        assert!(restarted
+            .get_trie_full(correlation_id, &root_hash)
+            .unwrap()
+            .is_none());
+
+        // Once complete, a further restart does not resume the pruning.
+        let restarted_again = LmdbGlobalState::empty(
+            Arc::clone(&restarted.environment),
+            Arc::clone(&restarted.trie_store),
+        )
+        .unwrap();
+        assert!(!restarted_again.is_pruning().unwrap());
+    }
 }
@@ -6,6 +6,9 @@ pub mod in_memory;
 /// Lmdb implementation of global state.
 pub mod lmdb;
 
+/// Incremental pruning of tries unreachable from retained state roots.
+pub mod pruning;
+
 /// Lmdb implementation of global state with cache.
 pub mod scratch;
 
@@ -32,7 +35,7 @@ use crate::{
     },
 };
 
-use super::trie_store::operations::DeleteResult;
+use super::trie_store::operations::{DeleteResult, DiffResult};
 
 /// A trait expressing the reading of state. This trait is used to abstract the underlying store.
 pub trait StateReader<K, V> {
@@ -55,6 +58,16 @@ pub trait StateReader<K, V> {
         correlation_id: CorrelationId,
         prefix: &[u8],
     ) -> Result<Vec<K>, Self::Error>;
+
+    /// Returns up to `limit` keys in the trie matching `prefix` whose serialized form sorts after
+    /// `after`, in ascending order of their serialized form.
+    fn keys_with_prefix_after(
+        &self,
+        correlation_id: CorrelationId,
+        prefix: &[u8],
+        after: &[u8],
+        limit: usize,
+    ) -> Result<Vec<K>, Self::Error>;
 }
 
 /// An error emitted by the execution engine on commit
@@ -123,6 +136,23 @@ pub trait StateProvider {
         trie_raw: &[u8],
     ) -> Result<Vec<Digest>, Self::Error>;
 
+    /// Inserts a trie node into the trie if all of its children are present in the state.
+    ///
+    /// Returns the missing children instead if any are not present, in which case nothing is
+    /// written.
+    fn put_trie_if_all_children_present(
+        &self,
+        correlation_id: CorrelationId,
+        trie_raw: &[u8],
+    ) -> Result<Result<Digest, Vec<Digest>>, Self::Error> {
+        let missing_children = self.missing_children(correlation_id, trie_raw)?;
+        if missing_children.is_empty() {
+            Ok(Ok(self.put_trie(correlation_id, trie_raw)?))
+        } else {
+            Ok(Err(missing_children))
+        }
+    }
+
     /// Delete key from the global state.
     fn delete_keys(
         &self,
@@ -130,6 +160,18 @@ pub trait StateProvider {
         root: Digest,
         keys_to_delete: &[Key],
     ) -> Result<DeleteResult, Self::Error>;
+
+    /// Returns the first `limit` differences between the key-value pairs under `left_root` and
+    /// `right_root` whose serialized keys start with `prefix` and are greater than `after`.
+    fn diff(
+        &self,
+        correlation_id: CorrelationId,
+        left_root: Digest,
+        right_root: Digest,
+        prefix: &[u8],
+        after: &[u8],
+        limit: usize,
+    ) -> Result<DiffResult<Key, StoredValue>, Self::Error>;
 }
 
 /// Write multiple key/stored value pairs to the store in a single rw transaction.
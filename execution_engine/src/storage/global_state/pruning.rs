@@ -0,0 +1,364 @@
+//! Incremental mark-and-sweep pruning of tries which are unreachable from a set of retained state
+//! roots.
+//!
+//! Pruning proceeds in two phases.  During the mark phase, every trie reachable from the retained
+//! roots is recorded, and nothing is deleted.  During the sweep phase, every trie in the store
+//! which was not marked is deleted.  Both phases are performed in bounded steps so that pruning can
+//! be interleaved with normal operation of the global state.
+//!
+//! The set of marked tries is as large as the retained state, so it is held in a dedicated LMDB
+//! database alongside the trie store rather than in memory.  It is cleared at the start and end of
+//! every pruning.
+//!
+//! Once sweeping has begun, the progress of the sweep is recorded in the same database, in the
+//! same transactions as the deletions.  A sweep interrupted by a restart is resumed via
+//! [`StatePruning::resume`], as the tries it has not yet visited may have lost descendants, and
+//! must still be treated as incomplete.
+//!
+//! Any root written to the store while pruning is in progress must be registered via
+//! [`StatePruning::register_root`] so that it and its descendants are retained.  While sweeping, an
+//! unmarked trie may already have lost some of its descendants, so a trie written on top of it
+//! must treat it as missing (see [`StatePruning::unretained_children`]).
+
+use std::convert::TryFrom;
+
+use lmdb::{Cursor, Database, DatabaseFlags};
+use tracing::debug;
+
+use casper_hashing::Digest;
+use casper_types::{Key, StoredValue};
+
+use crate::storage::{
+    error,
+    store::Store,
+    transaction_source::{
+        lmdb::LmdbEnvironment, Readable, Transaction, TransactionSource, Writable,
+    },
+    trie::{self, LazyTrieLeaf, Trie},
+    trie_store::lmdb::LmdbTrieStore,
+};
+
+/// The name of the database holding the tries marked by the in-progress pruning.
+const MARKS_DB_NAME: &str = "PRUNING_MARKS";
+
+/// The key under which the progress of the sweep phase is recorded in the marks database.
+///
+/// Marks are keyed by trie hashes, which are all longer than this key, so the two never collide.
+const SWEEP_PROGRESS_KEY: &[u8] = b"sweep_progress";
+
+/// The progress of an in-progress [`StatePruning`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PruningProgress {
+    /// Tries reachable from the retained roots are still being marked.  No tries have been deleted
+    /// yet.
+    Marking,
+    /// Unmarked tries are being deleted.  The state under any root other than the retained ones
+    /// may be incomplete, and hence is no longer queryable.
+    Sweeping,
+    /// Pruning has completed.
+    Complete {
+        /// The number of tries deleted.
+        deleted_count: u64,
+    },
+}
+
+/// An in-progress incremental mark-and-sweep of a trie store.
+#[derive(Debug)]
+pub struct StatePruning {
+    /// The database holding the tries known to be reachable from a retained root.
+    marks: Database,
+    /// The number of tries marked so far.
+    marked_count: u64,
+    /// Tries known to be reachable from a retained root whose descendants are yet to be marked.
+    to_mark: Vec<Digest>,
+    /// Whether the mark phase has completed.
+    is_sweeping: bool,
+    /// The key of the last trie visited during the sweep phase.
+    last_swept: Option<Digest>,
+    /// The number of tries deleted so far.
+    deleted_count: u64,
+}
+
+impl StatePruning {
+    /// Constructs a new `StatePruning` which will retain all tries reachable from `retained_roots`.
+    ///
+    /// Any marks left behind by a previous, unfinished pruning are discarded.
+    pub(crate) fn new<I: IntoIterator<Item = Digest>>(
+        environment: &LmdbEnvironment,
+        retained_roots: I,
+    ) -> Result<Self, error::Error> {
+        let marks = environment
+            .env()
+            .create_db(Some(MARKS_DB_NAME), DatabaseFlags::empty())?;
+        let mut txn = environment.create_read_write_txn()?;
+        txn.clear_db(marks)?;
+        txn.commit()?;
+        Ok(StatePruning {
+            marks,
+            marked_count: 0,
+            to_mark: retained_roots.into_iter().collect(),
+            is_sweeping: false,
+            last_swept: None,
+            deleted_count: 0,
+        })
+    }
+
+    /// Resumes the sweep phase of a pruning interrupted by a restart, if any.
+    ///
+    /// Returns `None` if no sweep was in progress, in which case any marks left behind by a
+    /// pruning interrupted during its mark phase are discarded by the next [`StatePruning::new`].
+    pub(crate) fn resume(environment: &LmdbEnvironment) -> Result<Option<Self>, error::Error> {
+        let marks = match environment.env().open_db(Some(MARKS_DB_NAME)) {
+            Ok(marks) => marks,
+            Err(lmdb::Error::NotFound) => return Ok(None),
+            Err(error) => return Err(error.into()),
+        };
+        let txn = environment.create_read_txn()?;
+        let progress = txn.read(marks, SWEEP_PROGRESS_KEY)?;
+        txn.commit()?;
+        let progress = match progress {
+            Some(progress) => progress,
+            None => return Ok(None),
+        };
+        if progress.len() < 8 {
+            return Err(lmdb::Error::Corrupted.into());
+        }
+        let (deleted_count, last_swept) = progress.split_at(8);
+        let deleted_count = u64::from_le_bytes(
+            <[u8; 8]>::try_from(deleted_count).map_err(|_| lmdb::Error::Corrupted)?,
+        );
+        let last_swept = if last_swept.is_empty() {
+            None
+        } else {
+            Some(Digest::try_from(last_swept).map_err(|_| lmdb::Error::Corrupted)?)
+        };
+        debug!(
+            deleted_count,
+            ?last_swept,
+            "state pruning: resuming sweep phase"
+        );
+        Ok(Some(StatePruning {
+            marks,
+            marked_count: 0,
+            to_mark: vec![],
+            is_sweeping: true,
+            last_swept,
+            deleted_count,
+        }))
+    }
+
+    /// Returns `true` if the state under `root` is still complete.
+    pub(crate) fn is_retained(
+        &self,
+        environment: &LmdbEnvironment,
+        root: &Digest,
+    ) -> Result<bool, error::Error> {
+        if !self.is_sweeping {
+            return Ok(true);
+        }
+        let txn = environment.create_read_txn()?;
+        let is_marked = self.is_marked(&txn, root)?;
+        txn.commit()?;
+        Ok(is_marked)
+    }
+
+    /// Returns `true` if the mark phase has completed and unmarked tries are being deleted.
+    pub(crate) fn is_sweeping(&self) -> bool {
+        self.is_sweeping
+    }
+
+    /// Returns the children of the given trie which have not been marked.
+    ///
+    /// During the sweep phase, these may be missing or have lost some of their descendants.
+    pub(crate) fn unretained_children<T>(
+        &self,
+        txn: &T,
+        trie_raw: &[u8],
+    ) -> Result<Vec<Digest>, error::Error>
+    where
+        T: Readable<Handle = Database>,
+        error::Error: From<T::Error>,
+    {
+        let lazy_trie: LazyTrieLeaf<Key, StoredValue> =
+            trie::lazy_trie_deserialize(trie_raw.to_vec().into())?;
+        let mut unretained = vec![];
+        for child in trie::lazy_trie_iter_children(&lazy_trie) {
+            if !self.is_marked(txn, &child)? {
+                unretained.push(child);
+            }
+        }
+        Ok(unretained)
+    }
+
+    /// Returns `true` if `trie_key` has been marked.
+    fn is_marked<T>(&self, txn: &T, trie_key: &Digest) -> Result<bool, error::Error>
+    where
+        T: Readable<Handle = Database>,
+        error::Error: From<T::Error>,
+    {
+        Ok(txn.read(self.marks, trie_key.as_ref())?.is_some())
+    }
+
+    /// Registers a root newly written to the store, ensuring it and its descendants are retained.
+    ///
+    /// If the sweep phase has already begun, the newly-written tries are marked immediately.
+    pub(crate) fn register_root(
+        &mut self,
+        environment: &LmdbEnvironment,
+        store: &LmdbTrieStore,
+        root: Digest,
+    ) -> Result<(), error::Error> {
+        let txn = environment.create_read_txn()?;
+        let is_marked = self.is_marked(&txn, &root)?;
+        txn.commit()?;
+        if is_marked {
+            return Ok(());
+        }
+        self.to_mark.push(root);
+        if self.is_sweeping {
+            self.mark(environment, store, usize::MAX)?;
+        }
+        Ok(())
+    }
+
+    /// Performs a single step of pruning, visiting at most `max_tries` tries.
+    pub(crate) fn step(
+        &mut self,
+        environment: &LmdbEnvironment,
+        store: &LmdbTrieStore,
+        max_tries: usize,
+    ) -> Result<PruningProgress, error::Error> {
+        if self.is_sweeping {
+            return self.sweep(environment, store, max_tries);
+        }
+        self.mark(environment, store, max_tries)?;
+        if !self.to_mark.is_empty() {
+            return Ok(PruningProgress::Marking);
+        }
+        debug!(
+            marked_count = self.marked_count,
+            "state pruning: mark phase complete"
+        );
+        let mut txn = environment.create_read_write_txn()?;
+        self.is_sweeping = true;
+        self.write_sweep_progress(&mut txn)?;
+        txn.commit()?;
+        Ok(PruningProgress::Sweeping)
+    }
+
+    /// Marks up to `max_tries` tries from `to_mark`, queueing their children for marking.
+    fn mark(
+        &mut self,
+        environment: &LmdbEnvironment,
+        store: &LmdbTrieStore,
+        max_tries: usize,
+    ) -> Result<(), error::Error> {
+        let mut txn = environment.create_read_write_txn()?;
+        let mut visited = 0;
+        while visited < max_tries {
+            let trie_key = match self.to_mark.pop() {
+                Some(trie_key) => trie_key,
+                None => break,
+            };
+            if self.is_marked(&txn, &trie_key)? {
+                continue;
+            }
+            visited += 1;
+            // A trie may legitimately be missing while its root is still being synchronized, in
+            // which case it will be registered once written.
+            let trie_bytes =
+                match Store::<Digest, Trie<Key, StoredValue>>::get_raw(store, &txn, &trie_key)? {
+                    Some(trie_bytes) => trie_bytes,
+                    None => {
+                        debug!(%trie_key, "state pruning: retained trie missing from store");
+                        continue;
+                    }
+                };
+            txn.write(self.marks, trie_key.as_ref(), &[])?;
+            self.marked_count += 1;
+            let lazy_trie: LazyTrieLeaf<Key, StoredValue> =
+                trie::lazy_trie_deserialize(trie_bytes)?;
+            for child in trie::lazy_trie_iter_children(&lazy_trie) {
+                if !self.is_marked(&txn, &child)? {
+                    self.to_mark.push(child);
+                }
+            }
+        }
+        txn.commit()?;
+        Ok(())
+    }
+
+    /// Deletes unmarked tries, visiting at most `max_tries` tries in key order from where the
+    /// previous sweep step finished.
+    fn sweep(
+        &mut self,
+        environment: &LmdbEnvironment,
+        store: &LmdbTrieStore,
+        max_tries: usize,
+    ) -> Result<PruningProgress, error::Error> {
+        let mut txn = environment.create_read_write_txn()?;
+        let mut is_complete = true;
+        let mut unmarked = vec![];
+        {
+            let mut cursor = lmdb::Transaction::open_ro_cursor(&txn, store.get_db())?;
+            let iter = match self.last_swept {
+                Some(last_swept) => cursor.iter_from(last_swept),
+                None => cursor.iter(),
+            };
+            let mut visited = 0;
+            for row in iter {
+                let (raw_key, _) = row?;
+                let trie_key = Digest::try_from(raw_key)
+                    .map_err(|_| error::Error::from(lmdb::Error::Corrupted))?;
+                if Some(trie_key) == self.last_swept {
+                    continue;
+                }
+                if visited >= max_tries {
+                    is_complete = false;
+                    break;
+                }
+                visited += 1;
+                if !self.is_marked(&txn, &trie_key)? {
+                    unmarked.push(trie_key);
+                }
+                self.last_swept = Some(trie_key);
+            }
+        }
+        for trie_key in unmarked {
+            txn.del(store.get_db(), &trie_key, None)?;
+            self.deleted_count += 1;
+        }
+        if is_complete {
+            txn.clear_db(self.marks)?;
+        } else {
+            self.write_sweep_progress(&mut txn)?;
+        }
+        txn.commit()?;
+
+        if is_complete {
+            debug!(
+                deleted_count = self.deleted_count,
+                "state pruning: sweep phase complete"
+            );
+            Ok(PruningProgress::Complete {
+                deleted_count: self.deleted_count,
+            })
+        } else {
+            Ok(PruningProgress::Sweeping)
+        }
+    }
+
+    /// Records the progress of the sweep phase, so that it can be resumed after a restart.
+    fn write_sweep_progress<T>(&self, txn: &mut T) -> Result<(), error::Error>
+    where
+        T: Writable<Handle = Database>,
+        error::Error: From<T::Error>,
+    {
+        let mut progress = self.deleted_count.to_le_bytes().to_vec();
+        if let Some(last_swept) = self.last_swept {
+            progress.extend_from_slice(last_swept.as_ref());
+        }
+        txn.write(self.marks, SWEEP_PROGRESS_KEY, &progress)?;
+        Ok(())
+    }
+}
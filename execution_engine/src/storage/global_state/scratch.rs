@@ -21,8 +21,8 @@ use crate::{
         trie_store::{
             lmdb::LmdbTrieStore,
             operations::{
-                delete, keys_with_prefix, missing_children, put_trie, read, read_with_proof,
-                DeleteResult, ReadResult,
+                delete, diff, keys_with_prefix, keys_with_prefix_after, missing_children, put_trie,
+                read, read_with_proof, DeleteResult, DiffResult, ReadResult,
             },
         },
     },
@@ -190,6 +190,33 @@ impl StateReader<Key, StoredValue> for ScratchGlobalStateView {
         txn.commit()?;
         Ok(ret)
     }
+
+    fn keys_with_prefix_after(
+        &self,
+        correlation_id: CorrelationId,
+        prefix: &[u8],
+        after: &[u8],
+        limit: usize,
+    ) -> Result<Vec<Key>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let keys_iter = keys_with_prefix_after::<Key, StoredValue, _, _>(
+            correlation_id,
+            &txn,
+            self.trie_store.deref(),
+            &self.root_hash,
+            prefix,
+            after,
+        );
+        let mut ret = Vec::new();
+        for result in keys_iter.take(limit) {
+            match result {
+                Ok(key) => ret.push(key),
+                Err(error) => return Err(error),
+            }
+        }
+        txn.commit()?;
+        Ok(ret)
+    }
 }
 
 impl CommitProvider for ScratchGlobalState {
@@ -353,6 +380,30 @@ impl StateProvider for ScratchGlobalState {
         txn.commit()?;
         Ok(DeleteResult::Deleted(state_root_hash))
     }
+
+    fn diff(
+        &self,
+        correlation_id: CorrelationId,
+        left_root: Digest,
+        right_root: Digest,
+        prefix: &[u8],
+        after: &[u8],
+        limit: usize,
+    ) -> Result<DiffResult<Key, StoredValue>, Self::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let diff_result = diff::<Key, StoredValue, lmdb::RoTransaction, LmdbTrieStore, Self::Error>(
+            correlation_id,
+            &txn,
+            self.trie_store.deref(),
+            &left_root,
+            &right_root,
+            prefix,
+            after,
+            limit,
+        )?;
+        txn.commit()?;
+        Ok(diff_result)
+    }
 }
 
 #[cfg(test)]
@@ -3,7 +3,13 @@ mod tests;
 
 #[cfg(test)]
 use std::collections::HashSet;
-use std::{borrow::Cow, cmp, collections::VecDeque, convert::TryInto, mem};
+use std::{
+    borrow::Cow,
+    cmp,
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    convert::TryInto,
+    mem,
+};
 
 use either::Either;
 use num_traits::FromPrimitive;
@@ -1035,12 +1041,32 @@ struct VisitedTrieNode<K, V> {
 
 pub struct KeysIterator<'a, 'b, K, V, T, S: TrieStore<K, V>> {
     initial_descend: VecDeque<u8>,
+    /// Only keys whose serialized form sorts after this are returned, if it is not empty.
+    lower_bound: Vec<u8>,
     visited: Vec<VisitedTrieNode<K, V>>,
     store: &'a S,
     txn: &'b T,
     state: KeysIteratorState<K, V, S>,
 }
 
+impl<'a, 'b, K, V, T, S: TrieStore<K, V>> KeysIterator<'a, 'b, K, V, T, S> {
+    /// Returns whether the subtrie at `path` may hold keys on either side of the lower bound.
+    fn is_on_lower_bound_path(&self, path: &[u8]) -> bool {
+        self.lower_bound.len() > path.len() && self.lower_bound.starts_with(path)
+    }
+
+    /// Returns whether all keys below the extension at `path` with the given affix sort before
+    /// the lower bound.
+    fn is_before_lower_bound(&self, path: &[u8], affix: &[u8]) -> bool {
+        if !self.is_on_lower_bound_path(path) {
+            return false;
+        }
+        let bound = &self.lower_bound[path.len()..];
+        let len = cmp::min(bound.len(), affix.len());
+        affix[..len] < bound[..len]
+    }
+}
+
 impl<'a, 'b, K, V, T, S> Iterator for KeysIterator<'a, 'b, K, V, T, S>
 where
     K: ToBytes + FromBytes + Clone + Eq + std::fmt::Debug,
@@ -1080,9 +1106,12 @@ where
                         }
                     };
                     debug_assert!(key_bytes.starts_with(&path));
-                    // only return the leaf if it matches the initial descend path
+                    // only return the leaf if it matches the initial descend path and sorts
+                    // after the lower bound
                     path.extend(&self.initial_descend);
-                    if key_bytes.starts_with(&path) {
+                    if key_bytes.starts_with(&path)
+                        && (self.lower_bound.is_empty() || key_bytes > self.lower_bound)
+                    {
                         return Some(Ok(key));
                     }
                 }
@@ -1096,6 +1125,10 @@ where
                         .map(|i| *i as usize)
                         .or(maybe_index)
                         .unwrap_or_default();
+                    // skip the children holding only keys before the lower bound
+                    if self.initial_descend.is_empty() && self.is_on_lower_bound_path(&path) {
+                        index = cmp::max(index, self.lower_bound[path.len()] as usize);
+                    }
                     while index < RADIX {
                         if let Some(ref pointer) = pointer_block[index] {
                             maybe_next_trie = match self.store.get(self.txn, pointer.hash()) {
@@ -1135,7 +1168,9 @@ where
                     // matches the descend path
                     // if we are not, the check_prefix will be empty, so we will enter the if
                     // anyway
-                    if affix.starts_with(&check_prefix) {
+                    if affix.starts_with(&check_prefix)
+                        && !self.is_before_lower_bound(&path, &affix)
+                    {
                         maybe_next_trie = match self.store.get(self.txn, pointer.hash()) {
                             Ok(trie) => trie,
                             Err(e) => {
@@ -1165,11 +1200,35 @@ where
 ///
 /// The root should be the apex of the trie.
 pub fn keys_with_prefix<'a, 'b, K, V, T, S>(
+    correlation_id: CorrelationId,
+    txn: &'b T,
+    store: &'a S,
+    root: &Digest,
+    prefix: &[u8],
+) -> KeysIterator<'a, 'b, K, V, T, S>
+where
+    K: ToBytes + FromBytes + Clone + Eq + std::fmt::Debug,
+    V: ToBytes + FromBytes + Clone + Eq + std::fmt::Debug,
+    T: Readable<Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<T::Error>,
+{
+    keys_with_prefix_after(correlation_id, txn, store, root, prefix, &[])
+}
+
+/// Returns the iterator over the keys in the subtrie matching `prefix` whose serialized form sorts
+/// after `after`, in ascending order of their serialized form.
+///
+/// Subtries holding only keys which sort before `after` are skipped without being read, so the
+/// iterator can be used to resume an enumeration from a cursor.  An empty `after` places no bound
+/// on the keys.
+pub fn keys_with_prefix_after<'a, 'b, K, V, T, S>(
     _correlation_id: CorrelationId,
     txn: &'b T,
     store: &'a S,
     root: &Digest,
     prefix: &[u8],
+    after: &[u8],
 ) -> KeysIterator<'a, 'b, K, V, T, S>
 where
     K: ToBytes + FromBytes + Clone + Eq + std::fmt::Debug,
@@ -1193,6 +1252,7 @@ where
 
     KeysIterator {
         initial_descend: prefix.iter().cloned().collect(),
+        lower_bound: after.to_vec(),
         visited,
         store,
         txn,
@@ -1200,6 +1260,331 @@ where
     }
 }
 
+/// A difference between the values stored under a key in two tries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrieDiff<K, V> {
+    /// The key is only present in the right trie.
+    Added {
+        /// The key.
+        key: K,
+        /// The value in the right trie.
+        value: V,
+    },
+    /// The key is only present in the left trie.
+    Removed {
+        /// The key.
+        key: K,
+        /// The value in the left trie.
+        value: V,
+    },
+    /// The key is present in both tries with different values.
+    Modified {
+        /// The key.
+        key: K,
+        /// The value in the left trie.
+        old_value: V,
+        /// The value in the right trie.
+        new_value: V,
+    },
+}
+
+impl<K, V> TrieDiff<K, V> {
+    /// Returns the key whose value differs.
+    pub fn key(&self) -> &K {
+        match self {
+            TrieDiff::Added { key, .. }
+            | TrieDiff::Removed { key, .. }
+            | TrieDiff::Modified { key, .. } => key,
+        }
+    }
+}
+
+/// The result of diffing two tries.
+#[derive(Debug, PartialEq, Eq)]
+pub enum DiffResult<K, V> {
+    /// The differences, in ascending order of serialized key.
+    Success(Vec<TrieDiff<K, V>>),
+    /// A trie reachable from either root is missing from the store.
+    TrieNotFound(Digest),
+}
+
+/// A subtrie visited while diffing two tries.
+///
+/// Extensions are walked one byte of their affix at a time, so that they can be compared against
+/// nodes at the same depth.
+#[derive(Clone, PartialEq, Eq)]
+enum DiffCursor {
+    /// A trie stored under the given hash.
+    Stored(Digest),
+    /// The remainder of an extension's affix, followed by its pointer.
+    Extension(Vec<u8>, Pointer),
+}
+
+/// The contents of a subtrie visited while diffing two tries.
+enum ExpandedTrie<K, V> {
+    Leaf(K, V),
+    Node(BTreeMap<u8, DiffCursor>),
+}
+
+enum DiffError<E> {
+    TrieNotFound(Digest),
+    Other(E),
+}
+
+struct TrieDiffer<'a, 'b, K, V, T, S> {
+    txn: &'b T,
+    store: &'a S,
+    prefix: &'a [u8],
+    after: &'a [u8],
+    limit: usize,
+    /// The index bytes leading from the roots to the subtries currently visited.
+    path: Vec<u8>,
+    diffs: Vec<TrieDiff<K, V>>,
+}
+
+impl<'a, 'b, K, V, T, S> TrieDiffer<'a, 'b, K, V, T, S>
+where
+    K: ToBytes + FromBytes,
+    V: ToBytes + FromBytes + Eq,
+    T: Readable<Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<T::Error>,
+{
+    fn diff<E>(
+        &mut self,
+        left: Option<DiffCursor>,
+        right: Option<DiffCursor>,
+        depth: usize,
+    ) -> Result<(), DiffError<E>>
+    where
+        E: From<S::Error> + From<bytesrepr::Error>,
+    {
+        // Identical subtries are skipped without being read, as is everything once the page is
+        // full.
+        if left == right || self.is_full() {
+            return Ok(());
+        }
+        let left = left.map(|cursor| self.expand::<E>(cursor)).transpose()?;
+        let right = right.map(|cursor| self.expand::<E>(cursor)).transpose()?;
+        match (left, right) {
+            (Some(ExpandedTrie::Node(mut left)), Some(ExpandedTrie::Node(mut right))) => {
+                let indices: BTreeSet<u8> = left.keys().chain(right.keys()).copied().collect();
+                for index in indices {
+                    if self.is_excluded(depth, index) {
+                        continue;
+                    }
+                    self.path.push(index);
+                    let result =
+                        self.diff::<E>(left.remove(&index), right.remove(&index), depth + 1);
+                    self.path.pop();
+                    result?;
+                }
+                Ok(())
+            }
+            (left, right) => {
+                // At least one side is a leaf (or absent), so compare the leaves beneath both.  As
+                // at most one side holds more than a single leaf, reading one more leaf per side
+                // than the differences still missing from the page is enough to fill it.
+                let max_leaves = self
+                    .limit
+                    .saturating_sub(self.diffs.len())
+                    .saturating_add(1);
+                let mut left_leaves = BTreeMap::new();
+                if let Some(left) = left {
+                    self.collect_leaves::<E>(left, depth, max_leaves, &mut left_leaves)?;
+                }
+                let mut right_leaves = BTreeMap::new();
+                if let Some(right) = right {
+                    self.collect_leaves::<E>(right, depth, max_leaves, &mut right_leaves)?;
+                }
+                // The differences are only known up to the last leaf read from a side which may
+                // hold further leaves.
+                let bound = [&left_leaves, &right_leaves]
+                    .into_iter()
+                    .filter(|leaves| leaves.len() >= max_leaves)
+                    .filter_map(|leaves| leaves.keys().next_back().cloned())
+                    .min();
+                if let Some(bound) = bound {
+                    left_leaves.retain(|key_bytes, _| *key_bytes <= bound);
+                    right_leaves.retain(|key_bytes, _| *key_bytes <= bound);
+                }
+                self.diff_leaves(left_leaves, right_leaves);
+                Ok(())
+            }
+        }
+    }
+
+    fn expand<E>(&self, cursor: DiffCursor) -> Result<ExpandedTrie<K, V>, DiffError<E>>
+    where
+        E: From<S::Error> + From<bytesrepr::Error>,
+    {
+        let (affix, pointer) = match cursor {
+            DiffCursor::Stored(digest) => {
+                let trie = self
+                    .store
+                    .get(self.txn, &digest)
+                    .map_err(|error| DiffError::Other(E::from(error)))?
+                    .ok_or(DiffError::TrieNotFound(digest))?;
+                match trie {
+                    Trie::Leaf { key, value } => return Ok(ExpandedTrie::Leaf(key, value)),
+                    Trie::Node { pointer_block } => {
+                        let children = pointer_block
+                            .as_indexed_pointers()
+                            .map(|(index, pointer)| {
+                                (index, DiffCursor::Stored(pointer.into_hash()))
+                            })
+                            .collect();
+                        return Ok(ExpandedTrie::Node(children));
+                    }
+                    Trie::Extension { affix, pointer } => (affix.to_vec(), pointer),
+                }
+            }
+            DiffCursor::Extension(affix, pointer) => (affix, pointer),
+        };
+        match affix.split_first() {
+            Some((index, [])) => Ok(ExpandedTrie::Node(BTreeMap::from([(
+                *index,
+                DiffCursor::Stored(pointer.into_hash()),
+            )]))),
+            Some((index, remainder)) => Ok(ExpandedTrie::Node(BTreeMap::from([(
+                *index,
+                DiffCursor::Extension(remainder.to_vec(), pointer),
+            )]))),
+            None => self.expand::<E>(DiffCursor::Stored(pointer.into_hash())),
+        }
+    }
+
+    /// Collects the leaves beneath `trie` which match the prefix and follow the cursor, in
+    /// ascending order of serialized key, until `max_leaves` leaves have been collected.
+    fn collect_leaves<E>(
+        &mut self,
+        trie: ExpandedTrie<K, V>,
+        depth: usize,
+        max_leaves: usize,
+        leaves: &mut BTreeMap<Vec<u8>, (K, V)>,
+    ) -> Result<(), DiffError<E>>
+    where
+        E: From<S::Error> + From<bytesrepr::Error>,
+    {
+        match trie {
+            ExpandedTrie::Leaf(key, value) => {
+                let key_bytes = key
+                    .to_bytes()
+                    .map_err(|error| DiffError::Other(E::from(error)))?;
+                if key_bytes.starts_with(self.prefix) && key_bytes.as_slice() > self.after {
+                    leaves.insert(key_bytes, (key, value));
+                }
+            }
+            ExpandedTrie::Node(children) => {
+                for (index, child) in children {
+                    if leaves.len() >= max_leaves {
+                        break;
+                    }
+                    if self.is_excluded(depth, index) {
+                        continue;
+                    }
+                    let child = self.expand::<E>(child)?;
+                    self.path.push(index);
+                    let result = self.collect_leaves::<E>(child, depth + 1, max_leaves, leaves);
+                    self.path.pop();
+                    result?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn diff_leaves(
+        &mut self,
+        mut left: BTreeMap<Vec<u8>, (K, V)>,
+        right: BTreeMap<Vec<u8>, (K, V)>,
+    ) {
+        let mut diffs = BTreeMap::new();
+        for (key_bytes, (key, value)) in right {
+            match left.remove(&key_bytes) {
+                Some((_, old_value)) if old_value == value => (),
+                Some((_, old_value)) => {
+                    let diff = TrieDiff::Modified {
+                        key,
+                        old_value,
+                        new_value: value,
+                    };
+                    diffs.insert(key_bytes, diff);
+                }
+                None => {
+                    diffs.insert(key_bytes, TrieDiff::Added { key, value });
+                }
+            }
+        }
+        for (key_bytes, (key, value)) in left {
+            diffs.insert(key_bytes, TrieDiff::Removed { key, value });
+        }
+        self.diffs.extend(diffs.into_values());
+        self.diffs.truncate(self.limit);
+    }
+
+    /// Returns `true` once `limit` differences have been found.
+    fn is_full(&self) -> bool {
+        self.diffs.len() >= self.limit
+    }
+
+    /// Returns `true` if the child at `index` of the node at `depth` on the current path cannot
+    /// contain keys matching the prefix, or only contains keys preceding the cursor.
+    fn is_excluded(&self, depth: usize, index: u8) -> bool {
+        if matches!(self.prefix.get(depth), Some(prefix_byte) if *prefix_byte != index) {
+            return true;
+        }
+        match self.after.get(depth) {
+            Some(after_byte) => self.path[..] == self.after[..depth] && index < *after_byte,
+            None => false,
+        }
+    }
+}
+
+/// Returns the first `limit` differences between the key-value pairs under `left_root` and
+/// `right_root` whose serialized keys start with `prefix` and are greater than `after`.
+///
+/// The tries are walked in parallel, and subtries with identical hashes under both roots or
+/// holding only keys up to `after` are skipped without being read, as is everything once `limit`
+/// differences have been found, so the cost is proportional to the size of the returned
+/// differences rather than the size of the tries.
+#[allow(clippy::too_many_arguments)]
+pub fn diff<K, V, T, S, E>(
+    _correlation_id: CorrelationId,
+    txn: &T,
+    store: &S,
+    left_root: &Digest,
+    right_root: &Digest,
+    prefix: &[u8],
+    after: &[u8],
+    limit: usize,
+) -> Result<DiffResult<K, V>, E>
+where
+    K: ToBytes + FromBytes,
+    V: ToBytes + FromBytes + Eq,
+    T: Readable<Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<T::Error>,
+    E: From<S::Error> + From<bytesrepr::Error>,
+{
+    let mut differ = TrieDiffer {
+        txn,
+        store,
+        prefix,
+        after,
+        limit,
+        path: Vec::new(),
+        diffs: Vec::new(),
+    };
+    let left = Some(DiffCursor::Stored(*left_root));
+    let right = Some(DiffCursor::Stored(*right_root));
+    match differ.diff::<E>(left, right, 0) {
+        Ok(()) => Ok(DiffResult::Success(differ.diffs)),
+        Err(DiffError::TrieNotFound(digest)) => Ok(DiffResult::TrieNotFound(digest)),
+        Err(DiffError::Other(error)) => Err(error),
+    }
+}
+
 /// Returns the iterator over the keys at a given root hash.
 ///
 /// The root should be the apex of the trie.
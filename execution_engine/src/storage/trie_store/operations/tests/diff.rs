@@ -0,0 +1,275 @@
+use std::collections::BTreeMap;
+
+use super::*;
+use crate::storage::trie_store::operations::{DiffResult, TrieDiff};
+
+fn leaves_to_map(leaves: &[TestTrie]) -> BTreeMap<TestKey, TestValue> {
+    leaves
+        .iter()
+        .map(|leaf| match leaf {
+            Trie::Leaf { key, value } => (*key, *value),
+            _ => panic!("leaves should contain only leaves"),
+        })
+        .collect()
+}
+
+fn expected_diffs(
+    left: &[TestTrie],
+    right: &[TestTrie],
+    prefix: &[u8],
+) -> Vec<TrieDiff<TestKey, TestValue>> {
+    let left = leaves_to_map(left);
+    let right = leaves_to_map(right);
+    let mut keys: Vec<TestKey> = left.keys().chain(right.keys()).copied().collect();
+    keys.sort();
+    keys.dedup();
+    keys.into_iter()
+        .filter(|key| key.0.starts_with(prefix))
+        .filter_map(|key| match (left.get(&key), right.get(&key)) {
+            (None, Some(value)) => Some(TrieDiff::Added { key, value: *value }),
+            (Some(value), None) => Some(TrieDiff::Removed { key, value: *value }),
+            (Some(old_value), Some(new_value)) if old_value != new_value => {
+                Some(TrieDiff::Modified {
+                    key,
+                    old_value: *old_value,
+                    new_value: *new_value,
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn write_root<'a, R, S, E>(
+    correlation_id: CorrelationId,
+    environment: &'a R,
+    store: &S,
+    root_hash: &Digest,
+    leaves: &[TestTrie],
+) -> Result<Digest, E>
+where
+    R: TransactionSource<'a, Handle = S::Handle>,
+    S: TrieStore<TestKey, TestValue>,
+    S::Error: From<R::Error>,
+    E: From<R::Error> + From<S::Error> + From<bytesrepr::Error>,
+{
+    let results =
+        write_leaves::<_, _, _, _, E>(correlation_id, environment, store, root_hash, leaves)?;
+    let root_hash = results
+        .into_iter()
+        .filter_map(|result| match result {
+            WriteResult::Written(hash) => Some(hash),
+            _ => None,
+        })
+        .last()
+        .unwrap_or(*root_hash);
+    Ok(root_hash)
+}
+
+fn check_diff<'a, R, S, E>(
+    correlation_id: CorrelationId,
+    environment: &'a R,
+    store: &S,
+    (left_root, left_leaves): (&Digest, &[TestTrie]),
+    (right_root, right_leaves): (&Digest, &[TestTrie]),
+    prefix: &[u8],
+) -> Result<(), E>
+where
+    R: TransactionSource<'a, Handle = S::Handle>,
+    S: TrieStore<TestKey, TestValue>,
+    S::Error: From<R::Error>,
+    E: From<R::Error> + From<S::Error> + From<bytesrepr::Error>,
+{
+    let txn: R::ReadTransaction = environment.create_read_txn()?;
+    let actual = operations::diff::<TestKey, TestValue, _, _, E>(
+        correlation_id,
+        &txn,
+        store,
+        left_root,
+        right_root,
+        prefix,
+        &[],
+        usize::MAX,
+    )?;
+    txn.commit()?;
+    let expected = expected_diffs(left_leaves, right_leaves, prefix);
+    assert_eq!(actual, DiffResult::Success(expected.clone()));
+
+    // Reading the differences in pages yields the same differences.
+    for limit in 1..=expected.len() {
+        let mut paged = Vec::new();
+        let mut after = Vec::new();
+        loop {
+            let txn: R::ReadTransaction = environment.create_read_txn()?;
+            let page = match operations::diff::<TestKey, TestValue, _, _, E>(
+                correlation_id,
+                &txn,
+                store,
+                left_root,
+                right_root,
+                prefix,
+                &after,
+                limit,
+            )? {
+                DiffResult::Success(page) => page,
+                DiffResult::TrieNotFound(digest) => panic!("trie {} should be present", digest),
+            };
+            txn.commit()?;
+            assert!(page.len() <= limit);
+            let last_key = match page.last() {
+                Some(diff) => *diff.key(),
+                None => break,
+            };
+            paged.extend(page);
+            after = last_key.to_bytes()?;
+        }
+        assert_eq!(paged, expected);
+    }
+    Ok(())
+}
+
+fn diffs_had_expected_results<'a, R, S, E>(
+    correlation_id: CorrelationId,
+    environment: &'a R,
+    store: &S,
+    empty_root: &Digest,
+) -> Result<(), E>
+where
+    R: TransactionSource<'a, Handle = S::Handle>,
+    S: TrieStore<TestKey, TestValue>,
+    S::Error: From<R::Error>,
+    E: From<R::Error> + From<S::Error> + From<bytesrepr::Error>,
+{
+    let full_root =
+        write_root::<_, _, E>(correlation_id, environment, store, empty_root, &TEST_LEAVES)?;
+    let full = (&full_root, &TEST_LEAVES[..]);
+
+    // Identical roots have no differences.
+    check_diff::<_, _, E>(correlation_id, environment, store, full, full, &[])?;
+
+    for num_leaves in 0..TEST_LEAVES_LENGTH {
+        let partial_leaves = &TEST_LEAVES[..num_leaves];
+        let partial_root = write_root::<_, _, E>(
+            correlation_id,
+            environment,
+            store,
+            empty_root,
+            partial_leaves,
+        )?;
+        let partial = (&partial_root, partial_leaves);
+
+        // Leaves written on top of a trie are added, and removed in the opposite direction.
+        check_diff::<_, _, E>(correlation_id, environment, store, partial, full, &[])?;
+        check_diff::<_, _, E>(correlation_id, environment, store, full, partial, &[])?;
+
+        // Leaves overwritten with different values are modified.
+        let mut updated_leaves = TEST_LEAVES.to_vec();
+        updated_leaves[..num_leaves].clone_from_slice(&TEST_LEAVES_UPDATED[..num_leaves]);
+        let updated_root = write_root::<_, _, E>(
+            correlation_id,
+            environment,
+            store,
+            &full_root,
+            &TEST_LEAVES_UPDATED[..num_leaves],
+        )?;
+        let updated = (&updated_root, &updated_leaves[..]);
+        check_diff::<_, _, E>(correlation_id, environment, store, full, updated, &[])?;
+        check_diff::<_, _, E>(correlation_id, environment, store, partial, updated, &[])?;
+
+        // Only differences under keys with the given prefix are reported.
+        for prefix in [&[0u8][..], &[0, 0], &[0, 1], &[0, 0, 0, 2], &[1]] {
+            check_diff::<_, _, E>(correlation_id, environment, store, partial, updated, prefix)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn missing_root_reported<'a, R, S, E>(
+    correlation_id: CorrelationId,
+    environment: &'a R,
+    store: &S,
+    root: &Digest,
+) -> Result<(), E>
+where
+    R: TransactionSource<'a, Handle = S::Handle>,
+    S: TrieStore<TestKey, TestValue>,
+    S::Error: From<R::Error>,
+    E: From<R::Error> + From<S::Error> + From<bytesrepr::Error>,
+{
+    let missing_root = Digest::hash(b"missing");
+    let txn: R::ReadTransaction = environment.create_read_txn()?;
+    let actual = operations::diff::<TestKey, TestValue, _, _, E>(
+        correlation_id,
+        &txn,
+        store,
+        root,
+        &missing_root,
+        &[],
+        &[],
+        usize::MAX,
+    )?;
+    txn.commit()?;
+    assert_eq!(actual, DiffResult::TrieNotFound(missing_root));
+    Ok(())
+}
+
+#[test]
+fn lmdb_diffs_had_expected_results() {
+    let correlation_id = CorrelationId::new();
+    let (root_hash, tries) = TEST_TRIE_GENERATORS[0]().unwrap();
+    let context = LmdbTestContext::new(&tries).unwrap();
+
+    diffs_had_expected_results::<_, _, error::Error>(
+        correlation_id,
+        &context.environment,
+        &context.store,
+        &root_hash,
+    )
+    .unwrap();
+}
+
+#[test]
+fn in_memory_diffs_had_expected_results() {
+    let correlation_id = CorrelationId::new();
+    let (root_hash, tries) = TEST_TRIE_GENERATORS[0]().unwrap();
+    let context = InMemoryTestContext::new(&tries).unwrap();
+
+    diffs_had_expected_results::<_, _, in_memory::Error>(
+        correlation_id,
+        &context.environment,
+        &context.store,
+        &root_hash,
+    )
+    .unwrap();
+}
+
+#[test]
+fn lmdb_diff_with_missing_root_reported() {
+    let correlation_id = CorrelationId::new();
+    let (root_hash, tries) = TEST_TRIE_GENERATORS[2]().unwrap();
+    let context = LmdbTestContext::new(&tries).unwrap();
+
+    missing_root_reported::<_, _, error::Error>(
+        correlation_id,
+        &context.environment,
+        &context.store,
+        &root_hash,
+    )
+    .unwrap();
+}
+
+#[test]
+fn in_memory_diff_with_missing_root_reported() {
+    let correlation_id = CorrelationId::new();
+    let (root_hash, tries) = TEST_TRIE_GENERATORS[2]().unwrap();
+    let context = InMemoryTestContext::new(&tries).unwrap();
+
+    missing_root_reported::<_, _, in_memory::Error>(
+        correlation_id,
+        &context.environment,
+        &context.store,
+        &root_hash,
+    )
+    .unwrap();
+}
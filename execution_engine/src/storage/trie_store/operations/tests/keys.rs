@@ -314,3 +314,68 @@ mod keys_with_prefix_iterator {
         test_prefix(&[0, 0, 0, 0, 0, 0, 1]); // 1 leaf
     }
 }
+
+mod keys_with_prefix_after_iterator {
+    use crate::{
+        shared::newtypes::CorrelationId,
+        storage::{
+            transaction_source::TransactionSource,
+            trie::Trie,
+            trie_store::operations::{
+                self,
+                tests::{create_6_leaf_trie, InMemoryTestContext, TestKey, TestValue, TEST_LEAVES},
+            },
+        },
+    };
+
+    fn expected_keys(prefix: &[u8], after: &[u8]) -> Vec<TestKey> {
+        let mut tmp = TEST_LEAVES
+            .iter()
+            .filter_map(Trie::key)
+            .filter(|key| key.0.starts_with(prefix) && key.0.as_slice() > after)
+            .cloned()
+            .collect::<Vec<TestKey>>();
+        tmp.sort();
+        tmp
+    }
+
+    fn test_prefix_after(prefix: &[u8], after: &[u8]) {
+        let correlation_id = CorrelationId::new();
+        let (root_hash, tries) = create_6_leaf_trie().expect("should create a trie");
+        let context = InMemoryTestContext::new(&tries).expect("should create a new context");
+        let txn = context
+            .environment
+            .create_read_txn()
+            .expect("should create a read txn");
+        let expected = expected_keys(prefix, after);
+        // The keys are expected in ascending order without sorting them.
+        let actual = operations::keys_with_prefix_after::<TestKey, TestValue, _, _>(
+            correlation_id,
+            &txn,
+            &context.store,
+            &root_hash,
+            prefix,
+            after,
+        )
+        .filter_map(Result::ok)
+        .collect::<Vec<_>>();
+        assert_eq!(expected, actual, "prefix {:?} after {:?}", prefix, after);
+    }
+
+    #[test]
+    fn test_prefixes_after() {
+        test_prefix_after(&[], &[]); // 6 leaves
+        test_prefix_after(&[], &[0]); // 6 leaves
+        test_prefix_after(&[], &[0, 0, 0, 0, 0, 0, 0]); // 5 leaves, bound is a leaf
+        test_prefix_after(&[], &[0, 0, 0, 0, 0, 0, 1]); // 4 leaves
+        test_prefix_after(&[], &[0, 0, 0, 0, 0, 1]); // 4 leaves, bound within an extension
+        test_prefix_after(&[], &[0, 0, 0, 1]); // 3 leaves, bound between children of a node
+        test_prefix_after(&[], &[0, 0, 3]); // 1 leaf
+        test_prefix_after(&[], &[0, 1, 0, 0, 0, 0, 0]); // 0 leaves, bound is the last leaf
+        test_prefix_after(&[], &[1]); // 0 leaves
+        test_prefix_after(&[0, 0], &[0, 0, 0, 0, 0, 0, 1]); // 3 leaves
+        test_prefix_after(&[0, 0, 0], &[0, 0, 0, 1]); // 1 leaf
+        test_prefix_after(&[0, 1], &[0, 0, 2, 0, 0, 0, 0]); // 1 leaf, bound before the prefix
+        test_prefix_after(&[0, 0], &[0, 1]); // 0 leaves, bound after the prefix
+    }
+}
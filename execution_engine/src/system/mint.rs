@@ -8,8 +8,10 @@ use tracing::warn;
 
 use casper_types::{
     account::AccountHash,
+    bytesrepr::ToBytes,
+    crypto,
     system::{
-        mint::{Error, ROUND_SEIGNIORAGE_RATE_KEY, TOTAL_SUPPLY_KEY},
+        mint::{Error, ALLOWANCES_KEY, ROUND_SEIGNIORAGE_RATE_KEY, TOTAL_SUPPLY_KEY},
         CallStackElement,
     },
     Key, Phase, PublicKey, StoredValue, URef, U512,
@@ -253,7 +255,7 @@ pub trait Mint: RuntimeProvider + StorageProvider + SystemProvider {
         }
         self.write_balance(source, source_balance - amount)?;
         self.add_balance(target, amount)?;
-        self.record_transfer(maybe_to, source, target, amount, id)?;
+        self.record_transfer(self.get_caller(), maybe_to, source, target, amount, id)?;
         Ok(())
     }
 
@@ -317,4 +319,129 @@ pub trait Mint: RuntimeProvider + StorageProvider + SystemProvider {
         self.add(total_supply_uref, amount)?;
         Ok(())
     }
+
+    /// Allows `spender` to transfer up to `amount` of tokens from the caller's main purse,
+    /// replacing any previous allowance.  An `amount` of zero revokes the allowance.
+    ///
+    /// Only the account itself may grant an allowance, i.e. this must be called directly by session
+    /// code of the caller's account.
+    fn approve(&mut self, spender: Key, amount: U512) -> Result<(), Error> {
+        let caller = self.get_caller();
+        match (self.get_phase(), self.get_immediate_caller()) {
+            (Phase::Session, Some(CallStackElement::Session { account_hash }))
+                if *account_hash == caller => {}
+            _ => return Err(Error::InvalidContext),
+        }
+        if !matches!(spender, Key::Account(_) | Key::Hash(_)) {
+            return Err(Error::InvalidSpender);
+        }
+        if !self.allow_unrestricted_transfers() && !self.is_administrator(&caller) {
+            return Err(Error::DisabledUnrestrictedTransfers);
+        }
+
+        let allowances_uref = match self.get_key(ALLOWANCES_KEY) {
+            Some(Key::URef(uref)) => uref,
+            Some(_) => return Err(Error::MissingKey),
+            None => {
+                // The dictionary is created on first use, so networks upgraded from a version
+                // without allowances need no migration.
+                let uref = self.new_uref(())?;
+                self.put_key(ALLOWANCES_KEY, Key::URef(uref))?;
+                uref
+            }
+        };
+        let item_key = allowance_item_key(caller, spender)?;
+        self.write_dictionary_item(allowances_uref, &item_key, amount)
+    }
+
+    /// Returns the amount of tokens `spender` may still transfer from the main purse of `owner`.
+    fn allowance(&mut self, owner: AccountHash, spender: Key) -> Result<U512, Error> {
+        let allowances_uref = match self.get_key(ALLOWANCES_KEY) {
+            Some(Key::URef(uref)) => uref,
+            Some(_) => return Err(Error::MissingKey),
+            None => return Ok(U512::zero()),
+        };
+        let item_key = allowance_item_key(owner, spender)?;
+        Ok(self
+            .read_dictionary_item(allowances_uref, &item_key)?
+            .unwrap_or_default())
+    }
+
+    /// Transfers `amount` of tokens from the main purse of `owner` to the `target` purse, deducting
+    /// it from the allowance granted by `owner` to the immediate caller.
+    fn transfer_from(
+        &mut self,
+        owner: AccountHash,
+        target: URef,
+        amount: U512,
+        id: Option<u64>,
+    ) -> Result<(), Error> {
+        let spender = match self.get_immediate_caller() {
+            Some(CallStackElement::Session { account_hash }) => Key::Account(*account_hash),
+            Some(CallStackElement::StoredSession { contract_hash, .. })
+            | Some(CallStackElement::StoredContract { contract_hash, .. }) => {
+                Key::Hash(contract_hash.value())
+            }
+            None => return Err(Error::InvalidContext),
+        };
+        if !self.allow_unrestricted_transfers() && !self.is_administrator(&self.get_caller()) {
+            return Err(Error::DisabledUnrestrictedTransfers);
+        }
+
+        let allowance = self.allowance(owner, spender)?;
+        if amount > allowance {
+            return Err(Error::InsufficientAllowance);
+        }
+
+        let source = match self.read_account(&owner) {
+            Ok(Some(StoredValue::Account(account))) => account.main_purse(),
+            Ok(_) => return Err(Error::SourceNotFound),
+            Err(error) => {
+                warn!(%error, "error while reading account");
+                return Err(Error::Storage);
+            }
+        };
+        if source.addr() == target.addr() {
+            return Err(Error::EqualSourceAndTarget);
+        }
+        if !target.is_addable() {
+            return Err(Error::InvalidAccessRights);
+        }
+        let source_balance: U512 = match self.read_balance(source)? {
+            Some(source_balance) => source_balance,
+            None => return Err(Error::SourceNotFound),
+        };
+        if amount > source_balance {
+            return Err(Error::InsufficientFunds);
+        }
+        if self.read_balance(target)?.is_none() {
+            return Err(Error::DestNotFound);
+        }
+        // An allowance doesn't lift the spending limit the owner approved for their own deploy.
+        if owner == self.get_caller() {
+            if amount > self.get_approved_spending_limit() {
+                return Err(Error::UnapprovedSpendingAmount);
+            }
+            self.sub_approved_spending_limit(amount);
+        }
+
+        let allowances_uref = match self.get_key(ALLOWANCES_KEY) {
+            Some(Key::URef(uref)) => uref,
+            _ => return Err(Error::MissingKey),
+        };
+        let item_key = allowance_item_key(owner, spender)?;
+        self.write_dictionary_item(allowances_uref, &item_key, allowance - amount)?;
+        self.write_balance(source, source_balance - amount)?;
+        self.add_balance(target, amount)?;
+        // The transfer is recorded as made by the owner, whose purse it debits.
+        self.record_transfer(owner, None, source, target, amount, id)?;
+        Ok(())
+    }
+}
+
+/// Returns the key of the item holding the allowance of `spender` over the main purse of `owner`
+/// in the allowances dictionary.
+fn allowance_item_key(owner: AccountHash, spender: Key) -> Result<String, Error> {
+    let bytes = (owner, spender).to_bytes().map_err(|_| Error::Serialize)?;
+    Ok(base16::encode_lower(&crypto::blake2b(bytes)))
 }
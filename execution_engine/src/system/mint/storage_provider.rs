@@ -26,4 +26,19 @@ pub trait StorageProvider {
 
     /// Add amount to an existing balance.
     fn add_balance(&mut self, uref: URef, value: U512) -> Result<(), Error>;
+
+    /// Read data from an item of the dictionary seeded by the given [`URef`].
+    fn read_dictionary_item<T: CLTyped + FromBytes>(
+        &mut self,
+        seed_uref: URef,
+        dictionary_item_key: &str,
+    ) -> Result<Option<T>, Error>;
+
+    /// Write data to an item of the dictionary seeded by the given [`URef`].
+    fn write_dictionary_item<T: CLTyped + ToBytes>(
+        &mut self,
+        seed_uref: URef,
+        dictionary_item_key: &str,
+        value: T,
+    ) -> Result<(), Error>;
 }
@@ -2,9 +2,10 @@ use casper_types::{account::AccountHash, system::mint::Error, URef, U512};
 
 /// Provides functionality of a system module.
 pub trait SystemProvider {
-    /// Records a transfer.
+    /// Records a transfer made by the account `from`.
     fn record_transfer(
         &mut self,
+        from: AccountHash,
         maybe_to: Option<AccountHash>,
         source: URef,
         target: URef,
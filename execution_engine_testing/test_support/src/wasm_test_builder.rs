@@ -31,6 +31,7 @@ use casper_execution_engine::{
             DEFAULT_MAX_QUERY_DEPTH,
         },
         execution,
+        runtime::call_trace::CallTrace,
     },
     shared::{
         additive_map::AdditiveMap,
@@ -101,6 +102,8 @@ pub struct WasmTestBuilder<S> {
     engine_state: Rc<EngineState<S>>,
     /// [`ExecutionResult`] is wrapped in [`Rc`] to work around a missing [`Clone`] implementation
     exec_results: Vec<Vec<Rc<ExecutionResult>>>,
+    /// Call traces recorded by each traced exec call.
+    call_traces: Vec<Vec<CallTrace>>,
     upgrade_results: Vec<Result<UpgradeSuccess, engine_state::Error>>,
     prune_results: Vec<Result<PruneResult, engine_state::Error>>,
     genesis_hash: Option<Digest>,
@@ -141,6 +144,7 @@ impl<S> Clone for WasmTestBuilder<S> {
         WasmTestBuilder {
             engine_state: Rc::clone(&self.engine_state),
             exec_results: self.exec_results.clone(),
+            call_traces: self.call_traces.clone(),
             upgrade_results: self.upgrade_results.clone(),
             prune_results: self.prune_results.clone(),
             genesis_hash: self.genesis_hash,
@@ -168,6 +172,7 @@ impl InMemoryWasmTestBuilder {
 
         WasmTestBuilder {
             exec_results: Vec::new(),
+            call_traces: Vec::new(),
             upgrade_results: Vec::new(),
             prune_results: Vec::new(),
             engine_state: Rc::new(engine_state),
@@ -193,6 +198,7 @@ impl InMemoryWasmTestBuilder {
         WasmTestBuilder {
             engine_state: Rc::new(engine_state),
             exec_results: Vec::new(),
+            call_traces: Vec::new(),
             upgrade_results: Vec::new(),
             prune_results: Vec::new(),
             genesis_hash: maybe_post_state_hash,
@@ -331,6 +337,7 @@ impl LmdbWasmTestBuilder {
         WasmTestBuilder {
             engine_state: Rc::new(engine_state),
             exec_results: Vec::new(),
+            call_traces: Vec::new(),
             upgrade_results: Vec::new(),
             prune_results: Vec::new(),
             genesis_hash: None,
@@ -436,6 +443,7 @@ impl LmdbWasmTestBuilder {
         let mut builder = WasmTestBuilder {
             engine_state: Rc::new(engine_state),
             exec_results: Vec::new(),
+            call_traces: Vec::new(),
             upgrade_results: Vec::new(),
             prune_results: Vec::new(),
             genesis_hash: None,
@@ -763,6 +771,34 @@ where
         self
     }
 
+    /// Runs an [`ExecuteRequest`] like [`WasmTestBuilder::exec`], also recording the call frames
+    /// entered while executing each deploy.
+    ///
+    /// The traces are available via [`WasmTestBuilder::get_last_call_traces`].
+    pub fn exec_with_call_traces(&mut self, mut exec_request: ExecuteRequest) -> &mut Self {
+        let exec_request = {
+            let hash = self.post_state_hash.expect("expected post_state_hash");
+            exec_request.parent_state_hash = hash;
+            exec_request
+        };
+
+        let (execution_results, call_traces): (Vec<_>, Vec<_>) = self
+            .engine_state
+            .run_execute_with_call_traces(CorrelationId::new(), exec_request)
+            .expect("should execute with call traces")
+            .into_iter()
+            .unzip();
+        self.transforms.extend(
+            execution_results
+                .iter()
+                .map(|res| res.execution_journal().clone()),
+        );
+        self.exec_results
+            .push(execution_results.into_iter().map(Rc::new).collect());
+        self.call_traces.push(call_traces);
+        self
+    }
+
     /// Commit effects of previous exec call on the latest post-state hash.
     pub fn commit(&mut self) -> &mut Self {
         let prestate_hash = self.post_state_hash.expect("Should have genesis hash");
@@ -1002,6 +1038,11 @@ where
         Some(exec_results.iter().map(Rc::clone).collect())
     }
 
+    /// Returns the call traces recorded by the last traced exec.
+    pub fn get_last_call_traces(&self) -> Option<&[CallTrace]> {
+        self.call_traces.last().map(Vec::as_slice)
+    }
+
     /// Returns the owned results of a specific exec.
     pub fn get_exec_result_owned(&self, index: usize) -> Option<Vec<Rc<ExecutionResult>>> {
         let exec_results = self.exec_results.get(index)?;
@@ -0,0 +1,78 @@
+use casper_engine_test_support::{
+    ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_ACCOUNT_ADDR,
+    PRODUCTION_RUN_GENESIS_REQUEST,
+};
+use casper_execution_engine::core::runtime::call_trace::CallFrame;
+use casper_types::{
+    contracts::DEFAULT_ENTRY_POINT_NAME, runtime_args, system::CallStackElement, ApiError, Phase,
+    RuntimeArgs,
+};
+
+const CONTRACT_EMIT_EVENT: &str = "emit_event.wasm";
+const ARG_TOPIC: &str = "topic";
+const ARG_VALUE: &str = "value";
+const ARG_REVERT: &str = "revert";
+const EMIT_EVENT_HOST_FUNCTION: &str = "casper_emit_event";
+
+fn exec_with_call_traces(revert: bool) -> InMemoryWasmTestBuilder {
+    let exec_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_EMIT_EVENT,
+        runtime_args! {
+            ARG_TOPIC => "transfer".to_string(),
+            ARG_VALUE => 42u64,
+            ARG_REVERT => revert,
+        },
+    )
+    .build();
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder
+        .run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST)
+        .exec_with_call_traces(exec_request)
+        .commit();
+    builder
+}
+
+fn session_frame(builder: &InMemoryWasmTestBuilder) -> CallFrame {
+    let call_traces = builder
+        .get_last_call_traces()
+        .expect("should have call traces");
+    assert_eq!(call_traces.len(), 1);
+    call_traces[0]
+        .frames()
+        .iter()
+        .find(|frame| frame.phase == Phase::Session)
+        .cloned()
+        .expect("should have session frame")
+}
+
+#[ignore]
+#[test]
+fn should_trace_session_frame() {
+    let mut builder = exec_with_call_traces(false);
+    builder.expect_success();
+
+    let frame = session_frame(&builder);
+    assert_eq!(frame.depth, 0);
+    assert_eq!(frame.entry_point, DEFAULT_ENTRY_POINT_NAME);
+    assert_eq!(
+        frame.call_stack_element,
+        Some(CallStackElement::session(*DEFAULT_ACCOUNT_ADDR))
+    );
+    assert!(frame.args_size > 0);
+    assert!(frame.gas_used.value() > 0.into());
+    assert_eq!(frame.revert_code, None);
+    assert_eq!(frame.error, None);
+    assert_eq!(frame.host_function_calls[EMIT_EVENT_HOST_FUNCTION], 1);
+}
+
+#[ignore]
+#[test]
+fn should_trace_revert_code() {
+    let builder = exec_with_call_traces(true);
+
+    let frame = session_frame(&builder);
+    assert_eq!(frame.revert_code, Some(u32::from(ApiError::User(0))));
+    assert!(frame.error.is_some());
+}
@@ -0,0 +1,88 @@
+use casper_engine_test_support::{
+    ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_ACCOUNT_ADDR,
+    PRODUCTION_RUN_GENESIS_REQUEST,
+};
+use casper_execution_engine::core::{engine_state::Error, execution};
+use casper_types::{
+    runtime_args, ApiError, CLValue, ContractEvent, Key, RuntimeArgs, EVENT_TOPIC_MAX_LENGTH,
+};
+
+const CONTRACT_EMIT_EVENT: &str = "emit_event.wasm";
+const ARG_TOPIC: &str = "topic";
+const ARG_VALUE: &str = "value";
+const ARG_REVERT: &str = "revert";
+const TOPIC: &str = "transfer";
+const VALUE: u64 = 42;
+
+fn exec_emit_event(topic: &str, revert: bool) -> InMemoryWasmTestBuilder {
+    let exec_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_EMIT_EVENT,
+        runtime_args! {
+            ARG_TOPIC => topic.to_string(),
+            ARG_VALUE => VALUE,
+            ARG_REVERT => revert,
+        },
+    )
+    .build();
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder
+        .run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST)
+        .exec(exec_request)
+        .commit();
+    builder
+}
+
+#[ignore]
+#[test]
+fn should_record_emitted_event() {
+    let mut builder = exec_emit_event(TOPIC, false);
+    builder.expect_success();
+
+    let exec_results = builder
+        .get_last_exec_results()
+        .expect("should have exec results");
+    let expected_event = ContractEvent::new(
+        Key::Account(*DEFAULT_ACCOUNT_ADDR),
+        TOPIC.to_string(),
+        CLValue::from_t(VALUE).unwrap(),
+    );
+    assert_eq!(exec_results[0].events(), &[expected_event]);
+}
+
+#[ignore]
+#[test]
+fn should_not_record_events_of_failed_deploy() {
+    let builder = exec_emit_event(TOPIC, true);
+
+    let error = builder.get_error().expect("should have error");
+    assert!(matches!(
+        error,
+        Error::Exec(execution::Error::Revert(ApiError::User(0)))
+    ));
+
+    let exec_results = builder
+        .get_last_exec_results()
+        .expect("should have exec results");
+    assert!(exec_results[0].events().is_empty());
+}
+
+#[ignore]
+#[test]
+fn should_reject_invalid_topic() {
+    let too_long_topic = "a".repeat(EVENT_TOPIC_MAX_LENGTH + 1);
+    for topic in ["", too_long_topic.as_str()] {
+        let builder = exec_emit_event(topic, false);
+
+        let error = builder.get_error().expect("should have error");
+        assert!(
+            matches!(
+                error,
+                Error::Exec(execution::Error::Revert(ApiError::InvalidArgument))
+            ),
+            "{:?}",
+            error
+        );
+    }
+}
@@ -1,6 +1,8 @@
 mod account;
+mod call_trace;
 mod create_purse;
 mod dictionary;
+mod emit_event;
 mod get_arg;
 mod get_blocktime;
 mod get_call_stack;
@@ -1,8 +1,16 @@
+use std::collections::BTreeMap;
+
 use casper_engine_test_support::{
     ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_ACCOUNT_ADDR,
     PRODUCTION_RUN_GENESIS_REQUEST,
 };
-use casper_types::{runtime_args, RuntimeArgs};
+use casper_execution_engine::{
+    core::engine_state::{
+        GetDictionaryItemsRequest, GetDictionaryItemsResult, GetKeysRequest, GetKeysResult,
+    },
+    shared::newtypes::CorrelationId,
+};
+use casper_types::{runtime_args, Key, KeyTag, RuntimeArgs};
 use rand::{rngs::StdRng, Rng, SeedableRng};
 
 #[ignore]
@@ -32,3 +40,85 @@ fn named_dictionaries_should_work_as_expected() {
         )
         .expect_success();
 }
+
+#[ignore]
+#[test]
+fn should_enumerate_dictionary_items_in_pages() {
+    const DICTIONARY_NAME: &str = "the";
+    const PAGE_SIZE: usize = 3;
+
+    let puts: Vec<(u8, u8, u8)> = (0..10)
+        .map(|key_seed| (0, key_seed, key_seed * 2))
+        .collect();
+
+    let builder = &mut InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+    builder
+        .exec(
+            ExecuteRequestBuilder::standard(
+                *DEFAULT_ACCOUNT_ADDR,
+                "named-dictionary-test.wasm",
+                runtime_args! { "puts" => puts.clone() },
+            )
+            .build(),
+        )
+        .commit()
+        .expect_success();
+
+    let seed_uref = builder
+        .get_account(*DEFAULT_ACCOUNT_ADDR)
+        .expect("should have account")
+        .named_keys()
+        .get(DICTIONARY_NAME)
+        .and_then(Key::as_uref)
+        .copied()
+        .expect("should have dictionary seed uref");
+    let state_hash = builder.get_post_state_hash();
+
+    let mut items = BTreeMap::new();
+    let mut after = None;
+    loop {
+        let request = GetDictionaryItemsRequest::new(state_hash, seed_uref, after, PAGE_SIZE);
+        let (page, next) = match builder
+            .get_engine_state()
+            .get_dictionary_items(CorrelationId::new(), request)
+            .expect("should get dictionary items")
+        {
+            GetDictionaryItemsResult::Success { items, next } => (items, next),
+            GetDictionaryItemsResult::RootNotFound => panic!("should have state root"),
+        };
+        assert!(page.len() <= PAGE_SIZE);
+        for item in page {
+            let item_key = String::from_utf8(item.dictionary_item_key.to_vec()).unwrap();
+            assert_eq!(item.key, Key::dictionary(seed_uref, item_key.as_bytes()));
+            let value: u8 = item.value.into_t().unwrap();
+            assert!(items.insert(item_key, value).is_none());
+        }
+        match next {
+            Some(next) => after = Some(next),
+            None => break,
+        }
+    }
+
+    let expected_items: BTreeMap<String, u8> = puts
+        .into_iter()
+        .map(|(_, key_seed, value)| (key_seed.to_string(), value))
+        .collect();
+    assert_eq!(items, expected_items);
+
+    let request = GetKeysRequest::new(state_hash, KeyTag::Dictionary, None, usize::MAX);
+    let dictionary_keys = match builder
+        .get_engine_state()
+        .get_keys(CorrelationId::new(), request)
+        .expect("should get keys")
+    {
+        GetKeysResult::Success { keys, next } => {
+            assert!(next.is_none());
+            keys
+        }
+        GetKeysResult::RootNotFound => panic!("should have state root"),
+    };
+    for item_key in expected_items.keys() {
+        assert!(dictionary_keys.contains(&Key::dictionary(seed_uref, item_key.as_bytes())));
+    }
+}
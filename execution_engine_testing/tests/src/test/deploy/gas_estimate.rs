@@ -0,0 +1,101 @@
+use casper_engine_test_support::{
+    DeployItemBuilder, ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_ACCOUNT_ADDR,
+    DEFAULT_GAS_PRICE, MINIMUM_ACCOUNT_CREATION_BALANCE, PRODUCTION_RUN_GENESIS_REQUEST,
+};
+use casper_execution_engine::{
+    core::engine_state::{ExecuteRequest, GasEstimate},
+    shared::newtypes::CorrelationId,
+};
+use casper_types::{account::AccountHash, runtime_args, Gas, RuntimeArgs, U512};
+
+const ACCOUNT_1_ADDR: AccountHash = AccountHash::new([42u8; 32]);
+const CONTRACT_TRANSFER_TO_ACCOUNT: &str = "transfer_to_account_u512.wasm";
+const ARG_TARGET: &str = "target";
+const ARG_AMOUNT: &str = "amount";
+
+fn transfer_request(payment_amount: U512) -> ExecuteRequest {
+    let deploy = DeployItemBuilder::new()
+        .with_address(*DEFAULT_ACCOUNT_ADDR)
+        .with_session_code(
+            CONTRACT_TRANSFER_TO_ACCOUNT,
+            runtime_args! {
+                ARG_TARGET => ACCOUNT_1_ADDR,
+                ARG_AMOUNT => U512::from(MINIMUM_ACCOUNT_CREATION_BALANCE),
+            },
+        )
+        .with_empty_payment_bytes(runtime_args! { ARG_AMOUNT => payment_amount })
+        .with_authorization_keys(&[*DEFAULT_ACCOUNT_ADDR])
+        .with_deploy_hash([1; 32])
+        .build();
+
+    ExecuteRequestBuilder::new().push_deploy(deploy).build()
+}
+
+fn estimate_gas(builder: &InMemoryWasmTestBuilder, payment_amount: U512) -> GasEstimate {
+    let mut exec_request = transfer_request(payment_amount);
+    exec_request.parent_state_hash = builder.get_post_state_hash();
+
+    let mut estimates = builder
+        .get_engine_state()
+        .estimate_gas(CorrelationId::new(), exec_request)
+        .expect("should estimate gas");
+    assert_eq!(estimates.len(), 1);
+    estimates.pop().unwrap()
+}
+
+#[ignore]
+#[test]
+fn should_estimate_gas_regardless_of_payment_amount() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+
+    let estimate = estimate_gas(&builder, U512::one());
+    assert!(estimate.error().is_none(), "{:?}", estimate.error());
+    assert_eq!(estimate.payment_gas(), Gas::default());
+    assert!(estimate.session_gas() > Gas::default());
+    assert_eq!(estimate.gas_price(), DEFAULT_GAS_PRICE);
+    assert_eq!(
+        estimate.recommended_payment().value(),
+        estimate.session_gas().value() * U512::from(DEFAULT_GAS_PRICE)
+    );
+
+    // Estimation must not depend on the payment amount of the deploy.
+    let other_estimate = estimate_gas(&builder, U512::from(MINIMUM_ACCOUNT_CREATION_BALANCE));
+    assert_eq!(estimate.session_gas(), other_estimate.session_gas());
+}
+
+#[ignore]
+#[test]
+fn should_execute_with_recommended_payment() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+
+    let estimate = estimate_gas(&builder, U512::one());
+    let recommended_payment = estimate.recommended_payment().value();
+
+    builder
+        .exec(transfer_request(recommended_payment))
+        .expect_success()
+        .commit();
+
+    let exec_results = builder
+        .get_last_exec_results()
+        .expect("should have exec results");
+    assert_eq!(Some(exec_results[0].cost()), estimate.total_gas());
+}
+
+#[ignore]
+#[test]
+fn should_fail_with_less_than_recommended_payment() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+
+    let estimate = estimate_gas(&builder, U512::one());
+    let insufficient_payment = estimate.recommended_payment().value() - U512::one();
+
+    builder
+        .exec(transfer_request(insufficient_payment))
+        .commit();
+
+    assert!(builder.is_error());
+}
@@ -1,4 +1,5 @@
 mod context_association;
+mod gas_estimate;
 mod non_standard_payment;
 mod preconditions;
 mod receipts;
@@ -0,0 +1,69 @@
+use assert_matches::assert_matches;
+use casper_wasm::{
+    builder,
+    elements::{BlockType, Instruction, Instructions},
+};
+
+use casper_engine_test_support::{
+    DeployItemBuilder, ExecuteRequestBuilder, InMemoryWasmTestBuilder, ARG_AMOUNT,
+    DEFAULT_ACCOUNT_ADDR, DEFAULT_PAYMENT, PRODUCTION_RUN_GENESIS_REQUEST,
+};
+use casper_execution_engine::{core::engine_state::Error, shared::newtypes::CorrelationId};
+use casper_types::{contracts::DEFAULT_ENTRY_POINT_NAME, runtime_args, RuntimeArgs};
+
+const BLOCK_GAS_LIMIT: u64 = 1_000_000_000;
+
+/// Creates session code which loops forever.
+fn make_endless_loop() -> Vec<u8> {
+    let module = builder::module()
+        .function()
+        .signature()
+        .build()
+        .body()
+        .with_instructions(Instructions::new(vec![
+            Instruction::Loop(BlockType::NoResult),
+            Instruction::Br(0),
+            Instruction::End,
+            Instruction::End,
+        ]))
+        .build()
+        .build()
+        .export()
+        .field(DEFAULT_ENTRY_POINT_NAME)
+        .build()
+        .memory()
+        .build()
+        .build();
+    casper_wasm::serialize(module).expect("should serialize")
+}
+
+#[ignore]
+#[test]
+fn should_stop_estimating_gas_at_block_gas_limit() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+
+    let mut exec_request = {
+        let deploy_item = DeployItemBuilder::new()
+            .with_address(*DEFAULT_ACCOUNT_ADDR)
+            .with_session_bytes(make_endless_loop(), RuntimeArgs::default())
+            .with_empty_payment_bytes(runtime_args! {
+                ARG_AMOUNT => *DEFAULT_PAYMENT
+            })
+            .with_authorization_keys(&[*DEFAULT_ACCOUNT_ADDR])
+            .with_deploy_hash([42; 32])
+            .build();
+        ExecuteRequestBuilder::from_deploy_item(deploy_item).build()
+    };
+    exec_request.parent_state_hash = builder.get_post_state_hash();
+
+    let estimates = builder
+        .get_engine_state()
+        .estimate_gas(CorrelationId::new(), exec_request, BLOCK_GAS_LIMIT)
+        .expect("should estimate gas");
+    assert_eq!(estimates.len(), 1);
+    assert_matches!(
+        estimates[0].error(),
+        Some(Error::ExceedsBlockGasLimit(BLOCK_GAS_LIMIT))
+    );
+}
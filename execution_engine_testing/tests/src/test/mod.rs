@@ -6,6 +6,7 @@ mod contract_context;
 mod deploy;
 mod explorer;
 mod gas_counter;
+mod gas_estimation;
 mod get_balance;
 mod groups;
 mod host_function_costs;
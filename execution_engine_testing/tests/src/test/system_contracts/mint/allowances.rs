@@ -0,0 +1,462 @@
+use casper_engine_test_support::{
+    ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_ACCOUNT_ADDR,
+    MINIMUM_ACCOUNT_CREATION_BALANCE, PRODUCTION_RUN_GENESIS_REQUEST,
+};
+use casper_execution_engine::core::{
+    engine_state::{EngineConfigBuilder, Error},
+    execution,
+};
+use casper_types::{
+    account::AccountHash,
+    runtime_args,
+    system::mint::{self, ALLOWANCES_KEY},
+    ApiError, ContractHash, Key, RuntimeArgs, U512,
+};
+
+const CONTRACT_MINT_ALLOWANCE: &str = "mint_allowance.wasm";
+
+const ARG_ACTION: &str = "action";
+const ARG_VALUE: &str = "value";
+const ARG_AMOUNT: &str = "amount";
+
+const ACTION_APPROVE: &str = "approve";
+const ACTION_ALLOWANCE: &str = "allowance";
+const ACTION_TRANSFER_FROM: &str = "transfer_from";
+const ACTION_INSTALL: &str = "install";
+
+const ALLOWANCE_KEY_NAME: &str = "allowance";
+const CONTRACT_HASH_KEY_NAME: &str = "mint_allowance_contract_hash";
+const ENTRY_POINT_APPROVE_FROM_CONTRACT: &str = "approve_from_contract";
+
+const SPENDER_ADDR: AccountHash = AccountHash::new([42; 32]);
+
+fn setup(builder: &mut InMemoryWasmTestBuilder) {
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+
+    let fund_spender = ExecuteRequestBuilder::transfer(
+        *DEFAULT_ACCOUNT_ADDR,
+        runtime_args! {
+            mint::ARG_TARGET => SPENDER_ADDR,
+            mint::ARG_AMOUNT => U512::from(MINIMUM_ACCOUNT_CREATION_BALANCE),
+            mint::ARG_ID => <Option<u64>>::None,
+        },
+    )
+    .build();
+    builder.exec(fund_spender).expect_success().commit();
+}
+
+fn approve(
+    builder: &mut InMemoryWasmTestBuilder,
+    owner: AccountHash,
+    spender: Key,
+    value: U512,
+) -> &mut InMemoryWasmTestBuilder {
+    let exec_request = ExecuteRequestBuilder::standard(
+        owner,
+        CONTRACT_MINT_ALLOWANCE,
+        runtime_args! {
+            ARG_ACTION => ACTION_APPROVE,
+            mint::ARG_SPENDER => spender,
+            ARG_VALUE => value,
+        },
+    )
+    .build();
+    builder.exec(exec_request)
+}
+
+fn transfer_from(
+    builder: &mut InMemoryWasmTestBuilder,
+    spender: AccountHash,
+    owner: AccountHash,
+    value: U512,
+    spending_limit: U512,
+) -> &mut InMemoryWasmTestBuilder {
+    let exec_request = ExecuteRequestBuilder::standard(
+        spender,
+        CONTRACT_MINT_ALLOWANCE,
+        runtime_args! {
+            ARG_ACTION => ACTION_TRANSFER_FROM,
+            mint::ARG_OWNER => owner,
+            ARG_VALUE => value,
+            ARG_AMOUNT => spending_limit,
+        },
+    )
+    .build();
+    builder.exec(exec_request)
+}
+
+fn allowance(builder: &mut InMemoryWasmTestBuilder, owner: AccountHash, spender: Key) -> U512 {
+    let exec_request = ExecuteRequestBuilder::standard(
+        SPENDER_ADDR,
+        CONTRACT_MINT_ALLOWANCE,
+        runtime_args! {
+            ARG_ACTION => ACTION_ALLOWANCE,
+            mint::ARG_OWNER => owner,
+            mint::ARG_SPENDER => spender,
+        },
+    )
+    .build();
+    builder.exec(exec_request).expect_success().commit();
+    builder
+        .query(
+            None,
+            Key::Account(SPENDER_ADDR),
+            &[ALLOWANCE_KEY_NAME.to_string()],
+        )
+        .expect("should have allowance")
+        .as_cl_value()
+        .cloned()
+        .expect("should be a CLValue")
+        .into_t()
+        .expect("should be U512")
+}
+
+fn main_purse_balance(builder: &InMemoryWasmTestBuilder, account_hash: AccountHash) -> U512 {
+    let main_purse = builder.get_expected_account(account_hash).main_purse();
+    builder.get_purse_balance(main_purse)
+}
+
+fn assert_mint_error(builder: &InMemoryWasmTestBuilder, expected: mint::Error) {
+    let error = builder.get_error().expect("should have error");
+    assert!(
+        matches!(
+            error,
+            Error::Exec(execution::Error::Revert(api_error))
+                if api_error == ApiError::from(expected)
+        ),
+        "expected {:?}, found {:?}",
+        expected,
+        error
+    );
+}
+
+#[ignore]
+#[test]
+fn should_transfer_from_owner_within_allowance() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    setup(&mut builder);
+    let spender = Key::Account(SPENDER_ADDR);
+
+    approve(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        spender,
+        U512::from(1_000),
+    )
+    .expect_success()
+    .commit();
+    assert_eq!(
+        allowance(&mut builder, *DEFAULT_ACCOUNT_ADDR, spender),
+        U512::from(1_000)
+    );
+
+    let owner_balance_before = main_purse_balance(&builder, *DEFAULT_ACCOUNT_ADDR);
+    transfer_from(
+        &mut builder,
+        SPENDER_ADDR,
+        *DEFAULT_ACCOUNT_ADDR,
+        U512::from(400),
+        U512::zero(),
+    )
+    .expect_success()
+    .commit();
+
+    assert_eq!(
+        main_purse_balance(&builder, *DEFAULT_ACCOUNT_ADDR),
+        owner_balance_before - U512::from(400)
+    );
+    assert_eq!(
+        allowance(&mut builder, *DEFAULT_ACCOUNT_ADDR, spender),
+        U512::from(600)
+    );
+}
+
+#[ignore]
+#[test]
+fn should_record_transfer_from_as_made_by_owner() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    setup(&mut builder);
+
+    approve(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        Key::Account(SPENDER_ADDR),
+        U512::from(1_000),
+    )
+    .expect_success()
+    .commit();
+    transfer_from(
+        &mut builder,
+        SPENDER_ADDR,
+        *DEFAULT_ACCOUNT_ADDR,
+        U512::from(400),
+        U512::zero(),
+    )
+    .expect_success()
+    .commit();
+
+    let exec_results = builder
+        .get_last_exec_results()
+        .expect("should have exec results");
+    let transfers = exec_results[0].transfers();
+    assert_eq!(transfers.len(), 1);
+    let transfer = builder
+        .get_transfer(transfers[0])
+        .expect("should have transfer");
+    let owner_main_purse = builder
+        .get_expected_account(*DEFAULT_ACCOUNT_ADDR)
+        .main_purse();
+    assert_eq!(transfer.from, *DEFAULT_ACCOUNT_ADDR);
+    assert_eq!(transfer.source, owner_main_purse);
+    assert_eq!(transfer.amount, U512::from(400));
+}
+
+#[ignore]
+#[test]
+fn should_not_transfer_from_owner_beyond_allowance() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    setup(&mut builder);
+    let spender = Key::Account(SPENDER_ADDR);
+
+    approve(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        spender,
+        U512::from(1_000),
+    )
+    .expect_success()
+    .commit();
+
+    let owner_balance_before = main_purse_balance(&builder, *DEFAULT_ACCOUNT_ADDR);
+    transfer_from(
+        &mut builder,
+        SPENDER_ADDR,
+        *DEFAULT_ACCOUNT_ADDR,
+        U512::from(1_001),
+        U512::zero(),
+    )
+    .expect_failure()
+    .commit();
+    assert_mint_error(&builder, mint::Error::InsufficientAllowance);
+
+    assert_eq!(
+        main_purse_balance(&builder, *DEFAULT_ACCOUNT_ADDR),
+        owner_balance_before
+    );
+    assert_eq!(
+        allowance(&mut builder, *DEFAULT_ACCOUNT_ADDR, spender),
+        U512::from(1_000)
+    );
+}
+
+#[ignore]
+#[test]
+fn should_revoke_allowance_by_approving_zero() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    setup(&mut builder);
+    let spender = Key::Account(SPENDER_ADDR);
+
+    approve(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        spender,
+        U512::from(1_000),
+    )
+    .expect_success()
+    .commit();
+    approve(&mut builder, *DEFAULT_ACCOUNT_ADDR, spender, U512::zero())
+        .expect_success()
+        .commit();
+    assert_eq!(
+        allowance(&mut builder, *DEFAULT_ACCOUNT_ADDR, spender),
+        U512::zero()
+    );
+
+    transfer_from(
+        &mut builder,
+        SPENDER_ADDR,
+        *DEFAULT_ACCOUNT_ADDR,
+        U512::one(),
+        U512::zero(),
+    )
+    .expect_failure()
+    .commit();
+    assert_mint_error(&builder, mint::Error::InsufficientAllowance);
+}
+
+#[ignore]
+#[test]
+fn should_not_approve_from_contract_context() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    setup(&mut builder);
+
+    let install_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_MINT_ALLOWANCE,
+        runtime_args! { ARG_ACTION => ACTION_INSTALL },
+    )
+    .build();
+    builder.exec(install_request).expect_success().commit();
+    let contract_hash = builder
+        .get_expected_account(*DEFAULT_ACCOUNT_ADDR)
+        .named_keys()
+        .get(CONTRACT_HASH_KEY_NAME)
+        .copied()
+        .and_then(Key::into_hash)
+        .map(ContractHash::new)
+        .expect("should have contract hash");
+
+    let approve_request = ExecuteRequestBuilder::contract_call_by_hash(
+        *DEFAULT_ACCOUNT_ADDR,
+        contract_hash,
+        ENTRY_POINT_APPROVE_FROM_CONTRACT,
+        runtime_args! {
+            mint::ARG_SPENDER => Key::Account(SPENDER_ADDR),
+            ARG_VALUE => U512::from(1_000),
+        },
+    )
+    .build();
+    builder.exec(approve_request).expect_failure().commit();
+    assert_mint_error(&builder, mint::Error::InvalidContext);
+
+    assert_eq!(
+        allowance(
+            &mut builder,
+            *DEFAULT_ACCOUNT_ADDR,
+            Key::Account(SPENDER_ADDR)
+        ),
+        U512::zero()
+    );
+}
+
+#[ignore]
+#[test]
+fn should_enforce_spending_limit_when_owner_is_caller() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    setup(&mut builder);
+    let owner_as_spender = Key::Account(*DEFAULT_ACCOUNT_ADDR);
+
+    approve(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        owner_as_spender,
+        U512::from(1_000),
+    )
+    .expect_success()
+    .commit();
+
+    // An allowance doesn't lift the spending limit of the owner's own deploy.
+    transfer_from(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        *DEFAULT_ACCOUNT_ADDR,
+        U512::from(500),
+        U512::from(499),
+    )
+    .expect_failure()
+    .commit();
+    assert_mint_error(&builder, mint::Error::UnapprovedSpendingAmount);
+
+    transfer_from(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        *DEFAULT_ACCOUNT_ADDR,
+        U512::from(500),
+        U512::from(500),
+    )
+    .expect_success()
+    .commit();
+    assert_eq!(
+        allowance(&mut builder, *DEFAULT_ACCOUNT_ADDR, owner_as_spender),
+        U512::from(500)
+    );
+}
+
+#[ignore]
+#[test]
+fn should_not_approve_or_transfer_from_with_unrestricted_transfers_disabled() {
+    let engine_config = EngineConfigBuilder::default()
+        .with_allow_unrestricted_transfers(false)
+        .build();
+    let mut builder = InMemoryWasmTestBuilder::new_with_config(engine_config);
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+
+    approve(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        Key::Account(SPENDER_ADDR),
+        U512::from(1_000),
+    )
+    .expect_failure()
+    .commit();
+    assert_mint_error(&builder, mint::Error::DisabledUnrestrictedTransfers);
+
+    let mint_hash = builder.get_mint_contract_hash();
+    assert!(!builder
+        .get_contract(mint_hash)
+        .expect("should have mint")
+        .named_keys()
+        .contains_key(ALLOWANCES_KEY));
+}
+
+#[ignore]
+#[test]
+fn should_create_allowances_dictionary_on_first_approve() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    setup(&mut builder);
+    let mint_hash = builder.get_mint_contract_hash();
+    let spender = Key::Account(SPENDER_ADDR);
+
+    assert!(!builder
+        .get_contract(mint_hash)
+        .expect("should have mint")
+        .named_keys()
+        .contains_key(ALLOWANCES_KEY));
+    // Reading an allowance doesn't create the dictionary.
+    assert_eq!(
+        allowance(&mut builder, *DEFAULT_ACCOUNT_ADDR, spender),
+        U512::zero()
+    );
+    assert!(!builder
+        .get_contract(mint_hash)
+        .expect("should have mint")
+        .named_keys()
+        .contains_key(ALLOWANCES_KEY));
+
+    approve(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        spender,
+        U512::from(1_000),
+    )
+    .expect_success()
+    .commit();
+    let allowances_key = *builder
+        .get_contract(mint_hash)
+        .expect("should have mint")
+        .named_keys()
+        .get(ALLOWANCES_KEY)
+        .expect("should have allowances dictionary");
+
+    // Later approvals reuse the same dictionary.
+    approve(
+        &mut builder,
+        *DEFAULT_ACCOUNT_ADDR,
+        spender,
+        U512::from(2_000),
+    )
+    .expect_success()
+    .commit();
+    assert_eq!(
+        builder
+            .get_contract(mint_hash)
+            .expect("should have mint")
+            .named_keys()
+            .get(ALLOWANCES_KEY),
+        Some(&allowances_key)
+    );
+    assert_eq!(
+        allowance(&mut builder, *DEFAULT_ACCOUNT_ADDR, spender),
+        U512::from(2_000)
+    );
+}
@@ -7,20 +7,24 @@
 mod tests;
 
 use bytes::Bytes;
+use futures::future;
 use http::{header::CONTENT_TYPE, HeaderMap, StatusCode};
+use serde::Serialize;
 use serde_json::{json, Map, Value};
 use tracing::{debug, trace, warn};
 use warp::{
     body,
     filters::BoxedFilter,
     reject::{self, Rejection},
-    reply::{self, WithStatus},
-    Filter,
+    reply::{self, reply, WithStatus},
+    Filter, Reply,
 };
 
 use crate::{
     error::{Error, ReservedErrorCode},
-    rejections::{BodyTooLarge, MissingContentTypeHeader, MissingId, UnsupportedMediaType},
+    rejections::{
+        BatchTooLarge, BodyTooLarge, MissingContentTypeHeader, MissingId, UnsupportedMediaType,
+    },
     request::{ErrorOrRejection, Request},
     request_handlers::RequestHandlers,
     response::Response,
@@ -66,12 +70,123 @@ pub fn base_filter<P: AsRef<str>>(path: P, max_body_bytes: u32) -> BoxedFilter<(
         .boxed()
 }
 
-/// Handles parsing a JSON-RPC request from the given HTTP body, executing it using the appropriate
+/// Returns `true` if the first non-whitespace character of the given HTTP body is '[', i.e. if the
+/// body should be treated as a JSON-RPC batch request.
+fn is_batch(body: &[u8]) -> bool {
+    body.iter().find(|byte| !byte.is_ascii_whitespace()) == Some(&b'[')
+}
+
+/// Validates the given JSON object as a JSON-RPC request, executing it using the appropriate
 /// handler, and providing a JSON-RPC response (which could be a success or failure).
 ///
 /// Returns an `Err(Rejection)` only if the request is a Notification as per the JSON-RPC
-/// specification, i.e. the request doesn't contain an "id" field.  In this case, no JSON-RPC
-/// response is sent to the client.
+/// specification, i.e. the request doesn't contain an "id" field.
+async fn handle_request_object(
+    unvalidated_request: Map<String, Value>,
+    handlers: &RequestHandlers,
+    allow_unknown_fields: bool,
+) -> Result<Response, Rejection> {
+    match Request::new(unvalidated_request, allow_unknown_fields) {
+        Ok(request) => Ok(handlers.handle_request(request).await),
+        Err(ErrorOrRejection::Error { id, error }) => {
+            debug!(?error, "got an invalid request");
+            Ok(Response::new_failure(id, error))
+        }
+        Err(ErrorOrRejection::Rejection(rejection)) => {
+            debug!(?rejection, "rejecting an invalid request");
+            Err(rejection)
+        }
+    }
+}
+
+/// Handles parsing a JSON-RPC batch request from the given HTTP body, executing each of its
+/// requests using the appropriate handler, and providing the corresponding JSON-RPC responses.
+///
+/// The requests are executed concurrently, and the responses are returned in the same order as
+/// their requests.  Any element of the batch which is a Notification, i.e. doesn't contain an "id"
+/// field, is neither executed nor included in the responses.
+///
+/// If the body cannot be parsed as a JSON array, or if the array is empty, a single JSON-RPC
+/// failure response is returned rather than an array.
+///
+/// Returns an `Err(Rejection)` only if the batch contains more than `max_batch_size` elements.
+async fn handle_batch_body(
+    body: Bytes,
+    handlers: RequestHandlers,
+    allow_unknown_fields: bool,
+    max_batch_size: u32,
+) -> Result<reply::Response, Rejection> {
+    let unvalidated_requests = match serde_json::from_slice::<Vec<Value>>(&body) {
+        Ok(unvalidated_requests) => unvalidated_requests,
+        Err(error) => {
+            debug!(%error, "got bad json");
+            let error = Error::new(ReservedErrorCode::ParseError, error.to_string());
+            return Ok(json_reply(&Response::new_failure(Value::Null, error)));
+        }
+    };
+
+    if unvalidated_requests.is_empty() {
+        debug!("got an empty batch request");
+        let error = Error::new(
+            ReservedErrorCode::InvalidRequest,
+            "Batch request must contain at least one request",
+        );
+        return Ok(json_reply(&Response::new_failure(Value::Null, error)));
+    }
+
+    if unvalidated_requests.len() > max_batch_size as usize {
+        trace!(
+            batch_size = unvalidated_requests.len(),
+            max_batch_size,
+            "batch request too large"
+        );
+        return Err(reject::custom(BatchTooLarge(max_batch_size)));
+    }
+
+    let responses = future::join_all(unvalidated_requests.into_iter().map(|element| {
+        let handlers = &handlers;
+        async move {
+            match element {
+                Value::Object(unvalidated_request) => {
+                    handle_request_object(unvalidated_request, handlers, allow_unknown_fields)
+                        .await
+                        .ok()
+                }
+                _ => {
+                    debug!("got a batch element which is not a json object");
+                    let error = Error::new(
+                        ReservedErrorCode::InvalidRequest,
+                        "Expected each element of a batch request to be an Object",
+                    );
+                    Some(Response::new_failure(Value::Null, error))
+                }
+            }
+        }
+    }))
+    .await
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>();
+
+    // As per the JSON-RPC specification, if there are no responses to send (i.e. all requests were
+    // Notifications), nothing should be returned.
+    if responses.is_empty() {
+        return Ok(reply::with_status(reply(), StatusCode::NO_CONTENT).into_response());
+    }
+
+    Ok(json_reply(&responses))
+}
+
+/// Handles parsing a JSON-RPC request or batch of requests from the given HTTP body, executing
+/// them using the appropriate handler, and providing a JSON-RPC response or array of responses
+/// (each of which could be a success or failure).
+///
+/// For a single request, returns an `Err(Rejection)` only if the request is a Notification as per
+/// the JSON-RPC specification, i.e. the request doesn't contain an "id" field.  In this case, no
+/// JSON-RPC response is sent to the client.
+///
+/// For a batch request, returns an `Err(Rejection)` only if the batch contains more than
+/// `max_batch_size` elements.
 ///
 /// If `allow_unknown_fields` is `false`, requests with unknown fields will cause the server to
 /// respond with an error.
@@ -79,49 +194,57 @@ async fn handle_body(
     body: Bytes,
     handlers: RequestHandlers,
     allow_unknown_fields: bool,
-) -> Result<Response, Rejection> {
+    max_batch_size: u32,
+) -> Result<reply::Response, Rejection> {
+    if is_batch(&body) {
+        return handle_batch_body(body, handlers, allow_unknown_fields, max_batch_size).await;
+    }
+
     let response = match serde_json::from_slice::<Map<String, Value>>(&body) {
-        Ok(unvalidated_request) => match Request::new(unvalidated_request, allow_unknown_fields) {
-            Ok(request) => handlers.handle_request(request).await,
-            Err(ErrorOrRejection::Error { id, error }) => {
-                debug!(?error, "got an invalid request");
-                Response::new_failure(id, error)
-            }
-            Err(ErrorOrRejection::Rejection(rejection)) => {
-                debug!(?rejection, "rejecting an invalid request");
-                return Err(rejection);
-            }
-        },
+        Ok(unvalidated_request) => {
+            handle_request_object(unvalidated_request, &handlers, allow_unknown_fields).await?
+        }
         Err(error) => {
             debug!(%error, "got bad json");
             let error = Error::new(ReservedErrorCode::ParseError, error.to_string());
             Response::new_failure(Value::Null, error)
         }
     };
-    Ok(response)
+    Ok(json_reply(&response))
 }
 
-/// Returns a boxed warp filter which handles parsing a JSON-RPC request from the given HTTP body,
-/// executing it using the appropriate handler, and providing a reply.
+/// Returns an HTTP 200 (OK) reply with the given value encoded as its JSON body.
+fn json_reply<T: Serialize>(value: &T) -> reply::Response {
+    reply::with_status(reply::json(value), StatusCode::OK).into_response()
+}
+
+/// Returns a boxed warp filter which handles parsing a JSON-RPC request or batch of requests from
+/// the given HTTP body, executing them using the appropriate handler, and providing a reply.
 ///
-/// The reply will normally be built from a JSON-RPC response (which could be a success or failure).
+/// The reply will normally be built from a JSON-RPC response (which could be a success or
+/// failure), or from an array of JSON-RPC responses if the body is a batch request, i.e. a JSON
+/// array.
 ///
 /// However, the reply could be built from a [`Rejection`] if the request is a Notification as per
 /// the JSON-RPC specification, i.e. the request doesn't contain an "id" field.  In this case, no
-/// JSON-RPC response is sent to the client, only an HTTP response.
+/// JSON-RPC response is sent to the client, only an HTTP response.  Notifications contained in a
+/// batch request are not executed, and are omitted from the array of responses.  If every element
+/// of a batch request is a Notification, an HTTP 204 (no content) response is returned.
+///
+/// Batch requests containing more than `max_batch_size` elements are rejected.
 ///
 /// If `allow_unknown_fields` is `false`, requests with unknown fields will cause the server to
 /// respond with an error.
 pub fn main_filter(
     handlers: RequestHandlers,
     allow_unknown_fields: bool,
-) -> BoxedFilter<(WithStatus<reply::Json>,)> {
+    max_batch_size: u32,
+) -> BoxedFilter<(reply::Response,)> {
     body::bytes()
         .and_then(move |body| {
             let handlers = handlers.clone();
-            async move { handle_body(body, handlers, allow_unknown_fields).await }
+            async move { handle_body(body, handlers, allow_unknown_fields, max_batch_size).await }
         })
-        .map(|response| reply::with_status(reply::json(&response), StatusCode::OK))
         .boxed()
 }
 
@@ -151,6 +274,10 @@ pub async fn handle_rejection(error: Rejection) -> Result<WithStatus<reply::Json
         trace!("{:?}", rejection);
         message = rejection.to_string();
         code = StatusCode::PAYLOAD_TOO_LARGE;
+    } else if let Some(rejection) = error.find::<BatchTooLarge>() {
+        trace!("{:?}", rejection);
+        message = rejection.to_string();
+        code = StatusCode::PAYLOAD_TOO_LARGE;
     } else if error.is_not_found() {
         trace!("{:?}", error);
         message = "Path not found".to_string();
@@ -16,6 +16,7 @@ use crate::{
 
 const GET_GOOD_THING: &str = "get good thing";
 const GET_BAD_THING: &str = "get bad thing";
+const MAX_BATCH_SIZE: u32 = 3;
 
 #[derive(PartialEq, Eq, Serialize, Deserialize, Debug)]
 struct GoodThing {
@@ -49,13 +50,18 @@ async fn from_http_response(response: http::Response<hyper::Body>) -> Response {
     serde_json::from_slice(&body_bytes).unwrap()
 }
 
+async fn from_batch_http_response(response: http::Response<hyper::Body>) -> Vec<Response> {
+    let body_bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    serde_json::from_slice(&body_bytes).unwrap()
+}
+
 fn main_filter_with_recovery() -> BoxedFilter<(impl Reply,)> {
     let mut handlers = RequestHandlersBuilder::new();
     handlers.register_handler(GET_GOOD_THING, Arc::new(get_good_thing));
     handlers.register_handler(GET_BAD_THING, Arc::new(get_bad_thing));
     let handlers = handlers.build();
 
-    main_filter(handlers, false)
+    main_filter(handlers, false, MAX_BATCH_SIZE)
         .recover(handle_rejection)
         .boxed()
 }
@@ -318,3 +324,188 @@ async fn should_handle_invalid_json() {
         )
     );
 }
+
+#[tokio::test]
+async fn should_handle_valid_batch_request() {
+    let _ = env_logger::try_init();
+
+    let filter = main_filter_with_recovery();
+
+    // This should return an array of responses in the same order as the requests, each handled
+    // independently.
+    let http_response = warp::test::request()
+        .body(
+            r#"[
+                {"jsonrpc":"2.0","id":1,"method":"get good thing","params":["one"]},
+                {"jsonrpc":"2.0","id":2,"method":"get good thing"},
+                {"jsonrpc":"2.0","id":3,"method":"get good thing","params":["three"]}
+            ]"#,
+        )
+        .filter(&filter)
+        .await
+        .unwrap()
+        .into_response();
+
+    assert_eq!(http_response.status(), StatusCode::OK);
+    let rpc_responses = from_batch_http_response(http_response).await;
+    assert_eq!(rpc_responses.len(), 3);
+    assert_eq!(rpc_responses[0].id(), 1);
+    assert_eq!(
+        rpc_responses[0].result(),
+        Some(GoodThing {
+            good_thing: "one".to_string()
+        })
+    );
+    assert_eq!(rpc_responses[1].id(), 2);
+    assert_eq!(
+        rpc_responses[1].error().unwrap(),
+        &Error::new(ReservedErrorCode::InvalidParams, "no params")
+    );
+    assert_eq!(rpc_responses[2].id(), 3);
+    assert_eq!(
+        rpc_responses[2].result(),
+        Some(GoodThing {
+            good_thing: "three".to_string()
+        })
+    );
+}
+
+#[tokio::test]
+async fn should_handle_batch_request_with_invalid_elements() {
+    let _ = env_logger::try_init();
+
+    let filter = main_filter_with_recovery();
+
+    // The notification (no ID) should be omitted from the responses, while the non-object element
+    // should yield a Response::Failure (invalid request).
+    let http_response = warp::test::request()
+        .body(
+            r#"[
+                {"jsonrpc":"2.0","method":"get good thing","params":["one"]},
+                1,
+                {"jsonrpc":"2.0","id":"a","method":"not registered"}
+            ]"#,
+        )
+        .filter(&filter)
+        .await
+        .unwrap()
+        .into_response();
+
+    assert_eq!(http_response.status(), StatusCode::OK);
+    let rpc_responses = from_batch_http_response(http_response).await;
+    assert_eq!(rpc_responses.len(), 2);
+    assert_eq!(rpc_responses[0].id(), &Value::Null);
+    assert_eq!(
+        rpc_responses[0].error().unwrap(),
+        &Error::new(
+            ReservedErrorCode::InvalidRequest,
+            "Expected each element of a batch request to be an Object"
+        )
+    );
+    assert_eq!(rpc_responses[1].id(), "a");
+    assert_eq!(
+        rpc_responses[1].error().unwrap(),
+        &Error::new(
+            ReservedErrorCode::MethodNotFound,
+            "'not registered' is not a supported json-rpc method on this server"
+        )
+    );
+}
+
+#[tokio::test]
+async fn should_handle_batch_request_of_only_notifications() {
+    let _ = env_logger::try_init();
+
+    let filter = main_filter_with_recovery();
+
+    // As every element is a notification, no JSON-RPC response should be sent.
+    let http_response = warp::test::request()
+        .body(r#"[{"jsonrpc":"2.0","method":"get good thing","params":["one"]}]"#)
+        .filter(&filter)
+        .await
+        .unwrap()
+        .into_response();
+
+    assert_eq!(http_response.status(), StatusCode::NO_CONTENT);
+    let body_bytes = hyper::body::to_bytes(http_response.into_body())
+        .await
+        .unwrap();
+    assert!(body_bytes.is_empty());
+}
+
+#[tokio::test]
+async fn should_handle_empty_batch_request() {
+    let _ = env_logger::try_init();
+
+    let filter = main_filter_with_recovery();
+
+    // An empty batch should yield a single Response::Failure (invalid request), not an array.
+    let http_response = warp::test::request()
+        .body(" [ ] ")
+        .filter(&filter)
+        .await
+        .unwrap()
+        .into_response();
+
+    assert_eq!(http_response.status(), StatusCode::OK);
+    let rpc_response = from_http_response(http_response).await;
+    assert_eq!(rpc_response.id(), &Value::Null);
+    assert_eq!(
+        rpc_response.error().unwrap(),
+        &Error::new(
+            ReservedErrorCode::InvalidRequest,
+            "Batch request must contain at least one request"
+        )
+    );
+}
+
+#[tokio::test]
+async fn should_handle_invalid_json_batch_request() {
+    let _ = env_logger::try_init();
+
+    let filter = main_filter_with_recovery();
+
+    // This should return a single Response::Failure (parse error) as it cannot be parsed as JSON.
+    let http_response = warp::test::request()
+        .body(r#"[{"jsonrpc":"2.0","id":1,"method":"get good thing"}"#)
+        .filter(&filter)
+        .await
+        .unwrap()
+        .into_response();
+
+    assert_eq!(http_response.status(), StatusCode::OK);
+    let rpc_response = from_http_response(http_response).await;
+    assert_eq!(rpc_response.id(), &Value::Null);
+    assert_eq!(
+        rpc_response.error().unwrap(),
+        &Error::new(
+            ReservedErrorCode::ParseError,
+            "EOF while parsing a list at line 1 column 51"
+        )
+    );
+}
+
+#[tokio::test]
+async fn should_handle_batch_request_exceeding_max_batch_size() {
+    let _ = env_logger::try_init();
+
+    let filter = main_filter_with_recovery();
+
+    // This should return no JSON-RPC response, only an HTTP response (payload too large) as the
+    // batch has more than `MAX_BATCH_SIZE` elements.
+    let request = r#"{"jsonrpc":"2.0","id":1,"method":"get good thing","params":["one"]}"#;
+    let body = format!("[{}]", vec![request; MAX_BATCH_SIZE as usize + 1].join(","));
+    let http_response = warp::test::request()
+        .body(body)
+        .filter(&filter)
+        .await
+        .unwrap()
+        .into_response();
+
+    assert_eq!(http_response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    let response_body = ResponseBodyOnRejection::from_response(http_response).await;
+    assert_eq!(
+        response_body.message,
+        "The batch request exceeds the maximum allowed of 3 requests"
+    );
+}
@@ -39,8 +39,15 @@
 //!     // Get the new route.
 //!     let path = "rpc";
 //!     let max_body_bytes = 1024;
+//!     let max_batch_size = 10;
 //!     let allow_unknown_fields = false;
-//!     let route = casper_json_rpc::route(path, max_body_bytes, handlers, allow_unknown_fields);
+//!     let route = casper_json_rpc::route(
+//!         path,
+//!         max_body_bytes,
+//!         max_batch_size,
+//!         handlers,
+//!         allow_unknown_fields,
+//!     );
 //!
 //!     // Convert it into a `Service` and run it.
 //!     let make_svc = hyper::service::make_service_fn(move |_| {
@@ -112,6 +119,9 @@ pub enum CorsOrigin {
 /// further details, see
 /// [`warp::filters::body::content_length_limit`](https://docs.rs/warp/latest/warp/filters/body/fn.content_length_limit.html).
 ///
+/// `max_batch_size` sets an upper limit for the number of requests in a single JSON-RPC batch
+/// request.
+///
 /// `handlers` is the map of functions to which incoming requests will be dispatched.  These are
 /// keyed by the JSON-RPC request's "method".
 ///
@@ -122,11 +132,16 @@ pub enum CorsOrigin {
 pub fn route<P: AsRef<str>>(
     path: P,
     max_body_bytes: u32,
+    max_batch_size: u32,
     handlers: RequestHandlers,
     allow_unknown_fields: bool,
 ) -> BoxedFilter<(impl Reply,)> {
     filters::base_filter(path, max_body_bytes)
-        .and(filters::main_filter(handlers, allow_unknown_fields))
+        .and(filters::main_filter(
+            handlers,
+            allow_unknown_fields,
+            max_batch_size,
+        ))
         .recover(filters::handle_rejection)
         .boxed()
 }
@@ -140,6 +155,9 @@ pub fn route<P: AsRef<str>>(
 /// further details, see
 /// [`warp::filters::body::content_length_limit`](https://docs.rs/warp/latest/warp/filters/body/fn.content_length_limit.html).
 ///
+/// `max_batch_size` sets an upper limit for the number of requests in a single JSON-RPC batch
+/// request.
+///
 /// `handlers` is the map of functions to which incoming requests will be dispatched.  These are
 /// keyed by the JSON-RPC request's "method".
 ///
@@ -156,12 +174,17 @@ pub fn route<P: AsRef<str>>(
 pub fn route_with_cors<P: AsRef<str>>(
     path: P,
     max_body_bytes: u32,
+    max_batch_size: u32,
     handlers: RequestHandlers,
     allow_unknown_fields: bool,
     cors_header: &CorsOrigin,
 ) -> BoxedFilter<(impl Reply,)> {
     filters::base_filter(path, max_body_bytes)
-        .and(filters::main_filter(handlers, allow_unknown_fields))
+        .and(filters::main_filter(
+            handlers,
+            allow_unknown_fields,
+            max_batch_size,
+        ))
         .recover(filters::handle_rejection)
         .with(match cors_header {
             CorsOrigin::Any => warp::cors()
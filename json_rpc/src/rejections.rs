@@ -70,3 +70,24 @@ impl Display for BodyTooLarge {
 }
 
 impl Reject for BodyTooLarge {}
+
+/// Indicates the JSON-RPC batch request contains more elements than the maximum allowed.
+///
+/// Wraps the configured maximum allowed on the server, set via the `max_batch_size` parameter in
+/// `main_filter()`.
+///
+/// This rejection is converted into an HTTP 413 (payload too large) error.
+#[derive(Debug)]
+pub(crate) struct BatchTooLarge(pub(crate) u32);
+
+impl Display for BatchTooLarge {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(
+            formatter,
+            "The batch request exceeds the maximum allowed of {} requests",
+            self.0
+        )
+    }
+}
+
+impl Reject for BatchTooLarge {}
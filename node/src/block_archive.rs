@@ -0,0 +1,700 @@
+//! Export and import of archives of stored blocks.
+//!
+//! An archive contains a contiguous range of blocks, each together with its finality signatures,
+//! deploys with their finalized approvals, execution results and approvals hashes, encoded
+//! independently of the layout of the storage component's databases.  Archives serve as offline
+//! backups and as a data exchange format.
+//!
+//! An archive file starts with `ARCHIVE_MAGIC`, the format version as a little-endian `u32` and
+//! the lowest and highest archived block heights as little-endian `u64`s.  It is followed by one
+//! record per block in ascending order of height, each consisting of the length of the record as
+//! a little-endian `u32`, the 32 byte hash of the record and the bytesrepr-encoded
+//! [`ArchivedBlock`].  The hash allows detecting corruption of the execution results, which unlike
+//! the rest of the record can't be verified against the block without its global state.
+//!
+//! On import every block is verified before it is stored: its hashes and those of its deploys,
+//! its signatures, its link to its parent and, except for the genesis block, that its signatures
+//! carry enough weight of the validators of its era, as recorded in the switch block of the
+//! previous era.  The previous era's switch block must therefore either be stored already or be
+//! part of the archive.  The genesis block carries no signatures, so it must either match the
+//! stored genesis block or the trusted hash given on import.
+
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use num_rational::Ratio;
+use thiserror::Error;
+use tracing::info;
+
+use casper_hashing::Digest;
+use casper_types::{
+    bytesrepr::{self, ToBytes},
+    EraId,
+};
+
+use crate::{
+    components::storage::{FatalStorageError, Storage},
+    reactor::main_reactor::Config,
+    types::{
+        chainspec, ArchivedBlock, ArchivedBlockValidationError, BlockHash, BlockHeader, Chainspec,
+        ChainspecRawBytes,
+    },
+    utils::{Loadable, WithDir},
+};
+
+/// The bytes with which every archive file starts.
+const ARCHIVE_MAGIC: &[u8; 8] = b"CSPRBLK\0";
+/// The version of the archive file format written by this version of the node.
+const ARCHIVE_FORMAT_VERSION: u32 = 1;
+/// The maximum accepted record size.
+const MAX_RECORD_SIZE: u32 = 256 * 1024 * 1024;
+
+/// Error returned as a result of exporting or importing a block archive.
+#[derive(Debug, Error)]
+pub(crate) enum Error {
+    /// Error loading the chainspec.
+    #[error("error loading chainspec: {0}")]
+    LoadChainspec(chainspec::Error),
+
+    /// Error accessing the block storage.
+    #[error("error accessing storage: {0}")]
+    Storage(#[from] FatalStorageError),
+
+    /// The requested range of heights is empty.
+    #[error("invalid block height range {from_height}..={to_height}")]
+    InvalidRange {
+        /// The lowest requested height.
+        from_height: u64,
+        /// The highest requested height.
+        to_height: u64,
+    },
+
+    /// A block in the requested range, or some of its data, is not stored.
+    #[error("block at height {0} is missing or incomplete in storage")]
+    MissingBlock(u64),
+
+    /// Error opening or syncing the archive file.
+    #[error("error accessing archive file {path}: {error}")]
+    Io {
+        /// The file path.
+        path: String,
+        /// The IO error.
+        error: io::Error,
+    },
+
+    /// Error writing the archive.
+    #[error("error writing archive: {0}")]
+    Write(io::Error),
+
+    /// Error encoding or decoding an archived block.
+    #[error("error encoding archived block at height {height}: {error}")]
+    Encoding {
+        /// The height of the block.
+        height: u64,
+        /// The encoding error.
+        error: bytesrepr::Error,
+    },
+
+    /// The file is not a block archive.
+    #[error("not a block archive file")]
+    InvalidMagic,
+
+    /// The archive was written in an unsupported format.
+    #[error("unsupported archive format version {0}")]
+    UnsupportedFormatVersion(u32),
+
+    /// A record is larger than allowed.
+    #[error("archive record of {0} bytes is too large")]
+    RecordTooLarge(u32),
+
+    /// A record doesn't match its checksum.
+    #[error("archive record of block at height {0} is corrupt")]
+    InvalidChecksum(u64),
+
+    /// The archive ends before its highest block.
+    #[error("archive truncated before block at height {0}")]
+    Truncated(u64),
+
+    /// The archive continues after its highest block.
+    #[error("unexpected data after the last archived block")]
+    TrailingData,
+
+    /// A block is not at the height expected at its position in the archive.
+    #[error("expected block at height {expected}, found one at height {actual}")]
+    UnexpectedHeight {
+        /// The expected height.
+        expected: u64,
+        /// The height of the archived block.
+        actual: u64,
+    },
+
+    /// An archived block is invalid.
+    #[error("invalid block at height {height}: {error}")]
+    InvalidBlock {
+        /// The height of the block.
+        height: u64,
+        /// The validation error.
+        error: ArchivedBlockValidationError,
+    },
+
+    /// A block's parent is not the block stored or archived at the previous height.
+    #[error("block {block_hash} has parent {parent_hash}, expected {expected}")]
+    ParentMismatch {
+        /// The hash of the block.
+        block_hash: BlockHash,
+        /// The hash of the block's parent.
+        parent_hash: BlockHash,
+        /// The hash of the block at the previous height.
+        expected: BlockHash,
+    },
+
+    /// A different block is already stored at the same height.
+    #[error("block {archived} conflicts with stored block {stored}")]
+    ConflictingBlock {
+        /// The hash of the archived block.
+        archived: BlockHash,
+        /// The hash of the stored block.
+        stored: BlockHash,
+    },
+
+    /// The validators of a block's era are unknown, as the previous era's switch block is neither
+    /// stored nor archived.
+    #[error("validators of {0} are unknown; import the switch block of the previous era first")]
+    UnknownValidators(EraId),
+
+    /// The archived genesis block matches neither a stored genesis block nor the trusted hash.
+    #[error("genesis block {0} is not trusted; pass its hash as the trusted hash")]
+    UntrustedGenesis(BlockHash),
+}
+
+/// A summary of an exported or imported archive.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct ArchiveSummary {
+    /// The height of the lowest archived block.
+    pub(crate) from_height: u64,
+    /// The height of the highest archived block.
+    pub(crate) to_height: u64,
+    /// The number of archived deploys.
+    pub(crate) deploy_count: u64,
+}
+
+/// Exports the blocks with heights from `from_height` to `to_height` inclusive to a new archive
+/// file at `path`.
+pub(crate) fn export_blocks(
+    config: WithDir<Config>,
+    from_height: u64,
+    to_height: u64,
+    path: &Path,
+) -> Result<ArchiveSummary, Error> {
+    let (_chainspec, storage) = open_storage(config)?;
+
+    let file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .map_err(|error| io_error(path, error))?;
+    let mut writer = BufWriter::new(file);
+    let summary = write_archive(&storage, from_height, to_height, &mut writer)?;
+    writer
+        .into_inner()
+        .map_err(|error| io_error(path, error.into_error()))?
+        .sync_all()
+        .map_err(|error| io_error(path, error))?;
+
+    info!(
+        path = %path.display(),
+        from_height = summary.from_height,
+        to_height = summary.to_height,
+        deploy_count = summary.deploy_count,
+        "exported blocks"
+    );
+    Ok(summary)
+}
+
+/// Imports the blocks from the archive file at `path`.
+///
+/// If no genesis block is stored, an archived genesis block is only accepted if its hash is
+/// `trusted_hash`.
+///
+/// Imported blocks are not marked as complete, even if their global state is present: the node
+/// verifies that their global state is complete when syncing them as usual.
+pub(crate) fn import_blocks(
+    config: WithDir<Config>,
+    trusted_hash: Option<BlockHash>,
+    path: &Path,
+) -> Result<ArchiveSummary, Error> {
+    let (chainspec, mut storage) = open_storage(config)?;
+
+    let file = File::open(path).map_err(|error| io_error(path, error))?;
+    let summary = read_archive(
+        &mut storage,
+        trusted_hash,
+        chainspec.core_config.finality_threshold_fraction,
+        BufReader::new(file),
+    )?;
+
+    info!(
+        path = %path.display(),
+        from_height = summary.from_height,
+        to_height = summary.to_height,
+        deploy_count = summary.deploy_count,
+        "imported blocks"
+    );
+    Ok(summary)
+}
+
+/// Opens the block storage of the node with the given config.
+fn open_storage(config: WithDir<Config>) -> Result<(Chainspec, Storage), Error> {
+    let (root_dir, config) = config.into_parts();
+    let (chainspec, _) =
+        <(Chainspec, ChainspecRawBytes)>::from_path(&root_dir).map_err(Error::LoadChainspec)?;
+
+    let storage = Storage::new(
+        &WithDir::new(&root_dir, config.storage),
+        None,
+        chainspec.protocol_config.version,
+        chainspec.protocol_config.activation_point.era_id(),
+        &chainspec.network_config.name,
+        chainspec.deploy_config.max_ttl.into(),
+        chainspec.core_config.recent_era_count(),
+        None,
+        false,
+    )?;
+    Ok((chainspec, storage))
+}
+
+/// Writes an archive of the stored blocks with heights from `from_height` to `to_height`
+/// inclusive.
+fn write_archive<W: Write>(
+    storage: &Storage,
+    from_height: u64,
+    to_height: u64,
+    writer: &mut W,
+) -> Result<ArchiveSummary, Error> {
+    if from_height > to_height {
+        return Err(Error::InvalidRange {
+            from_height,
+            to_height,
+        });
+    }
+
+    writer
+        .write_all(ARCHIVE_MAGIC)
+        .and_then(|()| writer.write_all(&ARCHIVE_FORMAT_VERSION.to_le_bytes()))
+        .and_then(|()| writer.write_all(&from_height.to_le_bytes()))
+        .and_then(|()| writer.write_all(&to_height.to_le_bytes()))
+        .map_err(Error::Write)?;
+
+    let mut deploy_count = 0;
+    for height in from_height..=to_height {
+        let archived_block = storage
+            .read_archived_block(height)?
+            .ok_or(Error::MissingBlock(height))?;
+        deploy_count += archived_block.deploys().len() as u64;
+
+        let record = archived_block
+            .to_bytes()
+            .map_err(|error| Error::Encoding { height, error })?;
+        let length = u32::try_from(record.len())
+            .ok()
+            .filter(|length| *length <= MAX_RECORD_SIZE)
+            .ok_or(Error::RecordTooLarge(u32::MAX))?;
+        let checksum = Digest::hash(&record);
+        writer
+            .write_all(&length.to_le_bytes())
+            .and_then(|()| writer.write_all(checksum.as_ref()))
+            .and_then(|()| writer.write_all(&record))
+            .map_err(Error::Write)?;
+    }
+
+    Ok(ArchiveSummary {
+        from_height,
+        to_height,
+        deploy_count,
+    })
+}
+
+/// Verifies and stores the blocks of an archive.
+fn read_archive<R: Read>(
+    storage: &mut Storage,
+    trusted_hash: Option<BlockHash>,
+    finality_threshold_fraction: Ratio<u64>,
+    mut reader: R,
+) -> Result<ArchiveSummary, Error> {
+    let mut magic = [0; ARCHIVE_MAGIC.len()];
+    let mut format_version = [0; 4];
+    let mut from_height = [0; 8];
+    let mut to_height = [0; 8];
+    reader
+        .read_exact(&mut magic)
+        .and_then(|()| reader.read_exact(&mut format_version))
+        .and_then(|()| reader.read_exact(&mut from_height))
+        .and_then(|()| reader.read_exact(&mut to_height))
+        .map_err(|_| Error::InvalidMagic)?;
+    if magic != *ARCHIVE_MAGIC {
+        return Err(Error::InvalidMagic);
+    }
+    let format_version = u32::from_le_bytes(format_version);
+    if format_version != ARCHIVE_FORMAT_VERSION {
+        return Err(Error::UnsupportedFormatVersion(format_version));
+    }
+    let from_height = u64::from_le_bytes(from_height);
+    let to_height = u64::from_le_bytes(to_height);
+    if from_height > to_height {
+        return Err(Error::InvalidRange {
+            from_height,
+            to_height,
+        });
+    }
+
+    let mut parent = match from_height.checked_sub(1) {
+        Some(parent_height) => storage.read_block_header_by_height(parent_height, false)?,
+        None => None,
+    };
+    let mut deploy_count = 0;
+    for height in from_height..=to_height {
+        let archived_block = read_record(&mut reader, height)?;
+        verify_archived_block(
+            storage,
+            &archived_block,
+            height,
+            parent.as_ref(),
+            trusted_hash,
+            finality_threshold_fraction,
+        )?;
+
+        if !storage.write_archived_block(&archived_block)? {
+            return Err(FatalStorageError::FailedToOverwriteBlock.into());
+        }
+        deploy_count += archived_block.deploys().len() as u64;
+        parent = Some(archived_block.block().header().clone());
+    }
+
+    if reader.read(&mut [0]).map_err(Error::Write)? != 0 {
+        return Err(Error::TrailingData);
+    }
+
+    Ok(ArchiveSummary {
+        from_height,
+        to_height,
+        deploy_count,
+    })
+}
+
+/// Reads the record of the block expected at `height`.
+fn read_record<R: Read>(reader: &mut R, height: u64) -> Result<ArchivedBlock, Error> {
+    let mut length = [0; 4];
+    reader
+        .read_exact(&mut length)
+        .map_err(|_| Error::Truncated(height))?;
+    let length = u32::from_le_bytes(length);
+    if length > MAX_RECORD_SIZE {
+        return Err(Error::RecordTooLarge(length));
+    }
+    let mut checksum = [0; Digest::LENGTH];
+    reader
+        .read_exact(&mut checksum)
+        .map_err(|_| Error::Truncated(height))?;
+    let mut record = vec![0; length as usize];
+    reader
+        .read_exact(&mut record)
+        .map_err(|_| Error::Truncated(height))?;
+    if Digest::hash(&record) != Digest::from(checksum) {
+        return Err(Error::InvalidChecksum(height));
+    }
+    bytesrepr::deserialize(record).map_err(|error| Error::Encoding { height, error })
+}
+
+/// Verifies an archived block expected at `height` before it is stored.
+fn verify_archived_block(
+    storage: &Storage,
+    archived_block: &ArchivedBlock,
+    height: u64,
+    parent: Option<&BlockHeader>,
+    trusted_hash: Option<BlockHash>,
+    finality_threshold_fraction: Ratio<u64>,
+) -> Result<(), Error> {
+    let block = archived_block.block();
+    if block.height() != height {
+        return Err(Error::UnexpectedHeight {
+            expected: height,
+            actual: block.height(),
+        });
+    }
+    let invalid_block = |error| Error::InvalidBlock { height, error };
+    archived_block.verify().map_err(invalid_block)?;
+
+    if let Some(parent) = parent {
+        if *block.header().parent_hash() != parent.block_hash() {
+            return Err(Error::ParentMismatch {
+                block_hash: *block.hash(),
+                parent_hash: *block.header().parent_hash(),
+                expected: parent.block_hash(),
+            });
+        }
+    }
+    let stored = storage.read_block_header_by_height(height, false)?;
+    if let Some(stored) = &stored {
+        if stored.block_hash() != *block.hash() {
+            return Err(Error::ConflictingBlock {
+                archived: *block.hash(),
+                stored: stored.block_hash(),
+            });
+        }
+    }
+
+    // The genesis block has no signatures, so it must be the stored one or the trusted one.
+    if block.header().is_genesis() {
+        if stored.is_none() && trusted_hash != Some(*block.hash()) {
+            return Err(Error::UntrustedGenesis(*block.hash()));
+        }
+        return Ok(());
+    }
+    let era_id = block.header().era_id();
+    let switch_block = match era_id.predecessor() {
+        Some(previous_era_id) => storage.read_switch_block_by_era_id(previous_era_id)?,
+        None => None,
+    };
+    let validator_weights = switch_block
+        .as_ref()
+        .and_then(|switch_block| switch_block.header().next_era_validator_weights())
+        .ok_or(Error::UnknownValidators(era_id))?;
+    archived_block
+        .verify_signature_weight(validator_weights, finality_threshold_fraction)
+        .map_err(invalid_block)
+}
+
+fn io_error(path: &Path, error: io::Error) -> Error {
+    Error::Io {
+        path: path.display().to_string(),
+        error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use rand::Rng;
+    use tempfile::TempDir;
+
+    use casper_types::{
+        testing::TestRng, ExecutionResult, ProtocolVersion, PublicKey, SecretKey, TimeDiff,
+        Timestamp, U512,
+    };
+
+    use super::*;
+    use crate::{
+        components::storage::Config as StorageConfig,
+        types::{
+            Block, BlockSignatures, Deploy, DeployWithFinalizedApprovals, FinalitySignature,
+            FinalizedBlock,
+        },
+    };
+
+    /// Returns an archived block with random deploys, signed by `signer` unless `None`.
+    fn archived_block(
+        rng: &mut TestRng,
+        parent: Option<&Block>,
+        era_id: EraId,
+        next_era_validator_weights: Option<BTreeMap<PublicKey, U512>>,
+        signer: Option<&SecretKey>,
+    ) -> ArchivedBlock {
+        let deploys: Vec<Deploy> = (0..3).map(|_| Deploy::random(rng)).collect();
+        let height = parent.map_or(0, |parent| parent.height() + 1);
+        let finalized_block = FinalizedBlock::random_with_specifics(
+            rng,
+            era_id,
+            height,
+            next_era_validator_weights.is_some(),
+            Timestamp::now(),
+            &deploys,
+        );
+        let parent_hash = parent.map_or_else(|| BlockHash::random(rng), |parent| *parent.hash());
+        let block = Block::new(
+            parent_hash,
+            rng.gen::<[u8; 32]>().into(),
+            rng.gen::<[u8; 32]>().into(),
+            finalized_block,
+            next_era_validator_weights,
+            ProtocolVersion::V1_0_0,
+        )
+        .unwrap();
+
+        let mut signatures = BlockSignatures::new(*block.hash(), era_id);
+        if let Some(secret_key) = signer {
+            let public_key = PublicKey::from(secret_key);
+            let signature =
+                FinalitySignature::create(*block.hash(), era_id, secret_key, public_key.clone());
+            let _ = signatures.insert_proof(public_key, signature.signature);
+        }
+        let execution_results = deploys
+            .iter()
+            .map(|deploy| (*deploy.hash(), rng.gen::<ExecutionResult>()))
+            .collect();
+        let deploys = deploys
+            .into_iter()
+            .map(|deploy| DeployWithFinalizedApprovals::new(deploy, None))
+            .collect();
+        ArchivedBlock::new(block, signatures, None, deploys, execution_results)
+    }
+
+    /// Returns a genesis switch block naming `validator` the only validator of era 1, followed by
+    /// a block in era 1 signed by `signer`.
+    fn chain(rng: &mut TestRng, validator: &SecretKey, signer: &SecretKey) -> Vec<ArchivedBlock> {
+        let mut validator_weights = BTreeMap::new();
+        validator_weights.insert(PublicKey::from(validator), U512::from(100));
+        let genesis = archived_block(rng, None, EraId::new(0), Some(validator_weights), None);
+        let child = archived_block(
+            rng,
+            Some(genesis.block()),
+            EraId::new(1),
+            None,
+            Some(signer),
+        );
+        vec![genesis, child]
+    }
+
+    fn new_storage() -> (Storage, TempDir) {
+        let (config, storage_dir) = StorageConfig::default_for_tests();
+        let storage = Storage::new(
+            &WithDir::new(storage_dir.path(), config),
+            None,
+            ProtocolVersion::V1_0_0,
+            EraId::default(),
+            "test",
+            TimeDiff::from_seconds(3600).into(),
+            5,
+            None,
+            false,
+        )
+        .unwrap();
+        (storage, storage_dir)
+    }
+
+    fn export(archived_blocks: &[ArchivedBlock]) -> Vec<u8> {
+        let (mut storage, _storage_dir) = new_storage();
+        for archived_block in archived_blocks {
+            assert!(storage.write_archived_block(archived_block).unwrap());
+        }
+        let to_height = archived_blocks.last().unwrap().block().height();
+        let mut archive = Vec::new();
+        let summary = write_archive(&storage, 0, to_height, &mut archive).unwrap();
+        assert_eq!(
+            summary.deploy_count,
+            archived_blocks
+                .iter()
+                .map(|archived_block| archived_block.deploys().len() as u64)
+                .sum::<u64>()
+        );
+        archive
+    }
+
+    fn import(
+        archive: &[u8],
+        trusted_hash: Option<BlockHash>,
+    ) -> (Result<ArchiveSummary, Error>, Storage) {
+        let (mut storage, _storage_dir) = new_storage();
+        let result = read_archive(&mut storage, trusted_hash, Ratio::new(1, 3), archive);
+        (result, storage)
+    }
+
+    #[test]
+    fn should_export_and_import_blocks() {
+        let mut rng = crate::new_rng();
+        let validator = SecretKey::random(&mut rng);
+        let archived_blocks = chain(&mut rng, &validator, &validator);
+        let archive = export(&archived_blocks);
+
+        let (result, storage) = import(&archive, Some(*archived_blocks[0].block().hash()));
+        let summary = result.unwrap();
+        assert_eq!(summary.from_height, 0);
+        assert_eq!(summary.to_height, 1);
+        // Completeness of the blocks' global state is left to the node's sync.
+        assert!(storage.read_highest_complete_block().unwrap().is_none());
+        for archived_block in &archived_blocks {
+            let imported = storage
+                .read_archived_block(archived_block.block().height())
+                .unwrap()
+                .unwrap();
+            assert_eq!(imported, *archived_block);
+        }
+    }
+
+    #[test]
+    fn should_reject_block_without_sufficient_signatures() {
+        let mut rng = crate::new_rng();
+        let validator = SecretKey::random(&mut rng);
+        let other = SecretKey::random(&mut rng);
+        let archived_blocks = chain(&mut rng, &validator, &other);
+        let archive = export(&archived_blocks);
+
+        let (result, storage) = import(&archive, Some(*archived_blocks[0].block().hash()));
+        assert!(matches!(
+            result,
+            Err(Error::InvalidBlock {
+                height: 1,
+                error: ArchivedBlockValidationError::InsufficientSignatures(_)
+            })
+        ));
+        // The genesis block preceding the invalid block has been imported.
+        assert!(storage.read_archived_block(0).unwrap().is_some());
+        assert!(storage.read_archived_block(1).unwrap().is_none());
+    }
+
+    #[test]
+    fn should_reject_corrupt_archive() {
+        let mut rng = crate::new_rng();
+        let validator = SecretKey::random(&mut rng);
+        let mut archive = export(&chain(&mut rng, &validator, &validator));
+
+        let index = rng.gen_range(ARCHIVE_MAGIC.len()..archive.len());
+        archive[index] ^= 1;
+
+        let (result, _storage) = import(&archive, None);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn should_reject_truncated_archive() {
+        let mut rng = crate::new_rng();
+        let validator = SecretKey::random(&mut rng);
+        let archived_blocks = chain(&mut rng, &validator, &validator);
+        let archive = export(&archived_blocks);
+
+        let trusted_hash = Some(*archived_blocks[0].block().hash());
+        let (result, _storage) = import(&archive[..archive.len() - 1], trusted_hash);
+        assert!(matches!(result, Err(Error::Truncated(1))));
+    }
+
+    #[test]
+    fn should_reject_untrusted_genesis_block() {
+        let mut rng = crate::new_rng();
+        let validator = SecretKey::random(&mut rng);
+        let archived_blocks = chain(&mut rng, &validator, &validator);
+        let archive = export(&archived_blocks);
+
+        // A forged chain with its own genesis block naming the forger the only validator.
+        let forger = SecretKey::random(&mut rng);
+        let forged_blocks = chain(&mut rng, &forger, &forger);
+        let forged_genesis_hash = *forged_blocks[0].block().hash();
+        let forged_archive = export(&forged_blocks);
+
+        let (result, storage) = import(&forged_archive, None);
+        assert!(
+            matches!(result, Err(Error::UntrustedGenesis(hash)) if hash == forged_genesis_hash)
+        );
+        assert!(storage.read_archived_block(0).unwrap().is_none());
+
+        let trusted_hash = Some(*archived_blocks[0].block().hash());
+        let (result, _storage) = import(&forged_archive, trusted_hash);
+        assert!(
+            matches!(result, Err(Error::UntrustedGenesis(hash)) if hash == forged_genesis_hash)
+        );
+        let (result, _storage) = import(&archive, trusted_hash);
+        assert!(result.is_ok());
+    }
+}
@@ -20,12 +20,14 @@ use structopt::StructOpt;
 use toml::{value::Table, Value};
 use tracing::info;
 
+use casper_hashing::Digest;
+
 use crate::{
     components::network::Identity as NetworkIdentity,
     logging,
     reactor::{main_reactor, Runner},
     setup_signal_hooks,
-    types::{Chainspec, ChainspecRawBytes, ExitCode},
+    types::{BlockHash, Chainspec, ChainspecRawBytes, ExitCode},
     utils::{Loadable, WithDir},
 };
 
@@ -78,6 +80,112 @@ pub enum Cli {
         #[structopt(long)]
         new_config: PathBuf,
     },
+    /// Export the global state of a block to a snapshot file.
+    ///
+    /// Writes every trie node reachable from the state root hash of the given block, or of the
+    /// highest complete block if not given, along with the block's header.
+    ExportGlobalState {
+        /// Path to configuration file.
+        config: PathBuf,
+        /// Hex-encoded hash of the block whose global state is exported.
+        #[structopt(long, parse(try_from_str = parse_block_hash))]
+        block_hash: Option<BlockHash>,
+        /// Path of the snapshot file to create.
+        #[structopt(long)]
+        output: PathBuf,
+    },
+    /// Import the global state from a snapshot file.
+    ///
+    /// Validates the hash of every trie node before storing it.  The node should then be started
+    /// with the snapshot's block hash as its trusted hash.
+    ImportGlobalState {
+        /// Path to configuration file.
+        config: PathBuf,
+        /// Hex-encoded hash of the block the snapshot must be of.
+        #[structopt(long, parse(try_from_str = parse_block_hash))]
+        trusted_block_hash: Option<BlockHash>,
+        /// Path of the snapshot file to import.
+        #[structopt(long)]
+        input: PathBuf,
+    },
+    /// Export a range of blocks, with their signatures, deploys and execution results, to an
+    /// archive file.
+    ExportBlocks {
+        /// Path to configuration file.
+        config: PathBuf,
+        /// Height of the first block to export.
+        #[structopt(long)]
+        from_height: u64,
+        /// Height of the last block to export.
+        #[structopt(long)]
+        to_height: u64,
+        /// Path of the archive file to create.
+        #[structopt(long)]
+        output: PathBuf,
+    },
+    /// Import the blocks from an archive file.
+    ///
+    /// Validates the hashes and signatures of every block before storing it.  The switch block of
+    /// the era preceding the first archived block must already be stored.
+    ImportBlocks {
+        /// Path to configuration file.
+        config: PathBuf,
+        /// Hex-encoded hash of the genesis block, required to import it unless already stored.
+        #[structopt(long, parse(try_from_str = parse_block_hash))]
+        trusted_hash: Option<BlockHash>,
+        /// Path of the archive file to import.
+        #[structopt(long)]
+        input: PathBuf,
+    },
+    /// Run a signer process holding the validator's secret key, to which nodes configured with a
+    /// `consensus.remote_signer_address` forward all signing requests.
+    ///
+    /// The signer refuses to sign conflicting consensus messages or finality signatures, and
+    /// persists what it has signed so that this protection survives restarts.
+    RunSigner {
+        /// Path to the validator's secret key file.
+        #[structopt(long)]
+        secret_key: PathBuf,
+        /// Address to listen on: either a loopback TCP socket address or the path of a Unix
+        /// socket.
+        #[structopt(long)]
+        address: String,
+        /// Path of the slashing protection database recording what has been signed.
+        #[structopt(long)]
+        state: PathBuf,
+        /// Path to a file containing the token nodes must authenticate with.  Required when
+        /// listening on a TCP socket, and by nodes on networks below protocol version 1.6.0.
+        #[structopt(long)]
+        auth_token: Option<PathBuf>,
+    },
+    /// Export the slashing protection database, recording the consensus messages and finality
+    /// signatures signed by this node, in the interchange format.
+    ///
+    /// The node should be stopped while exporting.
+    ExportSlashingProtection {
+        /// Path to configuration file.
+        config: PathBuf,
+        /// Path of the file to write.
+        #[structopt(long)]
+        output: PathBuf,
+    },
+    /// Merge a slashing protection database in the interchange format into this node's database,
+    /// e.g. when moving a validator key from another machine.
+    ///
+    /// The node must be stopped while importing.
+    ImportSlashingProtection {
+        /// Path to configuration file.
+        config: PathBuf,
+        /// Path of the file to import.
+        #[structopt(long)]
+        input: PathBuf,
+    },
+}
+
+/// Parses a hex-encoded block hash.
+fn parse_block_hash(input: &str) -> anyhow::Result<BlockHash> {
+    let digest = Digest::from_hex(input).context("could not parse block hash")?;
+    Ok(BlockHash::new(digest))
 }
 
 #[derive(Debug)]
@@ -237,6 +345,90 @@ impl Cli {
                 )?;
                 Ok(ExitCode::Success as i32)
             }
+            Cli::ExportGlobalState {
+                config,
+                block_hash,
+                output,
+            } => {
+                let config = Self::init(&config, vec![])?;
+
+                info!(build_version = %crate::VERSION_STRING.as_str(), "exporting global state");
+                crate::global_state_snapshot::export_global_state(config, block_hash, &output)?;
+                Ok(ExitCode::Success as i32)
+            }
+            Cli::ImportGlobalState {
+                config,
+                trusted_block_hash,
+                input,
+            } => {
+                let config = Self::init(&config, vec![])?;
+
+                info!(build_version = %crate::VERSION_STRING.as_str(), "importing global state");
+                crate::global_state_snapshot::import_global_state(
+                    config,
+                    trusted_block_hash,
+                    &input,
+                )?;
+                Ok(ExitCode::Success as i32)
+            }
+            Cli::ExportBlocks {
+                config,
+                from_height,
+                to_height,
+                output,
+            } => {
+                let config = Self::init(&config, vec![])?;
+
+                info!(build_version = %crate::VERSION_STRING.as_str(), "exporting blocks");
+                crate::block_archive::export_blocks(config, from_height, to_height, &output)?;
+                Ok(ExitCode::Success as i32)
+            }
+            Cli::ImportBlocks {
+                config,
+                trusted_hash,
+                input,
+            } => {
+                let config = Self::init(&config, vec![])?;
+
+                info!(build_version = %crate::VERSION_STRING.as_str(), "importing blocks");
+                crate::block_archive::import_blocks(config, trusted_hash, &input)?;
+                Ok(ExitCode::Success as i32)
+            }
+            Cli::RunSigner {
+                secret_key,
+                address,
+                state,
+                auth_token,
+            } => {
+                logging::init_with_config(&Default::default())?;
+
+                info!(build_version = %crate::VERSION_STRING.as_str(), "running signer");
+                tokio::task::spawn_blocking(move || {
+                    crate::signer::run_signer(&secret_key, &address, &state, auth_token.as_deref())
+                })
+                .await??;
+                Ok(ExitCode::Success as i32)
+            }
+            Cli::ExportSlashingProtection { config, output } => {
+                let config = Self::init(&config, vec![])?;
+
+                info!(
+                    build_version = %crate::VERSION_STRING.as_str(),
+                    "exporting slashing protection database"
+                );
+                crate::signer::export_slashing_protection(config, &output)?;
+                Ok(ExitCode::Success as i32)
+            }
+            Cli::ImportSlashingProtection { config, input } => {
+                let config = Self::init(&config, vec![])?;
+
+                info!(
+                    build_version = %crate::VERSION_STRING.as_str(),
+                    "importing slashing protection database"
+                );
+                crate::signer::import_slashing_protection(config, &input)?;
+                Ok(ExitCode::Success as i32)
+            }
         }
     }
 
@@ -51,6 +51,7 @@ pub mod consensus;
 pub mod contract_runtime;
 pub(crate) mod deploy_acceptor;
 pub(crate) mod deploy_buffer;
+pub(crate) mod deploy_staging;
 pub(crate) mod diagnostics_port;
 pub(crate) mod event_stream_server;
 pub(crate) mod fetcher;
@@ -32,7 +32,7 @@ use casper_execution_engine::core::engine_state;
 use casper_hashing::Digest;
 use casper_types::Timestamp;
 
-use super::network::blocklist::BlocklistJustification;
+use super::network::{blocklist::BlocklistJustification, reputation::Misbehavior};
 use crate::{
     components::{
         fetcher::{
@@ -526,6 +526,17 @@ impl BlockSynchronizer {
         }
     }
 
+    fn take_demoted_peers(&mut self) -> Vec<NodeId> {
+        let mut ret = vec![];
+        if let Some(builder) = &mut self.forward {
+            ret.extend(builder.take_demoted_peers());
+        }
+        if let Some(builder) = &mut self.historical {
+            ret.extend(builder.take_demoted_peers());
+        }
+        ret
+    }
+
     fn need_next<REv>(
         &mut self,
         effect_builder: EffectBuilder<REv>,
@@ -1424,7 +1435,8 @@ impl<REv: ReactorEvent> Component<REv> for BlockSynchronizer {
                     // sync'd
                     BlockSynchronizerRequest::NeedNext => self.need_next(effect_builder, rng),
                     // this component is periodically asked for any peers that have provided false
-                    // data (if any) which are then disconnected from
+                    // data (if any) which are then disconnected from, and for any peers that
+                    // failed to provide data in time (if any) which lose reputation
                     BlockSynchronizerRequest::DishonestPeers => {
                         let mut effects: Effects<Self::Event> = self
                             .dishonest_peers()
@@ -1439,6 +1451,11 @@ impl<REv: ReactorEvent> Component<REv> for BlockSynchronizer {
                             })
                             .collect();
                         self.flush_dishonest_peers();
+                        effects.extend(self.take_demoted_peers().into_iter().flat_map(|node_id| {
+                            effect_builder
+                                .announce_peer_misbehavior(node_id, Misbehavior::SlowResponse)
+                                .ignore()
+                        }));
                         effects.extend(
                             effect_builder
                                 .set_timeout(self.config.disconnect_dishonest_peers_interval.into())
@@ -390,6 +390,10 @@ impl BlockBuilder {
         self.peer_list.flush_dishonest_peers();
     }
 
+    pub(super) fn take_demoted_peers(&mut self) -> Vec<NodeId> {
+        self.peer_list.take_demoted_peers()
+    }
+
     pub(super) fn block_acquisition_action(
         &mut self,
         rng: &mut NodeRng,
@@ -29,6 +29,8 @@ pub(super) enum PeersStatus {
 #[derive(Clone, PartialEq, Eq, DataSize, Debug)]
 pub(super) struct PeerList {
     peer_list: BTreeMap<NodeId, PeerQuality>,
+    /// Peers demoted since the demotions were last taken, once per demotion.
+    demoted_peers: Vec<NodeId>,
     keep_fresh: Timestamp,
     max_simultaneous_peers: u8,
     peer_refresh_interval: TimeDiff,
@@ -38,6 +40,7 @@ impl PeerList {
     pub(super) fn new(max_simultaneous_peers: u8, peer_refresh_interval: TimeDiff) -> Self {
         PeerList {
             peer_list: BTreeMap::new(),
+            demoted_peers: Vec::new(),
             keep_fresh: Timestamp::now(),
             max_simultaneous_peers,
             peer_refresh_interval,
@@ -105,16 +108,24 @@ impl PeerList {
                 // no change
             }
             Entry::Occupied(entry) => match entry.get() {
-                PeerQuality::Dishonest | PeerQuality::Unreliable => {
+                PeerQuality::Dishonest => {
                     // no change
                 }
+                PeerQuality::Unreliable => {
+                    self.demoted_peers.push(peer);
+                }
                 PeerQuality::Reliable | PeerQuality::Unknown => {
                     self.peer_list.insert(peer, PeerQuality::Unreliable);
+                    self.demoted_peers.push(peer);
                 }
             },
         }
     }
 
+    pub(super) fn take_demoted_peers(&mut self) -> Vec<NodeId> {
+        std::mem::take(&mut self.demoted_peers)
+    }
+
     pub(super) fn need_peers(&mut self) -> PeersStatus {
         if !self
             .peer_list
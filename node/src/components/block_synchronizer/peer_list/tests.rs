@@ -127,3 +127,20 @@ fn unreliable_peer_remains_unreliable_if_demoted() {
     peer_list.demote_peer(test_peer);
     assert!(peer_list.is_peer_unreliable(&test_peer));
 }
+
+#[test]
+fn demotions_are_recorded_until_taken() {
+    let mut rng = TestRng::new();
+    let mut peer_list = PeerList::new(5, TimeDiff::from_seconds(1));
+    let test_peer = NodeId::random(&mut rng);
+    let dishonest_peer = NodeId::random(&mut rng);
+
+    peer_list.register_peer(test_peer);
+    peer_list.register_peer(dishonest_peer);
+    peer_list.disqualify_peer(dishonest_peer);
+    peer_list.demote_peer(test_peer);
+    peer_list.demote_peer(test_peer);
+    peer_list.demote_peer(dishonest_peer);
+    assert_eq!(peer_list.take_demoted_peers(), vec![test_peer, test_peer]);
+    assert!(peer_list.take_demoted_peers().is_empty());
+}
@@ -30,7 +30,8 @@ use derive_more::From;
 use serde::{Deserialize, Serialize};
 use tracing::{info, trace};
 
-use casper_types::{EraId, Timestamp};
+use casper_hashing::Digest;
+use casper_types::{crypto::Signature, EraId, Timestamp};
 
 use crate::{
     components::Component,
@@ -57,8 +58,9 @@ use crate::{
 use protocols::{highway::HighwayProtocol, zug::Zug};
 use traits::Context;
 
+pub(crate) use cl_context::decode_message_id;
 pub use cl_context::ClContext;
-pub(crate) use config::{ChainspecConsensusExt, Config};
+pub(crate) use config::{ChainspecConsensusExt, Config, LoadSignerError};
 pub(crate) use consensus_protocol::{BlockContext, EraReport, ProposedBlock};
 pub(crate) use era_supervisor::{debug::EraDump, EraSupervisor, SerializedMessage};
 #[cfg(test)]
@@ -67,6 +69,7 @@ pub(crate) use leader_sequence::LeaderSequence;
 pub(crate) use protocols::highway::max_rounds_per_era;
 #[cfg(test)]
 pub(crate) use protocols::highway::HighwayMessage;
+pub(crate) use traits::SignedMessageId;
 pub(crate) use validator_change::ValidatorChange;
 
 const COMPONENT_NAME: &str = "consensus";
@@ -172,6 +175,13 @@ pub(crate) enum Event {
     },
     /// The proposed block has been validated.
     ResolveValidity(ResolveValidity),
+    /// The signature of our consensus message with the given hash has been created, or the signer
+    /// refused to sign it.
+    Signature {
+        era_id: EraId,
+        hash: Digest,
+        signature: Option<Signature>,
+    },
     /// Deactivate the era with the given ID, unless the number of faulty validators increases.
     DeactivateEra {
         era_id: EraId,
@@ -293,6 +303,21 @@ impl Display for Event {
                 if *valid { "valid" } else { "invalid" },
                 proposed_block,
             ),
+            Event::Signature {
+                era_id,
+                hash,
+                signature,
+            } => write!(
+                f,
+                "signature of message {} in {}: {}",
+                hash,
+                era_id,
+                if signature.is_some() {
+                    "created"
+                } else {
+                    "refused"
+                },
+            ),
             Event::DeactivateEra {
                 era_id, faulty_num, ..
             } => write!(
@@ -463,6 +488,11 @@ where
             Event::ResolveValidity(resolve_validity) => {
                 self.resolve_validity(effect_builder, rng, resolve_validity)
             }
+            Event::Signature {
+                era_id,
+                hash,
+                signature,
+            } => self.handle_signature(effect_builder, rng, era_id, hash, signature),
             Event::DeactivateEra {
                 era_id,
                 faulty_num,
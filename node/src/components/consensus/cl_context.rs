@@ -1,51 +1,114 @@
 use std::sync::Arc;
 
+use bincode::Options;
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
-use tracing::info;
+use tracing::{error, info};
 
 use casper_hashing::Digest;
-use casper_types::{crypto, PublicKey, SecretKey, Signature};
+use casper_types::{crypto, PublicKey, SecretKey, Signature, Timestamp};
 
 use crate::{
-    components::consensus::traits::{ConsensusValueT, Context, ValidatorSecret},
+    components::consensus::{
+        highway_core::highway::{Ping, WireUnit},
+        protocols::zug::{Content, RoundId},
+        traits::{ConsensusValueT, Context, SignedMessageId, ValidatorSecret},
+        utils::ValidatorIndex,
+    },
+    signer::{LocalSigner, Signable, Signer},
     types::BlockPayload,
 };
 
 #[derive(DataSize)]
 pub struct Keypair {
-    secret_key: Arc<SecretKey>,
-    public_key: PublicKey,
+    #[data_size(skip)]
+    signer: Arc<dyn Signer>,
 }
 
 impl Keypair {
-    pub(crate) fn new(secret_key: Arc<SecretKey>, public_key: PublicKey) -> Self {
-        Self {
-            secret_key,
-            public_key,
-        }
+    pub(crate) fn new(signer: Arc<dyn Signer>) -> Self {
+        Self { signer }
     }
 
     #[cfg(test)]
     pub(crate) fn public_key(&self) -> &PublicKey {
-        &self.public_key
+        self.signer.public_key()
     }
 }
 
 impl From<Arc<SecretKey>> for Keypair {
     fn from(secret_key: Arc<SecretKey>) -> Self {
-        let public_key: PublicKey = secret_key.as_ref().into();
-        Self::new(secret_key, public_key)
+        Self::new(Arc::new(LocalSigner::new(secret_key)))
     }
 }
 
 impl ValidatorSecret for Keypair {
     type Hash = Digest;
     type Signature = Signature;
+    type InstanceId = Digest;
 
-    fn sign(&self, hash: &Digest) -> Signature {
-        crypto::sign(hash, self.secret_key.as_ref(), &self.public_key)
+    fn sign(
+        &self,
+        _hash: &Digest,
+        message_id: SignedMessageId<Digest>,
+        fields: &[u8],
+    ) -> Option<Signature> {
+        let message = Signable::Consensus {
+            message_id,
+            fields: fields.to_vec(),
+        };
+        match self.signer.sign(&message) {
+            Ok(signature) => Some(signature),
+            Err(error) => {
+                error!(%error, ?message_id, "failed to sign consensus message");
+                None
+            }
+        }
     }
+
+    fn may_block(&self) -> bool {
+        self.signer.may_block()
+    }
+}
+
+/// Returns the ID of the message of the same kind as `message_id` with the given serialized fields,
+/// or an error if they are not the fields of such a message.
+///
+/// The fields are decoded strictly, rejecting trailing bytes, so that the fields of one kind of
+/// message can't pass for those of another.
+pub(crate) fn decode_message_id(
+    message_id: &SignedMessageId<Digest>,
+    fields: &[u8],
+) -> Result<SignedMessageId<Digest>, bincode::Error> {
+    let options = bincode::DefaultOptions::new().with_fixint_encoding();
+    let decoded_id = match message_id {
+        SignedMessageId::HighwayUnit { .. } => {
+            let wire_unit: WireUnit<ClContext> = options.deserialize(fields)?;
+            SignedMessageId::HighwayUnit {
+                instance_id: wire_unit.instance_id,
+                seq_number: wire_unit.seq_number,
+            }
+        }
+        SignedMessageId::HighwayPing { .. } => {
+            let (_creator, timestamp, instance_id): (ValidatorIndex, Timestamp, Digest) =
+                options.deserialize(fields)?;
+            Ping::<ClContext>::message_id(timestamp, instance_id)
+        }
+        SignedMessageId::HighwayEndorsement => {
+            let (_unit, _creator): (Digest, ValidatorIndex) = options.deserialize(fields)?;
+            SignedMessageId::HighwayEndorsement
+        }
+        SignedMessageId::ZugEcho { .. } | SignedMessageId::ZugVote { .. } => {
+            let (round_id, instance_id, content, _validator_idx): (
+                RoundId,
+                Digest,
+                Content<ClContext>,
+                ValidatorIndex,
+            ) = options.deserialize(fields)?;
+            content.message_id(round_id, instance_id)
+        }
+    };
+    Ok(decoded_id)
 }
 
 impl ConsensusValueT for Arc<BlockPayload> {
@@ -82,17 +145,13 @@ impl Context for ClContext {
 mod specimen_support {
     use super::Keypair;
     use crate::utils::specimen::{Cache, LargestSpecimen, SizeEstimator};
-    use casper_types::{PublicKey, SecretKey};
+    use casper_types::SecretKey;
     use std::sync::Arc;
 
     impl LargestSpecimen for Keypair {
         fn largest_specimen<E: SizeEstimator>(estimator: &E, cache: &mut Cache) -> Self {
             let secret_key = SecretKey::largest_specimen(estimator, cache);
-            let public_key = PublicKey::from(&secret_key);
-            Keypair {
-                secret_key: Arc::new(secret_key),
-                public_key,
-            }
+            Keypair::from(Arc::new(secret_key))
         }
     }
 }
@@ -1,9 +1,13 @@
-use std::{path::Path, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
-use casper_types::{PublicKey, SecretKey};
+use casper_types::{ProtocolVersion, SecretKey};
 
 use crate::{
     components::consensus::{
@@ -11,6 +15,10 @@ use crate::{
         protocols::{highway::config::Config as HighwayConfig, zug::config::Config as ZugConfig},
         EraId,
     },
+    signer::{
+        self, AuthToken, LocalSigner, RemoteSigner, Signer, PREFIXED_HANDSHAKE_CERTIFICATE_VERSION,
+        SLASHING_PROTECTION_FILENAME,
+    },
     types::Chainspec,
     utils::{External, LoadError, Loadable},
 };
@@ -24,6 +32,19 @@ const DEFAULT_MAX_EXECUTION_DELAY: u64 = 3;
 pub struct Config {
     /// Path to secret key file.
     pub secret_key_path: External,
+    /// Address of a signer process holding the secret key, either a local TCP socket address or
+    /// the path of a Unix socket.  If set, `secret_key_path` is ignored.
+    #[serde(default)]
+    pub remote_signer_address: Option<String>,
+    /// Path to the file containing the token authenticating the node to the signer process.
+    /// Required if the signer process listens on TCP, or the network runs a protocol version below
+    /// 1.6.0.
+    #[serde(default)]
+    pub remote_signer_auth_token_path: Option<PathBuf>,
+    /// Path to the slashing protection database.  It must outlive the node's storage, so it is
+    /// kept outside of the storage directory.
+    #[serde(default = "default_slashing_protection_path")]
+    pub slashing_protection_path: PathBuf,
     /// The maximum number of blocks by which execution is allowed to lag behind finalization.
     /// If it is more than that, consensus will pause, and resume once the executor has caught up.
     pub max_execution_delay: u64,
@@ -39,6 +60,9 @@ impl Default for Config {
     fn default() -> Self {
         Config {
             secret_key_path: External::Missing,
+            remote_signer_address: None,
+            remote_signer_auth_token_path: None,
+            slashing_protection_path: default_slashing_protection_path(),
             max_execution_delay: DEFAULT_MAX_EXECUTION_DELAY,
             highway: HighwayConfig::default(),
             zug: ZugConfig::default(),
@@ -46,17 +70,74 @@ impl Default for Config {
     }
 }
 
+fn default_slashing_protection_path() -> PathBuf {
+    PathBuf::from(SLASHING_PROTECTION_FILENAME)
+}
+
 type LoadKeyError = LoadError<<Arc<SecretKey> as Loadable>::Error>;
 
+/// Error setting up the signer of the validator's messages.
+#[derive(Debug, Error)]
+pub(crate) enum LoadSignerError {
+    /// Error loading the secret key.
+    #[error("signing key pair load error: {0}")]
+    LoadKey(#[from] LoadKeyError),
+    /// Error loading the token authenticating the node to the remote signer.
+    #[error("could not load remote signer authentication token {}: {error}", path.display())]
+    AuthToken { path: PathBuf, error: signer::Error },
+    /// A remote signer without an authentication token on a network below the protocol version
+    /// from which handshake certificates are prefixed, so the signer would refuse to sign them.
+    #[error(
+        "a remote signer requires consensus.remote_signer_auth_token_path to sign handshake \
+        certificates below protocol version {PREFIXED_HANDSHAKE_CERTIFICATE_VERSION}"
+    )]
+    LegacyHandshakeWithoutAuthToken,
+    /// Error connecting to the remote signer.
+    #[error("could not connect to remote signer at {address}: {error}")]
+    RemoteSigner {
+        address: String,
+        error: signer::Error,
+    },
+}
+
 impl Config {
-    /// Loads the secret key from the configuration file and derives the public key.
-    pub(crate) fn load_keys<P: AsRef<Path>>(
+    /// Connects to the remote signer if one is configured, or otherwise loads the secret key from
+    /// the configuration file.
+    ///
+    /// On a network running a protocol version below 1.6.0, a remote signer only signs handshake
+    /// certificates for a node authenticated with its token, so one is required.
+    pub(crate) fn load_signer<P: AsRef<Path>>(
         &self,
         root: P,
-    ) -> Result<(Arc<SecretKey>, PublicKey), LoadKeyError> {
-        let secret_signing_key: Arc<SecretKey> = self.secret_key_path.clone().load(root)?;
-        let public_key: PublicKey = PublicKey::from(secret_signing_key.as_ref());
-        Ok((secret_signing_key, public_key))
+        protocol_version: ProtocolVersion,
+    ) -> Result<Arc<dyn Signer>, LoadSignerError> {
+        match &self.remote_signer_address {
+            Some(address) => {
+                let auth_token = match &self.remote_signer_auth_token_path {
+                    Some(path) => {
+                        let path = root.as_ref().join(path);
+                        let auth_token = AuthToken::from_file(&path)
+                            .map_err(|error| LoadSignerError::AuthToken { path, error })?;
+                        Some(auth_token)
+                    }
+                    None if protocol_version < PREFIXED_HANDSHAKE_CERTIFICATE_VERSION => {
+                        return Err(LoadSignerError::LegacyHandshakeWithoutAuthToken)
+                    }
+                    None => None,
+                };
+                match RemoteSigner::connect(address, auth_token) {
+                    Ok(signer) => Ok(Arc::new(signer)),
+                    Err(error) => Err(LoadSignerError::RemoteSigner {
+                        address: address.clone(),
+                        error,
+                    }),
+                }
+            }
+            None => {
+                let secret_signing_key: Arc<SecretKey> = self.secret_key_path.clone().load(root)?;
+                Ok(Arc::new(LocalSigner::new(secret_signing_key)))
+            }
+        }
     }
 }
 
@@ -12,7 +12,10 @@ use casper_hashing::Digest;
 use casper_types::{bytesrepr::ToBytes, TimeDiff, Timestamp};
 
 use crate::{
-    components::consensus::{traits::Context, ActionId, TimerId},
+    components::consensus::{
+        traits::{Context, SignedMessageId},
+        ActionId, TimerId,
+    },
     types::NodeId,
     NodeRng,
 };
@@ -201,6 +204,9 @@ pub(crate) enum ProtocolOutcome<C: Context> {
     QueueAction(ActionId),
     /// Request deploys for a new block, providing the necessary context.
     CreateNewBlock(BlockContext<C>),
+    /// Request a signature of the message with the given hash, identifier and serialized fields
+    /// from the validator's signer, to be passed to `ConsensusProtocol::handle_signature`.
+    SignMessage(C::Hash, SignedMessageId<C::InstanceId>, Vec<u8>),
     /// A block was finalized.
     FinalizedBlock(FinalizedBlock<C>),
     /// Request validation of the consensus value, contained in a message received from the given
@@ -276,6 +282,15 @@ pub(crate) trait ConsensusProtocol<C: Context>: Send {
     /// Proposes a new value for consensus.
     fn propose(&mut self, proposed_block: ProposedBlock<C>, now: Timestamp) -> ProtocolOutcomes<C>;
 
+    /// Handles the signature requested via `ProtocolOutcome::SignMessage`, or `None` if the
+    /// signer failed or refused to sign the message.
+    fn handle_signature(
+        &mut self,
+        hash: C::Hash,
+        signature: Option<C::Signature>,
+        now: Timestamp,
+    ) -> ProtocolOutcomes<C>;
+
     /// Marks the `value` as valid or invalid, based on validation requested via
     /// `ProtocolOutcome::ValidateConsensusvalue`.
     fn resolve_validity(
@@ -31,7 +31,7 @@ use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use tracing::{debug, error, info, trace, warn};
 
 use casper_hashing::Digest;
-use casper_types::{AsymmetricType, EraId, PublicKey, SecretKey, TimeDiff, Timestamp};
+use casper_types::{crypto::Signature, AsymmetricType, EraId, PublicKey, TimeDiff, Timestamp};
 
 use crate::{
     components::{
@@ -55,6 +55,7 @@ use crate::{
     },
     failpoints::Failpoint,
     fatal, protocol,
+    signer::{self, Signable, Signer},
     types::{
         chainspec::ConsensusProtocolName, BlockHash, BlockHeader, Chainspec, Deploy, DeployHash,
         DeployOrTransferHash, FinalizedApprovals, FinalizedBlock, MetaBlockState, NodeId,
@@ -96,7 +97,8 @@ pub struct EraSupervisor {
     /// Since eras at or before the most recent activation point are never instantiated, shortly
     /// after that there can temporarily be fewer than three entries in the map.
     open_eras: BTreeMap<EraId, Era>,
-    secret_signing_key: Arc<SecretKey>,
+    #[data_size(skip)]
+    signer: Arc<dyn Signer>,
     public_signing_key: PublicKey,
     chainspec: Arc<Chainspec>,
     config: Config,
@@ -132,7 +134,7 @@ impl EraSupervisor {
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         storage_dir: &Path,
-        secret_signing_key: Arc<SecretKey>,
+        signer: Arc<dyn Signer>,
         public_signing_key: PublicKey,
         config: Config,
         chainspec: Arc<Chainspec>,
@@ -145,7 +147,7 @@ impl EraSupervisor {
 
         let era_supervisor = Self {
             open_eras: Default::default(),
-            secret_signing_key,
+            signer,
             public_signing_key,
             chainspec,
             config,
@@ -384,7 +386,7 @@ impl EraSupervisor {
             vec![]
         } else {
             info!(era = era_id.value(), %our_id, "start voting");
-            let secret = Keypair::new(self.secret_signing_key.clone(), our_id.clone());
+            let secret = Keypair::new(Arc::clone(&self.signer));
             let instance_id = self.era(era_id).consensus.instance_id();
             let unit_hash_file = self.unit_file(instance_id);
             self.era_mut(era_id).consensus.activate_validator(
@@ -874,6 +876,19 @@ impl EraSupervisor {
         Ok(which_era)
     }
 
+    pub(super) fn handle_signature<REv: ReactorEventT>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        rng: &mut NodeRng,
+        era_id: EraId,
+        hash: Digest,
+        signature: Option<Signature>,
+    ) -> Effects<Event> {
+        self.delegate_to_era(effect_builder, rng, era_id, move |consensus, _| {
+            consensus.handle_signature(hash, signature, Timestamp::now())
+        })
+    }
+
     pub(super) fn resolve_validity<REv: ReactorEventT>(
         &mut self,
         effect_builder: EffectBuilder<REv>,
@@ -1033,6 +1048,24 @@ impl EraSupervisor {
             ProtocolOutcome::QueueAction(action_id) => effect_builder
                 .immediately()
                 .event(move |()| Event::Action { era_id, action_id }),
+            ProtocolOutcome::SignMessage(hash, message_id, fields) => {
+                let signer = Arc::clone(&self.signer);
+                async move {
+                    let message = Signable::Consensus { message_id, fields };
+                    match signer::sign_async(signer, message).await {
+                        Ok(signature) => Some(signature),
+                        Err(error) => {
+                            error!(%error, ?message_id, "failed to sign consensus message");
+                            None
+                        }
+                    }
+                }
+                .event(move |signature| Event::Signature {
+                    era_id,
+                    hash,
+                    signature,
+                })
+            }
             ProtocolOutcome::CreateNewBlock(block_context) => {
                 let accusations = self
                     .iter_past(era_id, PAST_EVIDENCE_ERAS)
@@ -1,4 +1,5 @@
 use std::{
+    collections::BTreeMap,
     fmt::{self, Debug},
     fs::{self, File},
     io::{self, Read, Write},
@@ -7,14 +8,14 @@ use std::{
 };
 
 use datasize::DataSize;
-use tracing::{error, info, trace, warn};
+use tracing::{debug, error, info, trace, warn};
 
 use casper_types::{TimeDiff, Timestamp};
 
 use super::{
     endorsement::{Endorsement, SignedEndorsement},
     evidence::Evidence,
-    highway::{Ping, ValidVertex, Vertex, WireUnit},
+    highway::{HashedWireUnit, Ping, ValidVertex, Vertex, WireUnit},
     state::{self, Panorama, State, Unit},
     ENABLE_ENDORSEMENTS,
 };
@@ -22,7 +23,7 @@ use super::{
 use crate::components::consensus::{
     consensus_protocol::BlockContext,
     highway_core::{highway::SignedWireUnit, state::Fault},
-    traits::{Context, ValidatorSecret},
+    traits::{Context, SignedMessageId, ValidatorSecret},
     utils::{ValidatorIndex, Weight},
 };
 
@@ -36,12 +37,26 @@ pub(crate) enum Effect<C: Context> {
     /// `propose` needs to be called with a value for a new block with the specified block context
     /// and parent value.
     RequestNewBlock(BlockContext<C>),
+    /// `on_signature` needs to be called with the signature of the message with the specified
+    /// hash, identifier and serialized fields.
+    RequestSignature(C::Hash, SignedMessageId<C::InstanceId>, Vec<u8>),
     /// This validator is faulty.
     ///
     /// When this is returned, the validator automatically deactivates.
     WeAreFaulty(Fault<C>),
 }
 
+/// A vertex created by us, to be signed.
+#[derive(Clone, DataSize, Debug)]
+enum UnsignedVertex<C>
+where
+    C: Context,
+{
+    Unit(HashedWireUnit<C>),
+    Ping(Timestamp, C::InstanceId),
+    Endorsement(Endorsement<C>),
+}
+
 /// A validator that actively participates in consensus by creating new vertices.
 ///
 /// It implements the Highway schedule. The protocol proceeds in rounds, and in each round one
@@ -80,6 +95,8 @@ where
     target_ftt: Weight,
     /// If this flag is set we don't create new units and just send pings instead.
     paused: bool,
+    /// The vertices waiting for the signatures requested via `Effect::RequestSignature`, by hash.
+    pending_signatures: BTreeMap<C::Hash, UnsignedVertex<C>>,
 }
 
 impl<C: Context> Debug for ActiveValidator<C> {
@@ -126,9 +143,10 @@ impl<C: Context> ActiveValidator<C> {
             own_last_unit,
             target_ftt,
             paused: false,
+            pending_signatures: BTreeMap::new(),
         };
         let mut effects = av.schedule_timer(start_time, state);
-        effects.push(av.send_ping(current_time, instance_id));
+        effects.extend(av.send_ping(current_time, instance_id));
         (av, effects)
     }
 
@@ -196,16 +214,14 @@ impl<C: Context> ActiveValidator<C> {
                 return effects;
             } else if timestamp == r_id.saturating_add(self.witness_offset(r_len)) {
                 let panorama = self.panorama_at(state, timestamp);
-                if let Some(witness_unit) =
-                    self.new_unit(panorama, timestamp, None, state, instance_id)
-                {
+                if let Some(effect) = self.new_unit(panorama, timestamp, None, state, instance_id) {
                     if self
                         .latest_unit(state)
                         .map_or(true, |latest_unit| latest_unit.round_id() != r_id)
                     {
                         info!(round_id = %r_id, "sending witness in round with no proposal");
                     }
-                    effects.push(Effect::NewVertex(ValidVertex(Vertex::Unit(witness_unit))));
+                    effects.push(effect);
                     return effects;
                 }
             }
@@ -218,15 +234,18 @@ impl<C: Context> ActiveValidator<C> {
             one_max_round_ago.saturating_add(TimeDiff::from_millis(1)),
         ) {
             warn!(%timestamp, "too many validators offline, sending ping");
-            effects.push(self.send_ping(timestamp, instance_id));
+            effects.extend(self.send_ping(timestamp, instance_id));
         }
         effects
     }
 
-    /// Creates a Ping vertex.
-    pub(crate) fn send_ping(&self, timestamp: Timestamp, instance_id: C::InstanceId) -> Effect<C> {
-        let ping = Ping::new(self.vidx, timestamp, instance_id, &self.secret);
-        Effect::NewVertex(ValidVertex(Vertex::Ping(ping)))
+    /// Creates a Ping vertex, unless the secret key refuses to sign it.
+    pub(crate) fn send_ping(
+        &mut self,
+        timestamp: Timestamp,
+        instance_id: C::InstanceId,
+    ) -> Option<Effect<C>> {
+        self.sign(UnsignedVertex::Ping(timestamp, instance_id))
     }
 
     /// Returns whether enough validators are online to finalize values with the target fault
@@ -261,17 +280,11 @@ impl<C: Context> ActiveValidator<C> {
         if self.should_send_confirmation(uhash, now, state) {
             let panorama = state.confirmation_panorama(self.vidx, uhash);
             if panorama.has_correct() {
-                if let Some(confirmation_unit) =
-                    self.new_unit(panorama, now, None, state, instance_id)
-                {
-                    let vv = ValidVertex(Vertex::Unit(confirmation_unit));
-                    effects.push(Effect::NewVertex(vv));
-                }
+                effects.extend(self.new_unit(panorama, now, None, state, instance_id));
             }
         };
         if self.should_endorse(uhash, state) {
-            let endorsement = self.endorse(uhash);
-            effects.extend(vec![Effect::NewVertex(ValidVertex(endorsement))]);
+            effects.extend(self.endorse(uhash));
         }
         effects
     }
@@ -294,8 +307,7 @@ impl<C: Context> ActiveValidator<C> {
                 let unit = state.unit(v);
                 unit.new_hash_obs(state, vidx)
             })
-            .map(|v| self.endorse(v))
-            .map(|endorsement| Effect::NewVertex(ValidVertex(endorsement)))
+            .filter_map(|v| self.endorse(v))
             .collect()
     }
 
@@ -317,9 +329,7 @@ impl<C: Context> ActiveValidator<C> {
         let maybe_parent_hash = state.fork_choice(&panorama);
         // If the parent is a terminal block, just create a unit without a new block.
         if maybe_parent_hash.map_or(false, |hash| state.is_terminal_block(hash)) {
-            return self
-                .new_unit(panorama, timestamp, None, state, instance_id)
-                .map(|proposal_unit| Effect::NewVertex(ValidVertex(Vertex::Unit(proposal_unit))));
+            return self.new_unit(panorama, timestamp, None, state, instance_id);
         }
         // Otherwise we need to request a new consensus value to propose.
         let ancestor_values = match maybe_parent_hash {
@@ -362,7 +372,6 @@ impl<C: Context> ActiveValidator<C> {
             return vec![];
         }
         self.new_unit(panorama, timestamp, Some(value), state, instance_id)
-            .map(|proposal_unit| Effect::NewVertex(ValidVertex(Vertex::Unit(proposal_unit))))
             .into_iter()
             .collect()
     }
@@ -411,7 +420,8 @@ impl<C: Context> ActiveValidator<C> {
         true
     }
 
-    /// Returns a new unit with the given data, and the correct sequence number.
+    /// Returns the effect adding a new unit with the given data and the correct sequence number,
+    /// or requesting its signature.
     ///
     /// Returns `None` if it's not possible to create a valid unit with the given panorama.
     fn new_unit(
@@ -421,7 +431,7 @@ impl<C: Context> ActiveValidator<C> {
         value: Option<C::ConsensusValue>,
         state: &State<C>,
         instance_id: C::InstanceId,
-    ) -> Option<SignedWireUnit<C>> {
+    ) -> Option<Effect<C>> {
         if value.is_none() && !panorama.has_correct() {
             return None; // Wait for the first proposal before creating a unit without a value.
         }
@@ -429,6 +439,11 @@ impl<C: Context> ActiveValidator<C> {
             info!(?self.own_last_unit, "not voting - last own unit unknown");
             return None;
         }
+        if self.has_pending_unit() {
+            // Our next unit must cite the pending one, so it can't be created yet.
+            info!(%timestamp, "not creating unit - still waiting for signature of the last one");
+            return None;
+        }
         if let Some((prop_context, _)) = self.next_proposal.take() {
             warn!(?prop_context, "canceling proposal due to unit");
         }
@@ -464,14 +479,116 @@ impl<C: Context> ActiveValidator<C> {
             endorsed,
         }
         .into_hashed();
-        let swunit = SignedWireUnit::new(hwunit, &self.secret);
-        write_last_unit(&self.unit_file, swunit.clone()).unwrap_or_else(|err| {
-            panic!(
-                "should successfully write unit's hash to {:?}, got {:?}",
-                self.unit_file, err
-            )
-        });
-        Some(swunit)
+        self.sign(UnsignedVertex::Unit(hwunit))
+    }
+
+    /// Returns the effect adding the signed vertex, or requesting its signature if the secret key
+    /// may block.
+    fn sign(&mut self, vertex: UnsignedVertex<C>) -> Option<Effect<C>> {
+        let (hash, message_id, fields) = match &vertex {
+            UnsignedVertex::Unit(hwunit) => (
+                hwunit.hash(),
+                hwunit.message_id(),
+                hwunit.wire_unit().signed_fields(),
+            ),
+            UnsignedVertex::Ping(timestamp, instance_id) => (
+                Ping::<C>::hash(self.vidx, *timestamp, *instance_id),
+                Ping::<C>::message_id(*timestamp, *instance_id),
+                Ping::<C>::signed_fields(self.vidx, *timestamp, *instance_id),
+            ),
+            UnsignedVertex::Endorsement(endorsement) => (
+                endorsement.hash(),
+                SignedMessageId::HighwayEndorsement,
+                endorsement.signed_fields(),
+            ),
+        };
+        if self.secret.may_block() {
+            self.pending_signatures.insert(hash, vertex);
+            return Some(Effect::RequestSignature(hash, message_id, fields));
+        }
+        let signature = self.secret.sign(&hash, message_id, &fields);
+        self.add_signature(vertex, signature)
+    }
+
+    /// Returns actions a validator needs to take upon receiving the signature requested via
+    /// `Effect::RequestSignature`, or `None` if the secret key refused to sign.
+    pub(crate) fn on_signature(
+        &mut self,
+        hash: &C::Hash,
+        signature: Option<C::Signature>,
+        state: &State<C>,
+    ) -> Vec<Effect<C>> {
+        let vertex = match self.pending_signatures.remove(hash) {
+            Some(vertex) => vertex,
+            None => {
+                debug!(?hash, "received signature for unknown vertex");
+                return vec![];
+            }
+        };
+        if let UnsignedVertex::Unit(hwunit) = &vertex {
+            // No other unit of ours can have been created in the meantime, but if we somehow added
+            // one to the state, this unit would be an equivocation.
+            if hwunit.wire_unit().panorama[self.vidx] != state.panorama()[self.vidx] {
+                error!(?hwunit, "own unit would be equivocation; dropping it");
+                return vec![];
+            }
+        }
+        self.add_signature(vertex, signature).into_iter().collect()
+    }
+
+    /// Returns the effect adding the vertex with the given signature.
+    fn add_signature(
+        &self,
+        vertex: UnsignedVertex<C>,
+        maybe_signature: Option<C::Signature>,
+    ) -> Option<Effect<C>> {
+        let signature = match maybe_signature {
+            Some(signature) => signature,
+            None => {
+                match vertex {
+                    UnsignedVertex::Unit(hwunit) => {
+                        let seq_number = hwunit.wire_unit().seq_number;
+                        error!(%seq_number, "failed to sign unit; canceling unit creation");
+                    }
+                    UnsignedVertex::Ping(timestamp, _) => {
+                        error!(%timestamp, "failed to sign ping");
+                    }
+                    UnsignedVertex::Endorsement(endorsement) => {
+                        error!(?endorsement, "failed to sign endorsement");
+                    }
+                }
+                return None;
+            }
+        };
+        let vertex = match vertex {
+            UnsignedVertex::Unit(hwunit) => {
+                let swunit = SignedWireUnit::with_signature(hwunit, signature);
+                write_last_unit(&self.unit_file, swunit.clone()).unwrap_or_else(|err| {
+                    panic!(
+                        "should successfully write unit's hash to {:?}, got {:?}",
+                        self.unit_file, err
+                    )
+                });
+                Vertex::Unit(swunit)
+            }
+            UnsignedVertex::Ping(timestamp, instance_id) => Vertex::Ping(Ping::with_signature(
+                self.vidx,
+                timestamp,
+                instance_id,
+                signature,
+            )),
+            UnsignedVertex::Endorsement(endorsement) => {
+                Vertex::Endorsements(SignedEndorsement::new(endorsement, signature).into())
+            }
+        };
+        Some(Effect::NewVertex(ValidVertex(vertex)))
+    }
+
+    /// Returns whether we are waiting for the signature of one of our units.
+    fn has_pending_unit(&self) -> bool {
+        self.pending_signatures
+            .values()
+            .any(|vertex| matches!(vertex, UnsignedVertex::Unit(_)))
     }
 
     /// Returns a `ScheduleTimer` effect for the next time we need to be called.
@@ -566,11 +683,11 @@ impl<C: Context> ActiveValidator<C> {
                 .any(|(vidx, _)| state.is_faulty(vidx) && unit.new_hash_obs(state, vidx))
     }
 
-    /// Creates endorsement of the `vhash`.
-    fn endorse(&self, vhash: &C::Hash) -> Vertex<C> {
-        let endorsement = Endorsement::new(*vhash, self.vidx);
-        let signature = self.secret.sign(&endorsement.hash());
-        Vertex::Endorsements(SignedEndorsement::new(endorsement, signature).into())
+    /// Creates endorsement of the `vhash`, unless the secret key refuses to sign it.
+    fn endorse(&mut self, vhash: &C::Hash) -> Option<Effect<C>> {
+        self.sign(UnsignedVertex::Endorsement(Endorsement::new(
+            *vhash, self.vidx,
+        )))
     }
 
     /// Returns a panorama that is valid to use in our own unit at the given timestamp.
@@ -995,7 +1112,8 @@ mod tests {
             TEST_INSTANCE_ID,
         );
 
-        let ping = Vertex::Ping(Ping::new(ALICE, 500.into(), TEST_INSTANCE_ID, &ALICE_SEC));
+        let ping =
+            Vertex::Ping(Ping::new(ALICE, 500.into(), TEST_INSTANCE_ID, &ALICE_SEC).unwrap());
 
         // The ping is suspicious if it is newer than the latest ping (or unit) that has been added
         // to the state.
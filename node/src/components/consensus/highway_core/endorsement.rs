@@ -44,9 +44,12 @@ impl<C: Context> Endorsement<C> {
 
     /// Returns the hash of the endorsement.
     pub fn hash(&self) -> C::Hash {
-        <C as Context>::hash(
-            &bincode::serialize(&(self.unit, self.creator)).expect("serialize endorsement"),
-        )
+        <C as Context>::hash(&self.signed_fields())
+    }
+
+    /// Returns the serialized fields the hash of the endorsement is computed from.
+    pub(crate) fn signed_fields(&self) -> Vec<u8> {
+        bincode::serialize(&(self.unit, self.creator)).expect("serialize endorsement")
     }
 }
 
@@ -5,13 +5,15 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use casper_types::Timestamp;
 
+#[cfg(test)]
+use crate::components::consensus::traits::ValidatorSecret;
 use crate::components::consensus::{
     highway_core::{
         endorsement::SignedEndorsement,
         highway::{PingError, VertexError},
         state::Panorama,
     },
-    traits::{Context, ValidatorSecret},
+    traits::{Context, SignedMessageId},
     utils::{ValidatorIndex, Validators},
 };
 
@@ -318,11 +320,25 @@ where
 }
 
 impl<C: Context> SignedWireUnit<C> {
+    /// Signs the unit, or returns `None` if the secret key refuses to sign it.
+    #[cfg(test)]
     pub(crate) fn new(
         hashed_wire_unit: HashedWireUnit<C>,
         secret_key: &C::ValidatorSecret,
+    ) -> Option<Self> {
+        let signature = secret_key.sign(
+            &hashed_wire_unit.hash,
+            hashed_wire_unit.message_id(),
+            &hashed_wire_unit.wire_unit.signed_fields(),
+        )?;
+        Some(Self::with_signature(hashed_wire_unit, signature))
+    }
+
+    /// Creates a signed unit from the unit and its creator's signature.
+    pub(crate) fn with_signature(
+        hashed_wire_unit: HashedWireUnit<C>,
+        signature: C::Signature,
     ) -> Self {
-        let signature = secret_key.sign(&hashed_wire_unit.hash);
         SignedWireUnit {
             hashed_wire_unit,
             signature,
@@ -380,6 +396,14 @@ where
     pub(crate) fn new_with_hash(wire_unit: WireUnit<C>, hash: C::Hash) -> Self {
         HashedWireUnit { hash, wire_unit }
     }
+
+    /// Returns the identifier of this unit among the messages signed by its creator.
+    pub(crate) fn message_id(&self) -> SignedMessageId<C::InstanceId> {
+        SignedMessageId::HighwayUnit {
+            instance_id: self.wire_unit.instance_id,
+            seq_number: self.wire_unit.seq_number,
+        }
+    }
 }
 
 impl<C: Context> Serialize for HashedWireUnit<C> {
@@ -459,7 +483,12 @@ impl<C: Context> WireUnit<C> {
     /// Returns the unit's hash, which is used as a unit identifier.
     fn compute_hash(&self) -> C::Hash {
         // TODO: Use serialize_into to avoid allocation?
-        <C as Context>::hash(&bincode::serialize(self).expect("serialize WireUnit"))
+        <C as Context>::hash(&self.signed_fields())
+    }
+
+    /// Returns the serialized unit, which its hash is computed from.
+    pub(crate) fn signed_fields(&self) -> Vec<u8> {
+        bincode::serialize(self).expect("serialize WireUnit")
     }
 }
 
@@ -518,14 +547,35 @@ where
 }
 
 impl<C: Context> Ping<C> {
-    /// Creates a new signed ping.
+    /// Creates a new signed ping, or returns `None` if the secret key refuses to sign it.
+    #[cfg(test)]
     pub(crate) fn new(
         creator: ValidatorIndex,
         timestamp: Timestamp,
         instance_id: C::InstanceId,
         sk: &C::ValidatorSecret,
+    ) -> Option<Self> {
+        let hash = Self::hash(creator, timestamp, instance_id);
+        let signature = sk.sign(
+            &hash,
+            Self::message_id(timestamp, instance_id),
+            &Self::signed_fields(creator, timestamp, instance_id),
+        )?;
+        Some(Self::with_signature(
+            creator,
+            timestamp,
+            instance_id,
+            signature,
+        ))
+    }
+
+    /// Creates a ping from its creator's signature.
+    pub(crate) fn with_signature(
+        creator: ValidatorIndex,
+        timestamp: Timestamp,
+        instance_id: C::InstanceId,
+        signature: C::Signature,
     ) -> Self {
-        let signature = sk.sign(&Self::hash(creator, timestamp, instance_id));
         Ping {
             creator,
             timestamp,
@@ -534,6 +584,17 @@ impl<C: Context> Ping<C> {
         }
     }
 
+    /// Returns the identifier of a ping among the messages signed by its creator.
+    pub(crate) fn message_id(
+        timestamp: Timestamp,
+        instance_id: C::InstanceId,
+    ) -> SignedMessageId<C::InstanceId> {
+        SignedMessageId::HighwayPing {
+            instance_id,
+            timestamp,
+        }
+    }
+
     /// The creator who signals that it is online.
     pub fn creator(&self) -> ValidatorIndex {
         self.creator
@@ -568,8 +629,20 @@ impl<C: Context> Ping<C> {
     }
 
     /// Computes the hash of a ping, i.e. of the creator and timestamp.
-    fn hash(creator: ValidatorIndex, timestamp: Timestamp, instance_id: C::InstanceId) -> C::Hash {
-        let bytes = bincode::serialize(&(creator, timestamp, instance_id)).expect("serialize Ping");
-        <C as Context>::hash(&bytes)
+    pub(crate) fn hash(
+        creator: ValidatorIndex,
+        timestamp: Timestamp,
+        instance_id: C::InstanceId,
+    ) -> C::Hash {
+        <C as Context>::hash(&Self::signed_fields(creator, timestamp, instance_id))
+    }
+
+    /// Returns the serialized fields the hash of a ping is computed from.
+    pub(crate) fn signed_fields(
+        creator: ValidatorIndex,
+        timestamp: Timestamp,
+        instance_id: C::InstanceId,
+    ) -> Vec<u8> {
+        bincode::serialize(&(creator, timestamp, instance_id)).expect("serialize Ping")
     }
 }
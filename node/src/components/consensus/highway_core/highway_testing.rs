@@ -34,7 +34,7 @@ use crate::{
             },
             queue::QueueEntry,
         },
-        traits::{ConsensusValueT, Context, ValidatorSecret},
+        traits::{ConsensusValueT, Context, SignedMessageId, ValidatorSecret},
         utils::{Validators, Weight},
         BlockContext,
     },
@@ -122,6 +122,7 @@ impl From<Effect<TestContext>> for HighwayMessage {
             Effect::ScheduleTimer(t) => HighwayMessage::Timer(t),
             Effect::RequestNewBlock(block_context) => HighwayMessage::RequestBlock(block_context),
             Effect::WeAreFaulty(fault) => HighwayMessage::WeAreFaulty(Box::new(fault)),
+            Effect::RequestSignature(..) => unreachable!("test secrets sign immediately"),
         }
     }
 }
@@ -283,7 +284,7 @@ impl HighwayValidator {
                                 }
                                 let secret = TestSecret(wunit2.creator.0.into());
                                 let hwunit2 = wunit2.into_hashed();
-                                let swunit2 = SignedWireUnit::new(hwunit2, &secret);
+                                let swunit2 = SignedWireUnit::new(hwunit2, &secret).unwrap();
                                 let vertex2 = Box::new(Vertex::Unit(swunit2));
                                 vec![msg, HighwayMessage::NewVertex(vertex2)]
                             }
@@ -1035,10 +1036,16 @@ impl Display for HashWrapper {
 
 impl ValidatorSecret for TestSecret {
     type Hash = HashWrapper;
+    type InstanceId = u64;
     type Signature = SignatureWrapper;
 
-    fn sign(&self, data: &Self::Hash) -> Self::Signature {
-        SignatureWrapper(data.0 + self.0)
+    fn sign(
+        &self,
+        data: &Self::Hash,
+        _message_id: SignedMessageId<u64>,
+        _fields: &[u8],
+    ) -> Option<Self::Signature> {
+        Some(SignatureWrapper(data.0 + self.0))
     }
 }
 
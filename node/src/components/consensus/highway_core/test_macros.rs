@@ -74,7 +74,8 @@ macro_rules! add_unit {
         };
         let hwunit = wunit.into_hashed();
         let hash = hwunit.hash();
-        let swunit = SignedWireUnit::new(hwunit, &TestSecret(($creator).0));
+        let swunit = SignedWireUnit::new(hwunit, &TestSecret(($creator).0))
+            .expect("test secret should sign");
         $state.add_unit(swunit).map(|()| hash)
     }};
     ($state: ident, $creator: expr, $time: expr, $round_exp: expr, $val: expr; $($obs:expr),*) => {{
@@ -102,7 +103,8 @@ macro_rules! add_unit {
         };
         let hwunit = wunit.into_hashed();
         let hash = hwunit.hash();
-        let swunit = SignedWireUnit::new(hwunit, &TestSecret(($creator).0));
+        let swunit = SignedWireUnit::new(hwunit, &TestSecret(($creator).0))
+            .expect("test secret should sign");
         $state.add_unit(swunit).map(|()| hash)
     }};
 }
@@ -116,12 +118,19 @@ macro_rules! endorse {
         }
     };
     ($state: ident, $creator: expr, $vote: expr) => {{
-        use crate::components::consensus::highway_core::endorsement::{
-            Endorsement, SignedEndorsement,
+        use crate::components::consensus::{
+            highway_core::endorsement::{Endorsement, SignedEndorsement},
+            traits::SignedMessageId,
         };
 
         let endorsement: Endorsement<TestContext> = Endorsement::new($vote, ($creator));
-        let signature = TestSecret(($creator).0).sign(&endorsement.hash());
+        let signature = TestSecret(($creator).0)
+            .sign(
+                &endorsement.hash(),
+                SignedMessageId::HighwayEndorsement,
+                &endorsement.signed_fields(),
+            )
+            .expect("test secret should sign");
         let endorsements = SignedEndorsement::new(endorsement, signature).into();
         let evidence = $state.find_conflicting_endorsements(&endorsements, &TEST_INSTANCE_ID);
         $state.add_endorsements(endorsements);
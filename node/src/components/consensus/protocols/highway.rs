@@ -254,6 +254,9 @@ impl<C: Context + 'static> HighwayProtocol<C> {
             AvEffect::RequestNewBlock(block_context) => {
                 vec![ProtocolOutcome::CreateNewBlock(block_context)]
             }
+            AvEffect::RequestSignature(hash, message_id, fields) => {
+                vec![ProtocolOutcome::SignMessage(hash, message_id, fields)]
+            }
             AvEffect::WeAreFaulty(fault) => {
                 error!("this validator is faulty: {:?}", fault);
                 vec![ProtocolOutcome::WeAreFaulty]
@@ -1017,6 +1020,16 @@ where
         self.process_av_effects(effects, now)
     }
 
+    fn handle_signature(
+        &mut self,
+        hash: C::Hash,
+        signature: Option<C::Signature>,
+        now: Timestamp,
+    ) -> ProtocolOutcomes<C> {
+        let effects = self.highway.handle_signature(&hash, signature, now);
+        self.process_av_effects(effects, now)
+    }
+
     fn resolve_validity(
         &mut self,
         proposed_block: ProposedBlock<C>,
@@ -22,8 +22,9 @@ use crate::{
         },
         traits::Context,
         utils::{ValidatorIndex, Weight},
-        SerializedMessage,
+        SerializedMessage, SignedMessageId,
     },
+    signer::{LocalSigner, ProtectedSigner, Signable, Signer, SlashingProtection},
     types::BlockPayload,
 };
 
@@ -121,7 +122,7 @@ fn send_a_wire_unit_with_too_small_a_round_exp() {
     };
     let alice_keypair: Keypair = Keypair::from(Arc::clone(&*ALICE_SECRET_KEY));
     let highway_message: HighwayMessage<ClContext> = HighwayMessage::NewVertex(Vertex::Unit(
-        SignedWireUnit::new(wunit.into_hashed(), &alice_keypair),
+        SignedWireUnit::new(wunit.into_hashed(), &alice_keypair).unwrap(),
     ));
     let mut highway_protocol = new_test_highway_protocol(validators, vec![]);
     let sender = *ALICE_NODE_ID;
@@ -151,7 +152,7 @@ fn send_a_valid_wire_unit() {
     };
     let alice_keypair: Keypair = Keypair::from(Arc::clone(&*ALICE_SECRET_KEY));
     let highway_message: HighwayMessage<ClContext> = HighwayMessage::NewVertex(Vertex::Unit(
-        SignedWireUnit::new(wunit.into_hashed(), &alice_keypair),
+        SignedWireUnit::new(wunit.into_hashed(), &alice_keypair).unwrap(),
     ));
 
     let mut highway_protocol = new_test_highway_protocol(validators, vec![]);
@@ -199,7 +200,7 @@ fn detect_doppelganger() {
     };
     let alice_keypair: Keypair = Keypair::from(Arc::clone(&*ALICE_SECRET_KEY));
     let highway_message: HighwayMessage<ClContext> = HighwayMessage::NewVertex(Vertex::Unit(
-        SignedWireUnit::new(wunit.into_hashed(), &alice_keypair),
+        SignedWireUnit::new(wunit.into_hashed(), &alice_keypair).unwrap(),
     ));
     let mut highway_protocol = new_test_highway_protocol(validators, vec![]);
     // Activate ALICE as validator.
@@ -223,6 +224,67 @@ fn detect_doppelganger() {
     panic!("failed to return DoppelgangerDetected effect");
 }
 
+#[test]
+fn sign_ping_with_blocking_signer() {
+    let validators = vec![
+        (ALICE_PUBLIC_KEY.clone(), 100),
+        (BOB_PUBLIC_KEY.clone(), 100),
+    ];
+    let signer: Arc<dyn Signer> = Arc::new(ProtectedSigner::new(
+        Arc::new(LocalSigner::new(Arc::clone(&*ALICE_SECRET_KEY))),
+        SlashingProtection::in_memory(),
+    ));
+    assert!(signer.may_block());
+    let mut highway_protocol = new_test_highway_protocol(validators, vec![]);
+    let now = Timestamp::zero();
+    let outcomes = highway_protocol.activate_validator(
+        ALICE_PUBLIC_KEY.clone(),
+        Keypair::new(Arc::clone(&signer)),
+        now,
+        None,
+    );
+
+    // The ping is not signed by the protocol itself, but requested.
+    let (hash, message_id, fields) = match outcomes.iter().find_map(|outcome| match outcome {
+        ProtocolOutcome::SignMessage(hash, message_id, fields) => {
+            Some((*hash, *message_id, fields.clone()))
+        }
+        _ => None,
+    }) {
+        Some(request) => request,
+        None => panic!("expected a signature request, got {:?}", outcomes),
+    };
+    assert!(matches!(message_id, SignedMessageId::HighwayPing { .. }));
+    assert!(!outcomes
+        .iter()
+        .any(|outcome| matches!(outcome, ProtocolOutcome::CreatedGossipMessage(_))));
+
+    // Once the signature arrives, the ping is gossiped.
+    let signature = signer
+        .sign(&Signable::Consensus { message_id, fields })
+        .unwrap();
+    let outcomes = highway_protocol.handle_signature(hash, Some(signature), now);
+    let ping = outcomes
+        .iter()
+        .find_map(|outcome| match outcome {
+            ProtocolOutcome::CreatedGossipMessage(msg) => {
+                match msg.deserialize_expect::<HighwayMessage<ClContext>>() {
+                    HighwayMessage::NewVertex(Vertex::Ping(ping)) => Some(ping),
+                    _ => None,
+                }
+            }
+            _ => None,
+        })
+        .expect("expected the ping to be gossiped");
+    assert_eq!(ping.creator(), ALICE);
+    assert_eq!(ping.timestamp(), now);
+
+    // A repeated or unknown signature is ignored.
+    assert!(highway_protocol
+        .handle_signature(hash, Some(signature), now)
+        .is_empty());
+}
+
 #[test]
 fn max_rounds_per_era_returns_the_correct_value_for_prod_chainspec_value() {
     let max_rounds_per_era = max_rounds_per_era(
@@ -92,7 +92,7 @@ use crate::{
         },
         era_supervisor::SerializedMessage,
         protocols,
-        traits::{ConsensusValueT, Context},
+        traits::{ConsensusValueT, Context, ValidatorSecret},
         utils::{ValidatorIndex, ValidatorMap, Validators, Weight},
         ActionId, LeaderSequence, TimerId,
     },
@@ -100,14 +100,14 @@ use crate::{
     utils, NodeRng,
 };
 use fault::Fault;
-use message::{Content, SignedMessage, SyncResponse};
+use message::{SignedMessage, SyncResponse};
 use params::Params;
 use participation::{Participation, ParticipationStatus};
 use proposal::{HashedProposal, Proposal};
 use round::Round;
 use wal::{Entry, ReadWal, WriteWal};
 
-pub(crate) use message::{Message, SyncRequest};
+pub(crate) use message::{Content, Message, SyncRequest};
 
 /// The timer for syncing with a random peer.
 const TIMER_ID_SYNC_PEER: TimerId = TimerId(0);
@@ -125,6 +125,7 @@ pub(crate) type RoundId = u32;
 
 type ProposalsAwaitingParent = HashSet<(RoundId, NodeId)>;
 type ProposalsAwaitingValidation<C> = HashSet<(RoundId, HashedProposal<C>, NodeId)>;
+type MessageAwaitingSignature<C> = (RoundId, Content<C>, Option<HashedProposal<C>>);
 
 /// Contains the portion of the state required for an active validator to participate in the
 /// protocol.
@@ -201,6 +202,9 @@ where
     write_wal: Option<WriteWal<C>>,
     /// The rewards based on the finalized rounds so far.
     rewards: BTreeMap<C::ValidatorId, u64>,
+    /// Our messages waiting for the signatures requested via `ProtocolOutcome::SignMessage`, by
+    /// hash, with the proposal to be gossiped together with an echo.
+    pending_signatures: HashMap<C::Hash, MessageAwaitingSignature<C>>,
 }
 
 impl<C: Context + 'static> Zug<C> {
@@ -268,6 +272,7 @@ impl<C: Context + 'static> Zug<C> {
             next_scheduled_update: Timestamp::MAX,
             write_wal: None,
             rewards,
+            pending_signatures: HashMap::new(),
         }
     }
 
@@ -626,67 +631,130 @@ impl<C: Context + 'static> Zug<C> {
         self.leader_sequence.leader(u64::from(round_id))
     }
 
-    fn create_message(
+    /// If we are an active validator and it would be safe for us to sign this message and we
+    /// haven't signed it before, we sign it, add it to our state and gossip it to the network.
+    /// If our secret key may block, we request the signature instead, and add the message in
+    /// `handle_signature`.
+    ///
+    /// If a proposal is given, the message must be our echo of it, and is gossiped together with
+    /// the proposal.
+    ///
+    /// Does not call `update`!
+    fn create_and_gossip_message(
         &mut self,
         round_id: RoundId,
         content: Content<C>,
-    ) -> Option<SignedMessage<C>> {
-        let (validator_idx, secret_key) = if let Some(active_validator) = &self.active_validator {
-            (active_validator.idx, &active_validator.secret)
+        maybe_proposal: Option<HashedProposal<C>>,
+    ) -> ProtocolOutcomes<C> {
+        let (validator_idx, may_block) = if let Some(active_validator) = &self.active_validator {
+            (active_validator.idx, active_validator.secret.may_block())
         } else {
-            return None;
+            return vec![];
         };
         if self.paused {
-            return None;
+            return vec![];
         }
         let already_signed = match &content {
             Content::Echo(_) => self.has_echoed(round_id, validator_idx),
             Content::Vote(_) => self.has_voted(round_id, validator_idx),
         };
-        if already_signed {
-            return None;
+        if already_signed || self.is_signature_pending(round_id, &content) {
+            return vec![];
         }
-        let signed_msg = SignedMessage::sign_new(
+        let (hash, message_id, fields) =
+            SignedMessage::signing_data(round_id, *self.instance_id(), &content, validator_idx);
+        if may_block {
+            self.pending_signatures
+                .insert(hash, (round_id, content, maybe_proposal));
+            return vec![ProtocolOutcome::SignMessage(hash, message_id, fields)];
+        }
+        let maybe_signature = self
+            .active_validator
+            .as_ref()
+            .and_then(|active_validator| active_validator.secret.sign(&hash, message_id, &fields));
+        self.add_own_message(round_id, content, maybe_signature, maybe_proposal)
+    }
+
+    /// Returns whether we are waiting for the signature of our echo or vote in the given round.
+    fn is_signature_pending(&self, round_id: RoundId, content: &Content<C>) -> bool {
+        self.pending_signatures
+            .values()
+            .any(|(pending_round_id, pending_content, _)| {
+                *pending_round_id == round_id
+                    && matches!(
+                        (pending_content, content),
+                        (Content::Echo(_), Content::Echo(_)) | (Content::Vote(_), Content::Vote(_))
+                    )
+            })
+    }
+
+    /// Adds our signed message to the WAL and the protocol state and gossips it, together with the
+    /// proposal if there is one.
+    ///
+    /// Does not call `update`!
+    fn add_own_message(
+        &mut self,
+        round_id: RoundId,
+        content: Content<C>,
+        maybe_signature: Option<C::Signature>,
+        maybe_proposal: Option<HashedProposal<C>>,
+    ) -> ProtocolOutcomes<C> {
+        let validator_idx = if let Some(active_validator) = &self.active_validator {
+            active_validator.idx
+        } else {
+            return vec![];
+        };
+        let signature = if let Some(signature) = maybe_signature {
+            signature
+        } else {
+            error!(our_idx = self.our_idx(), %round_id, "failed to sign message");
+            return vec![];
+        };
+        let signed_msg = SignedMessage::with_signature(
             round_id,
             *self.instance_id(),
             content,
             validator_idx,
-            secret_key,
+            signature,
         );
-        // We only return the new message if we are able to record it. If that fails we
+        // We only gossip the new message if we are able to record it. If that fails we
         // wouldn't know about our own message after a restart and risk double-signing.
-        if self.record_entry(&Entry::SignedMessage(signed_msg.clone()))
-            && self.add_content(signed_msg.clone())
+        if !self.record_entry(&Entry::SignedMessage(signed_msg.clone()))
+            || !self.add_content(signed_msg.clone())
         {
-            Some(signed_msg)
-        } else {
             debug!(
                 our_idx = self.our_idx(),
                 %round_id,
                 ?content,
                 "couldn't record a signed message in the WAL or add it to the protocol state"
             );
-            None
+            return vec![];
         }
-    }
-
-    /// If we are an active validator and it would be safe for us to sign this message and we
-    /// haven't signed it before, we sign it, add it to our state and gossip it to the network.
-    ///
-    /// Does not call `update`!
-    fn create_and_gossip_message(
-        &mut self,
-        round_id: RoundId,
-        content: Content<C>,
-    ) -> ProtocolOutcomes<C> {
-        let maybe_signed_msg = self.create_message(round_id, content);
-        maybe_signed_msg
-            .into_iter()
-            .map(|signed_msg| {
-                let message = Message::Signed(signed_msg);
-                ProtocolOutcome::CreatedGossipMessage(SerializedMessage::from_message(&message))
-            })
-            .collect()
+        let message = if let Some(hashed_prop) = maybe_proposal {
+            if !self.record_entry(&Entry::Proposal(hashed_prop.inner().clone(), round_id)) {
+                error!(
+                    our_idx = self.our_idx(),
+                    "could not record own proposal in WAL"
+                );
+                return vec![];
+            }
+            let proposal = hashed_prop.inner().clone();
+            if !self.round_mut(round_id).insert_proposal(hashed_prop) {
+                return vec![];
+            }
+            self.mark_dirty(round_id);
+            Message::Proposal {
+                round_id,
+                proposal,
+                instance_id: *self.instance_id(),
+                echo: signed_msg,
+            }
+        } else {
+            Message::Signed(signed_msg)
+        };
+        vec![ProtocolOutcome::CreatedGossipMessage(
+            SerializedMessage::from_message(&message),
+        )]
     }
 
     /// When we receive evidence for a fault, we must notify the rest of the network of this
@@ -1427,6 +1495,7 @@ impl<C: Context + 'static> Zug<C> {
                             | ProtocolOutcome::ScheduleTimer(_, _)
                             | ProtocolOutcome::QueueAction(_)
                             | ProtocolOutcome::CreateNewBlock(_)
+                            | ProtocolOutcome::SignMessage(..)
                             | ProtocolOutcome::DoppelgangerDetected
                             | ProtocolOutcome::Disconnect(_) => false,
                         }));
@@ -1570,7 +1639,7 @@ impl<C: Context + 'static> Zug<C> {
 
         // If we have a proposal, echo it.
         if let Some(&hash) = self.rounds[&round_id].proposal().map(HashedProposal::hash) {
-            outcomes.extend(self.create_and_gossip_message(round_id, Content::Echo(hash)));
+            outcomes.extend(self.create_and_gossip_message(round_id, Content::Echo(hash), None));
         }
 
         // Update the round outcome if there is a new accepted proposal.
@@ -1579,7 +1648,7 @@ impl<C: Context + 'static> Zug<C> {
                 self.update_proposal_timeout(now);
             }
             // Vote for finalizing this proposal.
-            outcomes.extend(self.create_and_gossip_message(round_id, Content::Vote(true)));
+            outcomes.extend(self.create_and_gossip_message(round_id, Content::Vote(true), None));
             // Proposed descendants of this proposal can now be validated.
             if let Some(proposals) = self.proposals_waiting_for_parent.remove(&round_id) {
                 let ancestor_values = self
@@ -1604,10 +1673,18 @@ impl<C: Context + 'static> Zug<C> {
                 .current_round_start
                 .saturating_add(self.proposal_timeout());
             if now >= current_timeout {
-                outcomes.extend(self.create_and_gossip_message(round_id, Content::Vote(false)));
+                outcomes.extend(self.create_and_gossip_message(
+                    round_id,
+                    Content::Vote(false),
+                    None,
+                ));
                 self.update_proposal_timeout(now);
             } else if self.faults.contains_key(&self.leader(round_id)) {
-                outcomes.extend(self.create_and_gossip_message(round_id, Content::Vote(false)));
+                outcomes.extend(self.create_and_gossip_message(
+                    round_id,
+                    Content::Vote(false),
+                    None,
+                ));
             }
             if self.is_skippable_round(round_id) || self.has_accepted_proposal(round_id) {
                 self.current_round_start = Timestamp::MAX;
@@ -1897,33 +1974,9 @@ impl<C: Context + 'static> Zug<C> {
     /// inserts them into our protocol state and gossips them.
     fn create_echo_and_proposal(&mut self, proposal: Proposal<C>) -> ProtocolOutcomes<C> {
         let round_id = self.current_round;
-        let hashed_prop = HashedProposal::new(proposal.clone());
+        let hashed_prop = HashedProposal::new(proposal);
         let echo_content = Content::Echo(*hashed_prop.hash());
-        let echo = if let Some(echo) = self.create_message(round_id, echo_content) {
-            echo
-        } else {
-            return vec![];
-        };
-        let prop_msg = Message::Proposal {
-            round_id,
-            proposal,
-            instance_id: *self.instance_id(),
-            echo,
-        };
-        if !self.record_entry(&Entry::Proposal(hashed_prop.inner().clone(), round_id)) {
-            error!(
-                our_idx = self.our_idx(),
-                "could not record own proposal in WAL"
-            );
-            vec![]
-        } else if self.round_mut(round_id).insert_proposal(hashed_prop) {
-            self.mark_dirty(round_id);
-            vec![ProtocolOutcome::CreatedGossipMessage(
-                SerializedMessage::from_message(&prop_msg),
-            )]
-        } else {
-            vec![]
-        }
+        self.create_and_gossip_message(round_id, echo_content, Some(hashed_prop))
     }
 
     /// Returns a parent if a block with that parent could be proposed in the current round, and the
@@ -2254,6 +2307,28 @@ where
         outcomes
     }
 
+    fn handle_signature(
+        &mut self,
+        hash: C::Hash,
+        signature: Option<C::Signature>,
+        now: Timestamp,
+    ) -> ProtocolOutcomes<C> {
+        let (round_id, content, maybe_proposal) = match self.pending_signatures.remove(&hash) {
+            Some(pending) => pending,
+            None => {
+                debug!(
+                    our_idx = self.our_idx(),
+                    ?hash,
+                    "received signature for unknown message"
+                );
+                return vec![];
+            }
+        };
+        let mut outcomes = self.add_own_message(round_id, content, signature, maybe_proposal);
+        outcomes.extend(self.update(now));
+        outcomes
+    }
+
     fn resolve_validity(
         &mut self,
         proposed_block: ProposedBlock<C>,
@@ -2553,6 +2628,7 @@ mod specimen_support {
                 LargestSpecimen::largest_specimen(estimator, cache),
                 &LargestSpecimen::largest_specimen(estimator, cache),
             )
+            .expect("should sign with a local key")
         }
     }
 
@@ -32,7 +32,7 @@ use crate::{
             },
             queue::QueueEntry,
         },
-        traits::{ConsensusValueT, Context, ValidatorSecret},
+        traits::{ConsensusValueT, Context, SignedMessageId, ValidatorSecret},
         utils::{Validators, Weight},
         ActionId, BlockContext, SerializedMessage, TimerId,
     },
@@ -151,6 +151,7 @@ impl From<ProtocolOutcome<TestContext>> for ZugMessage {
             ProtocolOutcome::HandledProposedBlock(proposed_block) => {
                 ZugMessage::HandledProposedBlock(proposed_block)
             }
+            ProtocolOutcome::SignMessage(..) => unreachable!("test secrets sign immediately"),
         }
     }
 }
@@ -313,7 +314,8 @@ impl ZugValidator {
                                     )),
                                     signed_msg.validator_idx,
                                     &TestSecret(signed_msg.validator_idx.0.into()),
-                                );
+                                )
+                                .unwrap();
                                 vec![
                                     ZugMessage::GossipMessage(SerializedMessage::from_message(
                                         &ZugProtocolMessage::Signed(conflicting_message),
@@ -328,7 +330,8 @@ impl ZugValidator {
                                     Content::<TestContext>::Vote(!vote),
                                     signed_msg.validator_idx,
                                     &TestSecret(signed_msg.validator_idx.0.into()),
-                                );
+                                )
+                                .unwrap();
                                 vec![
                                     ZugMessage::GossipMessage(SerializedMessage::from_message(
                                         &ZugProtocolMessage::Signed(conflicting_message),
@@ -1007,10 +1010,16 @@ impl Display for HashWrapper {
 
 impl ValidatorSecret for TestSecret {
     type Hash = HashWrapper;
+    type InstanceId = u64;
     type Signature = SignatureWrapper;
 
-    fn sign(&self, data: &Self::Hash) -> Self::Signature {
-        SignatureWrapper(data.0 + self.0)
+    fn sign(
+        &self,
+        data: &Self::Hash,
+        _message_id: SignedMessageId<u64>,
+        _fields: &[u8],
+    ) -> Option<Self::Signature> {
+        Some(SignatureWrapper(data.0 + self.0))
     }
 }
 
@@ -8,7 +8,7 @@ use either::Either;
 use crate::{
     components::consensus::{
         protocols::zug::{Proposal, RoundId},
-        traits::{ConsensusNetworkMessage, Context, ValidatorSecret},
+        traits::{ConsensusNetworkMessage, Context, SignedMessageId, ValidatorSecret},
         utils::ValidatorIndex,
     },
     utils::ds,
@@ -104,6 +104,24 @@ impl<C: Context> Content<C> {
             _ => false,
         }
     }
+
+    /// Returns the ID of the message with this content in the given round.
+    pub(crate) fn message_id(
+        &self,
+        round_id: RoundId,
+        instance_id: C::InstanceId,
+    ) -> SignedMessageId<C::InstanceId> {
+        match self {
+            Content::Echo(_) => SignedMessageId::ZugEcho {
+                instance_id,
+                round_id,
+            },
+            Content::Vote(_) => SignedMessageId::ZugVote {
+                instance_id,
+                round_id,
+            },
+        }
+    }
 }
 
 // This has to be implemented manually because of the <C> generic parameter, which isn't
@@ -128,24 +146,57 @@ where
 }
 
 impl<C: Context> SignedMessage<C> {
-    /// Creates a new signed message with a valid signature.
+    /// Creates a new signed message with a valid signature, or returns `None` if the secret key
+    /// refuses to sign it.
     pub(crate) fn sign_new(
         round_id: RoundId,
         instance_id: C::InstanceId,
         content: Content<C>,
         validator_idx: ValidatorIndex,
         secret: &C::ValidatorSecret,
+    ) -> Option<SignedMessage<C>> {
+        let (hash, message_id, fields) =
+            Self::signing_data(round_id, instance_id, &content, validator_idx);
+        let signature = secret.sign(&hash, message_id, &fields)?;
+        Some(SignedMessage::with_signature(
+            round_id,
+            instance_id,
+            content,
+            validator_idx,
+            signature,
+        ))
+    }
+
+    /// Creates a new signed message with the given signature, which must be valid.
+    pub(crate) fn with_signature(
+        round_id: RoundId,
+        instance_id: C::InstanceId,
+        content: Content<C>,
+        validator_idx: ValidatorIndex,
+        signature: C::Signature,
     ) -> SignedMessage<C> {
-        let hash = Self::hash_fields(round_id, &instance_id, &content, validator_idx);
         SignedMessage {
             round_id,
             instance_id,
             content,
             validator_idx,
-            signature: secret.sign(&hash),
+            signature,
         }
     }
 
+    /// Returns the hash to be signed, the ID and the serialized fields of the message with the
+    /// given fields.
+    pub(crate) fn signing_data(
+        round_id: RoundId,
+        instance_id: C::InstanceId,
+        content: &Content<C>,
+        validator_idx: ValidatorIndex,
+    ) -> (C::Hash, SignedMessageId<C::InstanceId>, Vec<u8>) {
+        let fields = Self::serialize_fields(round_id, &instance_id, content, validator_idx);
+        let hash = <C as Context>::hash(&fields);
+        (hash, content.message_id(round_id, instance_id), fields)
+    }
+
     /// Creates a new signed message with the alternative content and signature.
     pub(crate) fn with(&self, content: Content<C>, signature: C::Signature) -> SignedMessage<C> {
         SignedMessage {
@@ -173,10 +224,23 @@ impl<C: Context> SignedMessage<C> {
         content: &Content<C>,
         validator_idx: ValidatorIndex,
     ) -> C::Hash {
-        let serialized_fields =
-            bincode::serialize(&(round_id, instance_id, content, validator_idx))
-                .expect("failed to serialize fields");
-        <C as Context>::hash(&serialized_fields)
+        <C as Context>::hash(&Self::serialize_fields(
+            round_id,
+            instance_id,
+            content,
+            validator_idx,
+        ))
+    }
+
+    /// Returns all fields except the signature, serialized.
+    fn serialize_fields(
+        round_id: RoundId,
+        instance_id: &C::InstanceId,
+        content: &Content<C>,
+        validator_idx: ValidatorIndex,
+    ) -> Vec<u8> {
+        bincode::serialize(&(round_id, instance_id, content, validator_idx))
+            .expect("failed to serialize fields")
     }
 }
 
@@ -18,7 +18,9 @@ use crate::{
             BOB_SECRET_KEY, CAROL_PUBLIC_KEY, CAROL_SECRET_KEY,
         },
         traits::Context,
+        SignedMessageId,
     },
+    signer::{LocalSigner, ProtectedSigner, Signable, Signer, SlashingProtection},
     testing,
     types::BlockPayload,
 };
@@ -77,7 +79,7 @@ fn create_signed_message(
 ) -> SignedMessage<ClContext> {
     let validator_idx = validators.get_index(keypair.public_key()).unwrap();
     let instance_id = ClContext::hash(INSTANCE_ID_DATA);
-    SignedMessage::sign_new(round_id, instance_id, content, validator_idx, keypair)
+    SignedMessage::sign_new(round_id, instance_id, content, validator_idx, keypair).unwrap()
 }
 
 /// Creates a `Message::Signed`.
@@ -893,6 +895,85 @@ fn zug_handles_sync_request() {
     assert_eq!(zug.active, zug2.active);
 }
 
+/// Tests that with a signer that may block, our messages are only added and gossiped once the
+/// requested signature arrives, and the same message is not requested twice in the meantime.
+#[test]
+fn zug_signs_with_blocking_signer() {
+    let mut rng = crate::new_rng();
+    let (weights, validators) = abc_weights(60, 30, 10);
+    let alice_idx = validators.get_index(&*ALICE_PUBLIC_KEY).unwrap();
+    let bob_idx = validators.get_index(&*BOB_PUBLIC_KEY).unwrap();
+    let carol_idx = validators.get_index(&*CAROL_PUBLIC_KEY).unwrap();
+    let sender = *ALICE_NODE_ID;
+    let timestamp = Timestamp::from(100000);
+
+    let mut sc_c = new_test_zug(weights, vec![], &[bob_idx, alice_idx, alice_idx]);
+    let dir = tempdir().unwrap();
+    sc_c.open_wal(dir.path().join("wal"), timestamp);
+
+    let bob_kp = Keypair::from(BOB_SECRET_KEY.clone());
+    let signer: Arc<dyn Signer> = Arc::new(ProtectedSigner::new(
+        Arc::new(LocalSigner::new(CAROL_SECRET_KEY.clone())),
+        SlashingProtection::in_memory(),
+    ));
+    sc_c.activate_validator(
+        CAROL_PUBLIC_KEY.clone(),
+        Keypair::new(Arc::clone(&signer)),
+        timestamp,
+        None,
+    );
+
+    let proposal0 = Proposal::<ClContext> {
+        timestamp,
+        maybe_block: Some(new_payload(false)),
+        maybe_parent_round_id: None,
+        inactive: None,
+    };
+    let hash0 = proposal0.hash();
+
+    // Carol receives Bob's proposal and requests the signature of her echo.
+    let msg = create_proposal_message(0, &proposal0, &validators, &bob_kp);
+    let mut outcomes = sc_c.handle_message(&mut rng, sender, msg, timestamp);
+    let gossip = remove_gossip(&validators, &mut outcomes);
+    assert!(gossip.is_empty(), "unexpected gossip: {:?}", gossip);
+    let sign_requests: Vec<_> = outcomes
+        .iter()
+        .filter_map(|outcome| match outcome {
+            ProtocolOutcome::SignMessage(hash, message_id, fields) => {
+                Some((*hash, *message_id, fields.clone()))
+            }
+            _ => None,
+        })
+        .collect();
+    let (hash, message_id, fields) = match &*sign_requests {
+        [request] => request.clone(),
+        _ => panic!("expected one signature request, got {:?}", outcomes),
+    };
+    let expected_message_id = SignedMessageId::ZugEcho {
+        instance_id: ClContext::hash(INSTANCE_ID_DATA),
+        round_id: 0,
+    };
+    assert_eq!(message_id, expected_message_id);
+    assert!(!sc_c.has_echoed(0, carol_idx));
+
+    // While the signature is pending, the echo is not requested again.
+    let outcomes = sc_c.handle_timer(timestamp, timestamp, TIMER_ID_UPDATE, &mut rng);
+    assert!(!outcomes.iter().any(|outcome| matches!(
+        outcome,
+        ProtocolOutcome::SignMessage(_, message_id, _) if *message_id == expected_message_id
+    )));
+
+    // Once the signature arrives, the echo is added and gossiped.
+    let signature = signer
+        .sign(&Signable::Consensus { message_id, fields })
+        .unwrap();
+    let mut outcomes = sc_c.handle_signature(hash, Some(signature), timestamp);
+    let mut gossip = remove_gossip(&validators, &mut outcomes);
+    assert!(remove_signed(&mut gossip, 0, carol_idx, echo(hash0)));
+    assert!(gossip.is_empty(), "unexpected gossip: {:?}", gossip);
+    assert!(sc_c.has_echoed(0, carol_idx));
+}
+
 #[test]
 fn test_validator_bit_field() {
     fn test_roundtrip(zug: &Zug<ClContext>, first: u32, indexes: Vec<u32>, expected: Vec<u32>) {
@@ -4,7 +4,9 @@ use std::{
 };
 
 use datasize::DataSize;
-use serde::{de::DeserializeOwned, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use casper_types::Timestamp;
 
 /// A validator identifier.
 pub trait ValidatorIdT: Eq + Ord + Clone + Debug + Hash + Send + DataSize + Display {}
@@ -39,13 +41,58 @@ impl<H> HashT for H where
 {
 }
 
+/// Identifies a message signed by a validator by its kind and its position among the validator's
+/// messages of that kind in a protocol instance.
+///
+/// Signing two different messages at the same position, or a message at an earlier position than
+/// one already signed, may amount to equivocation, so a signer can use this to refuse such
+/// requests.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SignedMessageId<I> {
+    /// A Highway unit with the given sequence number.
+    HighwayUnit { instance_id: I, seq_number: u64 },
+    /// A Highway ping with the given timestamp.
+    HighwayPing {
+        instance_id: I,
+        timestamp: Timestamp,
+    },
+    /// A Highway endorsement.  Endorsements can't conflict with each other.
+    HighwayEndorsement,
+    /// A Zug echo in the given round.
+    ZugEcho { instance_id: I, round_id: u32 },
+    /// A Zug vote in the given round.
+    ZugVote { instance_id: I, round_id: u32 },
+}
+
 /// A validator's secret signing key.
 pub trait ValidatorSecret: Send + DataSize {
     type Hash: DataSize;
 
+    type InstanceId;
+
     type Signature: Eq + PartialEq + Clone + Debug + Hash + Serialize + DeserializeOwned + DataSize;
 
-    fn sign(&self, hash: &Self::Hash) -> Self::Signature;
+    /// Signs the `hash` of the message identified by `message_id`.
+    ///
+    /// `fields` are the serialized fields the hash was computed from, so that an external signer
+    /// can derive the message's identifier from them instead of trusting `message_id`.
+    ///
+    /// Returns `None` if the message could not be signed, e.g. because an external signer refused
+    /// to sign a message conflicting with one it signed before.
+    fn sign(
+        &self,
+        hash: &Self::Hash,
+        message_id: SignedMessageId<Self::InstanceId>,
+        fields: &[u8],
+    ) -> Option<Self::Signature>;
+
+    /// Returns whether `sign` may block the calling thread, e.g. waiting for an external signer.
+    ///
+    /// If so, the protocols don't call `sign`, but request signatures with
+    /// `ProtocolOutcome::SignMessage` and receive them via `ConsensusProtocol::handle_signature`.
+    fn may_block(&self) -> bool {
+        false
+    }
 }
 
 /// The collection of types the user can choose for cryptography, IDs, transactions, etc.
@@ -57,7 +104,11 @@ pub trait Context: Clone + DataSize + Debug + Eq + Ord + Hash + Send {
     /// Unique identifiers for validators.
     type ValidatorId: ValidatorIdT;
     /// A validator's secret signing key.
-    type ValidatorSecret: ValidatorSecret<Hash = Self::Hash, Signature = Self::Signature>;
+    type ValidatorSecret: ValidatorSecret<
+        Hash = Self::Hash,
+        InstanceId = Self::InstanceId,
+        Signature = Self::Signature,
+    >;
     /// A signature type.
     type Signature: Copy
         + Clone
@@ -4,6 +4,7 @@ mod config;
 mod error;
 mod metrics;
 mod operations;
+mod state_pruner;
 #[cfg(test)]
 mod tests;
 mod types;
@@ -67,9 +68,11 @@ pub(crate) use config::Config;
 pub(crate) use error::{BlockExecutionError, ConfigError};
 use metrics::Metrics;
 pub use operations::execute_finalized_block;
-use operations::execute_only;
+use operations::{execute_only, execute_only_many};
+use state_pruner::{PruningStep, StatePruner};
 pub(crate) use types::{
-    BlockAndExecutionResults, EraValidatorsRequest, StepEffectAndUpcomingEraValidators,
+    BlockAndExecutionResults, EraValidatorsRequest, SpeculativeExecutionResult,
+    SpeculativeExecutionResults, StepEffectAndUpcomingEraValidators,
 };
 
 const COMPONENT_NAME: &str = "contract_runtime";
@@ -212,6 +215,10 @@ pub(crate) struct ContractRuntime {
     system_contract_registry: Option<SystemContractRegistry>,
     activation_point: ActivationPoint,
     prune_batch_size: u64,
+    /// The maximum gas a block may consume, which also caps the gas available when estimating.
+    block_gas_limit: u64,
+    /// Prunes historical global state, if a retention window is configured.
+    state_pruner: Option<Arc<Mutex<StatePruner>>>,
 }
 
 impl Debug for ContractRuntime {
@@ -436,6 +443,14 @@ impl ContractRuntime {
                 responder,
             } => {
                 trace!(?trie_bytes, "put_trie request");
+                if let Some(state_pruner) = &self.state_pruner {
+                    state_pruner
+                        .lock()
+                        .expect(
+                            "components::contract_runtime: couldn't record trie; mutex poisoned",
+                        )
+                        .trie_synced();
+                }
                 let engine_state = Arc::clone(&self.engine_state);
                 let metrics = Arc::clone(&self.metrics);
                 async move {
@@ -494,6 +509,7 @@ impl ContractRuntime {
                         let shared_pre_state = Arc::clone(&self.execution_pre_state);
                         let activation_point = self.activation_point;
                         let prune_batch_size = self.prune_batch_size;
+                        let state_pruner = self.state_pruner.clone();
                         effects.extend(
                             Self::execute_finalized_block_or_requeue(
                                 engine_state,
@@ -508,6 +524,7 @@ impl ContractRuntime {
                                 activation_point,
                                 key_block_height_for_activation_point,
                                 prune_batch_size,
+                                state_pruner,
                                 meta_block_state,
                             )
                             .ignore(),
@@ -551,6 +568,57 @@ impl ContractRuntime {
                 }
                 .ignore()
             }
+            ContractRuntimeRequest::GetKeys {
+                get_keys_request,
+                responder,
+            } => {
+                trace!(?get_keys_request, "get keys request");
+                let engine_state = Arc::clone(&self.engine_state);
+                async move {
+                    let result = run_intensive_task(move || {
+                        engine_state.get_keys(CorrelationId::new(), get_keys_request)
+                    })
+                    .await;
+                    responder.respond(result).await
+                }
+                .ignore()
+            }
+            ContractRuntimeRequest::GetDictionaryItems {
+                get_dictionary_items_request,
+                responder,
+            } => {
+                trace!(
+                    ?get_dictionary_items_request,
+                    "get dictionary items request"
+                );
+                let engine_state = Arc::clone(&self.engine_state);
+                async move {
+                    let result = run_intensive_task(move || {
+                        engine_state.get_dictionary_items(
+                            CorrelationId::new(),
+                            get_dictionary_items_request,
+                        )
+                    })
+                    .await;
+                    responder.respond(result).await
+                }
+                .ignore()
+            }
+            ContractRuntimeRequest::GetStateDiff {
+                state_diff_request,
+                responder,
+            } => {
+                trace!(?state_diff_request, "get state diff request");
+                let engine_state = Arc::clone(&self.engine_state);
+                async move {
+                    let result = run_intensive_task(move || {
+                        engine_state.get_state_diff(CorrelationId::new(), state_diff_request)
+                    })
+                    .await;
+                    responder.respond(result).await
+                }
+                .ignore()
+            }
             ContractRuntimeRequest::GetExecutionResultsChecksum {
                 state_root_hash,
                 responder,
@@ -569,15 +637,43 @@ impl ContractRuntime {
             ContractRuntimeRequest::SpeculativeDeployExecution {
                 execution_prestate,
                 deploy,
+                trace,
+                estimate_gas,
                 responder,
             } => {
                 let engine_state = Arc::clone(&self.engine_state);
+                let block_gas_limit = self.block_gas_limit;
                 async move {
                     let result = run_intensive_task(move || {
                         execute_only(
                             engine_state.as_ref(),
                             execution_prestate,
                             DeployItem::from((*deploy).clone()),
+                            trace,
+                            estimate_gas,
+                            block_gas_limit,
+                        )
+                    })
+                    .await;
+                    responder.respond(result).await
+                }
+                .ignore()
+            }
+            ContractRuntimeRequest::SpeculativeDeploysExecution {
+                execution_prestate,
+                deploys,
+                responder,
+            } => {
+                let engine_state = Arc::clone(&self.engine_state);
+                async move {
+                    let result = run_intensive_task(move || {
+                        execute_only_many(
+                            engine_state.as_ref(),
+                            execution_prestate,
+                            deploys
+                                .iter()
+                                .map(|deploy| DeployItem::from((**deploy).clone()))
+                                .collect(),
                         )
                     })
                     .await;
@@ -602,6 +698,7 @@ impl ContractRuntime {
         minimum_delegation_amount: u64,
         activation_point: ActivationPoint,
         prune_batch_size: u64,
+        block_gas_limit: u64,
         strict_argument_checking: bool,
         vesting_schedule_period_millis: u64,
         max_delegators_per_validator: Option<u32>,
@@ -634,6 +731,7 @@ impl ContractRuntime {
         )?);
 
         let global_state = LmdbGlobalState::empty(environment, trie_store)?;
+        let is_pruning = global_state.is_pruning()?;
         let engine_config = EngineConfigBuilder::new()
             .with_max_query_depth(contract_runtime_config.max_query_depth_or_default())
             .with_max_associated_keys(max_associated_keys)
@@ -655,6 +753,19 @@ impl ContractRuntime {
 
         let metrics = Arc::new(Metrics::new(registry)?);
 
+        let state_pruner = contract_runtime_config
+            .state_retention_blocks
+            .map(|retention_blocks| {
+                let mut state_pruner = StatePruner::new(
+                    retention_blocks,
+                    contract_runtime_config.state_pruning_batch_size_or_default(),
+                );
+                if is_pruning {
+                    state_pruner.resume_sweep();
+                }
+                Arc::new(Mutex::new(state_pruner))
+            });
+
         Ok(ContractRuntime {
             state: ComponentState::Initialized,
             execution_pre_state,
@@ -665,6 +776,8 @@ impl ContractRuntime {
             system_contract_registry: None,
             activation_point,
             prune_batch_size,
+            block_gas_limit,
+            state_pruner,
         })
     }
 
@@ -742,6 +855,17 @@ impl ContractRuntime {
         debug!(next_block_height, "ContractRuntime: set initial state");
     }
 
+    /// Records the state root hash of a block whose global state is held by this node, e.g. a
+    /// historical block acquired by the block synchronizer, so that its state is not pruned.
+    pub(crate) fn register_block_state(&self, block_height: u64, state_root_hash: Digest) {
+        if let Some(state_pruner) = &self.state_pruner {
+            state_pruner
+                .lock()
+                .expect("components::contract_runtime: couldn't record state root; mutex poisoned")
+                .block_synced(block_height, state_root_hash);
+        }
+    }
+
     #[allow(clippy::too_many_arguments)]
     async fn execute_finalized_block_or_requeue<REv>(
         engine_state: Arc<EngineState<LmdbGlobalState>>,
@@ -756,6 +880,7 @@ impl ContractRuntime {
         activation_point: ActivationPoint,
         key_block_height_for_activation_point: u64,
         prune_batch_size: u64,
+        state_pruner: Option<Arc<Mutex<StatePruner>>>,
         mut meta_block_state: MetaBlockState,
     ) where
         REv: From<ContractRuntimeRequest>
@@ -767,6 +892,7 @@ impl ContractRuntime {
     {
         debug!("ContractRuntime: execute_finalized_block_or_requeue");
         let contract_runtime_metrics = metrics.clone();
+        let pruner_engine_state = Arc::clone(&engine_state);
         let BlockAndExecutionResults {
             block,
             approvals_hashes,
@@ -889,6 +1015,17 @@ impl ContractRuntime {
             );
         }
 
+        if let Some(state_pruner) = state_pruner {
+            Self::prune_global_state(
+                pruner_engine_state,
+                state_pruner,
+                effect_builder,
+                block.height(),
+                *block.state_root_hash(),
+            )
+            .await;
+        }
+
         let meta_block = MetaBlock::new(block, execution_results, meta_block_state);
         effect_builder.announce_meta_block(meta_block).await;
 
@@ -909,6 +1046,64 @@ impl ContractRuntime {
         }
     }
 
+    /// Records the state root hash of a newly-executed block with the state pruner and, if this
+    /// starts a new pruning, drives it to completion in the background.
+    async fn prune_global_state<REv>(
+        engine_state: Arc<EngineState<LmdbGlobalState>>,
+        state_pruner: Arc<Mutex<StatePruner>>,
+        effect_builder: EffectBuilder<REv>,
+        block_height: u64,
+        state_root_hash: Digest,
+    ) where
+        REv: From<StorageRequest> + Send,
+    {
+        let pruner = Arc::clone(&state_pruner);
+        let state = Arc::clone(&engine_state);
+        let started = run_intensive_task(move || {
+            pruner
+                .lock()
+                .expect("components::contract_runtime: couldn't record state root; mutex poisoned")
+                .block_executed(state.get_state(), block_height, state_root_hash)
+        })
+        .await;
+        match started {
+            Ok(true) => (),
+            Ok(false) => return,
+            Err(error) => {
+                error!(%error, "failed to start pruning global state");
+                return;
+            }
+        }
+
+        // Each step is short-lived, so block execution can interleave with pruning.
+        tokio::spawn(async move {
+            loop {
+                let pruner = Arc::clone(&state_pruner);
+                let state = Arc::clone(&engine_state);
+                let step = run_intensive_task(move || {
+                    pruner
+                        .lock()
+                        .expect(
+                            "components::contract_runtime: couldn't prune state; mutex poisoned",
+                        )
+                        .step(state.get_state())
+                })
+                .await;
+                match step {
+                    Ok(PruningStep::InProgress) => (),
+                    Ok(PruningStep::PrunedBelow(height)) => {
+                        effect_builder.put_state_pruned_below_height(height).await
+                    }
+                    Ok(PruningStep::Idle) => break,
+                    Err(error) => {
+                        error!(%error, "failed to prune global state");
+                        break;
+                    }
+                }
+            }
+        });
+    }
+
     /// Reads the trie (or chunk of a trie) under the given key and index.
     pub(crate) fn get_trie(
         &self,
@@ -997,7 +1192,10 @@ mod trie_chunking_tests {
     use crate::{
         components::fetcher::FetchResponse,
         contract_runtime::{Config as ContractRuntimeConfig, ContractRuntime},
-        types::{ActivationPoint, ChunkingError, TrieOrChunk, TrieOrChunkId, ValueOrChunk},
+        types::{
+            chainspec::DeployConfig, ActivationPoint, ChunkingError, TrieOrChunk, TrieOrChunkId,
+            ValueOrChunk,
+        },
     };
 
     use super::ContractRuntimeError;
@@ -1062,6 +1260,7 @@ mod trie_chunking_tests {
             10,
             ActivationPoint::EraId(EraId::from(2)),
             5,
+            DeployConfig::default().block_gas_limit,
             true,
             1,
             None,
@@ -3,10 +3,14 @@ use serde::{Deserialize, Serialize};
 
 use casper_execution_engine::shared::utils;
 
+use super::ConfigError;
+use crate::types::{Chainspec, SyncHandling};
+
 const DEFAULT_MAX_GLOBAL_STATE_SIZE: usize = 805_306_368_000; // 750 GiB
 const DEFAULT_MAX_READERS: u32 = 512;
 const DEFAULT_MAX_QUERY_DEPTH: u64 = 5;
 const DEFAULT_MANUAL_SYNC_ENABLED: bool = true;
+const DEFAULT_STATE_PRUNING_BATCH_SIZE: usize = 10_000;
 
 /// Contract runtime configuration.
 #[derive(Clone, Copy, DataSize, Debug, Deserialize, Serialize)]
@@ -31,6 +35,16 @@ pub struct Config {
     ///
     /// Defaults to `true`.
     pub enable_manual_sync: Option<bool>,
+    /// The number of most recent blocks whose global state is retained.
+    ///
+    /// If set, global state which is not reachable from the state root hash of any of the given
+    /// number of most recent blocks is periodically deleted in the background.  Defaults to
+    /// `None`, meaning the global state of all blocks is retained.
+    pub state_retention_blocks: Option<u64>,
+    /// The maximum number of global state trie nodes visited in a single step of pruning.
+    ///
+    /// Defaults to 10,000.
+    pub state_pruning_batch_size: Option<usize>,
 }
 
 impl Config {
@@ -58,6 +72,48 @@ impl Config {
         self.enable_manual_sync
             .unwrap_or(DEFAULT_MANUAL_SYNC_ENABLED)
     }
+
+    /// State pruning batch size.
+    pub fn state_pruning_batch_size_or_default(&self) -> usize {
+        self.state_pruning_batch_size
+            .unwrap_or(DEFAULT_STATE_PRUNING_BATCH_SIZE)
+    }
+
+    /// Checks that the configured state retention keeps the global state of every historical
+    /// block the node is configured to sync.
+    ///
+    /// Syncing to TTL acquires blocks back to the max TTL before the start of the current era, so
+    /// at most that many blocks at the minimum block time, plus one era's worth, must be retained.
+    pub(crate) fn validate_state_retention(
+        &self,
+        sync_handling: &SyncHandling,
+        chainspec: &Chainspec,
+    ) -> Result<(), ConfigError> {
+        let retention_blocks = match self.state_retention_blocks {
+            Some(retention_blocks) => retention_blocks,
+            None => return Ok(()),
+        };
+        match sync_handling {
+            SyncHandling::Genesis => Err(ConfigError::StateRetentionWithGenesisSync),
+            SyncHandling::Ttl => {
+                let core_config = &chainspec.core_config;
+                let block_time = core_config.minimum_block_time.millis().max(1);
+                let ttl_blocks = chainspec.deploy_config.max_ttl.millis() / block_time + 1;
+                let era_blocks = core_config
+                    .minimum_era_height
+                    .max(core_config.era_duration.millis() / block_time + 1);
+                let required_blocks = ttl_blocks.saturating_add(era_blocks);
+                if retention_blocks < required_blocks {
+                    return Err(ConfigError::StateRetentionBelowTtl {
+                        retention_blocks,
+                        required_blocks,
+                    });
+                }
+                Ok(())
+            }
+            SyncHandling::NoSync => Ok(()),
+        }
+    }
 }
 
 impl Default for Config {
@@ -67,6 +123,8 @@ impl Default for Config {
             max_readers: Some(DEFAULT_MAX_READERS),
             max_query_depth: Some(DEFAULT_MAX_QUERY_DEPTH),
             enable_manual_sync: Some(DEFAULT_MANUAL_SYNC_ENABLED),
+            state_retention_blocks: None,
+            state_pruning_batch_size: Some(DEFAULT_STATE_PRUNING_BATCH_SIZE),
         }
     }
 }
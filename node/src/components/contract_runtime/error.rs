@@ -23,6 +23,20 @@ pub(crate) enum ConfigError {
     /// Error initializing metrics.
     #[error("failed to initialize metrics for contract runtime: {0}")]
     Prometheus(#[from] prometheus::Error),
+    /// Global state is pruned, but the node is configured to sync historical blocks to genesis.
+    #[error("state_retention_blocks cannot be set when syncing historical blocks to genesis")]
+    StateRetentionWithGenesisSync,
+    /// Global state is pruned below the historical blocks the node is configured to sync.
+    #[error(
+        "state_retention_blocks of {retention_blocks} is too low to retain the global state of \
+         blocks synced to TTL, which requires at least {required_blocks}"
+    )]
+    StateRetentionBelowTtl {
+        /// The configured number of retained blocks.
+        retention_blocks: u64,
+        /// The minimum number of retained blocks required.
+        required_blocks: u64,
+    },
 }
 
 /// An error during block execution.
@@ -12,6 +12,7 @@ use casper_execution_engine::{
             StepSuccess,
         },
         execution,
+        runtime::call_trace::CallTrace,
     },
     shared::{additive_map::AdditiveMap, newtypes::CorrelationId, transform::Transform},
     storage::global_state::{lmdb::LmdbGlobalState, CommitProvider, StateProvider},
@@ -26,8 +27,9 @@ use crate::{
         consensus::EraReport,
         contract_runtime::{
             error::BlockExecutionError, types::StepEffectAndUpcomingEraValidators,
-            BlockAndExecutionResults, ExecutionPreState, Metrics, SpeculativeExecutionState,
-            APPROVALS_CHECKSUM_NAME, EXECUTION_RESULTS_CHECKSUM_NAME,
+            BlockAndExecutionResults, ExecutionPreState, Metrics, SpeculativeExecutionResult,
+            SpeculativeExecutionResults, SpeculativeExecutionState, APPROVALS_CHECKSUM_NAME,
+            EXECUTION_RESULTS_CHECKSUM_NAME,
         },
         fetcher::FetchItem,
     },
@@ -407,12 +409,17 @@ where
 /// Execute the transaction without commiting the effects.
 /// Intended to be used for discovery operations on read-only nodes.
 ///
-/// Returns effects of the execution.
+/// Returns effects of the execution, along with the call frames entered during execution if
+/// `trace` is set, and the gas consumed when executed with a payment covering up to
+/// `block_gas_limit` if `estimate_gas` is set.
 pub fn execute_only<S>(
     engine_state: &EngineState<S>,
     execution_state: SpeculativeExecutionState,
     deploy: DeployItem,
-) -> Result<Option<ExecutionResult>, engine_state::Error>
+    trace: bool,
+    estimate_gas: bool,
+    block_gas_limit: u64,
+) -> Result<Option<SpeculativeExecutionResult>, engine_state::Error>
 where
     S: StateProvider + CommitProvider,
     S::Error: Into<execution::Error>,
@@ -423,30 +430,131 @@ where
         protocol_version,
     } = execution_state;
     let deploy_hash = deploy.deploy_hash;
-    let execute_request = ExecuteRequest::new(
+    let new_execute_request = |deploy| {
+        ExecuteRequest::new(
+            state_root_hash,
+            block_time.millis(),
+            vec![deploy],
+            protocol_version,
+            PublicKey::System,
+        )
+    };
+    let gas_estimate = if estimate_gas {
+        let mut estimates = engine_state.estimate_gas(
+            CorrelationId::new(),
+            new_execute_request(deploy.clone()),
+            block_gas_limit,
+        )?;
+        match (estimates.pop(), estimates.is_empty()) {
+            (Some(estimate), true) => Some(estimate),
+            _ => {
+                warn!(
+                    ?deploy_hash,
+                    "got more or less than one gas estimate from a single transaction"
+                );
+                return Ok(None);
+            }
+        }
+    } else {
+        None
+    };
+    let execute_request = new_execute_request(deploy);
+    let mut results: Vec<(EngineExecutionResult, Option<CallTrace>)> = if trace {
+        engine_state
+            .run_execute_with_call_traces(CorrelationId::new(), execute_request)?
+            .into_iter()
+            .map(|(execution_result, call_trace)| (execution_result, Some(call_trace)))
+            .collect()
+    } else {
+        execute(engine_state, None, execute_request)?
+            .into_iter()
+            .map(|execution_result| (execution_result, None))
+            .collect()
+    };
+    let len = results.len();
+    if len != 1 {
+        warn!(
+            ?deploy_hash,
+            "got more ({}) execution results from a single transaction", len
+        );
+        return Ok(None);
+    }
+    // We need to transform the `engine_state::ExecutionResult` into
+    // `casper_types::ExecutionResult` as well.
+    Ok(results.pop().map(
+        |(execution_result, call_trace)| SpeculativeExecutionResult {
+            execution_result: execution_result.into(),
+            call_trace,
+            gas_estimate,
+        },
+    ))
+}
+
+/// Executes the deploys in order without committing their effects, with each deploy executed
+/// against the speculative state resulting from those before it.
+/// Intended to be used for simulating multi-step workflows.
+///
+/// Returns the result of each deploy and the state root hash which would result from committing
+/// all of their effects.
+pub fn execute_only_many(
+    engine_state: &EngineState<LmdbGlobalState>,
+    execution_state: SpeculativeExecutionState,
+    deploys: Vec<DeployItem>,
+) -> Result<Option<SpeculativeExecutionResults>, engine_state::Error> {
+    let SpeculativeExecutionState {
         state_root_hash,
-        block_time.millis(),
-        vec![deploy],
+        block_time,
         protocol_version,
-        PublicKey::System,
-    );
-    let results = execute(engine_state, None, execute_request);
-    results.map(|mut execution_results| {
-        let len = execution_results.len();
-        if len != 1 {
-            warn!(
-                ?deploy_hash,
-                "got more ({}) execution results from a single transaction", len
-            );
-            None
-        } else {
-            // We know it must be 1, we could unwrap and then wrap
-            // with `Some(_)` but `pop_front` already returns an `Option`.
-            // We need to transform the `engine_state::ExecutionResult` into
-            // `casper_types::ExecutionResult` as well.
-            execution_results.pop_front().map(Into::into)
+    } = execution_state;
+    // Create a new EngineState that reads from LMDB but only caches changes in memory.
+    let scratch_state = engine_state.get_scratch_engine_state();
+    let mut execution_results = Vec::with_capacity(deploys.len());
+    for deploy in deploys {
+        let deploy_hash = deploy.deploy_hash;
+        let execute_request = ExecuteRequest::new(
+            state_root_hash,
+            block_time.millis(),
+            vec![deploy],
+            protocol_version,
+            PublicKey::System,
+        );
+        let mut results = execute(&scratch_state, None, execute_request)?;
+        let ee_execution_result = match (results.pop_front(), results.is_empty()) {
+            (Some(ee_execution_result), true) => ee_execution_result,
+            _ => {
+                warn!(
+                    ?deploy_hash,
+                    "got more or less than one execution result from a single transaction"
+                );
+                return Ok(None);
+            }
+        };
+        execution_results.push((
+            deploy_hash.into(),
+            ExecutionResult::from(&ee_execution_result),
+        ));
+
+        // As in block execution, the effects of a failed deploy (e.g. payment) are still
+        // committed, so that later deploys observe them.
+        let execution_effect: AdditiveMap<Key, Transform> = match ee_execution_result {
+            EngineExecutionResult::Success {
+                execution_journal, ..
+            }
+            | EngineExecutionResult::Failure {
+                execution_journal, ..
+            } => execution_journal,
         }
-    })
+        .into();
+        // The scratch state ignores the state root hash, returning it unchanged.
+        commit_transforms(&scratch_state, None, state_root_hash, execution_effect)?;
+    }
+
+    let post_state_hash =
+        engine_state.scratch_state_root_hash(state_root_hash, scratch_state.into_inner())?;
+    Ok(Some(SpeculativeExecutionResults {
+        execution_results,
+        post_state_hash,
+    }))
 }
 
 fn execute<S>(
@@ -0,0 +1,369 @@
+use std::collections::BTreeMap;
+
+use datasize::DataSize;
+use tracing::{debug, info};
+
+use casper_execution_engine::storage::{
+    error::Error as GlobalStateError,
+    global_state::{lmdb::LmdbGlobalState, pruning::PruningProgress},
+};
+use casper_hashing::Digest;
+use casper_types::{TimeDiff, Timestamp};
+
+/// How long after a trie was last written by a global state sync no new pruning is started.
+///
+/// A sync writes tries bottom-up, so until its root is written, the tries it has written are not
+/// reachable from any retained root and would be swept, forcing them to be fetched again.
+const TRIE_SYNC_QUIET_PERIOD: TimeDiff = TimeDiff::from_seconds(90);
+
+/// The outcome of a single step of state pruning.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(super) enum PruningStep {
+    /// No pruning is in progress.
+    Idle,
+    /// Pruning is still in progress.
+    InProgress,
+    /// The sweep phase of pruning has just begun: the state of all blocks below the given height
+    /// is no longer queryable.
+    PrunedBelow(u64),
+}
+
+/// Tracks the state root hashes of the most recent blocks, and prunes global state which is not
+/// reachable from any of them.
+///
+/// The global state of every block in the retention window, i.e. the `retention_blocks` heights
+/// up to the highest known block, is retained, whether the block was executed by this node or
+/// synced along with its global state.  A pruning is only started once the state roots of all the
+/// blocks in the window are known, so that the state of a block the node holds but has not
+/// registered is never swept.
+///
+/// A new pruning is started once every `retention_blocks` blocks, so that at most roughly twice
+/// the retained number of blocks' worth of state is held at any time.
+///
+/// A pruning whose sweep was interrupted by a restart is resumed by the global state itself, and
+/// is driven to completion once the next block is executed (see [`StatePruner::resume_sweep`]).
+#[derive(DataSize, Debug)]
+pub(super) struct StatePruner {
+    /// The number of most recent blocks whose state is retained.
+    retention_blocks: u64,
+    /// The maximum number of tries visited in a single step of pruning.
+    batch_size: usize,
+    /// The state root hashes of the known blocks in the retention window, by height.
+    recent_roots: BTreeMap<u64, Digest>,
+    /// Whether a pruning is in progress.
+    is_pruning: bool,
+    /// Whether a sweep interrupted by a restart is yet to be driven to completion.
+    is_resume_pending: bool,
+    /// The height of the lowest block retained by the in-progress pruning, if known.
+    ///
+    /// This is unknown for a sweep resumed after a restart.
+    retained_from_height: Option<u64>,
+    /// Whether the in-progress pruning has begun deleting tries.
+    is_sweeping: bool,
+    /// The block height at or after which the next pruning may be started.
+    next_pruning_height: u64,
+    /// When a trie was last written by a global state sync, if ever.
+    last_trie_synced: Option<Timestamp>,
+}
+
+impl StatePruner {
+    pub(super) fn new(retention_blocks: u64, batch_size: usize) -> Self {
+        StatePruner {
+            // The state of the most recent block is always required for execution.
+            retention_blocks: retention_blocks.max(1),
+            batch_size: batch_size.max(1),
+            recent_roots: BTreeMap::new(),
+            is_pruning: false,
+            is_resume_pending: false,
+            retained_from_height: None,
+            is_sweeping: false,
+            next_pruning_height: 0,
+            last_trie_synced: None,
+        }
+    }
+
+    /// Records that the global state has resumed the sweep of a pruning interrupted by a restart.
+    ///
+    /// The next call to [`Self::block_executed`] returns `true`, so that the sweep is driven to
+    /// completion.  The heights below which state was pruned were recorded before the restart, so
+    /// are not reported again.
+    pub(super) fn resume_sweep(&mut self) {
+        self.is_pruning = true;
+        self.is_resume_pending = true;
+        self.is_sweeping = true;
+        self.retained_from_height = None;
+    }
+
+    /// Records that a trie was written by a global state sync, holding back new prunings until
+    /// the sync has gone quiet.
+    pub(super) fn trie_synced(&mut self) {
+        self.last_trie_synced = Some(Timestamp::now());
+    }
+
+    /// Returns `true` if a global state sync has written a trie recently.
+    fn is_syncing_tries(&self) -> bool {
+        self.last_trie_synced.map_or(false, |timestamp| {
+            timestamp.elapsed() < TRIE_SYNC_QUIET_PERIOD
+        })
+    }
+
+    /// Records the state root hash of a block whose global state the node holds, dropping those
+    /// of blocks which have fallen out of the retention window.
+    ///
+    /// Returns the lowest height of the retention window.
+    fn register_root(&mut self, height: u64, state_root_hash: Digest) -> u64 {
+        let _ = self.recent_roots.insert(height, state_root_hash);
+        let highest_height = self
+            .recent_roots
+            .keys()
+            .next_back()
+            .copied()
+            .unwrap_or(height);
+        let window_start = (highest_height + 1).saturating_sub(self.retention_blocks);
+        self.recent_roots = self.recent_roots.split_off(&window_start);
+        window_start
+    }
+
+    /// Records the state root hash of a block synced along with its global state, e.g. a
+    /// historical block acquired by the block synchronizer, so that its state is retained.
+    pub(super) fn block_synced(&mut self, height: u64, state_root_hash: Digest) {
+        let _ = self.register_root(height, state_root_hash);
+    }
+
+    /// Records the state root hash of a newly-executed block, starting a new pruning if due.
+    ///
+    /// Returns `true` if a new pruning was started, or a resumed sweep is yet to be driven, in
+    /// which case the caller should drive it to completion via [`Self::step`].
+    pub(super) fn block_executed(
+        &mut self,
+        global_state: &LmdbGlobalState,
+        height: u64,
+        state_root_hash: Digest,
+    ) -> Result<bool, GlobalStateError> {
+        let retained_from_height = self.register_root(height, state_root_hash);
+
+        if self.is_resume_pending {
+            info!("ContractRuntime: resuming pruning global state");
+            self.is_resume_pending = false;
+            return Ok(true);
+        }
+
+        // Heights are unique, so the window is complete once it holds `retention_blocks` roots.
+        if self.is_pruning
+            || (self.recent_roots.len() as u64) < self.retention_blocks
+            || height < self.next_pruning_height
+        {
+            return Ok(false);
+        }
+        if self.is_syncing_tries() {
+            debug!("ContractRuntime: postponing pruning global state while syncing tries");
+            return Ok(false);
+        }
+
+        let retained_roots = self.recent_roots.values().copied();
+        if !global_state.start_pruning(retained_roots)? {
+            return Ok(false);
+        }
+        info!(
+            retained_from_height,
+            "ContractRuntime: started pruning global state"
+        );
+        self.is_pruning = true;
+        self.retained_from_height = Some(retained_from_height);
+        self.is_sweeping = false;
+        self.next_pruning_height = height.saturating_add(self.retention_blocks);
+        Ok(true)
+    }
+
+    /// Performs a single step of the in-progress pruning, if any.
+    pub(super) fn step(
+        &mut self,
+        global_state: &LmdbGlobalState,
+    ) -> Result<PruningStep, GlobalStateError> {
+        if !self.is_pruning {
+            return Ok(PruningStep::Idle);
+        }
+        match global_state.prune_step(self.batch_size)? {
+            Some(PruningProgress::Marking) => Ok(PruningStep::InProgress),
+            Some(PruningProgress::Sweeping) if !self.is_sweeping => {
+                self.is_sweeping = true;
+                match self.retained_from_height {
+                    Some(retained_from_height) => {
+                        debug!(
+                            retained_from_height,
+                            "ContractRuntime: sweeping global state"
+                        );
+                        Ok(PruningStep::PrunedBelow(retained_from_height))
+                    }
+                    None => Ok(PruningStep::InProgress),
+                }
+            }
+            Some(PruningProgress::Sweeping) => Ok(PruningStep::InProgress),
+            Some(PruningProgress::Complete { deleted_count }) => {
+                info!(
+                    retained_from_height = ?self.retained_from_height,
+                    deleted_count, "ContractRuntime: finished pruning global state"
+                );
+                self.is_pruning = false;
+                self.retained_from_height = None;
+                Ok(PruningStep::Idle)
+            }
+            None => {
+                self.is_pruning = false;
+                self.retained_from_height = None;
+                Ok(PruningStep::Idle)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, sync::Arc};
+
+    use lmdb::DatabaseFlags;
+    use tempfile::TempDir;
+
+    use casper_execution_engine::{
+        shared::{additive_map::AdditiveMap, newtypes::CorrelationId, transform::Transform},
+        storage::{
+            global_state::{CommitProvider, StateProvider},
+            transaction_source::lmdb::LmdbEnvironment,
+            trie_store::lmdb::LmdbTrieStore,
+        },
+    };
+    use casper_types::{CLValue, Key, StoredValue};
+
+    use super::*;
+
+    /// Opens the global state held in `dir`, as on startup of the node.
+    fn open_global_state(dir: &Path) -> LmdbGlobalState {
+        let environment = Arc::new(LmdbEnvironment::new(dir, 50 * 1024 * 1024, 512, true).unwrap());
+        let trie_store =
+            Arc::new(LmdbTrieStore::new(&environment, None, DatabaseFlags::empty()).unwrap());
+        LmdbGlobalState::empty(environment, trie_store).unwrap()
+    }
+
+    /// Creates a global state holding one state root per block height up to `highest_height`,
+    /// each overwriting a shared key so that older roots become unreachable from newer ones.
+    fn create_chain(highest_height: u64) -> (TempDir, LmdbGlobalState, Vec<Digest>) {
+        let tempdir = tempfile::tempdir().unwrap();
+        let global_state = open_global_state(tempdir.path());
+
+        let mut state_root_hash = global_state.empty_state_root_hash();
+        let mut roots = vec![];
+        for height in 0..=highest_height {
+            let value = StoredValue::CLValue(CLValue::from_t(height).unwrap());
+            let mut effects = AdditiveMap::new();
+            effects.insert(Key::Hash([0; 32]), Transform::Write(value.clone()));
+            effects.insert(Key::Hash([height as u8 + 1; 32]), Transform::Write(value));
+            state_root_hash = global_state
+                .commit(CorrelationId::new(), state_root_hash, effects)
+                .unwrap();
+            roots.push(state_root_hash);
+        }
+        (tempdir, global_state, roots)
+    }
+
+    #[test]
+    fn should_retain_state_of_historically_synced_blocks() {
+        let (_tempdir, global_state, roots) = create_chain(12);
+        let mut pruner = StatePruner::new(4, 2);
+
+        // The node sync-leaps to block 10, then syncs back block 9 while executing block 11.
+        pruner.block_synced(10, roots[10]);
+        pruner.block_synced(9, roots[9]);
+        assert!(!pruner.block_executed(&global_state, 11, roots[11]).unwrap());
+
+        // Sync back fills the retention window, so executing the next block starts a pruning.
+        pruner.block_synced(8, roots[8]);
+        pruner.block_synced(7, roots[7]);
+        assert!(pruner.block_executed(&global_state, 12, roots[12]).unwrap());
+
+        let mut pruned_below = None;
+        loop {
+            match pruner.step(&global_state).unwrap() {
+                PruningStep::Idle => break,
+                PruningStep::InProgress => (),
+                PruningStep::PrunedBelow(height) => pruned_below = Some(height),
+            }
+        }
+        assert_eq!(pruned_below, Some(9));
+
+        for (height, state_root_hash) in roots.iter().enumerate() {
+            let is_retained = global_state.checkout(*state_root_hash).unwrap().is_some();
+            assert_eq!(
+                is_retained,
+                height >= 9,
+                "unexpected state at height {}",
+                height
+            );
+        }
+    }
+
+    #[test]
+    fn should_not_prune_before_retention_window_is_known() {
+        let (_tempdir, global_state, roots) = create_chain(12);
+        let mut pruner = StatePruner::new(4, 2);
+
+        // Blocks 0 to 2 were executed before the node restarted and sync-leapt to block 10.
+        for height in 0..3 {
+            assert!(!pruner
+                .block_executed(&global_state, height, roots[height as usize])
+                .unwrap());
+        }
+        assert!(!pruner.block_executed(&global_state, 11, roots[11]).unwrap());
+        assert!(!pruner.block_executed(&global_state, 12, roots[12]).unwrap());
+        assert_eq!(pruner.step(&global_state).unwrap(), PruningStep::Idle);
+        assert!(roots
+            .iter()
+            .all(|root| global_state.checkout(*root).unwrap().is_some()));
+    }
+    #[test]
+    fn should_resume_sweep_after_restart() {
+        let (tempdir, global_state, roots) = create_chain(12);
+        let mut pruner = StatePruner::new(4, 1);
+        for height in 9..12 {
+            assert!(!pruner
+                .block_executed(&global_state, height, roots[height as usize])
+                .unwrap());
+        }
+        assert!(pruner.block_executed(&global_state, 12, roots[12]).unwrap());
+        loop {
+            if let PruningStep::PrunedBelow(_) = pruner.step(&global_state).unwrap() {
+                break;
+            }
+        }
+        assert_eq!(pruner.step(&global_state).unwrap(), PruningStep::InProgress);
+
+        // Restart part way through the sweep.
+        drop(global_state);
+        let global_state = open_global_state(tempdir.path());
+        assert!(global_state.is_pruning().unwrap());
+        let mut pruner = StatePruner::new(4, 1);
+        pruner.resume_sweep();
+        assert_eq!(pruner.step(&global_state).unwrap(), PruningStep::InProgress);
+        assert!(pruner.block_executed(&global_state, 13, roots[12]).unwrap());
+        assert!(!pruner.block_executed(&global_state, 14, roots[12]).unwrap());
+
+        loop {
+            match pruner.step(&global_state).unwrap() {
+                PruningStep::Idle => break,
+                PruningStep::InProgress => (),
+                PruningStep::PrunedBelow(height) => {
+                    panic!("should not report pruning below {} again", height)
+                }
+            }
+        }
+        assert!(!global_state.is_pruning().unwrap());
+        for (height, state_root_hash) in roots.iter().enumerate() {
+            let is_retained = global_state.checkout(*state_root_hash).unwrap().is_some();
+            assert_eq!(
+                is_retained,
+                height >= 9,
+                "unexpected state at height {}",
+                height
+            );
+        }
+    }
+}
@@ -7,7 +7,9 @@ use serde::Serialize;
 use tempfile::TempDir;
 
 use casper_execution_engine::core::engine_state::ExecutableDeployItem;
-use casper_types::{runtime_args, EraId, PublicKey, RuntimeArgs, SecretKey, TimeDiff, U512};
+use casper_types::{
+    runtime_args, EraId, ExecutionResult, PublicKey, RuntimeArgs, SecretKey, TimeDiff, U512,
+};
 
 use super::*;
 use crate::{
@@ -20,7 +22,9 @@ use crate::{
     protocol::Message,
     reactor::{self, EventQueueHandle, ReactorEvent, Runner},
     testing::{self, network::NetworkedReactor, ConditionCheckReactor},
-    types::{BlockPayload, Chainspec, ChainspecRawBytes, Deploy, DeployHashWithApprovals},
+    types::{
+        BlockPayload, Chainspec, ChainspecRawBytes, Deploy, DeployHashWithApprovals, SyncHandling,
+    },
     utils::{Loadable, WithDir, RESOURCES_PATH},
     NodeRng,
 };
@@ -116,6 +120,7 @@ impl reactor::Reactor for Reactor {
             chainspec.core_config.minimum_delegation_amount,
             chainspec.protocol_config.activation_point,
             chainspec.core_config.prune_batch_size,
+            chainspec.deploy_config.block_gas_limit,
             chainspec.core_config.strict_argument_checking,
             chainspec.core_config.vesting_schedule_period.millis(),
             Some(chainspec.core_config.max_delegators_per_validator),
@@ -392,3 +397,159 @@ async fn should_not_set_shared_pre_state_to_lower_block_height() {
         next_block_height
     );
 }
+
+#[tokio::test]
+async fn should_execute_speculative_deploys_on_top_of_each_other() {
+    testing::init_logging();
+
+    let config = Config {
+        max_global_state_size: Some(100 * 1024 * 1024),
+        ..Config::default()
+    };
+    let (chainspec, chainspec_raw_bytes) =
+        <(Chainspec, ChainspecRawBytes)>::from_resources("local");
+    let chainspec = Arc::new(chainspec);
+    let chainspec_raw_bytes = Arc::new(chainspec_raw_bytes);
+
+    let mut rng = crate::new_rng();
+    let rng = &mut rng;
+
+    let runner: Runner<ConditionCheckReactor<Reactor>> = Runner::new(
+        config,
+        Arc::clone(&chainspec),
+        Arc::clone(&chainspec_raw_bytes),
+        rng,
+    )
+    .await
+    .unwrap();
+    let contract_runtime = &runner.reactor().inner().contract_runtime;
+    let post_commit_genesis_state_hash = contract_runtime
+        .commit_genesis(chainspec.as_ref(), chainspec_raw_bytes.as_ref())
+        .unwrap()
+        .post_state_hash;
+
+    // The first deploy funds a new account, and the second deploy transfers from that account, so
+    // can only succeed if executed on top of the effects of the first.
+    let node_1_secret_key = SecretKey::from_file(
+        RESOURCES_PATH
+            .join("local")
+            .join("secret_keys")
+            .join("node-1.pem"),
+    )
+    .unwrap();
+    let new_secret_key = SecretKey::random(rng);
+    let timestamp = Timestamp::now();
+    let ttl = TimeDiff::from_seconds(100);
+    let gas_price = 1;
+    let chain_name = chainspec.network_config.name.clone();
+    let payment = ExecutableDeployItem::ModuleBytes {
+        module_bytes: Bytes::new(),
+        args: runtime_args! {
+          "amount" => U512::from(chainspec.system_costs_config.wasmless_transfer_cost()),
+        },
+    };
+    let transfer = |secret_key: &SecretKey, target: PublicKey, amount: U512| {
+        let session = ExecutableDeployItem::Transfer {
+            args: runtime_args! {
+              "amount" => amount,
+              "target" => target,
+              "id" => Some(9_u64),
+            },
+        };
+        let deploy = Deploy::new(
+            timestamp,
+            ttl,
+            gas_price,
+            vec![],
+            chain_name.clone(),
+            payment.clone(),
+            session,
+            secret_key,
+            None,
+        );
+        DeployItem::from(deploy)
+    };
+    let minimum_motes = U512::from(chainspec.deploy_config.native_transfer_minimum_motes);
+    let funding_deploy = transfer(
+        &node_1_secret_key,
+        PublicKey::from(&new_secret_key),
+        minimum_motes * 10,
+    );
+    let spending_deploy = transfer(&new_secret_key, PublicKey::random(rng), minimum_motes);
+
+    let execution_state = SpeculativeExecutionState {
+        state_root_hash: post_commit_genesis_state_hash,
+        block_time: timestamp,
+        protocol_version: chainspec.protocol_version(),
+    };
+    let results = execute_only_many(
+        contract_runtime.engine_state.as_ref(),
+        execution_state.clone(),
+        vec![funding_deploy, spending_deploy.clone()],
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(results.execution_results.len(), 2);
+    for (deploy_hash, execution_result) in &results.execution_results {
+        assert!(
+            matches!(execution_result, ExecutionResult::Success { .. }),
+            "deploy {} failed: {:?}",
+            deploy_hash,
+            execution_result
+        );
+    }
+    assert_ne!(results.post_state_hash, post_commit_genesis_state_hash);
+
+    // On its own, the spending deploy has no account to spend from.
+    let spent_alone = execute_only_many(
+        contract_runtime.engine_state.as_ref(),
+        execution_state,
+        vec![spending_deploy],
+    );
+    assert!(!matches!(
+        spent_alone,
+        Ok(Some(SpeculativeExecutionResults { ref execution_results, .. }))
+            if matches!(execution_results[0].1, ExecutionResult::Success { .. })
+    ));
+}
+
+#[test]
+fn should_reject_state_retention_conflicting_with_sync_handling() {
+    let (chainspec, _) = <(Chainspec, ChainspecRawBytes)>::from_resources("local");
+    let config = |state_retention_blocks| Config {
+        state_retention_blocks,
+        ..Config::default()
+    };
+
+    for sync_handling in [
+        SyncHandling::Genesis,
+        SyncHandling::Ttl,
+        SyncHandling::NoSync,
+    ] {
+        assert!(config(None)
+            .validate_state_retention(&sync_handling, &chainspec)
+            .is_ok());
+    }
+    assert!(matches!(
+        config(Some(u64::MAX)).validate_state_retention(&SyncHandling::Genesis, &chainspec),
+        Err(ConfigError::StateRetentionWithGenesisSync)
+    ));
+    assert!(config(Some(1))
+        .validate_state_retention(&SyncHandling::NoSync, &chainspec)
+        .is_ok());
+
+    let required_blocks =
+        match config(Some(1)).validate_state_retention(&SyncHandling::Ttl, &chainspec) {
+            Err(ConfigError::StateRetentionBelowTtl {
+                retention_blocks: 1,
+                required_blocks,
+            }) => required_blocks,
+            result => panic!("unexpected result: {:?}", result),
+        };
+    assert!(config(Some(required_blocks))
+        .validate_state_retention(&SyncHandling::Ttl, &chainspec)
+        .is_ok());
+    assert!(config(Some(required_blocks - 1))
+        .validate_state_retention(&SyncHandling::Ttl, &chainspec)
+        .is_err());
+}
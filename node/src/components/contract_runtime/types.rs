@@ -3,7 +3,11 @@ use std::{collections::BTreeMap, sync::Arc};
 use datasize::DataSize;
 
 use casper_execution_engine::{
-    core::engine_state::GetEraValidatorsRequest, shared::execution_journal::ExecutionJournal,
+    core::{
+        engine_state::{GasEstimate, GetEraValidatorsRequest},
+        runtime::call_trace::CallTrace,
+    },
+    shared::execution_journal::ExecutionJournal,
 };
 use casper_hashing::Digest;
 use casper_types::{EraId, ExecutionResult, ProtocolVersion, PublicKey, U512};
@@ -107,3 +111,24 @@ pub struct BlockAndExecutionResults {
     pub(crate) maybe_step_effect_and_upcoming_era_validators:
         Option<StepEffectAndUpcomingEraValidators>,
 }
+
+/// The result of speculatively executing a single deploy.
+#[derive(Clone, Debug)]
+pub struct SpeculativeExecutionResult {
+    /// The result of executing the deploy.
+    pub(crate) execution_result: ExecutionResult,
+    /// The call frames entered while executing the deploy, if tracing was requested.
+    pub(crate) call_trace: Option<CallTrace>,
+    /// The gas consumed by the deploy when executed with a payment covering up to the block gas
+    /// limit, if estimation was requested.
+    pub(crate) gas_estimate: Option<GasEstimate>,
+}
+
+/// The results of speculatively executing a sequence of deploys cumulatively.
+#[derive(Clone, Debug)]
+pub struct SpeculativeExecutionResults {
+    /// The result of executing each deploy, in execution order.
+    pub(crate) execution_results: Vec<(DeployHash, ExecutionResult)>,
+    /// The state root hash which would result from committing the effects of all the deploys.
+    pub(crate) post_state_hash: Digest,
+}
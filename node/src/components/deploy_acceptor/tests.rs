@@ -167,6 +167,8 @@ enum TestScenario {
     FromClientInvalidDeploy,
     FromClientSlightlyFutureDatedDeploy,
     FromClientFutureDatedDeploy,
+    FromClientScheduledDeploy,
+    FromClientScheduledDeployWithExcessiveTtl,
     FromClientMissingAccount,
     FromClientInsufficientBalance,
     FromClientValidDeploy,
@@ -218,6 +220,8 @@ impl TestScenario {
             TestScenario::FromClientInvalidDeploy
             | TestScenario::FromClientSlightlyFutureDatedDeploy
             | TestScenario::FromClientFutureDatedDeploy
+            | TestScenario::FromClientScheduledDeploy
+            | TestScenario::FromClientScheduledDeployWithExcessiveTtl
             | TestScenario::FromClientMissingAccount
             | TestScenario::FromClientInsufficientBalance
             | TestScenario::FromClientValidDeploy
@@ -364,12 +368,29 @@ impl TestScenario {
                 Deploy::random_valid_native_transfer_with_timestamp_and_ttl(rng, timestamp, ttl)
             }
             TestScenario::FromClientFutureDatedDeploy => {
+                // Dated beyond the time up to which deploys can be scheduled.
+                let (chainspec, _) = <(Chainspec, ChainspecRawBytes)>::from_resources("local");
                 let timestamp = Timestamp::now()
                     + Config::default().timestamp_leeway
+                    + chainspec.deploy_config.max_scheduling_delay
                     + TimeDiff::from_millis(100);
                 let ttl = TimeDiff::from_seconds(300);
                 Deploy::random_valid_native_transfer_with_timestamp_and_ttl(rng, timestamp, ttl)
             }
+            TestScenario::FromClientScheduledDeploy
+            | TestScenario::FromClientScheduledDeployWithExcessiveTtl => {
+                let (chainspec, _) = <(Chainspec, ChainspecRawBytes)>::from_resources("local");
+                let scheduling_delay = chainspec.deploy_config.max_scheduling_delay / 2;
+                let timestamp =
+                    Timestamp::now() + Config::default().timestamp_leeway + scheduling_delay;
+                // The scheduling delay plus the TTL must not exceed the maximum TTL.
+                let ttl = if *self == TestScenario::FromClientScheduledDeploy {
+                    chainspec.deploy_config.max_ttl - scheduling_delay - TimeDiff::from_seconds(60)
+                } else {
+                    chainspec.deploy_config.max_ttl
+                };
+                Deploy::random_valid_native_transfer_with_timestamp_and_ttl(rng, timestamp, ttl)
+            }
         }
     }
 
@@ -383,12 +404,14 @@ impl TestScenario {
             | TestScenario::FromClientRepeatedValidDeploy
             | TestScenario::FromClientValidDeploy
             | TestScenario::FromClientSlightlyFutureDatedDeploy
+            | TestScenario::FromClientScheduledDeploy
             | TestScenario::ShouldAcceptExpiredDeploySentByPeer=> true,
             TestScenario::FromPeerInvalidDeploy
             | TestScenario::FromClientInsufficientBalance
             | TestScenario::FromClientMissingAccount
             | TestScenario::FromClientInvalidDeploy
             | TestScenario::FromClientFutureDatedDeploy
+            | TestScenario::FromClientScheduledDeployWithExcessiveTtl
             | TestScenario::FromClientAccountWithInsufficientWeight
             | TestScenario::FromClientAccountWithInvalidAssociatedKeys
             | TestScenario::AccountWithUnknownBalance
@@ -886,6 +909,7 @@ async fn run_deploy_acceptor_without_timeout(
             // with the appropriate source.
             TestScenario::FromClientInvalidDeploy
             | TestScenario::FromClientFutureDatedDeploy
+            | TestScenario::FromClientScheduledDeployWithExcessiveTtl
             | TestScenario::FromClientMissingAccount
             | TestScenario::FromClientInsufficientBalance
             | TestScenario::FromClientAccountWithInvalidAssociatedKeys
@@ -992,7 +1016,8 @@ async fn run_deploy_acceptor_without_timeout(
             // Check that a, new and valid, deploy sent by a client raises an `AcceptedNewDeploy`
             // announcement with the appropriate source.
             TestScenario::FromClientValidDeploy
-            | TestScenario::FromClientSlightlyFutureDatedDeploy => {
+            | TestScenario::FromClientSlightlyFutureDatedDeploy
+            | TestScenario::FromClientScheduledDeploy => {
                 matches!(
                     event,
                     Event::DeployAcceptorAnnouncement(
@@ -1144,6 +1169,23 @@ async fn should_reject_future_dated_deploy_from_client() {
     ))
 }
 
+#[tokio::test]
+async fn should_accept_scheduled_deploy_from_client() {
+    let result = run_deploy_acceptor(TestScenario::FromClientScheduledDeploy).await;
+    assert!(result.is_ok())
+}
+
+#[tokio::test]
+async fn should_reject_scheduled_deploy_with_excessive_ttl_from_client() {
+    let result = run_deploy_acceptor(TestScenario::FromClientScheduledDeployWithExcessiveTtl).await;
+    assert!(matches!(
+        result,
+        Err(super::Error::InvalidDeployConfiguration(
+            DeployConfigurationFailure::ExcessiveScheduledTimeToLive { .. }
+        ))
+    ))
+}
+
 #[tokio::test]
 async fn should_reject_valid_deploy_from_client_for_missing_account() {
     let result = run_deploy_acceptor(TestScenario::FromClientMissingAccount).await;
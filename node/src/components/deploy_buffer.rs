@@ -1,6 +1,7 @@
 mod config;
 mod event;
 mod metrics;
+mod selection_policy;
 #[cfg(test)]
 mod tests;
 
@@ -18,7 +19,7 @@ use prometheus::Registry;
 use smallvec::smallvec;
 use tracing::{debug, error, info, warn};
 
-use casper_types::Timestamp;
+use casper_types::{account::AccountHash, PublicKey, Timestamp};
 
 use crate::{
     components::{
@@ -37,15 +38,17 @@ use crate::{
         appendable_block::{AddError, AppendableBlock},
         chainspec::DeployConfig,
         Approval, Block, Deploy, DeployFootprint, DeployHash, DeployHashWithApprovals, DeployId,
-        FinalizedBlock,
+        FinalizedBlock, PendingDeploy, PendingDeployStatus,
     },
     utils::DisplayIter,
     NodeRng,
 };
 pub(crate) use config::Config;
 pub(crate) use event::Event;
+pub(crate) use selection_policy::SelectionPolicy;
 
 use metrics::Metrics;
+use selection_policy::SkipReason;
 
 const COMPONENT_NAME: &str = "deploy_buffer";
 
@@ -309,18 +312,22 @@ impl DeployBuffer {
     }
 
     /// Returns eligible deploys that are buffered and not held or dead.
-    fn proposable(&self) -> Vec<(DeployHashWithApprovals, DeployFootprint)> {
+    ///
+    /// Scheduled deploys, i.e. those with a timestamp later than `timestamp`, are not yet eligible.
+    fn proposable(&self, timestamp: Timestamp) -> Vec<(DeployHashWithApprovals, DeployFootprint)> {
         debug!("DeployBuffer: getting proposable deploys");
         self.buffer
             .iter()
             .filter(|(dh, _)| !self.hold.values().any(|hs| hs.contains(dh)))
             .filter(|(dh, _)| !self.dead.contains(dh))
             .filter_map(|(dh, (_, maybe_data))| {
-                maybe_data.as_ref().map(|(footprint, approvals)| {
-                    (
-                        DeployHashWithApprovals::new(*dh, approvals.clone()),
-                        footprint.clone(),
-                    )
+                maybe_data.as_ref().and_then(|(footprint, approvals)| {
+                    (footprint.header.timestamp() <= timestamp).then(|| {
+                        (
+                            DeployHashWithApprovals::new(*dh, approvals.clone()),
+                            footprint.clone(),
+                        )
+                    })
                 })
             })
             .collect()
@@ -332,11 +339,30 @@ impl DeployBuffer {
         let mut holds = HashSet::new();
         let mut have_hit_transfer_limit = false;
         let mut have_hit_deploy_limit = false;
-        for (with_approvals, footprint) in self.proposable() {
-            if footprint.is_transfer && have_hit_transfer_limit {
+        let mut per_account_counts: HashMap<PublicKey, u32> = HashMap::new();
+        let mut skipped: BTreeMap<SkipReason, usize> = BTreeMap::new();
+        let proposable = self.cfg.selection_policy.order(self.proposable(timestamp));
+        let proposable_count = proposable.len();
+        for (index, (with_approvals, footprint)) in proposable.into_iter().enumerate() {
+            if (footprint.is_transfer && have_hit_transfer_limit)
+                || (!footprint.is_transfer && have_hit_deploy_limit)
+            {
+                *skipped.entry(SkipReason::BlockLimit).or_default() += 1;
                 continue;
             }
-            if !footprint.is_transfer && have_hit_deploy_limit {
+            let account = footprint.header.account();
+            if let Some(max_deploys_per_account) = self.cfg.max_deploys_per_account {
+                if per_account_counts.get(account).copied().unwrap_or_default()
+                    >= max_deploys_per_account
+                {
+                    *skipped.entry(SkipReason::AccountLimit).or_default() += 1;
+                    continue;
+                }
+            }
+            if self.cfg.selection_policy.requires_dependencies()
+                && !self.dependencies_met(&footprint, &holds)
+            {
+                *skipped.entry(SkipReason::UnmetDependencies).or_default() += 1;
                 continue;
             }
             let deploy_hash = *with_approvals.deploy_hash();
@@ -345,6 +371,7 @@ impl DeployBuffer {
                 Ok(_) => {
                     debug!(%deploy_hash, "DeployBuffer: proposing deploy");
                     holds.insert(deploy_hash);
+                    *per_account_counts.entry(account.clone()).or_default() += 1;
                 }
                 Err(error) => {
                     match error {
@@ -382,8 +409,11 @@ impl DeployBuffer {
                                     ?deploy_hash,
                                     "DeployBuffer: block filled with transfers and deploys"
                                 );
+                                *skipped.entry(SkipReason::BlockLimit).or_default() +=
+                                    proposable_count - index;
                                 break;
                             }
+                            *skipped.entry(SkipReason::BlockLimit).or_default() += 1;
                             have_hit_transfer_limit = true;
                         }
                         AddError::DeployCount => {
@@ -392,12 +422,16 @@ impl DeployBuffer {
                                     ?deploy_hash,
                                     "DeployBuffer: block filled with deploys and transfers"
                                 );
+                                *skipped.entry(SkipReason::BlockLimit).or_default() +=
+                                    proposable_count - index;
                                 break;
                             }
+                            *skipped.entry(SkipReason::BlockLimit).or_default() += 1;
                             have_hit_deploy_limit = true;
                         }
                         AddError::ApprovalCount if has_multiple_approvals => {
                             // keep iterating, we can maybe fit in a deploy with fewer approvals
+                            *skipped.entry(SkipReason::BlockLimit).or_default() += 1;
                         }
                         AddError::ApprovalCount | AddError::GasLimit | AddError::BlockSize => {
                             info!(
@@ -406,6 +440,8 @@ impl DeployBuffer {
                                 "DeployBuffer: a block limit has been reached"
                             );
                             // a block limit has been reached
+                            *skipped.entry(SkipReason::BlockLimit).or_default() +=
+                                proposable_count - index;
                             break;
                         }
                     }
@@ -423,21 +459,86 @@ impl DeployBuffer {
             }
         }
         self.update_all_metrics();
+        for (reason, count) in &skipped {
+            self.metrics.record_skipped(*reason, *count);
+        }
 
         info!(
-            "produced {}, buffer has {} held, {} dead, {} total",
+            "produced {} using {} selection, buffer has {} held, {} dead, {} total, skipped {}",
             ret,
+            self.cfg.selection_policy,
             self.hold
                 .values()
                 .map(|deploys| deploys.len())
                 .sum::<usize>(),
             self.dead.len(),
-            self.buffer.len()
+            self.buffer.len(),
+            DisplayIter::new(
+                skipped
+                    .iter()
+                    .map(|(reason, count)| format!("{} ({})", count, reason))
+            )
         );
 
         ret
     }
 
+    /// Returns all deploys known to the buffer, optionally only those of the given account, ordered
+    /// by deploy hash.
+    ///
+    /// Deploys known only from their inclusion in a block have no footprint, and so are omitted if
+    /// an account is given.
+    fn pending_deploys(
+        &self,
+        account_hash: Option<AccountHash>,
+        now: Timestamp,
+    ) -> Vec<PendingDeploy> {
+        self.buffer
+            .iter()
+            .filter(|(_, (_, maybe_data))| match (&account_hash, maybe_data) {
+                (None, _) => true,
+                (Some(account_hash), Some((footprint, _))) => {
+                    footprint.header.account().to_account_hash() == *account_hash
+                }
+                (Some(_), None) => false,
+            })
+            .map(|(deploy_hash, (expiry, maybe_data))| {
+                let status = if self.dead.contains(deploy_hash) {
+                    PendingDeployStatus::Dead
+                } else if self.hold.values().any(|held| held.contains(deploy_hash)) {
+                    PendingDeployStatus::Held
+                } else if maybe_data
+                    .as_ref()
+                    .map_or(false, |(footprint, _)| footprint.header.timestamp() > now)
+                {
+                    PendingDeployStatus::Scheduled
+                } else {
+                    PendingDeployStatus::Proposable
+                };
+                PendingDeploy {
+                    deploy_hash: *deploy_hash,
+                    footprint: maybe_data
+                        .as_ref()
+                        .map(|(footprint, _)| footprint.clone().into()),
+                    ttl_remaining: expiry.saturating_diff(now),
+                    status,
+                }
+            })
+            .sorted_by_key(|pending_deploy| pending_deploy.deploy_hash)
+            .collect()
+    }
+
+    /// Returns `true` if none of the deploy's dependencies are buffered deploys still awaiting
+    /// inclusion, i.e. each is either unknown to the buffer, already included in a block, or in
+    /// `holds` for the block currently being proposed.
+    fn dependencies_met(&self, footprint: &DeployFootprint, holds: &HashSet<DeployHash>) -> bool {
+        footprint.header.dependencies().iter().all(|dependency| {
+            !self.buffer.contains_key(dependency)
+                || self.dead.contains(dependency)
+                || holds.contains(dependency)
+        })
+    }
+
     /// Updates all deploy count metrics based on the size of the internal structs.
     fn update_all_metrics(&mut self) {
         // if number of elements is too high to fit, we overflow the metric
@@ -552,6 +653,12 @@ where
                     timestamp,
                     responder,
                 }) => responder.respond(self.appendable_block(timestamp)).ignore(),
+                Event::Request(DeployBufferRequest::GetPendingDeploys {
+                    account_hash,
+                    responder,
+                }) => responder
+                    .respond(self.pending_deploys(account_hash, Timestamp::now()))
+                    .ignore(),
                 Event::BlockFinalized(finalized_block) => {
                     self.register_block_finalized(&finalized_block);
                     Effects::new()
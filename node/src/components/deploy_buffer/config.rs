@@ -3,6 +3,8 @@ use serde::{Deserialize, Serialize};
 
 use casper_types::TimeDiff;
 
+use super::SelectionPolicy;
+
 const DEFAULT_EXPIRY_CHECK_INTERVAL: &str = "1min";
 
 #[derive(Copy, Clone, DataSize, Debug, Deserialize, Serialize)]
@@ -10,6 +12,13 @@ const DEFAULT_EXPIRY_CHECK_INTERVAL: &str = "1min";
 pub struct Config {
     /// The interval of checking for expired deploys.
     pub expiry_check_interval: TimeDiff,
+    /// The policy by which proposable deploys are ordered when filling a proposed block.
+    #[serde(default)]
+    pub selection_policy: SelectionPolicy,
+    /// The maximum number of deploys and transfers from a single account to include in a proposed
+    /// block, if any.
+    #[serde(default)]
+    pub max_deploys_per_account: Option<u32>,
 }
 
 impl Config {
@@ -23,6 +32,8 @@ impl Default for Config {
     fn default() -> Self {
         Config {
             expiry_check_interval: DEFAULT_EXPIRY_CHECK_INTERVAL.parse().unwrap(),
+            selection_policy: SelectionPolicy::default(),
+            max_deploys_per_account: None,
         }
     }
 }
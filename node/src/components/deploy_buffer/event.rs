@@ -34,6 +34,9 @@ impl Display for Event {
             Event::Request(DeployBufferRequest::GetAppendableBlock { .. }) => {
                 write!(formatter, "get appendable block request")
             }
+            Event::Request(DeployBufferRequest::GetPendingDeploys { .. }) => {
+                write!(formatter, "get pending deploys request")
+            }
             Event::ReceiveDeployGossiped(deploy_id) => {
                 write!(formatter, "receive deploy gossiped {}", deploy_id)
             }
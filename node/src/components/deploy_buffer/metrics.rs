@@ -1,5 +1,6 @@
-use prometheus::{IntGauge, Registry};
+use prometheus::{IntCounter, IntGauge, Registry};
 
+use super::SkipReason;
 use crate::unregister_metric;
 
 /// Metrics for the deploy_buffer component.
@@ -11,6 +12,12 @@ pub(super) struct Metrics {
     pub(super) held_deploys: IntGauge,
     /// Number of deploys that should not be included in future proposals ever again.
     pub(super) dead_deploys: IntGauge,
+    /// Number of times a proposable deploy was skipped as its account had reached its limit.
+    pub(super) skipped_account_limit: IntCounter,
+    /// Number of times a proposable deploy was skipped as its dependencies were not yet included.
+    pub(super) skipped_unmet_dependencies: IntCounter,
+    /// Number of times a proposable deploy was skipped as a block limit had been reached.
+    pub(super) skipped_block_limit: IntCounter,
     registry: Registry,
 }
 
@@ -29,18 +36,52 @@ impl Metrics {
             "deploy_buffer_dead_deploys".to_string(),
             "number of deploys that should not be included in future proposals.".to_string(),
         )?;
+        let skipped_account_limit = IntCounter::new(
+            "deploy_buffer_skipped_account_limit".to_string(),
+            "number of times a proposable deploy was skipped by the selection policy as its \
+            account had reached the per-account limit."
+                .to_string(),
+        )?;
+        let skipped_unmet_dependencies = IntCounter::new(
+            "deploy_buffer_skipped_unmet_dependencies".to_string(),
+            "number of times a proposable deploy was skipped by the selection policy as its \
+            dependencies had not been included."
+                .to_string(),
+        )?;
+        let skipped_block_limit = IntCounter::new(
+            "deploy_buffer_skipped_block_limit".to_string(),
+            "number of times a proposable deploy was skipped by the selection policy as a block \
+            limit had been reached."
+                .to_string(),
+        )?;
 
         registry.register(Box::new(total_deploys.clone()))?;
         registry.register(Box::new(held_deploys.clone()))?;
         registry.register(Box::new(dead_deploys.clone()))?;
+        registry.register(Box::new(skipped_account_limit.clone()))?;
+        registry.register(Box::new(skipped_unmet_dependencies.clone()))?;
+        registry.register(Box::new(skipped_block_limit.clone()))?;
 
         Ok(Metrics {
             total_deploys,
             held_deploys,
             dead_deploys,
+            skipped_account_limit,
+            skipped_unmet_dependencies,
+            skipped_block_limit,
             registry: registry.clone(),
         })
     }
+
+    /// Records that `count` proposable deploys were skipped for the given reason.
+    pub(super) fn record_skipped(&self, reason: SkipReason, count: usize) {
+        let counter = match reason {
+            SkipReason::AccountLimit => &self.skipped_account_limit,
+            SkipReason::UnmetDependencies => &self.skipped_unmet_dependencies,
+            SkipReason::BlockLimit => &self.skipped_block_limit,
+        };
+        counter.inc_by(count as u64);
+    }
 }
 
 impl Drop for Metrics {
@@ -48,5 +89,8 @@ impl Drop for Metrics {
         unregister_metric!(self.registry, self.total_deploys);
         unregister_metric!(self.registry, self.held_deploys);
         unregister_metric!(self.registry, self.dead_deploys);
+        unregister_metric!(self.registry, self.skipped_account_limit);
+        unregister_metric!(self.registry, self.skipped_unmet_dependencies);
+        unregister_metric!(self.registry, self.skipped_block_limit);
     }
 }
@@ -0,0 +1,166 @@
+use std::{
+    collections::{BTreeSet, HashMap, VecDeque},
+    fmt::{self, Display, Formatter},
+};
+
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+
+use casper_types::{PublicKey, U512};
+
+use crate::types::{DeployFootprint, DeployHashWithApprovals};
+
+type Proposable = (DeployHashWithApprovals, DeployFootprint);
+
+/// The policy by which proposable deploys are ordered when filling a proposed block.
+#[derive(Copy, Clone, DataSize, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SelectionPolicy {
+    /// Oldest deploys first, by deploy timestamp.
+    #[default]
+    Arrival,
+    /// Deploys offering the largest payment first, oldest first where payments are equal.
+    PaymentPriority,
+    /// One deploy from each account in turn, oldest first within each account.
+    RoundRobin,
+    /// Oldest deploys first, but with every deploy following those of its dependencies which are
+    /// also proposable.  A deploy is only proposed once all of its dependencies have been included
+    /// in an earlier block or earlier in the same block.
+    DependencyAware,
+}
+
+impl SelectionPolicy {
+    /// Returns `true` if deploys whose dependencies have not yet been included should be skipped.
+    pub(super) fn requires_dependencies(self) -> bool {
+        matches!(self, SelectionPolicy::DependencyAware)
+    }
+
+    /// Orders the proposable deploys according to this policy.
+    pub(super) fn order(self, mut proposable: Vec<Proposable>) -> Vec<Proposable> {
+        proposable.sort_by_key(|(with_approvals, footprint)| {
+            (footprint.header.timestamp(), *with_approvals.deploy_hash())
+        });
+        match self {
+            SelectionPolicy::Arrival => proposable,
+            SelectionPolicy::PaymentPriority => {
+                // The sort is stable, so deploys offering equal payments remain in arrival order.
+                proposable.sort_by_key(|(_, footprint)| {
+                    std::cmp::Reverse(
+                        footprint.gas_estimate.value() * U512::from(footprint.header.gas_price()),
+                    )
+                });
+                proposable
+            }
+            SelectionPolicy::RoundRobin => round_robin(proposable),
+            SelectionPolicy::DependencyAware => dependencies_first(proposable),
+        }
+    }
+}
+
+impl Display for SelectionPolicy {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SelectionPolicy::Arrival => write!(formatter, "arrival"),
+            SelectionPolicy::PaymentPriority => write!(formatter, "payment priority"),
+            SelectionPolicy::RoundRobin => write!(formatter, "round robin"),
+            SelectionPolicy::DependencyAware => write!(formatter, "dependency aware"),
+        }
+    }
+}
+
+/// The reason a proposable deploy was not included in a proposed block.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(super) enum SkipReason {
+    /// The deploy's account already had the maximum number of deploys in the block.
+    AccountLimit,
+    /// Not all of the deploy's dependencies had been included in a block.
+    UnmetDependencies,
+    /// A block limit was reached before the deploy could be included.
+    BlockLimit,
+}
+
+impl Display for SkipReason {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SkipReason::AccountLimit => write!(formatter, "account limit"),
+            SkipReason::UnmetDependencies => write!(formatter, "unmet dependencies"),
+            SkipReason::BlockLimit => write!(formatter, "block limit"),
+        }
+    }
+}
+
+/// Interleaves the arrival-ordered deploys of each account, with accounts taking turns in order of
+/// their oldest deploy.
+fn round_robin(proposable: Vec<Proposable>) -> Vec<Proposable> {
+    let count = proposable.len();
+    let mut account_indices: HashMap<PublicKey, usize> = HashMap::new();
+    let mut queues: Vec<VecDeque<Proposable>> = vec![];
+    for item in proposable {
+        let index = *account_indices
+            .entry(item.1.header.account().clone())
+            .or_insert_with(|| {
+                queues.push(VecDeque::new());
+                queues.len() - 1
+            });
+        queues[index].push_back(item);
+    }
+
+    let mut ordered = Vec::with_capacity(count);
+    while ordered.len() < count {
+        ordered.extend(queues.iter_mut().filter_map(VecDeque::pop_front));
+    }
+    ordered
+}
+
+/// Orders the arrival-ordered deploys such that each follows any of its dependencies which are
+/// also proposable.  Deploys with cyclic dependencies are placed last.
+fn dependencies_first(proposable: Vec<Proposable>) -> Vec<Proposable> {
+    let positions: HashMap<_, _> = proposable
+        .iter()
+        .enumerate()
+        .map(|(position, (with_approvals, _))| (*with_approvals.deploy_hash(), position))
+        .collect();
+
+    let mut pending_dependency_counts = vec![0_usize; proposable.len()];
+    let mut dependents: Vec<Vec<usize>> = vec![vec![]; proposable.len()];
+    for (position, (_, footprint)) in proposable.iter().enumerate() {
+        for dependency in footprint.header.dependencies() {
+            if let Some(&dependency_position) = positions.get(dependency) {
+                if dependency_position != position {
+                    pending_dependency_counts[position] += 1;
+                    dependents[dependency_position].push(position);
+                }
+            }
+        }
+    }
+
+    let mut ready: BTreeSet<usize> = pending_dependency_counts
+        .iter()
+        .enumerate()
+        .filter(|(_, count)| **count == 0)
+        .map(|(position, _)| position)
+        .collect();
+    let mut order = Vec::with_capacity(proposable.len());
+    while let Some(position) = ready.pop_first() {
+        order.push(position);
+        for &dependent in &dependents[position] {
+            pending_dependency_counts[dependent] -= 1;
+            if pending_dependency_counts[dependent] == 0 {
+                ready.insert(dependent);
+            }
+        }
+    }
+    order.extend(
+        pending_dependency_counts
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(|(position, _)| position),
+    );
+
+    let mut slots: Vec<_> = proposable.into_iter().map(Some).collect();
+    order
+        .into_iter()
+        .filter_map(|position| slots[position].take())
+        .collect()
+}
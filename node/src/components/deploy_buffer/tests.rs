@@ -5,7 +5,10 @@ use crate::{
     types::{Block, FinalizedBlock},
     utils,
 };
-use casper_types::{testing::TestRng, EraId, TimeDiff};
+use casper_execution_engine::core::engine_state::ExecutableDeployItem;
+use casper_types::{
+    bytesrepr::Bytes, runtime_args, testing::TestRng, EraId, RuntimeArgs, SecretKey, TimeDiff, U512,
+};
 use prometheus::Registry;
 use rand::Rng;
 
@@ -194,7 +197,7 @@ fn get_proposable_deploys() {
 
     // Check which deploys are proposable. Should return the deploys that were not included in the
     // block since those should be dead.
-    let proposable = deploy_buffer.proposable();
+    let proposable = deploy_buffer.proposable(Timestamp::now());
     assert_eq!(proposable.len(), deploys.len());
     let proposable_deploy_hashes: HashSet<_> =
         proposable.iter().map(|(dh, _)| dh.deploy_hash()).collect();
@@ -213,7 +216,7 @@ fn get_proposable_deploys() {
     );
 
     // Check that held blocks are not proposable
-    let proposable = deploy_buffer.proposable();
+    let proposable = deploy_buffer.proposable(Timestamp::now());
     assert_eq!(
         proposable.len(),
         deploys.len() - appendable_block.deploy_and_transfer_set().len()
@@ -500,3 +503,298 @@ async fn expire_deploys_and_check_announcement() {
     // the valid deploys should still be in the buffer
     assert_container_sizes(&deploy_buffer, deploys.len(), 0, 0);
 }
+
+/// Creates a valid non-transfer deploy from the given account, paying `payment_amount` at a gas
+/// price of 1.
+fn create_deploy_from(
+    secret_key: &SecretKey,
+    timestamp: Timestamp,
+    payment_amount: u64,
+    dependencies: Vec<DeployHash>,
+) -> Deploy {
+    let payment = ExecutableDeployItem::ModuleBytes {
+        module_bytes: Bytes::new(),
+        args: runtime_args! { "amount" => U512::from(payment_amount) },
+    };
+    let session = ExecutableDeployItem::StoredContractByName {
+        name: "contract".to_string(),
+        entry_point: "entry_point".to_string(),
+        args: RuntimeArgs::new(),
+    };
+    Deploy::new(
+        timestamp,
+        TimeDiff::from_seconds(3600),
+        1,
+        dependencies,
+        "casper-example".to_string(),
+        payment,
+        session,
+        secret_key,
+        None,
+    )
+}
+
+/// Returns the hashes of the deploys in the block proposed by the deploy buffer, in order.
+fn proposed_deploy_hashes(
+    deploy_buffer: &mut DeployBuffer,
+    timestamp: Timestamp,
+) -> Vec<DeployHash> {
+    deploy_buffer
+        .appendable_block(timestamp)
+        .into_block_payload(vec![], false)
+        .deploys()
+        .iter()
+        .map(|with_approvals| *with_approvals.deploy_hash())
+        .collect()
+}
+
+fn deploy_buffer_with(
+    block_max_deploy_count: u32,
+    selection_policy: SelectionPolicy,
+    max_deploys_per_account: Option<u32>,
+) -> DeployBuffer {
+    let deploy_config = DeployConfig {
+        block_max_deploy_count,
+        ..Default::default()
+    };
+    let config = Config {
+        selection_policy,
+        max_deploys_per_account,
+        ..Default::default()
+    };
+    DeployBuffer::new(deploy_config, config, &Registry::new()).unwrap()
+}
+
+#[test]
+fn should_select_deploys_by_payment_priority() {
+    let mut rng = TestRng::new();
+    let now = Timestamp::now();
+    let mut deploy_buffer = deploy_buffer_with(2, SelectionPolicy::PaymentPriority, None);
+
+    let secret_key = SecretKey::random(&mut rng);
+    let deploys: Vec<_> = [10, 30, 20]
+        .iter()
+        .enumerate()
+        .map(|(index, amount)| {
+            let timestamp = now.saturating_sub(TimeDiff::from_seconds(10 - index as u32));
+            create_deploy_from(&secret_key, timestamp, *amount, vec![])
+        })
+        .collect();
+    deploys
+        .iter()
+        .for_each(|deploy| deploy_buffer.register_deploy(deploy.clone()));
+
+    let proposed = proposed_deploy_hashes(&mut deploy_buffer, now);
+    assert_eq!(proposed, vec![*deploys[1].hash(), *deploys[2].hash()]);
+    assert_eq!(deploy_buffer.metrics.skipped_block_limit.get(), 1);
+    assert_eq!(deploy_buffer.metrics.skipped_account_limit.get(), 0);
+}
+
+#[test]
+fn should_select_deploys_round_robin_across_accounts() {
+    let mut rng = TestRng::new();
+    let now = Timestamp::now();
+    let mut deploy_buffer = deploy_buffer_with(3, SelectionPolicy::RoundRobin, None);
+
+    let busy_account = SecretKey::random(&mut rng);
+    let quiet_account = SecretKey::random(&mut rng);
+    let busy_deploys: Vec<_> = (0..3)
+        .map(|index| {
+            let timestamp = now.saturating_sub(TimeDiff::from_seconds(10 - index));
+            create_deploy_from(&busy_account, timestamp, 10, vec![])
+        })
+        .collect();
+    let quiet_deploy = create_deploy_from(
+        &quiet_account,
+        now.saturating_sub(TimeDiff::from_seconds(1)),
+        10,
+        vec![],
+    );
+    busy_deploys
+        .iter()
+        .chain(std::iter::once(&quiet_deploy))
+        .for_each(|deploy| deploy_buffer.register_deploy(deploy.clone()));
+
+    let proposed = proposed_deploy_hashes(&mut deploy_buffer, now);
+    assert_eq!(
+        proposed,
+        vec![
+            *busy_deploys[0].hash(),
+            *quiet_deploy.hash(),
+            *busy_deploys[1].hash()
+        ]
+    );
+    assert_eq!(deploy_buffer.metrics.skipped_block_limit.get(), 1);
+}
+
+#[test]
+fn should_limit_deploys_per_account() {
+    let mut rng = TestRng::new();
+    let now = Timestamp::now();
+    let mut deploy_buffer = deploy_buffer_with(10, SelectionPolicy::Arrival, Some(1));
+
+    let busy_account = SecretKey::random(&mut rng);
+    let quiet_account = SecretKey::random(&mut rng);
+    let busy_deploys: Vec<_> = (0..3)
+        .map(|index| {
+            let timestamp = now.saturating_sub(TimeDiff::from_seconds(10 - index));
+            create_deploy_from(&busy_account, timestamp, 10, vec![])
+        })
+        .collect();
+    let quiet_deploy = create_deploy_from(
+        &quiet_account,
+        now.saturating_sub(TimeDiff::from_seconds(1)),
+        10,
+        vec![],
+    );
+    busy_deploys
+        .iter()
+        .chain(std::iter::once(&quiet_deploy))
+        .for_each(|deploy| deploy_buffer.register_deploy(deploy.clone()));
+
+    let proposed = proposed_deploy_hashes(&mut deploy_buffer, now);
+    assert_eq!(
+        proposed,
+        vec![*busy_deploys[0].hash(), *quiet_deploy.hash()]
+    );
+    assert_eq!(deploy_buffer.metrics.skipped_account_limit.get(), 2);
+    assert_eq!(deploy_buffer.metrics.skipped_block_limit.get(), 0);
+}
+
+#[test]
+fn should_select_dependencies_first() {
+    let mut rng = TestRng::new();
+    let now = Timestamp::now();
+    let mut deploy_buffer = deploy_buffer_with(1, SelectionPolicy::DependencyAware, None);
+
+    let secret_key = SecretKey::random(&mut rng);
+    let dependency = create_deploy_from(
+        &secret_key,
+        now.saturating_sub(TimeDiff::from_seconds(1)),
+        10,
+        vec![],
+    );
+    // Older than its dependency, so would be proposed first by every other policy.
+    let dependent = create_deploy_from(
+        &secret_key,
+        now.saturating_sub(TimeDiff::from_seconds(10)),
+        10,
+        vec![*dependency.hash()],
+    );
+    deploy_buffer.register_deploy(dependent.clone());
+    deploy_buffer.register_deploy(dependency.clone());
+
+    let proposed = proposed_deploy_hashes(&mut deploy_buffer, now);
+    assert_eq!(proposed, vec![*dependency.hash()]);
+    assert_eq!(deploy_buffer.metrics.skipped_block_limit.get(), 1);
+
+    // The dependency is only held in an in-flight proposal, so the dependent must wait.
+    let proposed = proposed_deploy_hashes(&mut deploy_buffer, now);
+    assert!(proposed.is_empty());
+    assert_eq!(deploy_buffer.metrics.skipped_unmet_dependencies.get(), 1);
+
+    // Once the dependency is finalized, the dependent becomes proposable.
+    let block = FinalizedBlock::random_with_deploys(&mut rng, std::iter::once(&dependency));
+    deploy_buffer.register_block_finalized(&block);
+    let proposed = proposed_deploy_hashes(&mut deploy_buffer, now);
+    assert_eq!(proposed, vec![*dependent.hash()]);
+}
+
+#[test]
+fn should_list_pending_deploys() {
+    let mut rng = TestRng::new();
+    let now = Timestamp::now();
+    let mut deploy_buffer = deploy_buffer_with(1, SelectionPolicy::Arrival, None);
+
+    let account = SecretKey::random(&mut rng);
+    let other_account = SecretKey::random(&mut rng);
+    let held = create_deploy_from(
+        &account,
+        now.saturating_sub(TimeDiff::from_seconds(10)),
+        10,
+        vec![],
+    );
+    let proposable = create_deploy_from(
+        &account,
+        now.saturating_sub(TimeDiff::from_seconds(5)),
+        10,
+        vec![],
+    );
+    let other = create_deploy_from(
+        &other_account,
+        now.saturating_sub(TimeDiff::from_seconds(1)),
+        10,
+        vec![],
+    );
+    for deploy in [&held, &proposable, &other] {
+        deploy_buffer.register_deploy(deploy.clone());
+    }
+
+    // A deploy only known from a finalized block has no footprint, and is dead.
+    let finalized = create_valid_deploys(&mut rng, 1, DeployType::Random, None, None);
+    let block = FinalizedBlock::random_with_deploys(&mut rng, finalized.iter());
+    deploy_buffer.register_block_finalized(&block);
+
+    let proposed = proposed_deploy_hashes(&mut deploy_buffer, now);
+    assert_eq!(proposed, vec![*held.hash()]);
+
+    let all = deploy_buffer.pending_deploys(None, now);
+    assert_eq!(all.len(), 4);
+    assert!(all
+        .windows(2)
+        .all(|pair| pair[0].deploy_hash < pair[1].deploy_hash));
+    let status_of = |deploy_hash: &DeployHash| {
+        all.iter()
+            .find(|pending| pending.deploy_hash == *deploy_hash)
+            .map(|pending| (pending.status, pending.footprint.is_some()))
+            .unwrap()
+    };
+    assert_eq!(status_of(held.hash()), (PendingDeployStatus::Held, true));
+    assert_eq!(
+        status_of(proposable.hash()),
+        (PendingDeployStatus::Proposable, true)
+    );
+    assert_eq!(
+        status_of(finalized[0].hash()),
+        (PendingDeployStatus::Dead, false)
+    );
+    let remaining = all
+        .iter()
+        .find(|pending| pending.deploy_hash == *held.hash())
+        .unwrap()
+        .ttl_remaining;
+    assert_eq!(remaining, TimeDiff::from_seconds(3590));
+
+    let account_hash = PublicKey::from(&account).to_account_hash();
+    let of_account: HashSet<_> = deploy_buffer
+        .pending_deploys(Some(account_hash), now)
+        .into_iter()
+        .map(|pending| pending.deploy_hash)
+        .collect();
+    assert_eq!(
+        of_account,
+        HashSet::from([*held.hash(), *proposable.hash()])
+    );
+}
+
+#[test]
+fn should_not_propose_scheduled_deploys_before_their_timestamp() {
+    let mut rng = TestRng::new();
+    let now = Timestamp::now();
+    let mut deploy_buffer = deploy_buffer_with(10, SelectionPolicy::Arrival, None);
+
+    let secret_key = SecretKey::random(&mut rng);
+    let scheduled_at = now + TimeDiff::from_seconds(60);
+    let scheduled = create_deploy_from(&secret_key, scheduled_at, 10, vec![]);
+    deploy_buffer.register_deploy(scheduled.clone());
+
+    // The deploy is neither proposed nor marked dead while its timestamp is in the future.
+    assert!(proposed_deploy_hashes(&mut deploy_buffer, now).is_empty());
+    assert!(!deploy_buffer.dead.contains(scheduled.hash()));
+    let pending = deploy_buffer.pending_deploys(None, now);
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].status, PendingDeployStatus::Scheduled);
+
+    let proposed = proposed_deploy_hashes(&mut deploy_buffer, scheduled_at);
+    assert_eq!(proposed, vec![*scheduled.hash()]);
+}
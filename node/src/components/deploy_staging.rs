@@ -0,0 +1,434 @@
+//! Staging area for deploys of multi-signature accounts.
+//!
+//! A deploy of an account whose deployment threshold exceeds the weight of any single associated
+//! key can't be accepted until enough of those keys have approved it.  The deploy staging area
+//! holds such deploys while their approvals are collected, checking the accumulated weight against
+//! the account's deployment threshold in the global state of the highest complete block.  Once the
+//! threshold is met, the deploy is handed to the `DeployAcceptor` and leaves the staging area.
+
+mod event;
+#[cfg(test)]
+mod tests;
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::Arc,
+};
+
+use datasize::DataSize;
+use thiserror::Error;
+use tracing::{debug, trace};
+
+use casper_types::{
+    account::{Account, AccountHash},
+    crypto, Key, PublicKey, TimeDiff, Timestamp,
+};
+
+use crate::{
+    components::{deploy_acceptor, Component},
+    effect::{
+        requests::{
+            AcceptDeployRequest, ContractRuntimeRequest, DeployStagingRequest, StorageRequest,
+        },
+        EffectBuilder, EffectExt, Effects, Responder,
+    },
+    types::{
+        chainspec::DeployConfig, Approval, BlockHeader, Chainspec, Deploy,
+        DeployConfigurationFailure, DeployHash, StagedDeploy, StagedDeployStatus,
+    },
+    NodeRng,
+};
+
+pub(crate) use event::Event;
+
+const COMPONENT_NAME: &str = "deploy_staging";
+
+/// The maximum number of deploys held in the staging area at any one time.
+const MAX_STAGED_DEPLOYS: usize = 1_000;
+/// The maximum number of deploys of a single account held in the staging area at any one time.
+const MAX_STAGED_DEPLOYS_PER_ACCOUNT: usize = 20;
+
+/// An error returned by the deploy staging area.
+#[derive(Debug, Error)]
+pub(crate) enum Error {
+    /// The deploy is invalid due to failing to meet the deploy configuration.
+    #[error("invalid deploy: {0}")]
+    InvalidDeploy(DeployConfigurationFailure),
+
+    /// The deploy has expired.
+    #[error("deploy expired at {deploy_expiry_timestamp}")]
+    ExpiredDeploy { deploy_expiry_timestamp: Timestamp },
+
+    /// The block chain has no blocks.
+    #[error("block chain has no blocks")]
+    EmptyBlockchain,
+
+    /// The deploy's account does not exist.
+    #[error("account with hash {account_hash} does not exist")]
+    NonexistentAccount { account_hash: AccountHash },
+
+    /// One of the approvals is not by an associated key of the deploy's account.
+    #[error("account authorization invalid")]
+    InvalidAssociatedKeys,
+
+    /// One of the approvals is not a valid signature of the deploy hash.
+    #[error("invalid approval by {signer}: {error_msg}")]
+    InvalidApproval {
+        signer: Box<PublicKey>,
+        error_msg: String,
+    },
+
+    /// The deploy is not held in the staging area.
+    #[error("deploy {deploy_hash} is not staged")]
+    UnknownDeploy { deploy_hash: DeployHash },
+
+    /// The staging area is full.
+    #[error("the deploy staging area is full")]
+    TooManyStagedDeploys,
+
+    /// The staging area holds the maximum number of deploys of the deploy's account.
+    #[error("too many staged deploys of account with hash {account_hash}")]
+    TooManyStagedDeploysForAccount { account_hash: AccountHash },
+
+    /// The deploy's approvals met the deployment threshold, but the deploy was rejected by the
+    /// `DeployAcceptor`.
+    #[error(transparent)]
+    Rejected(deploy_acceptor::Error),
+}
+
+/// A helper trait constraining `DeployStaging` compatible reactor events.
+pub(crate) trait ReactorEventT:
+    From<Event> + From<StorageRequest> + From<ContractRuntimeRequest> + From<AcceptDeployRequest> + Send
+{
+}
+
+impl<REv> ReactorEventT for REv where
+    REv: From<Event>
+        + From<StorageRequest>
+        + From<ContractRuntimeRequest>
+        + From<AcceptDeployRequest>
+        + Send
+{
+}
+
+/// Collects the approvals of deploys until they reach the deployment threshold of their account,
+/// then forwards them to the `DeployAcceptor`.
+#[derive(Debug, DataSize)]
+pub(crate) struct DeployStaging {
+    chain_name: String,
+    deploy_config: DeployConfig,
+    max_associated_keys: u32,
+    timestamp_leeway: TimeDiff,
+    staged: HashMap<DeployHash, StagedDeploy>,
+}
+
+impl DeployStaging {
+    pub(crate) fn new(chainspec: &Chainspec, timestamp_leeway: TimeDiff) -> Self {
+        DeployStaging {
+            chain_name: chainspec.network_config.name.clone(),
+            deploy_config: chainspec.deploy_config,
+            max_associated_keys: chainspec.core_config.max_associated_keys,
+            timestamp_leeway,
+            staged: HashMap::new(),
+        }
+    }
+
+    fn handle_request<REv: ReactorEventT>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        request: DeployStagingRequest,
+    ) -> Effects<Event> {
+        let now = Timestamp::now();
+        self.prune_expired(now);
+        match request {
+            DeployStagingRequest::StageDeploy { deploy, responder } => {
+                match self.merge_approvals(*deploy, now) {
+                    Ok(deploy) => Self::get_block_header(effect_builder, deploy, responder),
+                    Err(error) => responder.respond(Err(error)).ignore(),
+                }
+            }
+            DeployStagingRequest::AddApprovals {
+                deploy_hash,
+                approvals,
+                responder,
+            } => {
+                let deploy = match self.staged.get(&deploy_hash) {
+                    Some(staged) => staged.deploy.clone().with_approvals(approvals),
+                    None => {
+                        return responder
+                            .respond(Err(Error::UnknownDeploy { deploy_hash }))
+                            .ignore()
+                    }
+                };
+                match self.merge_approvals(deploy, now) {
+                    Ok(deploy) => Self::get_block_header(effect_builder, deploy, responder),
+                    Err(error) => responder.respond(Err(error)).ignore(),
+                }
+            }
+            DeployStagingRequest::GetStagedDeploys {
+                account_hash,
+                responder,
+            } => responder
+                .respond(self.staged_deploys(account_hash))
+                .ignore(),
+        }
+    }
+
+    fn get_block_header<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        deploy: Deploy,
+        responder: Responder<Result<StagedDeploy, Error>>,
+    ) -> Effects<Event> {
+        effect_builder
+            .get_highest_complete_block_header_from_storage()
+            .event(move |maybe_block_header| Event::GetBlockHeaderResult {
+                deploy: Box::new(deploy),
+                maybe_block_header: maybe_block_header.map(Box::new),
+                responder,
+            })
+    }
+
+    fn handle_get_block_header_result<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        deploy: Box<Deploy>,
+        maybe_block_header: Option<Box<BlockHeader>>,
+        responder: Responder<Result<StagedDeploy, Error>>,
+    ) -> Effects<Event> {
+        let block_header = match maybe_block_header {
+            Some(block_header) => block_header,
+            None => return responder.respond(Err(Error::EmptyBlockchain)).ignore(),
+        };
+        let account_key = Key::from(deploy.header().account().to_account_hash());
+        effect_builder
+            .get_account_from_global_state(*block_header.state_root_hash(), account_key)
+            .event(move |maybe_account| Event::GetAccountResult {
+                deploy,
+                maybe_account: maybe_account.map(Box::new),
+                responder,
+            })
+    }
+
+    fn handle_get_account_result<REv: ReactorEventT>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        deploy: Box<Deploy>,
+        maybe_account: Option<Box<Account>>,
+        responder: Responder<Result<StagedDeploy, Error>>,
+    ) -> Effects<Event> {
+        let account = match maybe_account {
+            Some(account) => account,
+            None => {
+                let account_hash = deploy.header().account().to_account_hash();
+                return responder
+                    .respond(Err(Error::NonexistentAccount { account_hash }))
+                    .ignore();
+            }
+        };
+        let staged_deploy = match self.update(*deploy, &account) {
+            Ok(staged_deploy) => staged_deploy,
+            Err(error) => return responder.respond(Err(error)).ignore(),
+        };
+        if staged_deploy.approval_weight < staged_deploy.deployment_threshold {
+            return responder.respond(Ok(staged_deploy)).ignore();
+        }
+
+        debug!(%staged_deploy, "deployment threshold met, forwarding staged deploy");
+        effect_builder
+            .try_accept_deploy(Arc::new(staged_deploy.deploy.clone()), None)
+            .event(move |result| Event::AcceptDeployResult {
+                staged_deploy: Box::new(staged_deploy),
+                result,
+                responder,
+            })
+    }
+
+    fn handle_accept_deploy_result(
+        mut staged_deploy: StagedDeploy,
+        result: Result<(), deploy_acceptor::Error>,
+        responder: Responder<Result<StagedDeploy, Error>>,
+    ) -> Effects<Event> {
+        let response = match result {
+            Ok(()) => {
+                staged_deploy.status = StagedDeployStatus::Accepted;
+                Ok(staged_deploy)
+            }
+            Err(error) => {
+                debug!(deploy_hash = %staged_deploy.deploy.hash(), %error, "staged deploy rejected");
+                Err(Error::Rejected(error))
+            }
+        };
+        responder.respond(response).ignore()
+    }
+
+    /// Checks the given deploy and its approvals, and merges them with the approvals already
+    /// collected for the same deploy.
+    fn merge_approvals(&self, deploy: Deploy, now: Timestamp) -> Result<Deploy, Error> {
+        deploy.has_valid_hash().map_err(Error::InvalidDeploy)?;
+        if deploy.header().expired(now) {
+            return Err(Error::ExpiredDeploy {
+                deploy_expiry_timestamp: deploy.header().expires(),
+            });
+        }
+        if deploy.approvals().is_empty() {
+            return Err(Error::InvalidDeploy(
+                DeployConfigurationFailure::EmptyApprovals,
+            ));
+        }
+        for approval in deploy.approvals() {
+            crypto::verify(deploy.hash(), approval.signature(), approval.signer()).map_err(
+                |error| Error::InvalidApproval {
+                    signer: Box::new(approval.signer().clone()),
+                    error_msg: error.to_string(),
+                },
+            )?;
+        }
+
+        let approvals: BTreeSet<Approval> = match self.staged.get(deploy.hash()) {
+            Some(staged) => staged
+                .deploy
+                .approvals()
+                .union(deploy.approvals())
+                .cloned()
+                .collect(),
+            None => deploy.approvals().clone(),
+        };
+        let deploy = deploy.with_approvals(approvals);
+        deploy
+            .is_config_compliant(
+                &self.chain_name,
+                &self.deploy_config,
+                self.max_associated_keys,
+                self.timestamp_leeway,
+                now,
+            )
+            .map_err(Error::InvalidDeploy)?;
+        Ok(deploy)
+    }
+
+    /// Weighs the approvals of the given deploy against the keys of its account.
+    ///
+    /// If the deployment threshold is met, the deploy leaves the staging area, otherwise it is
+    /// (re-)staged with all approvals collected so far.  A new deploy only counts against the
+    /// limits of the staging area once its approvals are known to be by keys of its account.
+    fn update(&mut self, deploy: Deploy, account: &Account) -> Result<StagedDeploy, Error> {
+        // Approvals may have been added while the account was being read.
+        let deploy = match self.staged.get(deploy.hash()) {
+            Some(staged) => {
+                let approvals = staged
+                    .deploy
+                    .approvals()
+                    .union(deploy.approvals())
+                    .cloned()
+                    .collect();
+                deploy.with_approvals(approvals)
+            }
+            None => deploy,
+        };
+
+        let authorization_keys: BTreeSet<AccountHash> = deploy
+            .approvals()
+            .iter()
+            .map(|approval| approval.signer().to_account_hash())
+            .collect();
+        if !account.can_authorize(&authorization_keys) {
+            debug!(?authorization_keys, "account authorization invalid");
+            return Err(Error::InvalidAssociatedKeys);
+        }
+
+        let staged_deploy = StagedDeploy {
+            approval_weight: account
+                .associated_keys()
+                .calculate_keys_weight(&authorization_keys)
+                .value(),
+            deployment_threshold: account.action_thresholds().deployment().value(),
+            status: StagedDeployStatus::AwaitingApprovals,
+            deploy,
+        };
+        let deploy_hash = *staged_deploy.deploy.hash();
+        if staged_deploy.approval_weight >= staged_deploy.deployment_threshold {
+            self.staged.remove(&deploy_hash);
+        } else {
+            if !self.staged.contains_key(&deploy_hash) {
+                let account_hash = account.account_hash();
+                if self.staged_deploy_count(account_hash) >= MAX_STAGED_DEPLOYS_PER_ACCOUNT {
+                    return Err(Error::TooManyStagedDeploysForAccount { account_hash });
+                }
+                if self.staged.len() >= MAX_STAGED_DEPLOYS {
+                    return Err(Error::TooManyStagedDeploys);
+                }
+            }
+            self.staged.insert(deploy_hash, staged_deploy.clone());
+        }
+        Ok(staged_deploy)
+    }
+
+    /// Returns the number of staged deploys of the given account.
+    fn staged_deploy_count(&self, account_hash: AccountHash) -> usize {
+        self.staged
+            .values()
+            .filter(|staged| staged.deploy.header().account().to_account_hash() == account_hash)
+            .count()
+    }
+
+    /// Returns the staged deploys of the given account, in ascending order of deploy hash.
+    fn staged_deploys(&self, account_hash: AccountHash) -> Vec<StagedDeploy> {
+        let mut staged_deploys: Vec<StagedDeploy> = self
+            .staged
+            .values()
+            .filter(|staged| staged.deploy.header().account().to_account_hash() == account_hash)
+            .cloned()
+            .collect();
+        staged_deploys.sort_by(|a, b| a.deploy.hash().cmp(b.deploy.hash()));
+        staged_deploys
+    }
+
+    fn prune_expired(&mut self, now: Timestamp) {
+        self.staged.retain(|deploy_hash, staged| {
+            let expired = staged.deploy.header().expired(now);
+            if expired {
+                debug!(%deploy_hash, "dropping expired staged deploy");
+            }
+            !expired
+        });
+    }
+}
+
+impl<REv: ReactorEventT> Component<REv> for DeployStaging {
+    type Event = Event;
+
+    fn handle_event(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        _rng: &mut NodeRng,
+        event: Self::Event,
+    ) -> Effects<Self::Event> {
+        trace!(?event, "DeployStaging: handling event");
+        match event {
+            Event::Request(request) => self.handle_request(effect_builder, request),
+            Event::GetBlockHeaderResult {
+                deploy,
+                maybe_block_header,
+                responder,
+            } => Self::handle_get_block_header_result(
+                effect_builder,
+                deploy,
+                maybe_block_header,
+                responder,
+            ),
+            Event::GetAccountResult {
+                deploy,
+                maybe_account,
+                responder,
+            } => self.handle_get_account_result(effect_builder, deploy, maybe_account, responder),
+            Event::AcceptDeployResult {
+                staged_deploy,
+                result,
+                responder,
+            } => Self::handle_accept_deploy_result(*staged_deploy, result, responder),
+        }
+    }
+
+    fn name(&self) -> &str {
+        COMPONENT_NAME
+    }
+}
@@ -0,0 +1,104 @@
+use std::fmt::{self, Display, Formatter};
+
+use derive_more::From;
+
+use casper_types::account::Account;
+
+use super::Error;
+use crate::{
+    components::deploy_acceptor,
+    effect::{requests::DeployStagingRequest, Responder},
+    types::{BlockHeader, Deploy, StagedDeploy},
+};
+
+/// `DeployStaging` events.
+#[derive(Debug, From)]
+pub(crate) enum Event {
+    /// A request to the deploy staging area.
+    #[from]
+    Request(DeployStagingRequest),
+    /// The result of querying the highest complete `BlockHeader` from the storage component.
+    GetBlockHeaderResult {
+        deploy: Box<Deploy>,
+        maybe_block_header: Option<Box<BlockHeader>>,
+        responder: Responder<Result<StagedDeploy, Error>>,
+    },
+    /// The result of querying global state for the `Account` associated with the `Deploy`.
+    GetAccountResult {
+        deploy: Box<Deploy>,
+        maybe_account: Option<Box<Account>>,
+        responder: Responder<Result<StagedDeploy, Error>>,
+    },
+    /// The result of the `DeployAcceptor` handling a deploy whose approvals met the deployment
+    /// threshold.
+    AcceptDeployResult {
+        staged_deploy: Box<StagedDeploy>,
+        result: Result<(), deploy_acceptor::Error>,
+        responder: Responder<Result<StagedDeploy, Error>>,
+    },
+}
+
+impl Display for Event {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::Request(request) => write!(formatter, "{}", request),
+            Event::GetBlockHeaderResult {
+                deploy,
+                maybe_block_header,
+                ..
+            } => {
+                if maybe_block_header.is_some() {
+                    write!(
+                        formatter,
+                        "received highest block from storage to stage deploy {}",
+                        deploy.hash()
+                    )
+                } else {
+                    write!(
+                        formatter,
+                        "failed to get highest block from storage to stage deploy {}",
+                        deploy.hash()
+                    )
+                }
+            }
+            Event::GetAccountResult {
+                deploy,
+                maybe_account,
+                ..
+            } => {
+                if maybe_account.is_some() {
+                    write!(
+                        formatter,
+                        "verifying approvals of staged deploy {}",
+                        deploy.hash()
+                    )
+                } else {
+                    write!(
+                        formatter,
+                        "nonexistent account for staged deploy {}",
+                        deploy.hash()
+                    )
+                }
+            }
+            Event::AcceptDeployResult {
+                staged_deploy,
+                result,
+                ..
+            } => {
+                if result.is_ok() {
+                    write!(
+                        formatter,
+                        "accepted staged deploy {}",
+                        staged_deploy.deploy.hash()
+                    )
+                } else {
+                    write!(
+                        formatter,
+                        "rejected staged deploy {}",
+                        staged_deploy.deploy.hash()
+                    )
+                }
+            }
+        }
+    }
+}
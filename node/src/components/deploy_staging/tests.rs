@@ -0,0 +1,260 @@
+use std::collections::BTreeMap;
+
+use rand::Rng;
+
+use casper_execution_engine::core::engine_state::ExecutableDeployItem;
+use casper_types::{
+    account::{ActionThresholds, AssociatedKeys, Weight},
+    bytesrepr::Bytes,
+    runtime_args,
+    testing::TestRng,
+    RuntimeArgs, SecretKey, URef, U512,
+};
+
+use super::*;
+
+const CHAIN_NAME: &str = "casper-example";
+
+fn new_deploy_staging() -> DeployStaging {
+    DeployStaging {
+        chain_name: CHAIN_NAME.to_string(),
+        deploy_config: DeployConfig::default(),
+        max_associated_keys: 100,
+        timestamp_leeway: TimeDiff::from_seconds(2),
+        staged: HashMap::new(),
+    }
+}
+
+/// Returns an account with one associated key of weight 1 per secret key, and a deployment
+/// threshold of `threshold`.
+fn new_account(account_key: &SecretKey, keys: &[&SecretKey], threshold: u8) -> Account {
+    let account_hash = PublicKey::from(account_key).to_account_hash();
+    let mut associated_keys = AssociatedKeys::new(account_hash, Weight::new(1));
+    for secret_key in keys {
+        associated_keys
+            .add_key(
+                PublicKey::from(*secret_key).to_account_hash(),
+                Weight::new(1),
+            )
+            .unwrap();
+    }
+    let action_thresholds =
+        ActionThresholds::new(Weight::new(threshold), Weight::new(threshold)).unwrap();
+    Account::new(
+        account_hash,
+        BTreeMap::new(),
+        URef::default(),
+        associated_keys,
+        action_thresholds,
+    )
+}
+
+/// Returns a native transfer of the account of `account_key`, signed by `signer`.
+fn new_deploy(rng: &mut TestRng, account_key: &SecretKey, signer: &SecretKey) -> Deploy {
+    let payment = ExecutableDeployItem::ModuleBytes {
+        module_bytes: Bytes::new(),
+        args: runtime_args! { "amount" => U512::from(10) },
+    };
+    let session = ExecutableDeployItem::Transfer {
+        args: runtime_args! {
+            "amount" => U512::from(rng.gen::<u64>()),
+            "target" => PublicKey::random(rng).to_account_hash(),
+        },
+    };
+    Deploy::new(
+        Timestamp::now(),
+        TimeDiff::from_seconds(300),
+        1,
+        vec![],
+        CHAIN_NAME.to_string(),
+        payment,
+        session,
+        signer,
+        Some(PublicKey::from(account_key)),
+    )
+}
+
+#[test]
+fn should_stage_deploy_until_threshold_met() {
+    let mut rng = TestRng::new();
+    let account_key = SecretKey::random(&mut rng);
+    let key_1 = SecretKey::random(&mut rng);
+    let key_2 = SecretKey::random(&mut rng);
+    let account = new_account(&account_key, &[&key_1, &key_2], 2);
+    let account_hash = account.account_hash();
+    let mut deploy_staging = new_deploy_staging();
+
+    let deploy = new_deploy(&mut rng, &account_key, &key_1);
+    let deploy_hash = *deploy.hash();
+    let deploy = deploy_staging
+        .merge_approvals(deploy, Timestamp::now())
+        .unwrap();
+    let staged_deploy = deploy_staging.update(deploy, &account).unwrap();
+    assert_eq!(staged_deploy.approval_weight, 1);
+    assert_eq!(staged_deploy.deployment_threshold, 2);
+    assert_eq!(staged_deploy.status, StagedDeployStatus::AwaitingApprovals);
+    assert_eq!(
+        deploy_staging.staged_deploys(account_hash),
+        vec![staged_deploy.clone()]
+    );
+    assert!(deploy_staging
+        .staged_deploys(PublicKey::random(&mut rng).to_account_hash())
+        .is_empty());
+
+    // Adding the approval of a second key meets the threshold and unstages the deploy.
+    let approval = Approval::create(&deploy_hash, &key_2);
+    let deploy = staged_deploy
+        .deploy
+        .with_approvals(std::iter::once(approval).collect());
+    let deploy = deploy_staging
+        .merge_approvals(deploy, Timestamp::now())
+        .unwrap();
+    assert_eq!(deploy.approvals().len(), 2);
+    let staged_deploy = deploy_staging.update(deploy, &account).unwrap();
+    assert_eq!(staged_deploy.approval_weight, 2);
+    assert!(deploy_staging.staged_deploys(account_hash).is_empty());
+}
+
+#[test]
+fn should_reject_invalid_approval() {
+    let mut rng = TestRng::new();
+    let account_key = SecretKey::random(&mut rng);
+    let key_1 = SecretKey::random(&mut rng);
+    let deploy_staging = new_deploy_staging();
+
+    let mut deploy = new_deploy(&mut rng, &account_key, &key_1);
+    let other_deploy = new_deploy(&mut rng, &account_key, &key_1);
+    let bogus_approval = Approval::create(other_deploy.hash(), &account_key);
+    let mut approvals = deploy.approvals().clone();
+    approvals.insert(bogus_approval);
+    deploy.replace_approvals(approvals);
+
+    assert!(matches!(
+        deploy_staging.merge_approvals(deploy, Timestamp::now()),
+        Err(Error::InvalidApproval { .. })
+    ));
+}
+
+#[test]
+fn should_reject_approval_by_unassociated_key() {
+    let mut rng = TestRng::new();
+    let account_key = SecretKey::random(&mut rng);
+    let key_1 = SecretKey::random(&mut rng);
+    let stranger = SecretKey::random(&mut rng);
+    let account = new_account(&account_key, &[&key_1], 2);
+    let mut deploy_staging = new_deploy_staging();
+
+    let deploy = new_deploy(&mut rng, &account_key, &stranger);
+    let deploy = deploy_staging
+        .merge_approvals(deploy, Timestamp::now())
+        .unwrap();
+    assert!(matches!(
+        deploy_staging.update(deploy, &account),
+        Err(Error::InvalidAssociatedKeys)
+    ));
+    assert!(deploy_staging
+        .staged_deploys(account.account_hash())
+        .is_empty());
+}
+
+#[test]
+fn should_prune_expired_deploys() {
+    let mut rng = TestRng::new();
+    let account_key = SecretKey::random(&mut rng);
+    let key_1 = SecretKey::random(&mut rng);
+    let account = new_account(&account_key, &[&key_1], 2);
+    let mut deploy_staging = new_deploy_staging();
+
+    let deploy = new_deploy(&mut rng, &account_key, &key_1);
+    let expiry = deploy.header().expires();
+    deploy_staging.update(deploy, &account).unwrap();
+    assert_eq!(
+        deploy_staging.staged_deploys(account.account_hash()).len(),
+        1
+    );
+
+    deploy_staging.prune_expired(expiry);
+    assert_eq!(
+        deploy_staging.staged_deploys(account.account_hash()).len(),
+        1
+    );
+    deploy_staging.prune_expired(expiry + TimeDiff::from_millis(1));
+    assert!(deploy_staging
+        .staged_deploys(account.account_hash())
+        .is_empty());
+}
+
+#[test]
+fn should_reject_new_deploys_once_full() {
+    let mut rng = TestRng::new();
+    let mut deploy_staging = new_deploy_staging();
+
+    for _ in 0..MAX_STAGED_DEPLOYS / MAX_STAGED_DEPLOYS_PER_ACCOUNT {
+        let account_key = SecretKey::random(&mut rng);
+        let account = new_account(&account_key, &[], 2);
+        for _ in 0..MAX_STAGED_DEPLOYS_PER_ACCOUNT {
+            let deploy = new_deploy(&mut rng, &account_key, &account_key);
+            deploy_staging.update(deploy, &account).unwrap();
+        }
+    }
+
+    let account_key = SecretKey::random(&mut rng);
+    let key_1 = SecretKey::random(&mut rng);
+    let account = new_account(&account_key, &[&key_1], 2);
+    let deploy = new_deploy(&mut rng, &account_key, &key_1);
+    let deploy = deploy_staging
+        .merge_approvals(deploy, Timestamp::now())
+        .unwrap();
+    assert!(matches!(
+        deploy_staging.update(deploy.clone(), &account),
+        Err(Error::TooManyStagedDeploys)
+    ));
+
+    // A deploy meeting its threshold doesn't need staging, so is accepted even when full.
+    let mut approvals = deploy.approvals().clone();
+    approvals.insert(Approval::create(deploy.hash(), &account_key));
+    let deploy = deploy.with_approvals(approvals);
+    let deploy = deploy_staging
+        .merge_approvals(deploy, Timestamp::now())
+        .unwrap();
+    let staged_deploy = deploy_staging.update(deploy, &account).unwrap();
+    assert_eq!(staged_deploy.approval_weight, 2);
+}
+
+#[test]
+fn should_limit_staged_deploys_per_account() {
+    let mut rng = TestRng::new();
+    let account_key = SecretKey::random(&mut rng);
+    let key_1 = SecretKey::random(&mut rng);
+    let account = new_account(&account_key, &[&key_1], 2);
+    let stranger = SecretKey::random(&mut rng);
+    let mut deploy_staging = new_deploy_staging();
+
+    for _ in 0..MAX_STAGED_DEPLOYS_PER_ACCOUNT {
+        let deploy = new_deploy(&mut rng, &account_key, &key_1);
+        deploy_staging.update(deploy, &account).unwrap();
+    }
+
+    // Approvals by keys not associated with the account are rejected before being counted.
+    let deploy = new_deploy(&mut rng, &account_key, &stranger);
+    assert!(matches!(
+        deploy_staging.update(deploy, &account),
+        Err(Error::InvalidAssociatedKeys)
+    ));
+    let deploy = new_deploy(&mut rng, &account_key, &key_1);
+    assert!(matches!(
+        deploy_staging.update(deploy, &account),
+        Err(Error::TooManyStagedDeploysForAccount { account_hash })
+            if account_hash == account.account_hash()
+    ));
+
+    // Other accounts may still stage deploys.
+    let other_key = SecretKey::random(&mut rng);
+    let other_account = new_account(&other_key, &[&key_1], 2);
+    let deploy = new_deploy(&mut rng, &other_key, &key_1);
+    deploy_staging.update(deploy, &other_account).unwrap();
+    assert_eq!(
+        deploy_staging.staged_deploys(account.account_hash()).len(),
+        MAX_STAGED_DEPLOYS_PER_ACCOUNT
+    );
+}
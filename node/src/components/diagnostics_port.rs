@@ -25,7 +25,7 @@ use crate::{
     effect::{
         announcements::ControlAnnouncement,
         diagnostics_port::DumpConsensusStateRequest,
-        requests::{NetworkInfoRequest, SetNodeStopRequest},
+        requests::{DeployBufferRequest, NetworkInfoRequest, SetNodeStopRequest},
         EffectBuilder, EffectExt, Effects,
     },
     reactor::main_reactor::MainEvent,
@@ -107,6 +107,7 @@ where
         + From<DumpConsensusStateRequest>
         + From<ControlAnnouncement>
         + From<NetworkInfoRequest>
+        + From<DeployBufferRequest>
         + From<SetNodeStopRequest>
         + Send,
 {
@@ -161,6 +162,7 @@ where
         + From<DumpConsensusStateRequest>
         + From<ControlAnnouncement>
         + From<NetworkInfoRequest>
+        + From<DeployBufferRequest>
         + From<SetNodeStopRequest>
         + Send,
 {
@@ -185,6 +187,7 @@ where
         + From<DumpConsensusStateRequest>
         + From<ControlAnnouncement>
         + From<NetworkInfoRequest>
+        + From<DeployBufferRequest>
         + From<SetNodeStopRequest>
         + Send,
 {
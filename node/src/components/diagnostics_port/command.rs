@@ -7,6 +7,8 @@ use serde::Serialize;
 use structopt::StructOpt;
 use thiserror::Error;
 
+use casper_types::{account::AccountHash, AsymmetricType, PublicKey};
+
 use super::StopAtSpec;
 
 /// Command processing error.
@@ -89,6 +91,13 @@ pub(super) enum Action {
     },
     /// Dump the event queues.
     DumpQueues,
+    /// Dump the deploys held in the deploy buffer.
+    DumpDeployBuffer {
+        /// Only dump the deploys of this account, given as a hex-encoded public key or a formatted
+        /// account hash (`account-hash-...`).
+        #[structopt(short, long, parse(try_from_str = parse_account_hash))]
+        account: Option<AccountHash>,
+    },
     /// Get detailed networking insights.
     NetInfo,
     /// Stop the node at a certain condition.
@@ -135,6 +144,16 @@ pub(super) enum Action {
     Quit,
 }
 
+/// Parses an account hash from either its formatted string or a hex-encoded public key.
+fn parse_account_hash(input: &str) -> Result<AccountHash, String> {
+    if let Ok(account_hash) = AccountHash::from_formatted_str(input) {
+        return Ok(account_hash);
+    }
+    PublicKey::from_hex(input)
+        .map(|public_key| public_key.to_account_hash())
+        .map_err(|_| "invalid account, must be a hex-encoded public key or an account hash".into())
+}
+
 /// A command to be performed on the node's diagnostic port.
 #[derive(Debug, StructOpt)]
 pub(super) struct Command {
@@ -153,6 +172,8 @@ impl Command {
 
 #[cfg(test)]
 mod tests {
+    use casper_types::{AsymmetricType, PublicKey};
+
     use crate::components::diagnostics_port::command::{Action, Command};
 
     #[test]
@@ -162,5 +183,21 @@ mod tests {
 
         let cmd = Command::from_line("dump-queues").expect("command parsing failed");
         assert!(matches!(cmd.action, Action::DumpQueues));
+
+        let cmd = Command::from_line("dump-deploy-buffer").expect("command parsing failed");
+        assert!(matches!(
+            cmd.action,
+            Action::DumpDeployBuffer { account: None }
+        ));
+
+        let account_hash = PublicKey::doc_example().to_account_hash();
+        let cmd = Command::from_line(&format!(
+            "dump-deploy-buffer --account {}",
+            PublicKey::doc_example().to_hex()
+        ))
+        .expect("command parsing failed");
+        assert!(
+            matches!(cmd.action, Action::DumpDeployBuffer { account } if account == Some(account_hash))
+        );
     }
 }
@@ -34,14 +34,32 @@ use crate::{
     effect::{
         announcements::{ControlAnnouncement, QueueDumpFormat},
         diagnostics_port::DumpConsensusStateRequest,
-        requests::{NetworkInfoRequest, SetNodeStopRequest},
+        requests::{DeployBufferRequest, NetworkInfoRequest, SetNodeStopRequest},
         EffectBuilder,
     },
     failpoints::FailpointActivation,
     logging,
+    types::PendingDeploy,
     utils::{display_error, opt_display::OptDisplay},
 };
 
+/// The deploys held in the deploy buffer, displayed one per line.
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+struct PendingDeploys(Vec<PendingDeploy>);
+
+impl Display for PendingDeploys {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "no deploys in deploy buffer");
+        }
+        for pending_deploy in &self.0 {
+            writeln!(f, "{}", pending_deploy)?;
+        }
+        Ok(())
+    }
+}
+
 /// Success or failure response.
 ///
 /// This response is sent back to clients after every operation (unless suppressed in quiet mode),
@@ -203,6 +221,7 @@ impl Session {
         REv: From<DumpConsensusStateRequest>
             + From<ControlAnnouncement>
             + From<NetworkInfoRequest>
+            + From<DeployBufferRequest>
             + From<SetNodeStopRequest>
             + Send,
     {
@@ -318,6 +337,13 @@ impl Session {
                             }
                         };
                     }
+                    Action::DumpDeployBuffer { account } => {
+                        self.send_outcome(writer, &Outcome::success("dumping deploy buffer"))
+                            .await?;
+                        let pending_deploys = effect_builder.get_pending_deploys(account).await;
+                        self.send_to_client(writer, &PendingDeploys(pending_deploys))
+                            .await?;
+                    }
                     Action::NetInfo => {
                         self.send_outcome(writer, &Outcome::success("collecting insights"))
                             .await?;
@@ -502,6 +528,7 @@ where
     REv: From<DumpConsensusStateRequest>
         + From<ControlAnnouncement>
         + From<NetworkInfoRequest>
+        + From<DeployBufferRequest>
         + From<SetNodeStopRequest>
         + Send,
 {
@@ -546,6 +573,7 @@ pub(super) async fn server<REv>(
     REv: From<DumpConsensusStateRequest>
         + From<ControlAnnouncement>
         + From<NetworkInfoRequest>
+        + From<DeployBufferRequest>
         + From<SetNodeStopRequest>
         + Send,
 {
@@ -626,7 +654,7 @@ mod tests {
         effect::{
             announcements::ControlAnnouncement,
             diagnostics_port::DumpConsensusStateRequest,
-            requests::{NetworkInfoRequest, SetNodeStopRequest},
+            requests::{DeployBufferRequest, NetworkInfoRequest, SetNodeStopRequest},
             EffectBuilder, EffectExt, Effects,
         },
         reactor::{
@@ -685,6 +713,8 @@ mod tests {
         #[from]
         NetworkInfoRequest(NetworkInfoRequest),
         #[from]
+        DeployBufferRequest(DeployBufferRequest),
+        #[from]
         SetNodeStopRequest(SetNodeStopRequest),
     }
 
@@ -732,7 +762,8 @@ mod tests {
                 Event::DumpConsensusStateRequest(_)
                 | Event::SetNodeStopRequest(_)
                 | Event::ControlAnnouncement(_)
-                | Event::NetworkInfoRequest(_) => {
+                | Event::NetworkInfoRequest(_)
+                | Event::DeployBufferRequest(_) => {
                     panic!("unexpected: {}", event)
                 }
             }
@@ -1,7 +1,8 @@
 //! Event stream server
 //!
 //! The event stream server provides clients with an event-stream returning Server-Sent Events
-//! (SSEs) holding JSON-encoded data.
+//! (SSEs) holding JSON-encoded data.  The same events are also available via a WebSocket, over
+//! which clients can change the set of event types they are subscribed to without reconnecting.
 //!
 //! The actual server is run in backgrounded tasks.
 //!
@@ -24,6 +25,7 @@ mod http_server;
 mod sse_server;
 #[cfg(test)]
 mod tests;
+mod ws_server;
 
 use std::{fmt::Debug, net::SocketAddr, path::PathBuf};
 
@@ -32,12 +32,15 @@ use warp::{
     Filter, Reply,
 };
 
+use casper_hashing::Digest;
 #[cfg(test)]
 use casper_types::testing::TestRng;
 use casper_types::{
-    EraId, ExecutionEffect, ExecutionResult, ProtocolVersion, PublicKey, TimeDiff, Timestamp,
+    AsymmetricType, EraId, ExecutionEffect, ExecutionResult, Key, ProtocolVersion, PublicKey,
+    TimeDiff, Timestamp,
 };
 
+use super::ws_server;
 use crate::types::{BlockHash, Deploy, DeployHash, FinalitySignature, JsonBlock};
 #[cfg(test)]
 use crate::{testing, types::Block};
@@ -53,6 +56,18 @@ pub const SSE_API_DEPLOYS_PATH: &str = "deploys";
 pub const SSE_API_SIGNATURES_PATH: &str = "sigs";
 /// The URL query string field name.
 pub const QUERY_FIELD: &str = "start_from";
+/// The URL query string field name for filtering deploy events by account public key.
+pub const ACCOUNT_QUERY_FIELD: &str = "account";
+/// The URL query string field name for filtering `DeployProcessed` events by contract hash.
+pub const CONTRACT_HASH_QUERY_FIELD: &str = "contract_hash";
+/// The URL query string field name for filtering `DeployProcessed` events by contract package hash.
+pub const CONTRACT_PACKAGE_HASH_QUERY_FIELD: &str = "contract_package_hash";
+/// The URL query string field name for filtering deploy events by deploy hash.
+pub const DEPLOY_HASH_QUERY_FIELD: &str = "deploy_hash";
+/// The URL query string field name for filtering era-specific events by era ID.
+pub const ERA_QUERY_FIELD: &str = "era";
+/// The separator of multiple values provided for a single content filter query field.
+const QUERY_VALUE_SEPARATOR: char = ',';
 
 /// The filter associated with `/events/main` path.
 const MAIN_FILTER: [EventFilter; 5] = [
@@ -264,7 +279,7 @@ pub(super) struct NewSubscriberInfo {
 }
 
 /// A filter for event types a client has subscribed to receive.
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Serialize, Deserialize, Debug)]
 pub(super) enum EventFilter {
     BlockAdded,
     DeployAccepted,
@@ -275,6 +290,138 @@ pub(super) enum EventFilter {
     Step,
 }
 
+/// Filters on the content of events a client has subscribed to receive, set via the query string.
+///
+/// Each filter only applies to the event types which carry the relevant information, and any other
+/// event types are unaffected by it.  Where more than one filter applies to a given event, the
+/// event must match all of them to be included.  A filter with several values matches if any one of
+/// the values matches.
+#[derive(Clone, Default, Eq, PartialEq, Debug)]
+pub(super) struct ContentFilter {
+    /// Applies to `DeployAccepted` and `DeployProcessed` events.
+    accounts: Option<HashSet<PublicKey>>,
+    /// The formatted `Key::Hash`es of contracts, one of which must appear in the execution
+    /// effects' transforms.  Applies to `DeployProcessed` events.
+    contract_hashes: Option<HashSet<String>>,
+    /// The formatted `Key::Hash`es of contract packages, one of which must appear in the execution
+    /// effects' transforms.  Applies to `DeployProcessed` events.
+    contract_package_hashes: Option<HashSet<String>>,
+    /// Applies to `DeployAccepted`, `DeployProcessed` and `DeployExpired` events.
+    deploy_hashes: Option<HashSet<DeployHash>>,
+    /// Applies to `BlockAdded`, `Fault`, `FinalitySignature` and `Step` events.
+    era_ids: Option<HashSet<EraId>>,
+}
+
+impl ContentFilter {
+    /// Returns `true` if the given data passes all applicable filters.
+    pub(super) fn matches(&self, data: &SseData) -> bool {
+        match data {
+            SseData::ApiVersion(_) | SseData::Shutdown => true,
+            SseData::BlockAdded { block, .. } => self.matches_era(&block.header.era_id),
+            SseData::DeployAccepted { deploy } => {
+                self.matches_account(deploy.header().account())
+                    && self.matches_deploy_hash(deploy.hash())
+            }
+            SseData::DeployProcessed {
+                deploy_hash,
+                account,
+                execution_result,
+                ..
+            } => {
+                self.matches_account(account)
+                    && self.matches_deploy_hash(deploy_hash)
+                    && Self::matches_hash_keys(&self.contract_hashes, execution_result)
+                    && Self::matches_hash_keys(&self.contract_package_hashes, execution_result)
+            }
+            SseData::DeployExpired { deploy_hash } => self.matches_deploy_hash(deploy_hash),
+            SseData::Fault { era_id, .. } | SseData::Step { era_id, .. } => {
+                self.matches_era(era_id)
+            }
+            SseData::FinalitySignature(finality_signature) => {
+                self.matches_era(&finality_signature.era_id)
+            }
+        }
+    }
+
+    fn matches_account(&self, account: &PublicKey) -> bool {
+        self.accounts
+            .as_ref()
+            .map_or(true, |accounts| accounts.contains(account))
+    }
+
+    fn matches_deploy_hash(&self, deploy_hash: &DeployHash) -> bool {
+        self.deploy_hashes
+            .as_ref()
+            .map_or(true, |deploy_hashes| deploy_hashes.contains(deploy_hash))
+    }
+
+    fn matches_era(&self, era_id: &EraId) -> bool {
+        self.era_ids
+            .as_ref()
+            .map_or(true, |era_ids| era_ids.contains(era_id))
+    }
+
+    fn matches_hash_keys(
+        hash_keys: &Option<HashSet<String>>,
+        execution_result: &ExecutionResult,
+    ) -> bool {
+        let hash_keys = match hash_keys.as_ref() {
+            Some(hash_keys) => hash_keys,
+            None => return true,
+        };
+        let effect = match execution_result {
+            ExecutionResult::Success { effect, .. } | ExecutionResult::Failure { effect, .. } => {
+                effect
+            }
+        };
+        effect
+            .transforms
+            .iter()
+            .any(|transform_entry| hash_keys.contains(&transform_entry.key))
+    }
+
+    /// Parses the value of the given content filter query field into `self`, returning `false` if
+    /// the field is not a content filter field or the value is invalid.
+    fn parse_field(&mut self, field: &str, value: &str) -> bool {
+        let values = value.split(QUERY_VALUE_SEPARATOR);
+        match field {
+            ACCOUNT_QUERY_FIELD => values
+                .map(|value| PublicKey::from_hex(value).ok())
+                .collect::<Option<HashSet<_>>>()
+                .map(|accounts| self.accounts = Some(accounts))
+                .is_some(),
+            CONTRACT_HASH_QUERY_FIELD => values
+                .map(parse_hash_key)
+                .collect::<Option<HashSet<_>>>()
+                .map(|contract_hashes| self.contract_hashes = Some(contract_hashes))
+                .is_some(),
+            CONTRACT_PACKAGE_HASH_QUERY_FIELD => values
+                .map(parse_hash_key)
+                .collect::<Option<HashSet<_>>>()
+                .map(|package_hashes| self.contract_package_hashes = Some(package_hashes))
+                .is_some(),
+            DEPLOY_HASH_QUERY_FIELD => values
+                .map(|value| Digest::from_hex(value).ok().map(DeployHash::new))
+                .collect::<Option<HashSet<_>>>()
+                .map(|deploy_hashes| self.deploy_hashes = Some(deploy_hashes))
+                .is_some(),
+            ERA_QUERY_FIELD => values
+                .map(|value| value.parse::<u64>().ok().map(EraId::new))
+                .collect::<Option<HashSet<_>>>()
+                .map(|era_ids| self.era_ids = Some(era_ids))
+                .is_some(),
+            _ => false,
+        }
+    }
+}
+
+/// Parses a hex-encoded hash into the formatted `Key::Hash` under which it appears in transforms.
+fn parse_hash_key(value: &str) -> Option<String> {
+    Digest::from_hex(value)
+        .ok()
+        .map(|digest| Key::Hash(digest.value()).to_formatted_string())
+}
+
 /// Filters the `event`, mapping it to a warp event, or `None` if it should be filtered out.
 async fn filter_map_server_sent_event(
     event: &ServerSentEvent,
@@ -345,26 +492,26 @@ pub(super) fn get_filter(path_param: &str) -> Option<&'static [EventFilter]> {
     }
 }
 
-/// Extracts the starting event ID from the provided query, or `None` if `query` is empty.
+/// Extracts the starting event ID and the content filter from the provided query.
 ///
-/// If `query` is not empty, returns a 422 response if `query` doesn't have exactly one entry,
-/// "starts_from" mapped to a value representing an event ID.
-fn parse_query(query: HashMap<String, String>) -> Result<Option<Id>, Response> {
-    if query.is_empty() {
-        return Ok(None);
-    }
-
-    if query.len() > 1 {
-        return Err(create_422());
-    }
-
-    match query
-        .get(QUERY_FIELD)
-        .and_then(|id_str| id_str.parse::<Id>().ok())
-    {
-        Some(id) => Ok(Some(id)),
-        None => Err(create_422()),
+/// The starting event ID is `None` if `query` has no "start_from" field, and the content filter is
+/// the default (i.e. filtering nothing out) if `query` has no content filter fields.
+///
+/// Returns a 422 response if `query` has any unknown fields, or any field with an invalid value.
+fn parse_query(query: HashMap<String, String>) -> Result<(Option<Id>, ContentFilter), Response> {
+    let mut start_from = None;
+    let mut content_filter = ContentFilter::default();
+    for (field, value) in query {
+        if field == QUERY_FIELD {
+            match value.parse::<Id>() {
+                Ok(id) => start_from = Some(id),
+                Err(_) => return Err(create_422()),
+            }
+        } else if !content_filter.parse_field(&field, &value) {
+            return Err(create_422());
+        }
     }
+    Ok((start_from, content_filter))
 }
 
 /// Creates a 404 response with a useful error message in the body.
@@ -384,8 +531,16 @@ fn create_404() -> Response {
 /// string.
 fn create_422() -> Response {
     let mut response = Response::new(Body::from(format!(
-        "invalid query: expected single field '{}=<EVENT ID>'\n",
-        QUERY_FIELD
+        "invalid query: expected any of '{}=<EVENT ID>', '{}=<PUBLIC KEYS>', \
+        '{}=<CONTRACT HASHES>', '{}=<CONTRACT PACKAGE HASHES>', '{}=<DEPLOY HASHES>' or \
+        '{}=<ERA IDS>', with multiple values separated by '{}'\n",
+        QUERY_FIELD,
+        ACCOUNT_QUERY_FIELD,
+        CONTRACT_HASH_QUERY_FIELD,
+        CONTRACT_PACKAGE_HASH_QUERY_FIELD,
+        DEPLOY_HASH_QUERY_FIELD,
+        ERA_QUERY_FIELD,
+        QUERY_VALUE_SEPARATOR
     )));
     *response.status_mut() = StatusCode::UNPROCESSABLE_ENTITY;
     response
@@ -393,7 +548,7 @@ fn create_422() -> Response {
 
 /// Creates a 503 response (Service Unavailable) to be returned if the server has too many
 /// subscribers.
-fn create_503() -> Response {
+pub(super) fn create_503() -> Response {
     let mut response = Response::new(Body::from("server has reached limit of subscribers"));
     *response.status_mut() = StatusCode::SERVICE_UNAVAILABLE;
     response
@@ -417,6 +572,13 @@ impl ChannelsAndFilter {
         // new client subscription.
         let (new_subscriber_info_sender, new_subscriber_info_receiver) = mpsc::unbounded_channel();
 
+        // Create the filter for clients subscribing via a WebSocket.
+        let ws_filter = ws_server::create_filter(
+            event_broadcaster.clone(),
+            new_subscriber_info_sender.clone(),
+            max_concurrent_subscribers,
+        );
+
         let serve = move |path_param: String,
                           query: HashMap<String, String>,
                           maybe_remote_address: Option<SocketAddr>| {
@@ -441,8 +603,8 @@ impl ChannelsAndFilter {
                 None => return create_404(),
             };
 
-            let start_from = match parse_query(query) {
-                Ok(maybe_id) => maybe_id,
+            let (start_from, content_filter) = match parse_query(query) {
+                Ok(parsed_query) => parsed_query,
                 Err(error_response) => return error_response,
             };
 
@@ -469,18 +631,21 @@ impl ChannelsAndFilter {
                 initial_events_receiver,
                 ongoing_events_receiver,
                 event_filter,
+                content_filter,
                 remote_address,
             )))
             .into_response()
         };
 
-        let sse_filter = warp::get()
-            .and(path(SSE_API_ROOT_PATH))
-            .and(path::param::<String>())
-            .and(path::end())
-            .and(warp::query())
-            .and(addr::remote())
-            .map(serve)
+        let sse_filter = ws_filter
+            .or(warp::get()
+                .and(path(SSE_API_ROOT_PATH))
+                .and(path::param::<String>())
+                .and(path::end())
+                .and(warp::query())
+                .and(addr::remote())
+                .map(serve))
+            .unify()
             .or_else(|_| async move { Ok::<_, Rejection>((create_404(),)) })
             .boxed();
 
@@ -505,13 +670,17 @@ impl ChannelsAndFilter {
 /// subscribed to the server's event stream.
 ///
 /// It also takes an `EventFilter` which causes events to which the client didn't subscribe to be
-/// skipped.
+/// skipped, and a `ContentFilter` which causes events whose content doesn't match the client's
+/// query to be skipped.
 fn stream_to_client(
     initial_events: mpsc::UnboundedReceiver<ServerSentEvent>,
     ongoing_events: broadcast::Receiver<BroadcastChannelMessage>,
     event_filter: &'static [EventFilter],
+    content_filter: ContentFilter,
     remote_address: String,
 ) -> impl Stream<Item = Result<WarpServerSentEvent, RecvError>> + 'static {
+    let content_filter = Arc::new(content_filter);
+
     // Keep a record of the IDs of the events delivered via the `initial_events` receiver.
     let initial_stream_ids = Arc::new(RwLock::new(HashSet::new()));
     let cloned_initial_ids = Arc::clone(&initial_stream_ids);
@@ -548,7 +717,7 @@ fn stream_to_client(
         .take_while(|result| future::ready(!matches!(result, Err(RecvError::Closed))));
 
     // Serve the initial events followed by the ongoing ones, filtering as dictated by the
-    // `event_filter`.
+    // `event_filter` and `content_filter`.
     UnboundedReceiverStream::new(initial_events)
         .map(move |event| {
             if let Some(id) = event.id {
@@ -557,10 +726,14 @@ fn stream_to_client(
             Ok(event)
         })
         .chain(ongoing_stream)
-        .filter_map(move |result| async move {
-            match result {
-                Ok(event) => filter_map_server_sent_event(&event, event_filter).await,
-                Err(error) => Some(Err(error)),
+        .filter_map(move |result| {
+            let content_filter = Arc::clone(&content_filter);
+            async move {
+                match result {
+                    Ok(event) if !content_filter.matches(&event.data) => None,
+                    Ok(event) => filter_map_server_sent_event(&event, event_filter).await,
+                    Err(error) => Some(Err(error)),
+                }
             }
         })
 }
@@ -569,7 +742,7 @@ fn stream_to_client(
 mod tests {
     use std::iter;
 
-    use casper_types::testing::TestRng;
+    use casper_types::{testing::TestRng, Transform, TransformEntry};
 
     use super::*;
     use crate::logging;
@@ -678,6 +851,197 @@ mod tests {
         should_filter_out(&step, &SIGNATURES_FILTER[..]).await;
     }
 
+    /// This test checks that the query string is parsed into the expected content filter, and that
+    /// events are filtered by their content accordingly.
+    #[test]
+    fn should_filter_events_by_content() {
+        fn deploy_processed(
+            account: &PublicKey,
+            deploy_hash: DeployHash,
+            block_hash: BlockHash,
+            hashes: &[[u8; 32]],
+        ) -> SseData {
+            let transforms = hashes
+                .iter()
+                .map(|hash| TransformEntry {
+                    key: Key::Hash(*hash).to_formatted_string(),
+                    transform: Transform::Identity,
+                })
+                .collect();
+            SseData::DeployProcessed {
+                deploy_hash: Box::new(deploy_hash),
+                account: Box::new(account.clone()),
+                timestamp: Timestamp::zero(),
+                ttl: TimeDiff::from_seconds(1),
+                dependencies: vec![],
+                block_hash: Box::new(block_hash),
+                execution_result: Box::new(ExecutionResult::Success {
+                    effect: ExecutionEffect::new(transforms),
+                    transfers: vec![],
+                    cost: 1.into(),
+                    events: vec![],
+                }),
+            }
+        }
+
+        let mut rng = crate::new_rng();
+
+        let account_a = PublicKey::random(&mut rng);
+        let account_b = PublicKey::random(&mut rng);
+        let deploy_hashes: Vec<_> = iter::repeat_with(|| DeployHash::random(&mut rng))
+            .take(4)
+            .collect();
+        let contract_hash = [1; 32];
+        let package_hash = [2; 32];
+        let other_hash = [3; 32];
+
+        let block_hash = BlockHash::random(&mut rng);
+        let touches_both = deploy_processed(
+            &account_a,
+            deploy_hashes[0],
+            block_hash,
+            &[contract_hash, package_hash],
+        );
+        let touches_contract =
+            deploy_processed(&account_a, deploy_hashes[1], block_hash, &[contract_hash]);
+        let touches_package =
+            deploy_processed(&account_b, deploy_hashes[2], block_hash, &[package_hash]);
+        let touches_neither = deploy_processed(&account_b, deploy_hashes[3], block_hash, &[]);
+        let (deploy_accepted, _) = SseData::random_deploy_accepted(&mut rng);
+        let fault_in_era = |era_id: u64| SseData::Fault {
+            era_id: EraId::new(era_id),
+            public_key: Box::new(account_a.clone()),
+            timestamp: Timestamp::zero(),
+        };
+        let events = vec![
+            touches_both.clone(),
+            touches_contract.clone(),
+            touches_package.clone(),
+            touches_neither,
+            deploy_accepted,
+            fault_in_era(1),
+            fault_in_era(2),
+            SseData::Shutdown,
+        ];
+
+        let parse = |query: &[(&str, String)]| {
+            parse_query(
+                query
+                    .iter()
+                    .map(|(field, value)| (field.to_string(), value.clone()))
+                    .collect(),
+            )
+            .map(|(_, content_filter)| content_filter)
+        };
+        let count_matches = |query: &[(&str, String)]| {
+            let content_filter = parse(query).unwrap();
+            events
+                .iter()
+                .filter(|event| content_filter.matches(event))
+                .count()
+        };
+        let join = |values: &[String]| values.join(&QUERY_VALUE_SEPARATOR.to_string());
+        let hex_hash =
+            |deploy_hash: &DeployHash| base16::encode_lower(&deploy_hash.inner().value());
+
+        // No content filter should match everything.
+        let content_filter = parse(&[(QUERY_FIELD, "1".to_string())]).unwrap();
+        assert_eq!(content_filter, ContentFilter::default());
+        assert_eq!(count_matches(&[]), 8);
+
+        // Filtering by account should only affect deploy events, and exclude the deploy accepted
+        // from another account.
+        assert_eq!(
+            count_matches(&[(ACCOUNT_QUERY_FIELD, account_a.to_hex())]),
+            5
+        );
+        assert_eq!(
+            count_matches(&[(
+                ACCOUNT_QUERY_FIELD,
+                join(&[account_a.to_hex(), account_b.to_hex()])
+            )]),
+            7
+        );
+
+        // Filtering by deploy hash.
+        assert_eq!(
+            count_matches(&[(
+                DEPLOY_HASH_QUERY_FIELD,
+                join(&[hex_hash(&deploy_hashes[0]), hex_hash(&deploy_hashes[2])])
+            )]),
+            5
+        );
+
+        // Filtering by contract or contract package hash should only affect `DeployProcessed`s.
+        let contract_query = base16::encode_lower(&contract_hash);
+        let package_query = base16::encode_lower(&package_hash);
+        let other_query = base16::encode_lower(&other_hash);
+        assert_eq!(
+            count_matches(&[(CONTRACT_HASH_QUERY_FIELD, contract_query.clone())]),
+            6
+        );
+        assert_eq!(
+            count_matches(&[(CONTRACT_PACKAGE_HASH_QUERY_FIELD, package_query.clone())]),
+            6
+        );
+        assert_eq!(
+            count_matches(&[(CONTRACT_HASH_QUERY_FIELD, other_query.clone())]),
+            4
+        );
+        assert_eq!(
+            count_matches(&[(
+                CONTRACT_HASH_QUERY_FIELD,
+                join(&[other_query, contract_query.clone()])
+            )]),
+            6
+        );
+
+        // A contract hash and a contract package hash must both be matched.
+        let content_filter = parse(&[
+            (CONTRACT_HASH_QUERY_FIELD, contract_query.clone()),
+            (CONTRACT_PACKAGE_HASH_QUERY_FIELD, package_query.clone()),
+        ])
+        .unwrap();
+        assert!(content_filter.matches(&touches_both));
+        assert!(!content_filter.matches(&touches_contract));
+        assert!(!content_filter.matches(&touches_package));
+        assert_eq!(
+            count_matches(&[
+                (CONTRACT_HASH_QUERY_FIELD, contract_query.clone()),
+                (CONTRACT_PACKAGE_HASH_QUERY_FIELD, package_query.clone()),
+            ]),
+            5
+        );
+
+        // A contract hash combined with an account must both be matched.
+        assert_eq!(
+            count_matches(&[
+                (CONTRACT_HASH_QUERY_FIELD, contract_query.clone()),
+                (ACCOUNT_QUERY_FIELD, account_a.to_hex()),
+            ]),
+            5
+        );
+        assert_eq!(
+            count_matches(&[
+                (CONTRACT_HASH_QUERY_FIELD, contract_query),
+                (ACCOUNT_QUERY_FIELD, account_b.to_hex()),
+            ]),
+            3
+        );
+
+        // Filtering by era should only affect era-specific events.
+        assert_eq!(count_matches(&[(ERA_QUERY_FIELD, "1".to_string())]), 7);
+        assert_eq!(count_matches(&[(ERA_QUERY_FIELD, "3".to_string())]), 6);
+
+        // Invalid values and unknown fields should be rejected.
+        assert!(parse(&[(ACCOUNT_QUERY_FIELD, "not hex".to_string())]).is_err());
+        assert!(parse(&[(DEPLOY_HASH_QUERY_FIELD, "01,".to_string())]).is_err());
+        assert!(parse(&[(CONTRACT_HASH_QUERY_FIELD, "zz".to_string())]).is_err());
+        assert!(parse(&[(CONTRACT_PACKAGE_HASH_QUERY_FIELD, "zz".to_string())]).is_err());
+        assert!(parse(&[(ERA_QUERY_FIELD, "-1".to_string())]).is_err());
+        assert!(parse(&[("unknown", "1".to_string())]).is_err());
+    }
+
     /// This test checks that events with incorrect IDs (i.e. no types have an ID except for
     /// `ApiVersion`) are filtered out.
     #[tokio::test]
@@ -855,6 +1219,7 @@ mod tests {
                 initial_events_receiver,
                 ongoing_events_receiver,
                 get_filter(path_filter).unwrap(),
+                ContentFilter::default(),
                 "127.0.0.1:3456".to_string(),
             )
             .collect()
@@ -9,7 +9,7 @@ use std::{
     time::Duration,
 };
 
-use futures::{join, StreamExt};
+use futures::{join, SinkExt, Stream, StreamExt};
 use http::StatusCode;
 use pretty_assertions::assert_eq;
 use reqwest::Response;
@@ -20,6 +20,7 @@ use tokio::{
     task::{self, JoinHandle},
     time,
 };
+use tokio_tungstenite::tungstenite::{Error as WsError, Message as WsMessage};
 use tracing::debug;
 
 use casper_types::testing::TestRng;
@@ -27,10 +28,12 @@ use casper_types::testing::TestRng;
 use super::*;
 use crate::{logging, testing::assert_schema};
 use sse_server::{
-    DeployAccepted, Id, QUERY_FIELD, SSE_API_DEPLOYS_PATH as DEPLOYS_PATH,
-    SSE_API_MAIN_PATH as MAIN_PATH, SSE_API_ROOT_PATH as ROOT_PATH,
-    SSE_API_SIGNATURES_PATH as SIGS_PATH,
+    DeployAccepted, EventFilter, Id, ACCOUNT_QUERY_FIELD, CONTRACT_HASH_QUERY_FIELD,
+    CONTRACT_PACKAGE_HASH_QUERY_FIELD, DEPLOY_HASH_QUERY_FIELD, ERA_QUERY_FIELD, QUERY_FIELD,
+    SSE_API_DEPLOYS_PATH as DEPLOYS_PATH, SSE_API_MAIN_PATH as MAIN_PATH,
+    SSE_API_ROOT_PATH as ROOT_PATH, SSE_API_SIGNATURES_PATH as SIGS_PATH,
 };
+use ws_server::{WsClientMessage, WS_API_PATH as WS_PATH};
 
 /// The total number of random events each `EventStreamServer` will emit by default, excluding the
 /// initial `ApiVersion` event.
@@ -955,11 +958,21 @@ async fn should_handle_bad_url_query() {
         format!("{}?{}=0&extra=1", main_url, QUERY_FIELD),
         format!("{}?{}=0&extra=1", deploys_url, QUERY_FIELD),
         format!("{}?{}=0&extra=1", sigs_url, QUERY_FIELD),
+        format!("{}?{}=not-hex", main_url, ACCOUNT_QUERY_FIELD),
+        format!("{}?{}=not-hex", deploys_url, DEPLOY_HASH_QUERY_FIELD),
+        format!("{}?{}=not-integer", sigs_url, ERA_QUERY_FIELD),
     ];
 
     let expected_body = format!(
-        "invalid query: expected single field '{}=<EVENT ID>'",
-        QUERY_FIELD
+        "invalid query: expected any of '{}=<EVENT ID>', '{}=<PUBLIC KEYS>', \
+        '{}=<CONTRACT HASHES>', '{}=<CONTRACT PACKAGE HASHES>', '{}=<DEPLOY HASHES>' or \
+        '{}=<ERA IDS>', with multiple values separated by ','",
+        QUERY_FIELD,
+        ACCOUNT_QUERY_FIELD,
+        CONTRACT_HASH_QUERY_FIELD,
+        CONTRACT_PACKAGE_HASH_QUERY_FIELD,
+        DEPLOY_HASH_QUERY_FIELD,
+        ERA_QUERY_FIELD,
     );
     for url in &urls {
         let response = reqwest::get(url).await.unwrap();
@@ -1185,6 +1198,175 @@ async fn should_limit_concurrent_subscribers() {
     fixture.stop_server().await;
 }
 
+/// Converts the `data` of each event to its canonical JSON string form, to allow comparison with
+/// events received via a WebSocket.
+fn normalize(events: Vec<ReceivedEvent>) -> Vec<ReceivedEvent> {
+    events
+        .into_iter()
+        .map(|event| ReceivedEvent {
+            id: event.id,
+            data: serde_json::from_str::<serde_json::Value>(&event.data)
+                .unwrap()
+                .to_string(),
+        })
+        .collect()
+}
+
+/// Returns the next message received via the given WebSocket, parsed as JSON.
+async fn next_ws_message<S>(websocket: &mut S) -> serde_json::Value
+where
+    S: Stream<Item = Result<WsMessage, WsError>> + Unpin,
+{
+    let message = websocket.next().await.unwrap().unwrap();
+    serde_json::from_str(&message.into_text().unwrap()).unwrap()
+}
+
+/// Runs a WebSocket client which subscribes to `event_filter` with the given `start_from`,
+/// consuming all events until the server has emitted the event with ID `final_event_id`.
+///
+/// The client waits at the barrier before connecting to the server, and then again once its
+/// subscription has been acknowledged.
+async fn subscribe_ws(
+    server_address: SocketAddr,
+    barrier: Arc<Barrier>,
+    event_filter: &[EventFilter],
+    start_from: Option<Id>,
+    final_event_id: Id,
+) -> Vec<ReceivedEvent> {
+    let url = format!("ws://{}/{}/{}", server_address, ROOT_PATH, WS_PATH);
+    barrier.wait().await;
+    let (mut websocket, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+
+    let subscribe = WsClientMessage::Subscribe {
+        events: event_filter.to_vec(),
+        start_from,
+    };
+    websocket
+        .send(WsMessage::Text(serde_json::to_string(&subscribe).unwrap()))
+        .await
+        .unwrap();
+
+    let mut received_events = Vec::new();
+    while let Some(message) = websocket.next().await {
+        let text = message.unwrap().into_text().unwrap();
+        let message: serde_json::Value = serde_json::from_str(&text).unwrap();
+        if let Some(subscriptions) = message.get("Subscriptions") {
+            let subscriptions: Vec<EventFilter> =
+                serde_json::from_value(subscriptions.clone()).unwrap();
+            assert_eq!(subscriptions, event_filter);
+            // The subscription is now active; any events emitted from here on which are also in
+            // the replayed buffer are deduplicated by the server.
+            barrier.wait().await;
+            continue;
+        }
+        let event = message.get("Event").expect("should be an event");
+        let id: Option<Id> = serde_json::from_value(event["id"].clone()).unwrap();
+        received_events.push(ReceivedEvent {
+            id,
+            data: event["data"].to_string(),
+        });
+        if id == Some(final_event_id) {
+            break;
+        }
+    }
+    let _ = websocket.close(None).await;
+    received_events
+}
+
+/// Client setup:
+///   * `<IP:port>/events/ws`, subscribing to the main events with `start_from=25`
+///   * connected just before event ID 50
+///
+/// Expected to receive main events from ID 25 onwards, as events 25 to 49 should still be in the
+/// server buffer.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn should_serve_events_via_websocket() {
+    let mut rng = crate::new_rng();
+    let mut fixture = TestFixture::new(&mut rng);
+
+    let connect_at_event_id = BUFFER_LENGTH;
+    let start_from_event_id = BUFFER_LENGTH / 2;
+
+    let mut server_behavior = ServerBehavior::new();
+    let barrier = server_behavior.add_client_sync_before_event(connect_at_event_id);
+    let server_address = fixture.run_server(server_behavior).await;
+
+    let (expected_events, final_id) = fixture.filtered_events(MAIN_PATH, start_from_event_id);
+    let event_filter = sse_server::get_filter(MAIN_PATH).unwrap();
+    let received_events = time::timeout(
+        MAX_TEST_TIME,
+        subscribe_ws(
+            server_address,
+            barrier,
+            event_filter,
+            Some(start_from_event_id),
+            final_id,
+        ),
+    )
+    .await
+    .expect("websocket client timed out");
+    fixture.stop_server().await;
+
+    assert_eq!(received_events, normalize(expected_events));
+}
+
+/// Checks that a WebSocket client can change its subscriptions without reconnecting.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn should_change_websocket_subscriptions() {
+    let mut rng = crate::new_rng();
+    let mut fixture = TestFixture::new(&mut rng);
+    let server_address = fixture.run_server(ServerBehavior::new()).await;
+
+    let url = format!("ws://{}/{}/{}", server_address, ROOT_PATH, WS_PATH);
+    let (mut websocket, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+
+    // The first message should be the `ApiVersion` event.
+    let message = next_ws_message(&mut websocket).await;
+    assert_eq!(
+        message["Event"]["data"],
+        serde_json::to_value(SseData::ApiVersion(fixture.protocol_version)).unwrap()
+    );
+
+    let requests_and_responses = [
+        (
+            r#"{"Subscribe":{"events":["BlockAdded","Step"]}}"#,
+            serde_json::json!({"Subscriptions": ["BlockAdded", "Step"]}),
+        ),
+        (
+            r#"{"Subscribe":{"events":["Step","Fault"]}}"#,
+            serde_json::json!({"Subscriptions": ["BlockAdded", "Step", "Fault"]}),
+        ),
+        (
+            r#"{"Unsubscribe":{"events":["BlockAdded","Step","Fault"]}}"#,
+            serde_json::json!({"Subscriptions": []}),
+        ),
+    ];
+    for (request, expected_response) in requests_and_responses {
+        websocket
+            .send(WsMessage::Text(request.to_string()))
+            .await
+            .unwrap();
+        // Skip any events sent before the response to the request.
+        let response = loop {
+            let message = next_ws_message(&mut websocket).await;
+            if message.get("Event").is_none() {
+                break message;
+            }
+        };
+        assert_eq!(response, expected_response);
+    }
+
+    websocket
+        .send(WsMessage::Text("not json".to_string()))
+        .await
+        .unwrap();
+    let response = next_ws_message(&mut websocket).await;
+    assert!(response.get("Error").is_some());
+
+    let _ = websocket.close(None).await;
+    fixture.stop_server().await;
+}
+
 /// Rather than being a test proper, this is more a means to easily determine differences between
 /// versions of the events emitted by the SSE server by comparing the contents of
 /// `resources/test/sse_data_schema.json` across different versions of the codebase.
@@ -0,0 +1,404 @@
+//! Types and functions used by the http server to manage event-stream subscriptions made over a
+//! WebSocket.
+//!
+//! Unlike an SSE subscription, a WebSocket subscription is not tied to a single set of event types
+//! fixed at connection time.  A newly-connected client receives only the initial `ApiVersion`
+//! event, and thereafter sends [`WsClientMessage`]s to subscribe to or unsubscribe from individual
+//! event types, optionally replaying buffered events of the newly-subscribed types.
+
+use std::{collections::BTreeSet, net::SocketAddr};
+
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::{
+    select,
+    sync::{
+        broadcast::{self, error::RecvError},
+        mpsc,
+    },
+};
+use tracing::{debug, error, info, warn};
+use warp::{
+    addr,
+    filters::BoxedFilter,
+    path,
+    reply::Response,
+    ws::{Message, WebSocket, Ws},
+    Filter, Reply,
+};
+
+use super::sse_server::{
+    create_503, BroadcastChannelMessage, DeployAccepted, EventFilter, Id, NewSubscriberInfo,
+    ServerSentEvent, SseData, SSE_API_ROOT_PATH,
+};
+
+/// The URL path part to subscribe to events via a WebSocket.
+pub const WS_API_PATH: &str = "ws";
+
+/// A message sent by a client to the event stream server over a WebSocket.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub(super) enum WsClientMessage {
+    /// Subscribe to the given event types.
+    ///
+    /// If `start_from` is provided, buffered events of the newly-subscribed types are sent first,
+    /// starting from the given event ID.
+    Subscribe {
+        events: Vec<EventFilter>,
+        #[serde(default)]
+        start_from: Option<Id>,
+    },
+    /// Unsubscribe from the given event types.
+    Unsubscribe { events: Vec<EventFilter> },
+}
+
+/// A message sent by the event stream server to a client over a WebSocket.
+#[derive(Serialize, Debug)]
+enum WsServerMessage<'a> {
+    /// An event, where `data` is the same as the "data" field of the equivalent SSE.
+    Event { id: Option<Id>, data: Value },
+    /// The full set of event types the client is subscribed to, sent in reply to every
+    /// `WsClientMessage`.
+    Subscriptions(&'a [EventFilter]),
+    /// The client's message could not be handled.
+    Error(String),
+}
+
+impl<'a> WsServerMessage<'a> {
+    fn from_event(event: &ServerSentEvent) -> Option<Self> {
+        let result = match &event.data {
+            SseData::DeployAccepted { deploy } => serde_json::to_value(DeployAccepted {
+                deploy_accepted: deploy.clone(),
+            }),
+            data => serde_json::to_value(data),
+        };
+        match result {
+            Ok(data) => Some(WsServerMessage::Event { id: event.id, data }),
+            Err(error) => {
+                warn!(%error, ?event, "failed to jsonify websocket event");
+                None
+            }
+        }
+    }
+
+    fn to_message(&self) -> Message {
+        Message::text(serde_json::to_string(self).unwrap_or_else(|error| {
+            error!(%error, "failed to jsonify websocket message");
+            String::new()
+        }))
+    }
+}
+
+/// Requests the initial events from the server, i.e. the `ApiVersion` followed by any buffered
+/// events from `start_from` onwards.
+async fn initial_events(
+    new_subscriber_info_sender: mpsc::UnboundedSender<NewSubscriberInfo>,
+    start_from: Option<Id>,
+) -> Vec<ServerSentEvent> {
+    let (initial_events_sender, mut initial_events_receiver) = mpsc::unbounded_channel();
+    let new_subscriber_info = NewSubscriberInfo {
+        start_from,
+        initial_events_sender,
+    };
+    if new_subscriber_info_sender
+        .send(new_subscriber_info)
+        .is_err()
+    {
+        error!("failed to send new subscriber info");
+        return vec![];
+    }
+
+    let mut events = vec![];
+    while let Some(event) = initial_events_receiver.recv().await {
+        events.push(event);
+    }
+    events
+}
+
+/// Returns whether the ongoing event with the given ID has already been sent as part of a replay.
+///
+/// Ongoing events arrive in ascending ID order, so all replayed IDs at or below `id` can never be
+/// hit again and are pruned.
+fn was_replayed(replayed_ids: &mut BTreeSet<Id>, id: Id) -> bool {
+    let was_replayed = replayed_ids.contains(&id);
+    replayed_ids.retain(|replayed_id| *replayed_id > id);
+    was_replayed
+}
+
+/// The state of a single client's WebSocket subscription.
+struct WsSubscriber {
+    websocket: WebSocket,
+    /// The event types the client is currently subscribed to.
+    event_filter: Vec<EventFilter>,
+    /// The IDs of events already sent to the client as a result of a `start_from` replay, used to
+    /// avoid sending them again when they arrive via the ongoing events channel.  IDs are dropped
+    /// once the ongoing events have caught up with them.
+    replayed_ids: BTreeSet<Id>,
+    new_subscriber_info_sender: mpsc::UnboundedSender<NewSubscriberInfo>,
+    remote_address: String,
+}
+
+impl WsSubscriber {
+    /// Sends the given message, returning `false` if the client has disconnected.
+    async fn send(&mut self, message: Message) -> bool {
+        if let Err(error) = self.websocket.send(message).await {
+            debug!(%error, remote_address = %self.remote_address, "websocket send failed");
+            return false;
+        }
+        true
+    }
+
+    /// Handles a message from the client, returning `false` if the client has disconnected.
+    async fn handle_client_message(&mut self, message: Message) -> bool {
+        if message.is_close() {
+            return false;
+        }
+        let text = match message.to_str() {
+            Ok(text) => text,
+            // Pings, pongs and binary messages are ignored.
+            Err(()) => return true,
+        };
+
+        let client_message = match serde_json::from_str::<WsClientMessage>(text) {
+            Ok(client_message) => client_message,
+            Err(error) => {
+                debug!(%error, remote_address = %self.remote_address, "invalid websocket message");
+                let message = WsServerMessage::Error(format!("invalid message: {}", error));
+                return self.send(message.to_message()).await;
+            }
+        };
+
+        match client_message {
+            WsClientMessage::Subscribe { events, start_from } => {
+                let mut newly_subscribed = vec![];
+                for event in events {
+                    if !self.event_filter.contains(&event) {
+                        self.event_filter.push(event);
+                        newly_subscribed.push(event);
+                    }
+                }
+                let subscriptions = WsServerMessage::Subscriptions(&self.event_filter).to_message();
+                if !self.send(subscriptions).await {
+                    return false;
+                }
+                if start_from.is_some() && !newly_subscribed.is_empty() {
+                    let sender = self.new_subscriber_info_sender.clone();
+                    for event in initial_events(sender, start_from).await {
+                        // The `ApiVersion` event has no ID, and has already been sent.
+                        let id = match event.id {
+                            Some(id) => id,
+                            None => continue,
+                        };
+                        if !event.data.should_include(&newly_subscribed) {
+                            continue;
+                        }
+                        let _ = self.replayed_ids.insert(id);
+                        if let Some(message) = WsServerMessage::from_event(&event) {
+                            if !self.send(message.to_message()).await {
+                                return false;
+                            }
+                        }
+                    }
+                }
+                true
+            }
+            WsClientMessage::Unsubscribe { events } => {
+                self.event_filter.retain(|event| !events.contains(event));
+                let subscriptions = WsServerMessage::Subscriptions(&self.event_filter).to_message();
+                self.send(subscriptions).await
+            }
+        }
+    }
+
+    /// Handles an event arriving via the ongoing events channel, returning `false` if the client
+    /// has disconnected or should be disconnected.
+    async fn handle_ongoing_event(
+        &mut self,
+        result: Result<BroadcastChannelMessage, RecvError>,
+    ) -> bool {
+        match result {
+            Ok(BroadcastChannelMessage::ServerSentEvent(event)) => {
+                if let Some(id) = event.id {
+                    if was_replayed(&mut self.replayed_ids, id) {
+                        debug!(event_id=%id, "skipped duplicate event");
+                        return true;
+                    }
+                }
+                if !event.data.should_include(&self.event_filter) {
+                    return true;
+                }
+                match WsServerMessage::from_event(&event) {
+                    Some(message) => self.send(message.to_message()).await,
+                    None => true,
+                }
+            }
+            Ok(BroadcastChannelMessage::Shutdown) | Err(RecvError::Closed) => false,
+            Err(RecvError::Lagged(lagged_count)) => {
+                info!(
+                    remote_address = %self.remote_address,
+                    %lagged_count,
+                    "client lagged: dropping websocket connection to client",
+                );
+                let message = WsServerMessage::Error(format!(
+                    "client lagged by {} events and is being disconnected",
+                    lagged_count
+                ));
+                let _ = self.send(message.to_message()).await;
+                false
+            }
+        }
+    }
+}
+
+/// Handles a single client's WebSocket connection until either the client disconnects, or the
+/// server shuts down.
+async fn handle_websocket(
+    websocket: WebSocket,
+    mut ongoing_events: broadcast::Receiver<BroadcastChannelMessage>,
+    new_subscriber_info_sender: mpsc::UnboundedSender<NewSubscriberInfo>,
+    remote_address: String,
+) {
+    let mut subscriber = WsSubscriber {
+        websocket,
+        event_filter: vec![],
+        replayed_ids: BTreeSet::new(),
+        new_subscriber_info_sender,
+        remote_address,
+    };
+
+    // First send the client the `ApiVersion` event.
+    let sender = subscriber.new_subscriber_info_sender.clone();
+    for event in initial_events(sender, None).await {
+        if let Some(message) = WsServerMessage::from_event(&event) {
+            if !subscriber.send(message.to_message()).await {
+                return;
+            }
+        }
+    }
+
+    loop {
+        let keep_going = select! {
+            maybe_message = subscriber.websocket.next() => match maybe_message {
+                Some(Ok(message)) => subscriber.handle_client_message(message).await,
+                Some(Err(error)) => {
+                    debug!(%error, remote_address = %subscriber.remote_address, "websocket error");
+                    false
+                }
+                None => false,
+            },
+            result = ongoing_events.recv() => subscriber.handle_ongoing_event(result).await,
+        };
+        if !keep_going {
+            break;
+        }
+    }
+
+    let _ = subscriber.websocket.close().await;
+    debug!(remote_address = %subscriber.remote_address, "websocket client disconnected");
+}
+
+/// Creates the warp filter for the `/events/ws` path, upgrading the connection to a WebSocket.
+pub(super) fn create_filter(
+    broadcaster: broadcast::Sender<BroadcastChannelMessage>,
+    new_subscriber_info_sender: mpsc::UnboundedSender<NewSubscriberInfo>,
+    max_concurrent_subscribers: u32,
+) -> BoxedFilter<(Response,)> {
+    let serve = move |ws: Ws, maybe_remote_address: Option<SocketAddr>| {
+        let remote_address = match maybe_remote_address {
+            Some(address) => address.to_string(),
+            None => "unknown".to_string(),
+        };
+
+        // If we already have the maximum number of subscribers, reject this new one.
+        if broadcaster.receiver_count() >= max_concurrent_subscribers as usize {
+            info!(
+                %remote_address,
+                %max_concurrent_subscribers,
+                "event stream server has max subscribers: rejecting new websocket one"
+            );
+            return create_503();
+        }
+
+        // Create a channel for the client's handler to receive the stream of ongoing events.
+        let ongoing_events = broadcaster.subscribe();
+        let new_subscriber_info_sender = new_subscriber_info_sender.clone();
+        ws.on_upgrade(move |websocket| {
+            handle_websocket(
+                websocket,
+                ongoing_events,
+                new_subscriber_info_sender,
+                remote_address,
+            )
+        })
+        .into_response()
+    };
+
+    warp::get()
+        .and(path(SSE_API_ROOT_PATH))
+        .and(path(WS_API_PATH))
+        .and(path::end())
+        .and(warp::ws())
+        .and(addr::remote())
+        .map(serve)
+        .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_client_messages() {
+        let message: WsClientMessage = serde_json::from_str(
+            r#"{"Subscribe":{"events":["BlockAdded","DeployProcessed"],"start_from":7}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            message,
+            WsClientMessage::Subscribe {
+                events: vec![EventFilter::BlockAdded, EventFilter::DeployProcessed],
+                start_from: Some(7),
+            }
+        );
+
+        let message: WsClientMessage =
+            serde_json::from_str(r#"{"Subscribe":{"events":["Step"]}}"#).unwrap();
+        assert_eq!(
+            message,
+            WsClientMessage::Subscribe {
+                events: vec![EventFilter::Step],
+                start_from: None,
+            }
+        );
+
+        let message: WsClientMessage =
+            serde_json::from_str(r#"{"Unsubscribe":{"events":["Fault"]}}"#).unwrap();
+        assert_eq!(
+            message,
+            WsClientMessage::Unsubscribe {
+                events: vec![EventFilter::Fault],
+            }
+        );
+
+        assert!(serde_json::from_str::<WsClientMessage>(r#"{"Subscribe":{}}"#).is_err());
+        assert!(
+            serde_json::from_str::<WsClientMessage>(r#"{"Subscribe":{"events":["Bad"]}}"#).is_err()
+        );
+    }
+
+    #[test]
+    fn should_prune_replayed_ids_once_caught_up() {
+        let mut replayed_ids: BTreeSet<Id> = [3, 5, 6, 9].into_iter().collect();
+
+        assert!(!was_replayed(&mut replayed_ids, 4));
+        assert_eq!(replayed_ids, [5, 6, 9].into_iter().collect());
+
+        assert!(was_replayed(&mut replayed_ids, 5));
+        assert!(!was_replayed(&mut replayed_ids, 7));
+        assert_eq!(replayed_ids, [9].into_iter().collect());
+
+        assert!(!was_replayed(&mut replayed_ids, 10));
+        assert!(replayed_ids.is_empty());
+    }
+}
@@ -18,7 +18,7 @@ use prometheus::Registry;
 use tracing::trace;
 
 use crate::{
-    components::Component,
+    components::{network::reputation::Misbehavior, Component},
     effect::{
         announcements::{
             FetchedNewBlockAnnouncement, FetchedNewFinalitySignatureAnnouncement,
@@ -159,14 +159,33 @@ where
                     peer,
                 )
             }
-            Event::TimeoutPeer { id, peer } => self.signal(
-                id.clone(),
-                Err(Error::TimedOut {
-                    id: Box::new(id),
+            Event::TimeoutPeer { id, peer } => {
+                // The peer is only at fault if we are still waiting for its response.
+                let is_awaiting_response = self
+                    .item_handles
+                    .get(&id)
+                    .and_then(|item_handles| item_handles.get(&peer))
+                    .map_or(false, |item_handle| !item_handle.has_no_responders());
+                let mut effects = self.signal(
+                    id.clone(),
+                    Err(Error::TimedOut {
+                        id: Box::new(id),
+                        peer,
+                    }),
                     peer,
-                }),
-                peer,
-            ),
+                );
+                if is_awaiting_response {
+                    effects.extend(
+                        effect_builder
+                            .announce_peer_misbehavior(
+                                peer,
+                                Misbehavior::FetchTimedOut { tag: T::TAG },
+                            )
+                            .ignore(),
+                    );
+                }
+                effects
+            }
             Event::PutToStorage { item, peer } => {
                 let mut effects =
                     Self::announce_fetched_new_item(effect_builder, (*item).clone(), peer).ignore();
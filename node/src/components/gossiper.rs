@@ -21,9 +21,9 @@ use prometheus::Registry;
 use tracing::{debug, error, trace, warn};
 
 use crate::{
-    components::Component,
+    components::{network::reputation::Misbehavior, Component},
     effect::{
-        announcements::GossiperAnnouncement,
+        announcements::{GossiperAnnouncement, PeerBehaviorAnnouncement},
         incoming::GossiperIncoming,
         requests::{BeginGossipRequest, NetworkRequest, StorageRequest},
         EffectBuilder, EffectExt, Effects, GossipTarget,
@@ -273,8 +273,13 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> Gossiper<ID_IS_CO
         action: GossipAction,
     ) -> Effects<Event<T>>
     where
-        REv: From<NetworkRequest<Message<T>>> + From<GossiperAnnouncement<T>> + Send,
+        REv: From<NetworkRequest<Message<T>>>
+            + From<GossiperAnnouncement<T>>
+            + From<PeerBehaviorAnnouncement>
+            + Send,
     {
+        let is_repeated =
+            self.table.register_gossip_request(&item_id, sender) && !T::IS_REGOSSIPED_PERIODICALLY;
         let mut effects = match action {
             GossipAction::ShouldGossip(should_gossip) => {
                 debug!(item=%item_id, %sender, %should_gossip, "received gossip request");
@@ -349,6 +354,13 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> Gossiper<ID_IS_CO
                 effects
             }
         };
+        if is_repeated {
+            effects.extend(
+                effect_builder
+                    .announce_peer_misbehavior(sender, Misbehavior::GossipSpam)
+                    .ignore(),
+            );
+        }
         if T::REQUIRES_GOSSIP_RECEIVED_ANNOUNCEMENT {
             effects.extend(
                 effect_builder
@@ -558,6 +570,7 @@ where
     REv: From<NetworkRequest<Message<T>>>
         + From<StorageRequest>
         + From<GossiperAnnouncement<T>>
+        + From<PeerBehaviorAnnouncement>
         + Send,
     Self: ItemProvider<T>,
 {
@@ -660,6 +673,7 @@ where
     REv: From<NetworkRequest<Message<T>>>
         + From<StorageRequest>
         + From<GossiperAnnouncement<T>>
+        + From<PeerBehaviorAnnouncement>
         + Send,
     Self: ItemProvider<T>,
 {
@@ -18,6 +18,9 @@ pub(crate) trait GossipItem:
     const ID_IS_COMPLETE_ITEM: bool;
     /// Whether the arrival of a new gossip message should be announced or not.
     const REQUIRES_GOSSIP_RECEIVED_ANNOUNCEMENT: bool;
+    /// Whether the item is gossiped again periodically by its origin, so that repeated gossip
+    /// requests for it are not a sign of misbehavior.
+    const IS_REGOSSIPED_PERIODICALLY: bool;
 
     /// The ID of the specific item.
     fn gossip_id(&self) -> Self::Id;
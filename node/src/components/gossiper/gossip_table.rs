@@ -15,6 +15,13 @@ use tracing::{error, trace, warn};
 use super::Config;
 use crate::{effect::GossipTarget, types::NodeId, utils::DisplayIter};
 
+/// The period over which gossip requests for the same data from the same peer are counted.
+const GOSSIP_REQUEST_WINDOW: Duration = Duration::from_secs(10);
+/// The maximum number of gossip requests for the same data a peer may send us within
+/// `GOSSIP_REQUEST_WINDOW`.  An honest peer can send several if it gossips the data again before
+/// learning that it already sent it to us, or if it restarts gossiping the data.
+const MAX_GOSSIP_REQUESTS_PER_PEER: u8 = 5;
+
 #[derive(Debug, PartialEq, Eq)]
 pub(super) enum GossipAction {
     /// This is new data, previously unknown by us, and for which we don't yet hold everything
@@ -97,6 +104,9 @@ pub(super) struct State {
     target: Option<GossipTarget>,
     /// The set of peers we attempted to infect.
     attempted_to_infect: HashSet<NodeId>,
+    /// The start of the current window and the number of gossip requests for this data each peer
+    /// has sent us within it.
+    gossip_requests: HashMap<NodeId, (Instant, u8)>,
 }
 
 impl State {
@@ -312,6 +322,25 @@ impl<T: Clone + Eq + Hash + Display> GossipTable<T> {
         action
     }
 
+    /// We received a gossip request for the data from `sender`.  Should be called after the request
+    /// has been registered via `new_data_id` or `new_complete_data`.
+    ///
+    /// Returns `true` if `sender` has sent us more than `MAX_GOSSIP_REQUESTS_PER_PEER` gossip
+    /// requests for this data within `GOSSIP_REQUEST_WINDOW` while we are still gossiping it, which
+    /// an honest peer does not do.
+    pub(super) fn register_gossip_request(&mut self, data_id: &T, sender: NodeId) -> bool {
+        let now = Instant::now();
+        self.current.get_mut(data_id).map_or(false, |state| {
+            let (window_start, count) = state.gossip_requests.entry(sender).or_insert((now, 0));
+            if now >= *window_start + GOSSIP_REQUEST_WINDOW {
+                *window_start = now;
+                *count = 0;
+            }
+            *count = count.saturating_add(1);
+            *count > MAX_GOSSIP_REQUESTS_PER_PEER
+        })
+    }
+
     pub(super) fn register_infection_attempt<'a>(
         &'a mut self,
         item_id: &T,
@@ -1094,6 +1123,42 @@ mod tests {
         assert!(!gossip_table.finished.contains(&data_id));
     }
 
+    #[test]
+    fn should_detect_repeated_gossip_requests() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+        let node_ids = random_node_ids(&mut rng);
+        let data_id: u64 = rng.gen();
+
+        let mut gossip_table = GossipTable::new(Config::default());
+
+        // Unknown data IDs are never considered repeated.
+        assert!(!gossip_table.register_gossip_request(&data_id, node_ids[0]));
+
+        let _ = gossip_table.new_data_id(&data_id, node_ids[0]);
+        assert!(!gossip_table.register_gossip_request(&data_id, node_ids[0]));
+        let _ = gossip_table.new_data_id(&data_id, node_ids[1]);
+        assert!(!gossip_table.register_gossip_request(&data_id, node_ids[1]));
+
+        // The same peer gossiping the same data ID a few more times may not have learned yet that
+        // it already did, but any more is a repetition.
+        for _ in 1..MAX_GOSSIP_REQUESTS_PER_PEER {
+            let _ = gossip_table.new_data_id(&data_id, node_ids[0]);
+            assert!(!gossip_table.register_gossip_request(&data_id, node_ids[0]));
+        }
+        let _ = gossip_table.new_data_id(&data_id, node_ids[0]);
+        assert!(gossip_table.register_gossip_request(&data_id, node_ids[0]));
+        assert!(!gossip_table.register_gossip_request(&data_id, node_ids[1]));
+
+        // Requests are only counted within a window.
+        Instant::advance_time(GOSSIP_REQUEST_WINDOW.as_millis() as u64);
+        assert!(!gossip_table.register_gossip_request(&data_id, node_ids[0]));
+
+        // Once finished, the data ID is forgotten.
+        assert!(gossip_table.force_finish(&data_id));
+        assert!(!gossip_table.register_gossip_request(&data_id, node_ids[0]));
+    }
+
     #[test]
     fn timeouts_purge_in_order() {
         let mut timeouts = Timeouts::new();
@@ -26,6 +26,8 @@
 mod bincode_format;
 pub(crate) mod blocklist;
 mod chain_info;
+mod channels;
+mod compression;
 mod config;
 mod counting_format;
 mod error;
@@ -39,6 +41,7 @@ mod message;
 mod message_pack_format;
 mod metrics;
 mod outgoing;
+pub(crate) mod reputation;
 mod symmetry;
 pub(crate) mod tasks;
 #[cfg(test)]
@@ -53,6 +56,7 @@ use std::{
     time::{Duration, Instant},
 };
 
+use bincode::Options;
 use datasize::DataSize;
 use futures::{future::BoxFuture, FutureExt};
 use itertools::Itertools;
@@ -62,19 +66,12 @@ use rand::{
     Rng,
 };
 use serde::{Deserialize, Serialize};
-use tokio::{
-    net::TcpStream,
-    sync::{
-        mpsc::{self, UnboundedSender},
-        watch,
-    },
-    task::JoinHandle,
-};
+use tokio::{net::TcpStream, sync::watch, task::JoinHandle};
 use tokio_openssl::SslStream;
 use tokio_util::codec::LengthDelimitedCodec;
 use tracing::{debug, error, info, trace, warn, Instrument, Span};
 
-use casper_types::{EraId, PublicKey, SecretKey};
+use casper_types::EraId;
 
 pub(crate) use self::{
     bincode_format::BincodeFormat,
@@ -92,6 +89,8 @@ pub(crate) use self::{
 use self::{
     blocklist::BlocklistJustification,
     chain_info::ChainInfo,
+    channels::{QueueError, QueueSender},
+    compression::{CompressingFormat, FrameCompression, FRAME_HEADER_SIZE},
     counting_format::{ConnectionId, CountingFormat, Role},
     error::{ConnectionError, Result},
     event::{IncomingConnection, OutgoingConnection},
@@ -100,8 +99,9 @@ use self::{
     message::NodeKeyPair,
     metrics::Metrics,
     outgoing::{DialOutcome, DialRequest, OutgoingConfig, OutgoingManager},
+    reputation::{PeerScores, Penalty},
     symmetry::ConnectionSymmetry,
-    tasks::{MessageQueueItem, NetworkContext},
+    tasks::NetworkContext,
 };
 use crate::{
     components::{gossiper::GossipItem, Component, ComponentState, InitializedComponent},
@@ -111,6 +111,7 @@ use crate::{
         AutoClosingResponder, EffectBuilder, EffectExt, Effects, GossipTarget,
     },
     reactor::{Finalize, ReactorEvent},
+    signer::Signer,
     tls,
     types::{NodeId, ValidatorMatrix},
     utils::{self, display_error, Source},
@@ -150,8 +151,8 @@ const PING_RETRIES: u16 = 5;
 
 #[derive(Clone, DataSize, Debug)]
 pub(crate) struct OutgoingHandle<P> {
-    #[data_size(skip)] // Unfortunately, there is no way to inspect an `UnboundedSender`.
-    sender: UnboundedSender<MessageQueueItem<P>>,
+    #[data_size(skip)]
+    sender: QueueSender<P>,
     peer_addr: SocketAddr,
 }
 
@@ -180,6 +181,9 @@ where
     /// Tracks nodes that have announced themselves as nodes that are syncing.
     syncing_nodes: HashSet<NodeId>,
 
+    /// Reputation scores of peers.
+    peer_scores: PeerScores,
+
     channel_management: Option<ChannelManagement>,
 
     /// Networking metrics.
@@ -242,11 +246,12 @@ where
     pub(crate) fn new<C: Into<ChainInfo>>(
         cfg: Config,
         our_identity: Identity,
-        node_key_pair: Option<(Arc<SecretKey>, PublicKey)>,
+        node_key_pair: Option<Arc<dyn Signer>>,
         registry: &Registry,
         chain_info_source: C,
         validator_matrix: ValidatorMatrix,
     ) -> Result<Network<REv, P>> {
+        cfg.reputation.validate()?;
         let net_metrics = Arc::new(Metrics::new(registry)?);
 
         let outgoing_limiter = Limiter::new(
@@ -277,6 +282,8 @@ where
             net_metrics.create_outgoing_metrics(),
         );
 
+        let peer_scores = PeerScores::new(cfg.reputation.clone());
+
         let context = Arc::new(NetworkContext::new(
             cfg.clone(),
             our_identity,
@@ -291,6 +298,7 @@ where
             outgoing_manager,
             connection_symmetries: HashMap::new(),
             syncing_nodes: HashSet::new(),
+            peer_scores,
             channel_management: None,
             net_metrics,
             outgoing_limiter,
@@ -414,6 +422,10 @@ where
     fn broadcast_message_to_validators(&self, msg: Arc<Message<P>>, era_id: EraId) {
         self.net_metrics.broadcast_requests.inc();
 
+        let estimated_wire_size = match estimated_wire_size(&msg) {
+            Some(size) => size,
+            None => return,
+        };
         let mut total_connected_validators_in_era = 0;
         let mut total_outgoing_manager_connected_peers = 0;
 
@@ -421,7 +433,7 @@ where
             total_outgoing_manager_connected_peers += 1;
             if self.outgoing_limiter.is_validator_in_era(era_id, &peer_id) {
                 total_connected_validators_in_era += 1;
-                self.send_message(peer_id, msg.clone(), None)
+                self.queue_message(peer_id, msg.clone(), estimated_wire_size, None)
             }
         }
 
@@ -445,14 +457,31 @@ where
     ) -> HashSet<NodeId> {
         let is_validator_in_era =
             |era: EraId, peer_id: &NodeId| self.outgoing_limiter.is_validator_in_era(era, peer_id);
-        let peer_ids = choose_gossip_peers(
+
+        // Peers with a poor reputation are only chosen if there are not enough other peers.
+        let now = Instant::now();
+        let (poor_peers, other_peers): (Vec<_>, Vec<_>) = self
+            .outgoing_manager
+            .connected_peers()
+            .partition(|peer_id| self.peer_scores.is_poor(peer_id, now));
+        let mut peer_ids = choose_gossip_peers(
             rng,
             gossip_target,
             count,
             exclude.clone(),
-            self.outgoing_manager.connected_peers(),
+            other_peers.into_iter(),
             is_validator_in_era,
         );
+        if peer_ids.len() < count {
+            peer_ids.extend(choose_gossip_peers(
+                rng,
+                gossip_target,
+                count - peer_ids.len(),
+                exclude.clone(),
+                poor_peers.into_iter(),
+                is_validator_in_era,
+            ));
+        }
 
         // todo!() - consider sampling more validators (for example: 10%, but not fewer than 5)
 
@@ -476,8 +505,10 @@ where
             }
         }
 
-        for &peer_id in &peer_ids {
-            self.send_message(peer_id, msg.clone(), None);
+        if let Some(estimated_wire_size) = estimated_wire_size(&msg) {
+            for &peer_id in &peer_ids {
+                self.queue_message(peer_id, msg.clone(), estimated_wire_size, None);
+            }
         }
 
         peer_ids.into_iter().collect()
@@ -489,6 +520,21 @@ where
         dest: NodeId,
         msg: Arc<Message<P>>,
         opt_responder: Option<AutoClosingResponder<()>>,
+    ) {
+        if let Some(estimated_wire_size) = estimated_wire_size(&msg) {
+            self.queue_message(dest, msg, estimated_wire_size, opt_responder);
+        }
+    }
+
+    /// Queues a message with a known estimated serialized size to be sent to a specific node.
+    ///
+    /// Messages sent to several nodes should only have their size estimated once.
+    fn queue_message(
+        &self,
+        dest: NodeId,
+        msg: Arc<Message<P>>,
+        estimated_wire_size: u32,
+        opt_responder: Option<AutoClosingResponder<()>>,
     ) {
         // Try to send the message.
         if let Some(connection) = self.outgoing_manager.get_route(dest) {
@@ -499,11 +545,20 @@ where
                 error!(kind=%msg.classify(), node_id=%dest, "sending unsafe message to syncing node");
             }
 
-            if let Err(msg) = connection.sender.send((msg, opt_responder)) {
-                // We lost the connection, but that fact has not reached us yet.
-                warn!(our_id=%self.context.our_id(), %dest, ?msg, "dropped outgoing message, lost connection");
-            } else {
-                self.net_metrics.queued_messages.inc();
+            let kind = msg.classify();
+            match connection
+                .sender
+                .send(kind, msg, opt_responder, estimated_wire_size)
+            {
+                Ok(()) => {}
+                Err(QueueError::Closed) => {
+                    // We lost the connection, but that fact has not reached us yet.
+                    warn!(our_id=%self.context.our_id(), %dest, %kind, "dropped outgoing message, lost connection");
+                }
+                Err(QueueError::Full) => {
+                    // The drop has already been counted in the channel's metrics.
+                    warn!(our_id=%self.context.our_id(), %dest, %kind, "dropped outgoing message, channel full");
+                }
             }
         } else {
             // We are not connected, so the reconnection is likely already in progress.
@@ -758,7 +813,11 @@ where
             } => {
                 info!("new outgoing connection established");
 
-                let (sender, receiver) = mpsc::unbounded_channel();
+                let (sender, receiver) = channels::outgoing_queue(
+                    &self.cfg.channels,
+                    &self.net_metrics.channels,
+                    self.net_metrics.queued_messages.clone(),
+                );
                 let handle = OutgoingHandle { sender, peer_addr };
 
                 let request = self
@@ -789,7 +848,6 @@ where
                         sink,
                         self.outgoing_limiter
                             .create_handle(peer_id, peer_consensus_public_key),
-                        self.net_metrics.queued_messages.clone(),
                     )
                     .instrument(span)
                     .event(move |_| Event::OutgoingDropped {
@@ -865,9 +923,11 @@ where
         peer_id: NodeId,
         peer_addr: SocketAddr,
     ) -> Effects<Event<P>> {
+        let now = Instant::now();
+        let preferred = !self.peer_scores.is_poor(&peer_id, now);
         let requests = self
             .outgoing_manager
-            .handle_connection_drop(peer_addr, Instant::now());
+            .handle_connection_drop(peer_addr, now, preferred);
 
         self.connection_symmetries
             .entry(peer_id)
@@ -1009,11 +1069,49 @@ where
         rng: &mut NodeRng,
         count: usize,
     ) -> Vec<NodeId> {
-        self.connection_symmetries
+        // Peers with a poor reputation are only chosen if there are not enough other peers.
+        let now = Instant::now();
+        let (poor_peers, other_peers): (Vec<_>, Vec<_>) = self
+            .connection_symmetries
             .iter()
             .filter(|(_, sym)| matches!(sym, ConnectionSymmetry::Symmetric { .. }))
             .map(|(node_id, _)| *node_id)
-            .choose_multiple(rng, count)
+            .partition(|node_id| self.peer_scores.is_poor(node_id, now));
+        let mut chosen = other_peers.into_iter().choose_multiple(rng, count);
+        if chosen.len() < count {
+            chosen.extend(
+                poor_peers
+                    .into_iter()
+                    .choose_multiple(rng, count - chosen.len()),
+            );
+        }
+        chosen
+    }
+
+    /// Lowers the reputation of a peer, blocking it if its score drops to the block threshold.
+    fn penalize_peer(
+        &mut self,
+        offender: NodeId,
+        penalty: Penalty,
+        justification: impl FnOnce(i32) -> BlocklistJustification,
+    ) -> Effects<Event<P>> {
+        let now = Instant::now();
+        if !self.peer_scores.penalize(offender, penalty, now) {
+            return Effects::new();
+        }
+
+        let justification = justification(self.peer_scores.score(&offender, now));
+        // TODO: We do not have a proper by-node-ID blocklist, but rather only block the current
+        // outgoing address of a peer.
+        info!(%offender, %justification, "adding peer to blocklist after transgression");
+
+        if let Some(addr) = self.outgoing_manager.get_addr(offender) {
+            let requests = self.outgoing_manager.block_addr(addr, now, justification);
+            self.process_dial_requests(requests)
+        } else {
+            // Peer got away with it, no longer an outgoing connection.
+            Effects::new()
+        }
     }
 
     pub(crate) fn has_sufficient_fully_connected_peers(&self) -> bool {
@@ -1070,6 +1168,22 @@ where
     }
 }
 
+/// Returns the estimated serialized size of an outgoing message, or `None` if it can't be
+/// serialized and should be dropped.
+fn estimated_wire_size<P: Payload>(msg: &Message<P>) -> Option<u32> {
+    match BincodeFormat::default().0.serialized_size(msg) {
+        Ok(size) => Some(size as u32),
+        Err(error) => {
+            error!(
+                error = display_error(&error),
+                kind = %msg.classify(),
+                "failed to get serialized size of outgoing message, dropping it"
+            );
+            None
+        }
+    }
+}
+
 fn choose_gossip_peers<F>(
     rng: &mut NodeRng,
     gossip_target: GossipTarget,
@@ -1209,6 +1323,9 @@ where
                     NetworkInfoRequest::Peers { responder } => {
                         responder.respond(self.peers()).ignore()
                     }
+                    NetworkInfoRequest::PeerReputations { responder } => responder
+                        .respond(self.peer_scores.snapshot(Instant::now()))
+                        .ignore(),
                     NetworkInfoRequest::FullyConnectedPeers { count, responder } => responder
                         .respond(self.fully_connected_peers_random(rng, count))
                         .ignore(),
@@ -1244,6 +1361,7 @@ where
                 Event::SweepOutgoing => {
                     let now = Instant::now();
                     let requests = self.outgoing_manager.perform_housekeeping(rng, now);
+                    self.peer_scores.prune(now);
 
                     let mut effects = self.process_dial_requests(requests);
 
@@ -1260,22 +1378,15 @@ where
                         offender,
                         justification,
                     } => {
-                        // TODO: We do not have a proper by-node-ID blocklist, but rather only block
-                        // the current outgoing address of a peer.
-                        info!(%offender, %justification, "adding peer to blocklist after transgression");
-
-                        if let Some(addr) = self.outgoing_manager.get_addr(*offender) {
-                            let requests = self.outgoing_manager.block_addr(
-                                addr,
-                                Instant::now(),
-                                *justification,
-                            );
-                            self.process_dial_requests(requests)
-                        } else {
-                            // Peer got away with it, no longer an outgoing connection.
-                            Effects::new()
-                        }
+                        // Offenses are provably bad behavior, so the peer is blocked right away.
+                        self.penalize_peer(*offender, Penalty::Block, |_| *justification)
                     }
+                    PeerBehaviorAnnouncement::MisbehaviorObserved {
+                        offender,
+                        misbehavior,
+                    } => self.penalize_peer(*offender, misbehavior.penalty(), |score| {
+                        BlocklistJustification::PoorReputation { score }
+                    }),
                 },
             },
         }
@@ -1320,7 +1431,7 @@ pub(crate) type FullTransport<P> = tokio_serde::Framed<
     FramedTransport,
     Message<P>,
     Arc<Message<P>>,
-    CountingFormat<BincodeFormat>,
+    CountingFormat<CompressingFormat<BincodeFormat>>,
 >;
 
 pub(crate) type FramedTransport = tokio_util::codec::Framed<Transport, LengthDelimitedCodec>;
@@ -1328,28 +1439,34 @@ pub(crate) type FramedTransport = tokio_util::codec::Framed<Transport, LengthDel
 /// Constructs a new full transport on a stream.
 ///
 /// A full transport contains the framing as well as the encoding scheme used to send messages.
+/// Frames are compressed if `compression` is given.
 fn full_transport<P>(
     metrics: Weak<Metrics>,
     connection_id: ConnectionId,
     framed: FramedTransport,
+    compression: Option<FrameCompression>,
     role: Role,
 ) -> FullTransport<P>
 where
     for<'de> P: Serialize + Deserialize<'de>,
     for<'de> Message<P>: Serialize + Deserialize<'de>,
 {
+    let compressing_format =
+        CompressingFormat::new(metrics.clone(), compression, BincodeFormat::default());
     tokio_serde::Framed::new(
         framed,
-        CountingFormat::new(metrics, connection_id, role, BincodeFormat::default()),
+        CountingFormat::new(metrics, connection_id, role, compressing_format),
     )
 }
 
 /// Constructs a framed transport.
 fn framed_transport(transport: Transport, maximum_net_message_size: u32) -> FramedTransport {
+    // Leave room for the frame header in case compression is negotiated.
+    let max_frame_length = maximum_net_message_size.saturating_add(FRAME_HEADER_SIZE);
     tokio_util::codec::Framed::new(
         transport,
         LengthDelimitedCodec::builder()
-            .max_frame_length(maximum_net_message_size as usize)
+            .max_frame_length(max_frame_length as usize)
             .new_codec(),
     )
 }
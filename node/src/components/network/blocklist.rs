@@ -57,6 +57,11 @@ pub(crate) enum BlocklistJustification {
     DishonestPeer,
     /// Peer sent too many finality signatures.
     SentTooManyFinalitySignatures { max_allowed: u32 },
+    /// Peer's reputation dropped to the block threshold due to repeated misbehavior.
+    PoorReputation {
+        /// The score of the peer at the time it was blocked.
+        score: i32,
+    },
 }
 
 impl Display for BlocklistJustification {
@@ -105,6 +110,9 @@ impl Display for BlocklistJustification {
                 f,
                 "sent too many finality signatures: maximum {max_allowed} signatures are allowed"
             ),
+            BlocklistJustification::PoorReputation { score } => {
+                write!(f, "reputation dropped to {}", score)
+            }
         }
     }
 }
@@ -46,21 +46,28 @@ impl ChainInfo {
     }
 
     /// Create a handshake based on chain identification data.
-    pub(super) fn create_handshake<P>(
+    pub(super) async fn create_handshake<P>(
         &self,
         public_addr: SocketAddr,
         consensus_keys: Option<&NodeKeyPair>,
         connection_id: ConnectionId,
         is_syncing: bool,
+        accepts_compression: bool,
     ) -> Message<P> {
+        let consensus_certificate = match consensus_keys {
+            Some(key_pair) => {
+                ConsensusCertificate::create(connection_id, key_pair, self.protocol_version).await
+            }
+            None => None,
+        };
         Message::Handshake {
             network_name: self.network_name.clone(),
             public_addr,
             protocol_version: self.protocol_version,
-            consensus_certificate: consensus_keys
-                .map(|key_pair| ConsensusCertificate::create(connection_id, key_pair)),
+            consensus_certificate,
             is_syncing,
             chainspec_hash: Some(self.chainspec_hash),
+            accepts_compression,
         }
     }
 }
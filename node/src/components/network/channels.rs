@@ -0,0 +1,499 @@
+//! Per-message-kind channels sharing an outgoing connection.
+//!
+//! Every outgoing connection has one logical channel per [`MessageKind`], each with its own queue.
+//! Whenever the connection is ready to send, the next message is taken from the channel with the
+//! highest priority (i.e. the lowest `priority` value) which still has credit.  Channels earn
+//! credit in proportion to their `bandwidth_share` whenever all channels with queued messages have
+//! used theirs up (deficit round robin), so that under contention every channel gets at least its
+//! share of the connection's bandwidth, and a channel with higher priority doesn't wait behind a
+//! backlog of lower priority messages as long as it has credit.
+//!
+//! Priorities only determine the order in which messages are taken from the queue: a message
+//! already taken is sent first.  In particular, if the connection is subject to a bandwidth limit
+//! (see `max_outgoing_byte_rate_non_validators`), a message with higher priority can still wait
+//! for the allowance of a lower priority message taken from the queue before it.
+//!
+//! If a channel's queue exceeds `max_queued_bytes`, further messages sent on it are dropped and
+//! counted in the channel's `dropped` metric, unless the sender is waiting for the message to be
+//! sent, in which case it is queued anyway, and the waiting sender is held back until it is sent.
+
+use std::{
+    collections::VecDeque,
+    fmt::{self, Debug, Formatter},
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use datasize::DataSize;
+use prometheus::IntGauge;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use tokio::sync::Notify;
+
+use super::{metrics::ChannelMetrics, Message, MessageKind};
+use crate::effect::AutoClosingResponder;
+
+/// The number of bytes of credit a channel earns per round for each unit of bandwidth share.
+const BYTES_PER_SHARE: i64 = 4 * 1024;
+
+/// Configuration of the channel for one kind of message.
+#[derive(Clone, Copy, DataSize, Debug, Deserialize, Serialize, PartialEq, Eq)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct ChannelConfig {
+    /// Priority of the channel: messages on channels with a lower value are sent first.
+    pub priority: u8,
+    /// The channel's share of the connection's bandwidth, relative to the other channels'.
+    pub bandwidth_share: u16,
+    /// Maximum total size of the messages queued on the channel.  Unlimited if 0.
+    pub max_queued_bytes: u32,
+}
+
+impl ChannelConfig {
+    const fn new(priority: u8, bandwidth_share: u16, max_queued_bytes: u32) -> Self {
+        ChannelConfig {
+            priority,
+            bandwidth_share,
+            max_queued_bytes,
+        }
+    }
+}
+
+/// Configuration of the channels for all kinds of messages.
+#[derive(Clone, DataSize, Debug, Deserialize, Serialize, PartialEq, Eq)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields, default)]
+pub struct ChannelsConfig {
+    /// Non-payload messages, like handshakes and pings.
+    pub protocol: ChannelConfig,
+    /// Consensus messages.
+    pub consensus: ChannelConfig,
+    /// Deploys being gossiped.
+    pub deploy_gossip: ChannelConfig,
+    /// Blocks being gossiped.
+    pub block_gossip: ChannelConfig,
+    /// Finality signatures being gossiped.
+    pub finality_signature_gossip: ChannelConfig,
+    /// Addresses being gossiped.
+    pub address_gossip: ChannelConfig,
+    /// Deploys being transferred directly.
+    pub deploy_transfer: ChannelConfig,
+    /// Blocks being transferred directly.
+    pub block_transfer: ChannelConfig,
+    /// Tries being transferred directly.
+    pub trie_transfer: ChannelConfig,
+    /// Any other messages.
+    pub other: ChannelConfig,
+}
+
+impl Default for ChannelsConfig {
+    fn default() -> Self {
+        const MIB: u32 = 1024 * 1024;
+        ChannelsConfig {
+            protocol: ChannelConfig::new(0, 10, 0),
+            consensus: ChannelConfig::new(0, 40, 0),
+            deploy_gossip: ChannelConfig::new(2, 10, 16 * MIB),
+            block_gossip: ChannelConfig::new(1, 10, 16 * MIB),
+            finality_signature_gossip: ChannelConfig::new(1, 10, 16 * MIB),
+            address_gossip: ChannelConfig::new(2, 5, 16 * MIB),
+            deploy_transfer: ChannelConfig::new(2, 10, 64 * MIB),
+            block_transfer: ChannelConfig::new(3, 20, 128 * MIB),
+            trie_transfer: ChannelConfig::new(3, 20, 128 * MIB),
+            other: ChannelConfig::new(2, 5, 16 * MIB),
+        }
+    }
+}
+
+impl ChannelsConfig {
+    /// Returns the configuration of the channel for the given kind of message.
+    pub(super) fn get(&self, kind: MessageKind) -> ChannelConfig {
+        match kind {
+            MessageKind::Protocol => self.protocol,
+            MessageKind::Consensus => self.consensus,
+            MessageKind::DeployGossip => self.deploy_gossip,
+            MessageKind::BlockGossip => self.block_gossip,
+            MessageKind::FinalitySignatureGossip => self.finality_signature_gossip,
+            MessageKind::AddressGossip => self.address_gossip,
+            MessageKind::DeployTransfer => self.deploy_transfer,
+            MessageKind::BlockTransfer => self.block_transfer,
+            MessageKind::TrieTransfer => self.trie_transfer,
+            MessageKind::Other => self.other,
+        }
+    }
+}
+
+/// A message waiting to be sent.
+pub(super) struct QueuedMessage<P> {
+    /// The message.
+    pub(super) message: Arc<Message<P>>,
+    /// A responder to notify once the message has been sent.
+    pub(super) responder: Option<AutoClosingResponder<()>>,
+    /// The estimated serialized size of the message.
+    pub(super) size: u32,
+    /// The kind of the message, identifying the channel it was queued on.
+    kind: MessageKind,
+    /// When the message was queued.
+    queued_at: Instant,
+}
+
+/// The error returned when a message could not be queued.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum QueueError {
+    /// The connection has been closed.
+    Closed,
+    /// The channel's queue is full.
+    Full,
+}
+
+/// A channel's queue and scheduling state.
+struct Channel<P> {
+    config: ChannelConfig,
+    queue: VecDeque<QueuedMessage<P>>,
+    queued_bytes: u64,
+    /// The number of bytes the channel can send before it needs to earn more credit.
+    deficit: i64,
+    metrics: ChannelMetrics,
+}
+
+impl<P> Channel<P> {
+    /// Returns the credit earned per round.
+    fn quantum(&self) -> i64 {
+        i64::from(self.config.bandwidth_share.max(1)) * BYTES_PER_SHARE
+    }
+}
+
+/// The state shared by the sending and receiving ends of an outgoing queue.
+struct State<P> {
+    /// The channels, indexed by message kind.
+    channels: Vec<Channel<P>>,
+    closed: bool,
+}
+
+impl<P> State<P> {
+    /// Removes the next message to be sent, if any.
+    fn pop(&mut self) -> Option<QueuedMessage<P>> {
+        loop {
+            let next = self
+                .channels
+                .iter_mut()
+                .filter(|channel| !channel.queue.is_empty() && channel.deficit > 0)
+                .min_by_key(|channel| channel.config.priority);
+            if let Some(channel) = next {
+                let queued_message = channel.queue.pop_front()?;
+                channel.queued_bytes -= u64::from(queued_message.size);
+                channel.deficit -= i64::from(queued_message.size);
+                if channel.queue.is_empty() {
+                    // Idle channels don't accumulate credit.
+                    channel.deficit = channel.deficit.min(0);
+                }
+                return Some(queued_message);
+            }
+
+            // All channels with queued messages have used up their credit: Start as many rounds as
+            // are needed for one of them to have credit again.
+            let rounds = self
+                .channels
+                .iter()
+                .filter(|channel| !channel.queue.is_empty())
+                .map(|channel| -channel.deficit / channel.quantum() + 1)
+                .min()?;
+            for channel in &mut self.channels {
+                if !channel.queue.is_empty() {
+                    channel.deficit += rounds * channel.quantum();
+                }
+            }
+        }
+    }
+}
+
+/// The queue of messages to be sent on an outgoing connection.
+struct Shared<P> {
+    state: Mutex<State<P>>,
+    /// Notified whenever a message is queued or the queue is closed.
+    notify: Notify,
+    /// The total number of messages queued on all connections.
+    total_queued: IntGauge,
+}
+
+impl<P> Shared<P> {
+    fn close(&self) {
+        self.state.lock().unwrap().closed = true;
+        self.notify.notify_one();
+    }
+}
+
+/// Closes the queue when the last sending handle is dropped.
+struct CloseOnDrop<P>(Arc<Shared<P>>);
+
+impl<P> Drop for CloseOnDrop<P> {
+    fn drop(&mut self) {
+        self.0.close();
+    }
+}
+
+/// The sending end of an outgoing queue.
+pub(super) struct QueueSender<P> {
+    shared: Arc<Shared<P>>,
+    _close_on_drop: Arc<CloseOnDrop<P>>,
+}
+
+impl<P> Clone for QueueSender<P> {
+    fn clone(&self) -> Self {
+        QueueSender {
+            shared: Arc::clone(&self.shared),
+            _close_on_drop: Arc::clone(&self._close_on_drop),
+        }
+    }
+}
+
+impl<P> Debug for QueueSender<P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QueueSender").finish_non_exhaustive()
+    }
+}
+
+impl<P> QueueSender<P> {
+    /// Queues `message`, of the given kind and estimated serialized size, to be sent.
+    pub(super) fn send(
+        &self,
+        kind: MessageKind,
+        message: Arc<Message<P>>,
+        responder: Option<AutoClosingResponder<()>>,
+        size: u32,
+    ) -> Result<(), QueueError> {
+        let mut state = self.shared.state.lock().unwrap();
+        if state.closed {
+            return Err(QueueError::Closed);
+        }
+        let channel = &mut state.channels[kind as usize];
+        let max_queued_bytes = u64::from(channel.config.max_queued_bytes);
+        if responder.is_none()
+            && max_queued_bytes != 0
+            && channel.queued_bytes + u64::from(size) > max_queued_bytes
+        {
+            channel.metrics.dropped.inc();
+            return Err(QueueError::Full);
+        }
+        channel.queue.push_back(QueuedMessage {
+            message,
+            responder,
+            size,
+            kind,
+            queued_at: Instant::now(),
+        });
+        channel.queued_bytes += u64::from(size);
+        channel.metrics.queued.inc();
+        self.shared.total_queued.inc();
+        drop(state);
+        self.shared.notify.notify_one();
+        Ok(())
+    }
+}
+
+/// The receiving end of an outgoing queue.
+pub(super) struct QueueReceiver<P> {
+    shared: Arc<Shared<P>>,
+}
+
+impl<P> QueueReceiver<P> {
+    /// Waits for the next message to be sent.  Returns `None` once the queue has been closed and
+    /// all messages have been received.
+    pub(super) async fn recv(&mut self) -> Option<QueuedMessage<P>> {
+        loop {
+            {
+                let mut state = self.shared.state.lock().unwrap();
+                if let Some(queued_message) = state.pop() {
+                    let metrics = &state.channels[queued_message.kind as usize].metrics;
+                    metrics.queued.dec();
+                    metrics
+                        .queue_time
+                        .inc_by(queued_message.queued_at.elapsed().as_secs_f64());
+                    self.shared.total_queued.dec();
+                    return Some(queued_message);
+                }
+                if state.closed {
+                    return None;
+                }
+            }
+            self.shared.notify.notified().await;
+        }
+    }
+
+    /// Closes the queue and drops all messages still queued.
+    fn close_and_drain(&mut self) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.closed = true;
+        for channel in &mut state.channels {
+            let count = channel.queue.len() as i64;
+            channel.metrics.queued.sub(count);
+            self.shared.total_queued.sub(count);
+            channel.queue.clear();
+            channel.queued_bytes = 0;
+        }
+    }
+}
+
+impl<P> Drop for QueueReceiver<P> {
+    fn drop(&mut self) {
+        // Ensure metrics are up to date and senders notice that the connection is gone.
+        self.close_and_drain();
+    }
+}
+
+/// Creates an outgoing queue with channels configured by `config`.
+pub(super) fn outgoing_queue<P>(
+    config: &ChannelsConfig,
+    metrics: &[ChannelMetrics],
+    total_queued: IntGauge,
+) -> (QueueSender<P>, QueueReceiver<P>) {
+    let channels = MessageKind::iter()
+        .map(|kind| Channel {
+            config: config.get(kind),
+            queue: VecDeque::new(),
+            queued_bytes: 0,
+            deficit: 0,
+            metrics: metrics[kind as usize].clone(),
+        })
+        .collect();
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            channels,
+            closed: false,
+        }),
+        notify: Notify::new(),
+        total_queued,
+    });
+    let sender = QueueSender {
+        shared: Arc::clone(&shared),
+        _close_on_drop: Arc::new(CloseOnDrop(Arc::clone(&shared))),
+    };
+    (sender, QueueReceiver { shared })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use prometheus::{IntGauge, Registry};
+
+    use super::{outgoing_queue, ChannelConfig, ChannelsConfig, QueueError, QueueSender};
+    use crate::components::network::{Message, MessageKind, Metrics};
+
+    fn ping() -> Arc<Message<()>> {
+        Arc::new(Message::Ping {
+            nonce: rand::random(),
+        })
+    }
+
+    fn send(sender: &QueueSender<()>, kind: MessageKind, size: u32) -> Result<(), QueueError> {
+        sender.send(kind, ping(), None, size)
+    }
+
+    #[tokio::test]
+    async fn should_send_higher_priority_first() {
+        let metrics = Metrics::new(&Registry::new()).unwrap();
+        let (sender, mut receiver) = outgoing_queue(
+            &ChannelsConfig::default(),
+            &metrics.channels,
+            IntGauge::new("q", "q").unwrap(),
+        );
+
+        send(&sender, MessageKind::TrieTransfer, 1000).unwrap();
+        send(&sender, MessageKind::TrieTransfer, 1000).unwrap();
+        send(&sender, MessageKind::Consensus, 10).unwrap();
+
+        assert_eq!(receiver.recv().await.unwrap().size, 10);
+        assert_eq!(receiver.recv().await.unwrap().size, 1000);
+        assert_eq!(receiver.recv().await.unwrap().size, 1000);
+    }
+
+    #[tokio::test]
+    async fn should_share_bandwidth_under_contention() {
+        let metrics = Metrics::new(&Registry::new()).unwrap();
+        let config = ChannelsConfig {
+            consensus: ChannelConfig::new(0, 3, 0),
+            trie_transfer: ChannelConfig::new(1, 1, 0),
+            ..Default::default()
+        };
+        let (sender, mut receiver) =
+            outgoing_queue(&config, &metrics.channels, IntGauge::new("q", "q").unwrap());
+
+        for _ in 0..100 {
+            send(&sender, MessageKind::Consensus, 1024).unwrap();
+            send(&sender, MessageKind::TrieTransfer, 1025).unwrap();
+        }
+
+        // Although consensus messages have priority, trie transfers get a quarter of the bandwidth.
+        let mut trie_transfers = 0;
+        for _ in 0..80 {
+            if receiver.recv().await.unwrap().size == 1025 {
+                trie_transfers += 1;
+            }
+        }
+        assert!((18..=22).contains(&trie_transfers), "{}", trie_transfers);
+    }
+
+    #[tokio::test]
+    async fn should_drop_messages_when_channel_is_full() {
+        let metrics = Metrics::new(&Registry::new()).unwrap();
+        let config = ChannelsConfig {
+            deploy_gossip: ChannelConfig::new(2, 10, 1000),
+            ..Default::default()
+        };
+        let total_queued = IntGauge::new("q", "q").unwrap();
+        let (sender, mut receiver) =
+            outgoing_queue(&config, &metrics.channels, total_queued.clone());
+
+        send(&sender, MessageKind::DeployGossip, 600).unwrap();
+        assert_eq!(
+            send(&sender, MessageKind::DeployGossip, 600),
+            Err(QueueError::Full)
+        );
+        // Other channels are not affected.
+        send(&sender, MessageKind::BlockGossip, 600).unwrap();
+
+        let channel_metrics = &metrics.channels[MessageKind::DeployGossip as usize];
+        assert_eq!(channel_metrics.dropped.get(), 1);
+        assert_eq!(channel_metrics.queued.get(), 1);
+        assert_eq!(total_queued.get(), 2);
+
+        receiver.recv().await.unwrap();
+        receiver.recv().await.unwrap();
+        assert_eq!(total_queued.get(), 0);
+        send(&sender, MessageKind::DeployGossip, 600).unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_close_queue_when_either_end_is_dropped() {
+        let metrics = Metrics::new(&Registry::new()).unwrap();
+        let total_queued = IntGauge::new("q", "q").unwrap();
+        let (sender, mut receiver) = outgoing_queue(
+            &ChannelsConfig::default(),
+            &metrics.channels,
+            total_queued.clone(),
+        );
+
+        send(&sender, MessageKind::Consensus, 10).unwrap();
+        let other_sender = sender.clone();
+        drop(sender);
+        send(&other_sender, MessageKind::Consensus, 10).unwrap();
+        drop(other_sender);
+        // Queued messages are still received after all senders are gone.
+        assert!(receiver.recv().await.is_some());
+        assert!(receiver.recv().await.is_some());
+        assert!(receiver.recv().await.is_none());
+
+        let (sender, receiver) = outgoing_queue(
+            &ChannelsConfig::default(),
+            &metrics.channels,
+            total_queued.clone(),
+        );
+        send(&sender, MessageKind::Consensus, 10).unwrap();
+        drop(receiver);
+        assert_eq!(total_queued.get(), 0);
+        assert_eq!(
+            send(&sender, MessageKind::Consensus, 10),
+            Err(QueueError::Closed)
+        );
+    }
+}
@@ -0,0 +1,282 @@
+//! Transparent compression of network frames.
+//!
+//! Nodes advertise in their handshake whether they accept compressed frames.  If both ends of a
+//! connection do, every frame sent on it is prefixed with a single header byte indicating whether
+//! the rest of the frame is the serialized message as-is, or the serialized message compressed
+//! using zstd.  Only messages at or above a configurable size are compressed, and only if
+//! compressing actually makes them smaller.
+//!
+//! Connections to peers that do not advertise compression are unaffected: frames are sent without
+//! a header, exactly as before.
+
+use std::{
+    io,
+    pin::Pin,
+    sync::{Arc, Weak},
+};
+
+use bytes::{BufMut, Bytes, BytesMut};
+use datasize::DataSize;
+use pin_project::pin_project;
+use serde::{Deserialize, Serialize};
+use tokio_serde::{Deserializer, Serializer};
+
+use super::{Message, Metrics};
+
+/// Header byte of a frame containing an uncompressed message.
+const UNCOMPRESSED: u8 = 0;
+/// Header byte of a frame containing a zstd-compressed message.
+const ZSTD: u8 = 1;
+
+/// The number of bytes added to every frame if compression is in use.
+pub(super) const FRAME_HEADER_SIZE: u32 = 1;
+
+/// Configuration of the compression of network frames.
+#[derive(Clone, DataSize, Debug, Deserialize, Serialize, PartialEq, Eq)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields, default)]
+pub struct CompressionConfig {
+    /// Whether to advertise support for compression to peers, and compress messages to peers that
+    /// advertise it.
+    pub enabled: bool,
+    /// Minimum size of a serialized message, in bytes, for it to be compressed.
+    pub threshold: u32,
+    /// The zstd compression level.
+    pub level: i32,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        CompressionConfig {
+            enabled: true,
+            threshold: 1024,
+            level: 3,
+        }
+    }
+}
+
+/// The compression settings in effect on a connection on which both peers accept compression.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct FrameCompression {
+    /// Minimum size of a serialized message for it to be compressed.
+    threshold: usize,
+    /// The zstd compression level.
+    level: i32,
+    /// The maximum size of a decompressed message.
+    maximum_message_size: usize,
+}
+
+impl FrameCompression {
+    /// Returns the settings for a connection, or `None` if frames must not have a header, because
+    /// either we or the peer do not accept compression.
+    pub(super) fn negotiate(
+        config: &CompressionConfig,
+        peer_accepts_compression: bool,
+        maximum_message_size: u32,
+    ) -> Option<Self> {
+        (config.enabled && peer_accepts_compression).then_some(FrameCompression {
+            threshold: config.threshold as usize,
+            level: config.level,
+            maximum_message_size: maximum_message_size as usize,
+        })
+    }
+
+    /// Encodes a serialized message into a frame.
+    fn compress(&self, serialized: Bytes) -> io::Result<Bytes> {
+        if serialized.len() >= self.threshold {
+            let compressed = zstd::bulk::compress(&serialized, self.level)?;
+            if compressed.len() < serialized.len() {
+                return Ok(with_header(ZSTD, &compressed));
+            }
+        }
+        Ok(with_header(UNCOMPRESSED, &serialized))
+    }
+
+    /// Decodes a frame into a serialized message.
+    fn decompress(&self, frame: &[u8]) -> io::Result<BytesMut> {
+        match frame.split_first() {
+            Some((&UNCOMPRESSED, serialized)) => Ok(BytesMut::from(serialized)),
+            Some((&ZSTD, compressed)) => {
+                // Only the size declared in the zstd frame header is allocated, and frames which
+                // don't declare their size or exceed the limit are rejected, guarding against
+                // decompression bombs.
+                let serialized_size = match zstd::zstd_safe::get_frame_content_size(compressed) {
+                    Ok(Some(size)) if size <= self.maximum_message_size as u64 => size as usize,
+                    Ok(Some(size)) => {
+                        return Err(invalid_data(format!(
+                            "compressed message size of {} exceeds maximum of {}",
+                            size, self.maximum_message_size
+                        )))
+                    }
+                    Ok(None) => return Err(invalid_data("compressed frame without content size")),
+                    Err(_) => return Err(invalid_data("malformed compressed frame")),
+                };
+                let serialized = zstd::bulk::decompress(compressed, serialized_size)?;
+                if serialized.len() != serialized_size {
+                    return Err(invalid_data(
+                        "compressed message size differs from its frame header",
+                    ));
+                }
+                Ok(BytesMut::from(serialized.as_slice()))
+            }
+            Some((header, _)) => Err(invalid_data(format!("invalid frame header {}", header))),
+            None => Err(invalid_data("frame without header")),
+        }
+    }
+}
+
+/// Returns an error indicating an invalid frame.
+fn invalid_data<E: Into<Box<dyn std::error::Error + Send + Sync>>>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+/// Prepends `header` to `payload`.
+fn with_header(header: u8, payload: &[u8]) -> Bytes {
+    let mut frame = BytesMut::with_capacity(payload.len() + FRAME_HEADER_SIZE as usize);
+    frame.put_u8(header);
+    frame.put_slice(payload);
+    frame.freeze()
+}
+
+/// A serializer/deserializer wrapper compressing and decompressing frames, if negotiated.
+#[pin_project]
+#[derive(Debug)]
+pub struct CompressingFormat<F> {
+    /// The actual serializer performing the work.
+    #[pin]
+    inner: F,
+    /// The compression settings, if compression is in use on the connection.
+    compression: Option<FrameCompression>,
+    /// Metrics to update.
+    metrics: Weak<Metrics>,
+}
+
+impl<F> CompressingFormat<F> {
+    /// Creates a new compressing formatter.
+    pub(super) fn new(
+        metrics: Weak<Metrics>,
+        compression: Option<FrameCompression>,
+        inner: F,
+    ) -> Self {
+        CompressingFormat {
+            inner,
+            compression,
+            metrics,
+        }
+    }
+}
+
+impl<F, P> Serializer<Arc<Message<P>>> for CompressingFormat<F>
+where
+    F: Serializer<Arc<Message<P>>, Error = io::Error>,
+{
+    type Error = io::Error;
+
+    #[inline]
+    fn serialize(self: Pin<&mut Self>, item: &Arc<Message<P>>) -> Result<Bytes, Self::Error> {
+        let this = self.project();
+        let serialized = F::serialize(this.inner, item)?;
+        match this.compression {
+            Some(compression) => {
+                let serialized_size = serialized.len();
+                let frame = compression.compress(serialized)?;
+                Metrics::record_compression_out(this.metrics, serialized_size, frame.len());
+                Ok(frame)
+            }
+            None => Ok(serialized),
+        }
+    }
+}
+
+impl<F, P> Deserializer<Message<P>> for CompressingFormat<F>
+where
+    F: Deserializer<Message<P>, Error = io::Error>,
+{
+    type Error = io::Error;
+
+    #[inline]
+    fn deserialize(self: Pin<&mut Self>, src: &BytesMut) -> Result<Message<P>, Self::Error> {
+        let this = self.project();
+        match this.compression {
+            Some(compression) => {
+                let serialized = compression.decompress(src)?;
+                Metrics::record_compression_in(this.metrics, serialized.len(), src.len());
+                F::deserialize(this.inner, &serialized)
+            }
+            None => F::deserialize(this.inner, src),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CompressionConfig, FrameCompression, UNCOMPRESSED, ZSTD};
+
+    fn compression() -> FrameCompression {
+        FrameCompression::negotiate(&CompressionConfig::default(), true, 1024 * 1024).unwrap()
+    }
+
+    #[test]
+    fn should_only_compress_if_both_peers_accept_compression() {
+        let config = CompressionConfig::default();
+        assert!(FrameCompression::negotiate(&config, true, 1024).is_some());
+        assert!(FrameCompression::negotiate(&config, false, 1024).is_none());
+        let disabled = CompressionConfig {
+            enabled: false,
+            ..config
+        };
+        assert!(FrameCompression::negotiate(&disabled, true, 1024).is_none());
+    }
+
+    #[test]
+    fn should_roundtrip_compressible_and_small_messages() {
+        let compression = compression();
+
+        let large = vec![7u8; 100_000];
+        let frame = compression.compress(large.clone().into()).unwrap();
+        assert_eq!(frame[0], ZSTD);
+        assert!(frame.len() < 1_000);
+        assert_eq!(&compression.decompress(&frame).unwrap()[..], &large[..]);
+
+        let small = vec![7u8; 100];
+        let frame = compression.compress(small.clone().into()).unwrap();
+        assert_eq!(frame[0], UNCOMPRESSED);
+        assert_eq!(&compression.decompress(&frame).unwrap()[..], &small[..]);
+    }
+
+    #[test]
+    fn should_not_compress_incompressible_messages() {
+        let compression = compression();
+        let random: Vec<u8> = (0..10_000).map(|_| rand::random()).collect();
+        let frame = compression.compress(random.clone().into()).unwrap();
+        assert_eq!(frame[0], UNCOMPRESSED);
+        assert_eq!(frame.len(), random.len() + 1);
+    }
+
+    #[test]
+    fn should_reject_oversized_and_malformed_frames() {
+        let compression = compression();
+        let huge = vec![0u8; 2 * 1024 * 1024];
+        let frame = compression.compress(huge.into()).unwrap();
+        assert_eq!(frame[0], ZSTD);
+        assert!(compression.decompress(&frame).is_err());
+
+        // Frames which don't declare the size of their content are rejected.
+        let mut compressor = zstd::bulk::Compressor::new(3).unwrap();
+        compressor
+            .set_parameter(zstd::zstd_safe::CParameter::ContentSizeFlag(false))
+            .unwrap();
+        let compressed = compressor.compress(&[7u8; 10_000]).unwrap();
+        assert!(matches!(
+            zstd::zstd_safe::get_frame_content_size(&compressed),
+            Ok(None)
+        ));
+        let mut frame = vec![ZSTD];
+        frame.extend_from_slice(&compressed);
+        assert!(compression.decompress(&frame).is_err());
+
+        assert!(compression.decompress(&[]).is_err());
+        assert!(compression.decompress(&[2, 0, 0]).is_err());
+        assert!(compression.decompress(&[ZSTD, 1, 2, 3]).is_err());
+    }
+}
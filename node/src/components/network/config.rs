@@ -6,7 +6,10 @@ use casper_types::{ProtocolVersion, TimeDiff};
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
-use super::EstimatorWeights;
+use super::{
+    channels::ChannelsConfig, compression::CompressionConfig, reputation::ReputationConfig,
+    EstimatorWeights,
+};
 
 /// Default binding address.
 ///
@@ -53,6 +56,9 @@ impl Default for Config {
             max_in_flight_demands: 50,
             blocklist_retain_duration: TimeDiff::from_seconds(600),
             identity: None,
+            channels: Default::default(),
+            compression: Default::default(),
+            reputation: Default::default(),
         }
     }
 }
@@ -116,6 +122,15 @@ pub struct Config {
     /// An identity will be automatically generated when starting up a node if this option is
     /// unspecified.
     pub identity: Option<IdentityConfig>,
+    /// Priorities, bandwidth shares and queue limits of the channels of outgoing connections.
+    #[serde(default)]
+    pub channels: ChannelsConfig,
+    /// Compression of messages sent to and received from peers which also support it.
+    #[serde(default)]
+    pub compression: CompressionConfig,
+    /// Scoring of peers' behavior, used to prefer well-behaved peers and block misbehaving ones.
+    #[serde(default)]
+    pub reputation: ReputationConfig,
 }
 
 #[cfg(test)]
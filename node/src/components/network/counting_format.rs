@@ -261,7 +261,7 @@ impl ConnectionId {
 
     #[inline]
     /// Returns a reference to the raw bytes of the connection ID.
-    pub(crate) fn as_bytes(&self) -> &[u8] {
+    pub(crate) fn as_bytes(&self) -> &[u8; Digest::LENGTH] {
         &self.0
     }
 
@@ -71,6 +71,21 @@ pub enum Error {
         #[from]
         LoadCertError,
     ),
+    /// The reputation score at which peers are blocked is not negative.
+    #[error("reputation block_threshold must be negative, got {0}")]
+    NonNegativeBlockThreshold(i32),
+    /// The reputation score at which peers are considered poor is not above the one at which they
+    /// are blocked.
+    #[error(
+        "reputation poor_threshold {poor_threshold} must be above block_threshold \
+         {block_threshold}"
+    )]
+    PoorThresholdNotAboveBlockThreshold {
+        /// The configured poor threshold.
+        poor_threshold: i32,
+        /// The configured block threshold.
+        block_threshold: i32,
+    },
 }
 
 // Manual implementation for `DataSize` - the type contains too many FFI variants that are hard to
@@ -32,6 +32,7 @@ impl Display for GossipedAddress {
 impl GossipItem for GossipedAddress {
     const ID_IS_COMPLETE_ITEM: bool = true;
     const REQUIRES_GOSSIP_RECEIVED_ANNOUNCEMENT: bool = false;
+    const IS_REGOSSIPED_PERIODICALLY: bool = true;
 
     type Id = GossipedAddress;
 
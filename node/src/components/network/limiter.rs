@@ -299,7 +299,7 @@ mod tests {
     use tokio::time::Instant;
 
     use super::{Limiter, NodeId, PublicKey};
-    use crate::{testing::init_logging, types::ValidatorMatrix};
+    use crate::{signer::LocalSigner, testing::init_logging, types::ValidatorMatrix};
 
     /// Something that happens almost immediately, with some allowance for test jitter.
     const SHORT_TIME: Duration = Duration::from_millis(250);
@@ -462,7 +462,7 @@ mod tests {
                 Ratio::new(1, 3),
                 None,
                 EraId::from(0),
-                Arc::new(secret_key),
+                Arc::new(LocalSigner::new(Arc::new(secret_key))),
                 consensus_key.clone(),
                 2,
             ),
@@ -10,17 +10,23 @@ use serde::{
     de::{DeserializeOwned, Error as SerdeError},
     Deserialize, Deserializer, Serialize, Serializer,
 };
-use strum::EnumDiscriminants;
+use strum::{EnumDiscriminants, EnumIter};
+use tracing::warn;
 
 use casper_hashing::Digest;
+use casper_types::{crypto, AsymmetricType, ProtocolVersion, PublicKey, Signature};
 #[cfg(test)]
-use casper_types::testing::TestRng;
-use casper_types::{crypto, AsymmetricType, ProtocolVersion, PublicKey, SecretKey, Signature};
+use casper_types::{testing::TestRng, SecretKey};
 
 use super::{counting_format::ConnectionId, health::Nonce, BincodeFormat};
+#[cfg(test)]
+use crate::signer::LocalSigner;
 use crate::{
     effect::EffectBuilder,
     protocol,
+    signer::{
+        self, Error as SignerError, Signable, Signer, PREFIXED_HANDSHAKE_CERTIFICATE_VERSION,
+    },
     types::{Chainspec, NodeId},
     utils::{
         opt_display::OptDisplay,
@@ -55,6 +61,9 @@ pub(crate) enum Message<P> {
         /// Hash of the chainspec the node is running.
         #[serde(default)]
         chainspec_hash: Option<Digest>,
+        /// True if the node accepts compressed frames.
+        #[serde(default)]
+        accepts_compression: bool,
     },
     /// A ping request.
     Ping {
@@ -136,24 +145,30 @@ impl<P: Payload> Message<P> {
     }
 }
 
-/// A pair of secret keys used by consensus.
+/// The signer of the consensus key pair.
 pub(super) struct NodeKeyPair {
-    secret_key: Arc<SecretKey>,
-    public_key: PublicKey,
+    signer: Arc<dyn Signer>,
 }
 
 impl NodeKeyPair {
     /// Creates a new key pair for consensus signing.
-    pub(super) fn new(key_pair: (Arc<SecretKey>, PublicKey)) -> Self {
-        Self {
-            secret_key: key_pair.0,
-            public_key: key_pair.1,
-        }
+    pub(super) fn new(signer: Arc<dyn Signer>) -> Self {
+        Self { signer }
     }
 
-    /// Sign a value using this keypair.
-    fn sign<T: AsRef<[u8]>>(&self, value: T) -> Signature {
-        crypto::sign(value, &self.secret_key, &self.public_key)
+    /// Signs the certificate for the connection with the given ID on a network running the given
+    /// protocol version using this keypair, on a blocking thread if the signer may block.
+    async fn sign(
+        &self,
+        connection_id: ConnectionId,
+        protocol_version: ProtocolVersion,
+    ) -> Result<Signature, SignerError> {
+        let message = if protocol_version < PREFIXED_HANDSHAKE_CERTIFICATE_VERSION {
+            Signable::LegacyHandshake(*connection_id.as_bytes())
+        } else {
+            Signable::Handshake(*connection_id.as_bytes())
+        };
+        signer::sign_async(Arc::clone(&self.signer), message).await
     }
 }
 
@@ -169,30 +184,58 @@ pub(crate) struct ConsensusCertificate {
 }
 
 impl ConsensusCertificate {
-    /// Creates a new consensus certificate from a connection ID and key pair.
-    pub(super) fn create(connection_id: ConnectionId, key_pair: &NodeKeyPair) -> Self {
-        let signature = key_pair.sign(connection_id.as_bytes());
-        ConsensusCertificate {
-            public_key: key_pair.public_key.clone(),
-            signature,
+    /// Creates a new consensus certificate from a connection ID and key pair, for a network running
+    /// the given protocol version.
+    ///
+    /// Returns `None` if signing failed.
+    pub(super) async fn create(
+        connection_id: ConnectionId,
+        key_pair: &NodeKeyPair,
+        protocol_version: ProtocolVersion,
+    ) -> Option<Self> {
+        match key_pair.sign(connection_id, protocol_version).await {
+            Ok(signature) => Some(ConsensusCertificate {
+                public_key: key_pair.signer.public_key().clone(),
+                signature,
+            }),
+            Err(error) => {
+                warn!(%error, "failed to sign consensus certificate");
+                None
+            }
         }
     }
 
-    /// Validates a certificate, returning a `PublicKey` if valid.
-    pub(super) fn validate(self, connection_id: ConnectionId) -> Result<PublicKey, crypto::Error> {
-        crypto::verify(connection_id.as_bytes(), &self.signature, &self.public_key)?;
+    /// Validates a certificate received on a network running the given protocol version, returning
+    /// a `PublicKey` if valid.
+    ///
+    /// The protocol version must be the one activated on our side, never the one reported by the
+    /// peer, which could otherwise choose which certificates are accepted.
+    pub(super) fn validate(
+        self,
+        connection_id: ConnectionId,
+        protocol_version: ProtocolVersion,
+    ) -> Result<PublicKey, crypto::Error> {
+        let message = Signable::Handshake(*connection_id.as_bytes());
+        match crypto::verify(message.bytes_to_sign(), &self.signature, &self.public_key) {
+            Ok(()) => (),
+            Err(_) if protocol_version < PREFIXED_HANDSHAKE_CERTIFICATE_VERSION => {
+                crypto::verify(connection_id.as_bytes(), &self.signature, &self.public_key)?
+            }
+            Err(error) => return Err(error),
+        }
         Ok(self.public_key)
     }
 
     /// Creates a random `ConnectionId`.
     #[cfg(test)]
     fn random(rng: &mut TestRng) -> Self {
-        let secret_key = SecretKey::random(rng);
-        let public_key = PublicKey::from(&secret_key);
-        ConsensusCertificate::create(
-            ConnectionId::random(rng),
-            &NodeKeyPair::new((Arc::new(secret_key), public_key)),
-        )
+        let signer = LocalSigner::new(Arc::new(SecretKey::random(rng)));
+        let message = Signable::Handshake(*ConnectionId::random(rng).as_bytes());
+        let signature = signer.sign(&message).expect("local signer should not fail");
+        ConsensusCertificate {
+            public_key: signer.public_key().clone(),
+            signature,
+        }
     }
 }
 
@@ -293,16 +336,18 @@ impl<P: Display> Display for Message<P> {
                 consensus_certificate,
                 is_syncing,
                 chainspec_hash,
+                accepts_compression,
             } => {
                 write!(
                     f,
-                    "handshake: {}, public addr: {}, protocol_version: {}, consensus_certificate: {}, is_syncing: {}, chainspec_hash: {}",
+                    "handshake: {}, public addr: {}, protocol_version: {}, consensus_certificate: {}, is_syncing: {}, chainspec_hash: {}, accepts_compression: {}",
                     network_name,
                     public_addr,
                     protocol_version,
                     OptDisplay::new(consensus_certificate.as_ref(), "none"),
                     is_syncing,
-                    OptDisplay::new(chainspec_hash.as_ref(), "none")
+                    OptDisplay::new(chainspec_hash.as_ref(), "none"),
+                    accepts_compression
                 )
             }
             Message::Ping { nonce } => write!(f, "ping({})", nonce),
@@ -313,7 +358,7 @@ impl<P: Display> Display for Message<P> {
 }
 
 /// A classification system for networking messages.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, EnumIter)]
 pub(crate) enum MessageKind {
     /// Non-payload messages, like handshakes.
     Protocol,
@@ -464,6 +509,7 @@ mod specimen_support {
                         consensus_certificate: LargestSpecimen::largest_specimen(estimator, cache),
                         is_syncing: LargestSpecimen::largest_specimen(estimator, cache),
                         chainspec_hash: LargestSpecimen::largest_specimen(estimator, cache),
+                        accepts_compression: LargestSpecimen::largest_specimen(estimator, cache),
                     },
                     MessageDiscriminants::Ping => Message::Ping {
                         nonce: LargestSpecimen::largest_specimen(estimator, cache),
@@ -768,6 +814,7 @@ mod tests {
             consensus_certificate: Some(ConsensusCertificate::random(&mut rng)),
             is_syncing: false,
             chainspec_hash: Some(Digest::hash("example-chainspec")),
+            accepts_compression: true,
         };
 
         let legacy_handshake: V1_0_0_Message = roundtrip_message(&modern_handshake);
@@ -802,6 +849,7 @@ mod tests {
             consensus_certificate,
             is_syncing,
             chainspec_hash,
+            accepts_compression,
         } = modern_handshake
         {
             assert_eq!(network_name, "example-handshake");
@@ -809,7 +857,8 @@ mod tests {
             assert_eq!(protocol_version, ProtocolVersion::V1_0_0);
             assert!(consensus_certificate.is_none());
             assert!(!is_syncing);
-            assert!(chainspec_hash.is_none())
+            assert!(chainspec_hash.is_none());
+            assert!(!accepts_compression);
         } else {
             panic!("did not expect modern handshake to deserialize to anything but")
         }
@@ -826,6 +875,7 @@ mod tests {
             consensus_certificate,
             is_syncing,
             chainspec_hash,
+            accepts_compression,
         } = modern_handshake
         {
             assert!(!is_syncing);
@@ -834,7 +884,8 @@ mod tests {
             assert_eq!(protocol_version, ProtocolVersion::V1_0_0);
             assert!(consensus_certificate.is_none());
             assert!(!is_syncing);
-            assert!(chainspec_hash.is_none())
+            assert!(chainspec_hash.is_none());
+            assert!(!accepts_compression);
         } else {
             panic!("did not expect modern handshake to deserialize to anything but")
         }
@@ -851,6 +902,7 @@ mod tests {
             consensus_certificate,
             is_syncing,
             chainspec_hash,
+            accepts_compression,
         } = modern_handshake
         {
             assert_eq!(network_name, "example-handshake");
@@ -878,7 +930,8 @@ mod tests {
                 .unwrap()
             );
             assert!(!is_syncing);
-            assert!(chainspec_hash.is_none())
+            assert!(chainspec_hash.is_none());
+            assert!(!accepts_compression);
         } else {
             panic!("did not expect modern handshake to deserialize to anything but")
         }
@@ -895,6 +948,7 @@ mod tests {
             consensus_certificate,
             is_syncing,
             chainspec_hash,
+            accepts_compression,
         } = modern_handshake
         {
             assert!(!is_syncing);
@@ -922,12 +976,54 @@ mod tests {
                 .unwrap()
             );
             assert!(!is_syncing);
-            assert!(chainspec_hash.is_none())
+            assert!(chainspec_hash.is_none());
+            assert!(!accepts_compression);
         } else {
             panic!("did not expect modern handshake to deserialize to anything but")
         }
     }
 
+    #[test]
+    fn should_accept_legacy_certificate_until_activation() {
+        let mut rng = crate::new_rng();
+        let secret_key = SecretKey::random(&mut rng);
+        let public_key = PublicKey::from(&secret_key);
+        let connection_id = ConnectionId::random(&mut rng);
+        let legacy_certificate = ConsensusCertificate {
+            public_key: public_key.clone(),
+            signature: crypto::sign(connection_id.as_bytes(), &secret_key, &public_key),
+        };
+        let message = Signable::Handshake(*connection_id.as_bytes());
+        let certificate = ConsensusCertificate {
+            public_key: public_key.clone(),
+            signature: crypto::sign(message.bytes_to_sign(), &secret_key, &public_key),
+        };
+
+        let legacy_version = ProtocolVersion::from_parts(1, 5, 5);
+        assert_eq!(
+            legacy_certificate
+                .clone()
+                .validate(connection_id, legacy_version)
+                .unwrap(),
+            public_key
+        );
+        assert!(legacy_certificate
+            .validate(connection_id, PREFIXED_HANDSHAKE_CERTIFICATE_VERSION)
+            .is_err());
+        for version in [legacy_version, PREFIXED_HANDSHAKE_CERTIFICATE_VERSION] {
+            assert_eq!(
+                certificate
+                    .clone()
+                    .validate(connection_id, version)
+                    .unwrap(),
+                public_key
+            );
+        }
+        assert!(certificate
+            .validate(ConnectionId::random(&mut rng), legacy_version)
+            .is_err());
+    }
+
     fn roundtrip_certificate(use_human_readable: bool) {
         let mut rng = crate::new_rng();
         let certificate = ConsensusCertificate::random(&mut rng);
@@ -1,7 +1,8 @@
 use std::sync::Weak;
 
 use prometheus::{Counter, IntCounter, IntGauge, Registry};
-use tracing::debug;
+use strum::IntoEnumIterator;
+use tracing::{debug, error};
 
 use super::{outgoing::OutgoingMetrics, MessageKind};
 use crate::unregister_metric;
@@ -115,6 +116,14 @@ pub(super) struct Metrics {
     /// Total time spent delaying incoming traffic from non-validators due to limiter, in seconds.
     pub(super) accumulated_incoming_limiter_delay: Counter,
 
+    /// Volume in bytes saved by compressing outgoing messages.
+    pub(super) out_bytes_saved_by_compression: IntCounter,
+    /// Volume in bytes saved by the compression of incoming messages.
+    pub(super) in_bytes_saved_by_compression: IntCounter,
+
+    /// Metrics of the outgoing channels, indexed by message kind.
+    pub(super) channels: Vec<ChannelMetrics>,
+
     /// Registry instance.
     registry: Registry,
 }
@@ -337,6 +346,15 @@ impl Metrics {
             "seconds spent delaying incoming traffic from non-validators due to limiter, in seconds."
         )?;
 
+        let out_bytes_saved_by_compression = IntCounter::new(
+            "net_out_bytes_saved_by_compression",
+            "volume in bytes saved by compressing outgoing messages",
+        )?;
+        let in_bytes_saved_by_compression = IntCounter::new(
+            "net_in_bytes_saved_by_compression",
+            "volume in bytes saved by the compression of incoming messages",
+        )?;
+
         registry.register(Box::new(broadcast_requests.clone()))?;
         registry.register(Box::new(direct_message_requests.clone()))?;
         registry.register(Box::new(queued_messages.clone()))?;
@@ -398,6 +416,13 @@ impl Metrics {
         registry.register(Box::new(accumulated_outgoing_limiter_delay.clone()))?;
         registry.register(Box::new(accumulated_incoming_limiter_delay.clone()))?;
 
+        registry.register(Box::new(out_bytes_saved_by_compression.clone()))?;
+        registry.register(Box::new(in_bytes_saved_by_compression.clone()))?;
+
+        let channels = MessageKind::iter()
+            .map(|kind| ChannelMetrics::new(kind, registry))
+            .collect::<Result<_, _>>()?;
+
         Ok(Metrics {
             broadcast_requests,
             direct_message_requests,
@@ -452,6 +477,9 @@ impl Metrics {
             requests_for_trie_finished,
             accumulated_outgoing_limiter_delay,
             accumulated_incoming_limiter_delay,
+            out_bytes_saved_by_compression,
+            in_bytes_saved_by_compression,
+            channels,
             registry: registry.clone(),
         })
     }
@@ -567,6 +595,38 @@ impl Metrics {
         }
     }
 
+    /// Records the compression of an outgoing message of `serialized_size` into a frame of
+    /// `frame_size` bytes.
+    pub(super) fn record_compression_out(
+        this: &Weak<Self>,
+        serialized_size: usize,
+        frame_size: usize,
+    ) {
+        if let Some(metrics) = this.upgrade() {
+            metrics
+                .out_bytes_saved_by_compression
+                .inc_by(serialized_size.saturating_sub(frame_size) as u64);
+        } else {
+            debug!("not recording metrics, component already shut down");
+        }
+    }
+
+    /// Records the decompression of an incoming frame of `frame_size` into a message of
+    /// `serialized_size` bytes.
+    pub(super) fn record_compression_in(
+        this: &Weak<Self>,
+        serialized_size: usize,
+        frame_size: usize,
+    ) {
+        if let Some(metrics) = this.upgrade() {
+            metrics
+                .in_bytes_saved_by_compression
+                .inc_by(serialized_size.saturating_sub(frame_size) as u64);
+        } else {
+            debug!("not recording metrics, component already shut down");
+        }
+    }
+
     /// Records that a trie request has been started.
     pub(super) fn record_trie_request_start(this: &Weak<Self>) {
         if let Some(metrics) = this.upgrade() {
@@ -648,5 +708,61 @@ impl Drop for Metrics {
 
         unregister_metric!(self.registry, self.accumulated_outgoing_limiter_delay);
         unregister_metric!(self.registry, self.accumulated_incoming_limiter_delay);
+
+        unregister_metric!(self.registry, self.out_bytes_saved_by_compression);
+        unregister_metric!(self.registry, self.in_bytes_saved_by_compression);
+
+        for (kind, channel) in MessageKind::iter().zip(&self.channels) {
+            channel.unregister(kind, &self.registry);
+        }
+    }
+}
+
+/// Metrics of the outgoing channel for one kind of message, shared by all connections.
+#[derive(Clone, Debug)]
+pub(super) struct ChannelMetrics {
+    /// Number of messages waiting to be sent out on the channel.
+    pub(super) queued: IntGauge,
+    /// Number of messages dropped because the channel's queue was full.
+    pub(super) dropped: IntCounter,
+    /// Total time spent by messages waiting in the channel's queue, in seconds.
+    pub(super) queue_time: Counter,
+}
+
+impl ChannelMetrics {
+    /// Creates and registers the metrics of the channel for `kind`.
+    fn new(kind: MessageKind, registry: &Registry) -> Result<Self, prometheus::Error> {
+        let queued = IntGauge::new(
+            format!("net_channel_{}_queued", kind),
+            format!("number of {} messages waiting to be sent out", kind),
+        )?;
+        let dropped = IntCounter::new(
+            format!("net_channel_{}_dropped", kind),
+            format!("number of {} messages dropped due to a full queue", kind),
+        )?;
+        let queue_time = Counter::new(
+            format!("net_channel_{}_queue_time_seconds", kind),
+            format!(
+                "total time spent by {} messages waiting to be sent out",
+                kind
+            ),
+        )?;
+        registry.register(Box::new(queued.clone()))?;
+        registry.register(Box::new(dropped.clone()))?;
+        registry.register(Box::new(queue_time.clone()))?;
+        Ok(ChannelMetrics {
+            queued,
+            dropped,
+            queue_time,
+        })
+    }
+
+    /// Unregisters the metrics of the channel for `kind`.
+    fn unregister(&self, kind: MessageKind, registry: &Registry) {
+        registry
+            .unregister(Box::new(self.queued.clone()))
+            .and_then(|_| registry.unregister(Box::new(self.dropped.clone())))
+            .and_then(|_| registry.unregister(Box::new(self.queue_time.clone())))
+            .unwrap_or_else(|_| error!("unregistering {} channel metrics failed", kind));
     }
 }
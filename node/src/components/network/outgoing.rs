@@ -88,6 +88,13 @@
 //! If a conflict (multiple successful dial results) occurs, the more recent connection takes
 //! precedence over the previous one. This prevents problems when a notification of a terminated
 //! connection is overtaken by the new connection announcement.
+//!
+//! # Connection preference
+//!
+//! A dropped connection to a preferred peer is reconnected immediately. For any other peer (e.g.
+//! one with a poor reputation), the address is put into the waiting state instead, as if
+//! `DEFERRED_RECONNECTION_FAILURES` attempts had failed, leaving the resources to preferred peers
+//! in the meantime.
 
 // Clippy has a lot of false positives due to `span.clone()`-closures.
 #![allow(clippy::redundant_clone)]
@@ -114,6 +121,9 @@ use super::{
     NodeId,
 };
 
+/// The number of failed attempts a deferred reconnection is treated like, determining its delay.
+const DEFERRED_RECONNECTION_FAILURES: u8 = 4;
+
 /// An outgoing connection/address in various states.
 #[derive(DataSize, Debug)]
 pub struct Outgoing<H, E>
@@ -974,11 +984,13 @@ where
 
     /// Notifies the connection manager about a dropped connection.
     ///
-    /// This will usually result in an immediate reconnection.
+    /// This will usually result in an immediate reconnection, unless the peer is not `preferred`,
+    /// in which case reconnecting is deferred.
     pub(crate) fn handle_connection_drop(
         &mut self,
         addr: SocketAddr,
         now: Instant,
+        preferred: bool,
     ) -> Option<DialRequest<H>> {
         let span = make_span(addr, self.outgoing.get(&addr));
 
@@ -993,6 +1005,20 @@ where
                         warn!("unexpected drop notification");
                         None
                     }
+                    OutgoingState::Connected { .. } if !preferred => {
+                        // Drop the handle, reconnect on a later housekeeping call.
+                        info!("deferring reconnection to non-preferred peer");
+                        self.change_outgoing_state(
+                            addr,
+                            OutgoingState::Waiting {
+                                failures_so_far: DEFERRED_RECONNECTION_FAILURES
+                                    .min(self.config.retry_attempts),
+                                error: None,
+                                last_failure: now,
+                            },
+                        );
+                        None
+                    }
                     OutgoingState::Connected { .. } => {
                         // Drop the handle, immediately initiate a reconnection.
                         self.change_outgoing_state(
@@ -1168,7 +1194,7 @@ mod tests {
         clock.advance_time(20_000);
         assert!(dials(
             addr_a,
-            &manager.handle_connection_drop(addr_a, clock.now())
+            &manager.handle_connection_drop(addr_a, clock.now(), true)
         ));
         assert_eq!(manager.metrics().out_state_connecting.get(), 1);
         assert_eq!(manager.metrics().out_state_waiting.get(), 0);
@@ -1183,6 +1209,54 @@ mod tests {
             .is_empty());
     }
 
+    #[test]
+    fn reconnection_to_non_preferred_peers_is_deferred() {
+        init_logging();
+
+        let mut rng = crate::new_rng();
+        let mut clock = TestClock::new();
+
+        let addr_a: SocketAddr = "1.2.3.4:1234".parse().unwrap();
+        let id_a = NodeId::random(&mut rng);
+
+        let mut manager = OutgoingManager::<u32, TestDialerError>::new(test_config());
+
+        assert!(dials(
+            addr_a,
+            &manager.learn_addr(addr_a, false, clock.now())
+        ));
+        assert!(manager
+            .handle_dial_outcome(DialOutcome::Successful {
+                addr: addr_a,
+                handle: 99,
+                node_id: id_a,
+                when: clock.now(),
+            })
+            .is_none());
+
+        // The peer is no longer preferred when its connection drops, so we do not reconnect
+        // immediately.
+        assert!(manager
+            .handle_connection_drop(addr_a, clock.now(), false)
+            .is_none());
+        assert_eq!(manager.metrics().out_state_connected.get(), 0);
+        assert_eq!(manager.metrics().out_state_waiting.get(), 1);
+        assert!(manager.get_route(id_a).is_none());
+
+        // The reconnection is delayed as if all retry attempts had failed (2^3 * base_timeout),
+        // but the address is not forgotten.
+        clock.advance_time(7_000);
+        assert!(manager
+            .perform_housekeeping(&mut rng, clock.now())
+            .is_empty());
+        clock.advance_time(1_000);
+        assert!(dials(
+            addr_a,
+            &manager.perform_housekeeping(&mut rng, clock.now())
+        ));
+        assert_eq!(manager.metrics().out_state_connecting.get(), 1);
+    }
+
     #[test]
     fn connections_forgotten_after_too_many_tries() {
         init_logging();
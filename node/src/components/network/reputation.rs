@@ -0,0 +1,291 @@
+//! Peer reputation scoring.
+//!
+//! Every peer is assigned a score, starting out at zero. Misbehavior reported by other components
+//! (see [`Misbehavior`]) lowers the score of the offending peer, while the passing of time lets it
+//! decay back towards zero with a configurable half-life. Offenses (see
+//! [`BlocklistJustification`](super::blocklist::BlocklistJustification)) are provably bad behavior,
+//! and drop the score to the block threshold immediately.
+//!
+//! Peers whose score is at or below the "poor" threshold are avoided when choosing peers to gossip
+//! to or fetch from, and are not reconnected to immediately when their connection drops. Peers
+//! whose score drops to the block threshold are disconnected and blocked.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::{self, Display, Formatter},
+    time::Instant,
+};
+
+use casper_types::TimeDiff;
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+
+use super::error::{Error, Result};
+use crate::{components::fetcher::Tag, types::NodeId};
+
+/// Scores closer to zero than this are considered fully recovered and forgotten.
+const NEGLIGIBLE_SCORE: f64 = 0.5;
+
+/// Configuration of peer reputation scoring.
+#[derive(Clone, DataSize, Debug, Deserialize, Serialize, PartialEq, Eq)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields, default)]
+pub struct ReputationConfig {
+    /// Time after which half of a peer's accumulated penalty has been forgiven.
+    pub half_life: TimeDiff,
+    /// Score at or below which a peer is only chosen if no better peers are available.
+    pub poor_threshold: i32,
+    /// Score at or below which a peer is disconnected and blocked.
+    pub block_threshold: i32,
+}
+
+impl ReputationConfig {
+    /// Checks that peers are blocked only below zero, and considered poor before being blocked.
+    pub(super) fn validate(&self) -> Result<()> {
+        if self.block_threshold >= 0 {
+            return Err(Error::NonNegativeBlockThreshold(self.block_threshold));
+        }
+        if self.poor_threshold <= self.block_threshold {
+            return Err(Error::PoorThresholdNotAboveBlockThreshold {
+                poor_threshold: self.poor_threshold,
+                block_threshold: self.block_threshold,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Default for ReputationConfig {
+    fn default() -> Self {
+        ReputationConfig {
+            half_life: TimeDiff::from_seconds(600),
+            poor_threshold: -20,
+            block_threshold: -100,
+        }
+    }
+}
+
+/// Misbehavior that does not warrant blocking a peer on its own, but lowers its reputation.
+#[derive(Clone, Copy, DataSize, Debug, Serialize)]
+pub(crate) enum Misbehavior {
+    /// Peer did not answer a fetch request in time.
+    FetchTimedOut { tag: Tag },
+    /// Peer was slow or failed to provide data requested during block synchronization.
+    SlowResponse,
+    /// Peer repeatedly gossiped the same item to us.
+    GossipSpam,
+}
+
+impl Misbehavior {
+    /// Returns the penalty incurred by the misbehavior.
+    pub(super) fn penalty(&self) -> Penalty {
+        match self {
+            Misbehavior::FetchTimedOut { .. } | Misbehavior::SlowResponse => Penalty::Points(5),
+            Misbehavior::GossipSpam => Penalty::Points(10),
+        }
+    }
+}
+
+impl Display for Misbehavior {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Misbehavior::FetchTimedOut { tag } => {
+                write!(f, "did not answer a request for a {} in time", tag)
+            }
+            Misbehavior::SlowResponse => f.write_str("was slow to provide requested data"),
+            Misbehavior::GossipSpam => f.write_str("repeatedly gossiped the same item"),
+        }
+    }
+}
+
+/// A reduction of a peer's score.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum Penalty {
+    /// The score is reduced by the given number of points.
+    Points(u32),
+    /// The score is reduced to the block threshold, regardless of its current value.
+    Block,
+}
+
+/// The score of a single peer.
+#[derive(Clone, Copy, DataSize, Debug)]
+struct Score {
+    /// The score at the time of the last update, never positive.
+    value: f64,
+    /// The time of the last update.
+    #[data_size(skip)]
+    updated: Instant,
+}
+
+/// Decaying reputation scores of peers.
+#[derive(DataSize, Debug)]
+pub(super) struct PeerScores {
+    /// Scoring configuration.
+    config: ReputationConfig,
+    /// Scores of peers with a non-negligible score. Peers not present have a score of zero.
+    scores: HashMap<NodeId, Score>,
+}
+
+impl PeerScores {
+    /// Creates a new set of scores, with every peer starting out at zero.
+    pub(super) fn new(config: ReputationConfig) -> Self {
+        PeerScores {
+            config,
+            scores: HashMap::new(),
+        }
+    }
+
+    /// Returns the value of `score` decayed to `now`.
+    fn decayed(&self, score: &Score, now: Instant) -> f64 {
+        let half_life = self.config.half_life.millis();
+        if half_life == 0 {
+            return 0.0;
+        }
+        let elapsed = now.saturating_duration_since(score.updated).as_millis() as f64;
+        score.value * 0.5f64.powf(elapsed / half_life as f64)
+    }
+
+    /// Lowers the score of `peer` by `penalty`.
+    ///
+    /// Returns `true` if the score has dropped to or below the block threshold.
+    pub(super) fn penalize(&mut self, peer: NodeId, penalty: Penalty, now: Instant) -> bool {
+        let block_threshold = f64::from(self.config.block_threshold);
+        let current = self
+            .scores
+            .get(&peer)
+            .map_or(0.0, |score| self.decayed(score, now));
+        let value = match penalty {
+            Penalty::Points(points) => current - f64::from(points),
+            Penalty::Block => current.min(block_threshold),
+        };
+        self.scores.insert(
+            peer,
+            Score {
+                value,
+                updated: now,
+            },
+        );
+        value <= block_threshold
+    }
+
+    /// Returns the current score of `peer`.
+    pub(super) fn score(&self, peer: &NodeId, now: Instant) -> i32 {
+        self.scores
+            .get(peer)
+            .map_or(0, |score| self.decayed(score, now).round() as i32)
+    }
+
+    /// Returns whether `peer` has a poor reputation and should be avoided if possible.
+    pub(super) fn is_poor(&self, peer: &NodeId, now: Instant) -> bool {
+        self.score(peer, now) <= self.config.poor_threshold
+    }
+
+    /// Returns the current, non-zero scores of all peers.
+    pub(super) fn snapshot(&self, now: Instant) -> BTreeMap<NodeId, i32> {
+        self.scores
+            .keys()
+            .map(|peer| (*peer, self.score(peer, now)))
+            .filter(|(_, score)| *score != 0)
+            .collect()
+    }
+
+    /// Forgets the scores of peers which have decayed back to (almost) zero.
+    pub(super) fn prune(&mut self, now: Instant) {
+        let negligible: Vec<_> = self
+            .scores
+            .iter()
+            .filter(|(_, score)| self.decayed(score, now) > -NEGLIGIBLE_SCORE)
+            .map(|(peer, _)| *peer)
+            .collect();
+        for peer in negligible {
+            self.scores.remove(&peer);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use casper_types::{testing::TestRng, TimeDiff};
+
+    use super::{PeerScores, Penalty, ReputationConfig};
+    use crate::{components::network::Error, types::NodeId};
+
+    fn scores() -> PeerScores {
+        PeerScores::new(ReputationConfig {
+            half_life: TimeDiff::from_seconds(60),
+            poor_threshold: -20,
+            block_threshold: -100,
+        })
+    }
+
+    #[test]
+    fn should_validate_thresholds() {
+        assert!(ReputationConfig::default().validate().is_ok());
+
+        let config = |poor_threshold, block_threshold| ReputationConfig {
+            poor_threshold,
+            block_threshold,
+            ..ReputationConfig::default()
+        };
+        assert!(matches!(
+            config(-20, 0).validate(),
+            Err(Error::NonNegativeBlockThreshold(0))
+        ));
+        assert!(matches!(
+            config(-100, -100).validate(),
+            Err(Error::PoorThresholdNotAboveBlockThreshold { .. })
+        ));
+        assert!(matches!(
+            config(-120, -100).validate(),
+            Err(Error::PoorThresholdNotAboveBlockThreshold { .. })
+        ));
+        assert!(config(0, -1).validate().is_ok());
+    }
+
+    #[test]
+    fn should_accumulate_penalties_up_to_block_threshold() {
+        let mut rng = TestRng::new();
+        let peer = NodeId::random(&mut rng);
+        let other = NodeId::random(&mut rng);
+        let mut scores = scores();
+        let now = Instant::now();
+
+        assert!(!scores.penalize(peer, Penalty::Points(10), now));
+        assert!(!scores.is_poor(&peer, now));
+        assert!(!scores.penalize(peer, Penalty::Points(10), now));
+        assert!(scores.is_poor(&peer, now));
+        assert!(!scores.penalize(peer, Penalty::Points(79), now));
+        assert!(scores.penalize(peer, Penalty::Points(1), now));
+        assert_eq!(scores.score(&peer, now), -100);
+
+        assert!(scores.penalize(other, Penalty::Block, now));
+        assert_eq!(scores.score(&other, now), -100);
+        assert_eq!(scores.snapshot(now).len(), 2);
+    }
+
+    #[test]
+    fn should_decay_scores_towards_zero() {
+        let mut rng = TestRng::new();
+        let peer = NodeId::random(&mut rng);
+        let mut scores = scores();
+        let now = Instant::now();
+
+        scores.penalize(peer, Penalty::Points(40), now);
+        assert_eq!(scores.score(&peer, now + Duration::from_secs(60)), -20);
+        assert_eq!(scores.score(&peer, now + Duration::from_secs(120)), -10);
+
+        // A penalty applied later adds to the decayed score.
+        let later = now + Duration::from_secs(60);
+        assert!(!scores.penalize(peer, Penalty::Points(79), later));
+        assert_eq!(scores.score(&peer, later), -99);
+        assert!(scores.is_poor(&peer, later));
+
+        // Eventually, the peer is forgiven entirely.
+        let much_later = later + Duration::from_secs(3600);
+        assert!(!scores.is_poor(&peer, much_later));
+        scores.prune(much_later);
+        assert!(scores.snapshot(much_later).is_empty());
+    }
+}
@@ -13,7 +13,6 @@ use std::{
     time::Duration,
 };
 
-use bincode::Options;
 use futures::{
     future::{self, Either},
     stream::{SplitSink, SplitStream},
@@ -24,17 +23,16 @@ use openssl::{
     ssl::Ssl,
     x509::X509,
 };
-use prometheus::IntGauge;
 use rand::Rng;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use tokio::{
     net::TcpStream,
-    sync::{mpsc::UnboundedReceiver, watch, Semaphore},
+    sync::{watch, Semaphore},
 };
 use tokio_openssl::SslStream;
 use tokio_serde::{Deserializer, Serializer};
 use tracing::{
-    debug, error, error_span,
+    debug, error_span,
     field::{self, Empty},
     info, trace, warn, Instrument, Span,
 };
@@ -43,6 +41,8 @@ use casper_types::{ProtocolVersion, PublicKey, TimeDiff};
 
 use super::{
     chain_info::ChainInfo,
+    channels::QueueReceiver,
+    compression::{CompressionConfig, FrameCompression},
     counting_format::{ConnectionId, Role},
     error::{ConnectionError, IoError},
     event::{IncomingConnection, OutgoingConnection},
@@ -54,23 +54,14 @@ use super::{
     Transport,
 };
 use crate::{
-    components::network::{framed_transport, BincodeFormat, Config, FromIncoming},
-    effect::{
-        announcements::PeerBehaviorAnnouncement, requests::NetworkRequest, AutoClosingResponder,
-        EffectBuilder,
-    },
+    components::network::{framed_transport, Config, FromIncoming},
+    effect::{announcements::PeerBehaviorAnnouncement, requests::NetworkRequest, EffectBuilder},
     reactor::{EventQueueHandle, QueueKind},
     tls::{self, TlsCert, ValidationError},
     types::NodeId,
     utils::display_error,
 };
 
-/// An item on the internal outgoing message queue.
-///
-/// Contains a reference counted message and an optional responder to call once the message has been
-/// successfully handed over to the kernel for sending.
-pub(super) type MessageQueueItem<P> = (Arc<Message<P>>, Option<AutoClosingResponder<()>>);
-
 /// The outcome of the handshake process.
 struct HandshakeOutcome {
     /// A framed transport for peer.
@@ -81,6 +72,8 @@ struct HandshakeOutcome {
     peer_consensus_public_key: Option<PublicKey>,
     /// Holds the information whether the remote node is syncing.
     is_peer_syncing: bool,
+    /// The compression settings to use, if both we and the peer accept compression.
+    compression: Option<FrameCompression>,
 }
 
 /// Low-level TLS connection function.
@@ -163,6 +156,7 @@ where
             public_addr,
             peer_consensus_public_key,
             is_peer_syncing: is_syncing,
+            compression,
         }) => {
             if let Some(ref public_key) = peer_consensus_public_key {
                 Span::current().record("consensus_key", &field::display(public_key));
@@ -178,6 +172,7 @@ where
                 context.net_metrics.clone(),
                 connection_id,
                 framed_transport,
+                compression,
                 Role::Dialer,
             );
             let (sink, _stream) = full_transport.split();
@@ -236,6 +231,8 @@ where
     max_in_flight_demands: usize,
     /// Flag indicating whether this node is syncing.
     is_syncing: AtomicBool,
+    /// Configuration of the compression of frames.
+    compression: CompressionConfig,
 }
 
 impl<REv> NetworkContext<REv> {
@@ -277,6 +274,7 @@ impl<REv> NetworkContext<REv> {
             tarpit_chance: cfg.tarpit_chance,
             max_in_flight_demands,
             is_syncing: AtomicBool::new(false),
+            compression: cfg.compression,
         }
     }
 
@@ -362,6 +360,7 @@ where
             public_addr,
             peer_consensus_public_key,
             is_peer_syncing: _,
+            compression,
         }) => {
             if let Some(ref public_key) = peer_consensus_public_key {
                 Span::current().record("consensus_key", &field::display(public_key));
@@ -372,6 +371,7 @@ where
                 context.net_metrics.clone(),
                 connection_id,
                 framed_transport,
+                compression,
                 Role::Listener,
             );
 
@@ -469,12 +469,16 @@ where
     let mut encoder = MessagePackFormat;
 
     // Manually encode a handshake.
-    let handshake_message = context.chain_info.create_handshake::<P>(
-        context.public_addr.expect("component not initialized"),
-        context.node_key_pair.as_ref(),
-        connection_id,
-        context.is_syncing.load(Ordering::SeqCst),
-    );
+    let handshake_message = context
+        .chain_info
+        .create_handshake::<P>(
+            context.public_addr.expect("component not initialized"),
+            context.node_key_pair.as_ref(),
+            connection_id,
+            context.is_syncing.load(Ordering::SeqCst),
+            context.compression.enabled,
+        )
+        .await;
 
     let serialized_handshake_message = Pin::new(&mut encoder)
         .serialize(&Arc::new(handshake_message))
@@ -513,6 +517,7 @@ where
         consensus_certificate,
         is_syncing,
         chainspec_hash,
+        accepts_compression,
     } = remote_message
     {
         debug!(%protocol_version, "handshake received");
@@ -557,7 +562,7 @@ where
 
         let peer_consensus_public_key = consensus_certificate
             .map(|cert| {
-                cert.validate(connection_id)
+                cert.validate(connection_id, context.chain_info.protocol_version)
                     .map_err(ConnectionError::InvalidConsensusCertificate)
             })
             .transpose()?;
@@ -571,6 +576,11 @@ where
             public_addr,
             peer_consensus_public_key,
             is_peer_syncing: is_syncing,
+            compression: FrameCompression::negotiate(
+                &context.compression,
+                accepts_compression,
+                context.chain_info.maximum_net_message_size,
+            ),
         })
     } else {
         // Received a non-handshake, this is an error.
@@ -793,34 +803,22 @@ where
 
 /// Network message sender.
 ///
-/// Reads from a channel and sends all messages, until the stream is closed or an error occurs.
+/// Reads from the outgoing queue in the order determined by its channels and sends all messages,
+/// until the queue is closed or an error occurs.
 pub(super) async fn message_sender<P>(
-    mut queue: UnboundedReceiver<MessageQueueItem<P>>,
+    mut queue: QueueReceiver<P>,
     mut sink: SplitSink<FullTransport<P>, Arc<Message<P>>>,
     limiter: LimiterHandle,
-    counter: IntGauge,
 ) where
     P: Payload,
 {
-    while let Some((message, opt_responder)) = queue.recv().await {
-        counter.dec();
-
-        let estimated_wire_size = match BincodeFormat::default().0.serialized_size(&*message) {
-            Ok(size) => size as u32,
-            Err(error) => {
-                error!(
-                    error = display_error(&error),
-                    "failed to get serialized size of outgoing message, closing outgoing connection"
-                );
-                break;
-            }
-        };
-        limiter.request_allowance(estimated_wire_size).await;
+    while let Some(queued_message) = queue.recv().await {
+        limiter.request_allowance(queued_message.size).await;
 
-        let mut outcome = sink.send(message).await;
+        let mut outcome = sink.send(queued_message.message).await;
 
         // Notify via responder that the message has been buffered by the kernel.
-        if let Some(auto_closing_responder) = opt_responder {
+        if let Some(auto_closing_responder) = queued_message.responder {
             // Since someone is interested in the message, flush the socket to ensure it was sent.
             outcome = outcome.and(sink.flush().await);
             auto_closing_responder.respond(()).await;
@@ -833,12 +831,7 @@ pub(super) async fn message_sender<P>(
                 "message send failed, closing outgoing connection"
             );
 
-            // To ensure, metrics are up to date, we close the queue and drain it.
-            queue.close();
-            while queue.recv().await.is_some() {
-                counter.dec();
-            }
-
+            // Dropping the queue closes and drains it.
             break;
         };
     }
@@ -40,13 +40,14 @@ use crate::{
     effect::{
         requests::{
             AcceptDeployRequest, BlockSynchronizerRequest, ChainspecRawBytesRequest,
-            ConsensusRequest, ContractRuntimeRequest, MetricsRequest, NetworkInfoRequest,
-            ReactorStatusRequest, RpcRequest, StorageRequest, UpgradeWatcherRequest,
+            ConsensusRequest, ContractRuntimeRequest, DeployBufferRequest, DeployStagingRequest,
+            MetricsRequest, NetworkInfoRequest, ReactorStatusRequest, RpcRequest, StorageRequest,
+            UpgradeWatcherRequest,
         },
         EffectBuilder, EffectExt, Effects, Responder,
     },
     reactor::main_reactor::MainEvent,
-    types::{ChainspecInfo, StatusFeed},
+    types::{ChainspecInfo, PeersMap, StatusFeed, SyncLeapIdentifier},
     utils::{self, ListeningError},
     NodeRng,
 };
@@ -65,6 +66,8 @@ pub(crate) trait ReactorEventT:
     + From<UpgradeWatcherRequest>
     + From<ContractRuntimeRequest>
     + From<ConsensusRequest>
+    + From<DeployBufferRequest>
+    + From<DeployStagingRequest>
     + From<MetricsRequest>
     + From<NetworkInfoRequest>
     + From<StorageRequest>
@@ -82,6 +85,8 @@ impl<REv> ReactorEventT for REv where
         + From<UpgradeWatcherRequest>
         + From<ContractRuntimeRequest>
         + From<ConsensusRequest>
+        + From<DeployBufferRequest>
+        + From<DeployStagingRequest>
         + From<MetricsRequest>
         + From<NetworkInfoRequest>
         + From<StorageRequest>
@@ -284,6 +289,37 @@ where
                             main_responder: responder,
                         })
                 }
+                Event::RpcRequest(RpcRequest::GetKeys {
+                    get_keys_request,
+                    responder,
+                }) => async move {
+                    responder
+                        .respond(effect_builder.get_keys(get_keys_request).await)
+                        .await
+                }
+                .ignore(),
+                Event::RpcRequest(RpcRequest::GetDictionaryItems {
+                    get_dictionary_items_request,
+                    responder,
+                }) => async move {
+                    responder
+                        .respond(
+                            effect_builder
+                                .get_dictionary_items(get_dictionary_items_request)
+                                .await,
+                        )
+                        .await
+                }
+                .ignore(),
+                Event::RpcRequest(RpcRequest::GetStateDiff {
+                    state_diff_request,
+                    responder,
+                }) => async move {
+                    responder
+                        .respond(effect_builder.get_state_diff(state_diff_request).await)
+                        .await
+                }
+                .ignore(),
                 Event::RpcRequest(RpcRequest::GetBalance {
                     state_root_hash,
                     purse_uref,
@@ -311,12 +347,17 @@ where
                         }),
                         main_responder: responder,
                     }),
-                Event::RpcRequest(RpcRequest::GetPeers { responder }) => effect_builder
-                    .network_peers()
-                    .event(move |peers| Event::GetPeersResult {
-                        peers,
-                        main_responder: responder,
-                    }),
+                Event::RpcRequest(RpcRequest::GetPeers { responder }) => async move {
+                    join!(
+                        effect_builder.network_peers(),
+                        effect_builder.network_peer_reputations()
+                    )
+                }
+                .event(move |(peers, reputations)| Event::GetPeersResult {
+                    peers,
+                    reputations,
+                    main_responder: responder,
+                }),
                 Event::RpcRequest(RpcRequest::GetStatus { responder }) => {
                     let node_uptime = self.node_startup_instant.elapsed();
                     let network_name = self.network_name.clone();
@@ -372,6 +413,35 @@ where
                         .await
                 }
                 .ignore(),
+                Event::RpcRequest(RpcRequest::GetAccountActivity {
+                    subject,
+                    after,
+                    limit,
+                    responder,
+                }) => async move {
+                    responder
+                        .respond(
+                            effect_builder
+                                .get_account_activity_from_storage(subject, after, limit)
+                                .await,
+                        )
+                        .await
+                }
+                .ignore(),
+                Event::RpcRequest(RpcRequest::GetSyncLeap {
+                    trusted_block_hash,
+                    responder,
+                }) => async move {
+                    let sync_leap_identifier = SyncLeapIdentifier::sync_to_tip(trusted_block_hash);
+                    responder
+                        .respond(
+                            effect_builder
+                                .get_sync_leap_from_storage(sync_leap_identifier)
+                                .await,
+                        )
+                        .await
+                }
+                .ignore(),
                 Event::GetBlockTransfersResult {
                     block_hash: _,
                     result,
@@ -396,8 +466,11 @@ where
                 } => main_responder.respond(result).ignore(),
                 Event::GetPeersResult {
                     peers,
+                    reputations,
                     main_responder,
-                } => main_responder.respond(peers).ignore(),
+                } => main_responder
+                    .respond(PeersMap::with_reputations(peers, &reputations))
+                    .ignore(),
                 Event::GetBalanceResult {
                     result,
                     main_responder,
@@ -453,6 +526,8 @@ where
                 self.api_version,
                 cfg.qps_limit,
                 cfg.max_body_bytes,
+                cfg.max_batch_size,
+                cfg.max_deploys,
                 cfg.cors_origin.clone(),
             ));
             Some(())
@@ -468,6 +543,7 @@ where
             self.api_version,
             cfg.qps_limit,
             cfg.max_body_bytes,
+            cfg.max_batch_size,
             cfg.cors_origin.clone(),
         ));
 
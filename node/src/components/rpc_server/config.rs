@@ -10,6 +10,8 @@ const DEFAULT_QPS_LIMIT: u64 = 100;
 /// Default max body bytes.  This is 2.5MB which should be able to accommodate the largest valid
 /// JSON-RPC request, which would be an "account_put_deploy".
 const DEFAULT_MAX_BODY_BYTES: u32 = 2_621_440;
+/// Default max batch size.
+const DEFAULT_MAX_BATCH_SIZE: u32 = 100;
 /// Default CORS origin.
 const DEFAULT_CORS_ORIGIN: &str = "";
 
@@ -26,6 +28,9 @@ pub struct Config {
     pub qps_limit: u64,
     /// Maximum number of bytes to accept in a single request body.
     pub max_body_bytes: u32,
+    /// Maximum number of requests to accept in a single JSON-RPC batch request.
+    #[serde(default = "default_max_batch_size")]
+    pub max_batch_size: u32,
     /// CORS origin.
     pub cors_origin: String,
 }
@@ -38,11 +43,17 @@ impl Config {
             address: DEFAULT_ADDRESS.to_string(),
             qps_limit: DEFAULT_QPS_LIMIT,
             max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
             cors_origin: DEFAULT_CORS_ORIGIN.to_string(),
         }
     }
 }
 
+/// Returns the default max batch size, used if it is absent from an older config file.
+fn default_max_batch_size() -> u32 {
+    DEFAULT_MAX_BATCH_SIZE
+}
+
 impl Default for Config {
     fn default() -> Self {
         Config::new()
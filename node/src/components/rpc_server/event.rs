@@ -12,7 +12,7 @@ use casper_types::{system::auction::EraValidators, Transfer};
 
 use crate::{
     effect::{requests::RpcRequest, Responder},
-    types::{BlockHash, Deploy, DeployHash, DeployMetadataExt, NodeId},
+    types::{BlockHash, Deploy, DeployHash, DeployMetadataExt, NodeId, PeersMap},
 };
 
 #[derive(Debug, From)]
@@ -44,7 +44,8 @@ pub(crate) enum Event {
     },
     GetPeersResult {
         peers: BTreeMap<NodeId, String>,
-        main_responder: Responder<BTreeMap<NodeId, String>>,
+        reputations: BTreeMap<NodeId, i32>,
+        main_responder: Responder<PeersMap>,
     },
     GetBalanceResult {
         result: Result<BalanceResult, engine_state::Error>,
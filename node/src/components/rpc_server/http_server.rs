@@ -5,17 +5,21 @@ use casper_types::ProtocolVersion;
 
 use super::{
     rpcs::{
-        account::PutDeploy,
+        account::{AddDeployApprovals, GetStagedDeploys, PutDeploy, StageDeploy},
         chain::{
-            GetBlock, GetBlockTransfers, GetEraInfoBySwitchBlock, GetEraSummary, GetStateRootHash,
+            GetBlock, GetBlockTransfers, GetEraInfoBySwitchBlock, GetEraSummary, GetStateDiff,
+            GetStateRootHash, GetSyncLeap,
         },
         docs::ListRpcs,
-        info::{GetChainspec, GetDeploy, GetPeers, GetStatus, GetValidatorChanges},
+        info::{
+            GetAccountDeploys, GetChainspec, GetDeploy, GetPeers, GetPendingDeploys, GetStatus,
+            GetTransfersByAccount, GetValidatorChanges,
+        },
         state::{
-            GetAccountInfo, GetAuctionInfo, GetBalance, GetDictionaryItem, GetItem, GetTrie,
-            QueryBalance, QueryGlobalState,
+            GetAccountInfo, GetAuctionInfo, GetBalance, GetDictionaryItem, GetDictionaryItems,
+            GetItem, GetKeys, GetNamedKeys, GetTrie, QueryBalance, QueryGlobalState,
         },
-        RpcWithOptionalParams, RpcWithParams, RpcWithoutParams,
+        RequestLimiter, RpcWithOptionalParams, RpcWithParams, RpcWithoutParams,
     },
     ReactorEventT,
 };
@@ -33,29 +37,52 @@ pub(super) async fn run<REv: ReactorEventT>(
     api_version: ProtocolVersion,
     qps_limit: u64,
     max_body_bytes: u32,
+    max_batch_size: u32,
     cors_origin: String,
 ) {
+    let limiter = RequestLimiter::new(qps_limit);
     let mut handlers = RequestHandlersBuilder::new();
-    PutDeploy::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetBlock::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetBlockTransfers::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetStateRootHash::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetItem::register_as_handler(effect_builder, api_version, &mut handlers);
-    QueryGlobalState::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetBalance::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetAccountInfo::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetDeploy::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetPeers::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetStatus::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetEraInfoBySwitchBlock::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetEraSummary::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetAuctionInfo::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetTrie::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetValidatorChanges::register_as_handler(effect_builder, api_version, &mut handlers);
-    ListRpcs::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetDictionaryItem::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetChainspec::register_as_handler(effect_builder, api_version, &mut handlers);
-    QueryBalance::register_as_handler(effect_builder, api_version, &mut handlers);
+    PutDeploy::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    StageDeploy::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    AddDeployApprovals::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetStagedDeploys::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetBlock::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetBlockTransfers::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetStateRootHash::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetItem::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    QueryGlobalState::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetBalance::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetAccountInfo::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetDeploy::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetPeers::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetStatus::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetEraInfoBySwitchBlock::register_as_handler(
+        effect_builder,
+        api_version,
+        &limiter,
+        &mut handlers,
+    );
+    GetEraSummary::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetSyncLeap::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetStateDiff::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetAuctionInfo::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetTrie::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetValidatorChanges::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    ListRpcs::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetDictionaryItem::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetDictionaryItems::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetNamedKeys::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetKeys::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetChainspec::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetAccountDeploys::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    GetTransfersByAccount::register_as_handler(
+        effect_builder,
+        api_version,
+        &limiter,
+        &mut handlers,
+    );
+    GetPendingDeploys::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    QueryBalance::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
     let handlers = handlers.build();
 
     match cors_origin.as_str() {
@@ -65,6 +92,7 @@ pub(super) async fn run<REv: ReactorEventT>(
                 handlers,
                 qps_limit,
                 max_body_bytes,
+                max_batch_size,
                 RPC_API_PATH,
                 RPC_API_SERVER_NAME,
             )
@@ -76,6 +104,7 @@ pub(super) async fn run<REv: ReactorEventT>(
                 handlers,
                 qps_limit,
                 max_body_bytes,
+                max_batch_size,
                 RPC_API_PATH,
                 RPC_API_SERVER_NAME,
                 CorsOrigin::Any,
@@ -88,6 +117,7 @@ pub(super) async fn run<REv: ReactorEventT>(
                 handlers,
                 qps_limit,
                 max_body_bytes,
+                max_batch_size,
                 RPC_API_PATH,
                 RPC_API_SERVER_NAME,
                 CorsOrigin::Specified(cors_origin),
@@ -19,7 +19,10 @@ use hyper::server::{conn::AddrIncoming, Builder};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use tokio::sync::oneshot;
+use tokio::{
+    sync::{oneshot, Mutex},
+    time::Instant,
+};
 use tower::ServiceBuilder;
 use tracing::info;
 use warp::Filter;
@@ -88,11 +91,17 @@ pub(super) trait RpcWithParams {
     fn register_as_handler<REv: ReactorEventT>(
         effect_builder: EffectBuilder<REv>,
         api_version: ProtocolVersion,
+        limiter: &RequestLimiter,
         handlers_builder: &mut RequestHandlersBuilder,
     ) {
-        let handler = move |maybe_params| async move {
-            let params = Self::try_parse_params(maybe_params)?;
-            Self::do_handle_request(effect_builder, api_version, params).await
+        let limiter = limiter.clone();
+        let handler = move |maybe_params| {
+            let limiter = limiter.clone();
+            async move {
+                limiter.acquire().await;
+                let params = Self::try_parse_params(maybe_params)?;
+                Self::do_handle_request(effect_builder, api_version, params).await
+            }
         };
         handlers_builder.register_handler(Self::METHOD, Arc::new(handler))
     }
@@ -147,11 +156,17 @@ pub(super) trait RpcWithoutParams {
     fn register_as_handler<REv: ReactorEventT>(
         effect_builder: EffectBuilder<REv>,
         api_version: ProtocolVersion,
+        limiter: &RequestLimiter,
         handlers_builder: &mut RequestHandlersBuilder,
     ) {
-        let handler = move |maybe_params| async move {
-            Self::check_no_params(maybe_params)?;
-            Self::do_handle_request(effect_builder, api_version).await
+        let limiter = limiter.clone();
+        let handler = move |maybe_params| {
+            let limiter = limiter.clone();
+            async move {
+                limiter.acquire().await;
+                Self::check_no_params(maybe_params)?;
+                Self::do_handle_request(effect_builder, api_version).await
+            }
         };
         handlers_builder.register_handler(Self::METHOD, Arc::new(handler))
     }
@@ -227,11 +242,17 @@ pub(super) trait RpcWithOptionalParams {
     fn register_as_handler<REv: ReactorEventT>(
         effect_builder: EffectBuilder<REv>,
         api_version: ProtocolVersion,
+        limiter: &RequestLimiter,
         handlers_builder: &mut RequestHandlersBuilder,
     ) {
-        let handler = move |maybe_params| async move {
-            let params = Self::try_parse_params(maybe_params)?;
-            Self::do_handle_request(effect_builder, api_version, params).await
+        let limiter = limiter.clone();
+        let handler = move |maybe_params| {
+            let limiter = limiter.clone();
+            async move {
+                limiter.acquire().await;
+                let params = Self::try_parse_params(maybe_params)?;
+                Self::do_handle_request(effect_builder, api_version, params).await
+            }
         };
         handlers_builder.register_handler(Self::METHOD, Arc::new(handler))
     }
@@ -254,12 +275,62 @@ pub(super) trait RpcWithOptionalParams {
     ) -> Result<Self::ResponseResult, Error>;
 }
 
+/// Limits the rate at which JSON-RPC requests are handled.
+///
+/// Every request is charged individually, including each element of a batch request.  Once
+/// `qps_limit` requests have been handled within the current one second window, further requests
+/// are delayed until the next window.
+///
+/// This complements the limit on the rate at which connections are accepted, which also applies
+/// to requests that are rejected before reaching a handler.
+#[derive(Clone)]
+pub(super) struct RequestLimiter {
+    qps_limit: u64,
+    /// The start of the current window and the number of requests remaining in it.
+    window: Arc<Mutex<(Instant, u64)>>,
+}
+
+impl RequestLimiter {
+    /// The length of a rate-limiting window.
+    const WINDOW: Duration = Duration::from_secs(1);
+
+    /// Creates a new limiter allowing `qps_limit` requests per second.
+    pub(super) fn new(qps_limit: u64) -> Self {
+        RequestLimiter {
+            qps_limit,
+            window: Arc::new(Mutex::new((Instant::now(), qps_limit))),
+        }
+    }
+
+    /// Waits until the request can be handled without exceeding the limit, and charges it.
+    ///
+    /// Waiters hold the lock while sleeping, so requests are admitted in the order they arrived.
+    pub(super) async fn acquire(&self) {
+        let mut window = self.window.lock().await;
+        loop {
+            let (start, remaining) = &mut *window;
+            let now = Instant::now();
+            if now.saturating_duration_since(*start) >= Self::WINDOW {
+                *start = now;
+                *remaining = self.qps_limit;
+            }
+            if *remaining > 0 {
+                *remaining -= 1;
+                return;
+            }
+            tokio::time::sleep_until(*start + Self::WINDOW).await;
+        }
+    }
+}
+
 /// Start JSON RPC server with CORS enabled in a background.
+#[allow(clippy::too_many_arguments)]
 pub(super) async fn run_with_cors(
     builder: Builder<AddrIncoming>,
     handlers: RequestHandlers,
     qps_limit: u64,
     max_body_bytes: u32,
+    max_batch_size: u32,
     api_path: &'static str,
     server_name: &'static str,
     cors_header: CorsOrigin,
@@ -268,6 +339,7 @@ pub(super) async fn run_with_cors(
         let service_routes = casper_json_rpc::route_with_cors(
             api_path,
             max_body_bytes,
+            max_batch_size,
             handlers.clone(),
             ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST,
             &cors_header,
@@ -306,6 +378,7 @@ pub(super) async fn run(
     handlers: RequestHandlers,
     qps_limit: u64,
     max_body_bytes: u32,
+    max_batch_size: u32,
     api_path: &'static str,
     server_name: &'static str,
 ) {
@@ -313,6 +386,7 @@ pub(super) async fn run(
         let service_routes = casper_json_rpc::route(
             api_path,
             max_body_bytes,
+            max_batch_size,
             handlers.clone(),
             ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST,
         );
@@ -346,7 +420,10 @@ pub(super) async fn run(
 
 #[cfg(test)]
 mod tests {
-    use std::fmt::Write;
+    use std::{
+        fmt::Write,
+        io::{Read, Write as _},
+    };
 
     use http::StatusCode;
     use warp::{filters::BoxedFilter, Filter, Reply};
@@ -356,6 +433,8 @@ mod tests {
     use super::*;
     use crate::types::DeployHash;
 
+    const MAX_BATCH_SIZE: u32 = 1;
+
     async fn send_request(
         method: &str,
         maybe_params: Option<&str>,
@@ -381,6 +460,61 @@ mod tests {
         serde_json::from_slice(&body_bytes).unwrap()
     }
 
+    #[tokio::test(start_paused = true)]
+    async fn limiter_should_charge_every_request() {
+        let limiter = RequestLimiter::new(2);
+        let start = Instant::now();
+
+        limiter.acquire().await;
+        limiter.acquire().await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        // The third request within the window has to wait for the next one.
+        limiter.acquire().await;
+        assert!(start.elapsed() >= RequestLimiter::WINDOW);
+    }
+
+    #[tokio::test]
+    async fn invalid_requests_should_be_rate_limited() {
+        let incoming = AddrIncoming::bind(&([127, 0, 0, 1], 0).into()).unwrap();
+        let address = incoming.local_addr();
+        let builder = hyper::Server::builder(incoming);
+        let handlers = RequestHandlersBuilder::new().build();
+        tokio::spawn(run(
+            builder,
+            handlers,
+            1,
+            1_000,
+            MAX_BATCH_SIZE,
+            "rpc",
+            "test",
+        ));
+
+        // The body is not valid JSON, so the request never reaches a handler.
+        let send_invalid_request = move || {
+            let mut stream = std::net::TcpStream::connect(address).unwrap();
+            stream
+                .write_all(
+                    b"POST /rpc HTTP/1.1\r\nhost: localhost\r\ncontent-type: application/json\r\n\
+                    content-length: 1\r\nconnection: close\r\n\r\n{",
+                )
+                .unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            assert!(response.contains("Parse error"), "{}", response);
+        };
+
+        let start = Instant::now();
+        tokio::task::spawn_blocking(send_invalid_request)
+            .await
+            .unwrap();
+        tokio::task::spawn_blocking(send_invalid_request)
+            .await
+            .unwrap();
+        // The second request had to wait for the next one second window.
+        assert!(start.elapsed() >= Duration::from_secs(1));
+    }
+
     mod rpc_with_params {
         use super::*;
         use crate::components::rpc_server::rpcs::info::{
@@ -392,9 +526,13 @@ mod tests {
             GetDeploy::register_as_test_handler(&mut handlers);
             let handlers = handlers.build();
 
-            filters::main_filter(handlers, ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST)
-                .recover(filters::handle_rejection)
-                .boxed()
+            filters::main_filter(
+                handlers,
+                ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST,
+                MAX_BATCH_SIZE,
+            )
+            .recover(filters::handle_rejection)
+            .boxed()
         }
 
         #[tokio::test]
@@ -459,9 +597,13 @@ mod tests {
             GetPeers::register_as_test_handler(&mut handlers);
             let handlers = handlers.build();
 
-            filters::main_filter(handlers, ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST)
-                .recover(filters::handle_rejection)
-                .boxed()
+            filters::main_filter(
+                handlers,
+                ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST,
+                MAX_BATCH_SIZE,
+            )
+            .recover(filters::handle_rejection)
+            .boxed()
         }
 
         #[tokio::test]
@@ -513,9 +655,13 @@ mod tests {
             GetBlock::register_as_test_handler(&mut handlers);
             let handlers = handlers.build();
 
-            filters::main_filter(handlers, ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST)
-                .recover(filters::handle_rejection)
-                .boxed()
+            filters::main_filter(
+                handlers,
+                ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST,
+                MAX_BATCH_SIZE,
+            )
+            .recover(filters::handle_rejection)
+            .boxed()
         }
 
         #[tokio::test]
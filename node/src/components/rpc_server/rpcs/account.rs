@@ -3,7 +3,7 @@
 // TODO - remove once schemars stops causing warning.
 #![allow(clippy::field_reassign_with_default)]
 
-use std::{str, sync::Arc};
+use std::{collections::BTreeSet, str, sync::Arc};
 
 use async_trait::async_trait;
 use once_cell::sync::Lazy;
@@ -11,16 +11,17 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tracing::debug;
 
-use casper_types::ProtocolVersion;
+use casper_types::{ProtocolVersion, PublicKey};
 
 use super::{
     docs::{DocExample, DOCS_EXAMPLE_PROTOCOL_VERSION},
+    state::AccountIdentifier,
     Error, ReactorEventT, RpcWithParams,
 };
 use crate::{
-    components::rpc_server::rpcs::ErrorCode,
+    components::{deploy_staging, rpc_server::rpcs::ErrorCode},
     effect::EffectBuilder,
-    types::{Deploy, DeployHash},
+    types::{Approval, Deploy, DeployHash, StagedDeploy, StagedDeployStatus},
 };
 
 static PUT_DEPLOY_PARAMS: Lazy<PutDeployParams> = Lazy::new(|| PutDeployParams {
@@ -30,6 +31,42 @@ static PUT_DEPLOY_RESULT: Lazy<PutDeployResult> = Lazy::new(|| PutDeployResult {
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     deploy_hash: *Deploy::doc_example().hash(),
 });
+static STAGE_DEPLOY_PARAMS: Lazy<StageDeployParams> = Lazy::new(|| StageDeployParams {
+    deploy: Deploy::doc_example().clone(),
+});
+static STAGE_DEPLOY_RESULT: Lazy<StageDeployResult> = Lazy::new(|| StageDeployResult {
+    api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+    staged_deploy: StagedDeploy {
+        deploy: Deploy::doc_example().clone(),
+        approval_weight: 1,
+        deployment_threshold: 2,
+        status: StagedDeployStatus::AwaitingApprovals,
+    },
+});
+static ADD_DEPLOY_APPROVALS_PARAMS: Lazy<AddDeployApprovalsParams> =
+    Lazy::new(|| AddDeployApprovalsParams {
+        deploy_hash: *Deploy::doc_example().hash(),
+        approvals: Deploy::doc_example().approvals().clone(),
+    });
+static ADD_DEPLOY_APPROVALS_RESULT: Lazy<AddDeployApprovalsResult> =
+    Lazy::new(|| AddDeployApprovalsResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        staged_deploy: StagedDeploy {
+            deploy: Deploy::doc_example().clone(),
+            approval_weight: 2,
+            deployment_threshold: 2,
+            status: StagedDeployStatus::Accepted,
+        },
+    });
+static GET_STAGED_DEPLOYS_PARAMS: Lazy<GetStagedDeploysParams> =
+    Lazy::new(|| GetStagedDeploysParams {
+        account_identifier: AccountIdentifier::PublicKey(PublicKey::doc_example().clone()),
+    });
+static GET_STAGED_DEPLOYS_RESULT: Lazy<GetStagedDeploysResult> =
+    Lazy::new(|| GetStagedDeploysResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        deploys: vec![STAGE_DEPLOY_RESULT.staged_deploy.clone()],
+    });
 
 /// Params for "account_put_deploy" RPC request.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
@@ -102,3 +139,197 @@ impl RpcWithParams for PutDeploy {
         }
     }
 }
+
+/// Params for "account_stage_deploy" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct StageDeployParams {
+    /// The `Deploy`, carrying one or more approvals by associated keys of its account.
+    pub deploy: Deploy,
+}
+
+impl DocExample for StageDeployParams {
+    fn doc_example() -> &'static Self {
+        &STAGE_DEPLOY_PARAMS
+    }
+}
+
+/// Result for "account_stage_deploy" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct StageDeployResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The staged deploy.
+    pub staged_deploy: StagedDeploy,
+}
+
+impl DocExample for StageDeployResult {
+    fn doc_example() -> &'static Self {
+        &STAGE_DEPLOY_RESULT
+    }
+}
+
+/// "account_stage_deploy" RPC.
+pub struct StageDeploy {}
+
+#[async_trait]
+impl RpcWithParams for StageDeploy {
+    const METHOD: &'static str = "account_stage_deploy";
+    type RequestParams = StageDeployParams;
+    type ResponseResult = StageDeployResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let deploy_hash = *params.deploy.hash();
+        match effect_builder.stage_deploy(params.deploy).await {
+            Ok(staged_deploy) => {
+                debug!(%staged_deploy, "deploy was staged");
+                Ok(Self::ResponseResult {
+                    api_version,
+                    staged_deploy,
+                })
+            }
+            Err(error) => {
+                debug!(%deploy_hash, %error, "failed to stage deploy");
+                Err(staged_deploy_error(error))
+            }
+        }
+    }
+}
+
+/// Params for "account_add_deploy_approvals" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AddDeployApprovalsParams {
+    /// The hash of the staged deploy.
+    pub deploy_hash: DeployHash,
+    /// The approvals to add to the staged deploy.
+    pub approvals: BTreeSet<Approval>,
+}
+
+impl DocExample for AddDeployApprovalsParams {
+    fn doc_example() -> &'static Self {
+        &ADD_DEPLOY_APPROVALS_PARAMS
+    }
+}
+
+/// Result for "account_add_deploy_approvals" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AddDeployApprovalsResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The staged deploy.
+    pub staged_deploy: StagedDeploy,
+}
+
+impl DocExample for AddDeployApprovalsResult {
+    fn doc_example() -> &'static Self {
+        &ADD_DEPLOY_APPROVALS_RESULT
+    }
+}
+
+/// "account_add_deploy_approvals" RPC.
+pub struct AddDeployApprovals {}
+
+#[async_trait]
+impl RpcWithParams for AddDeployApprovals {
+    const METHOD: &'static str = "account_add_deploy_approvals";
+    type RequestParams = AddDeployApprovalsParams;
+    type ResponseResult = AddDeployApprovalsResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let deploy_hash = params.deploy_hash;
+        match effect_builder
+            .add_staged_deploy_approvals(deploy_hash, params.approvals)
+            .await
+        {
+            Ok(staged_deploy) => {
+                debug!(%staged_deploy, "approvals were added to staged deploy");
+                Ok(Self::ResponseResult {
+                    api_version,
+                    staged_deploy,
+                })
+            }
+            Err(error) => {
+                debug!(%deploy_hash, %error, "failed to add approvals to staged deploy");
+                Err(staged_deploy_error(error))
+            }
+        }
+    }
+}
+
+/// Params for "account_get_staged_deploys" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetStagedDeploysParams {
+    /// The account whose staged deploys are requested.
+    pub account_identifier: AccountIdentifier,
+}
+
+impl DocExample for GetStagedDeploysParams {
+    fn doc_example() -> &'static Self {
+        &GET_STAGED_DEPLOYS_PARAMS
+    }
+}
+
+/// Result for "account_get_staged_deploys" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetStagedDeploysResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The deploys of the account awaiting approvals, in ascending order of deploy hash.
+    pub deploys: Vec<StagedDeploy>,
+}
+
+impl DocExample for GetStagedDeploysResult {
+    fn doc_example() -> &'static Self {
+        &GET_STAGED_DEPLOYS_RESULT
+    }
+}
+
+/// "account_get_staged_deploys" RPC.
+pub struct GetStagedDeploys {}
+
+#[async_trait]
+impl RpcWithParams for GetStagedDeploys {
+    const METHOD: &'static str = "account_get_staged_deploys";
+    type RequestParams = GetStagedDeploysParams;
+    type ResponseResult = GetStagedDeploysResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let account_hash = match params.account_identifier {
+            AccountIdentifier::PublicKey(public_key) => public_key.to_account_hash(),
+            AccountIdentifier::AccountHash(account_hash) => account_hash,
+        };
+        let deploys = effect_builder.get_staged_deploys(account_hash).await;
+        Ok(Self::ResponseResult {
+            api_version,
+            deploys,
+        })
+    }
+}
+
+fn staged_deploy_error(error: deploy_staging::Error) -> Error {
+    let error_code = match error {
+        deploy_staging::Error::UnknownDeploy { .. } => ErrorCode::NoSuchStagedDeploy,
+        _ => ErrorCode::InvalidDeploy,
+    };
+    Error::new(error_code, error.to_string())
+}
@@ -11,20 +11,31 @@ use async_trait::async_trait;
 use once_cell::sync::Lazy;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
 
-use casper_execution_engine::core::engine_state::{self, QueryResult};
+use casper_execution_engine::core::engine_state::{
+    self, QueryResult, StateDiffRequest, StateDiffResult, TrieDiff,
+};
 use casper_hashing::Digest;
-use casper_types::{Key, ProtocolVersion, Transfer};
+use casper_types::{
+    bytesrepr::FromBytes, CLValue, Key, ProtocolVersion, StoredValue as DomainStoredValue,
+    Transfer, U512,
+};
 
 use super::{
     docs::{DocExample, DOCS_EXAMPLE_PROTOCOL_VERSION},
     Error, ErrorCode, ReactorEventT, ReservedErrorCode, RpcRequest, RpcWithOptionalParams,
+    RpcWithParams,
 };
 use crate::{
+    components::fetcher::FetchResponse,
     effect::EffectBuilder,
     reactor::QueueKind,
     rpcs::{common, state},
-    types::{Block, BlockHash, BlockWithMetadata, JsonBlock},
+    types::{
+        json_compatibility::StoredValue, Block, BlockHash, BlockWithMetadata, JsonBlock,
+        JsonSyncLeap,
+    },
 };
 pub use era_summary::EraSummary;
 use era_summary::ERA_SUMMARY;
@@ -69,6 +80,31 @@ static GET_ERA_SUMMARY_RESULT: Lazy<GetEraSummaryResult> = Lazy::new(|| GetEraSu
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     era_summary: ERA_SUMMARY.clone(),
 });
+static GET_SYNC_LEAP_PARAMS: Lazy<GetSyncLeapParams> = Lazy::new(|| GetSyncLeapParams {
+    trusted_block_hash: *Block::doc_example().hash(),
+});
+static GET_SYNC_LEAP_RESULT: Lazy<GetSyncLeapResult> = Lazy::new(|| GetSyncLeapResult {
+    api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+    sync_leap: JsonSyncLeap::doc_example().clone(),
+});
+static GET_STATE_DIFF_PARAMS: Lazy<GetStateDiffParams> = Lazy::new(|| GetStateDiffParams {
+    pre_state_identifier: state::GlobalStateIdentifier::BlockHash(*Block::doc_example().hash()),
+    post_state_identifier: state::GlobalStateIdentifier::StateRootHash(
+        *Block::doc_example().header().state_root_hash(),
+    ),
+    key_tag: Some(state::KeyTag::Balance),
+    page_token: None,
+    page_size: None,
+});
+static GET_STATE_DIFF_RESULT: Lazy<GetStateDiffResult> = Lazy::new(|| GetStateDiffResult {
+    api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+    diffs: vec![StateDiff::Modified {
+        key: "balance-09480c3248ef76b603d386f3f4f8a5f87f597d4eaffd475433f861af187ab5db".to_string(),
+        old_value: StoredValue::CLValue(CLValue::from_t(U512::from(123_456)).unwrap()),
+        new_value: StoredValue::CLValue(CLValue::from_t(U512::from(654_321)).unwrap()),
+    }],
+    next_page_token: None,
+});
 
 /// Identifier for possible ways to retrieve a block.
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, JsonSchema)]
@@ -470,6 +506,273 @@ impl RpcWithOptionalParams for GetEraSummary {
     }
 }
 
+/// Params for "chain_get_sync_leap" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetSyncLeapParams {
+    /// The hash of a block trusted by the caller.
+    pub trusted_block_hash: BlockHash,
+}
+
+impl DocExample for GetSyncLeapParams {
+    fn doc_example() -> &'static Self {
+        &GET_SYNC_LEAP_PARAMS
+    }
+}
+
+/// Result for "chain_get_sync_leap" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetSyncLeapResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The headers and signatures proving the chain from the trusted block to the highest
+    /// complete block known to the node.
+    pub sync_leap: JsonSyncLeap,
+}
+
+impl DocExample for GetSyncLeapResult {
+    fn doc_example() -> &'static Self {
+        &GET_SYNC_LEAP_RESULT
+    }
+}
+
+/// "chain_get_sync_leap" RPC.
+pub struct GetSyncLeap {}
+
+#[async_trait]
+impl RpcWithParams for GetSyncLeap {
+    const METHOD: &'static str = "chain_get_sync_leap";
+    type RequestParams = GetSyncLeapParams;
+    type ResponseResult = GetSyncLeapResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let trusted_block_hash = params.trusted_block_hash;
+        let fetch_response = effect_builder
+            .make_request(
+                |responder| RpcRequest::GetSyncLeap {
+                    trusted_block_hash,
+                    responder,
+                },
+                QueueKind::Api,
+            )
+            .await;
+
+        match fetch_response {
+            FetchResponse::Fetched(sync_leap) => Ok(Self::ResponseResult {
+                api_version,
+                sync_leap: JsonSyncLeap::from(sync_leap),
+            }),
+            FetchResponse::NotFound(_) => Err(common::missing_block_or_state_root_error(
+                effect_builder,
+                ErrorCode::NoSuchBlock,
+                format!(
+                    "block {:?} not stored on this node",
+                    trusted_block_hash.inner()
+                ),
+            )
+            .await),
+            FetchResponse::NotProvided(_) => {
+                let message = format!(
+                    "block {:?} is too many eras behind the highest complete block",
+                    trusted_block_hash.inner()
+                );
+                Err(Error::new(ErrorCode::TrustedBlockTooOld, message))
+            }
+        }
+    }
+}
+
+/// Params for "chain_get_state_diff" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetStateDiffParams {
+    /// The identifier of the global state against which differences are reported.
+    pub pre_state_identifier: state::GlobalStateIdentifier,
+    /// The identifier of the global state whose differences are reported.
+    pub post_state_identifier: state::GlobalStateIdentifier,
+    /// If given, only differences under keys of this type are reported.
+    pub key_tag: Option<state::KeyTag>,
+    /// The `next_page_token` returned by a previous request, or omitted to request the first page.
+    pub page_token: Option<String>,
+    /// The maximum number of differences to return, capped at 100.  Defaults to 100 if omitted.
+    pub page_size: Option<u32>,
+}
+
+impl DocExample for GetStateDiffParams {
+    fn doc_example() -> &'static Self {
+        &GET_STATE_DIFF_PARAMS
+    }
+}
+
+/// A difference between the values stored under a key in two global states.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub enum StateDiff {
+    /// The key is only present in the post-state.
+    Added {
+        /// The formatted key.
+        key: String,
+        /// The value in the post-state.
+        value: StoredValue,
+    },
+    /// The key is only present in the pre-state.
+    Removed {
+        /// The formatted key.
+        key: String,
+        /// The value in the pre-state.
+        value: StoredValue,
+    },
+    /// The key is present in both states with different values.
+    Modified {
+        /// The formatted key.
+        key: String,
+        /// The value in the pre-state.
+        old_value: StoredValue,
+        /// The value in the post-state.
+        new_value: StoredValue,
+    },
+}
+
+impl TryFrom<TrieDiff<Key, DomainStoredValue>> for StateDiff {
+    type Error = Error;
+
+    fn try_from(diff: TrieDiff<Key, DomainStoredValue>) -> Result<Self, Self::Error> {
+        let encode = |value: DomainStoredValue| {
+            StoredValue::try_from(value).map_err(|error| {
+                warn!(?error, "failed to encode stored value");
+                Error::new(
+                    ReservedErrorCode::InternalError,
+                    format!("failed to encode stored value: {}", error),
+                )
+            })
+        };
+        let state_diff = match diff {
+            TrieDiff::Added { key, value } => StateDiff::Added {
+                key: key.to_formatted_string(),
+                value: encode(value)?,
+            },
+            TrieDiff::Removed { key, value } => StateDiff::Removed {
+                key: key.to_formatted_string(),
+                value: encode(value)?,
+            },
+            TrieDiff::Modified {
+                key,
+                old_value,
+                new_value,
+            } => StateDiff::Modified {
+                key: key.to_formatted_string(),
+                old_value: encode(old_value)?,
+                new_value: encode(new_value)?,
+            },
+        };
+        Ok(state_diff)
+    }
+}
+
+/// Result for "chain_get_state_diff" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetStateDiffResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The differences, in ascending order of serialized key.
+    pub diffs: Vec<StateDiff>,
+    /// The token with which to request the next page, if there are further differences.
+    pub next_page_token: Option<String>,
+}
+
+impl DocExample for GetStateDiffResult {
+    fn doc_example() -> &'static Self {
+        &GET_STATE_DIFF_RESULT
+    }
+}
+
+/// "chain_get_state_diff" RPC.
+pub struct GetStateDiff {}
+
+#[async_trait]
+impl RpcWithParams for GetStateDiff {
+    const METHOD: &'static str = "chain_get_state_diff";
+    type RequestParams = GetStateDiffParams;
+    type ResponseResult = GetStateDiffResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let (pre_state_hash, _) = state::get_state_root_hash_and_optional_header(
+            effect_builder,
+            params.pre_state_identifier,
+        )
+        .await?;
+        let (post_state_hash, _) = state::get_state_root_hash_and_optional_header(
+            effect_builder,
+            params.post_state_identifier,
+        )
+        .await?;
+
+        let after = state::parse_page_token(params.page_token.as_deref(), |bytes| {
+            Key::from_bytes(&bytes).ok().map(|(key, _)| key)
+        })?;
+        let limit = state::page_limit(params.page_size);
+
+        let state_diff_request = StateDiffRequest::new(
+            pre_state_hash,
+            post_state_hash,
+            params.key_tag.map(Into::into),
+            after,
+            limit,
+        );
+        let state_diff_result = effect_builder
+            .make_request(
+                |responder| RpcRequest::GetStateDiff {
+                    state_diff_request,
+                    responder,
+                },
+                QueueKind::Api,
+            )
+            .await;
+
+        let (diffs, next) = match state_diff_result {
+            Ok(StateDiffResult::Success { diffs, next }) => (diffs, next),
+            Ok(StateDiffResult::RootNotFound(state_root_hash)) => {
+                return Err(common::missing_block_or_state_root_error(
+                    effect_builder,
+                    ErrorCode::NoSuchStateRoot,
+                    format!("failed to get state root at {:?}", state_root_hash),
+                )
+                .await)
+            }
+            Err(error) => {
+                info!(?error, "get state diff failed to execute");
+                return Err(Error::new(
+                    ErrorCode::QueryFailedToExecute,
+                    format!("{:?}", error),
+                ));
+            }
+        };
+
+        let diffs = diffs
+            .into_iter()
+            .map(StateDiff::try_from)
+            .collect::<Result<_, _>>()?;
+        let result = Self::ResponseResult {
+            api_version,
+            diffs,
+            next_page_token: next.map(state::key_to_page_token),
+        };
+        Ok(result)
+    }
+}
+
 pub(super) async fn get_block_with_metadata<REv: ReactorEventT>(
     maybe_id: Option<BlockIdentifier>,
     only_from_available_block_range: bool,
@@ -16,14 +16,18 @@ use serde_json::{json, Value};
 use casper_types::ProtocolVersion;
 
 use super::{
-    account::PutDeploy,
+    account::{AddDeployApprovals, GetStagedDeploys, PutDeploy, StageDeploy},
     chain::{
-        GetBlock, GetBlockTransfers, GetEraInfoBySwitchBlock, GetEraSummary, GetStateRootHash,
+        GetBlock, GetBlockTransfers, GetEraInfoBySwitchBlock, GetEraSummary, GetStateDiff,
+        GetStateRootHash, GetSyncLeap,
+    },
+    info::{
+        GetAccountDeploys, GetChainspec, GetDeploy, GetPeers, GetPendingDeploys, GetStatus,
+        GetTransfersByAccount, GetValidatorChanges,
     },
-    info::{GetChainspec, GetDeploy, GetPeers, GetStatus, GetValidatorChanges},
     state::{
-        GetAccountInfo, GetAuctionInfo, GetBalance, GetDictionaryItem, GetItem, QueryBalance,
-        QueryGlobalState,
+        GetAccountInfo, GetAuctionInfo, GetBalance, GetDictionaryItem, GetDictionaryItems, GetItem,
+        GetKeys, GetNamedKeys, QueryBalance, QueryGlobalState,
     },
     Error, ReactorEventT, RpcWithOptionalParams, RpcWithParams, RpcWithoutParams,
 };
@@ -69,6 +73,13 @@ pub(crate) static OPEN_RPC_SCHEMA: Lazy<OpenRpcSchema> = Lazy::new(|| {
     };
 
     schema.push_with_params::<PutDeploy>("receives a Deploy to be executed by the network");
+    schema.push_with_params::<StageDeploy>(
+        "stages a Deploy until its approvals meet the deployment threshold of its account",
+    );
+    schema.push_with_params::<AddDeployApprovals>("adds approvals to a staged Deploy");
+    schema.push_with_params::<GetStagedDeploys>(
+        "returns the staged Deploys of an account awaiting approvals",
+    );
     schema.push_with_params::<GetDeploy>("returns a Deploy from the network");
     schema.push_with_params::<GetAccountInfo>("returns an Account from the network");
     schema.push_with_params::<GetDictionaryItem>("returns an item from a Dictionary");
@@ -98,6 +109,13 @@ pub(crate) static OPEN_RPC_SCHEMA: Lazy<OpenRpcSchema> = Lazy::new(|| {
         `query_global_state` instead.",
     );
     schema.push_with_params::<GetBalance>("returns a purse's balance from the network");
+    schema.push_with_params::<GetNamedKeys>(
+        "returns a page of the named keys of an account or contract",
+    );
+    schema.push_with_params::<GetDictionaryItems>(
+        "returns a page of the items of the dictionary with the given seed URef",
+    );
+    schema.push_with_params::<GetKeys>("returns a page of the global state keys of a given type");
     schema.push_with_optional_params::<GetEraInfoBySwitchBlock>(
         "returns an EraInfo from the network",
     );
@@ -109,6 +127,24 @@ pub(crate) static OPEN_RPC_SCHEMA: Lazy<OpenRpcSchema> = Lazy::new(|| {
         "returns the era summary at either a specific block (by height or hash), or the most \
         recently added block",
     );
+    schema.push_with_params::<GetAccountDeploys>(
+        "returns the deploys sent by an account, if the node maintains an activity index",
+    );
+    schema.push_with_optional_params::<GetPendingDeploys>(
+        "returns the deploys held in the node's deploy buffer, optionally only those of a given \
+        account",
+    );
+    schema.push_with_params::<GetTransfersByAccount>(
+        "returns the transfers to or from an account or purse, if the node maintains an activity \
+        index",
+    );
+    schema.push_with_params::<GetSyncLeap>(
+        "returns the headers and signatures proving the chain from a trusted block to the most \
+        recent complete block, from which light clients can follow the validator set",
+    );
+    schema.push_with_params::<GetStateDiff>(
+        "returns the keys whose values were added, removed or modified between two global states",
+    );
 
     schema
 });
@@ -35,6 +35,14 @@ pub enum ErrorCode {
     FailedToGetTrie = -32011,
     /// The requested state root hash was not found.
     NoSuchStateRoot = -32012,
+    /// The account activity index is not enabled on this node.
+    ActivityIndexNotEnabled = -32013,
+    /// The given page token is invalid.
+    InvalidPageToken = -32014,
+    /// The trusted block is too old for the node to prove the chain from it to the tip.
+    TrustedBlockTooOld = -32015,
+    /// The requested deploy is not held in the deploy staging area.
+    NoSuchStagedDeploy = -32016,
 }
 
 impl From<ErrorCode> for (i64, &'static str) {
@@ -59,6 +67,10 @@ impl From<ErrorCode> for (i64, &'static str) {
             }
             ErrorCode::FailedToGetTrie => (error_code as i64, "Failed to get trie"),
             ErrorCode::NoSuchStateRoot => (error_code as i64, "No such state root"),
+            ErrorCode::ActivityIndexNotEnabled => (error_code as i64, "Activity index not enabled"),
+            ErrorCode::InvalidPageToken => (error_code as i64, "Invalid page token"),
+            ErrorCode::TrustedBlockTooOld => (error_code as i64, "Trusted block too old"),
+            ErrorCode::NoSuchStagedDeploy => (error_code as i64, "No such staged deploy"),
         }
     }
 }
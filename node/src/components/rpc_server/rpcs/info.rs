@@ -11,19 +11,28 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tracing::info;
 
-use casper_types::{EraId, ExecutionResult, ProtocolVersion, PublicKey};
+use casper_types::{
+    account::AccountHash, EraId, ExecutionResult, ProtocolVersion, PublicKey, TimeDiff, Transfer,
+    URef,
+};
 
 use super::{
     docs::{DocExample, DOCS_EXAMPLE_PROTOCOL_VERSION},
-    Error, ErrorCode, ReactorEventT, RpcRequest, RpcWithParams, RpcWithoutParams,
+    state::AccountIdentifier,
+    Error, ErrorCode, ReactorEventT, RpcRequest, RpcWithOptionalParams, RpcWithParams,
+    RpcWithoutParams,
 };
 use crate::{
-    components::consensus::ValidatorChange,
+    components::{
+        consensus::ValidatorChange,
+        storage::{ActivityCursor, ActivityPage, ActivitySubject},
+    },
     effect::EffectBuilder,
     reactor::QueueKind,
     types::{
         Block, BlockHash, BlockHashAndHeight, ChainspecRawBytes, Deploy, DeployHash,
-        DeployMetadataExt, GetStatusResult, PeersMap,
+        DeployMetadataExt, GetStatusResult, JsonDeployFootprint, PeersMap, PendingDeploy,
+        PendingDeployStatus,
     },
 };
 
@@ -53,6 +62,57 @@ static GET_VALIDATOR_CHANGES_RESULT: Lazy<GetValidatorChangesResult> = Lazy::new
         changes,
     }
 });
+static GET_ACCOUNT_DEPLOYS_PARAMS: Lazy<GetAccountDeploysParams> =
+    Lazy::new(|| GetAccountDeploysParams {
+        account_identifier: AccountIdentifier::PublicKey(PublicKey::doc_example().clone()),
+        page_token: None,
+        page_size: Some(MAX_ACTIVITY_PAGE_SIZE),
+    });
+static GET_ACCOUNT_DEPLOYS_RESULT: Lazy<GetAccountDeploysResult> =
+    Lazy::new(|| GetAccountDeploysResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        deploys: vec![JsonAccountDeploy {
+            deploy_hash: *Deploy::doc_example().hash(),
+            block_hash: *Block::doc_example().hash(),
+            block_height: Block::doc_example().header().height(),
+        }],
+        next_page_token: None,
+    });
+static GET_TRANSFERS_BY_ACCOUNT_PARAMS: Lazy<GetTransfersByAccountParams> =
+    Lazy::new(|| GetTransfersByAccountParams {
+        transfer_participant: TransferParticipant::AccountHash(
+            PublicKey::doc_example().to_account_hash(),
+        ),
+        page_token: None,
+        page_size: Some(MAX_ACTIVITY_PAGE_SIZE),
+    });
+static GET_TRANSFERS_BY_ACCOUNT_RESULT: Lazy<GetTransfersByAccountResult> =
+    Lazy::new(|| GetTransfersByAccountResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        transfers: vec![JsonAccountTransfer {
+            block_hash: *Block::doc_example().hash(),
+            block_height: Block::doc_example().header().height(),
+            transfer: Transfer::default(),
+        }],
+        next_page_token: None,
+    });
+static GET_PENDING_DEPLOYS_PARAMS: Lazy<GetPendingDeploysParams> =
+    Lazy::new(|| GetPendingDeploysParams {
+        account_identifier: AccountIdentifier::PublicKey(PublicKey::doc_example().clone()),
+    });
+static GET_PENDING_DEPLOYS_RESULT: Lazy<GetPendingDeploysResult> =
+    Lazy::new(|| GetPendingDeploysResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        deploys: vec![PendingDeploy {
+            deploy_hash: *Deploy::doc_example().hash(),
+            footprint: Deploy::doc_example()
+                .footprint()
+                .ok()
+                .map(JsonDeployFootprint::from),
+            ttl_remaining: TimeDiff::from_seconds(1800),
+            status: PendingDeployStatus::Proposable,
+        }],
+    });
 static GET_CHAINSPEC_RESULT: Lazy<GetChainspecResult> = Lazy::new(|| GetChainspecResult {
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     chainspec_bytes: ChainspecRawBytes::new(vec![42, 42].into(), None, None),
@@ -213,10 +273,7 @@ impl RpcWithoutParams for GetPeers {
             )
             .await;
 
-        let result = Self::ResponseResult {
-            api_version,
-            peers: PeersMap::from(peers),
-        };
+        let result = Self::ResponseResult { api_version, peers };
         Ok(result)
     }
 }
@@ -390,3 +447,312 @@ impl RpcWithoutParams for GetChainspec {
         Ok(result)
     }
 }
+
+/// The maximum number of entries returned in a single page by the activity index RPCs.
+pub const MAX_ACTIVITY_PAGE_SIZE: u32 = 100;
+
+/// Reads a page of the activity index for the given subject, handling the page token and size.
+async fn get_activity_page<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    subject: ActivitySubject,
+    page_token: Option<&str>,
+    page_size: Option<u32>,
+) -> Result<ActivityPage, Error> {
+    let after = match page_token {
+        Some(page_token) => match ActivityCursor::from_page_token(page_token) {
+            Some(cursor) => Some(cursor),
+            None => {
+                let message = format!("failed to parse page token '{}'", page_token);
+                return Err(Error::new(ErrorCode::InvalidPageToken, message));
+            }
+        },
+        None => None,
+    };
+    let limit = page_size
+        .unwrap_or(MAX_ACTIVITY_PAGE_SIZE)
+        .clamp(1, MAX_ACTIVITY_PAGE_SIZE) as usize;
+
+    let maybe_page = effect_builder
+        .make_request(
+            |responder| RpcRequest::GetAccountActivity {
+                subject,
+                after,
+                limit,
+                responder,
+            },
+            QueueKind::Api,
+        )
+        .await;
+
+    maybe_page.ok_or_else(|| {
+        let message = "the activity index is not enabled on this node".to_string();
+        info!("{}", message);
+        Error::new(ErrorCode::ActivityIndexNotEnabled, message)
+    })
+}
+
+/// Params for "info_get_account_deploys" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetAccountDeploysParams {
+    /// The account whose deploys are requested.
+    pub account_identifier: AccountIdentifier,
+    /// The `next_page_token` returned by a previous request, or omitted to request the first page.
+    pub page_token: Option<String>,
+    /// The maximum number of deploys to return, capped at 100.  Defaults to 100 if omitted.
+    pub page_size: Option<u32>,
+}
+
+impl DocExample for GetAccountDeploysParams {
+    fn doc_example() -> &'static Self {
+        &GET_ACCOUNT_DEPLOYS_PARAMS
+    }
+}
+
+/// A deploy sent by an account, along with the block in which it was executed.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JsonAccountDeploy {
+    /// The deploy hash.
+    pub deploy_hash: DeployHash,
+    /// The hash of the block in which the deploy was executed.
+    pub block_hash: BlockHash,
+    /// The height of the block in which the deploy was executed.
+    pub block_height: u64,
+}
+
+/// Result for "info_get_account_deploys" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetAccountDeploysResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The deploys sent by the account, in ascending order of block height.
+    pub deploys: Vec<JsonAccountDeploy>,
+    /// The token with which to request the next page, if there are further deploys.
+    pub next_page_token: Option<String>,
+}
+
+impl DocExample for GetAccountDeploysResult {
+    fn doc_example() -> &'static Self {
+        &GET_ACCOUNT_DEPLOYS_RESULT
+    }
+}
+
+/// "info_get_account_deploys" RPC.
+pub struct GetAccountDeploys {}
+
+#[async_trait]
+impl RpcWithParams for GetAccountDeploys {
+    const METHOD: &'static str = "info_get_account_deploys";
+    type RequestParams = GetAccountDeploysParams;
+    type ResponseResult = GetAccountDeploysResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let account_hash = match params.account_identifier {
+            AccountIdentifier::PublicKey(public_key) => public_key.to_account_hash(),
+            AccountIdentifier::AccountHash(account_hash) => account_hash,
+        };
+        let page = get_activity_page(
+            effect_builder,
+            ActivitySubject::AccountDeploys(account_hash),
+            params.page_token.as_deref(),
+            params.page_size,
+        )
+        .await?;
+
+        let deploys = page
+            .entries
+            .into_iter()
+            .map(|entry| JsonAccountDeploy {
+                deploy_hash: entry.deploy_hash,
+                block_hash: entry.block_hash,
+                block_height: entry.block_height,
+            })
+            .collect();
+
+        let result = Self::ResponseResult {
+            api_version,
+            deploys,
+            next_page_token: page.next.map(ActivityCursor::to_page_token),
+        };
+        Ok(result)
+    }
+}
+
+/// Identifier of the account or purse whose transfers are requested.
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum TransferParticipant {
+    /// Transfers to or from the account identified by this public key.
+    PublicKey(PublicKey),
+    /// Transfers to or from the account identified by this account hash.
+    AccountHash(AccountHash),
+    /// Transfers to or from the purse identified by this URef.
+    PurseUref(URef),
+}
+
+/// Params for "info_get_transfers_by_account" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetTransfersByAccountParams {
+    /// The account or purse whose transfers are requested.
+    pub transfer_participant: TransferParticipant,
+    /// The `next_page_token` returned by a previous request, or omitted to request the first page.
+    pub page_token: Option<String>,
+    /// The maximum number of transfers to return, capped at 100.  Defaults to 100 if omitted.
+    pub page_size: Option<u32>,
+}
+
+impl DocExample for GetTransfersByAccountParams {
+    fn doc_example() -> &'static Self {
+        &GET_TRANSFERS_BY_ACCOUNT_PARAMS
+    }
+}
+
+/// A successful transfer, along with the block in which it was executed.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JsonAccountTransfer {
+    /// The hash of the block in which the transfer was executed.
+    pub block_hash: BlockHash,
+    /// The height of the block in which the transfer was executed.
+    pub block_height: u64,
+    /// The transfer.
+    pub transfer: Transfer,
+}
+
+/// Result for "info_get_transfers_by_account" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetTransfersByAccountResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The transfers to or from the account or purse, in ascending order of block height.
+    pub transfers: Vec<JsonAccountTransfer>,
+    /// The token with which to request the next page, if there are further transfers.
+    pub next_page_token: Option<String>,
+}
+
+impl DocExample for GetTransfersByAccountResult {
+    fn doc_example() -> &'static Self {
+        &GET_TRANSFERS_BY_ACCOUNT_RESULT
+    }
+}
+
+/// "info_get_transfers_by_account" RPC.
+pub struct GetTransfersByAccount {}
+
+#[async_trait]
+impl RpcWithParams for GetTransfersByAccount {
+    const METHOD: &'static str = "info_get_transfers_by_account";
+    type RequestParams = GetTransfersByAccountParams;
+    type ResponseResult = GetTransfersByAccountResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let subject = match params.transfer_participant {
+            TransferParticipant::PublicKey(public_key) => {
+                ActivitySubject::AccountTransfers(public_key.to_account_hash())
+            }
+            TransferParticipant::AccountHash(account_hash) => {
+                ActivitySubject::AccountTransfers(account_hash)
+            }
+            TransferParticipant::PurseUref(uref) => ActivitySubject::PurseTransfers(uref),
+        };
+        let page = get_activity_page(
+            effect_builder,
+            subject,
+            params.page_token.as_deref(),
+            params.page_size,
+        )
+        .await?;
+
+        let transfers = page
+            .entries
+            .into_iter()
+            .filter_map(|entry| {
+                Some(JsonAccountTransfer {
+                    block_hash: entry.block_hash,
+                    block_height: entry.block_height,
+                    transfer: entry.transfer?,
+                })
+            })
+            .collect();
+
+        let result = Self::ResponseResult {
+            api_version,
+            transfers,
+            next_page_token: page.next.map(ActivityCursor::to_page_token),
+        };
+        Ok(result)
+    }
+}
+
+/// Params for "info_get_pending_deploys" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetPendingDeploysParams {
+    /// The account whose pending deploys are requested.
+    pub account_identifier: AccountIdentifier,
+}
+
+impl DocExample for GetPendingDeploysParams {
+    fn doc_example() -> &'static Self {
+        &GET_PENDING_DEPLOYS_PARAMS
+    }
+}
+
+/// Result for "info_get_pending_deploys" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetPendingDeploysResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The deploys held in the node's deploy buffer, in ascending order of deploy hash.
+    pub deploys: Vec<PendingDeploy>,
+}
+
+impl DocExample for GetPendingDeploysResult {
+    fn doc_example() -> &'static Self {
+        &GET_PENDING_DEPLOYS_RESULT
+    }
+}
+
+/// "info_get_pending_deploys" RPC.
+pub struct GetPendingDeploys {}
+
+#[async_trait]
+impl RpcWithOptionalParams for GetPendingDeploys {
+    const METHOD: &'static str = "info_get_pending_deploys";
+    type OptionalRequestParams = GetPendingDeploysParams;
+    type ResponseResult = GetPendingDeploysResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        maybe_params: Option<Self::OptionalRequestParams>,
+    ) -> Result<Self::ResponseResult, Error> {
+        let maybe_account_hash = maybe_params.map(|params| match params.account_identifier {
+            AccountIdentifier::PublicKey(public_key) => public_key.to_account_hash(),
+            AccountIdentifier::AccountHash(account_hash) => account_hash,
+        });
+        let deploys = effect_builder.get_pending_deploys(maybe_account_hash).await;
+
+        let result = Self::ResponseResult {
+            api_version,
+            deploys,
+        };
+        Ok(result)
+    }
+}
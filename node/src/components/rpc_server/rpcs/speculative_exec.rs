@@ -10,31 +10,59 @@ use once_cell::sync::Lazy;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use casper_execution_engine::core::engine_state::Error as EngineStateError;
-use casper_json_rpc::ReservedErrorCode;
-use casper_types::{ExecutionResult, ProtocolVersion};
+use casper_execution_engine::core::engine_state::{Error as EngineStateError, GasEstimate};
+use casper_hashing::Digest;
+use casper_json_rpc::{RequestHandlersBuilder, ReservedErrorCode};
+use casper_types::{ExecutionResult, ProtocolVersion, U512};
 
 use super::{
     chain::BlockIdentifier,
     common,
     docs::{DocExample, DOCS_EXAMPLE_PROTOCOL_VERSION},
-    Error, ErrorCode, ReactorEventT, RpcWithParams,
+    Error, ErrorCode, ReactorEventT, RequestLimiter, RpcWithParams,
 };
 use crate::{
-    components::contract_runtime::SpeculativeExecutionState,
+    components::contract_runtime::{
+        SpeculativeExecutionResult, SpeculativeExecutionResults, SpeculativeExecutionState,
+    },
     effect::EffectBuilder,
-    types::{Block, BlockHash, Deploy},
+    types::{json_compatibility::CallTrace, Block, BlockHash, Deploy, DeployHash},
 };
 
 static SPECULATIVE_EXEC_PARAMS: Lazy<SpeculativeExecParams> = Lazy::new(|| SpeculativeExecParams {
     block_identifier: Some(BlockIdentifier::Hash(*Block::doc_example().hash())),
     deploy: Deploy::doc_example().clone(),
+    trace: false,
+    estimate_gas: true,
 });
 static SPECULATIVE_EXEC_RESULT: Lazy<SpeculativeExecResult> = Lazy::new(|| SpeculativeExecResult {
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     block_hash: *Block::doc_example().hash(),
     execution_result: ExecutionResult::example().clone(),
+    call_trace: None,
+    gas_estimate: Some(JsonGasEstimate {
+        payment_gas: U512::zero(),
+        session_gas: U512::from(123_456),
+        gas_price: 1,
+        recommended_payment: U512::from(123_456),
+        error_message: None,
+    }),
 });
+static SPECULATIVE_EXEC_MANY_PARAMS: Lazy<SpeculativeExecManyParams> =
+    Lazy::new(|| SpeculativeExecManyParams {
+        block_identifier: Some(BlockIdentifier::Hash(*Block::doc_example().hash())),
+        deploys: vec![Deploy::doc_example().clone()],
+    });
+static SPECULATIVE_EXEC_MANY_RESULT: Lazy<SpeculativeExecManyResult> =
+    Lazy::new(|| SpeculativeExecManyResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        block_hash: *Block::doc_example().hash(),
+        execution_results: vec![JsonSpeculativeExecutionResult {
+            deploy_hash: *Deploy::doc_example().hash(),
+            execution_result: ExecutionResult::example().clone(),
+        }],
+        post_state_hash: *Block::doc_example().state_root_hash(),
+    });
 
 /// Params for "speculative_exec" RPC request.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
@@ -44,6 +72,13 @@ pub struct SpeculativeExecParams {
     pub block_identifier: Option<BlockIdentifier>,
     /// Deploy to execute.
     pub deploy: Deploy,
+    /// Whether to record the call frames entered while executing the deploy.
+    #[serde(default)]
+    pub trace: bool,
+    /// Whether to also estimate the gas consumed by the deploy when executed with a payment
+    /// covering up to the block gas limit.
+    #[serde(default)]
+    pub estimate_gas: bool,
 }
 
 impl DocExample for SpeculativeExecParams {
@@ -63,6 +98,47 @@ pub struct SpeculativeExecResult {
     pub block_hash: BlockHash,
     /// Result of the execution.
     pub execution_result: ExecutionResult,
+    /// The call frames entered during execution, if tracing was requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub call_trace: Option<CallTrace>,
+    /// The gas consumed when executed with a payment covering up to the block gas limit, if
+    /// estimation was requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_estimate: Option<JsonGasEstimate>,
+}
+
+/// The gas consumed by a deploy executed with a payment covering up to the block gas limit.
+///
+/// Deploys using standard payment are executed with a payment buying the block gas limit, and fail
+/// as exceeding it if they run out of gas, while deploys using custom payment code are executed as
+/// given.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JsonGasEstimate {
+    /// The gas consumed by the payment code.
+    pub payment_gas: U512,
+    /// The gas consumed by the session code, including the costs of any system contract entry
+    /// points it called.
+    pub session_gas: U512,
+    /// The gas price the deploy is charged at.
+    pub gas_price: u64,
+    /// The smallest payment amount in motes which covers the gas consumed by the deploy.
+    pub recommended_payment: U512,
+    /// The error the deploy failed with, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_message: Option<String>,
+}
+
+impl From<GasEstimate> for JsonGasEstimate {
+    fn from(gas_estimate: GasEstimate) -> Self {
+        JsonGasEstimate {
+            payment_gas: gas_estimate.payment_gas().value(),
+            session_gas: gas_estimate.session_gas().value(),
+            gas_price: gas_estimate.gas_price(),
+            recommended_payment: gas_estimate.recommended_payment().value(),
+            error_message: gas_estimate.error().map(ToString::to_string),
+        }
+    }
 }
 
 impl DocExample for SpeculativeExecResult {
@@ -88,6 +164,8 @@ impl RpcWithParams for SpeculativeExec {
         let SpeculativeExecParams {
             block_identifier: maybe_block_id,
             deploy,
+            trace,
+            estimate_gas,
         } = params;
         let deploy = Arc::new(deploy);
         let only_from_available_block_range = true;
@@ -114,15 +192,26 @@ impl RpcWithParams for SpeculativeExec {
         }
 
         let result = effect_builder
-            .speculative_execute_deploy(execution_prestate, Arc::clone(&deploy))
+            .speculative_execute_deploy(
+                execution_prestate,
+                Arc::clone(&deploy),
+                trace,
+                estimate_gas,
+            )
             .await;
 
         match result {
-            Ok(Some(execution_result)) => {
+            Ok(Some(SpeculativeExecutionResult {
+                execution_result,
+                call_trace,
+                gas_estimate,
+            })) => {
                 let result = Self::ResponseResult {
                     api_version,
                     block_hash,
                     execution_result,
+                    call_trace: call_trace.map(CallTrace::from),
+                    gas_estimate: gas_estimate.map(JsonGasEstimate::from),
                 };
                 Ok(result)
             }
@@ -130,50 +219,225 @@ impl RpcWithParams for SpeculativeExec {
                 ErrorCode::NoSuchBlock,
                 "block hash not found".to_string(),
             )),
-            Err(error) => {
-                let rpc_error = match error {
-                    EngineStateError::RootNotFound(_) => Error::new(ErrorCode::NoSuchStateRoot, ""),
-                    EngineStateError::WasmPreprocessing(error) => {
-                        Error::new(ErrorCode::InvalidDeploy, error.to_string())
-                    }
-                    EngineStateError::InvalidDeployItemVariant(error) => {
-                        Error::new(ErrorCode::InvalidDeploy, error)
-                    }
-                    EngineStateError::InvalidProtocolVersion(_) => Error::new(
-                        ErrorCode::InvalidDeploy,
-                        format!("deploy used invalid protocol version {}", error),
-                    ),
-                    EngineStateError::Deploy => Error::new(ErrorCode::InvalidDeploy, ""),
-                    EngineStateError::Genesis(_)
-                    | EngineStateError::WasmSerialization(_)
-                    | EngineStateError::Exec(_)
-                    | EngineStateError::Storage(_)
-                    | EngineStateError::Authorization
-                    | EngineStateError::InsufficientPayment
-                    | EngineStateError::GasConversionOverflow
-                    | EngineStateError::Finalization
-                    | EngineStateError::Bytesrepr(_)
-                    | EngineStateError::Mint(_)
-                    | EngineStateError::InvalidKeyVariant
-                    | EngineStateError::ProtocolUpgrade(_)
-                    | EngineStateError::CommitError(_)
-                    | EngineStateError::MissingSystemContractRegistry
-                    | EngineStateError::MissingSystemContractHash(_)
-                    | EngineStateError::RuntimeStackOverflow
-                    | EngineStateError::FailedToGetWithdrawKeys
-                    | EngineStateError::FailedToGetStoredWithdraws
-                    | EngineStateError::FailedToGetWithdrawPurses
-                    | EngineStateError::FailedToRetrieveUnbondingDelay
-                    | EngineStateError::FailedToRetrieveEraId => {
-                        Error::new(ReservedErrorCode::InternalError, error.to_string())
-                    }
-                    _ => Error::new(
-                        ReservedErrorCode::InternalError,
-                        format!("Unhandled engine state error: {}", error),
-                    ),
+            Err(error) => Err(engine_state_error_to_rpc_error(error)),
+        }
+    }
+}
+
+/// Params for "speculative_exec_many" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SpeculativeExecManyParams {
+    /// Block hash on top of which to execute the deploys.
+    pub block_identifier: Option<BlockIdentifier>,
+    /// Deploys to execute, in order.  Each deploy is executed against the state resulting from
+    /// the deploys before it.
+    pub deploys: Vec<Deploy>,
+}
+
+impl DocExample for SpeculativeExecManyParams {
+    fn doc_example() -> &'static Self {
+        &SPECULATIVE_EXEC_MANY_PARAMS
+    }
+}
+
+/// The result of speculatively executing one of a sequence of deploys.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JsonSpeculativeExecutionResult {
+    /// The deploy hash.
+    pub deploy_hash: DeployHash,
+    /// Result of the execution.
+    pub execution_result: ExecutionResult,
+}
+
+/// Result for "speculative_exec_many" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SpeculativeExecManyResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// Hash of the block on top of which the deploys were executed.
+    pub block_hash: BlockHash,
+    /// Results of the executions, in execution order.
+    pub execution_results: Vec<JsonSpeculativeExecutionResult>,
+    /// The state root hash which would result from committing the effects of all the deploys.
+    pub post_state_hash: Digest,
+}
+
+impl DocExample for SpeculativeExecManyResult {
+    fn doc_example() -> &'static Self {
+        &SPECULATIVE_EXEC_MANY_RESULT
+    }
+}
+
+/// "speculative_exec_many" RPC
+pub struct SpeculativeExecMany {}
+
+impl SpeculativeExecMany {
+    /// Registers this RPC as the handler for "speculative_exec_many" requests, rejecting any
+    /// request with more than `max_deploys` deploys.
+    pub(in crate::components::rpc_server) fn register_as_handler_with_max_deploys<
+        REv: ReactorEventT,
+    >(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        max_deploys: u32,
+        limiter: &RequestLimiter,
+        handlers_builder: &mut RequestHandlersBuilder,
+    ) {
+        let limiter = limiter.clone();
+        let handler = move |maybe_params| {
+            let limiter = limiter.clone();
+            async move {
+                limiter.acquire().await;
+                let params = Self::try_parse_params(maybe_params)?;
+                if params.deploys.len() > max_deploys as usize {
+                    return Err(Error::new(
+                        ReservedErrorCode::InvalidParams,
+                        format!(
+                            "too many deploys: got {}, but the maximum is {}",
+                            params.deploys.len(),
+                            max_deploys
+                        ),
+                    ));
+                }
+                Self::do_handle_request(effect_builder, api_version, params).await
+            }
+        };
+        handlers_builder.register_handler(Self::METHOD, Arc::new(handler))
+    }
+}
+
+#[async_trait]
+impl RpcWithParams for SpeculativeExecMany {
+    const METHOD: &'static str = "speculative_exec_many";
+    type RequestParams = SpeculativeExecManyParams;
+    type ResponseResult = SpeculativeExecManyResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let SpeculativeExecManyParams {
+            block_identifier: maybe_block_id,
+            deploys,
+        } = params;
+        if deploys.is_empty() {
+            return Err(Error::new(
+                ErrorCode::InvalidDeploy,
+                "no deploys provided".to_string(),
+            ));
+        }
+        let deploys: Vec<_> = deploys.into_iter().map(Arc::new).collect();
+        let only_from_available_block_range = true;
+
+        let block = common::get_block(
+            maybe_block_id,
+            only_from_available_block_range,
+            effect_builder,
+        )
+        .await?;
+        let block_hash = *block.hash();
+        let execution_prestate = SpeculativeExecutionState {
+            state_root_hash: *block.state_root_hash(),
+            block_time: block.timestamp(),
+            protocol_version: block.protocol_version(),
+        };
+
+        // Each deploy is validated against the block's state, not the speculative state resulting
+        // from the deploys before it.
+        let block_header = block.take_header();
+        for deploy in &deploys {
+            let accept_deploy_result = effect_builder
+                .try_accept_deploy(Arc::clone(deploy), Some(Box::new(block_header.clone())))
+                .await;
+            if let Err(error) = accept_deploy_result {
+                return Err(Error::new(
+                    ErrorCode::InvalidDeploy,
+                    format!("deploy {}: {}", deploy.hash(), error),
+                ));
+            }
+        }
+
+        let result = effect_builder
+            .speculative_execute_deploys(execution_prestate, deploys)
+            .await;
+
+        match result {
+            Ok(Some(SpeculativeExecutionResults {
+                execution_results,
+                post_state_hash,
+            })) => {
+                let execution_results = execution_results
+                    .into_iter()
+                    .map(
+                        |(deploy_hash, execution_result)| JsonSpeculativeExecutionResult {
+                            deploy_hash,
+                            execution_result,
+                        },
+                    )
+                    .collect();
+                let result = Self::ResponseResult {
+                    api_version,
+                    block_hash,
+                    execution_results,
+                    post_state_hash,
                 };
-                Err(rpc_error)
+                Ok(result)
             }
+            Ok(None) => Err(Error::new(
+                ReservedErrorCode::InternalError,
+                "speculative execution did not produce exactly one result per deploy".to_string(),
+            )),
+            Err(error) => Err(engine_state_error_to_rpc_error(error)),
+        }
+    }
+}
+
+/// Converts an error from speculative execution into an RPC error.
+fn engine_state_error_to_rpc_error(error: EngineStateError) -> Error {
+    match error {
+        EngineStateError::RootNotFound(_) => Error::new(ErrorCode::NoSuchStateRoot, ""),
+        EngineStateError::WasmPreprocessing(error) => {
+            Error::new(ErrorCode::InvalidDeploy, error.to_string())
+        }
+        EngineStateError::InvalidDeployItemVariant(error) => {
+            Error::new(ErrorCode::InvalidDeploy, error)
+        }
+        EngineStateError::InvalidProtocolVersion(_) => Error::new(
+            ErrorCode::InvalidDeploy,
+            format!("deploy used invalid protocol version {}", error),
+        ),
+        EngineStateError::Deploy => Error::new(ErrorCode::InvalidDeploy, ""),
+        EngineStateError::Genesis(_)
+        | EngineStateError::WasmSerialization(_)
+        | EngineStateError::Exec(_)
+        | EngineStateError::Storage(_)
+        | EngineStateError::Authorization
+        | EngineStateError::InsufficientPayment
+        | EngineStateError::GasConversionOverflow
+        | EngineStateError::Finalization
+        | EngineStateError::Bytesrepr(_)
+        | EngineStateError::Mint(_)
+        | EngineStateError::InvalidKeyVariant
+        | EngineStateError::ProtocolUpgrade(_)
+        | EngineStateError::CommitError(_)
+        | EngineStateError::MissingSystemContractRegistry
+        | EngineStateError::MissingSystemContractHash(_)
+        | EngineStateError::RuntimeStackOverflow
+        | EngineStateError::FailedToGetWithdrawKeys
+        | EngineStateError::FailedToGetStoredWithdraws
+        | EngineStateError::FailedToGetWithdrawPurses
+        | EngineStateError::FailedToRetrieveUnbondingDelay
+        | EngineStateError::FailedToRetrieveEraId => {
+            Error::new(ReservedErrorCode::InternalError, error.to_string())
         }
+        _ => Error::new(
+            ReservedErrorCode::InternalError,
+            format!("Unhandled engine state error: {}", error),
+        ),
     }
 }
@@ -3,7 +3,7 @@
 // TODO - remove once schemars stops causing warning.
 #![allow(clippy::field_reassign_with_default)]
 
-use std::str;
+use std::{ops::Bound, str};
 
 use async_trait::async_trait;
 use once_cell::sync::Lazy;
@@ -19,9 +19,9 @@ use casper_hashing::Digest;
 use casper_json_rpc::ReservedErrorCode;
 use casper_types::{
     account::AccountHash,
-    bytesrepr::{Bytes, ToBytes},
-    CLValue, Key, ProtocolVersion, PublicKey, SecretKey, StoredValue as DomainStoredValue, URef,
-    U512,
+    bytesrepr::{Bytes, FromBytes, ToBytes},
+    AccessRights, CLValue, Key, KeyTag as DomainKeyTag, NamedKey, ProtocolVersion, PublicKey,
+    SecretKey, StoredValue as DomainStoredValue, Tagged, URef, U512,
 };
 
 use crate::{
@@ -129,6 +129,60 @@ static QUERY_BALANCE_RESULT: Lazy<QueryBalanceResult> = Lazy::new(|| QueryBalanc
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     balance: U512::from(123_456),
 });
+static GET_NAMED_KEYS_PARAMS: Lazy<GetNamedKeysParams> = Lazy::new(|| GetNamedKeysParams {
+    state_identifier: Some(GlobalStateIdentifier::BlockHash(
+        *Block::doc_example().hash(),
+    )),
+    key: "account-hash-0909090909090909090909090909090909090909090909090909090909090909"
+        .to_string(),
+    page_token: None,
+    page_size: Some(MAX_STATE_PAGE_SIZE),
+});
+static GET_NAMED_KEYS_RESULT: Lazy<GetNamedKeysResult> = Lazy::new(|| GetNamedKeysResult {
+    api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+    named_keys: vec![NamedKey {
+        name: "main_purse".to_string(),
+        key: "uref-09480c3248ef76b603d386f3f4f8a5f87f597d4eaffd475433f861af187ab5db-007"
+            .to_string(),
+    }],
+    next_page_token: None,
+});
+static GET_DICTIONARY_ITEMS_PARAMS: Lazy<GetDictionaryItemsParams> =
+    Lazy::new(|| GetDictionaryItemsParams {
+        state_identifier: Some(GlobalStateIdentifier::BlockHash(
+            *Block::doc_example().hash(),
+        )),
+        seed_uref: URef::new([9; 32], AccessRights::READ_ADD_WRITE),
+        page_token: None,
+        page_size: Some(MAX_STATE_PAGE_SIZE),
+    });
+static GET_DICTIONARY_ITEMS_RESULT: Lazy<GetDictionaryItemsResult> =
+    Lazy::new(|| GetDictionaryItemsResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        items: vec![JsonDictionaryItem {
+            dictionary_key:
+                "dictionary-67518854aa916c97d4e53df8570c8217ccc259da2721b692102d76acd0ee8d1f"
+                    .to_string(),
+            dictionary_item_key: "a_unique_entry_identifier".to_string(),
+            value: CLValue::from_t(1u64).unwrap(),
+        }],
+        next_page_token: None,
+    });
+static GET_KEYS_PARAMS: Lazy<GetKeysParams> = Lazy::new(|| GetKeysParams {
+    state_identifier: Some(GlobalStateIdentifier::BlockHash(
+        *Block::doc_example().hash(),
+    )),
+    key_tag: KeyTag::Account,
+    page_token: None,
+    page_size: Some(MAX_STATE_PAGE_SIZE),
+});
+static GET_KEYS_RESULT: Lazy<GetKeysResult> = Lazy::new(|| GetKeysResult {
+    api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+    keys: vec![
+        "account-hash-0909090909090909090909090909090909090909090909090909090909090909".to_string(),
+    ],
+    next_page_token: None,
+});
 
 /// Params for "state_get_item" RPC request.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
@@ -1098,6 +1152,469 @@ impl RpcWithParams for GetTrie {
     }
 }
 
+/// The maximum number of entries returned in a single page by the global state enumeration RPCs.
+pub const MAX_STATE_PAGE_SIZE: u32 = 100;
+
+/// Params for "state_get_named_keys" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetNamedKeysParams {
+    /// The identifier of the state to query.  If none is passed the tip of the chain will be used.
+    pub state_identifier: Option<GlobalStateIdentifier>,
+    /// The formatted key of the account or contract whose named keys are requested.
+    pub key: String,
+    /// The `next_page_token` returned by a previous request, or omitted to request the first page.
+    pub page_token: Option<String>,
+    /// The maximum number of named keys to return, capped at 100.  Defaults to 100 if omitted.
+    pub page_size: Option<u32>,
+}
+
+impl DocExample for GetNamedKeysParams {
+    fn doc_example() -> &'static Self {
+        &GET_NAMED_KEYS_PARAMS
+    }
+}
+
+/// Result for "state_get_named_keys" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetNamedKeysResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The named keys, in ascending order of name.
+    pub named_keys: Vec<NamedKey>,
+    /// The token with which to request the next page, if there are further named keys.
+    pub next_page_token: Option<String>,
+}
+
+impl DocExample for GetNamedKeysResult {
+    fn doc_example() -> &'static Self {
+        &GET_NAMED_KEYS_RESULT
+    }
+}
+
+/// "state_get_named_keys" RPC.
+pub struct GetNamedKeys {}
+
+#[async_trait]
+impl RpcWithParams for GetNamedKeys {
+    const METHOD: &'static str = "state_get_named_keys";
+    type RequestParams = GetNamedKeysParams;
+    type ResponseResult = GetNamedKeysResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let state_root_hash = get_state_root_hash(effect_builder, params.state_identifier).await?;
+        let after = parse_page_token(params.page_token.as_deref(), |bytes| {
+            String::from_utf8(bytes).ok()
+        })?;
+        let limit = page_limit(params.page_size);
+
+        let base_key = Key::from_formatted_str(&params.key).map_err(|error| {
+            let error_msg = format!("failed to parse key: {}", error);
+            info!("{}", error_msg);
+            Error::new(ErrorCode::FailedToParseQueryKey, error_msg)
+        })?;
+
+        let (stored_value, _) =
+            run_query(effect_builder, state_root_hash, base_key, vec![]).await?;
+        let all_named_keys = match &stored_value {
+            DomainStoredValue::Account(account) => account.named_keys(),
+            DomainStoredValue::Contract(contract) => contract.named_keys(),
+            other => {
+                return Err(Error::new(
+                    ErrorCode::QueryFailed,
+                    format!(
+                        "expected account or contract, but got {}",
+                        other.type_name()
+                    ),
+                ))
+            }
+        };
+
+        let lower_bound = match &after {
+            Some(name) => Bound::Excluded(name),
+            None => Bound::Unbounded,
+        };
+        let mut remaining = all_named_keys.range::<String, _>((lower_bound, Bound::Unbounded));
+        let named_keys: Vec<NamedKey> = remaining
+            .by_ref()
+            .take(limit)
+            .map(|(name, key)| NamedKey {
+                name: name.clone(),
+                key: key.to_formatted_string(),
+            })
+            .collect();
+        let next_page_token = match remaining.next() {
+            Some(_) => named_keys
+                .last()
+                .map(|named_key| base16::encode_lower(named_key.name.as_bytes())),
+            None => None,
+        };
+
+        let result = Self::ResponseResult {
+            api_version,
+            named_keys,
+            next_page_token,
+        };
+        Ok(result)
+    }
+}
+
+/// Params for "state_get_dictionary_items" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetDictionaryItemsParams {
+    /// The identifier of the state to query.  If none is passed the tip of the chain will be used.
+    pub state_identifier: Option<GlobalStateIdentifier>,
+    /// The seed URef of the dictionary whose items are requested.
+    pub seed_uref: URef,
+    /// The `next_page_token` returned by a previous request, or omitted to request the first page.
+    pub page_token: Option<String>,
+    /// The maximum number of items to return, capped at 100.  Defaults to 100 if omitted.
+    pub page_size: Option<u32>,
+}
+
+impl DocExample for GetDictionaryItemsParams {
+    fn doc_example() -> &'static Self {
+        &GET_DICTIONARY_ITEMS_PARAMS
+    }
+}
+
+/// An item of a dictionary.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JsonDictionaryItem {
+    /// The key under which the item is stored.
+    pub dictionary_key: String,
+    /// The dictionary item key with which the item was written.
+    pub dictionary_item_key: String,
+    /// The value of the item.
+    pub value: CLValue,
+}
+
+/// Result for "state_get_dictionary_items" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetDictionaryItemsResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The items of the dictionary, in ascending order of their keys.
+    pub items: Vec<JsonDictionaryItem>,
+    /// The token with which to request the next page, if there may be further items.  As only a
+    /// bounded number of global state entries are examined per request, a page may hold fewer
+    /// items than requested, or none, while further items remain.
+    pub next_page_token: Option<String>,
+}
+
+impl DocExample for GetDictionaryItemsResult {
+    fn doc_example() -> &'static Self {
+        &GET_DICTIONARY_ITEMS_RESULT
+    }
+}
+
+/// "state_get_dictionary_items" RPC.
+pub struct GetDictionaryItems {}
+
+#[async_trait]
+impl RpcWithParams for GetDictionaryItems {
+    const METHOD: &'static str = "state_get_dictionary_items";
+    type RequestParams = GetDictionaryItemsParams;
+    type ResponseResult = GetDictionaryItemsResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let state_root_hash = get_state_root_hash(effect_builder, params.state_identifier).await?;
+        let after = parse_page_token(params.page_token.as_deref(), |bytes| {
+            Key::from_bytes(&bytes)
+                .ok()
+                .map(|(key, _)| key)
+                .filter(Key::is_dictionary_key)
+        })?;
+        let limit = page_limit(params.page_size);
+
+        let get_dictionary_items_request = engine_state::GetDictionaryItemsRequest::new(
+            state_root_hash,
+            params.seed_uref,
+            after,
+            limit,
+        );
+        let get_dictionary_items_result = effect_builder
+            .make_request(
+                |responder| RpcRequest::GetDictionaryItems {
+                    get_dictionary_items_request,
+                    responder,
+                },
+                QueueKind::Api,
+            )
+            .await;
+
+        let (items, next) = match get_dictionary_items_result {
+            Ok(engine_state::GetDictionaryItemsResult::Success { items, next }) => (items, next),
+            Ok(engine_state::GetDictionaryItemsResult::RootNotFound) => {
+                return Err(state_root_not_found_error(effect_builder, state_root_hash).await)
+            }
+            Err(error) => {
+                info!(?error, "get dictionary items failed to execute");
+                return Err(Error::new(
+                    ErrorCode::QueryFailedToExecute,
+                    format!("{:?}", error),
+                ));
+            }
+        };
+
+        let items = items
+            .into_iter()
+            .map(|item| JsonDictionaryItem {
+                dictionary_key: item.key.to_formatted_string(),
+                dictionary_item_key: String::from_utf8_lossy(&item.dictionary_item_key)
+                    .into_owned(),
+                value: item.value,
+            })
+            .collect();
+
+        let result = Self::ResponseResult {
+            api_version,
+            items,
+            next_page_token: next.map(key_to_page_token),
+        };
+        Ok(result)
+    }
+}
+
+/// The type of a global state key.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum KeyTag {
+    /// Account keys.
+    Account,
+    /// Hash keys, under which contracts, contract packages and contract Wasm are stored.
+    Hash,
+    /// URef keys.
+    URef,
+    /// Transfer keys.
+    Transfer,
+    /// Deploy info keys.
+    DeployInfo,
+    /// Era info keys.
+    EraInfo,
+    /// Purse balance keys.
+    Balance,
+    /// Bid keys.
+    Bid,
+    /// Withdraw keys.
+    Withdraw,
+    /// Dictionary item keys.
+    Dictionary,
+    /// The system contract registry key.
+    SystemContractRegistry,
+    /// The era summary key.
+    EraSummary,
+    /// Unbond keys.
+    Unbond,
+    /// The chainspec registry key.
+    ChainspecRegistry,
+    /// The checksum registry key.
+    ChecksumRegistry,
+}
+
+impl From<KeyTag> for DomainKeyTag {
+    fn from(key_tag: KeyTag) -> Self {
+        match key_tag {
+            KeyTag::Account => DomainKeyTag::Account,
+            KeyTag::Hash => DomainKeyTag::Hash,
+            KeyTag::URef => DomainKeyTag::URef,
+            KeyTag::Transfer => DomainKeyTag::Transfer,
+            KeyTag::DeployInfo => DomainKeyTag::DeployInfo,
+            KeyTag::EraInfo => DomainKeyTag::EraInfo,
+            KeyTag::Balance => DomainKeyTag::Balance,
+            KeyTag::Bid => DomainKeyTag::Bid,
+            KeyTag::Withdraw => DomainKeyTag::Withdraw,
+            KeyTag::Dictionary => DomainKeyTag::Dictionary,
+            KeyTag::SystemContractRegistry => DomainKeyTag::SystemContractRegistry,
+            KeyTag::EraSummary => DomainKeyTag::EraSummary,
+            KeyTag::Unbond => DomainKeyTag::Unbond,
+            KeyTag::ChainspecRegistry => DomainKeyTag::ChainspecRegistry,
+            KeyTag::ChecksumRegistry => DomainKeyTag::ChecksumRegistry,
+        }
+    }
+}
+
+/// Params for "state_get_keys" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetKeysParams {
+    /// The identifier of the state to query.  If none is passed the tip of the chain will be used.
+    pub state_identifier: Option<GlobalStateIdentifier>,
+    /// The type of the keys requested.
+    pub key_tag: KeyTag,
+    /// The `next_page_token` returned by a previous request, or omitted to request the first page.
+    pub page_token: Option<String>,
+    /// The maximum number of keys to return, capped at 100.  Defaults to 100 if omitted.
+    pub page_size: Option<u32>,
+}
+
+impl DocExample for GetKeysParams {
+    fn doc_example() -> &'static Self {
+        &GET_KEYS_PARAMS
+    }
+}
+
+/// Result for "state_get_keys" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetKeysResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The formatted keys, in ascending order of their serialized form.
+    pub keys: Vec<String>,
+    /// The token with which to request the next page, if there are further keys.
+    pub next_page_token: Option<String>,
+}
+
+impl DocExample for GetKeysResult {
+    fn doc_example() -> &'static Self {
+        &GET_KEYS_RESULT
+    }
+}
+
+/// "state_get_keys" RPC.
+pub struct GetKeys {}
+
+#[async_trait]
+impl RpcWithParams for GetKeys {
+    const METHOD: &'static str = "state_get_keys";
+    type RequestParams = GetKeysParams;
+    type ResponseResult = GetKeysResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let state_root_hash = get_state_root_hash(effect_builder, params.state_identifier).await?;
+        let key_tag = DomainKeyTag::from(params.key_tag);
+        let after = parse_page_token(params.page_token.as_deref(), |bytes| {
+            Key::from_bytes(&bytes)
+                .ok()
+                .map(|(key, _)| key)
+                .filter(|key| Tagged::<DomainKeyTag>::tag(key) == key_tag)
+        })?;
+        let limit = page_limit(params.page_size);
+
+        let get_keys_request =
+            engine_state::GetKeysRequest::new(state_root_hash, key_tag, after, limit);
+        let get_keys_result = effect_builder
+            .make_request(
+                |responder| RpcRequest::GetKeys {
+                    get_keys_request,
+                    responder,
+                },
+                QueueKind::Api,
+            )
+            .await;
+
+        let (keys, next) = match get_keys_result {
+            Ok(engine_state::GetKeysResult::Success { keys, next }) => (keys, next),
+            Ok(engine_state::GetKeysResult::RootNotFound) => {
+                return Err(state_root_not_found_error(effect_builder, state_root_hash).await)
+            }
+            Err(error) => {
+                info!(?error, "get keys failed to execute");
+                return Err(Error::new(
+                    ErrorCode::QueryFailedToExecute,
+                    format!("{:?}", error),
+                ));
+            }
+        };
+
+        let result = Self::ResponseResult {
+            api_version,
+            keys: keys.into_iter().map(Key::to_formatted_string).collect(),
+            next_page_token: next.map(key_to_page_token),
+        };
+        Ok(result)
+    }
+}
+
+/// Parses an optional page token, returning an error if it is invalid.
+pub(super) fn parse_page_token<T>(
+    page_token: Option<&str>,
+    parse: impl FnOnce(Vec<u8>) -> Option<T>,
+) -> Result<Option<T>, Error> {
+    let page_token = match page_token {
+        Some(page_token) => page_token,
+        None => return Ok(None),
+    };
+    match base16::decode(page_token).ok().and_then(parse) {
+        Some(after) => Ok(Some(after)),
+        None => {
+            let message = format!("failed to parse page token '{}'", page_token);
+            Err(Error::new(ErrorCode::InvalidPageToken, message))
+        }
+    }
+}
+
+/// Returns the page token with which to request the page of keys following `key`.
+pub(super) fn key_to_page_token(key: Key) -> String {
+    // Serializing a `Key` cannot fail.
+    base16::encode_lower(&key.to_bytes().unwrap_or_default())
+}
+
+/// Returns the number of entries to return in a page given the requested page size.
+pub(super) fn page_limit(page_size: Option<u32>) -> usize {
+    page_size
+        .unwrap_or(MAX_STATE_PAGE_SIZE)
+        .clamp(1, MAX_STATE_PAGE_SIZE) as usize
+}
+
+/// Returns the state root hash identified by `state_identifier`, or that of the highest complete
+/// block if `None`.
+async fn get_state_root_hash<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    state_identifier: Option<GlobalStateIdentifier>,
+) -> Result<Digest, Error> {
+    match state_identifier {
+        None => match effect_builder
+            .get_highest_complete_block_header_from_storage()
+            .await
+        {
+            None => Err(Error::new(
+                ErrorCode::NoSuchBlock,
+                "failed to retrieve highest block header",
+            )),
+            Some(block_header) => Ok(*block_header.state_root_hash()),
+        },
+        Some(state_identifier) => {
+            let (state_root_hash, _) =
+                get_state_root_hash_and_optional_header(effect_builder, state_identifier).await?;
+            Ok(state_root_hash)
+        }
+    }
+}
+
+async fn state_root_not_found_error<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    state_root_hash: Digest,
+) -> Error {
+    info!("query failed: root not found");
+    common::missing_block_or_state_root_error(
+        effect_builder,
+        ErrorCode::NoSuchStateRoot,
+        format!("failed to get state root at {:?}", state_root_hash),
+    )
+    .await
+}
+
 type QuerySuccess = (
     DomainStoredValue,
     Vec<TrieMerkleProof<Key, DomainStoredValue>>,
@@ -9,6 +9,10 @@ const DEFAULT_ADDRESS: &str = "0.0.0.0:1";
 const DEFAULT_QPS_LIMIT: u64 = 1;
 /// Default max body bytes (2.5MB).
 const DEFAULT_MAX_BODY_BYTES: u32 = 2_621_440;
+/// Default max batch size.
+const DEFAULT_MAX_BATCH_SIZE: u32 = 1;
+/// Default max number of deploys in a single "speculative_exec_many" request.
+const DEFAULT_MAX_DEPLOYS: u32 = 10;
 /// Default CORS origin.
 const DEFAULT_CORS_ORIGIN: &str = "";
 
@@ -25,6 +29,12 @@ pub struct Config {
     pub qps_limit: u64,
     /// Maximum number of bytes to accept in a single request body.
     pub max_body_bytes: u32,
+    /// Maximum number of requests to accept in a single JSON-RPC batch request.
+    #[serde(default = "default_max_batch_size")]
+    pub max_batch_size: u32,
+    /// Maximum number of deploys to accept in a single "speculative_exec_many" request.
+    #[serde(default = "default_max_deploys")]
+    pub max_deploys: u32,
     /// CORS origin.
     pub cors_origin: String,
 }
@@ -37,11 +47,23 @@ impl Config {
             address: DEFAULT_ADDRESS.to_string(),
             qps_limit: DEFAULT_QPS_LIMIT,
             max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
+            max_deploys: DEFAULT_MAX_DEPLOYS,
             cors_origin: DEFAULT_CORS_ORIGIN.to_string(),
         }
     }
 }
 
+/// Returns the default max batch size, used if it is absent from an older config file.
+fn default_max_batch_size() -> u32 {
+    DEFAULT_MAX_BATCH_SIZE
+}
+
+/// Returns the default max number of deploys, used if it is absent from an older config file.
+fn default_max_deploys() -> u32 {
+    DEFAULT_MAX_DEPLOYS
+}
+
 impl Default for Config {
     fn default() -> Self {
         Config::new()
@@ -6,7 +6,10 @@ use casper_types::ProtocolVersion;
 use super::ReactorEventT;
 use crate::{
     effect::EffectBuilder,
-    rpcs::{speculative_exec::SpeculativeExec, RpcWithParams},
+    rpcs::{
+        speculative_exec::{SpeculativeExec, SpeculativeExecMany},
+        RequestLimiter, RpcWithParams,
+    },
 };
 
 /// The URL path for all JSON-RPC requests.
@@ -15,16 +18,27 @@ pub const SPECULATIVE_EXEC_API_PATH: &str = "rpc";
 pub const SPECULATIVE_EXEC_SERVER_NAME: &str = "speculative execution";
 
 /// Run the speculative execution server.
+#[allow(clippy::too_many_arguments)]
 pub(super) async fn run<REv: ReactorEventT>(
     builder: Builder<AddrIncoming>,
     effect_builder: EffectBuilder<REv>,
     api_version: ProtocolVersion,
     qps_limit: u64,
     max_body_bytes: u32,
+    max_batch_size: u32,
+    max_deploys: u32,
     cors_origin: String,
 ) {
+    let limiter = RequestLimiter::new(qps_limit);
     let mut handlers = RequestHandlersBuilder::new();
-    SpeculativeExec::register_as_handler(effect_builder, api_version, &mut handlers);
+    SpeculativeExec::register_as_handler(effect_builder, api_version, &limiter, &mut handlers);
+    SpeculativeExecMany::register_as_handler_with_max_deploys(
+        effect_builder,
+        api_version,
+        max_deploys,
+        &limiter,
+        &mut handlers,
+    );
     let handlers = handlers.build();
 
     match cors_origin.as_str() {
@@ -34,6 +48,7 @@ pub(super) async fn run<REv: ReactorEventT>(
                 handlers,
                 qps_limit,
                 max_body_bytes,
+                max_batch_size,
                 SPECULATIVE_EXEC_API_PATH,
                 SPECULATIVE_EXEC_SERVER_NAME,
             )
@@ -45,6 +60,7 @@ pub(super) async fn run<REv: ReactorEventT>(
                 handlers,
                 qps_limit,
                 max_body_bytes,
+                max_batch_size,
                 SPECULATIVE_EXEC_API_PATH,
                 SPECULATIVE_EXEC_SERVER_NAME,
                 CorsOrigin::Any,
@@ -57,6 +73,7 @@ pub(super) async fn run<REv: ReactorEventT>(
                 handlers,
                 qps_limit,
                 max_body_bytes,
+                max_batch_size,
                 SPECULATIVE_EXEC_API_PATH,
                 SPECULATIVE_EXEC_SERVER_NAME,
                 CorsOrigin::Specified(cors_origin),
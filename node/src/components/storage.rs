@@ -6,7 +6,8 @@
 //! * storing and loading blocks,
 //! * storing and loading deploys,
 //! * [temporary until refactored] holding `DeployMetadata` for each deploy,
-//! * keeping an index of blocks by height and
+//! * keeping an index of blocks by height,
+//! * [optional] keeping an index of deploys and transfers by account and purse and
 //! * [unimplemented] managing disk usage by pruning blocks and deploys from storage.
 //!
 //! Any I/O performed by the component is done on the event handling thread, this is on purpose as
@@ -30,6 +31,7 @@
 //! The storage component itself is panic free and in general reports three classes of errors:
 //! Corruption, temporary resource exhaustion and potential bugs.
 
+mod activity_index;
 pub(crate) mod disjoint_sequences;
 mod error;
 mod lmdb_ext;
@@ -47,7 +49,7 @@ use std::{
     fmt::{self, Display, Formatter},
     fs::{self, OpenOptions},
     io::ErrorKind,
-    mem,
+    iter, mem,
     path::{Path, PathBuf},
     rc::Rc,
     sync::Arc,
@@ -90,8 +92,8 @@ use crate::{
     fatal,
     protocol::Message,
     types::{
-        ApprovalsHash, ApprovalsHashes, AvailableBlockRange, Block, BlockAndDeploys, BlockBody,
-        BlockExecutionResultsOrChunk, BlockExecutionResultsOrChunkId, BlockHash,
+        ApprovalsHash, ApprovalsHashes, ArchivedBlock, AvailableBlockRange, Block, BlockAndDeploys,
+        BlockBody, BlockExecutionResultsOrChunk, BlockExecutionResultsOrChunkId, BlockHash,
         BlockHashAndHeight, BlockHashHeightAndEra, BlockHeader, BlockHeaderWithMetadata,
         BlockSignatures, BlockWithMetadata, Deploy, DeployHash, DeployHeader, DeployId,
         DeployMetadata, DeployMetadataExt, DeployWithFinalizedApprovals, FinalitySignature,
@@ -101,6 +103,8 @@ use crate::{
     utils::{display_error, WithDir},
     NodeRng,
 };
+use activity_index::ACTIVITY_INDEX_DB_NAME;
+pub(crate) use activity_index::{ActivityCursor, ActivityPage, ActivitySubject};
 use disjoint_sequences::{DisjointSequences, Sequence};
 pub use error::FatalStorageError;
 use error::GetRequestError;
@@ -129,9 +133,11 @@ const DEFAULT_MAX_DEPLOY_METADATA_STORE_SIZE: usize = 300 * GIB;
 /// Default max state store size.
 const DEFAULT_MAX_STATE_STORE_SIZE: usize = 10 * GIB;
 /// Maximum number of allowed dbs.
-const MAX_DB_COUNT: u32 = 9;
+const MAX_DB_COUNT: u32 = 10;
 /// Key under which completed blocks are to be stored.
 const COMPLETED_BLOCKS_STORAGE_KEY: &[u8] = b"completed_blocks_disjoint_sequences";
+/// Key under which the height below which global state has been pruned is to be stored.
+const STATE_PRUNED_BELOW_HEIGHT_STORAGE_KEY: &[u8] = b"state_pruned_below_height";
 /// Name of the file created when initializing a force resync.
 const FORCE_RESYNC_FILE_NAME: &str = "force_resync";
 
@@ -192,6 +198,9 @@ pub struct Storage {
     /// The finalized approvals database.
     #[data_size(skip)]
     finalized_approvals_db: Database,
+    /// The account and purse activity index database, if enabled.
+    #[data_size(skip)]
+    activity_index_db: Option<Database>,
     /// A map of block height to block ID.
     block_height_index: BTreeMap<u64, BlockHash>,
     /// A map of era ID to switch block ID.
@@ -200,6 +209,8 @@ pub struct Storage {
     deploy_hash_index: BTreeMap<DeployHash, BlockHashHeightAndEra>,
     /// Runs of completed blocks known in storage.
     completed_blocks: DisjointSequences,
+    /// The height below which the global state of blocks has been pruned by the contract runtime.
+    state_pruned_below_height: u64,
     /// The activation point era of the current protocol version.
     activation_era: EraId,
     /// The height of the final switch block of the previous protocol version.
@@ -417,6 +428,11 @@ impl Storage {
         let block_body_db = env.create_db(Some("block_body"), DatabaseFlags::empty())?;
         let approvals_hashes_db =
             env.create_db(Some("approvals_hashes"), DatabaseFlags::empty())?;
+        let activity_index_db = if config.enable_activity_index {
+            Some(env.create_db(Some(ACTIVITY_INDEX_DB_NAME), DatabaseFlags::empty())?)
+        } else {
+            None
+        };
 
         // We now need to restore the block-height index. Log messages allow timing here.
         info!("indexing block store");
@@ -508,10 +524,12 @@ impl Storage {
             transfer_db,
             state_store_db,
             finalized_approvals_db,
+            activity_index_db,
             block_height_index,
             switch_block_era_id_index,
             deploy_hash_index,
             completed_blocks: Default::default(),
+            state_pruned_below_height: 0,
             activation_era,
             key_block_height_for_activation_point: None,
             enable_mem_deduplication: config.enable_mem_deduplication,
@@ -521,6 +539,14 @@ impl Storage {
             metrics,
         };
 
+        if let Some(raw) =
+            component.read_state_store(&Cow::Borrowed(STATE_PRUNED_BELOW_HEIGHT_STORAGE_KEY))?
+        {
+            let (height, _) = u64::from_bytes(&raw)
+                .map_err(FatalStorageError::UnexpectedDeserializationFailure)?;
+            component.state_pruned_below_height = height;
+        }
+
         if force_resync {
             let force_resync_file_path = component.root_path().join(FORCE_RESYNC_FILE_NAME);
             // Check if resync is already in progress. Force resync will kick
@@ -1153,6 +1179,10 @@ impl Storage {
             StorageRequest::GetAvailableBlockRange { responder } => {
                 responder.respond(self.get_available_block_range()).ignore()
             }
+            StorageRequest::PutStatePrunedBelowHeight { height, responder } => {
+                self.put_state_pruned_below_height(height)?;
+                responder.respond(()).ignore()
+            }
             StorageRequest::StoreFinalizedApprovals {
                 ref deploy_hash,
                 ref finalized_approvals,
@@ -1168,6 +1198,20 @@ impl Storage {
             } => responder
                 .respond(self.put_executed_block(&block, &approvals_hashes, execution_results)?)
                 .ignore(),
+            StorageRequest::GetAccountActivity {
+                subject,
+                after,
+                limit,
+                responder,
+            } => responder
+                .respond(self.read_activity(subject, after.map(|after| *after), limit)?)
+                .ignore(),
+            StorageRequest::GetSyncLeap {
+                sync_leap_identifier,
+                responder,
+            } => responder
+                .respond(self.get_sync_leap(sync_leap_identifier)?)
+                .ignore(),
             StorageRequest::GetKeyBlockHeightForActivationPoint { responder } => {
                 // If we haven't already cached the height, try to retrieve the key block header.
                 if self.key_block_height_for_activation_point.is_none() {
@@ -1248,6 +1292,22 @@ impl Storage {
         self.write_state_store(Cow::Borrowed(COMPLETED_BLOCKS_STORAGE_KEY), &serialized)
     }
 
+    /// Persists the height below which global state has been pruned, if higher than the current
+    /// one.
+    fn put_state_pruned_below_height(&mut self, height: u64) -> Result<(), FatalStorageError> {
+        if height <= self.state_pruned_below_height {
+            return Ok(());
+        }
+        self.state_pruned_below_height = height;
+        let serialized = height
+            .to_bytes()
+            .map_err(FatalStorageError::UnexpectedSerializationFailure)?;
+        self.write_state_store(
+            Cow::Borrowed(STATE_PRUNED_BELOW_HEIGHT_STORAGE_KEY),
+            &serialized,
+        )
+    }
+
     /// Put a single deploy into storage.
     pub fn put_deploy(&self, deploy: &Deploy) -> Result<bool, FatalStorageError> {
         let mut txn = self.env.begin_rw_txn()?;
@@ -1276,12 +1336,92 @@ impl Storage {
         }
 
         let _ = self.write_approvals_hashes(&mut txn, approvals_hashes)?;
+        self.write_activity_index(&mut txn, block, &execution_results)?;
         let _ = self.write_execution_results(&mut txn, block.hash(), execution_results)?;
         txn.commit()?;
 
         Ok(true)
     }
 
+    /// Adds the deploys and successful transfers of the given block to the activity index, if
+    /// enabled.
+    fn write_activity_index(
+        &self,
+        txn: &mut RwTransaction,
+        block: &Block,
+        execution_results: &HashMap<DeployHash, ExecutionResult>,
+    ) -> Result<(), FatalStorageError> {
+        let db = match self.activity_index_db {
+            Some(db) => db,
+            None => return Ok(()),
+        };
+        let block_hash = *block.hash();
+        let block_height = block.height();
+
+        for deploy_hash in block.deploy_and_transfer_hashes() {
+            let cursor = ActivityCursor::new(block_height, *deploy_hash, 0);
+            match txn.get_value::<_, Deploy>(self.deploy_db, deploy_hash)? {
+                Some(deploy) => {
+                    let subject = ActivitySubject::AccountDeploys(
+                        deploy.header().account().to_account_hash(),
+                    );
+                    activity_index::put_entry(txn, db, subject, cursor, block_hash, None)?;
+                }
+                None => {
+                    warn!(%deploy_hash, %block_hash, "deploy missing while updating activity index");
+                }
+            }
+
+            let transfers = execution_results
+                .get(deploy_hash)
+                .map(successful_transfers)
+                .unwrap_or_default();
+            for (index, transfer) in (0..).zip(transfers) {
+                let cursor = ActivityCursor::new(block_height, *deploy_hash, index);
+                let subjects = iter::once(ActivitySubject::AccountTransfers(transfer.from))
+                    .chain(transfer.to.map(ActivitySubject::AccountTransfers))
+                    .chain(iter::once(ActivitySubject::PurseTransfers(transfer.source)))
+                    .chain(iter::once(ActivitySubject::PurseTransfers(transfer.target)));
+                for subject in subjects {
+                    activity_index::put_entry(
+                        txn,
+                        db,
+                        subject,
+                        cursor,
+                        block_hash,
+                        Some(transfer),
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads a page of the activity index for the given subject.
+    ///
+    /// Returns `None` if the activity index is not enabled.
+    fn read_activity(
+        &self,
+        subject: ActivitySubject,
+        after: Option<ActivityCursor>,
+        limit: usize,
+    ) -> Result<Option<ActivityPage>, FatalStorageError> {
+        let db = match self.activity_index_db {
+            Some(db) => db,
+            None => return Ok(None),
+        };
+        let txn = self.env.begin_ro_txn()?;
+        // Skip entries for blocks which have been removed from storage, e.g. by a hard reset.
+        let is_current = |deploy_hash: &DeployHash, block_hash: &BlockHash| {
+            self.deploy_hash_index
+                .get(deploy_hash)
+                .map_or(false, |block_info| block_info.block_hash == *block_hash)
+        };
+        let page = activity_index::read_page(&txn, db, subject, after, limit, is_current)?;
+        txn.commit()?;
+        Ok(Some(page))
+    }
+
     /// Retrieves a block by hash.
     pub fn read_block(&self, block_hash: &BlockHash) -> Result<Option<Block>, FatalStorageError> {
         self.get_single_block(&mut self.env.begin_ro_txn()?, block_hash)
@@ -1464,6 +1604,62 @@ impl Storage {
         Ok(wrote)
     }
 
+    /// Writes an archived block, which has already been verified, to storage together with its
+    /// deploys, execution results, signatures and approvals hashes.
+    ///
+    /// Signatures already stored for the block are kept.  The block is not marked as complete.
+    ///
+    /// Returns `Ok(true)` if the block has been successfully written, `Ok(false)` if a part of it
+    /// couldn't be written because it already existed, and `Err(_)` if there was an error.
+    pub(crate) fn write_archived_block(
+        &mut self,
+        archived_block: &ArchivedBlock,
+    ) -> Result<bool, FatalStorageError> {
+        let block = archived_block.block();
+        let env = Rc::clone(&self.env);
+        let mut txn = env.begin_rw_txn()?;
+        if !self.write_validated_block(&mut txn, block)? {
+            return Ok(false);
+        }
+
+        for deploy in archived_block.deploys() {
+            let deploy_hash = deploy.deploy().hash();
+            let _ = txn.put_value(self.deploy_db, deploy_hash, deploy.deploy(), false)?;
+            if let Some(finalized_approvals) = deploy.finalized_approvals() {
+                let _ = txn.put_value(
+                    self.finalized_approvals_db,
+                    deploy_hash,
+                    finalized_approvals,
+                    true,
+                )?;
+            }
+        }
+
+        let mut block_signatures = self
+            .get_block_signatures(&mut txn, block.hash())?
+            .unwrap_or_else(|| BlockSignatures::new(*block.hash(), block.header().era_id()));
+        for (public_key, signature) in &archived_block.signatures().proofs {
+            let _ = block_signatures.insert_proof(public_key.clone(), *signature);
+        }
+        let _ = txn.put_value(
+            self.block_metadata_db,
+            block.hash(),
+            &block_signatures,
+            true,
+        )?;
+
+        if let Some(approvals_hashes) = archived_block.approvals_hashes() {
+            let _ = self.write_approvals_hashes(&mut txn, approvals_hashes)?;
+        }
+
+        let execution_results: HashMap<DeployHash, ExecutionResult> =
+            archived_block.execution_results().iter().cloned().collect();
+        self.write_activity_index(&mut txn, block, &execution_results)?;
+        let _ = self.write_execution_results(&mut txn, block.hash(), execution_results)?;
+        txn.commit()?;
+        Ok(true)
+    }
+
     fn write_execution_results(
         &mut self,
         txn: &mut RwTransaction,
@@ -1722,6 +1918,53 @@ impl Storage {
         }))
     }
 
+    /// Retrieves a block by height, together with its deploys, execution results, approvals hashes
+    /// and all stored block signatures.
+    ///
+    /// Returns `None` if the block is not stored, or if any of its deploys or execution results are
+    /// missing.
+    pub(crate) fn read_archived_block(
+        &self,
+        height: u64,
+    ) -> Result<Option<ArchivedBlock>, FatalStorageError> {
+        let mut txn = self.env.begin_ro_txn()?;
+        let block = match self.get_block_by_height(&mut txn, height)? {
+            Some(block) => block,
+            None => return Ok(None),
+        };
+        let deploy_hashes = block.deploy_and_transfer_hashes().copied().collect_vec();
+        let deploys = match self
+            .get_deploys_with_finalized_approvals(&mut txn, &deploy_hashes)?
+            .into_iter()
+            .collect::<Option<Vec<_>>>()
+        {
+            Some(deploys) => deploys,
+            None => {
+                debug!(height, "not all deploys stored for block");
+                return Ok(None);
+            }
+        };
+        let execution_results = match self.get_execution_results(&mut txn, block.hash())? {
+            Some(execution_results) => execution_results,
+            None => {
+                debug!(height, "not all execution results stored for block");
+                return Ok(None);
+            }
+        };
+        let signatures = self
+            .get_block_signatures(&mut txn, block.hash())?
+            .unwrap_or_else(|| BlockSignatures::new(*block.hash(), block.header().era_id()));
+        let approvals_hashes = txn.get_value(self.approvals_hashes_db, block.hash())?;
+        txn.commit()?;
+        Ok(Some(ArchivedBlock::new(
+            block,
+            signatures,
+            approvals_hashes,
+            deploys,
+            execution_results,
+        )))
+    }
+
     /// Retrieves single block and all of its deploys, with the finalized approvals.
     /// If any of the deploys can't be found, returns `Ok(None)`.
     fn read_block_and_finalized_deploys_by_hash(
@@ -2269,6 +2512,23 @@ impl Storage {
         Ok(maybe_signatures.and_then(|signatures| signatures.get_finality_signature(public_key)))
     }
 
+    /// Returns the height of the highest block with a finality signature by `public_key` in each
+    /// era, among the `block_count` highest stored blocks.
+    pub(crate) fn read_highest_heights_signed_by(
+        &self,
+        public_key: &PublicKey,
+        block_count: usize,
+    ) -> Result<BTreeMap<EraId, u64>, FatalStorageError> {
+        let mut txn = self.env.begin_ro_txn()?;
+        let mut highest_heights = BTreeMap::new();
+        for (height, block_hash) in self.block_height_index.iter().rev().take(block_count) {
+            if let Some(signature) = self.get_block_signature(&mut txn, block_hash, public_key)? {
+                highest_heights.entry(signature.era_id).or_insert(*height);
+            }
+        }
+        Ok(highest_heights)
+    }
+
     /// Retrieves block signatures for a block with a given block hash.
     fn read_block_signatures(
         &self,
@@ -2481,13 +2741,22 @@ impl Storage {
         }
     }
 
+    /// Returns the highest contiguous range of complete blocks whose global state has not been
+    /// pruned.
     pub(crate) fn get_available_block_range(&self) -> AvailableBlockRange {
         match self.completed_blocks.highest_sequence() {
-            Some(&seq) => seq.into(),
+            Some(&seq) => {
+                AvailableBlockRange::from(seq).with_min_low(self.state_pruned_below_height)
+            }
             None => AvailableBlockRange::RANGE_0_0,
         }
     }
 
+    /// Returns the height below which global state has been pruned.
+    pub(crate) fn state_pruned_below_height(&self) -> u64 {
+        self.state_pruned_below_height
+    }
+
     pub(crate) fn get_highest_orphaned_block_header(&self) -> HighestOrphanedBlockResult {
         match self.completed_blocks.highest_sequence() {
             None => HighestOrphanedBlockResult::MissingHighestSequence,
@@ -2813,6 +3082,12 @@ pub struct Config {
     pub enable_mem_deduplication: bool,
     /// How many loads before memory duplication checks for dead references.
     pub mem_pool_prune_interval: u16,
+    /// Whether or not to maintain an index of deploys and transfers by account and purse.
+    ///
+    /// Only blocks executed by this node after enabling the index are included.  Defaults to
+    /// `false` if absent from the config file.
+    #[serde(default)]
+    pub enable_activity_index: bool,
 }
 
 impl Default for Config {
@@ -2826,6 +3101,7 @@ impl Default for Config {
             max_state_store_size: DEFAULT_MAX_STATE_STORE_SIZE,
             enable_mem_deduplication: true,
             mem_pool_prune_interval: 4096,
+            enable_activity_index: false,
         }
     }
 }
@@ -0,0 +1,241 @@
+//! An optional secondary index of account and purse activity.
+//!
+//! When enabled via [`Config::enable_activity_index`](super::Config::enable_activity_index), every
+//! block stored via `put_executed_block` is indexed so that the deploys sent by a given account,
+//! and the successful transfers to or from a given account or purse, can be listed without
+//! replaying the chain.
+//!
+//! All entries live in a single LMDB database, keyed as
+//!
+//! ```text
+//! [kind: 1 byte][account hash or purse address: 32 bytes][block height: 8 bytes, big-endian]
+//! [deploy hash: 32 bytes][transfer index: 4 bytes, big-endian]
+//! ```
+//!
+//! so that a cursor positioned at the 33 byte prefix iterates the activity of a single account or
+//! purse in ascending order of block height.
+
+use std::{
+    convert::{TryFrom, TryInto},
+    fmt::{self, Display, Formatter},
+};
+
+use datasize::DataSize;
+use lmdb::{Cursor, Database, RwTransaction, Transaction};
+use serde::{Deserialize, Serialize};
+
+use casper_hashing::Digest;
+use casper_types::{account::AccountHash, Transfer, URef};
+
+use super::lmdb_ext::{self, LmdbExtError, WriteTransactionExt};
+use crate::types::{BlockHash, DeployHash};
+
+/// The name of the LMDB database holding the activity index.
+pub(super) const ACTIVITY_INDEX_DB_NAME: &str = "activity_index";
+
+const PREFIX_LENGTH: usize = 1 + 32;
+const CURSOR_LENGTH: usize = 8 + Digest::LENGTH + 4;
+const KEY_LENGTH: usize = PREFIX_LENGTH + CURSOR_LENGTH;
+
+/// The account or purse whose activity is being indexed or queried.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Debug)]
+pub(crate) enum ActivitySubject {
+    /// Deploys sent by the given account.
+    AccountDeploys(AccountHash),
+    /// Successful transfers to or from the given account.
+    AccountTransfers(AccountHash),
+    /// Successful transfers to or from the given purse.
+    PurseTransfers(URef),
+}
+
+impl ActivitySubject {
+    fn prefix(&self) -> [u8; PREFIX_LENGTH] {
+        let (tag, id) = match self {
+            ActivitySubject::AccountDeploys(account_hash) => (0, account_hash.value()),
+            ActivitySubject::AccountTransfers(account_hash) => (1, account_hash.value()),
+            ActivitySubject::PurseTransfers(uref) => (2, uref.addr()),
+        };
+        let mut prefix = [0; PREFIX_LENGTH];
+        prefix[0] = tag;
+        prefix[1..].copy_from_slice(&id);
+        prefix
+    }
+}
+
+impl Display for ActivitySubject {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ActivitySubject::AccountDeploys(account_hash) => {
+                write!(formatter, "deploys sent by {}", account_hash)
+            }
+            ActivitySubject::AccountTransfers(account_hash) => {
+                write!(formatter, "transfers involving {}", account_hash)
+            }
+            ActivitySubject::PurseTransfers(uref) => {
+                write!(
+                    formatter,
+                    "transfers involving purse {}",
+                    uref.to_formatted_string()
+                )
+            }
+        }
+    }
+}
+
+/// The position of an entry within the activity of a single account or purse.
+///
+/// Used to resume a paginated query after the last entry previously returned.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, DataSize, Serialize, Debug)]
+pub(crate) struct ActivityCursor {
+    block_height: u64,
+    deploy_hash: DeployHash,
+    transfer_index: u32,
+}
+
+impl ActivityCursor {
+    pub(super) fn new(block_height: u64, deploy_hash: DeployHash, transfer_index: u32) -> Self {
+        ActivityCursor {
+            block_height,
+            deploy_hash,
+            transfer_index,
+        }
+    }
+
+    fn to_bytes(self) -> [u8; CURSOR_LENGTH] {
+        let mut bytes = [0; CURSOR_LENGTH];
+        bytes[..8].copy_from_slice(&self.block_height.to_be_bytes());
+        bytes[8..8 + Digest::LENGTH].copy_from_slice(self.deploy_hash.as_ref());
+        bytes[8 + Digest::LENGTH..].copy_from_slice(&self.transfer_index.to_be_bytes());
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != CURSOR_LENGTH {
+            return None;
+        }
+        let block_height = u64::from_be_bytes(bytes[..8].try_into().ok()?);
+        let deploy_hash = DeployHash::new(Digest::try_from(&bytes[8..8 + Digest::LENGTH]).ok()?);
+        let transfer_index = u32::from_be_bytes(bytes[8 + Digest::LENGTH..].try_into().ok()?);
+        Some(ActivityCursor {
+            block_height,
+            deploy_hash,
+            transfer_index,
+        })
+    }
+
+    /// Returns the cursor encoded as an opaque, hex-encoded page token.
+    pub(crate) fn to_page_token(self) -> String {
+        base16::encode_lower(&self.to_bytes())
+    }
+
+    /// Parses a page token previously returned by [`Self::to_page_token`].
+    pub(crate) fn from_page_token(page_token: &str) -> Option<Self> {
+        base16::decode(page_token)
+            .ok()
+            .and_then(|bytes| Self::from_bytes(&bytes))
+    }
+}
+
+/// The value stored under each key of the activity index.
+#[derive(Serialize, Deserialize)]
+struct ActivityRecord {
+    block_hash: BlockHash,
+    transfer: Option<Transfer>,
+}
+
+/// A single entry of the activity index.
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
+pub(crate) struct ActivityEntry {
+    /// The hash of the block in which the deploy was executed.
+    pub(crate) block_hash: BlockHash,
+    /// The height of the block in which the deploy was executed.
+    pub(crate) block_height: u64,
+    /// The hash of the deploy.
+    pub(crate) deploy_hash: DeployHash,
+    /// The transfer, if the subject of the query was transfer activity.
+    pub(crate) transfer: Option<Transfer>,
+}
+
+/// A page of entries of the activity index.
+#[derive(Clone, PartialEq, Eq, Serialize, Debug)]
+pub(crate) struct ActivityPage {
+    /// The entries, in ascending order of block height.
+    pub(crate) entries: Vec<ActivityEntry>,
+    /// The cursor from which to request the next page, or `None` if this is the last page.
+    pub(crate) next: Option<ActivityCursor>,
+}
+
+/// Writes an entry to the activity index, overwriting any identical existing entry.
+pub(super) fn put_entry(
+    txn: &mut RwTransaction,
+    db: Database,
+    subject: ActivitySubject,
+    cursor: ActivityCursor,
+    block_hash: BlockHash,
+    transfer: Option<Transfer>,
+) -> Result<(), LmdbExtError> {
+    let mut key = [0; KEY_LENGTH];
+    key[..PREFIX_LENGTH].copy_from_slice(&subject.prefix());
+    key[PREFIX_LENGTH..].copy_from_slice(&cursor.to_bytes());
+    let record = ActivityRecord {
+        block_hash,
+        transfer,
+    };
+    let _ = txn.put_value(db, &key, &record, true)?;
+    Ok(())
+}
+
+/// Reads up to `limit` entries for the given subject, starting immediately after `after` (or from
+/// the lowest block height if `None`).
+///
+/// Entries for which `is_current` returns `false` are skipped; this is used to ignore entries
+/// relating to blocks which have since been removed from storage.
+pub(super) fn read_page<T: Transaction, F: Fn(&DeployHash, &BlockHash) -> bool>(
+    txn: &T,
+    db: Database,
+    subject: ActivitySubject,
+    after: Option<ActivityCursor>,
+    limit: usize,
+    is_current: F,
+) -> Result<ActivityPage, LmdbExtError> {
+    let prefix = subject.prefix();
+    let mut start_key = prefix.to_vec();
+    if let Some(cursor) = after {
+        start_key.extend_from_slice(&cursor.to_bytes());
+    }
+
+    let mut entries = vec![];
+    let mut last_cursor = None;
+    let mut next = None;
+    let mut cursor = txn.open_ro_cursor(db)?;
+    for row in cursor.iter_from(&start_key) {
+        let (raw_key, raw_value) = row?;
+        if !raw_key.starts_with(&prefix) {
+            break;
+        }
+        let activity_cursor = match ActivityCursor::from_bytes(&raw_key[PREFIX_LENGTH..]) {
+            Some(activity_cursor) => activity_cursor,
+            None => continue,
+        };
+        if Some(activity_cursor) == after {
+            continue;
+        }
+        let record: ActivityRecord = lmdb_ext::deserialize(raw_value)?;
+        if !is_current(&activity_cursor.deploy_hash, &record.block_hash) {
+            continue;
+        }
+        if entries.len() >= limit {
+            next = last_cursor;
+            break;
+        }
+        entries.push(ActivityEntry {
+            block_hash: record.block_hash,
+            block_height: activity_cursor.block_height,
+            deploy_hash: activity_cursor.deploy_hash,
+            transfer: record.transfer,
+        });
+        last_cursor = Some(activity_cursor);
+    }
+
+    Ok(ActivityPage { entries, next })
+}
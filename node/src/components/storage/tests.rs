@@ -1,7 +1,7 @@
 //! Unit tests for the storage component.
 
 use std::{
-    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
     fs::{self, File},
     iter::{self, FromIterator},
     rc::Rc,
@@ -13,17 +13,20 @@ use rand::{prelude::SliceRandom, Rng};
 use serde::{Deserialize, Serialize};
 use smallvec::smallvec;
 
+use casper_execution_engine::storage::trie::merkle_proof::TrieMerkleProof;
 use casper_types::{
-    generate_ed25519_keypair, system::auction::UnbondingPurse, testing::TestRng, AccessRights,
-    EraId, ExecutionEffect, ExecutionResult, Key, ProtocolVersion, PublicKey, SecretKey, TimeDiff,
-    Transfer, Transform, TransformEntry, URef, U512,
+    account::AccountHash, generate_ed25519_keypair, system::auction::UnbondingPurse,
+    testing::TestRng, AccessRights, CLValue, EraId, ExecutionEffect, ExecutionResult, Key,
+    ProtocolVersion, PublicKey, SecretKey, StoredValue, TimeDiff, Transfer, Transform,
+    TransformEntry, URef, U512,
 };
 
 use super::{
+    disjoint_sequences::{DisjointSequences, Sequence},
     initialize_block_metadata_db,
     lmdb_ext::{deserialize_internal, serialize_internal, TransactionExt, WriteTransactionExt},
-    move_storage_files_to_network_subdir, should_move_storage_files_to_network_subdir, Config,
-    Storage, FORCE_RESYNC_FILE_NAME,
+    move_storage_files_to_network_subdir, should_move_storage_files_to_network_subdir,
+    ActivitySubject, Config, Storage, FORCE_RESYNC_FILE_NAME,
 };
 use crate::{
     components::fetcher::{FetchItem, FetchResponse},
@@ -33,11 +36,11 @@ use crate::{
     },
     testing::{ComponentHarness, UnitTestEvent},
     types::{
-        sync_leap_validation_metadata::SyncLeapValidationMetaData, AvailableBlockRange, Block,
-        BlockHash, BlockHashAndHeight, BlockHashHeightAndEra, BlockHeader, BlockHeaderWithMetadata,
-        BlockSignatures, Chainspec, ChainspecRawBytes, Deploy, DeployHash, DeployMetadata,
-        DeployMetadataExt, DeployWithFinalizedApprovals, FinalitySignature, LegacyDeploy,
-        SyncLeapIdentifier, TestBlockBuilder,
+        sync_leap_validation_metadata::SyncLeapValidationMetaData, ApprovalsHashes,
+        AvailableBlockRange, Block, BlockHash, BlockHashAndHeight, BlockHashHeightAndEra,
+        BlockHeader, BlockHeaderWithMetadata, BlockSignatures, Chainspec, ChainspecRawBytes,
+        Deploy, DeployHash, DeployMetadata, DeployMetadataExt, DeployWithFinalizedApprovals,
+        FinalitySignature, LegacyDeploy, SyncLeapIdentifier, TestBlockBuilder,
     },
     utils::{Loadable, WithDir},
 };
@@ -57,6 +60,7 @@ fn new_config(harness: &ComponentHarness<UnitTestEvent>) -> Config {
         max_state_store_size: 50 * MIB,
         enable_mem_deduplication: true,
         mem_pool_prune_interval: 4,
+        enable_activity_index: true,
     }
 }
 
@@ -1199,6 +1203,7 @@ fn prepare_exec_result_with_transfer(
         effect,
         transfers: vec![],
         cost: rng.gen(),
+        events: vec![],
     };
     (exec_result, transfer)
 }
@@ -1256,6 +1261,114 @@ fn store_identical_execution_results() {
     assert_eq!(retrieved_transfers[0], transfer);
 }
 
+#[test]
+fn should_index_account_activity() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let deploy = Deploy::random_valid_native_transfer(&mut harness.rng);
+    let deploy_hash = *deploy.hash();
+    let sender = deploy.header().account().to_account_hash();
+    put_deploy(&mut harness, &mut storage, Arc::new(deploy.clone()));
+    let block = Block::random_with_deploys(&mut harness.rng, Some(&deploy));
+
+    // Two transfers from the same account and purse, to different accounts and purses.
+    let from: AccountHash = harness.rng.gen();
+    let source: URef = harness.rng.gen();
+    let transfers: Vec<Transfer> = (0..2)
+        .map(|_| {
+            Transfer::new(
+                deploy_hash.into(),
+                from,
+                Some(harness.rng.gen()),
+                source,
+                harness.rng.gen(),
+                harness.rng.gen(),
+                harness.rng.gen(),
+                None,
+            )
+        })
+        .collect();
+    let transforms = transfers
+        .iter()
+        .map(|transfer| TransformEntry {
+            key: Key::DeployInfo(deploy_hash.into()).to_formatted_string(),
+            transform: Transform::WriteTransfer(*transfer),
+        })
+        .collect();
+    let exec_result = ExecutionResult::Success {
+        effect: ExecutionEffect::new(transforms),
+        transfers: vec![],
+        cost: harness.rng.gen(),
+        events: vec![],
+    };
+    let approvals_hashes = ApprovalsHashes::new(
+        block.hash(),
+        vec![deploy.approvals_hash().unwrap()],
+        TrieMerkleProof::new(
+            URef::new([255; 32], AccessRights::NONE).into(),
+            StoredValue::CLValue(CLValue::from_t(()).unwrap()),
+            VecDeque::new(),
+        ),
+    );
+    assert!(storage
+        .put_executed_block(
+            &block,
+            &approvals_hashes,
+            iter::once((deploy_hash, exec_result)).collect()
+        )
+        .unwrap());
+
+    // The deploy should be indexed under its sender.
+    let page = storage
+        .read_activity(ActivitySubject::AccountDeploys(sender), None, 10)
+        .unwrap()
+        .expect("activity index should be enabled");
+    assert_eq!(page.entries.len(), 1);
+    assert_eq!(page.entries[0].deploy_hash, deploy_hash);
+    assert_eq!(page.entries[0].block_hash, *block.hash());
+    assert_eq!(page.entries[0].block_height, block.height());
+    assert_eq!(page.entries[0].transfer, None);
+    assert_eq!(page.next, None);
+
+    // Each transfer should be indexed under its recipient and its target purse.
+    for transfer in &transfers {
+        for subject in [
+            ActivitySubject::AccountTransfers(transfer.to.unwrap()),
+            ActivitySubject::PurseTransfers(transfer.target),
+        ] {
+            let page = storage.read_activity(subject, None, 10).unwrap().unwrap();
+            assert_eq!(page.entries.len(), 1);
+            assert_eq!(page.entries[0].transfer, Some(*transfer));
+        }
+    }
+
+    // Both transfers should be indexed under the sender and the source purse, and be paginated.
+    for subject in [
+        ActivitySubject::AccountTransfers(from),
+        ActivitySubject::PurseTransfers(source),
+    ] {
+        let first_page = storage.read_activity(subject, None, 1).unwrap().unwrap();
+        assert_eq!(first_page.entries.len(), 1);
+        assert_eq!(first_page.entries[0].transfer, Some(transfers[0]));
+        assert!(first_page.next.is_some());
+
+        let second_page = storage
+            .read_activity(subject, first_page.next, 1)
+            .unwrap()
+            .unwrap();
+        assert_eq!(second_page.entries.len(), 1);
+        assert_eq!(second_page.entries[0].transfer, Some(transfers[1]));
+        assert_eq!(second_page.next, None);
+    }
+
+    // An unrelated account has no activity.
+    let unrelated = ActivitySubject::AccountDeploys(harness.rng.gen());
+    let page = storage.read_activity(unrelated, None, 10).unwrap().unwrap();
+    assert!(page.entries.is_empty());
+    assert_eq!(page.next, None);
+}
+
 /// This is a regression test for the issue where `Transfer`s under a block with no deploys could be
 /// returned as `None` rather than the expected `Some(vec![])`.  The fix should ensure that if no
 /// Transfers are found, storage will respond with an empty collection and store the correct value
@@ -2065,6 +2178,75 @@ fn check_force_resync_with_marker_file() {
     );
 }
 
+/// Requests the available block range, as reported to clients, from a storage component.
+fn get_available_block_range(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+) -> AvailableBlockRange {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::GetAvailableBlockRange { responder }.into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
+/// Records the height below which global state has been pruned in a storage component.
+fn put_state_pruned_below_height(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    height: u64,
+) {
+    harness.send_request(storage, move |responder| {
+        StorageRequest::PutStatePrunedBelowHeight { height, responder }.into()
+    });
+    assert!(harness.is_idle());
+}
+
+#[test]
+fn should_exclude_pruned_state_from_available_block_range() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+    storage.completed_blocks = DisjointSequences::new(Sequence::new(10, 20));
+    storage.persist_completed_blocks().unwrap();
+
+    assert_eq!(
+        get_available_block_range(&mut harness, &mut storage),
+        AvailableBlockRange::new(10, 20)
+    );
+
+    put_state_pruned_below_height(&mut harness, &mut storage, 15);
+    assert_eq!(
+        get_available_block_range(&mut harness, &mut storage),
+        AvailableBlockRange::new(15, 20)
+    );
+    // The range used internally, e.g. for syncing, is limited likewise.
+    assert_eq!(
+        storage.get_available_block_range(),
+        AvailableBlockRange::new(15, 20)
+    );
+
+    // The pruned height never decreases.
+    put_state_pruned_below_height(&mut harness, &mut storage, 12);
+    assert_eq!(
+        get_available_block_range(&mut harness, &mut storage),
+        AvailableBlockRange::new(15, 20)
+    );
+
+    // The pruned height is persisted across restarts.
+    drop(storage);
+    let mut storage = storage_fixture(&harness);
+    assert_eq!(
+        get_available_block_range(&mut harness, &mut storage),
+        AvailableBlockRange::new(15, 20)
+    );
+
+    put_state_pruned_below_height(&mut harness, &mut storage, 25);
+    assert_eq!(
+        get_available_block_range(&mut harness, &mut storage),
+        AvailableBlockRange::new(20, 20)
+    );
+}
+
 #[test]
 fn should_read_legacy_unbonding_purse() {
     // These bytes represent the `UnbondingPurse` struct with the `new_validator` field removed
@@ -2235,3 +2417,94 @@ fn should_initialize_block_metadata_db() {
     assert_signatures(&storage, *block_3.hash(), vec![]);
     assert_signatures(&storage, *block_4.hash(), vec![]);
 }
+
+#[test]
+fn should_read_highest_heights_signed_by_validator() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+    let validator = SecretKey::random(&mut harness.rng);
+    let public_key = PublicKey::from(&validator);
+
+    // Store 4 blocks, 0 and 1 in era 0, 2 and 3 in era 1.
+    let blocks: Vec<Block> = (0..4)
+        .map(|height| {
+            Block::random_with_specifics(
+                &mut harness.rng,
+                EraId::from(height / 2),
+                height,
+                ProtocolVersion::V1_0_0,
+                height % 2 == 1,
+                iter::empty(),
+            )
+        })
+        .collect();
+    for block in &blocks {
+        assert!(put_complete_block(
+            &mut harness,
+            &mut storage,
+            Arc::new(block.clone())
+        ));
+        let block_signatures = random_signatures(&mut harness.rng, block);
+        assert!(put_block_signatures(
+            &mut harness,
+            &mut storage,
+            block_signatures
+        ));
+    }
+    assert!(storage
+        .read_highest_heights_signed_by(&public_key, 10)
+        .unwrap()
+        .is_empty());
+
+    let sign =
+        |harness: &mut ComponentHarness<UnitTestEvent>, storage: &mut Storage, block: &Block| {
+            let mut block_signatures =
+                get_block_signatures(storage, *block.hash()).expect("should have signatures");
+            let signature = FinalitySignature::create(
+                *block.hash(),
+                block.header().era_id(),
+                &validator,
+                public_key.clone(),
+            );
+            block_signatures.insert_proof(signature.public_key, signature.signature);
+            assert!(put_block_signatures(harness, storage, block_signatures));
+        };
+
+    sign(&mut harness, &mut storage, &blocks[0]);
+    sign(&mut harness, &mut storage, &blocks[1]);
+    assert_eq!(
+        storage
+            .read_highest_heights_signed_by(&public_key, 10)
+            .unwrap(),
+        BTreeMap::from([(EraId::from(0), 1)])
+    );
+    // Only the given number of highest blocks is checked.
+    assert!(storage
+        .read_highest_heights_signed_by(&public_key, 2)
+        .unwrap()
+        .is_empty());
+
+    sign(&mut harness, &mut storage, &blocks[2]);
+    assert_eq!(
+        storage
+            .read_highest_heights_signed_by(&public_key, 10)
+            .unwrap(),
+        BTreeMap::from([(EraId::from(0), 1), (EraId::from(1), 2)])
+    );
+}
+
+#[test]
+fn should_deserialize_config_without_activity_index() {
+    // The storage section of a config file predating the activity index.
+    let encoded = r#"
+        path = '../node-storage'
+        max_block_store_size = 19_327_352_832
+        max_deploy_store_size = 12_884_901_888
+        max_deploy_metadata_store_size = 12_884_901_888
+        max_state_store_size = 10_737_418_240
+        enable_mem_deduplication = true
+        mem_pool_prune_interval = 4096
+    "#;
+    let config: Config = toml::from_str(encoded).expect("should deserialize config");
+    assert!(!config.enable_activity_index);
+}
@@ -98,7 +98,7 @@ pub(crate) mod requests;
 use std::{
     any::type_name,
     borrow::Cow,
-    collections::{BTreeMap, HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     fmt::{self, Debug, Display, Formatter},
     future::Future,
     mem,
@@ -117,16 +117,19 @@ use tracing::{debug, error, warn};
 use casper_execution_engine::{
     core::engine_state::{
         self, era_validators::GetEraValidatorsError, BalanceRequest, BalanceResult, GetBidsRequest,
-        GetBidsResult, QueryRequest, QueryResult,
+        GetBidsResult, GetDictionaryItemsRequest, GetDictionaryItemsResult, GetKeysRequest,
+        GetKeysResult, QueryRequest, QueryResult, StateDiffRequest, StateDiffResult,
     },
     shared::execution_journal::ExecutionJournal,
     storage::trie::TrieRaw,
 };
 use casper_hashing::Digest;
 use casper_types::{
-    account::Account, bytesrepr::Bytes, system::auction::EraValidators, Contract, ContractPackage,
-    EraId, ExecutionEffect, ExecutionResult, Key, PublicKey, TimeDiff, Timestamp, Transfer, URef,
-    U512,
+    account::{Account, AccountHash},
+    bytesrepr::Bytes,
+    system::auction::EraValidators,
+    Contract, ContractPackage, EraId, ExecutionEffect, ExecutionResult, Key, PublicKey, TimeDiff,
+    Timestamp, Transfer, URef, U512,
 };
 
 use crate::{
@@ -137,23 +140,30 @@ use crate::{
         },
         consensus::{ClContext, EraDump, ProposedBlock, ValidatorChange},
         contract_runtime::{ContractRuntimeError, EraValidatorsRequest},
-        deploy_acceptor,
+        deploy_acceptor, deploy_staging,
         diagnostics_port::StopAtSpec,
-        fetcher::{FetchItem, FetchResult},
+        fetcher::{FetchItem, FetchResponse, FetchResult},
         gossiper::GossipItem,
-        network::{blocklist::BlocklistJustification, FromIncoming, NetworkInsights},
+        network::{
+            blocklist::BlocklistJustification, reputation::Misbehavior, FromIncoming,
+            NetworkInsights,
+        },
+        storage::{ActivityCursor, ActivityPage, ActivitySubject},
         upgrade_watcher::NextUpgrade,
     },
-    contract_runtime::SpeculativeExecutionState,
+    contract_runtime::{
+        SpeculativeExecutionResult, SpeculativeExecutionResults, SpeculativeExecutionState,
+    },
     failpoints::FailpointActivation,
     reactor::{main_reactor::ReactorState, EventQueueHandle, QueueKind},
     types::{
-        appendable_block::AppendableBlock, ApprovalsHashes, AvailableBlockRange, Block,
+        appendable_block::AppendableBlock, Approval, ApprovalsHashes, AvailableBlockRange, Block,
         BlockExecutionResultsOrChunk, BlockExecutionResultsOrChunkId, BlockHash, BlockHeader,
         BlockSignatures, BlockWithMetadata, ChainspecRawBytes, Deploy, DeployHash, DeployHeader,
         DeployId, DeployMetadataExt, DeployWithFinalizedApprovals, FinalitySignature,
         FinalitySignatureId, FinalizedApprovals, FinalizedBlock, LegacyDeploy, MetaBlock,
-        MetaBlockState, NodeId, TrieOrChunk, TrieOrChunkId,
+        MetaBlockState, NodeId, PendingDeploy, StagedDeploy, SyncLeap, SyncLeapIdentifier,
+        TrieOrChunk, TrieOrChunkId,
     },
     utils::{fmt_limit::FmtLimit, SharedFlag, Source},
 };
@@ -168,9 +178,10 @@ use diagnostics_port::DumpConsensusStateRequest;
 use requests::{
     AcceptDeployRequest, BeginGossipRequest, BlockAccumulatorRequest, BlockSynchronizerRequest,
     BlockValidationRequest, ChainspecRawBytesRequest, ConsensusRequest, ContractRuntimeRequest,
-    DeployBufferRequest, FetcherRequest, MakeBlockExecutableRequest, MarkBlockCompletedRequest,
-    MetricsRequest, NetworkInfoRequest, NetworkRequest, ReactorStatusRequest, SetNodeStopRequest,
-    StorageRequest, SyncGlobalStateRequest, TrieAccumulatorRequest, UpgradeWatcherRequest,
+    DeployBufferRequest, DeployStagingRequest, FetcherRequest, MakeBlockExecutableRequest,
+    MarkBlockCompletedRequest, MetricsRequest, NetworkInfoRequest, NetworkRequest,
+    ReactorStatusRequest, SetNodeStopRequest, StorageRequest, SyncGlobalStateRequest,
+    TrieAccumulatorRequest, UpgradeWatcherRequest,
 };
 
 /// A resource that will never be available, thus trying to acquire it will wait forever.
@@ -784,6 +795,18 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Gets a map of network peers with a non-zero reputation to their current score.
+    pub(crate) async fn network_peer_reputations(self) -> BTreeMap<NodeId, i32>
+    where
+        REv: From<NetworkInfoRequest>,
+    {
+        self.make_request(
+            |responder| NetworkInfoRequest::PeerReputations { responder },
+            QueueKind::Api,
+        )
+        .await
+    }
+
     /// Gets up to `count` fully-connected network peers in random order.
     pub async fn get_fully_connected_peers(self, count: usize) -> Vec<NodeId>
     where
@@ -1304,6 +1327,49 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Gets a page of the account and purse activity index from storage.
+    ///
+    /// Returns `None` if the activity index is not enabled.
+    pub(crate) async fn get_account_activity_from_storage(
+        self,
+        subject: ActivitySubject,
+        after: Option<ActivityCursor>,
+        limit: usize,
+    ) -> Option<ActivityPage>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetAccountActivity {
+                subject,
+                after: after.map(Box::new),
+                limit,
+                responder,
+            },
+            QueueKind::FromStorage,
+        )
+        .await
+    }
+
+    /// Gets the headers and signatures proving the chain from a trusted block to the highest
+    /// complete block from storage.
+    pub(crate) async fn get_sync_leap_from_storage(
+        self,
+        sync_leap_identifier: SyncLeapIdentifier,
+    ) -> FetchResponse<SyncLeap, SyncLeapIdentifier>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetSyncLeap {
+                sync_leap_identifier,
+                responder,
+            },
+            QueueKind::FromStorage,
+        )
+        .await
+    }
+
     /// Returns the era IDs of the blocks in which the given deploys were executed.  If none of the
     /// deploys have been executed yet, an empty set will be returned.
     pub(crate) async fn get_deploys_era_ids(
@@ -1359,6 +1425,18 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Records that the global state of all blocks below the given height has been pruned.
+    pub(crate) async fn put_state_pruned_below_height(self, height: u64)
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::PutStatePrunedBelowHeight { height, responder },
+            QueueKind::ToStorage,
+        )
+        .await
+    }
+
     /// Synchronize global state under the given root hash.
     pub(crate) async fn sync_global_state(
         self,
@@ -1719,6 +1797,79 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Retrieves the deploys known to the deploy buffer, optionally only those of the given
+    /// account.
+    pub(crate) async fn get_pending_deploys(
+        self,
+        account_hash: Option<AccountHash>,
+    ) -> Vec<PendingDeploy>
+    where
+        REv: From<DeployBufferRequest>,
+    {
+        self.make_request(
+            |responder| DeployBufferRequest::GetPendingDeploys {
+                account_hash,
+                responder,
+            },
+            QueueKind::Api,
+        )
+        .await
+    }
+
+    /// Stages a deploy in the deploy staging area until its approvals reach the deployment
+    /// threshold of its account.
+    pub(crate) async fn stage_deploy(
+        self,
+        deploy: Deploy,
+    ) -> Result<StagedDeploy, deploy_staging::Error>
+    where
+        REv: From<DeployStagingRequest>,
+    {
+        self.make_request(
+            |responder| DeployStagingRequest::StageDeploy {
+                deploy: Box::new(deploy),
+                responder,
+            },
+            QueueKind::Api,
+        )
+        .await
+    }
+
+    /// Adds approvals to a deploy held in the deploy staging area.
+    pub(crate) async fn add_staged_deploy_approvals(
+        self,
+        deploy_hash: DeployHash,
+        approvals: BTreeSet<Approval>,
+    ) -> Result<StagedDeploy, deploy_staging::Error>
+    where
+        REv: From<DeployStagingRequest>,
+    {
+        self.make_request(
+            |responder| DeployStagingRequest::AddApprovals {
+                deploy_hash,
+                approvals,
+                responder,
+            },
+            QueueKind::Api,
+        )
+        .await
+    }
+
+    /// Retrieves the deploys of the given account held in the deploy staging area.
+    pub(crate) async fn get_staged_deploys(self, account_hash: AccountHash) -> Vec<StagedDeploy>
+    where
+        REv: From<DeployStagingRequest>,
+    {
+        self.make_request(
+            |responder| DeployStagingRequest::GetStagedDeploys {
+                account_hash,
+                responder,
+            },
+            QueueKind::Api,
+        )
+        .await
+    }
+
     /// Enqueues a finalized block execution.
     pub(crate) async fn enqueue_block_for_execution(
         self,
@@ -1846,9 +1997,10 @@ impl<REv> EffectBuilder<REv> {
             .await
     }
 
-    /// Blocks a specific peer due to a transgression.
+    /// Reports a transgression by a specific peer, lowering its reputation and potentially getting
+    /// it blocked.
     ///
-    /// This function will also emit a log message for the block.
+    /// This function will also emit a log message for the transgression.
     pub(crate) async fn announce_block_peer_with_justification(
         self,
         offender: NodeId,
@@ -1856,7 +2008,7 @@ impl<REv> EffectBuilder<REv> {
     ) where
         REv: From<PeerBehaviorAnnouncement>,
     {
-        warn!(%offender, %justification, "banning peer");
+        warn!(%offender, %justification, "peer committed offense");
         self.event_queue
             .schedule(
                 PeerBehaviorAnnouncement::OffenseCommitted {
@@ -1868,6 +2020,23 @@ impl<REv> EffectBuilder<REv> {
             .await
     }
 
+    /// Reports misbehavior of a specific peer, lowering its reputation.
+    pub(crate) async fn announce_peer_misbehavior(self, offender: NodeId, misbehavior: Misbehavior)
+    where
+        REv: From<PeerBehaviorAnnouncement>,
+    {
+        debug!(%offender, %misbehavior, "peer misbehaved");
+        self.event_queue
+            .schedule(
+                PeerBehaviorAnnouncement::MisbehaviorObserved {
+                    offender: Box::new(offender),
+                    misbehavior,
+                },
+                QueueKind::NetworkInfo,
+            )
+            .await
+    }
+
     /// Gets the next scheduled upgrade, if any.
     pub(crate) async fn get_next_upgrade(self) -> Option<NextUpgrade>
     where
@@ -2024,6 +2193,59 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Requests a page of the keys of a given type under a state root hash.
+    pub(crate) async fn get_keys(
+        self,
+        get_keys_request: GetKeysRequest,
+    ) -> Result<GetKeysResult, engine_state::Error>
+    where
+        REv: From<ContractRuntimeRequest>,
+    {
+        self.make_request(
+            |responder| ContractRuntimeRequest::GetKeys {
+                get_keys_request,
+                responder,
+            },
+            QueueKind::ContractRuntime,
+        )
+        .await
+    }
+
+    /// Requests a page of the items of a dictionary under a state root hash.
+    pub(crate) async fn get_dictionary_items(
+        self,
+        get_dictionary_items_request: GetDictionaryItemsRequest,
+    ) -> Result<GetDictionaryItemsResult, engine_state::Error>
+    where
+        REv: From<ContractRuntimeRequest>,
+    {
+        self.make_request(
+            |responder| ContractRuntimeRequest::GetDictionaryItems {
+                get_dictionary_items_request,
+                responder,
+            },
+            QueueKind::ContractRuntime,
+        )
+        .await
+    }
+    /// Requests the differences between the global state under two state root hashes.
+    pub(crate) async fn get_state_diff(
+        self,
+        state_diff_request: StateDiffRequest,
+    ) -> Result<StateDiffResult, engine_state::Error>
+    where
+        REv: From<ContractRuntimeRequest>,
+    {
+        self.make_request(
+            |responder| ContractRuntimeRequest::GetStateDiff {
+                state_diff_request,
+                responder,
+            },
+            QueueKind::ContractRuntime,
+        )
+        .await
+    }
+
     /// Returns the value of the execution results checksum stored in the ChecksumRegistry for the
     /// given state root hash.
     pub(crate) async fn get_execution_results_checksum(
@@ -2200,7 +2422,9 @@ impl<REv> EffectBuilder<REv> {
         self,
         execution_prestate: SpeculativeExecutionState,
         deploy: Arc<Deploy>,
-    ) -> Result<Option<ExecutionResult>, engine_state::Error>
+        trace: bool,
+        estimate_gas: bool,
+    ) -> Result<Option<SpeculativeExecutionResult>, engine_state::Error>
     where
         REv: From<ContractRuntimeRequest>,
     {
@@ -2208,6 +2432,29 @@ impl<REv> EffectBuilder<REv> {
             |responder| ContractRuntimeRequest::SpeculativeDeployExecution {
                 execution_prestate,
                 deploy,
+                trace,
+                estimate_gas,
+                responder,
+            },
+            QueueKind::ContractRuntime,
+        )
+        .await
+    }
+
+    /// Requests cumulative execution of a sequence of deploys, without commiting their effects.
+    /// Inteded to be used for simulating multi-step workflows.
+    pub(crate) async fn speculative_execute_deploys(
+        self,
+        execution_prestate: SpeculativeExecutionState,
+        deploys: Vec<Arc<Deploy>>,
+    ) -> Result<Option<SpeculativeExecutionResults>, engine_state::Error>
+    where
+        REv: From<ContractRuntimeRequest>,
+    {
+        self.make_request(
+            |responder| ContractRuntimeRequest::SpeculativeDeploysExecution {
+                execution_prestate,
+                deploys,
                 responder,
             },
             QueueKind::ContractRuntime,
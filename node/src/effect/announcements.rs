@@ -22,7 +22,7 @@ use crate::{
         diagnostics_port::FileSerializer,
         fetcher::FetchItem,
         gossiper::GossipItem,
-        network::blocklist::BlocklistJustification,
+        network::{blocklist::BlocklistJustification, reputation::Misbehavior},
         upgrade_watcher::NextUpgrade,
     },
     effect::Responder,
@@ -285,6 +285,13 @@ pub(crate) enum PeerBehaviorAnnouncement {
         /// Justification for blocking the peer.
         justification: Box<BlocklistJustification>,
     },
+    /// A given peer misbehaved in a way that lowers its reputation.
+    MisbehaviorObserved {
+        /// The peer ID of the misbehaving node.
+        offender: Box<NodeId>,
+        /// The kind of misbehavior.
+        misbehavior: Misbehavior,
+    },
 }
 
 impl Display for PeerBehaviorAnnouncement {
@@ -296,6 +303,12 @@ impl Display for PeerBehaviorAnnouncement {
             } => {
                 write!(f, "peer {} committed offense: {}", offender, justification)
             }
+            PeerBehaviorAnnouncement::MisbehaviorObserved {
+                offender,
+                misbehavior,
+            } => {
+                write!(f, "peer {} misbehaved: {}", offender, misbehavior)
+            }
         }
     }
 }
@@ -4,7 +4,7 @@
 //! top-level module documentation for details.
 
 use std::{
-    collections::{BTreeMap, HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     fmt::{self, Debug, Display, Formatter},
     mem,
     sync::Arc,
@@ -21,14 +21,17 @@ use casper_execution_engine::{
         balance::{BalanceRequest, BalanceResult},
         era_validators::GetEraValidatorsError,
         get_bids::{GetBidsRequest, GetBidsResult},
+        get_dictionary_items::{GetDictionaryItemsRequest, GetDictionaryItemsResult},
+        get_keys::{GetKeysRequest, GetKeysResult},
         query::{QueryRequest, QueryResult},
+        state_diff::{StateDiffRequest, StateDiffResult},
     },
     storage::trie::TrieRaw,
 };
 use casper_hashing::Digest;
 use casper_types::{
-    bytesrepr::Bytes, system::auction::EraValidators, EraId, ExecutionResult, Key, ProtocolVersion,
-    PublicKey, TimeDiff, Timestamp, Transfer, URef,
+    account::AccountHash, bytesrepr::Bytes, system::auction::EraValidators, EraId, ExecutionResult,
+    Key, ProtocolVersion, PublicKey, TimeDiff, Timestamp, Transfer, URef,
 };
 
 use crate::{
@@ -39,24 +42,29 @@ use crate::{
         },
         consensus::{ClContext, ProposedBlock, ValidatorChange},
         contract_runtime::EraValidatorsRequest,
-        deploy_acceptor,
+        deploy_acceptor, deploy_staging,
         diagnostics_port::StopAtSpec,
-        fetcher::{FetchItem, FetchResult},
+        fetcher::{FetchItem, FetchResponse, FetchResult},
         gossiper::GossipItem,
         network::NetworkInsights,
+        storage::{ActivityCursor, ActivityPage, ActivitySubject},
         upgrade_watcher::NextUpgrade,
     },
-    contract_runtime::{ContractRuntimeError, SpeculativeExecutionState},
+    contract_runtime::{
+        ContractRuntimeError, SpeculativeExecutionResult, SpeculativeExecutionResults,
+        SpeculativeExecutionState,
+    },
     effect::{AutoClosingResponder, Responder},
     reactor::main_reactor::ReactorState,
     rpcs::docs::OpenRpcSchema,
     types::{
-        appendable_block::AppendableBlock, ApprovalsHashes, AvailableBlockRange, Block,
+        appendable_block::AppendableBlock, Approval, ApprovalsHashes, AvailableBlockRange, Block,
         BlockExecutionResultsOrChunk, BlockExecutionResultsOrChunkId, BlockHash, BlockHeader,
         BlockSignatures, BlockWithMetadata, ChainspecRawBytes, Deploy, DeployHash, DeployHeader,
         DeployId, DeployMetadataExt, DeployWithFinalizedApprovals, FinalitySignature,
         FinalitySignatureId, FinalizedApprovals, FinalizedBlock, LegacyDeploy, MetaBlockState,
-        NodeId, StatusFeed, TrieOrChunk, TrieOrChunkId,
+        NodeId, PeersMap, PendingDeploy, StagedDeploy, StatusFeed, SyncLeap, SyncLeapIdentifier,
+        TrieOrChunk, TrieOrChunkId,
     },
     utils::{DisplayIter, Source},
 };
@@ -203,6 +211,11 @@ pub(crate) enum NetworkInfoRequest {
         /// Responds with a map from [NodeId]s to a socket address, represented as a string.
         responder: Responder<BTreeMap<NodeId, String>>,
     },
+    /// Get the reputation scores of peers.
+    PeerReputations {
+        /// Responder to be called with the scores of all peers whose score is not zero.
+        responder: Responder<BTreeMap<NodeId, i32>>,
+    },
     /// Get up to `count` fully-connected peers in random order.
     FullyConnectedPeers {
         count: usize,
@@ -221,6 +234,9 @@ impl Display for NetworkInfoRequest {
             NetworkInfoRequest::Peers { responder: _ } => {
                 formatter.write_str("get peers-to-socket-address map")
             }
+            NetworkInfoRequest::PeerReputations { responder: _ } => {
+                formatter.write_str("get peer reputations")
+            }
             NetworkInfoRequest::FullyConnectedPeers {
                 count,
                 responder: _,
@@ -480,12 +496,19 @@ pub(crate) enum StorageRequest {
         /// stored.
         responder: Responder<bool>,
     },
-    /// Retrieve the height range of fully available blocks (not just block headers). Returns
-    /// `[u64::MAX, u64::MAX]` when there are no sequences.
+    /// Retrieve the height range of fully available blocks (not just block headers) whose global
+    /// state has not been pruned. Returns `[u64::MAX, u64::MAX]` when there are no sequences.
     GetAvailableBlockRange {
         /// Responder to call with the result.
         responder: Responder<AvailableBlockRange>,
     },
+    /// Record that the global state of all blocks below the given height has been pruned.
+    PutStatePrunedBelowHeight {
+        /// The height below which global state has been pruned.
+        height: u64,
+        /// Responder to call once the height has been persisted.
+        responder: Responder<()>,
+    },
     /// Store a set of finalized approvals for a specific deploy.
     StoreFinalizedApprovals {
         /// The deploy hash to store the finalized approvals for.
@@ -498,6 +521,26 @@ pub(crate) enum StorageRequest {
     },
     /// Retrieve the height of the final block of the previous protocol version, if known.
     GetKeyBlockHeightForActivationPoint { responder: Responder<Option<u64>> },
+    /// Retrieve a page of the account and purse activity index.
+    GetAccountActivity {
+        /// The account or purse whose activity should be retrieved.
+        subject: ActivitySubject,
+        /// The position after which to start the page, or `None` to start from the beginning.
+        after: Option<Box<ActivityCursor>>,
+        /// The maximum number of entries to retrieve.
+        limit: usize,
+        /// Responder to call with the result.  Returns `None` if the activity index is not
+        /// enabled.
+        responder: Responder<Option<ActivityPage>>,
+    },
+    /// Retrieve the headers and signatures proving the chain from a trusted block to the highest
+    /// complete block.
+    GetSyncLeap {
+        /// The identifier of the sync leap, holding the hash of the trusted block.
+        sync_leap_identifier: SyncLeapIdentifier,
+        /// Responder to call with the result.
+        responder: Responder<FetchResponse<SyncLeap, SyncLeapIdentifier>>,
+    },
 }
 
 impl Display for StorageRequest {
@@ -608,6 +651,9 @@ impl Display for StorageRequest {
             StorageRequest::GetAvailableBlockRange { .. } => {
                 write!(formatter, "get available block range",)
             }
+            StorageRequest::PutStatePrunedBelowHeight { height, .. } => {
+                write!(formatter, "put state pruned below height {}", height)
+            }
             StorageRequest::StoreFinalizedApprovals { deploy_hash, .. } => {
                 write!(formatter, "finalized approvals for deploy {}", deploy_hash)
             }
@@ -620,6 +666,15 @@ impl Display for StorageRequest {
                     "get key block height for current activation point"
                 )
             }
+            StorageRequest::GetAccountActivity { subject, .. } => {
+                write!(formatter, "get activity: {}", subject)
+            }
+            StorageRequest::GetSyncLeap {
+                sync_leap_identifier,
+                ..
+            } => {
+                write!(formatter, "get sync leap: {}", sync_leap_identifier)
+            }
         }
     }
 }
@@ -664,6 +719,11 @@ pub(crate) enum DeployBufferRequest {
         timestamp: Timestamp,
         responder: Responder<AppendableBlock>,
     },
+    /// Returns the deploys known to the deploy buffer, optionally only those of the given account.
+    GetPendingDeploys {
+        account_hash: Option<AccountHash>,
+        responder: Responder<Vec<PendingDeploy>>,
+    },
 }
 
 impl Display for DeployBufferRequest {
@@ -676,6 +736,65 @@ impl Display for DeployBufferRequest {
                     timestamp
                 )
             }
+            DeployBufferRequest::GetPendingDeploys {
+                account_hash: Some(account_hash),
+                ..
+            } => {
+                write!(formatter, "request for pending deploys of {}", account_hash)
+            }
+            DeployBufferRequest::GetPendingDeploys {
+                account_hash: None, ..
+            } => {
+                write!(formatter, "request for pending deploys")
+            }
+        }
+    }
+}
+
+/// A request to the deploy staging area, which collects the approvals of a deploy until they reach
+/// the deployment threshold of its account.
+#[derive(Debug, Serialize)]
+#[must_use]
+pub(crate) enum DeployStagingRequest {
+    /// Stages a deploy, merging its approvals with those of the same deploy if already staged.
+    StageDeploy {
+        deploy: Box<Deploy>,
+        responder: Responder<Result<StagedDeploy, deploy_staging::Error>>,
+    },
+    /// Adds approvals to a staged deploy.
+    AddApprovals {
+        deploy_hash: DeployHash,
+        approvals: BTreeSet<Approval>,
+        responder: Responder<Result<StagedDeploy, deploy_staging::Error>>,
+    },
+    /// Returns the staged deploys of the given account.
+    GetStagedDeploys {
+        account_hash: AccountHash,
+        responder: Responder<Vec<StagedDeploy>>,
+    },
+}
+
+impl Display for DeployStagingRequest {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            DeployStagingRequest::StageDeploy { deploy, .. } => {
+                write!(formatter, "stage deploy {}", deploy.hash())
+            }
+            DeployStagingRequest::AddApprovals {
+                deploy_hash,
+                approvals,
+                ..
+            } => {
+                write!(
+                    formatter,
+                    "add {} approvals to staged deploy {}",
+                    approvals.len(),
+                    deploy_hash
+                )
+            }
+            DeployStagingRequest::GetStagedDeploys { account_hash, .. } => {
+                write!(formatter, "request for staged deploys of {}", account_hash)
+            }
         }
     }
 }
@@ -721,6 +840,27 @@ pub(crate) enum RpcRequest {
         /// Responder to call with the result.
         responder: Responder<Result<GetBidsResult, engine_state::Error>>,
     },
+    /// Get a page of the keys of a given type at the given root hash.
+    GetKeys {
+        /// The request for the page of keys.
+        get_keys_request: GetKeysRequest,
+        /// Responder to call with the result.
+        responder: Responder<Result<GetKeysResult, engine_state::Error>>,
+    },
+    /// Get a page of the items of a dictionary at the given root hash.
+    GetDictionaryItems {
+        /// The request for the page of dictionary items.
+        get_dictionary_items_request: GetDictionaryItemsRequest,
+        /// Responder to call with the result.
+        responder: Responder<Result<GetDictionaryItemsResult, engine_state::Error>>,
+    },
+    /// Get the differences between the global state at two root hashes.
+    GetStateDiff {
+        /// The request for the differences.
+        state_diff_request: StateDiffRequest,
+        /// Responder to call with the result.
+        responder: Responder<Result<StateDiffResult, engine_state::Error>>,
+    },
 
     /// Query the global state at the given root hash.
     GetBalance {
@@ -743,7 +883,7 @@ pub(crate) enum RpcRequest {
     /// Return the connected peers.
     GetPeers {
         /// Responder to call with the result.
-        responder: Responder<BTreeMap<NodeId, String>>,
+        responder: Responder<PeersMap>,
     },
     /// Return string formatted status or `None` if an error occurred.
     GetStatus {
@@ -755,6 +895,25 @@ pub(crate) enum RpcRequest {
         /// Responder to call with the result.
         responder: Responder<AvailableBlockRange>,
     },
+    /// Return a page of the account and purse activity index, or `None` if it is not enabled.
+    GetAccountActivity {
+        /// The account or purse whose activity should be retrieved.
+        subject: ActivitySubject,
+        /// The position after which to start the page, or `None` to start from the beginning.
+        after: Option<ActivityCursor>,
+        /// The maximum number of entries to retrieve.
+        limit: usize,
+        /// Responder to call with the result.
+        responder: Responder<Option<ActivityPage>>,
+    },
+    /// Return the headers and signatures proving the chain from a trusted block to the highest
+    /// complete block.
+    GetSyncLeap {
+        /// The hash of the trusted block.
+        trusted_block_hash: BlockHash,
+        /// Responder to call with the result.
+        responder: Responder<FetchResponse<SyncLeap, SyncLeapIdentifier>>,
+    },
 }
 
 impl Display for RpcRequest {
@@ -782,6 +941,20 @@ impl Display for RpcRequest {
             } => {
                 write!(formatter, "bids {}", state_root_hash)
             }
+            RpcRequest::GetKeys {
+                get_keys_request, ..
+            } => write!(formatter, "keys {:?}", get_keys_request),
+            RpcRequest::GetDictionaryItems {
+                get_dictionary_items_request,
+                ..
+            } => write!(
+                formatter,
+                "dictionary items {:?}",
+                get_dictionary_items_request
+            ),
+            RpcRequest::GetStateDiff {
+                state_diff_request, ..
+            } => write!(formatter, "state diff {:?}", state_diff_request),
             RpcRequest::GetBalance {
                 state_root_hash,
                 purse_uref,
@@ -805,6 +978,14 @@ impl Display for RpcRequest {
             RpcRequest::GetAvailableBlockRange { .. } => {
                 write!(formatter, "get available block range")
             }
+            RpcRequest::GetAccountActivity { subject, .. } => {
+                write!(formatter, "get activity: {}", subject)
+            }
+            RpcRequest::GetSyncLeap {
+                trusted_block_hash, ..
+            } => {
+                write!(formatter, "get sync leap for {}", trusted_block_hash)
+            }
         }
     }
 }
@@ -889,6 +1070,30 @@ pub(crate) enum ContractRuntimeRequest {
         /// Responder to call with the result.
         responder: Responder<Result<GetBidsResult, engine_state::Error>>,
     },
+    /// Return a page of the keys of a given type at a given state root hash.
+    GetKeys {
+        /// Get keys request.
+        #[serde(skip_serializing)]
+        get_keys_request: GetKeysRequest,
+        /// Responder to call with the result.
+        responder: Responder<Result<GetKeysResult, engine_state::Error>>,
+    },
+    /// Return a page of the items of a dictionary at a given state root hash.
+    GetDictionaryItems {
+        /// Get dictionary items request.
+        #[serde(skip_serializing)]
+        get_dictionary_items_request: GetDictionaryItemsRequest,
+        /// Responder to call with the result.
+        responder: Responder<Result<GetDictionaryItemsResult, engine_state::Error>>,
+    },
+    /// Return the differences between the global state at two state root hashes.
+    GetStateDiff {
+        /// State diff request.
+        #[serde(skip_serializing)]
+        state_diff_request: StateDiffRequest,
+        /// Responder to call with the result.
+        responder: Responder<Result<StateDiffResult, engine_state::Error>>,
+    },
     /// Returns the value of the execution results checksum stored in the ChecksumRegistry for the
     /// given state root hash.
     GetExecutionResultsChecksum {
@@ -922,8 +1127,21 @@ pub(crate) enum ContractRuntimeRequest {
         execution_prestate: SpeculativeExecutionState,
         /// Deploy to execute.
         deploy: Arc<Deploy>,
+        /// Whether to record the call frames entered while executing the deploy.
+        trace: bool,
+        /// Whether to also estimate the gas consumed by the deploy.
+        estimate_gas: bool,
+        /// Results, with the call trace if requested.
+        responder: Responder<Result<Option<SpeculativeExecutionResult>, engine_state::Error>>,
+    },
+    /// Execute a sequence of deploys cumulatively without commiting results
+    SpeculativeDeploysExecution {
+        /// Hash of a block on top of which to execute the deploys.
+        execution_prestate: SpeculativeExecutionState,
+        /// Deploys to execute, in order.
+        deploys: Vec<Arc<Deploy>>,
         /// Results
-        responder: Responder<Result<Option<ExecutionResult>, engine_state::Error>>,
+        responder: Responder<Result<Option<SpeculativeExecutionResults>, engine_state::Error>>,
     },
 }
 
@@ -949,6 +1167,30 @@ impl Display for ContractRuntimeRequest {
             } => {
                 write!(formatter, "get bids request: {:?}", get_bids_request)
             }
+            ContractRuntimeRequest::GetKeys {
+                get_keys_request, ..
+            } => {
+                write!(formatter, "get keys request: {:?}", get_keys_request)
+            }
+            ContractRuntimeRequest::GetDictionaryItems {
+                get_dictionary_items_request,
+                ..
+            } => {
+                write!(
+                    formatter,
+                    "get dictionary items request: {:?}",
+                    get_dictionary_items_request
+                )
+            }
+            ContractRuntimeRequest::GetStateDiff {
+                state_diff_request, ..
+            } => {
+                write!(
+                    formatter,
+                    "get state diff request: {:?}",
+                    state_diff_request
+                )
+            }
             ContractRuntimeRequest::GetExecutionResultsChecksum {
                 state_root_hash, ..
             } => write!(
@@ -979,6 +1221,18 @@ impl Display for ContractRuntimeRequest {
                     execution_prestate.state_root_hash
                 )
             }
+            ContractRuntimeRequest::SpeculativeDeploysExecution {
+                execution_prestate,
+                deploys,
+                ..
+            } => {
+                write!(
+                    formatter,
+                    "Execute {} on {}",
+                    DisplayIter::new(deploys.iter().map(|deploy| deploy.hash())),
+                    execution_prestate.state_root_hash
+                )
+            }
         }
     }
 }
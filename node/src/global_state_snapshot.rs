@@ -0,0 +1,725 @@
+//! Export and import of portable snapshots of the global state.
+//!
+//! A snapshot contains every trie node reachable from the state root hash of a block, together with
+//! the header of that block.  Importing a snapshot into an empty node lets it skip fetching the
+//! global state from its peers, while the block header allows the snapshot to be verified against
+//! the chain: a node started with the block's hash as its trusted hash will only accept it if the
+//! block is part of the chain, and the header commits to the snapshot's state root hash.
+//!
+//! A snapshot file starts with `SNAPSHOT_MAGIC`, followed by a sequence of frames.  Each frame
+//! consists of the length of its payload as a little-endian `u32`, the 32 byte hash of the payload
+//! and the bincode-encoded [`SnapshotFrame`] payload.  The first frame is always a header frame,
+//! followed by any number of chunk frames and a final end frame.
+//!
+//! Trie nodes are written in post-order, i.e. every node is preceded by all of its descendants.
+//! This lets the importer check that every node's children are present before storing it, so once
+//! the root node has been stored the imported global state is known to be complete.
+
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+    sync::Arc,
+};
+
+use lmdb::DatabaseFlags;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::info;
+
+use casper_execution_engine::{
+    shared::newtypes::CorrelationId,
+    storage::{
+        error::Error as GlobalStateError,
+        global_state::{lmdb::LmdbGlobalState, StateProvider},
+        transaction_source::lmdb::LmdbEnvironment,
+        trie::Trie,
+        trie_store::lmdb::LmdbTrieStore,
+    },
+};
+use casper_hashing::Digest;
+use casper_types::{
+    bytesrepr::{self, Bytes},
+    Key, StoredValue,
+};
+
+use crate::{
+    components::{
+        contract_runtime::Config as ContractRuntimeConfig,
+        storage::{FatalStorageError, Storage},
+    },
+    reactor::main_reactor::Config,
+    types::{chainspec, BlockHash, BlockHeader, Chainspec, ChainspecRawBytes},
+    utils::{Loadable, WithDir},
+};
+
+/// The bytes with which every snapshot file starts.
+const SNAPSHOT_MAGIC: &[u8; 8] = b"CSPRGSS\0";
+/// The version of the snapshot file format written by this version of the node.
+const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+/// The size of the serialized trie nodes above which a chunk is written out.
+const MAX_CHUNK_SIZE: usize = 8 * 1024 * 1024;
+/// The maximum accepted frame payload size.
+///
+/// A chunk can exceed `MAX_CHUNK_SIZE` by the size of its last trie node, which can be large if it
+/// holds contract Wasm.
+const MAX_FRAME_SIZE: u32 = 256 * 1024 * 1024;
+
+/// Error returned as a result of exporting or importing a global state snapshot.
+#[derive(Debug, Error)]
+pub(crate) enum Error {
+    /// Error loading the chainspec.
+    #[error("error loading chainspec: {0}")]
+    LoadChainspec(chainspec::Error),
+
+    /// Error opening the block storage.
+    #[error("error opening storage: {0}")]
+    Storage(#[from] FatalStorageError),
+
+    /// Error accessing the global state.
+    #[error("error accessing global state: {0}")]
+    GlobalState(#[from] GlobalStateError),
+
+    /// The requested block is not stored.
+    #[error("block {0} not found in storage")]
+    NoSuchBlock(BlockHash),
+
+    /// No complete block is stored.
+    #[error("no complete block found in storage")]
+    NoCompleteBlock,
+
+    /// A trie node reachable from the state root hash is not stored.
+    #[error("trie node {0} not found in global state")]
+    MissingTrie(Digest),
+
+    /// Error parsing a trie node.
+    #[error("error parsing trie node {digest}: {error}")]
+    ParseTrie {
+        /// The hash of the trie node.
+        digest: Digest,
+        /// The parsing error.
+        error: bytesrepr::Error,
+    },
+
+    /// Error opening or syncing the snapshot file.
+    #[error("error accessing snapshot file {path}: {error}")]
+    Io {
+        /// The file path.
+        path: String,
+        /// The IO error.
+        error: io::Error,
+    },
+
+    /// Error writing the snapshot.
+    #[error("error writing snapshot: {0}")]
+    Write(io::Error),
+
+    /// Error encoding or decoding a snapshot frame.
+    #[error("error encoding snapshot frame: {0}")]
+    Encoding(#[from] bincode::Error),
+
+    /// The file is not a global state snapshot.
+    #[error("not a global state snapshot file")]
+    InvalidMagic,
+
+    /// The snapshot was written in an unsupported format.
+    #[error("unsupported snapshot format version {0}")]
+    UnsupportedFormatVersion(u32),
+
+    /// A frame's payload is larger than allowed.
+    #[error("snapshot frame of {0} bytes is too large")]
+    FrameTooLarge(u32),
+
+    /// A frame's payload doesn't match its checksum.
+    #[error("snapshot frame {index} is corrupt")]
+    InvalidChecksum {
+        /// The index of the frame.
+        index: u64,
+    },
+
+    /// The frames of the snapshot are not in the expected order.
+    #[error("unexpected snapshot frame {index}: {reason}")]
+    UnexpectedFrame {
+        /// The index of the frame.
+        index: u64,
+        /// Why the frame was not expected.
+        reason: &'static str,
+    },
+
+    /// A trie node's hash doesn't match the hash it is listed under.
+    #[error("trie node {expected} has hash {actual}")]
+    InvalidTrieHash {
+        /// The hash under which the trie node is listed.
+        expected: Digest,
+        /// The actual hash of the trie node.
+        actual: Digest,
+    },
+
+    /// A trie node was listed before some of its children.
+    #[error("trie node {0} listed before its children")]
+    MissingTrieChildren(Digest),
+
+    /// The snapshot's block is not the trusted block.
+    #[error("snapshot is of block {actual}, not the trusted block {trusted}")]
+    UntrustedBlock {
+        /// The trusted block hash.
+        trusted: BlockHash,
+        /// The hash of the snapshot's block.
+        actual: BlockHash,
+    },
+
+    /// The snapshot's totals don't match its contents.
+    #[error("snapshot lists {expected} trie nodes in {expected_chunks} chunks but contains {actual} in {actual_chunks}")]
+    CountMismatch {
+        /// The number of trie nodes listed in the end frame.
+        expected: u64,
+        /// The number of chunks listed in the end frame.
+        expected_chunks: u64,
+        /// The number of trie nodes read.
+        actual: u64,
+        /// The number of chunks read.
+        actual_chunks: u64,
+    },
+
+    /// The snapshot doesn't contain the block's state root.
+    #[error("snapshot does not contain the state root {0} of its block")]
+    IncompleteSnapshot(Digest),
+}
+
+/// A frame of a snapshot file.
+#[derive(Debug, Serialize, Deserialize)]
+enum SnapshotFrame {
+    /// The first frame, identifying the global state of the snapshot.
+    Header {
+        /// The version of the snapshot file format.
+        format_version: u32,
+        /// The header of the block whose global state is contained in the snapshot.
+        block_header: Box<BlockHeader>,
+    },
+    /// A chunk of trie nodes.
+    Chunk {
+        /// The index of the chunk, starting at 0.
+        index: u64,
+        /// The trie nodes, each with its hash.
+        tries: Vec<(Digest, Bytes)>,
+    },
+    /// The last frame, allowing a truncated snapshot to be detected.
+    End {
+        /// The total number of chunks.
+        chunk_count: u64,
+        /// The total number of trie nodes.
+        trie_count: u64,
+    },
+}
+
+/// A summary of an exported or imported snapshot.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct SnapshotSummary {
+    /// The hash of the block whose global state is contained in the snapshot.
+    pub(crate) block_hash: BlockHash,
+    /// The height of the block.
+    pub(crate) block_height: u64,
+    /// The state root hash of the block.
+    pub(crate) state_root_hash: Digest,
+    /// The number of chunks in the snapshot.
+    pub(crate) chunk_count: u64,
+    /// The number of trie nodes in the snapshot.
+    pub(crate) trie_count: u64,
+}
+
+/// Exports the global state under the state root hash of the given block, or of the highest
+/// complete block if `None`, to a new snapshot file at `path`.
+pub(crate) fn export_global_state(
+    config: WithDir<Config>,
+    block_hash: Option<BlockHash>,
+    path: &Path,
+) -> Result<SnapshotSummary, Error> {
+    let (storage, global_state) = open_storage_and_global_state(config)?;
+    let block_header = match block_hash {
+        Some(block_hash) => storage
+            .read_block_header(&block_hash)?
+            .ok_or(Error::NoSuchBlock(block_hash))?,
+        None => storage
+            .read_highest_complete_block()?
+            .ok_or(Error::NoCompleteBlock)?
+            .take_header(),
+    };
+
+    let file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .map_err(|error| io_error(path, error))?;
+    let mut writer = BufWriter::new(file);
+    let summary = write_snapshot(&global_state, block_header, &mut writer)?;
+    writer
+        .into_inner()
+        .map_err(|error| io_error(path, error.into_error()))?
+        .sync_all()
+        .map_err(|error| io_error(path, error))?;
+
+    info!(
+        path = %path.display(),
+        block_hash = %summary.block_hash,
+        state_root_hash = %summary.state_root_hash,
+        trie_count = summary.trie_count,
+        "exported global state snapshot"
+    );
+    Ok(summary)
+}
+
+/// Imports the global state from the snapshot file at `path`.
+///
+/// If `trusted_block_hash` is given, the snapshot must be of the global state of that block.
+pub(crate) fn import_global_state(
+    config: WithDir<Config>,
+    trusted_block_hash: Option<BlockHash>,
+    path: &Path,
+) -> Result<SnapshotSummary, Error> {
+    let (_storage, global_state) = open_storage_and_global_state(config)?;
+
+    let file = File::open(path).map_err(|error| io_error(path, error))?;
+    let summary = read_snapshot(&global_state, trusted_block_hash, BufReader::new(file))?;
+
+    info!(
+        path = %path.display(),
+        block_hash = %summary.block_hash,
+        state_root_hash = %summary.state_root_hash,
+        trie_count = summary.trie_count,
+        "imported global state snapshot; start the node with this block hash as its trusted hash"
+    );
+    Ok(summary)
+}
+
+/// Opens the block storage and the global state of the node with the given config.
+fn open_storage_and_global_state(
+    config: WithDir<Config>,
+) -> Result<(Storage, LmdbGlobalState), Error> {
+    let (root_dir, config) = config.into_parts();
+    let (chainspec, _) =
+        <(Chainspec, ChainspecRawBytes)>::from_path(&root_dir).map_err(Error::LoadChainspec)?;
+
+    let storage = Storage::new(
+        &WithDir::new(&root_dir, config.storage.clone()),
+        None,
+        chainspec.protocol_config.version,
+        chainspec.protocol_config.activation_point.era_id(),
+        &chainspec.network_config.name,
+        chainspec.deploy_config.max_ttl.into(),
+        chainspec.core_config.recent_era_count(),
+        None,
+        false,
+    )?;
+    let global_state = open_global_state(storage.root_path(), &config.contract_runtime)?;
+    Ok((storage, global_state))
+}
+
+/// Opens the global state stored in `storage_dir`.
+pub(crate) fn open_global_state(
+    storage_dir: &Path,
+    contract_runtime_config: &ContractRuntimeConfig,
+) -> Result<LmdbGlobalState, GlobalStateError> {
+    let environment = Arc::new(LmdbEnvironment::new(
+        storage_dir,
+        contract_runtime_config.max_global_state_size_or_default(),
+        contract_runtime_config.max_readers_or_default(),
+        contract_runtime_config.manual_sync_enabled_or_default(),
+    )?);
+    let trie_store = Arc::new(LmdbTrieStore::new(
+        &environment,
+        None,
+        DatabaseFlags::empty(),
+    )?);
+    LmdbGlobalState::empty(environment, trie_store)
+}
+
+/// Writes a snapshot of the global state under the block's state root hash.
+fn write_snapshot<W: Write>(
+    global_state: &LmdbGlobalState,
+    block_header: BlockHeader,
+    writer: &mut W,
+) -> Result<SnapshotSummary, Error> {
+    let correlation_id = CorrelationId::new();
+    let block_hash = block_header.block_hash();
+    let block_height = block_header.height();
+    let state_root_hash = *block_header.state_root_hash();
+
+    writer.write_all(SNAPSHOT_MAGIC).map_err(Error::Write)?;
+    write_frame(
+        writer,
+        &SnapshotFrame::Header {
+            format_version: SNAPSHOT_FORMAT_VERSION,
+            block_header: Box::new(block_header),
+        },
+    )?;
+
+    let mut chunk_count = 0;
+    let mut trie_count = 0;
+    let mut chunk = Vec::new();
+    let mut chunk_size = 0;
+
+    // A depth-first traversal emitting each trie node after all of its children.  Each stack entry
+    // is the hash of a trie node, and its bytes once its children have been pushed.
+    let mut stack: Vec<(Digest, Option<Bytes>)> = vec![(state_root_hash, None)];
+    while let Some((digest, maybe_bytes)) = stack.pop() {
+        let bytes = match maybe_bytes {
+            Some(bytes) => bytes,
+            None => {
+                let bytes = global_state
+                    .get_trie_full(correlation_id, &digest)?
+                    .ok_or(Error::MissingTrie(digest))?
+                    .into_inner();
+                let trie: Trie<Key, StoredValue> = bytesrepr::deserialize_from_slice(&bytes)
+                    .map_err(|error| Error::ParseTrie { digest, error })?;
+                let children: Vec<Digest> = trie.iter_children().collect();
+                if !children.is_empty() {
+                    stack.push((digest, Some(bytes)));
+                    stack.extend(children.into_iter().map(|child| (child, None)));
+                    continue;
+                }
+                bytes
+            }
+        };
+
+        chunk_size += bytes.len();
+        chunk.push((digest, bytes));
+        trie_count += 1;
+        if chunk_size >= MAX_CHUNK_SIZE {
+            write_frame(
+                writer,
+                &SnapshotFrame::Chunk {
+                    index: chunk_count,
+                    tries: std::mem::take(&mut chunk),
+                },
+            )?;
+            chunk_count += 1;
+            chunk_size = 0;
+        }
+    }
+    if !chunk.is_empty() {
+        write_frame(
+            writer,
+            &SnapshotFrame::Chunk {
+                index: chunk_count,
+                tries: chunk,
+            },
+        )?;
+        chunk_count += 1;
+    }
+    write_frame(
+        writer,
+        &SnapshotFrame::End {
+            chunk_count,
+            trie_count,
+        },
+    )?;
+
+    Ok(SnapshotSummary {
+        block_hash,
+        block_height,
+        state_root_hash,
+        chunk_count,
+        trie_count,
+    })
+}
+
+/// Reads a snapshot, validating and storing every trie node it contains in the global state.
+fn read_snapshot<R: Read>(
+    global_state: &LmdbGlobalState,
+    trusted_block_hash: Option<BlockHash>,
+    mut reader: R,
+) -> Result<SnapshotSummary, Error> {
+    let correlation_id = CorrelationId::new();
+
+    let mut magic = [0; SNAPSHOT_MAGIC.len()];
+    reader
+        .read_exact(&mut magic)
+        .map_err(|_| Error::InvalidMagic)?;
+    if &magic != SNAPSHOT_MAGIC {
+        return Err(Error::InvalidMagic);
+    }
+
+    let mut frame_index = 0;
+    let block_header = match read_frame(&mut reader, frame_index)? {
+        SnapshotFrame::Header {
+            format_version,
+            block_header,
+        } => {
+            if format_version != SNAPSHOT_FORMAT_VERSION {
+                return Err(Error::UnsupportedFormatVersion(format_version));
+            }
+            block_header
+        }
+        _ => {
+            return Err(Error::UnexpectedFrame {
+                index: frame_index,
+                reason: "expected header",
+            })
+        }
+    };
+    let block_hash = block_header.block_hash();
+    if let Some(trusted) = trusted_block_hash {
+        if trusted != block_hash {
+            return Err(Error::UntrustedBlock {
+                trusted,
+                actual: block_hash,
+            });
+        }
+    }
+    let state_root_hash = *block_header.state_root_hash();
+
+    let mut chunk_count = 0;
+    let mut trie_count = 0;
+    loop {
+        frame_index += 1;
+        match read_frame(&mut reader, frame_index)? {
+            SnapshotFrame::Header { .. } => {
+                return Err(Error::UnexpectedFrame {
+                    index: frame_index,
+                    reason: "duplicate header",
+                })
+            }
+            SnapshotFrame::Chunk { index, tries } => {
+                if index != chunk_count {
+                    return Err(Error::UnexpectedFrame {
+                        index: frame_index,
+                        reason: "chunk out of order",
+                    });
+                }
+                for (expected, bytes) in tries {
+                    let actual = Digest::hash(&bytes);
+                    if actual != expected {
+                        return Err(Error::InvalidTrieHash { expected, actual });
+                    }
+                    if !global_state
+                        .missing_children(correlation_id, &bytes)?
+                        .is_empty()
+                    {
+                        return Err(Error::MissingTrieChildren(expected));
+                    }
+                    global_state.put_trie(correlation_id, &bytes)?;
+                    trie_count += 1;
+                }
+                chunk_count += 1;
+            }
+            SnapshotFrame::End {
+                chunk_count: expected_chunks,
+                trie_count: expected,
+            } => {
+                if expected != trie_count || expected_chunks != chunk_count {
+                    return Err(Error::CountMismatch {
+                        expected,
+                        expected_chunks,
+                        actual: trie_count,
+                        actual_chunks: chunk_count,
+                    });
+                }
+                break;
+            }
+        }
+    }
+
+    // Every trie node was only stored once all of its children were, so the global state is
+    // complete if its root is present.
+    if global_state
+        .get_trie_full(correlation_id, &state_root_hash)?
+        .is_none()
+    {
+        return Err(Error::IncompleteSnapshot(state_root_hash));
+    }
+
+    Ok(SnapshotSummary {
+        block_hash,
+        block_height: block_header.height(),
+        state_root_hash,
+        chunk_count,
+        trie_count,
+    })
+}
+
+fn write_frame<W: Write>(writer: &mut W, frame: &SnapshotFrame) -> Result<(), Error> {
+    let payload = bincode::serialize(frame)?;
+    let length = u32::try_from(payload.len())
+        .ok()
+        .filter(|length| *length <= MAX_FRAME_SIZE)
+        .ok_or(Error::FrameTooLarge(u32::MAX))?;
+    let checksum = Digest::hash(&payload);
+    writer
+        .write_all(&length.to_le_bytes())
+        .and_then(|()| writer.write_all(checksum.as_ref()))
+        .and_then(|()| writer.write_all(&payload))
+        .map_err(Error::Write)
+}
+
+fn read_frame<R: Read>(reader: &mut R, index: u64) -> Result<SnapshotFrame, Error> {
+    let truncated = |_| Error::UnexpectedFrame {
+        index,
+        reason: "snapshot truncated",
+    };
+    let mut length = [0; 4];
+    reader.read_exact(&mut length).map_err(truncated)?;
+    let length = u32::from_le_bytes(length);
+    if length > MAX_FRAME_SIZE {
+        return Err(Error::FrameTooLarge(length));
+    }
+    let mut checksum = [0; Digest::LENGTH];
+    reader.read_exact(&mut checksum).map_err(truncated)?;
+    let mut payload = vec![0; length as usize];
+    reader.read_exact(&mut payload).map_err(truncated)?;
+    if Digest::hash(&payload) != Digest::from(checksum) {
+        return Err(Error::InvalidChecksum { index });
+    }
+    Ok(bincode::deserialize(&payload)?)
+}
+
+fn io_error(path: &Path, error: io::Error) -> Error {
+    Error::Io {
+        path: path.display().to_string(),
+        error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use rand::Rng;
+
+    use casper_types::{testing::TestRng, CLValue, EraId, ProtocolVersion, Timestamp, U512};
+
+    use super::*;
+    use crate::types::{Block, FinalizedBlock};
+
+    fn new_global_state(dir: &Path) -> LmdbGlobalState {
+        open_global_state(dir, &ContractRuntimeConfig::default()).unwrap()
+    }
+
+    /// Returns a populated global state and the header of a block committing to it.
+    fn populated_global_state(rng: &mut TestRng, dir: &Path) -> (LmdbGlobalState, BlockHeader) {
+        let global_state = new_global_state(dir);
+        let stored_values: HashMap<Key, StoredValue> = (0..500u64)
+            .map(|index| {
+                let key = Key::Balance(rng.gen());
+                let value = StoredValue::CLValue(CLValue::from_t(U512::from(index)).unwrap());
+                (key, value)
+            })
+            .collect();
+        let state_root_hash = global_state
+            .put_stored_values(
+                CorrelationId::new(),
+                global_state.empty_state_root_hash(),
+                stored_values,
+            )
+            .unwrap();
+        let finalized_block = FinalizedBlock::random_with_specifics(
+            rng,
+            EraId::new(1),
+            10,
+            false,
+            Timestamp::now(),
+            None,
+        );
+        let block = Block::new(
+            BlockHash::new(Digest::hash([1])),
+            Digest::hash([2]),
+            state_root_hash,
+            finalized_block,
+            None,
+            ProtocolVersion::V1_0_0,
+        )
+        .unwrap();
+        (global_state, block.take_header())
+    }
+
+    fn export(global_state: &LmdbGlobalState, block_header: &BlockHeader) -> Vec<u8> {
+        let mut snapshot = Vec::new();
+        write_snapshot(global_state, block_header.clone(), &mut snapshot).unwrap();
+        snapshot
+    }
+
+    #[test]
+    fn should_export_and_import_snapshot() {
+        let mut rng = crate::new_rng();
+        let source_dir = tempfile::tempdir().unwrap();
+        let (source, block_header) = populated_global_state(&mut rng, source_dir.path());
+        let snapshot = export(&source, &block_header);
+
+        let target_dir = tempfile::tempdir().unwrap();
+        let target = new_global_state(target_dir.path());
+        let summary = read_snapshot(
+            &target,
+            Some(block_header.block_hash()),
+            snapshot.as_slice(),
+        )
+        .unwrap();
+        assert_eq!(summary.block_hash, block_header.block_hash());
+        assert_eq!(summary.state_root_hash, *block_header.state_root_hash());
+        assert!(summary.trie_count > 500);
+
+        // The imported global state should be identical to the exported one.
+        let diff = target
+            .diff(
+                CorrelationId::new(),
+                target.empty_state_root_hash(),
+                summary.state_root_hash,
+                &[],
+                &[],
+                usize::MAX,
+            )
+            .unwrap();
+        let expected = source
+            .diff(
+                CorrelationId::new(),
+                source.empty_state_root_hash(),
+                summary.state_root_hash,
+                &[],
+                &[],
+                usize::MAX,
+            )
+            .unwrap();
+        assert_eq!(diff, expected);
+    }
+
+    #[test]
+    fn should_reject_snapshot_of_untrusted_block() {
+        let mut rng = crate::new_rng();
+        let source_dir = tempfile::tempdir().unwrap();
+        let (source, block_header) = populated_global_state(&mut rng, source_dir.path());
+        let snapshot = export(&source, &block_header);
+
+        let target_dir = tempfile::tempdir().unwrap();
+        let target = new_global_state(target_dir.path());
+        let trusted = BlockHash::new(Digest::hash([3]));
+        let result = read_snapshot(&target, Some(trusted), snapshot.as_slice());
+        assert!(matches!(result, Err(Error::UntrustedBlock { .. })));
+    }
+
+    #[test]
+    fn should_reject_corrupt_snapshot() {
+        let mut rng = crate::new_rng();
+        let source_dir = tempfile::tempdir().unwrap();
+        let (source, block_header) = populated_global_state(&mut rng, source_dir.path());
+        let mut snapshot = export(&source, &block_header);
+
+        let index = rng.gen_range(SNAPSHOT_MAGIC.len()..snapshot.len());
+        snapshot[index] ^= 1;
+
+        let target_dir = tempfile::tempdir().unwrap();
+        let target = new_global_state(target_dir.path());
+        assert!(read_snapshot(&target, None, snapshot.as_slice()).is_err());
+    }
+
+    #[test]
+    fn should_reject_truncated_snapshot() {
+        let mut rng = crate::new_rng();
+        let source_dir = tempfile::tempdir().unwrap();
+        let (source, block_header) = populated_global_state(&mut rng, source_dir.path());
+        let snapshot = export(&source, &block_header);
+
+        let target_dir = tempfile::tempdir().unwrap();
+        let target = new_global_state(target_dir.path());
+        let truncated = &snapshot[..snapshot.len() - 1];
+        let result = read_snapshot(&target, None, truncated);
+        assert!(matches!(result, Err(Error::UnexpectedFrame { .. })));
+    }
+}
@@ -22,6 +22,7 @@
 )]
 #![allow(clippy::bool_comparison)]
 
+mod block_archive;
 pub mod cli;
 pub(crate) mod components;
 mod config_migration;
@@ -30,9 +31,11 @@ pub(crate) mod effect;
 #[cfg_attr(not(feature = "failpoints"), path = "failpoints_disabled.rs")]
 pub(crate) mod failpoints;
 
+mod global_state_snapshot;
 pub mod logging;
 pub(crate) mod protocol;
 pub(crate) mod reactor;
+mod signer;
 #[cfg(test)]
 pub(crate) mod testing;
 pub(crate) mod tls;
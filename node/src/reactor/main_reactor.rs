@@ -38,6 +38,7 @@ use crate::{
         contract_runtime::ContractRuntime,
         deploy_acceptor::{self, DeployAcceptor},
         deploy_buffer::{self, DeployBuffer},
+        deploy_staging::DeployStaging,
         diagnostics_port::DiagnosticsPort,
         event_stream_server::{self, EventStreamServer},
         gossiper::{self, GossipItem, Gossiper},
@@ -72,6 +73,7 @@ use crate::{
         main_reactor::{fetchers::Fetchers, upgrade_shutdown::SignatureGossipTracker},
         EventQueueHandle, QueueKind,
     },
+    signer::{ProtectedSigner, Signer, SlashingProtection},
     types::{
         Block, BlockHash, Chainspec, ChainspecRawBytes, Deploy, FinalitySignature, MetaBlock,
         MetaBlockState, SyncHandling, TrieOrChunk, ValidatorMatrix,
@@ -84,6 +86,10 @@ pub(crate) use error::Error;
 pub(crate) use event::MainEvent;
 pub(crate) use reactor_state::ReactorState;
 
+/// The number of most recent stored blocks whose finality signatures by the validator seed the
+/// slashing protection database when it has no record of the validator yet.
+const SLASHING_PROTECTION_CHECKED_BLOCKS: usize = 1_000;
+
 /// Main node reactor.
 ///
 /// This following diagram represents how the components involved in the **sync process** interact
@@ -152,6 +158,7 @@ pub(crate) struct MainReactor {
     // deploy handling
     deploy_acceptor: DeployAcceptor,
     deploy_buffer: DeployBuffer,
+    deploy_staging: DeployStaging,
 
     // gossiping components
     address_gossiper: Gossiper<{ GossipedAddress::ID_IS_COMPLETE_ITEM }, GossipedAddress>,
@@ -365,6 +372,7 @@ impl reactor::Reactor for MainReactor {
                         );
                         effects.extend(self.dispatch_event(effect_builder, rng, event));
                     }
+                    PeerBehaviorAnnouncement::MisbehaviorObserved { .. } => {}
                 }
                 effects.extend(self.dispatch_event(
                     effect_builder,
@@ -799,6 +807,13 @@ impl reactor::Reactor for MainReactor {
             MainEvent::DeployBufferRequest(req) => {
                 self.dispatch_event(effect_builder, rng, MainEvent::DeployBuffer(req.into()))
             }
+            MainEvent::DeployStaging(event) => reactor::wrap_effects(
+                MainEvent::DeployStaging,
+                self.deploy_staging.handle_event(effect_builder, rng, event),
+            ),
+            MainEvent::DeployStagingRequest(req) => {
+                self.dispatch_event(effect_builder, rng, MainEvent::DeployStaging(req.into()))
+            }
             MainEvent::DeployBufferAnnouncement(DeployBufferAnnouncement::DeploysExpired(
                 hashes,
             )) => {
@@ -888,6 +903,9 @@ impl reactor::Reactor for MainReactor {
             // states of a block after an upgrade and its parent. Once that happens, we can check
             // for the signs of any changes happening during the upgrade and register the correct
             // set of validators in the validators matrix.
+            MainEvent::CreatedFinalitySignature(finality_signature) => {
+                self.handle_created_finality_signature(effect_builder, rng, *finality_signature)
+            }
             MainEvent::GotBlockAfterUpgradeEraValidators(
                 era_id,
                 parent_era_validators,
@@ -1004,20 +1022,6 @@ impl reactor::Reactor for MainReactor {
 
         let trusted_hash = config.value().node.trusted_hash;
         let (root_dir, config) = config.into_parts();
-        let (our_secret_key, our_public_key) = config.consensus.load_keys(&root_dir)?;
-        let validator_matrix = ValidatorMatrix::new(
-            chainspec.core_config.finality_threshold_fraction,
-            chainspec
-                .protocol_config
-                .global_state_update
-                .as_ref()
-                .and_then(|global_state_update| global_state_update.validators.clone()),
-            chainspec.protocol_config.activation_point.era_id(),
-            our_secret_key.clone(),
-            our_public_key.clone(),
-            chainspec.core_config.auction_delay,
-        );
-
         let storage_config = WithDir::new(&root_dir, config.storage.clone());
 
         let hard_reset_to_start_of_era = chainspec.hard_reset_to_start_of_era();
@@ -1033,6 +1037,41 @@ impl reactor::Reactor for MainReactor {
             config.node.force_resync,
         )?;
 
+        let signer = config.consensus.load_signer(&root_dir, protocol_version)?;
+        let slashing_protection_path = root_dir.join(&config.consensus.slashing_protection_path);
+        let mut slashing_protection = SlashingProtection::open(slashing_protection_path)?;
+        // A database created on a node which has been validating before, or replacing a lost one,
+        // has no record of what was signed, so refuse to sign finality signatures conflicting with
+        // the stored ones.
+        if !slashing_protection.has_signed(signer.public_key()) {
+            let highest_heights = storage.read_highest_heights_signed_by(
+                signer.public_key(),
+                SLASHING_PROTECTION_CHECKED_BLOCKS,
+            )?;
+            if !highest_heights.is_empty() {
+                info!(
+                    ?highest_heights,
+                    "seeding slashing protection database from stored finality signatures"
+                );
+                slashing_protection
+                    .seed_finality_signatures(signer.public_key(), highest_heights)?;
+            }
+        }
+        let signer: Arc<dyn Signer> = Arc::new(ProtectedSigner::new(signer, slashing_protection));
+        let our_public_key = signer.public_key().clone();
+        let validator_matrix = ValidatorMatrix::new(
+            chainspec.core_config.finality_threshold_fraction,
+            chainspec
+                .protocol_config
+                .global_state_update
+                .as_ref()
+                .and_then(|global_state_update| global_state_update.validators.clone()),
+            chainspec.protocol_config.activation_point.era_id(),
+            Arc::clone(&signer),
+            our_public_key.clone(),
+            chainspec.core_config.auction_delay,
+        );
+
         let max_delegators_per_validator =
             if chainspec.core_config.max_delegators_per_validator == 0 {
                 None
@@ -1040,6 +1079,9 @@ impl reactor::Reactor for MainReactor {
                 Some(chainspec.core_config.max_delegators_per_validator)
             };
 
+        config
+            .contract_runtime
+            .validate_state_retention(&config.node.sync_handling, &chainspec)?;
         let contract_runtime = ContractRuntime::new(
             protocol_version,
             storage.root_path(),
@@ -1051,6 +1093,7 @@ impl reactor::Reactor for MainReactor {
             chainspec.core_config.minimum_delegation_amount,
             chainspec.protocol_config.activation_point,
             chainspec.core_config.prune_batch_size,
+            chainspec.deploy_config.block_gas_limit,
             chainspec.core_config.strict_argument_checking,
             chainspec.core_config.vesting_schedule_period.millis(),
             max_delegators_per_validator,
@@ -1065,7 +1108,7 @@ impl reactor::Reactor for MainReactor {
         let network = Network::new(
             config.network.clone(),
             network_identity,
-            Some((our_secret_key.clone(), our_public_key.clone())),
+            Some(Arc::clone(&signer)),
             registry,
             chainspec.as_ref(),
             validator_matrix.clone(),
@@ -1124,7 +1167,7 @@ impl reactor::Reactor for MainReactor {
         // consensus
         let consensus = EraSupervisor::new(
             storage.root_path(),
-            our_secret_key,
+            signer,
             our_public_key,
             config.consensus,
             chainspec.clone(),
@@ -1151,6 +1194,8 @@ impl reactor::Reactor for MainReactor {
         let block_validator = BlockValidator::new(Arc::clone(&chainspec), config.block_validator);
         let upgrade_watcher =
             UpgradeWatcher::new(chainspec.as_ref(), config.upgrade_watcher, &root_dir)?;
+        let deploy_staging =
+            DeployStaging::new(chainspec.as_ref(), config.deploy_acceptor.timestamp_leeway);
         let deploy_acceptor =
             DeployAcceptor::new(config.deploy_acceptor, chainspec.as_ref(), registry)?;
         let deploy_buffer =
@@ -1176,6 +1221,7 @@ impl reactor::Reactor for MainReactor {
             finality_signature_gossiper,
             sync_leaper,
             deploy_buffer,
+            deploy_staging,
             consensus,
             block_validator,
             block_accumulator,
@@ -1249,6 +1295,47 @@ impl MainReactor {
         effects
     }
 
+    fn handle_created_finality_signature(
+        &mut self,
+        effect_builder: EffectBuilder<MainEvent>,
+        rng: &mut NodeRng,
+        finality_signature: FinalitySignature,
+    ) -> Effects<MainEvent> {
+        debug!(
+            %finality_signature,
+            "MetaBlock: registering finality signature: {}",
+            finality_signature.block_hash,
+        );
+
+        let mut effects = reactor::wrap_effects(
+            MainEvent::Storage,
+            effect_builder
+                .put_finality_signature_to_storage(finality_signature.clone())
+                .ignore(),
+        );
+
+        effects.extend(reactor::wrap_effects(
+            MainEvent::BlockAccumulator,
+            self.block_accumulator.handle_event(
+                effect_builder,
+                rng,
+                block_accumulator::Event::CreatedFinalitySignature {
+                    finality_signature: Box::new(finality_signature.clone()),
+                },
+            ),
+        ));
+
+        let era_id = finality_signature.era_id;
+        let payload = Message::FinalitySignature(Box::new(finality_signature));
+        effects.extend(reactor::wrap_effects(
+            MainEvent::Network,
+            effect_builder
+                .broadcast_message_to_validators(payload, era_id)
+                .ignore(),
+        ));
+        effects
+    }
+
     fn handle_meta_block(
         &mut self,
         effect_builder: EffectBuilder<MainEvent>,
@@ -1344,43 +1431,22 @@ impl MainReactor {
 
         if state.register_we_have_tried_to_sign().was_updated() {
             // When this node is a validator in this era, sign and announce.
-            if let Some(finality_signature) = self
+            if let Some(create_signature) = self
                 .validator_matrix
                 .create_finality_signature(block.header())
             {
                 debug!(
-                    %finality_signature,
-                    "MetaBlock: registering finality signature: {} {}",
+                    "MetaBlock: creating finality signature: {} {}",
                     block.height(),
                     block.hash(),
                 );
-
-                effects.extend(reactor::wrap_effects(
-                    MainEvent::Storage,
-                    effect_builder
-                        .put_finality_signature_to_storage(finality_signature.clone())
-                        .ignore(),
-                ));
-
-                effects.extend(reactor::wrap_effects(
-                    MainEvent::BlockAccumulator,
-                    self.block_accumulator.handle_event(
-                        effect_builder,
-                        rng,
-                        block_accumulator::Event::CreatedFinalitySignature {
-                            finality_signature: Box::new(finality_signature.clone()),
-                        },
-                    ),
-                ));
-
-                let era_id = finality_signature.era_id;
-                let payload = Message::FinalitySignature(Box::new(finality_signature));
-                effects.extend(reactor::wrap_effects(
-                    MainEvent::Network,
-                    effect_builder
-                        .broadcast_message_to_validators(payload, era_id)
-                        .ignore(),
-                ));
+                effects.extend(create_signature.events(|maybe_finality_signature| {
+                    maybe_finality_signature
+                        .map(|finality_signature| {
+                            MainEvent::CreatedFinalitySignature(Box::new(finality_signature))
+                        })
+                        .into_iter()
+                }));
             }
         }
 
@@ -1494,6 +1560,10 @@ impl MainReactor {
             return effects;
         }
 
+        // Blocks synced rather than executed are not otherwise known to the state pruner.
+        self.contract_runtime
+            .register_block_state(block.height(), *block.state_root_hash());
+
         debug!(
             "MetaBlock: notifying event stream: {} {}",
             block.height(),
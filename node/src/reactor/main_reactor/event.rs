@@ -14,7 +14,7 @@ use crate::{
         block_accumulator,
         block_synchronizer::{self, GlobalStateSynchronizerEvent, TrieAccumulatorEvent},
         block_validator, consensus, contract_runtime, deploy_acceptor, deploy_buffer,
-        diagnostics_port, event_stream_server, fetcher, gossiper,
+        deploy_staging, diagnostics_port, event_stream_server, fetcher, gossiper,
         network::{self, GossipedAddress},
         rest_server, rpc_server, shutdown_trigger, storage, sync_leaper, upgrade_watcher,
     },
@@ -35,8 +35,8 @@ use crate::{
         requests::{
             AcceptDeployRequest, BeginGossipRequest, BlockAccumulatorRequest,
             BlockSynchronizerRequest, BlockValidationRequest, ChainspecRawBytesRequest,
-            ConsensusRequest, ContractRuntimeRequest, DeployBufferRequest, FetcherRequest,
-            MakeBlockExecutableRequest, MarkBlockCompletedRequest, MetricsRequest,
+            ConsensusRequest, ContractRuntimeRequest, DeployBufferRequest, DeployStagingRequest,
+            FetcherRequest, MakeBlockExecutableRequest, MarkBlockCompletedRequest, MetricsRequest,
             NetworkInfoRequest, NetworkRequest, ReactorStatusRequest, RestRequest, RpcRequest,
             SetNodeStopRequest, StorageRequest, SyncGlobalStateRequest, TrieAccumulatorRequest,
             UpgradeWatcherRequest,
@@ -209,6 +209,10 @@ pub(crate) enum MainEvent {
     #[from]
     DeployBufferRequest(DeployBufferRequest),
     #[from]
+    DeployStaging(#[serde(skip_serializing)] deploy_staging::Event),
+    #[from]
+    DeployStagingRequest(DeployStagingRequest),
+    #[from]
     ContractRuntime(contract_runtime::Event),
     #[from]
     ContractRuntimeRequest(ContractRuntimeRequest),
@@ -247,6 +251,9 @@ pub(crate) enum MainEvent {
 
     // Event related to figuring out validators for blocks after upgrades.
     GotBlockAfterUpgradeEraValidators(EraId, EraValidators, EraValidators),
+
+    /// Our finality signature has been created.
+    CreatedFinalitySignature(Box<FinalitySignature>),
 }
 
 impl ReactorEvent for MainEvent {
@@ -269,6 +276,7 @@ impl ReactorEvent for MainEvent {
             MainEvent::Network(_) => "Network",
             MainEvent::SyncLeaper(_) => "SyncLeaper",
             MainEvent::DeployBuffer(_) => "DeployBuffer",
+            MainEvent::DeployStaging(_) => "DeployStaging",
             MainEvent::Storage(_) => "Storage",
             MainEvent::RpcServer(_) => "RpcServer",
             MainEvent::RestServer(_) => "RestServer",
@@ -307,6 +315,7 @@ impl ReactorEvent for MainEvent {
             MainEvent::SyncLeapFetcherRequest(_) => "SyncLeapFetcherRequest",
             MainEvent::ApprovalsHashesFetcherRequest(_) => "ApprovalsHashesFetcherRequest",
             MainEvent::DeployBufferRequest(_) => "DeployBufferRequest",
+            MainEvent::DeployStagingRequest(_) => "DeployStagingRequest",
             MainEvent::BlockValidatorRequest(_) => "BlockValidatorRequest",
             MainEvent::MetricsRequest(_) => "MetricsRequest",
             MainEvent::ChainspecRawBytesRequest(_) => "ChainspecRawBytesRequest",
@@ -362,6 +371,7 @@ impl ReactorEvent for MainEvent {
             MainEvent::GotBlockAfterUpgradeEraValidators(_, _, _) => {
                 "GotImmediateSwitchBlockEraValidators"
             }
+            MainEvent::CreatedFinalitySignature(_) => "CreatedFinalitySignature",
         }
     }
 }
@@ -374,6 +384,7 @@ impl Display for MainEvent {
             MainEvent::Network(event) => write!(f, "network: {}", event),
             MainEvent::SyncLeaper(event) => write!(f, "sync leaper: {}", event),
             MainEvent::DeployBuffer(event) => write!(f, "deploy buffer: {}", event),
+            MainEvent::DeployStaging(event) => write!(f, "deploy staging: {}", event),
             MainEvent::RpcServer(event) => write!(f, "rpc server: {}", event),
             MainEvent::RestServer(event) => write!(f, "rest server: {}", event),
             MainEvent::EventStreamServer(event) => {
@@ -477,6 +488,9 @@ impl Display for MainEvent {
             MainEvent::DeployBufferRequest(req) => {
                 write!(f, "deploy buffer request: {}", req)
             }
+            MainEvent::DeployStagingRequest(req) => {
+                write!(f, "deploy staging request: {}", req)
+            }
             MainEvent::BlockValidatorRequest(req) => {
                 write!(f, "block validator request: {}", req)
             }
@@ -546,6 +560,9 @@ impl Display for MainEvent {
                     era_id
                 )
             }
+            MainEvent::CreatedFinalitySignature(finality_signature) => {
+                write!(f, "created finality signature: {}", finality_signature)
+            }
         }
     }
 }
@@ -46,6 +46,7 @@ enum SyncBackInstruction {
     Syncing,
     TtlSynced,
     GenesisSynced,
+    StatePruned,
     NoSync,
 }
 
@@ -58,6 +59,7 @@ impl Display for SyncBackInstruction {
             SyncBackInstruction::Syncing => write!(f, "syncing"),
             SyncBackInstruction::TtlSynced => write!(f, "ttl reached"),
             SyncBackInstruction::GenesisSynced => write!(f, "genesis reached"),
+            SyncBackInstruction::StatePruned => write!(f, "pruned global state reached"),
             SyncBackInstruction::NoSync => write!(f, "configured to not sync"),
         }
     }
@@ -288,7 +290,8 @@ impl MainReactor {
             Ok(Some(sbi @ sync_back_instruction)) => match sync_back_instruction {
                 SyncBackInstruction::NoSync
                 | SyncBackInstruction::GenesisSynced
-                | SyncBackInstruction::TtlSynced => {
+                | SyncBackInstruction::TtlSynced
+                | SyncBackInstruction::StatePruned => {
                     // we don't need to sync any historical blocks currently, so we clear both the
                     // historical synchronizer and the sync back leap activity since they will not
                     // be required anymore
@@ -651,6 +654,11 @@ impl MainReactor {
             return Ok(Some(SyncBackInstruction::GenesisSynced));
         }
 
+        // global state below the pruned height is not retained, so syncing it would be wasted.
+        if highest_orphaned_block_header.height() <= self.storage.state_pruned_below_height() {
+            return Ok(Some(SyncBackInstruction::StatePruned));
+        }
+
         if self.sync_handling.is_sync_to_genesis() {
             return Ok(None);
         }
@@ -1,4 +1,6 @@
-use std::{collections::BTreeMap, iter, net::SocketAddr, str::FromStr, sync::Arc, time::Duration};
+use std::{
+    collections::BTreeMap, fs, iter, net::SocketAddr, str::FromStr, sync::Arc, time::Duration,
+};
 
 use either::Either;
 use num::Zero;
@@ -260,6 +262,7 @@ impl TestFixture {
                 .expect("could not write secret key");
             cfg.consensus.secret_key_path = External::Path(secret_key_path);
         }
+        cfg.consensus.slashing_protection_path = temp_dir.path().join("slashing_protection.log");
         cfg.storage = storage_cfg;
         cfg.node.trusted_hash = maybe_trusted_hash;
 
@@ -1171,3 +1174,58 @@ async fn network_should_recover_from_stall() {
     // Ensure all nodes progress until block 3 is marked complete.
     fixture.run_until_block_height(3, TEN_SECS).await;
 }
+
+#[tokio::test]
+async fn should_start_with_stored_signatures_and_no_slashing_protection() {
+    let initial_stakes = InitialStakes::AllEqual {
+        count: 3,
+        stake: 100,
+    };
+    let mut fixture = TestFixture::new(initial_stakes, None).await;
+    fixture.run_until_block_height(2, ONE_MIN).await;
+
+    // Let node 1 store a finality signature of its own.
+    let node_1 = fixture.node_contexts[1].id;
+    let public_key_1 = PublicKey::from(&*fixture.node_contexts[1].secret_key);
+    fixture
+        .run_until(
+            |nodes: &Nodes| {
+                !nodes
+                    .get(&node_1)
+                    .expect("should have node 1")
+                    .main_reactor()
+                    .storage()
+                    .read_highest_heights_signed_by(&public_key_1, 1_000)
+                    .expect("should not error reading db")
+                    .is_empty()
+            },
+            ONE_MIN,
+        )
+        .await;
+
+    // Restart it without its slashing protection database, as when upgrading from a version
+    // without one.
+    let node_context = fixture.remove_and_stop_node(1);
+    let slashing_protection_path = node_context
+        .config
+        .consensus
+        .slashing_protection_path
+        .clone();
+    fs::remove_file(&slashing_protection_path).expect("should remove database");
+    fixture
+        .add_node(
+            node_context.secret_key,
+            node_context.config,
+            node_context.storage_dir,
+        )
+        .await;
+
+    // The database has been seeded from the stored signatures, and the network keeps going.
+    assert!(
+        fs::metadata(&slashing_protection_path)
+            .expect("should have recreated database")
+            .len()
+            > 0
+    );
+    fixture.run_until_block_height(4, ONE_MIN).await;
+}
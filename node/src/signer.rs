@@ -0,0 +1,372 @@
+//! Signing on behalf of the node's validator.
+//!
+//! The node signs consensus messages, finality signatures and the certificates proving its
+//! validator identity in network handshakes.  A [`Signer`] is either a [`LocalSigner`] holding the
+//! validator's secret key in memory, or a [`RemoteSigner`] forwarding every request to a separate
+//! signer process, so that the secret key can be kept in an isolated process or an HSM.
+//!
+//! The node and the signer process communicate over a Unix socket or a local TCP socket.  Every
+//! message is a bincode-encoded [`SignerRequest`] or [`SignerResponse`], preceded by its length
+//! as a big-endian `u32`.  The Unix socket is only accessible to the signer's user, and a signer
+//! listening on TCP requires every connection to authenticate with a shared [`AuthToken`] first.
+//!
+//! A [`Signable`] message is what the validator signs, not just the bytes to sign: the signer
+//! derives the bytes, and the position of messages which are part of a [`Sequence`], from the
+//! message itself.  Both the node and the signer process run by [`run_signer`] protect against
+//! double signing by consulting a [`SlashingProtection`] database before signing any such message.
+//!
+//! Signing with a remote signer or a slashing protection database involves I/O, so the node never
+//! calls such a signer on the reactor, but uses [`sign_async`] to sign on a blocking thread.
+
+mod remote;
+mod server;
+mod slashing_protection;
+#[cfg(test)]
+mod tests;
+
+use std::{
+    fmt::{self, Debug, Formatter},
+    fs,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use thiserror::Error;
+use tokio::task::{self, JoinError};
+
+use casper_hashing::Digest;
+use casper_types::{crypto, EraId, ProtocolVersion, PublicKey, SecretKey, Signature};
+
+use crate::{
+    components::consensus::{self, SignedMessageId},
+    types::{BlockHeader, FinalitySignature},
+};
+pub(crate) use remote::RemoteSigner;
+pub(crate) use server::run_signer;
+pub(crate) use slashing_protection::{
+    export_slashing_protection, import_slashing_protection, ProtectedSigner, SlashingProtection,
+    SLASHING_PROTECTION_FILENAME,
+};
+
+/// The maximum accepted size of a message exchanged with a signer process.
+const MAX_FRAME_SIZE: u32 = 1024 * 1024;
+/// The prefix of the bytes signed in a handshake certificate, which sets them apart from the bytes
+/// signed in any other message.
+const HANDSHAKE_CERTIFICATE_PREFIX: &[u8] = b"casper-node handshake certificate";
+/// The protocol version from which handshake certificates sign the connection ID with the handshake
+/// certificate prefix.
+///
+/// Nodes of earlier versions sign and verify the bare connection ID, so until the network activates
+/// this version, certificates are created the same way.  As peers only connect if they run the same
+/// protocol version, the network's version is also the one every connected peer runs.
+pub(crate) const PREFIXED_HANDSHAKE_CERTIFICATE_VERSION: ProtocolVersion =
+    ProtocolVersion::from_parts(1, 6, 0);
+/// The minimum length of an authentication token.
+const MIN_AUTH_TOKEN_LENGTH: usize = 16;
+
+/// Error returned by a [`Signer`].
+#[derive(Debug, Error)]
+pub(crate) enum Error {
+    /// The signer refused to sign.
+    #[error("signer refused to sign: {0}")]
+    Refused(String),
+    /// The signer process did not accept the authentication token.
+    #[error("signer authentication failed")]
+    AuthenticationFailed,
+    /// The authentication token is too short.
+    #[error("authentication token must be at least {MIN_AUTH_TOKEN_LENGTH} bytes long")]
+    AuthTokenTooShort,
+    /// Error communicating with the signer process.
+    #[error("signer connection error: {0}")]
+    Io(#[from] io::Error),
+    /// Error encoding or decoding a message.
+    #[error("invalid signer message: {0}")]
+    Serialization(#[from] bincode::Error),
+    /// A message exceeded the maximum size.
+    #[error("signer message of {0} bytes exceeds the maximum of {MAX_FRAME_SIZE} bytes")]
+    FrameTooLarge(usize),
+    /// The signer process responded with a message not matching the request.
+    #[error("unexpected response from signer")]
+    UnexpectedResponse,
+    /// The signer process responded with a signature not made with the validator's key.
+    #[error("signer returned an invalid signature")]
+    InvalidSignature,
+    /// Error encoding or decoding a slashing protection database.
+    #[error("invalid slashing protection database: {0}")]
+    Json(#[from] serde_json::Error),
+    /// The slashing protection database is in use by another process.
+    #[error("slashing protection database {} is in use by another process", .0.display())]
+    DatabaseLocked(PathBuf),
+    /// A slashing protection database in an unsupported version of the interchange format.
+    #[error("unsupported slashing protection interchange format version {0}")]
+    UnsupportedInterchangeVersion(u32),
+    /// The blocking task signing the message failed.
+    #[error("signing task failed: {0}")]
+    Task(#[from] JoinError),
+}
+
+/// A message to be signed with the validator's secret key.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum Signable {
+    /// A consensus protocol message, given by its ID and the serialized fields its hash is
+    /// computed from.  The hash is signed.
+    Consensus {
+        message_id: SignedMessageId<Digest>,
+        fields: Vec<u8>,
+    },
+    /// A finality signature for the block with the given header.
+    FinalitySignature(Box<BlockHeader>),
+    /// A certificate proving the validator's identity in the network handshake of the connection
+    /// with the given ID.
+    Handshake([u8; Digest::LENGTH]),
+    /// A handshake certificate as created by nodes before the handshake certificate prefix was
+    /// introduced, signing the bare connection ID.
+    ///
+    /// As the bare ID is indistinguishable from the hash of a consensus message, a signer process
+    /// only signs it on connections authenticated with its token.
+    LegacyHandshake([u8; Digest::LENGTH]),
+}
+
+/// A sequence of messages, in which the same validator must never sign two different messages
+/// at the same position.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "id", rename_all = "snake_case")]
+pub(crate) enum Sequence {
+    /// The units of a Highway instance, by sequence number.
+    HighwayUnits(Digest),
+    /// The echoes of a Zug instance, by round.
+    ZugEchoes(Digest),
+    /// The votes of a Zug instance, by round.
+    ZugVotes(Digest),
+    /// The finality signatures of an era, by block height.
+    FinalitySignatures(EraId),
+}
+
+impl Sequence {
+    /// Returns whether the positions at which messages are signed in the sequence only ever
+    /// increase, so that nothing new must be signed below the highest position signed at.
+    ///
+    /// A Highway unit cites its creator's previous unit, and finality signatures are created for
+    /// the blocks in the order of their execution.  Zug echoes and votes, however, may be signed
+    /// in any round which is still open.
+    fn is_increasing(&self) -> bool {
+        match self {
+            Sequence::HighwayUnits(_) | Sequence::FinalitySignatures(_) => true,
+            Sequence::ZugEchoes(_) | Sequence::ZugVotes(_) => false,
+        }
+    }
+}
+
+impl Signable {
+    /// Returns the bytes to be signed.
+    pub(crate) fn bytes_to_sign(&self) -> Vec<u8> {
+        match self {
+            Signable::Consensus { fields, .. } => Digest::hash(fields).as_ref().to_vec(),
+            Signable::FinalitySignature(block_header) => {
+                FinalitySignature::bytes_to_sign(&block_header.block_hash(), block_header.era_id())
+            }
+            Signable::Handshake(connection_id) => {
+                [HANDSHAKE_CERTIFICATE_PREFIX, connection_id.as_slice()].concat()
+            }
+            Signable::LegacyHandshake(connection_id) => connection_id.to_vec(),
+        }
+    }
+
+    /// Returns the sequence the message belongs to and its position in it, or `None` if signing
+    /// the message can't amount to equivocation.
+    ///
+    /// The position is derived from the message itself: the fields of a consensus message are
+    /// decoded, and the request is refused if they don't match the message's ID.
+    pub(crate) fn position(&self) -> Result<Option<(Sequence, u64)>, Error> {
+        let message_id = match self {
+            Signable::Consensus { message_id, fields } => {
+                let decoded_id =
+                    consensus::decode_message_id(message_id, fields).map_err(|error| {
+                        Error::Refused(format!("invalid fields of {:?}: {}", message_id, error))
+                    })?;
+                if decoded_id != *message_id {
+                    return Err(Error::Refused(format!(
+                        "fields of {:?} belong to {:?}",
+                        message_id, decoded_id
+                    )));
+                }
+                decoded_id
+            }
+            Signable::FinalitySignature(block_header) => {
+                return Ok(Some((
+                    Sequence::FinalitySignatures(block_header.era_id()),
+                    block_header.height(),
+                )));
+            }
+            Signable::Handshake(_) | Signable::LegacyHandshake(_) => return Ok(None),
+        };
+        let position = match message_id {
+            SignedMessageId::HighwayUnit {
+                instance_id,
+                seq_number,
+            } => Some((Sequence::HighwayUnits(instance_id), seq_number)),
+            SignedMessageId::ZugEcho {
+                instance_id,
+                round_id,
+            } => Some((Sequence::ZugEchoes(instance_id), round_id.into())),
+            SignedMessageId::ZugVote {
+                instance_id,
+                round_id,
+            } => Some((Sequence::ZugVotes(instance_id), round_id.into())),
+            SignedMessageId::HighwayPing { .. } | SignedMessageId::HighwayEndorsement => None,
+        };
+        Ok(position)
+    }
+}
+
+/// A secret shared by a signer process and the nodes allowed to use it.
+#[derive(Clone)]
+pub(crate) struct AuthToken(Vec<u8>);
+
+impl AuthToken {
+    /// Reads the token from the file at `path`, ignoring leading and trailing whitespace.
+    pub(crate) fn from_file(path: &Path) -> Result<Self, Error> {
+        Self::new(fs::read_to_string(path)?.trim().as_bytes().to_vec())
+    }
+
+    fn new(token: Vec<u8>) -> Result<Self, Error> {
+        if token.len() < MIN_AUTH_TOKEN_LENGTH {
+            return Err(Error::AuthTokenTooShort);
+        }
+        Ok(AuthToken(token))
+    }
+
+    /// Returns whether `presented` is this token.
+    ///
+    /// The hashes are compared rather than the tokens, so that the time taken doesn't reveal how
+    /// much of the presented token is right.
+    fn matches(&self, presented: &[u8]) -> bool {
+        Digest::hash(&self.0) == Digest::hash(presented)
+    }
+}
+
+impl Debug for AuthToken {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        formatter.write_str("AuthToken(..)")
+    }
+}
+
+/// A request sent to a signer process.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum SignerRequest {
+    /// Authenticates the connection with the given token.  If the signer process requires a
+    /// token, this must be the first request on every connection.
+    Authenticate(Vec<u8>),
+    /// Requests the validator's public key.
+    GetPublicKey,
+    /// Requests a signature of the message.
+    Sign(Signable),
+}
+
+/// A response sent by a signer process.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum SignerResponse {
+    /// The connection is authenticated.
+    Authenticated,
+    /// The validator's public key.
+    PublicKey(PublicKey),
+    /// The requested signature.
+    Signature(Signature),
+    /// The signer refused to sign, for the given reason.
+    Refused(String),
+}
+
+/// Signs messages with the validator's secret key.
+pub(crate) trait Signer: Send + Sync + Debug {
+    /// Returns the validator's public key.
+    fn public_key(&self) -> &PublicKey;
+
+    /// Signs `message`.
+    fn sign(&self, message: &Signable) -> Result<Signature, Error>;
+
+    /// Returns whether `sign` may block the calling thread, e.g. waiting for I/O.
+    fn may_block(&self) -> bool;
+}
+
+/// Signs `message` on a blocking thread if the signer may block.
+pub(crate) async fn sign_async(
+    signer: Arc<dyn Signer>,
+    message: Signable,
+) -> Result<Signature, Error> {
+    if !signer.may_block() {
+        return signer.sign(&message);
+    }
+    task::spawn_blocking(move || signer.sign(&message)).await?
+}
+
+/// A signer holding the validator's secret key in memory.
+pub(crate) struct LocalSigner {
+    secret_key: Arc<SecretKey>,
+    public_key: PublicKey,
+}
+
+impl LocalSigner {
+    /// Creates a signer using the given secret key.
+    pub(crate) fn new(secret_key: Arc<SecretKey>) -> Self {
+        let public_key = PublicKey::from(secret_key.as_ref());
+        LocalSigner {
+            secret_key,
+            public_key,
+        }
+    }
+}
+
+impl Debug for LocalSigner {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("LocalSigner")
+            .field("public_key", &self.public_key)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Signer for LocalSigner {
+    fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+
+    fn sign(&self, message: &Signable) -> Result<Signature, Error> {
+        Ok(crypto::sign(
+            message.bytes_to_sign(),
+            &self.secret_key,
+            &self.public_key,
+        ))
+    }
+
+    fn may_block(&self) -> bool {
+        false
+    }
+}
+
+/// Writes `message` to `stream`, preceded by its length.
+fn write_frame<W: Write, T: Serialize>(stream: &mut W, message: &T) -> Result<(), Error> {
+    let bytes = bincode::serialize(message)?;
+    let length = u32::try_from(bytes.len())
+        .ok()
+        .filter(|length| *length <= MAX_FRAME_SIZE)
+        .ok_or(Error::FrameTooLarge(bytes.len()))?;
+    stream.write_all(&length.to_be_bytes())?;
+    stream.write_all(&bytes)?;
+    stream.flush()?;
+    Ok(())
+}
+
+/// Reads a message preceded by its length from `stream`.
+fn read_frame<R: Read, T: DeserializeOwned>(stream: &mut R) -> Result<T, Error> {
+    let mut length_bytes = [0; 4];
+    stream.read_exact(&mut length_bytes)?;
+    let length = u32::from_be_bytes(length_bytes);
+    if length > MAX_FRAME_SIZE {
+        return Err(Error::FrameTooLarge(length as usize));
+    }
+    let mut bytes = vec![0; length as usize];
+    stream.read_exact(&mut bytes)?;
+    Ok(bincode::deserialize(&bytes)?)
+}
@@ -0,0 +1,227 @@
+//! The node's side of the connection to a signer process.
+
+use std::{
+    fmt::{self, Debug, Display, Formatter},
+    io::{self, Read, Write},
+    net::{SocketAddr, TcpStream},
+    os::unix::net::UnixStream,
+    path::PathBuf,
+    sync::{Mutex, PoisonError},
+    time::Duration,
+};
+
+use tracing::{info, warn};
+
+use casper_types::{crypto, PublicKey, Signature};
+
+use super::{
+    read_frame, write_frame, AuthToken, Error, Signable, Signer, SignerRequest, SignerResponse,
+};
+
+/// The timeout for connecting to the signer process, and for each read and write.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The address of a signer process.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) enum Address {
+    /// A local TCP socket.
+    Tcp(SocketAddr),
+    /// A Unix socket.
+    Unix(PathBuf),
+}
+
+impl Address {
+    /// Parses a socket address, treating anything which isn't a valid IP address and port as the
+    /// path of a Unix socket.
+    pub(super) fn parse(address: &str) -> Self {
+        match address.parse() {
+            Ok(socket_addr) => Address::Tcp(socket_addr),
+            Err(_) => Address::Unix(PathBuf::from(address)),
+        }
+    }
+
+    fn connect(&self) -> io::Result<Stream> {
+        let stream = match self {
+            Address::Tcp(socket_addr) => {
+                let stream = TcpStream::connect_timeout(socket_addr, TIMEOUT)?;
+                stream.set_nodelay(true)?;
+                stream.set_read_timeout(Some(TIMEOUT))?;
+                stream.set_write_timeout(Some(TIMEOUT))?;
+                Stream::Tcp(stream)
+            }
+            Address::Unix(path) => {
+                let stream = UnixStream::connect(path)?;
+                stream.set_read_timeout(Some(TIMEOUT))?;
+                stream.set_write_timeout(Some(TIMEOUT))?;
+                Stream::Unix(stream)
+            }
+        };
+        Ok(stream)
+    }
+}
+
+impl Display for Address {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Address::Tcp(socket_addr) => write!(formatter, "{}", socket_addr),
+            Address::Unix(path) => write!(formatter, "{}", path.display()),
+        }
+    }
+}
+
+/// A connection to a signer process.
+enum Stream {
+    Tcp(TcpStream),
+    Unix(UnixStream),
+}
+
+impl Read for Stream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Stream::Tcp(stream) => stream.read(buf),
+            Stream::Unix(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for Stream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Stream::Tcp(stream) => stream.write(buf),
+            Stream::Unix(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Stream::Tcp(stream) => stream.flush(),
+            Stream::Unix(stream) => stream.flush(),
+        }
+    }
+}
+
+/// Sends `request` and waits for the response.
+fn exchange(stream: &mut Stream, request: &SignerRequest) -> Result<SignerResponse, Error> {
+    write_frame(stream, request)?;
+    read_frame(stream)
+}
+
+/// Connects to the signer process at `address`, authenticating with `auth_token` if given.
+fn open(address: &Address, auth_token: Option<&AuthToken>) -> Result<Stream, Error> {
+    let mut stream = address.connect()?;
+    if let Some(auth_token) = auth_token {
+        let request = SignerRequest::Authenticate(auth_token.0.clone());
+        match exchange(&mut stream, &request)? {
+            SignerResponse::Authenticated => {}
+            SignerResponse::Refused(_) => return Err(Error::AuthenticationFailed),
+            SignerResponse::PublicKey(_) | SignerResponse::Signature(_) => {
+                return Err(Error::UnexpectedResponse)
+            }
+        }
+    }
+    Ok(stream)
+}
+
+/// A signer forwarding requests to a signer process.
+///
+/// Requests block the calling thread until the response arrives or `TIMEOUT` elapses, so they must
+/// not be made on the reactor.
+pub(crate) struct RemoteSigner {
+    address: Address,
+    auth_token: Option<AuthToken>,
+    public_key: PublicKey,
+    /// The connection, or `None` if it needs to be reestablished.
+    stream: Mutex<Option<Stream>>,
+}
+
+impl RemoteSigner {
+    /// Connects to the signer process listening on `address`, which is either a TCP socket
+    /// address or the path of a Unix socket, and retrieves the validator's public key from it.
+    ///
+    /// Every connection is authenticated with `auth_token` if given.
+    pub(crate) fn connect(address: &str, auth_token: Option<AuthToken>) -> Result<Self, Error> {
+        let address = Address::parse(address);
+        let mut stream = open(&address, auth_token.as_ref())?;
+        let public_key = match exchange(&mut stream, &SignerRequest::GetPublicKey)? {
+            SignerResponse::PublicKey(public_key) => public_key,
+            // The signer process only refuses requests on connections not authenticated yet.
+            SignerResponse::Refused(_) => return Err(Error::AuthenticationFailed),
+            SignerResponse::Authenticated | SignerResponse::Signature(_) => {
+                return Err(Error::UnexpectedResponse)
+            }
+        };
+        info!(%address, %public_key, "connected to remote signer");
+        Ok(RemoteSigner {
+            address,
+            auth_token,
+            public_key,
+            stream: Mutex::new(Some(stream)),
+        })
+    }
+
+    /// Sends `request` and waits for the response.
+    ///
+    /// If the request fails on an existing connection, it is retried once on a new one, as the
+    /// signer process may have been restarted.  Retrying is safe since the signer process signs
+    /// the same message at the same position again.
+    fn request(&self, request: &SignerRequest) -> Result<SignerResponse, Error> {
+        let mut guard = self.stream.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut reconnected = false;
+        loop {
+            let result = match guard.as_mut() {
+                Some(stream) => exchange(stream, request),
+                None => {
+                    reconnected = true;
+                    let mut stream = open(&self.address, self.auth_token.as_ref())?;
+                    let result = exchange(&mut stream, request);
+                    *guard = Some(stream);
+                    result
+                }
+            };
+            match result {
+                Ok(response) => return Ok(response),
+                Err(error) => {
+                    *guard = None;
+                    if reconnected {
+                        return Err(error);
+                    }
+                    warn!(%error, address = %self.address, "reconnecting to remote signer");
+                }
+            }
+        }
+    }
+}
+
+impl Debug for RemoteSigner {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("RemoteSigner")
+            .field("address", &self.address)
+            .field("public_key", &self.public_key)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Signer for RemoteSigner {
+    fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+
+    fn sign(&self, message: &Signable) -> Result<Signature, Error> {
+        match self.request(&SignerRequest::Sign(message.clone()))? {
+            SignerResponse::Signature(signature) => {
+                crypto::verify(message.bytes_to_sign(), &signature, &self.public_key)
+                    .map_err(|_| Error::InvalidSignature)?;
+                Ok(signature)
+            }
+            SignerResponse::Refused(reason) => Err(Error::Refused(reason)),
+            SignerResponse::Authenticated | SignerResponse::PublicKey(_) => {
+                Err(Error::UnexpectedResponse)
+            }
+        }
+    }
+
+    fn may_block(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,221 @@
+//! The signer process, holding the validator's secret key on behalf of one or more nodes.
+
+use std::{
+    fs,
+    io::{self, ErrorKind, Read, Write},
+    net::TcpListener,
+    os::unix::{fs::FileTypeExt, net::UnixListener},
+    path::Path,
+    sync::{Arc, Mutex, PoisonError},
+    thread,
+};
+
+use anyhow::{bail, Context};
+use tracing::{debug, info, warn};
+
+use casper_types::{crypto, PublicKey, SecretKey};
+
+use crate::utils::umask;
+
+use super::{
+    read_frame, remote::Address, write_frame, AuthToken, Error, Signable, SignerRequest,
+    SignerResponse, SlashingProtection,
+};
+
+/// The umask the Unix socket is created with, so that only the signer's user may connect to it.
+const SOCKET_UMASK: umask::Mode = 0o177;
+
+/// The secret key and the record of what has been signed with it.
+pub(super) struct SignerState {
+    secret_key: SecretKey,
+    public_key: PublicKey,
+    protection: SlashingProtection,
+}
+
+impl SignerState {
+    /// Creates the state for signing with `secret_key`, consulting `protection` before signing.
+    pub(super) fn new(secret_key: SecretKey, protection: SlashingProtection) -> Self {
+        let public_key = PublicKey::from(&secret_key);
+        SignerState {
+            secret_key,
+            public_key,
+            protection,
+        }
+    }
+
+    /// Returns the response to `request`, received on a connection which authenticated with the
+    /// signer's token if `is_authenticated` is `true`.
+    ///
+    /// Legacy handshake certificates sign a bare hash, so they are only signed on authenticated
+    /// connections.
+    pub(super) fn handle(
+        &mut self,
+        request: SignerRequest,
+        is_authenticated: bool,
+    ) -> Result<SignerResponse, Error> {
+        let message = match request {
+            SignerRequest::Authenticate(_) if is_authenticated => {
+                return Ok(SignerResponse::Authenticated)
+            }
+            SignerRequest::Authenticate(_) => {
+                let reason = "signer has no authentication token".to_string();
+                return Ok(SignerResponse::Refused(reason));
+            }
+            SignerRequest::GetPublicKey => {
+                return Ok(SignerResponse::PublicKey(self.public_key.clone()))
+            }
+            SignerRequest::Sign(Signable::LegacyHandshake(_)) if !is_authenticated => {
+                let reason =
+                    "legacy handshake certificates are only signed on authenticated connections"
+                        .to_string();
+                return Ok(SignerResponse::Refused(reason));
+            }
+            SignerRequest::Sign(message) => message,
+        };
+        match self.protection.check_and_record(&self.public_key, &message) {
+            Ok(()) => {}
+            Err(Error::Refused(reason)) => return Ok(SignerResponse::Refused(reason)),
+            Err(error) => return Err(error),
+        }
+        debug!(?message, "signing");
+        let signature = crypto::sign(message.bytes_to_sign(), &self.secret_key, &self.public_key);
+        Ok(SignerResponse::Signature(signature))
+    }
+}
+
+/// Serves the requests received on `stream` until the connection is closed.
+///
+/// If `auth_token` is given, the first request must authenticate the connection with it.
+fn serve<S: Read + Write>(
+    mut stream: S,
+    state: &Mutex<SignerState>,
+    auth_token: Option<&AuthToken>,
+) -> Result<(), Error> {
+    if let Some(auth_token) = auth_token {
+        match read_frame(&mut stream)? {
+            SignerRequest::Authenticate(presented) if auth_token.matches(&presented) => {
+                write_frame(&mut stream, &SignerResponse::Authenticated)?;
+            }
+            _ => {
+                let reason = "authentication required".to_string();
+                write_frame(&mut stream, &SignerResponse::Refused(reason))?;
+                return Err(Error::AuthenticationFailed);
+            }
+        }
+    }
+    loop {
+        let request = match read_frame(&mut stream) {
+            Ok(request) => request,
+            Err(Error::Io(error)) if error.kind() == ErrorKind::UnexpectedEof => return Ok(()),
+            Err(error) => return Err(error),
+        };
+        let response = state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .handle(request, auth_token.is_some())?;
+        write_frame(&mut stream, &response)?;
+    }
+}
+
+/// Spawns a thread serving the requests received on `stream`.
+fn spawn_connection<S: Read + Write + Send + 'static>(
+    stream: S,
+    state: &Arc<Mutex<SignerState>>,
+    auth_token: &Option<Arc<AuthToken>>,
+) {
+    let state = Arc::clone(state);
+    let auth_token = auth_token.clone();
+    thread::spawn(move || {
+        if let Err(error) = serve(stream, &state, auth_token.as_deref()) {
+            warn!(%error, "signer connection failed");
+        }
+    });
+}
+
+/// Runs a signer process listening on `address`, which is either a loopback TCP socket address
+/// or the path of a Unix socket, and signing with the secret key read from `secret_key_path`.
+///
+/// If `auth_token_path` is given, every connection must first authenticate with the token read
+/// from it.  This is required when listening on TCP, as any local user can connect to it, whereas
+/// the Unix socket is only accessible to the signer's user.  Legacy handshake certificates, which
+/// nodes create until the network activates protocol version 1.6.0, are only signed on connections
+/// authenticated with the token.
+///
+/// The slashing protection database is logged at `state_path`.  Never returns unless listening
+/// fails.
+pub(crate) fn run_signer(
+    secret_key_path: &Path,
+    address: &str,
+    state_path: &Path,
+    auth_token_path: Option<&Path>,
+) -> anyhow::Result<()> {
+    let address = Address::parse(address);
+    let auth_token = match auth_token_path {
+        Some(path) => {
+            Some(Arc::new(AuthToken::from_file(path).with_context(|| {
+                format!("could not load {}", path.display())
+            })?))
+        }
+        None => None,
+    };
+    if matches!(address, Address::Tcp(_)) && auth_token.is_none() {
+        bail!("a signer listening on TCP requires an authentication token");
+    }
+    let secret_key = SecretKey::from_file(secret_key_path)
+        .with_context(|| format!("could not load {}", secret_key_path.display()))?;
+    let protection = SlashingProtection::open(state_path.to_path_buf())
+        .with_context(|| format!("could not open {}", state_path.display()))?;
+    let state = SignerState::new(secret_key, protection);
+    info!(public_key = %state.public_key, "signer state loaded");
+    let state = Arc::new(Mutex::new(state));
+
+    match address {
+        Address::Tcp(socket_addr) => {
+            if !socket_addr.ip().is_loopback() {
+                bail!(
+                    "signer must listen on a loopback address, not {}",
+                    socket_addr
+                );
+            }
+            let listener = TcpListener::bind(socket_addr)?;
+            info!(%socket_addr, "signer listening");
+            for stream in listener.incoming() {
+                // A failure to accept one connection must not stop the signer serving others.
+                let stream = match stream {
+                    Ok(stream) => stream,
+                    Err(error) => {
+                        warn!(%error, "failed to accept signer connection");
+                        continue;
+                    }
+                };
+                if let Err(error) = stream.set_nodelay(true) {
+                    warn!(%error, "failed to set TCP_NODELAY on signer connection");
+                }
+                spawn_connection(stream, &state, &auth_token);
+            }
+        }
+        Address::Unix(path) => {
+            // Only a stale socket may be replaced, never a file which happens to be at the path.
+            match fs::symlink_metadata(&path) {
+                Ok(metadata) if metadata.file_type().is_socket() => {
+                    fs::remove_file(&path)?;
+                    debug!(path = %path.display(), "removed stale signer socket");
+                }
+                Ok(_) => bail!("{} exists and is not a socket", path.display()),
+                Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+                Err(error) => return Err(error.into()),
+            }
+            let umask_guard = umask::temp_umask(SOCKET_UMASK);
+            let listener = UnixListener::bind(&path)?;
+            drop(umask_guard);
+            info!(path = %path.display(), "signer listening");
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(stream) => spawn_connection(stream, &state, &auth_token),
+                    Err(error) => warn!(%error, "failed to accept signer connection"),
+                }
+            }
+        }
+    }
+    Ok(())
+}
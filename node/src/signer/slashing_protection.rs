@@ -0,0 +1,744 @@
+//! A persistent record of the messages signed by validators, protecting them from equivocating.
+//!
+//! Before a message which is part of a [`Sequence`] is signed, the [`SlashingProtection`] database
+//! is consulted: if a different message has already been signed at the same position, the
+//! signature is refused.  Otherwise the message is recorded, and the record persisted, before the
+//! signature is created.  This prevents a validator restarted or restored from a backup from
+//! producing evidence of equivocation against itself.  It cannot protect a validator run on two
+//! machines at once, each with its own database; within one machine, the database is locked
+//! exclusively while open, so that it is used by a single process at a time.
+//! In the sequences whose positions only ever increase, i.e. Highway units and finality
+//! signatures, nothing new is signed below the highest position signed at, either.
+//!
+//! Only the most recent `MAX_POSITIONS_PER_SEQUENCE` positions of each sequence are retained, and
+//! every position below the lowest retained one is refused.  Likewise only the
+//! `MAX_SEQUENCES_PER_VALIDATOR` most recently used sequences of each validator are retained: the
+//! least recently used one is then retired, keeping only the fact that nothing can be signed in it
+//! anymore.
+//!
+//! A node whose database has no record of its validator yet, e.g. when it is first created on a
+//! node which has been validating before, seeds it from the finality signatures in its storage:
+//! in each era, nothing is signed at or below the highest block height found signed.
+//!
+//! # Storage
+//!
+//! The database is stored as an append-only log: every signed message appends one record, which is
+//! synced to disk before the signature is created.  When the log has grown too long, it is
+//! replaced by a compacted one containing only the retained histories.  A record which was only
+//! partially written, e.g. due to a crash, is discarded when the log is opened: the corresponding
+//! signature has never been created.  After a compaction, the containing directory is synced too,
+//! so that the replacement survives a crash.
+//!
+//! # Interchange format
+//!
+//! The database can be exported to and imported from a JSON file in the interchange format, with
+//! the `export-slashing-protection` and `import-slashing-protection` subcommands, to move a
+//! validator key between machines:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "validators": [
+//!     {
+//!       "public_key": "01a3...",
+//!       "sequences": [
+//!         {
+//!           "sequence": { "kind": "highway_units", "id": "<instance ID>" },
+//!           "lowest_position": 0,
+//!           "last_signed": "2023-05-01T12:00:00.000Z",
+//!           "signed": [{ "position": 17, "message_hash": "<message hash>" }]
+//!         }
+//!       ]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! The kind of a sequence is one of `highway_units` (positions are sequence numbers),
+//! `zug_echoes` and `zug_votes` (positions are round IDs), each identified by the consensus
+//! instance ID, or `finality_signatures` (positions are block heights), identified by the era ID.
+//! The message hash is the BLAKE2b-256 hash of the signed bytes.  A retired sequence has no
+//! signed positions, and the lowest position `18446744073709551615`.
+
+use std::{
+    collections::BTreeMap,
+    fs::{self, File, OpenOptions},
+    io::{Cursor, ErrorKind, Read},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use anyhow::Context;
+use fs2::FileExt;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use casper_hashing::Digest;
+use casper_types::{EraId, PublicKey, Signature, Timestamp};
+
+use super::{read_frame, write_frame, Error, Sequence, Signable, Signer};
+use crate::{reactor::main_reactor::Config, utils::WithDir};
+
+/// The default name of the database file, in the node's config directory.
+pub(crate) const SLASHING_PROTECTION_FILENAME: &str = "slashing_protection.log";
+/// The version of the interchange format written by this version of the node.
+const INTERCHANGE_FORMAT_VERSION: u32 = 1;
+/// The maximum number of positions retained per sequence.
+const MAX_POSITIONS_PER_SEQUENCE: usize = 1_000;
+/// The maximum number of sequences retained per validator.  When exceeded, the sequence signed in
+/// least recently is retired, i.e. its history is replaced by a refusal to sign anything in it.
+const MAX_SEQUENCES_PER_VALIDATOR: usize = 100;
+/// The number of records appended to the log after which it is compacted.
+const MAX_APPENDED_RECORDS: usize = 10_000;
+
+/// The messages signed in a sequence.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct History {
+    /// The lowest position at which signing is allowed.
+    lowest_position: u64,
+    /// When the last message was signed.
+    last_signed: Timestamp,
+    /// The hash of the message signed at each position.
+    signed: BTreeMap<u64, Digest>,
+}
+
+impl History {
+    fn new() -> Self {
+        History {
+            lowest_position: 0,
+            last_signed: Timestamp::zero(),
+            signed: BTreeMap::new(),
+        }
+    }
+
+    /// Returns whether the sequence has been retired, i.e. nothing can be signed in it anymore.
+    fn is_retired(&self) -> bool {
+        self.lowest_position == u64::MAX
+    }
+
+    /// Forgets the history, and refuses to sign anything in the sequence from now on.
+    fn retire(&mut self) {
+        self.lowest_position = u64::MAX;
+        self.signed.clear();
+    }
+
+    /// Returns the highest position at which a message has been signed.
+    ///
+    /// Pruning only forgets the lowest positions, so this is retained until the sequence retires.
+    fn highest_position(&self) -> Option<u64> {
+        self.signed.keys().next_back().copied()
+    }
+
+    /// Returns the reason why the message with the given hash must not be signed at `position` of
+    /// `sequence`, or `None` if it can be signed.
+    fn refusal(&self, sequence: &Sequence, position: u64, message_hash: &Digest) -> Option<String> {
+        if self.is_retired() {
+            return Some("the sequence has been retired".to_string());
+        }
+        if position < self.lowest_position {
+            return Some(format!(
+                "position {} is below the lowest retained position {}",
+                position, self.lowest_position
+            ));
+        }
+        match self.signed.get(&position) {
+            Some(signed_hash) if signed_hash != message_hash => Some(format!(
+                "already signed a different message at position {}",
+                position
+            )),
+            Some(_) => None,
+            None => match self.highest_position() {
+                Some(highest) if sequence.is_increasing() && position < highest => Some(format!(
+                    "position {} is below the highest signed position {}",
+                    position, highest
+                )),
+                _ => None,
+            },
+        }
+    }
+
+    /// Forgets the lowest positions in excess of `MAX_POSITIONS_PER_SEQUENCE`.
+    fn prune(&mut self) {
+        while self.signed.len() > MAX_POSITIONS_PER_SEQUENCE {
+            if let Some((position, _)) = self.signed.pop_first() {
+                self.lowest_position = position.saturating_add(1);
+            }
+        }
+    }
+
+    /// Merges in the history of the same sequence from another database.
+    ///
+    /// If the two histories contain different messages at the same position, the existing one is
+    /// kept: both have been signed already, and neither machine must sign a third one.
+    fn merge(&mut self, other: History) -> bool {
+        let mut conflict = false;
+        self.lowest_position = self.lowest_position.max(other.lowest_position);
+        self.last_signed = self.last_signed.max(other.last_signed);
+        for (position, message_hash) in other.signed {
+            let existing = self.signed.entry(position).or_insert(message_hash);
+            conflict |= *existing != message_hash;
+        }
+        let lowest_position = self.lowest_position;
+        self.signed
+            .retain(|position, _| *position >= lowest_position);
+        self.prune();
+        conflict
+    }
+}
+
+/// A message signed at a position, in the interchange format.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SignedPosition {
+    position: u64,
+    message_hash: Digest,
+}
+
+/// The history of a sequence, in the interchange format.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SequenceRecord {
+    sequence: Sequence,
+    lowest_position: u64,
+    last_signed: Timestamp,
+    signed: Vec<SignedPosition>,
+}
+
+/// The histories of a validator's sequences, in the interchange format.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ValidatorRecord {
+    public_key: PublicKey,
+    sequences: Vec<SequenceRecord>,
+}
+
+/// A slashing protection database, in the interchange format.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Interchange {
+    version: u32,
+    validators: Vec<ValidatorRecord>,
+}
+
+type Histories = BTreeMap<PublicKey, BTreeMap<Sequence, History>>;
+
+impl Interchange {
+    fn parse(bytes: &[u8]) -> Result<Histories, Error> {
+        let interchange: Interchange = serde_json::from_slice(bytes)?;
+        if interchange.version != INTERCHANGE_FORMAT_VERSION {
+            return Err(Error::UnsupportedInterchangeVersion(interchange.version));
+        }
+        let histories = interchange
+            .validators
+            .into_iter()
+            .map(|validator| {
+                let sequences = validator
+                    .sequences
+                    .into_iter()
+                    .map(|record| {
+                        let history = History {
+                            lowest_position: record.lowest_position,
+                            last_signed: record.last_signed,
+                            signed: record
+                                .signed
+                                .into_iter()
+                                .map(|signed| (signed.position, signed.message_hash))
+                                .collect(),
+                        };
+                        (record.sequence, history)
+                    })
+                    .collect();
+                (validator.public_key, sequences)
+            })
+            .collect();
+        Ok(histories)
+    }
+
+    fn from_histories(histories: &Histories) -> Self {
+        let validators = histories
+            .iter()
+            .map(|(public_key, sequences)| ValidatorRecord {
+                public_key: public_key.clone(),
+                sequences: sequences
+                    .iter()
+                    .map(|(sequence, history)| SequenceRecord {
+                        sequence: *sequence,
+                        lowest_position: history.lowest_position,
+                        last_signed: history.last_signed,
+                        signed: history
+                            .signed
+                            .iter()
+                            .map(|(position, message_hash)| SignedPosition {
+                                position: *position,
+                                message_hash: *message_hash,
+                            })
+                            .collect(),
+                    })
+                    .collect(),
+            })
+            .collect();
+        Interchange {
+            version: INTERCHANGE_FORMAT_VERSION,
+            validators,
+        }
+    }
+}
+
+/// A sequence, as encoded in the database log.
+///
+/// `Sequence` is serialized as an adjacently tagged enum for the interchange format, which bincode
+/// can't deserialize.
+#[derive(Serialize, Deserialize)]
+enum LogSequence {
+    HighwayUnits(Digest),
+    ZugEchoes(Digest),
+    ZugVotes(Digest),
+    FinalitySignatures(EraId),
+}
+
+impl From<Sequence> for LogSequence {
+    fn from(sequence: Sequence) -> Self {
+        match sequence {
+            Sequence::HighwayUnits(instance_id) => LogSequence::HighwayUnits(instance_id),
+            Sequence::ZugEchoes(instance_id) => LogSequence::ZugEchoes(instance_id),
+            Sequence::ZugVotes(instance_id) => LogSequence::ZugVotes(instance_id),
+            Sequence::FinalitySignatures(era_id) => LogSequence::FinalitySignatures(era_id),
+        }
+    }
+}
+
+impl From<LogSequence> for Sequence {
+    fn from(sequence: LogSequence) -> Self {
+        match sequence {
+            LogSequence::HighwayUnits(instance_id) => Sequence::HighwayUnits(instance_id),
+            LogSequence::ZugEchoes(instance_id) => Sequence::ZugEchoes(instance_id),
+            LogSequence::ZugVotes(instance_id) => Sequence::ZugVotes(instance_id),
+            LogSequence::FinalitySignatures(era_id) => Sequence::FinalitySignatures(era_id),
+        }
+    }
+}
+
+/// A record in the database log.
+#[derive(Serialize, Deserialize)]
+enum LogRecord {
+    /// A message signed by `public_key` at `position` in `sequence`.
+    Signed {
+        public_key: PublicKey,
+        sequence: LogSequence,
+        position: u64,
+        message_hash: Digest,
+        timestamp: Timestamp,
+    },
+    /// The complete history of a sequence, replacing any previous records of it.
+    History {
+        public_key: PublicKey,
+        sequence: LogSequence,
+        lowest_position: u64,
+        last_signed: Timestamp,
+        signed: Vec<(u64, Digest)>,
+    },
+}
+
+/// The log file the database is persisted to.
+#[derive(Debug)]
+struct Log {
+    path: PathBuf,
+    /// The log file, exclusively locked and positioned at its end.
+    file: File,
+    /// The number of records appended since the log was last compacted.
+    appended: usize,
+}
+
+impl Log {
+    /// Appends the record and syncs it to disk.
+    fn append(&mut self, record: &LogRecord) -> Result<(), Error> {
+        write_frame(&mut self.file, record)?;
+        self.file.sync_data()?;
+        self.appended += 1;
+        Ok(())
+    }
+
+    /// Atomically replaces the log with one containing only the given histories.
+    ///
+    /// The replacement is locked before it is renamed into place, so that the log at `path` is
+    /// never unlocked while open.
+    fn compact(&mut self, histories: &Histories) -> Result<(), Error> {
+        let temp_path = self.path.with_extension("tmp");
+        let mut file = File::create(&temp_path)?;
+        lock(&file, &temp_path)?;
+        for (public_key, sequences) in histories {
+            for (sequence, history) in sequences {
+                let record = LogRecord::History {
+                    public_key: public_key.clone(),
+                    sequence: (*sequence).into(),
+                    lowest_position: history.lowest_position,
+                    last_signed: history.last_signed,
+                    signed: history
+                        .signed
+                        .iter()
+                        .map(|(position, message_hash)| (*position, *message_hash))
+                        .collect(),
+                };
+                write_frame(&mut file, &record)?;
+            }
+        }
+        file.sync_all()?;
+        fs::rename(&temp_path, &self.path)?;
+        let directory = match self.path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        File::open(directory)?.sync_all()?;
+        self.file = file;
+        self.appended = 0;
+        Ok(())
+    }
+}
+
+/// Takes an exclusive lock on the log file, failing if another process holds it already.
+fn lock(file: &File, path: &Path) -> Result<(), Error> {
+    file.try_lock_exclusive()
+        .map_err(|_| Error::DatabaseLocked(path.to_path_buf()))
+}
+
+/// The record of the messages signed by each validator.
+#[derive(Debug)]
+pub(crate) struct SlashingProtection {
+    /// The log the database is persisted to, or `None` to keep it in memory only.
+    log: Option<Log>,
+    histories: Histories,
+}
+
+impl SlashingProtection {
+    /// Opens the database logged at `path`, or creates an empty one if the file doesn't exist.
+    ///
+    /// The database stays locked until it is dropped: opening it while it is open in another
+    /// process fails.
+    pub(crate) fn open(path: PathBuf) -> Result<Self, Error> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(&path)?;
+        lock(&file, &path)?;
+        let mut bytes = vec![];
+        file.read_to_end(&mut bytes)?;
+        let mut protection = SlashingProtection {
+            log: None,
+            histories: BTreeMap::new(),
+        };
+        let mut cursor = Cursor::new(&bytes[..]);
+        let mut records = 0;
+        while cursor.position() < bytes.len() as u64 {
+            let start = cursor.position();
+            match read_frame(&mut cursor) {
+                Ok(record) => protection.apply(record),
+                Err(Error::Io(error)) if error.kind() == ErrorKind::UnexpectedEof => {
+                    // Only the last record can be incomplete, if writing it was interrupted.
+                    warn!(
+                        path = %path.display(),
+                        "discarding incomplete last slashing protection record"
+                    );
+                    file.set_len(start)?;
+                    break;
+                }
+                Err(error) => return Err(error),
+            }
+            records += 1;
+        }
+        protection.log = Some(Log {
+            path,
+            file,
+            appended: records,
+        });
+        Ok(protection)
+    }
+
+    /// Creates an empty database which is not persisted.
+    #[cfg(test)]
+    pub(crate) fn in_memory() -> Self {
+        SlashingProtection {
+            log: None,
+            histories: BTreeMap::new(),
+        }
+    }
+
+    /// Returns `true` if anything signed by `public_key` is recorded.
+    pub(crate) fn has_signed(&self, public_key: &PublicKey) -> bool {
+        self.histories.contains_key(public_key)
+    }
+
+    /// Refuses to sign finality signatures by `public_key` at or below the given block height of
+    /// each era from now on, as found signed e.g. in the node's storage.
+    ///
+    /// The hashes of the signed blocks aren't recorded, so not even the same block can be signed
+    /// again at those heights.
+    pub(crate) fn seed_finality_signatures<I>(
+        &mut self,
+        public_key: &PublicKey,
+        highest_heights: I,
+    ) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = (EraId, u64)>,
+    {
+        for (era_id, height) in highest_heights {
+            let sequence = Sequence::FinalitySignatures(era_id);
+            let mut history = History {
+                lowest_position: height.saturating_add(1),
+                last_signed: Timestamp::now(),
+                signed: BTreeMap::new(),
+            };
+            if let Some(existing) = self
+                .histories
+                .get(public_key)
+                .and_then(|sequences| sequences.get(&sequence))
+            {
+                let seed = history;
+                history = existing.clone();
+                history.merge(seed);
+            }
+            let record = LogRecord::History {
+                public_key: public_key.clone(),
+                sequence: sequence.into(),
+                lowest_position: history.lowest_position,
+                last_signed: history.last_signed,
+                signed: history.signed.into_iter().collect(),
+            };
+            if let Some(log) = &mut self.log {
+                log.append(&record)?;
+            }
+            self.apply(record);
+        }
+        self.compact_if_needed()
+    }
+
+    /// Checks that `message` can be signed by `public_key` without equivocating, and records it as
+    /// signed.
+    ///
+    /// Returns `Error::Refused` if it must not be signed.  The record is persisted before this
+    /// returns, so that it is never forgotten once the signature is created.
+    pub(crate) fn check_and_record(
+        &mut self,
+        public_key: &PublicKey,
+        message: &Signable,
+    ) -> Result<(), Error> {
+        let (sequence, position) = match message.position() {
+            Ok(Some(sequence_and_position)) => sequence_and_position,
+            Ok(None) => return Ok(()),
+            Err(error) => {
+                warn!(?message, %public_key, %error, "refused to sign invalid message");
+                return Err(error);
+            }
+        };
+        let message_hash = Digest::hash(message.bytes_to_sign());
+        if let Some(history) = self
+            .histories
+            .get(public_key)
+            .and_then(|sequences| sequences.get(&sequence))
+        {
+            if let Some(reason) = history.refusal(&sequence, position, &message_hash) {
+                warn!(?message, %public_key, %reason, "slashing protection refused to sign");
+                return Err(Error::Refused(format!("{} of {:?}", reason, sequence)));
+            }
+        }
+        let record = LogRecord::Signed {
+            public_key: public_key.clone(),
+            sequence: sequence.into(),
+            position,
+            message_hash,
+            timestamp: Timestamp::now(),
+        };
+        if let Some(log) = &mut self.log {
+            log.append(&record)?;
+        }
+        self.apply(record);
+        self.compact_if_needed()
+    }
+
+    /// Merges in a database in the interchange format.  Returns the number of sequences in which
+    /// both databases contain different messages at the same position.
+    pub(crate) fn import(&mut self, interchange: &[u8]) -> Result<usize, Error> {
+        let mut conflicts = 0;
+        for (public_key, sequences) in Interchange::parse(interchange)? {
+            let existing_sequences = self.histories.entry(public_key).or_default();
+            for (sequence, history) in sequences {
+                match existing_sequences.get_mut(&sequence) {
+                    Some(existing) => {
+                        if existing.merge(history) {
+                            warn!(
+                                ?sequence,
+                                "imported conflicting slashing protection history"
+                            );
+                            conflicts += 1;
+                        }
+                    }
+                    None => {
+                        existing_sequences.insert(sequence, history);
+                    }
+                }
+            }
+            retire_excess_sequences(existing_sequences);
+        }
+        if let Some(log) = &mut self.log {
+            log.compact(&self.histories)?;
+        }
+        Ok(conflicts)
+    }
+
+    /// Returns the database in the interchange format.
+    pub(crate) fn export(&self) -> Result<Vec<u8>, Error> {
+        Ok(serde_json::to_vec_pretty(&Interchange::from_histories(
+            &self.histories,
+        ))?)
+    }
+
+    /// Applies a log record to the histories.
+    fn apply(&mut self, record: LogRecord) {
+        match record {
+            LogRecord::Signed {
+                public_key,
+                sequence,
+                position,
+                message_hash,
+                timestamp,
+            } => {
+                let sequences = self.histories.entry(public_key).or_default();
+                let history = sequences
+                    .entry(sequence.into())
+                    .or_insert_with(History::new);
+                history.signed.insert(position, message_hash);
+                history.last_signed = timestamp;
+                history.prune();
+                retire_excess_sequences(sequences);
+            }
+            LogRecord::History {
+                public_key,
+                sequence,
+                lowest_position,
+                last_signed,
+                signed,
+            } => {
+                let history = History {
+                    lowest_position,
+                    last_signed,
+                    signed: signed.into_iter().collect(),
+                };
+                self.histories
+                    .entry(public_key)
+                    .or_default()
+                    .insert(sequence.into(), history);
+            }
+        }
+    }
+
+    /// Compacts the log if too many records have been appended to it.
+    fn compact_if_needed(&mut self) -> Result<(), Error> {
+        match &mut self.log {
+            Some(log) if log.appended > MAX_APPENDED_RECORDS => log.compact(&self.histories),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Retires the least recently used sequences in excess of `MAX_SEQUENCES_PER_VALIDATOR`.
+fn retire_excess_sequences(sequences: &mut BTreeMap<Sequence, History>) {
+    let mut active: Vec<_> = sequences
+        .iter_mut()
+        .filter(|(_, history)| !history.is_retired())
+        .map(|(_, history)| history)
+        .collect();
+    if active.len() <= MAX_SEQUENCES_PER_VALIDATOR {
+        return;
+    }
+    active.sort_by_key(|history| history.last_signed);
+    let excess = active.len() - MAX_SEQUENCES_PER_VALIDATOR;
+    for history in active.into_iter().take(excess) {
+        history.retire();
+    }
+}
+
+/// A signer consulting a slashing protection database before every signature.
+#[derive(Debug)]
+pub(crate) struct ProtectedSigner {
+    signer: Arc<dyn Signer>,
+    protection: Mutex<SlashingProtection>,
+}
+
+impl ProtectedSigner {
+    /// Creates a signer forwarding to `signer` the requests allowed by `protection`.
+    pub(crate) fn new(signer: Arc<dyn Signer>, protection: SlashingProtection) -> Self {
+        ProtectedSigner {
+            signer,
+            protection: Mutex::new(protection),
+        }
+    }
+}
+
+impl Signer for ProtectedSigner {
+    fn public_key(&self) -> &PublicKey {
+        self.signer.public_key()
+    }
+
+    fn sign(&self, message: &Signable) -> Result<Signature, Error> {
+        self.protection
+            .lock()
+            .unwrap()
+            .check_and_record(self.signer.public_key(), message)?;
+        self.signer.sign(message)
+    }
+
+    fn may_block(&self) -> bool {
+        // Recording a signature persists the database.
+        true
+    }
+}
+
+/// Returns the path of the slashing protection database of the node with the given config.
+fn database_path(config: &WithDir<Config>) -> PathBuf {
+    config.with_dir(config.value().consensus.slashing_protection_path.clone())
+}
+
+/// Exports the slashing protection database of the node with the given config to `output`.
+///
+/// The node must be stopped while exporting, as the database is locked while the node is running.
+pub(crate) fn export_slashing_protection(
+    config: WithDir<Config>,
+    output: &Path,
+) -> anyhow::Result<()> {
+    let path = database_path(&config);
+    let protection = SlashingProtection::open(path.clone())
+        .with_context(|| format!("could not open {}", path.display()))?;
+    fs::write(output, protection.export()?)
+        .with_context(|| format!("could not write {}", output.display()))?;
+    info!(output = %output.display(), "exported slashing protection database");
+    Ok(())
+}
+
+/// Merges the slashing protection database exported to `input` into the database of the node with
+/// the given config.
+///
+/// The node must be stopped while importing, as the database is locked while the node is running.
+pub(crate) fn import_slashing_protection(
+    config: WithDir<Config>,
+    input: &Path,
+) -> anyhow::Result<()> {
+    let path = database_path(&config);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("could not create {}", parent.display()))?;
+    }
+    let mut protection = SlashingProtection::open(path.clone())
+        .with_context(|| format!("could not open {}", path.display()))?;
+    let interchange =
+        fs::read(input).with_context(|| format!("could not read {}", input.display()))?;
+    let conflicts = protection
+        .import(&interchange)
+        .with_context(|| format!("could not import {}", input.display()))?;
+    info!(
+        input = %input.display(),
+        database = %path.display(),
+        conflicts,
+        "imported slashing protection database"
+    );
+    Ok(())
+}
@@ -0,0 +1,683 @@
+use std::{
+    collections::BTreeSet,
+    fs::{self, OpenOptions},
+    io::Write,
+    os::unix::fs::PermissionsExt,
+    sync::{Arc, Mutex},
+    thread::{self, ThreadId},
+    time::Duration,
+};
+
+use tempfile::TempDir;
+
+use casper_hashing::Digest;
+use casper_types::{
+    crypto, testing::TestRng, EraId, ProtocolVersion, PublicKey, SecretKey, Signature, Timestamp,
+};
+
+use super::{
+    server::SignerState, AuthToken, Error, LocalSigner, ProtectedSigner, RemoteSigner, Signable,
+    Signer, SignerRequest, SignerResponse, SlashingProtection, SLASHING_PROTECTION_FILENAME,
+};
+use crate::{
+    components::consensus::{
+        highway_core::{highway::WireUnit, state::Panorama},
+        protocols::zug::Content,
+        utils::ValidatorIndex,
+        ClContext, Config as ConsensusConfig, LoadSignerError, SignedMessageId,
+    },
+    types::Block,
+};
+
+fn secret_key() -> SecretKey {
+    SecretKey::ed25519_from_bytes([7; SecretKey::ED25519_LENGTH]).unwrap()
+}
+
+/// Returns the Highway unit with the given sequence number in the given instance.  Units with
+/// different timestamps are different messages.
+fn unit_in(instance: u8, seq_number: u64, timestamp: u64) -> Signable {
+    let instance_id = Digest::hash([instance]);
+    let wire_unit = WireUnit::<ClContext> {
+        panorama: Panorama::new(1),
+        creator: ValidatorIndex(0),
+        instance_id,
+        value: None,
+        seq_number,
+        timestamp: Timestamp::from(timestamp),
+        round_exp: 0,
+        endorsed: BTreeSet::new(),
+    };
+    Signable::Consensus {
+        message_id: SignedMessageId::HighwayUnit {
+            instance_id,
+            seq_number,
+        },
+        fields: wire_unit.signed_fields(),
+    }
+}
+
+fn unit(seq_number: u64, timestamp: u64) -> Signable {
+    unit_in(1, seq_number, timestamp)
+}
+
+fn ping(timestamp: u64) -> Signable {
+    let instance_id = Digest::hash([1]);
+    let timestamp = Timestamp::from(timestamp);
+    Signable::Consensus {
+        message_id: SignedMessageId::HighwayPing {
+            instance_id,
+            timestamp,
+        },
+        fields: bincode::serialize(&(ValidatorIndex(0), timestamp, instance_id)).unwrap(),
+    }
+}
+
+fn zug_echo(round_id: u32, proposal: u8) -> Signable {
+    let instance_id = Digest::hash([1]);
+    let content = Content::<ClContext>::Echo(Digest::hash([proposal]));
+    Signable::Consensus {
+        message_id: SignedMessageId::ZugEcho {
+            instance_id,
+            round_id,
+        },
+        fields: bincode::serialize(&(round_id, instance_id, content, ValidatorIndex(0))).unwrap(),
+    }
+}
+
+fn zug_vote(round_id: u32, vote: bool) -> Signable {
+    let instance_id = Digest::hash([1]);
+    let content = Content::<ClContext>::Vote(vote);
+    Signable::Consensus {
+        message_id: SignedMessageId::ZugVote {
+            instance_id,
+            round_id,
+        },
+        fields: bincode::serialize(&(round_id, instance_id, content, ValidatorIndex(0))).unwrap(),
+    }
+}
+
+fn finality_signature(rng: &mut TestRng, era_id: u64, height: u64) -> Signable {
+    let block = Block::random_with_specifics(
+        rng,
+        EraId::new(era_id),
+        height,
+        ProtocolVersion::V1_0_0,
+        false,
+        None,
+    );
+    Signable::FinalitySignature(Box::new(block.header().clone()))
+}
+
+fn sign(state: &mut SignerState, message: &Signable) -> SignerResponse {
+    sign_on(state, message, false)
+}
+
+/// Signs `message` as if requested on a connection authenticated with the signer's token if
+/// `is_authenticated` is `true`.
+fn sign_on(state: &mut SignerState, message: &Signable, is_authenticated: bool) -> SignerResponse {
+    let request = SignerRequest::Sign(message.clone());
+    state
+        .handle(request, is_authenticated)
+        .expect("should handle request")
+}
+
+fn is_refused(response: &SignerResponse) -> bool {
+    matches!(response, SignerResponse::Refused(_))
+}
+
+/// Starts a signer process listening on `address`, and connects to it with `auth_token`.
+fn start_signer(
+    tempdir: &TempDir,
+    address: &str,
+    signer_auth_token: Option<&str>,
+    auth_token: Option<&str>,
+) -> Result<RemoteSigner, Error> {
+    let secret_key_path = tempdir.path().join("secret_key.pem");
+    let state_path = tempdir.path().join("signer_state.bin");
+    secret_key().to_file(&secret_key_path).unwrap();
+    let auth_token_path = signer_auth_token.map(|token| {
+        let path = tempdir.path().join("auth_token");
+        fs::write(&path, token).unwrap();
+        path
+    });
+    let signer_address = address.to_string();
+    thread::spawn(move || {
+        super::run_signer(
+            &secret_key_path,
+            &signer_address,
+            &state_path,
+            auth_token_path.as_deref(),
+        )
+    });
+    let auth_token = auth_token.map(|token| AuthToken::new(token.as_bytes().to_vec()).unwrap());
+    let mut attempts = 0;
+    loop {
+        match RemoteSigner::connect(address, auth_token.clone()) {
+            Err(Error::Io(error)) => {
+                attempts += 1;
+                assert!(attempts < 100, "could not connect to signer: {}", error);
+                thread::sleep(Duration::from_millis(50));
+            }
+            result => return result,
+        }
+    }
+}
+
+/// A signer that may block, recording the threads it was called on.
+#[derive(Debug)]
+struct ThreadRecordingSigner {
+    signer: LocalSigner,
+    threads: Mutex<Vec<ThreadId>>,
+}
+
+impl Signer for ThreadRecordingSigner {
+    fn public_key(&self) -> &PublicKey {
+        self.signer.public_key()
+    }
+
+    fn sign(&self, message: &Signable) -> Result<Signature, Error> {
+        self.threads.lock().unwrap().push(thread::current().id());
+        self.signer.sign(message)
+    }
+
+    fn may_block(&self) -> bool {
+        true
+    }
+}
+
+#[test]
+fn should_refuse_to_sign_conflicting_messages() {
+    let mut state = SignerState::new(secret_key(), SlashingProtection::in_memory());
+
+    assert!(!is_refused(&sign(&mut state, &unit(0, 0))));
+    assert!(!is_refused(&sign(&mut state, &unit(1, 0))));
+    // Signing the same message again is allowed, e.g. after the node restarted.
+    assert!(!is_refused(&sign(&mut state, &unit(1, 0))));
+    // A different message at the same position is an equivocation.
+    assert!(is_refused(&sign(&mut state, &unit(1, 1))));
+    // The message at an earlier position can be signed again, but no other one.
+    assert!(!is_refused(&sign(&mut state, &unit(0, 0))));
+    assert!(is_refused(&sign(&mut state, &unit(0, 1))));
+    assert!(!is_refused(&sign(&mut state, &unit(2, 0))));
+}
+
+#[test]
+fn should_track_sequences_independently() {
+    let mut rng = TestRng::new();
+    let mut state = SignerState::new(secret_key(), SlashingProtection::in_memory());
+    let block_0 = finality_signature(&mut rng, 1, 0);
+    let other_block_0 = finality_signature(&mut rng, 1, 0);
+
+    assert!(!is_refused(&sign(&mut state, &unit(5, 0))));
+    assert!(!is_refused(&sign(&mut state, &unit_in(2, 0, 0))));
+    assert!(!is_refused(&sign(&mut state, &ping(0))));
+    assert!(!is_refused(&sign(&mut state, &block_0)));
+    assert!(is_refused(&sign(&mut state, &other_block_0)));
+    assert!(!is_refused(&sign(
+        &mut state,
+        &finality_signature(&mut rng, 2, 0)
+    )));
+    // Messages which are not part of a sequence are always signed.
+    assert!(!is_refused(&sign(&mut state, &ping(1))));
+    assert!(!is_refused(&sign(
+        &mut state,
+        &Signable::Handshake([1; 32])
+    )));
+    assert!(!is_refused(&sign(
+        &mut state,
+        &Signable::Handshake([2; 32])
+    )));
+}
+
+#[test]
+fn should_refuse_positions_below_the_highest_signed_one() {
+    let mut rng = TestRng::new();
+    let mut state = SignerState::new(secret_key(), SlashingProtection::in_memory());
+
+    assert!(!is_refused(&sign(&mut state, &unit(5, 0))));
+    assert!(is_refused(&sign(&mut state, &unit(3, 0))));
+    assert!(!is_refused(&sign(&mut state, &unit(6, 0))));
+
+    assert!(!is_refused(&sign(
+        &mut state,
+        &finality_signature(&mut rng, 1, 10)
+    )));
+    assert!(is_refused(&sign(
+        &mut state,
+        &finality_signature(&mut rng, 1, 9)
+    )));
+
+    // Zug echoes and votes may be signed in earlier rounds which are still open.
+    assert!(!is_refused(&sign(&mut state, &zug_echo(5, 0))));
+    assert!(!is_refused(&sign(&mut state, &zug_echo(3, 0))));
+    assert!(is_refused(&sign(&mut state, &zug_echo(3, 1))));
+    assert!(!is_refused(&sign(&mut state, &zug_vote(5, true))));
+    assert!(!is_refused(&sign(&mut state, &zug_vote(4, false))));
+    assert!(is_refused(&sign(&mut state, &zug_vote(5, false))));
+}
+
+#[test]
+fn should_derive_positions_from_the_messages() {
+    let mut state = SignerState::new(secret_key(), SlashingProtection::in_memory());
+    assert!(!is_refused(&sign(&mut state, &unit(0, 0))));
+
+    // A request claiming a different position than the message's is refused.
+    let mut misplaced_unit = unit(0, 1);
+    if let Signable::Consensus { message_id, .. } = &mut misplaced_unit {
+        *message_id = SignedMessageId::HighwayUnit {
+            instance_id: Digest::hash([1]),
+            seq_number: 1,
+        };
+    }
+    assert!(is_refused(&sign(&mut state, &misplaced_unit)));
+
+    // So is a unit passed off as a ping or an endorsement, which are not part of any sequence.
+    let fields = match unit(0, 1) {
+        Signable::Consensus { fields, .. } => fields,
+        _ => unreachable!(),
+    };
+    let as_ping = Signable::Consensus {
+        message_id: SignedMessageId::HighwayPing {
+            instance_id: Digest::hash([1]),
+            timestamp: Timestamp::from(0),
+        },
+        fields: fields.clone(),
+    };
+    assert!(is_refused(&sign(&mut state, &as_ping)));
+    let as_endorsement = Signable::Consensus {
+        message_id: SignedMessageId::HighwayEndorsement,
+        fields,
+    };
+    assert!(is_refused(&sign(&mut state, &as_endorsement)));
+
+    // Trailing bytes are not ignored either.
+    let mut padded_ping = ping(0);
+    if let Signable::Consensus { fields, .. } = &mut padded_ping {
+        fields.push(0);
+    }
+    assert!(is_refused(&sign(&mut state, &padded_ping)));
+    assert!(!is_refused(&sign(&mut state, &ping(0))));
+}
+
+#[test]
+fn handshake_certificates_should_not_sign_the_bare_connection_id() {
+    let signer = LocalSigner::new(Arc::new(secret_key()));
+    let message = Signable::Handshake([3; 32]);
+    let signature = signer.sign(&message).unwrap();
+    crypto::verify(message.bytes_to_sign(), &signature, signer.public_key()).unwrap();
+    assert!(crypto::verify([3; 32], &signature, signer.public_key()).is_err());
+}
+
+#[test]
+fn should_sign_legacy_handshake_certificates_only_on_authenticated_connections() {
+    let mut state = SignerState::new(secret_key(), SlashingProtection::in_memory());
+    let legacy_handshake = Signable::LegacyHandshake([3; 32]);
+    assert!(is_refused(&sign(&mut state, &legacy_handshake)));
+    assert!(!is_refused(&sign(
+        &mut state,
+        &Signable::Handshake([3; 32])
+    )));
+
+    match sign_on(&mut state, &legacy_handshake, true) {
+        SignerResponse::Signature(signature) => {
+            crypto::verify([3; 32], &signature, &PublicKey::from(&secret_key())).unwrap()
+        }
+        response => panic!("expected signature, got {:?}", response),
+    }
+}
+
+#[test]
+fn should_remember_signed_positions_across_restarts() {
+    let tempdir = TempDir::new().unwrap();
+    let state_path = tempdir.path().join("signer_state.bin");
+
+    let protection = SlashingProtection::open(state_path.clone()).unwrap();
+    let mut state = SignerState::new(secret_key(), protection);
+    assert!(!is_refused(&sign(&mut state, &unit(2, 0))));
+    assert!(!is_refused(&sign(&mut state, &unit(3, 0))));
+    drop(state);
+
+    let protection = SlashingProtection::open(state_path).unwrap();
+    let mut state = SignerState::new(secret_key(), protection);
+    assert!(is_refused(&sign(&mut state, &unit(3, 1))));
+    assert!(is_refused(&sign(&mut state, &unit(2, 1))));
+    assert!(!is_refused(&sign(&mut state, &unit(3, 0))));
+}
+
+#[test]
+fn should_sign_via_unix_socket() {
+    let tempdir = TempDir::new().unwrap();
+    let socket_path = tempdir.path().join("signer.sock");
+    let signer = start_signer(&tempdir, socket_path.to_str().unwrap(), None, None).unwrap();
+
+    // Only the signer's user may connect.
+    let mode = fs::metadata(&socket_path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+
+    let public_key = PublicKey::from(&secret_key());
+    assert_eq!(signer.public_key(), &public_key);
+
+    let message = unit(0, 0);
+    let signature = signer.sign(&message).unwrap();
+    crypto::verify(message.bytes_to_sign(), &signature, &public_key).unwrap();
+
+    match signer.sign(&unit(0, 1)) {
+        Err(Error::Refused(_)) => {}
+        result => panic!("expected refusal, got {:?}", result),
+    }
+    assert!(matches!(
+        signer.sign(&Signable::LegacyHandshake([3; 32])),
+        Err(Error::Refused(_))
+    ));
+
+    // A node expecting to authenticate is told the signer has no token.
+    let auth_token = AuthToken::new(b"0123456789abcdef".to_vec()).unwrap();
+    assert!(matches!(
+        RemoteSigner::connect(socket_path.to_str().unwrap(), Some(auth_token)),
+        Err(Error::AuthenticationFailed)
+    ));
+}
+
+#[test]
+fn should_not_replace_a_file_which_is_not_a_socket() {
+    let tempdir = TempDir::new().unwrap();
+    let secret_key_path = tempdir.path().join("secret_key.pem");
+    secret_key().to_file(&secret_key_path).unwrap();
+    let socket_path = tempdir.path().join("signer.sock");
+    fs::write(&socket_path, b"not a socket").unwrap();
+
+    assert!(super::run_signer(
+        &secret_key_path,
+        socket_path.to_str().unwrap(),
+        &tempdir.path().join("signer_state.bin"),
+        None
+    )
+    .is_err());
+    assert_eq!(fs::read(&socket_path).unwrap(), b"not a socket");
+}
+
+#[test]
+fn should_require_authentication_on_tcp() {
+    const TOKEN: &str = "0123456789abcdef0123456789abcdef";
+
+    // A signer listening on TCP refuses to start without a token.
+    let tempdir = TempDir::new().unwrap();
+    let secret_key_path = tempdir.path().join("secret_key.pem");
+    secret_key().to_file(&secret_key_path).unwrap();
+    assert!(super::run_signer(
+        &secret_key_path,
+        "127.0.0.1:0",
+        &tempdir.path().join("signer_state.bin"),
+        None
+    )
+    .is_err());
+
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let address = format!("127.0.0.1:{}", port);
+    let signer = start_signer(&tempdir, &address, Some(TOKEN), Some(TOKEN)).unwrap();
+    let message = unit(0, 0);
+    let signature = signer.sign(&message).unwrap();
+    crypto::verify(message.bytes_to_sign(), &signature, signer.public_key()).unwrap();
+
+    // Authenticated nodes on networks below 1.6.0 get their handshake certificates signed.
+    let signature = signer.sign(&Signable::LegacyHandshake([3; 32])).unwrap();
+    crypto::verify([3; 32], &signature, signer.public_key()).unwrap();
+
+    let wrong_token = AuthToken::new(b"fedcba9876543210fedcba9876543210".to_vec()).unwrap();
+    assert!(matches!(
+        RemoteSigner::connect(&address, Some(wrong_token)),
+        Err(Error::AuthenticationFailed)
+    ));
+    assert!(matches!(
+        RemoteSigner::connect(&address, None),
+        Err(Error::AuthenticationFailed)
+    ));
+    assert!(matches!(
+        AuthToken::new(b"short".to_vec()),
+        Err(Error::AuthTokenTooShort)
+    ));
+}
+
+#[test]
+fn should_require_an_auth_token_for_a_remote_signer_below_1_6_0() {
+    let tempdir = TempDir::new().unwrap();
+    let config = ConsensusConfig {
+        remote_signer_address: Some("127.0.0.1:1".to_string()),
+        ..ConsensusConfig::default()
+    };
+    assert!(matches!(
+        config.load_signer(tempdir.path(), ProtocolVersion::from_parts(1, 5, 5)),
+        Err(LoadSignerError::LegacyHandshakeWithoutAuthToken)
+    ));
+    // From 1.6.0, the node goes on to connect to the signer without a token.
+    assert!(matches!(
+        config.load_signer(tempdir.path(), ProtocolVersion::from_parts(1, 6, 0)),
+        Err(LoadSignerError::RemoteSigner { .. })
+    ));
+}
+
+#[tokio::test]
+async fn should_sign_off_the_calling_thread_if_signer_may_block() {
+    let signer = Arc::new(ThreadRecordingSigner {
+        signer: LocalSigner::new(Arc::new(secret_key())),
+        threads: Mutex::new(vec![]),
+    });
+    let message = unit(0, 0);
+    let signature = super::sign_async(signer.clone(), message.clone())
+        .await
+        .unwrap();
+    crypto::verify(message.bytes_to_sign(), &signature, signer.public_key()).unwrap();
+    let threads = signer.threads.lock().unwrap();
+    assert_eq!(threads.len(), 1);
+    assert_ne!(threads[0], thread::current().id());
+}
+
+#[test]
+fn protected_signer_should_refuse_conflicting_messages() {
+    let signer = LocalSigner::new(Arc::new(secret_key()));
+    let signer = ProtectedSigner::new(Arc::new(signer), SlashingProtection::in_memory());
+
+    let vote = zug_vote(4, true);
+    let signature = signer.sign(&vote).unwrap();
+    crypto::verify(vote.bytes_to_sign(), &signature, signer.public_key()).unwrap();
+    assert!(signer.sign(&vote).is_ok());
+    assert!(matches!(
+        signer.sign(&zug_vote(4, false)),
+        Err(Error::Refused(_))
+    ));
+}
+
+#[test]
+fn should_refuse_positions_below_retained_history() {
+    let mut protection = SlashingProtection::in_memory();
+    let public_key = PublicKey::from(&secret_key());
+    for seq_number in 0..1_001u64 {
+        protection
+            .check_and_record(&public_key, &unit(seq_number, 0))
+            .unwrap();
+    }
+    // The record of position 0 has been pruned, so nothing can be signed there anymore.
+    assert!(matches!(
+        protection.check_and_record(&public_key, &unit(0, 0)),
+        Err(Error::Refused(_))
+    ));
+    assert!(protection
+        .check_and_record(&public_key, &unit(1, 0))
+        .is_ok());
+}
+
+#[test]
+fn should_refuse_finality_signatures_at_or_below_seeded_heights() {
+    let mut rng = TestRng::new();
+    let tempdir = TempDir::new().unwrap();
+    let state_path = tempdir.path().join(SLASHING_PROTECTION_FILENAME);
+    let public_key = PublicKey::from(&secret_key());
+
+    let mut protection = SlashingProtection::open(state_path.clone()).unwrap();
+    assert!(!protection.has_signed(&public_key));
+    let signed_in_era_2 = finality_signature(&mut rng, 2, 25);
+    protection
+        .check_and_record(&public_key, &signed_in_era_2)
+        .unwrap();
+    protection
+        .seed_finality_signatures(&public_key, [(EraId::new(1), 15), (EraId::new(2), 20)])
+        .unwrap();
+    drop(protection);
+
+    // The seeded heights are persisted, and merged with what was signed before.
+    let mut protection = SlashingProtection::open(state_path).unwrap();
+    assert!(protection.has_signed(&public_key));
+    for (era_id, height) in [(1, 14), (1, 15), (2, 20)] {
+        assert!(matches!(
+            protection.check_and_record(&public_key, &finality_signature(&mut rng, era_id, height)),
+            Err(Error::Refused(_))
+        ));
+    }
+    assert!(protection
+        .check_and_record(&public_key, &signed_in_era_2)
+        .is_ok());
+    assert!(protection
+        .check_and_record(&public_key, &finality_signature(&mut rng, 1, 16))
+        .is_ok());
+    assert!(protection
+        .check_and_record(&public_key, &finality_signature(&mut rng, 3, 0))
+        .is_ok());
+}
+
+#[test]
+fn should_refuse_to_sign_in_retired_sequences() {
+    let mut protection = SlashingProtection::in_memory();
+    let public_key = PublicKey::from(&secret_key());
+    protection
+        .check_and_record(&public_key, &unit_in(0, 0, 0))
+        .unwrap();
+    thread::sleep(Duration::from_millis(2));
+    // Signing in 100 more sequences retires the least recently used one.
+    for instance in 1..=100 {
+        protection
+            .check_and_record(&public_key, &unit_in(instance, 0, 0))
+            .unwrap();
+    }
+    assert!(matches!(
+        protection.check_and_record(&public_key, &unit_in(0, 0, 0)),
+        Err(Error::Refused(_))
+    ));
+    assert!(protection
+        .check_and_record(&public_key, &unit_in(1, 0, 0))
+        .is_ok());
+
+    // The retired sequence is exported, too.
+    let mut other_machine = SlashingProtection::in_memory();
+    other_machine.import(&protection.export().unwrap()).unwrap();
+    assert!(matches!(
+        other_machine.check_and_record(&public_key, &unit_in(0, 0, 0)),
+        Err(Error::Refused(_))
+    ));
+}
+
+#[test]
+fn should_discard_incomplete_last_record() {
+    let tempdir = TempDir::new().unwrap();
+    let path = tempdir.path().join(SLASHING_PROTECTION_FILENAME);
+    let public_key = PublicKey::from(&secret_key());
+    let mut protection = SlashingProtection::open(path.clone()).unwrap();
+    protection
+        .check_and_record(&public_key, &unit(0, 0))
+        .unwrap();
+    drop(protection);
+
+    // Simulate a crash while appending a record.
+    let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+    file.write_all(&[0, 0, 1, 0, 42]).unwrap();
+    drop(file);
+
+    let mut protection = SlashingProtection::open(path.clone()).unwrap();
+    assert!(matches!(
+        protection.check_and_record(&public_key, &unit(0, 1)),
+        Err(Error::Refused(_))
+    ));
+    protection
+        .check_and_record(&public_key, &unit(1, 0))
+        .unwrap();
+    drop(protection);
+
+    let mut protection = SlashingProtection::open(path).unwrap();
+    assert!(matches!(
+        protection.check_and_record(&public_key, &unit(1, 1)),
+        Err(Error::Refused(_))
+    ));
+}
+
+#[test]
+fn should_lock_the_database_while_open() {
+    let tempdir = TempDir::new().unwrap();
+    let path = tempdir.path().join(SLASHING_PROTECTION_FILENAME);
+    let public_key = PublicKey::from(&secret_key());
+    let mut protection = SlashingProtection::open(path.clone()).unwrap();
+    assert!(matches!(
+        SlashingProtection::open(path.clone()),
+        Err(Error::DatabaseLocked(_))
+    ));
+
+    // Importing compacts the log, and the lock is carried over to the compacted one.
+    let mut other_machine = SlashingProtection::in_memory();
+    other_machine
+        .check_and_record(&public_key, &unit(0, 0))
+        .unwrap();
+    protection.import(&other_machine.export().unwrap()).unwrap();
+    assert!(matches!(
+        SlashingProtection::open(path.clone()),
+        Err(Error::DatabaseLocked(_))
+    ));
+
+    drop(protection);
+    assert!(SlashingProtection::open(path).is_ok());
+}
+
+#[test]
+fn should_export_and_import_slashing_protection() {
+    let public_key = PublicKey::from(&secret_key());
+    let mut old_machine = SlashingProtection::in_memory();
+    old_machine
+        .check_and_record(&public_key, &unit(0, 0))
+        .unwrap();
+    old_machine
+        .check_and_record(&public_key, &unit(1, 0))
+        .unwrap();
+    let exported = old_machine.export().unwrap();
+
+    let tempdir = TempDir::new().unwrap();
+    let path = tempdir.path().join(SLASHING_PROTECTION_FILENAME);
+    let mut new_machine = SlashingProtection::open(path.clone()).unwrap();
+    new_machine
+        .check_and_record(&public_key, &unit(1, 1))
+        .unwrap();
+    assert_eq!(new_machine.import(&exported).unwrap(), 1);
+    drop(new_machine);
+
+    let mut new_machine = SlashingProtection::open(path).unwrap();
+    assert!(matches!(
+        new_machine.check_and_record(&public_key, &unit(0, 1)),
+        Err(Error::Refused(_))
+    ));
+    assert!(new_machine
+        .check_and_record(&public_key, &unit(0, 0))
+        .is_ok());
+    assert!(new_machine
+        .check_and_record(&public_key, &unit(2, 0))
+        .is_ok());
+}
+
+#[test]
+fn should_reject_unsupported_interchange_version() {
+    let mut protection = SlashingProtection::in_memory();
+    let interchange = br#"{"version": 2, "validators": []}"#;
+    assert!(matches!(
+        protection.import(interchange),
+        Err(Error::UnsupportedInterchangeVersion(2))
+    ));
+}
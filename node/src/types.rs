@@ -27,12 +27,12 @@ use rand_chacha::ChaCha20Rng;
 
 pub use available_block_range::AvailableBlockRange;
 pub(crate) use block::{
-    compute_approvals_checksum, ApprovalsHashes, BlockHashAndHeight, BlockHeaderWithMetadata,
-    BlockPayload, BlockWithMetadata, FinalitySignatureId, MetaBlock, MetaBlockMergeError,
-    MetaBlockState,
+    compute_approvals_checksum, ApprovalsHashes, ArchivedBlock, ArchivedBlockValidationError,
+    BlockHashAndHeight, BlockHeaderWithMetadata, BlockPayload, BlockWithMetadata,
+    FinalitySignatureId, MetaBlock, MetaBlockMergeError, MetaBlockState,
 };
 pub use block::{
-    json_compatibility::{JsonBlock, JsonBlockHeader},
+    json_compatibility::{JsonBlock, JsonBlockHeader, JsonProof},
     Block, BlockAndDeploys, BlockBody, BlockExecutionResultsOrChunk,
     BlockExecutionResultsOrChunkId, BlockExecutionResultsOrChunkIdDisplay, BlockHash, BlockHeader,
     BlockSignatures, FinalitySignature, FinalizedBlock,
@@ -45,12 +45,13 @@ pub use datasize::DataSize;
 pub use deploy::{
     Approval, ApprovalsHash, Deploy, DeployConfigurationFailure, DeployError, DeployHash,
     DeployHeader, DeployOrTransferHash, ExcessiveSizeError as ExcessiveSizeDeployError,
+    JsonDeployFootprint, PendingDeploy, PendingDeployStatus, StagedDeploy, StagedDeployStatus,
 };
 pub(crate) use deploy::{
     DeployFootprint, DeployHashWithApprovals, DeployId, DeployMetadata, DeployMetadataExt,
     DeployWithFinalizedApprovals, FinalizedApprovals, LegacyDeploy,
 };
-pub use error::BlockValidationError;
+pub use error::{BlockValidationError, GlobalStateQueryVerificationError};
 pub use exit_code::ExitCode;
 pub(crate) use max_ttl::MaxTtl;
 pub use node_config::{NodeConfig, SyncHandling};
@@ -58,6 +59,7 @@ pub(crate) use node_id::NodeId;
 pub use peers_map::PeersMap;
 pub use status_feed::{ChainspecInfo, GetStatusResult, StatusFeed};
 pub(crate) use sync_leap::{GlobalStatesMetadata, SyncLeap, SyncLeapIdentifier};
+pub use sync_leap::{JsonSignedBlockHeader, JsonSyncLeap};
 pub(crate) use validator_matrix::{EraValidatorWeights, SignatureWeight, ValidatorMatrix};
 pub use value_or_chunk::{
     ChunkingError, TrieOrChunk, TrieOrChunkId, TrieOrChunkIdDisplay, ValueOrChunk,
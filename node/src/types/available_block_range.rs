@@ -43,6 +43,15 @@ impl AvailableBlockRange {
         AvailableBlockRange { low, high }
     }
 
+    /// Returns the range with its lower bound raised to at least `low`, but not above the upper
+    /// bound.
+    pub(crate) fn with_min_low(self, low: u64) -> Self {
+        AvailableBlockRange {
+            low: self.low.max(low).min(self.high),
+            high: self.high,
+        }
+    }
+
     /// Returns `true` if `height` is within the range.
     pub fn contains(&self, height: u64) -> bool {
         height >= self.low && height <= self.high
@@ -6,6 +6,7 @@
 pub(crate) mod test_block_builder;
 
 mod approvals_hashes;
+mod archived_block;
 mod meta_block;
 
 use std::{
@@ -20,6 +21,7 @@ use std::{
 
 use datasize::DataSize;
 use derive_more::Into;
+use num_rational::Ratio;
 use once_cell::sync::{Lazy, OnceCell};
 #[cfg(any(feature = "testing", test))]
 use rand::Rng;
@@ -31,7 +33,8 @@ use tracing::error;
 use casper_hashing::{ChunkWithProofVerificationError, Digest};
 use casper_types::{
     bytesrepr::{self, FromBytes, ToBytes},
-    crypto, EraId, ProtocolVersion, PublicKey, SecretKey, Signature, Timestamp, U512,
+    crypto, EraId, EraTransitionHeader, Key, ProtocolVersion, PublicKey, SecretKey, Signature,
+    StoredValue, Timestamp, U512,
 };
 #[cfg(any(feature = "testing", test))]
 use casper_types::{
@@ -48,13 +51,17 @@ use crate::{
     effect::GossipTarget,
     rpcs::docs::DocExample,
     types::{
-        error::{BlockCreationError, BlockHeaderWithMetadataValidationError, BlockValidationError},
+        error::{
+            BlockCreationError, BlockHeaderWithMetadataValidationError, BlockValidationError,
+            GlobalStateQueryVerificationError,
+        },
         Approval, Chunkable, Deploy, DeployHash, DeployHashWithApprovals, DeployId,
         DeployOrTransferHash, JsonBlock, JsonBlockHeader, ValueOrChunk,
     },
     utils::{ds, DisplayIter},
 };
-pub(crate) use approvals_hashes::ApprovalsHashes;
+pub(crate) use approvals_hashes::{ApprovalsHashes, ApprovalsHashesValidationError};
+pub(crate) use archived_block::{ArchivedBlock, ArchivedBlockValidationError};
 pub(crate) use meta_block::{
     MergeMismatchError as MetaBlockMergeError, MetaBlock, State as MetaBlockState,
 };
@@ -1042,6 +1049,54 @@ impl BlockHeader {
         self.block_hash.get_or_init(|| block_hash);
     }
 
+    /// Verifies that a global state query for `key` and `path` yields `value` in the global state
+    /// resulting from this block, and that this block is finalized.
+    ///
+    /// The block is considered finalized if `finality_signatures` are valid signatures of it by a
+    /// quorum of `trusted_validator_weights`, the validators of the block's era.  `merkle_proof` is
+    /// the hex-encoded proof returned along with the query result.  Only the validator weights
+    /// need to be trusted; the header, signatures and proof may be supplied by an untrusted node.
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_global_state_query(
+        &self,
+        finality_signatures: &[FinalitySignature],
+        trusted_validator_weights: &BTreeMap<PublicKey, U512>,
+        fault_tolerance_fraction: Ratio<u64>,
+        key: &Key,
+        path: &[String],
+        value: &StoredValue,
+        merkle_proof: &str,
+    ) -> Result<(), GlobalStateQueryVerificationError> {
+        let block_hash = self.block_hash();
+        let mut signatures = BTreeMap::new();
+        for signature in finality_signatures {
+            if signature.block_hash != block_hash || signature.era_id != self.era_id {
+                return Err(GlobalStateQueryVerificationError::SignatureForOtherBlock {
+                    public_key: Box::new(signature.public_key.clone()),
+                    block_hash: signature.block_hash,
+                    era_id: signature.era_id,
+                });
+            }
+            let _ = signatures.insert(signature.public_key.clone(), signature.signature);
+        }
+
+        casper_types::verify_finality_signatures(
+            block_hash.inner().value(),
+            self.era_id,
+            &signatures,
+            trusted_validator_weights,
+            fault_tolerance_fraction,
+        )?;
+        casper_types::verify_global_state_query(
+            &self.state_root_hash.value(),
+            key,
+            path,
+            value,
+            merkle_proof,
+        )?;
+        Ok(())
+    }
+
     /// Returns true if block is Genesis.
     /// Genesis child block is from era 0 and height 0.
     pub(crate) fn is_genesis(&self) -> bool {
@@ -1054,6 +1109,28 @@ impl BlockHeader {
     }
 }
 
+impl EraTransitionHeader for BlockHeader {
+    fn block_hash(&self) -> [u8; Digest::LENGTH] {
+        BlockHeader::block_hash(self).inner().value()
+    }
+
+    fn parent_hash(&self) -> [u8; Digest::LENGTH] {
+        self.parent_hash.inner().value()
+    }
+
+    fn era_id(&self) -> EraId {
+        self.era_id
+    }
+
+    fn height(&self) -> u64 {
+        self.height
+    }
+
+    fn next_era_validator_weights(&self) -> Option<&BTreeMap<PublicKey, U512>> {
+        BlockHeader::next_era_validator_weights(self)
+    }
+}
+
 impl PartialEq for BlockHeader {
     fn eq(&self, other: &BlockHeader) -> bool {
         // Destructure to make sure we don't accidentally omit fields.
@@ -1395,6 +1472,36 @@ impl Display for BlockSignatures {
     }
 }
 
+impl ToBytes for BlockSignatures {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        buffer.extend(self.block_hash.to_bytes()?);
+        buffer.extend(self.era_id.to_bytes()?);
+        buffer.extend(self.proofs.to_bytes()?);
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.block_hash.serialized_length()
+            + self.era_id.serialized_length()
+            + self.proofs.serialized_length()
+    }
+}
+
+impl FromBytes for BlockSignatures {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (block_hash, remainder) = BlockHash::from_bytes(bytes)?;
+        let (era_id, remainder) = EraId::from_bytes(remainder)?;
+        let (proofs, remainder) = BTreeMap::<PublicKey, Signature>::from_bytes(remainder)?;
+        let block_signatures = BlockSignatures {
+            block_hash,
+            era_id,
+            proofs,
+        };
+        Ok((block_signatures, remainder))
+    }
+}
+
 /// A proposed block after execution, with the resulting post-state-hash.  This is the core
 /// component of the Casper linear blockchain.
 #[derive(DataSize, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -1798,6 +1905,7 @@ impl GossipItem for Block {
 
     const ID_IS_COMPLETE_ITEM: bool = false;
     const REQUIRES_GOSSIP_RECEIVED_ANNOUNCEMENT: bool = true;
+    const IS_REGOSSIPED_PERIODICALLY: bool = false;
 
     fn gossip_id(&self) -> Self::Id {
         *self.hash()
@@ -2433,8 +2541,7 @@ impl FinalitySignature {
         secret_key: &SecretKey,
         public_key: PublicKey,
     ) -> Self {
-        let mut bytes = block_hash.inner().into_vec();
-        bytes.extend_from_slice(&era_id.to_le_bytes());
+        let bytes = Self::bytes_to_sign(&block_hash, era_id);
         let signature = crypto::sign(bytes, secret_key, &public_key);
         FinalitySignature {
             block_hash,
@@ -2461,13 +2568,18 @@ impl FinalitySignature {
         }
     }
 
+    /// Returns the bytes a validator signs to create a finality signature for the given block.
+    pub(crate) fn bytes_to_sign(block_hash: &BlockHash, era_id: EraId) -> Vec<u8> {
+        let mut bytes = block_hash.inner().into_vec();
+        bytes.extend_from_slice(&era_id.to_le_bytes());
+        bytes
+    }
+
     /// Verifies whether the signature is correct.
     pub fn is_verified(&self) -> Result<(), crypto::Error> {
         self.is_verified
             .get_or_init(|| {
-                // NOTE: This needs to be in sync with the `new` constructor.
-                let mut bytes = self.block_hash.inner().into_vec();
-                bytes.extend_from_slice(&self.era_id.to_le_bytes());
+                let bytes = Self::bytes_to_sign(&self.block_hash, self.era_id);
                 crypto::verify(bytes, &self.signature, &self.public_key)
             })
             .clone()
@@ -2586,6 +2698,7 @@ impl GossipItem for FinalitySignature {
 
     const ID_IS_COMPLETE_ITEM: bool = false;
     const REQUIRES_GOSSIP_RECEIVED_ANNOUNCEMENT: bool = true;
+    const IS_REGOSSIPED_PERIODICALLY: bool = false;
 
     fn gossip_id(&self) -> Self::Id {
         // Note: Unfortunately this is somewhat of a mismatch, as finality signature IDs are fairly
@@ -2634,9 +2747,90 @@ pub(crate) fn compute_approvals_checksum(
 mod tests {
     use std::rc::Rc;
 
-    use casper_types::{bytesrepr, testing::TestRng};
+    use casper_types::{bytesrepr, testing::TestRng, FinalitySignatureError};
 
-    use super::*;
+    use super::{test_block_builder::TestBlockBuilder, *};
+
+    #[test]
+    fn should_verify_global_state_query() {
+        let mut rng = TestRng::new();
+        let key = Key::Hash(rng.gen());
+        let value = StoredValue::CLValue(casper_types::CLValue::from_t(rng.gen::<u64>()).unwrap());
+
+        // A global state trie holding a single leaf, whose hash is the state root hash.
+        let mut leaf_bytes = vec![0_u8];
+        leaf_bytes.extend(key.to_bytes().unwrap());
+        leaf_bytes.extend(value.to_bytes().unwrap());
+        let state_root_hash = Digest::hash_into_chunks_if_necessary(&leaf_bytes);
+        let mut proof_bytes = 1_u32.to_bytes().unwrap();
+        proof_bytes.extend(key.to_bytes().unwrap());
+        proof_bytes.extend(value.to_bytes().unwrap());
+        proof_bytes.extend(0_u32.to_bytes().unwrap());
+        let merkle_proof = base16::encode_lower(&proof_bytes);
+
+        let block = TestBlockBuilder::new()
+            .state_root_hash(state_root_hash)
+            .build(&mut rng);
+        let header = block.header();
+        let validators: Vec<_> = (0..3).map(|_| generate_ed25519_keypair()).collect();
+        let validator_weights: BTreeMap<_, _> = validators
+            .iter()
+            .map(|(_, public_key)| (public_key.clone(), U512::from(10)))
+            .collect();
+        let signatures: Vec<_> = validators
+            .iter()
+            .map(|(secret_key, public_key)| {
+                FinalitySignature::create(
+                    *block.hash(),
+                    header.era_id(),
+                    secret_key,
+                    public_key.clone(),
+                )
+            })
+            .collect();
+        let fault_tolerance_fraction = Ratio::new(1, 3);
+
+        let verify = |signatures: &[FinalitySignature], value: &StoredValue| {
+            header.verify_global_state_query(
+                signatures,
+                &validator_weights,
+                fault_tolerance_fraction,
+                &key,
+                &[],
+                value,
+                &merkle_proof,
+            )
+        };
+
+        verify(&signatures, &value).unwrap();
+        assert!(matches!(
+            verify(&signatures[..1], &value),
+            Err(GlobalStateQueryVerificationError::NotFinalized(
+                FinalitySignatureError::InsufficientSignatureWeight { .. }
+            ))
+        ));
+        let other_block_signature = FinalitySignature::random_for_block(
+            BlockHash::random(&mut rng),
+            header.era_id().value(),
+        );
+        assert!(matches!(
+            verify(&[other_block_signature], &value),
+            Err(GlobalStateQueryVerificationError::SignatureForOtherBlock { .. })
+        ));
+        let non_validator_signature =
+            FinalitySignature::random_for_block(*block.hash(), header.era_id().value());
+        assert!(matches!(
+            verify(&[non_validator_signature], &value),
+            Err(GlobalStateQueryVerificationError::NotFinalized(
+                FinalitySignatureError::UnknownSigner { .. }
+            ))
+        ));
+        let other_value = StoredValue::CLValue(casper_types::CLValue::unit());
+        assert!(matches!(
+            verify(&signatures, &other_value),
+            Err(GlobalStateQueryVerificationError::InvalidProof(_))
+        ));
+    }
 
     #[test]
     fn json_block_roundtrip() {
@@ -10,7 +10,10 @@ use thiserror::Error;
 
 use casper_execution_engine::storage::trie::merkle_proof::TrieMerkleProof;
 use casper_hashing::Digest;
-use casper_types::{bytesrepr, Key, StoredValue};
+use casper_types::{
+    bytesrepr::{self, FromBytes, ToBytes},
+    Key, StoredValue,
+};
 
 use super::{Block, BlockHash};
 use crate::{
@@ -134,6 +137,38 @@ impl Display for ApprovalsHashes {
     }
 }
 
+impl ToBytes for ApprovalsHashes {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        buffer.extend(self.block_hash.to_bytes()?);
+        buffer.extend(self.approvals_hashes.to_bytes()?);
+        buffer.extend(self.merkle_proof_approvals.to_bytes()?);
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.block_hash.serialized_length()
+            + self.approvals_hashes.serialized_length()
+            + self.merkle_proof_approvals.serialized_length()
+    }
+}
+
+impl FromBytes for ApprovalsHashes {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (block_hash, remainder) = BlockHash::from_bytes(bytes)?;
+        let (approvals_hashes, remainder) = Vec::<ApprovalsHash>::from_bytes(remainder)?;
+        let (merkle_proof_approvals, remainder) =
+            TrieMerkleProof::<Key, StoredValue>::from_bytes(remainder)?;
+        let approvals_hashes = ApprovalsHashes {
+            block_hash,
+            approvals_hashes,
+            merkle_proof_approvals,
+            is_verified: OnceCell::new(),
+        };
+        Ok((approvals_hashes, remainder))
+    }
+}
+
 /// An error that can arise when validating `ApprovalsHashes`.
 #[derive(Error, Clone, Debug, PartialEq, Eq, DataSize)]
 #[non_exhaustive]
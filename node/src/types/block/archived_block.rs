@@ -0,0 +1,268 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Display, Formatter},
+};
+
+use num_rational::Ratio;
+use thiserror::Error;
+
+use casper_types::{
+    bytesrepr::{self, FromBytes, ToBytes},
+    crypto, EraId, ExecutionResult, PublicKey, U512,
+};
+
+use super::{ApprovalsHashes, ApprovalsHashesValidationError, Block, BlockHash, BlockSignatures};
+use crate::{
+    components::fetcher::FetchItem,
+    types::{
+        error::BlockValidationError, ApprovalsHash, DeployConfigurationFailure, DeployHash,
+        DeployWithFinalizedApprovals,
+    },
+    utils::{self, BlockSignatureError},
+};
+
+/// A block together with everything stored alongside it, in a form suitable for archiving outside
+/// of storage.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct ArchivedBlock {
+    block: Block,
+    signatures: BlockSignatures,
+    approvals_hashes: Option<ApprovalsHashes>,
+    deploys: Vec<DeployWithFinalizedApprovals>,
+    execution_results: Vec<(DeployHash, ExecutionResult)>,
+}
+
+impl ArchivedBlock {
+    pub(crate) fn new(
+        block: Block,
+        signatures: BlockSignatures,
+        approvals_hashes: Option<ApprovalsHashes>,
+        deploys: Vec<DeployWithFinalizedApprovals>,
+        execution_results: Vec<(DeployHash, ExecutionResult)>,
+    ) -> Self {
+        ArchivedBlock {
+            block,
+            signatures,
+            approvals_hashes,
+            deploys,
+            execution_results,
+        }
+    }
+
+    pub(crate) fn block(&self) -> &Block {
+        &self.block
+    }
+
+    pub(crate) fn signatures(&self) -> &BlockSignatures {
+        &self.signatures
+    }
+
+    pub(crate) fn approvals_hashes(&self) -> Option<&ApprovalsHashes> {
+        self.approvals_hashes.as_ref()
+    }
+
+    pub(crate) fn deploys(&self) -> &[DeployWithFinalizedApprovals] {
+        &self.deploys
+    }
+
+    pub(crate) fn execution_results(&self) -> &[(DeployHash, ExecutionResult)] {
+        &self.execution_results
+    }
+
+    /// Checks the hashes of the block and its deploys, the cryptographic validity of the
+    /// signatures, and that the deploys, execution results and approvals hashes all belong to the
+    /// block.
+    ///
+    /// The weight of the signatures is not checked; see [`Self::verify_signature_weight`].
+    pub(crate) fn verify(&self) -> Result<(), ArchivedBlockValidationError> {
+        self.block.verify()?;
+
+        if self.signatures.block_hash != *self.block.hash()
+            || self.signatures.era_id != self.block.header().era_id()
+        {
+            return Err(ArchivedBlockValidationError::SignaturesMismatch {
+                block_hash: *self.block.hash(),
+                signatures_block_hash: self.signatures.block_hash,
+                signatures_era_id: self.signatures.era_id,
+            });
+        }
+        self.signatures
+            .verify()
+            .map_err(ArchivedBlockValidationError::InvalidSignature)?;
+
+        let block_deploy_hashes: Vec<DeployHash> =
+            self.block.deploy_and_transfer_hashes().copied().collect();
+        let deploy_hashes: Vec<DeployHash> = self
+            .deploys
+            .iter()
+            .map(|deploy| *deploy.deploy().hash())
+            .collect();
+        if deploy_hashes != block_deploy_hashes {
+            return Err(ArchivedBlockValidationError::DeploysMismatch);
+        }
+        for deploy in &self.deploys {
+            deploy.deploy().is_valid().map_err(|error| {
+                ArchivedBlockValidationError::InvalidDeploy {
+                    deploy_hash: *deploy.deploy().hash(),
+                    error,
+                }
+            })?;
+        }
+
+        let result_hashes: BTreeSet<DeployHash> = self
+            .execution_results
+            .iter()
+            .map(|(deploy_hash, _)| *deploy_hash)
+            .collect();
+        if result_hashes.len() != self.execution_results.len()
+            || result_hashes != block_deploy_hashes.iter().copied().collect()
+        {
+            return Err(ArchivedBlockValidationError::ExecutionResultsMismatch);
+        }
+
+        if let Some(approvals_hashes) = &self.approvals_hashes {
+            if approvals_hashes.block_hash() != self.block.hash() {
+                return Err(ArchivedBlockValidationError::ApprovalsHashesMismatch);
+            }
+            approvals_hashes.validate(&self.block)?;
+            // The approvals the deploys were executed with must be the ones agreed by consensus.
+            if approvals_hashes.approvals_hashes().len() != self.deploys.len() {
+                return Err(ArchivedBlockValidationError::ApprovalsHashesMismatch);
+            }
+            for (deploy, expected) in self.deploys.iter().zip(approvals_hashes.approvals_hashes()) {
+                let approvals = deploy.finalized_approvals().map_or_else(
+                    || deploy.deploy().approvals(),
+                    |approvals| approvals.inner(),
+                );
+                let actual = ApprovalsHash::compute(approvals)
+                    .map_err(ArchivedBlockValidationError::BytesRepr)?;
+                if actual != *expected {
+                    return Err(ArchivedBlockValidationError::ApprovalsHashesMismatch);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the signatures carry enough weight of the given validators to consider the
+    /// block finalized.
+    pub(crate) fn verify_signature_weight(
+        &self,
+        validator_weights: &BTreeMap<PublicKey, U512>,
+        finality_threshold_fraction: Ratio<u64>,
+    ) -> Result<(), ArchivedBlockValidationError> {
+        utils::check_sufficient_block_signatures(
+            validator_weights,
+            finality_threshold_fraction,
+            Some(&self.signatures),
+        )
+        .map_err(|error| ArchivedBlockValidationError::InsufficientSignatures(Box::new(error)))
+    }
+}
+
+impl Display for ArchivedBlock {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "archived block {} at height {} with {} deploys and {} signatures",
+            self.block.hash(),
+            self.block.height(),
+            self.deploys.len(),
+            self.signatures.proofs.len()
+        )
+    }
+}
+
+impl ToBytes for ArchivedBlock {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        buffer.extend(self.block.to_bytes()?);
+        buffer.extend(self.signatures.to_bytes()?);
+        buffer.extend(self.approvals_hashes.to_bytes()?);
+        buffer.extend(self.deploys.to_bytes()?);
+        buffer.extend(self.execution_results.to_bytes()?);
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.block.serialized_length()
+            + self.signatures.serialized_length()
+            + self.approvals_hashes.serialized_length()
+            + self.deploys.serialized_length()
+            + self.execution_results.serialized_length()
+    }
+}
+
+impl FromBytes for ArchivedBlock {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (block, remainder) = Block::from_bytes(bytes)?;
+        let (signatures, remainder) = BlockSignatures::from_bytes(remainder)?;
+        let (approvals_hashes, remainder) = Option::<ApprovalsHashes>::from_bytes(remainder)?;
+        let (deploys, remainder) = Vec::<DeployWithFinalizedApprovals>::from_bytes(remainder)?;
+        let (execution_results, remainder) =
+            Vec::<(DeployHash, ExecutionResult)>::from_bytes(remainder)?;
+        let archived_block = ArchivedBlock {
+            block,
+            signatures,
+            approvals_hashes,
+            deploys,
+            execution_results,
+        };
+        Ok((archived_block, remainder))
+    }
+}
+
+/// An error that can arise when validating an `ArchivedBlock`.
+#[derive(Error, Debug)]
+pub(crate) enum ArchivedBlockValidationError {
+    /// The block's hashes are invalid.
+    #[error(transparent)]
+    Block(#[from] BlockValidationError),
+
+    /// The signatures are not for the archived block.
+    #[error(
+        "signatures for block {signatures_block_hash} in {signatures_era_id} archived with block \
+         {block_hash}"
+    )]
+    SignaturesMismatch {
+        block_hash: BlockHash,
+        signatures_block_hash: BlockHash,
+        signatures_era_id: EraId,
+    },
+
+    /// One of the signatures is invalid.
+    #[error("invalid block signature: {0}")]
+    InvalidSignature(crypto::Error),
+
+    /// The signatures don't carry enough weight.
+    #[error("insufficient block signatures: {0}")]
+    InsufficientSignatures(Box<BlockSignatureError>),
+
+    /// The deploys don't match the ones listed in the block.
+    #[error("archived deploys don't match those of the block")]
+    DeploysMismatch,
+
+    /// One of the deploys is invalid.
+    #[error("invalid deploy {deploy_hash}: {error}")]
+    InvalidDeploy {
+        deploy_hash: DeployHash,
+        error: DeployConfigurationFailure,
+    },
+
+    /// The execution results don't match the deploys listed in the block.
+    #[error("archived execution results don't match the deploys of the block")]
+    ExecutionResultsMismatch,
+
+    /// The approvals hashes don't match the block or its deploys.
+    #[error("archived approvals hashes don't match the block or its deploys")]
+    ApprovalsHashesMismatch,
+
+    /// The approvals hashes are invalid.
+    #[error(transparent)]
+    ApprovalsHashes(#[from] ApprovalsHashesValidationError),
+
+    /// Failed to compute the hash of a deploy's approvals.
+    #[error("failed to compute approvals hash: {0}")]
+    BytesRepr(bytesrepr::Error),
+}
@@ -7,6 +7,7 @@ use num_traits::Zero;
 #[cfg(test)]
 use rand::Rng;
 use serde::{Deserialize, Serialize};
+use tracing::warn;
 
 #[cfg(test)]
 use casper_execution_engine::core::engine_state::MAX_PAYMENT_AMOUNT;
@@ -35,11 +36,25 @@ pub struct DeployConfig {
     pub(crate) session_args_max_length: u32,
     pub(crate) native_transfer_minimum_motes: u64,
     pub(crate) max_timestamp_leeway: TimeDiff,
+    pub(crate) max_scheduling_delay: TimeDiff,
 }
 
 impl DeployConfig {
     /// Validates `DeployConfig` parameters
     pub fn is_valid(&self) -> bool {
+        // A scheduled deploy's scheduling delay plus its TTL may not exceed the maximum TTL, so
+        // deploys scheduled as far ahead as allowed must still be left some TTL.
+        if self.max_scheduling_delay > TimeDiff::from_seconds(0)
+            && self.max_scheduling_delay >= self.max_ttl
+        {
+            warn!(
+                max_scheduling_delay = %self.max_scheduling_delay,
+                max_ttl = %self.max_ttl,
+                "max scheduling delay should be less than max ttl",
+            );
+            return false;
+        }
+
         // the total number of deploys + transfers should not exceed the number of approvals because
         // each deploy or transfer needs at least one approval to be valid
         if let Some(total_deploy_and_transfer_slots) = self
@@ -71,6 +86,7 @@ impl DeployConfig {
         let native_transfer_minimum_motes =
             rng.gen_range(MAX_PAYMENT_AMOUNT..1_000_000_000_000_000);
         let max_timestamp_leeway = TimeDiff::from_seconds(rng.gen_range(0..6));
+        let max_scheduling_delay = TimeDiff::from_millis(rng.gen_range(0..max_ttl.millis()));
 
         DeployConfig {
             max_payment_cost,
@@ -86,6 +102,7 @@ impl DeployConfig {
             session_args_max_length,
             native_transfer_minimum_motes,
             max_timestamp_leeway,
+            max_scheduling_delay,
         }
     }
 }
@@ -107,6 +124,7 @@ impl Default for DeployConfig {
             session_args_max_length: 1024,
             native_transfer_minimum_motes: MAX_PAYMENT_AMOUNT,
             max_timestamp_leeway: TimeDiff::from_str("5sec").unwrap(),
+            max_scheduling_delay: TimeDiff::from_seconds(0),
         }
     }
 }
@@ -127,6 +145,7 @@ impl ToBytes for DeployConfig {
         buffer.extend(self.session_args_max_length.to_bytes()?);
         buffer.extend(self.native_transfer_minimum_motes.to_bytes()?);
         buffer.extend(self.max_timestamp_leeway.to_bytes()?);
+        buffer.extend(self.max_scheduling_delay.to_bytes()?);
         Ok(buffer)
     }
 
@@ -144,6 +163,7 @@ impl ToBytes for DeployConfig {
             + self.session_args_max_length.serialized_length()
             + self.native_transfer_minimum_motes.serialized_length()
             + self.max_timestamp_leeway.serialized_length()
+            + self.max_scheduling_delay.serialized_length()
     }
 }
 
@@ -163,6 +183,7 @@ impl FromBytes for DeployConfig {
         let (session_args_max_length, remainder) = u32::from_bytes(remainder)?;
         let (native_transfer_minimum_motes, remainder) = u64::from_bytes(remainder)?;
         let (max_timestamp_leeway, remainder) = TimeDiff::from_bytes(remainder)?;
+        let (max_scheduling_delay, remainder) = TimeDiff::from_bytes(remainder)?;
         let config = DeployConfig {
             max_payment_cost,
             max_ttl,
@@ -177,6 +198,7 @@ impl FromBytes for DeployConfig {
             session_args_max_length,
             native_transfer_minimum_motes,
             max_timestamp_leeway,
+            max_scheduling_delay,
         };
         Ok((config, remainder))
     }
@@ -228,4 +250,27 @@ mod tests {
         };
         assert!(config.is_valid());
     }
+    #[test]
+    fn scheduling_delay_valid() {
+        let config = DeployConfig {
+            max_ttl: TimeDiff::from_seconds(3600),
+            max_scheduling_delay: TimeDiff::from_seconds(3600),
+            ..Default::default()
+        };
+        assert!(!config.is_valid());
+
+        let config = DeployConfig {
+            max_ttl: TimeDiff::from_seconds(3600),
+            max_scheduling_delay: TimeDiff::from_seconds(1800),
+            ..Default::default()
+        };
+        assert!(config.is_valid());
+
+        let config = DeployConfig {
+            max_ttl: TimeDiff::from_seconds(0),
+            max_scheduling_delay: TimeDiff::from_seconds(0),
+            ..Default::default()
+        };
+        assert!(config.is_valid());
+    }
 }
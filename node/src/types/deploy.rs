@@ -14,6 +14,8 @@ mod footprint;
 mod id;
 mod legacy_deploy;
 mod metadata;
+mod pending_deploy;
+mod staged_deploy;
 
 use std::{
     cmp,
@@ -71,6 +73,8 @@ pub(crate) use footprint::Footprint as DeployFootprint;
 pub use id::Id as DeployId;
 pub(crate) use legacy_deploy::LegacyDeploy;
 pub(crate) use metadata::{Metadata as DeployMetadata, MetadataExt as DeployMetadataExt};
+pub use pending_deploy::{JsonDeployFootprint, PendingDeploy, PendingDeployStatus};
+pub use staged_deploy::{StagedDeploy, StagedDeployStatus};
 
 static DEPLOY: Lazy<Deploy> = Lazy::new(|| {
     let payment_args = runtime_args! {
@@ -297,6 +301,11 @@ impl Deploy {
     /// Returns Ok if and only if:
     ///   * the chain_name is correct,
     ///   * the configured parameters are complied with at the given timestamp
+    ///
+    /// The deploy's timestamp may be up to `timestamp_leeway` plus the configured
+    /// `max_scheduling_delay` later than `at`, i.e. the deploy may be scheduled to execute later.
+    /// As the TTL is measured from the timestamp, such a deploy only starts to expire once its
+    /// scheduled time has passed, so its scheduling delay plus its TTL may not exceed `max_ttl`.
     pub fn is_config_compliant(
         &self,
         chain_name: &str,
@@ -321,7 +330,28 @@ impl Deploy {
             });
         }
 
-        header.is_valid(config, timestamp_leeway, at, &self.hash)?;
+        header.is_valid(
+            config,
+            timestamp_leeway + config.max_scheduling_delay,
+            at,
+            &self.hash,
+        )?;
+
+        let scheduling_delay = header.timestamp().saturating_diff(at + timestamp_leeway);
+        if scheduling_delay.saturating_add(header.ttl().millis()) > config.max_ttl {
+            debug!(
+                deploy_hash = %self.hash(),
+                deploy_header = %header,
+                %scheduling_delay,
+                max_ttl = %config.max_ttl,
+                "scheduled deploy ttl excessive"
+            );
+            return Err(DeployConfigurationFailure::ExcessiveScheduledTimeToLive {
+                max_ttl: config.max_ttl,
+                scheduling_delay,
+                ttl: header.ttl(),
+            });
+        }
 
         if self.approvals.len() > max_associated_keys as usize {
             debug!(
@@ -553,6 +583,7 @@ impl GossipItem for Deploy {
 
     const ID_IS_COMPLETE_ITEM: bool = false;
     const REQUIRES_GOSSIP_RECEIVED_ANNOUNCEMENT: bool = false;
+    const IS_REGOSSIPED_PERIODICALLY: bool = false;
 
     fn gossip_id(&self) -> Self::Id {
         let deploy_hash = *self.hash();
@@ -1413,6 +1444,101 @@ mod tests {
         );
     }
 
+    #[test]
+    fn acceptable_if_scheduled_within_max_scheduling_delay() {
+        let mut rng = crate::new_rng();
+        let chain_name = "net-1";
+        let deploy_config = DeployConfig {
+            max_scheduling_delay: TimeDiff::from_seconds(3600),
+            ..Default::default()
+        };
+        let leeway = TimeDiff::from_seconds(2);
+
+        let deploy = create_deploy(
+            &mut rng,
+            deploy_config.max_ttl - deploy_config.max_scheduling_delay,
+            deploy_config.max_dependencies.into(),
+            chain_name,
+        );
+        let current_timestamp = deploy.header.timestamp() - TimeDiff::from_seconds(1800);
+        assert_eq!(
+            deploy.is_config_compliant(
+                chain_name,
+                &deploy_config,
+                DEFAULT_MAX_ASSOCIATED_KEYS,
+                leeway,
+                current_timestamp
+            ),
+            Ok(())
+        );
+
+        let current_timestamp = deploy.header.timestamp()
+            - leeway
+            - deploy_config.max_scheduling_delay
+            - TimeDiff::from_seconds(1);
+        let expected_error = DeployConfigurationFailure::TimestampInFuture {
+            validation_timestamp: current_timestamp,
+            timestamp_leeway: leeway + deploy_config.max_scheduling_delay,
+            got: deploy.header.timestamp(),
+        };
+        assert_eq!(
+            deploy.is_config_compliant(
+                chain_name,
+                &deploy_config,
+                DEFAULT_MAX_ASSOCIATED_KEYS,
+                leeway,
+                current_timestamp
+            ),
+            Err(expected_error)
+        );
+    }
+
+    #[test]
+    fn not_acceptable_if_scheduled_beyond_max_ttl() {
+        let mut rng = crate::new_rng();
+        let chain_name = "net-1";
+        let deploy_config = DeployConfig {
+            max_scheduling_delay: TimeDiff::from_seconds(3600),
+            ..Default::default()
+        };
+        let leeway = TimeDiff::from_seconds(2);
+
+        let deploy = create_deploy(
+            &mut rng,
+            deploy_config.max_ttl - TimeDiff::from_seconds(1800),
+            deploy_config.max_dependencies.into(),
+            chain_name,
+        );
+        let current_timestamp = deploy.header.timestamp() - leeway - TimeDiff::from_seconds(1800);
+        assert_eq!(
+            deploy.is_config_compliant(
+                chain_name,
+                &deploy_config,
+                DEFAULT_MAX_ASSOCIATED_KEYS,
+                leeway,
+                current_timestamp
+            ),
+            Ok(())
+        );
+
+        let current_timestamp = current_timestamp - TimeDiff::from_millis(1);
+        let expected_error = DeployConfigurationFailure::ExcessiveScheduledTimeToLive {
+            max_ttl: deploy_config.max_ttl,
+            scheduling_delay: TimeDiff::from_seconds(1800) + TimeDiff::from_millis(1),
+            ttl: deploy.header.ttl(),
+        };
+        assert_eq!(
+            deploy.is_config_compliant(
+                chain_name,
+                &deploy_config,
+                DEFAULT_MAX_ASSOCIATED_KEYS,
+                leeway,
+                current_timestamp
+            ),
+            Err(expected_error)
+        );
+    }
+
     #[test]
     fn acceptable_if_timestamp_slightly_in_future() {
         let mut rng = crate::new_rng();
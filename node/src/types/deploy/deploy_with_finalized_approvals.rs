@@ -4,6 +4,8 @@ use std::collections::BTreeSet;
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
+use casper_types::bytesrepr::{self, FromBytes, ToBytes};
+
 #[cfg(test)]
 use super::Approval;
 use super::{Deploy, FinalizedApprovals};
@@ -50,13 +52,44 @@ impl DeployWithFinalizedApprovals {
         self.deploy
     }
 
+    /// Returns the original deploy.
+    pub(crate) fn deploy(&self) -> &Deploy {
+        &self.deploy
+    }
+
     #[cfg(test)]
     pub(crate) fn original_approvals(&self) -> &BTreeSet<Approval> {
         self.deploy.approvals()
     }
 
-    #[cfg(test)]
+    /// Returns the approvals used to verify the deploy during block execution, if different from
+    /// the original ones.
     pub(crate) fn finalized_approvals(&self) -> Option<&FinalizedApprovals> {
         self.finalized_approvals.as_ref()
     }
 }
+
+impl ToBytes for DeployWithFinalizedApprovals {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        buffer.extend(self.deploy.to_bytes()?);
+        buffer.extend(self.finalized_approvals.to_bytes()?);
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.deploy.serialized_length() + self.finalized_approvals.serialized_length()
+    }
+}
+
+impl FromBytes for DeployWithFinalizedApprovals {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (deploy, remainder) = Deploy::from_bytes(bytes)?;
+        let (finalized_approvals, remainder) = Option::<FinalizedApprovals>::from_bytes(remainder)?;
+        let deploy_with_finalized_approvals = DeployWithFinalizedApprovals {
+            deploy,
+            finalized_approvals,
+        };
+        Ok((deploy_with_finalized_approvals, remainder))
+    }
+}
@@ -48,12 +48,26 @@ pub enum DeployConfigurationFailure {
     TimestampInFuture {
         /// The node's timestamp when validating the deploy.
         validation_timestamp: Timestamp,
-        /// Any configured leeway added to `validation_timestamp`.
+        /// Any configured leeway and scheduling delay added to `validation_timestamp`.
         timestamp_leeway: TimeDiff,
         /// The deploy's timestamp.
         got: Timestamp,
     },
 
+    /// A scheduled deploy would remain pending for longer than the maximum time-to-live.
+    #[error(
+        "scheduling delay of {scheduling_delay} plus time-to-live of {ttl} exceeds limit of \
+        {max_ttl}"
+    )]
+    ExcessiveScheduledTimeToLive {
+        /// The time-to-live limit.
+        max_ttl: TimeDiff,
+        /// How far the deploy's timestamp lies beyond the node's validation timestamp plus leeway.
+        scheduling_delay: TimeDiff,
+        /// The received time-to-live.
+        ttl: TimeDiff,
+    },
+
     /// The provided body hash does not match the actual hash of the body.
     #[error("the provided body hash does not match the actual hash of the body")]
     InvalidBodyHash,
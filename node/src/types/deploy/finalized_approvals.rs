@@ -3,6 +3,8 @@ use std::collections::BTreeSet;
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
+use casper_types::bytesrepr::{self, FromBytes, ToBytes};
+
 use super::Approval;
 
 /// A set of approvals that has been agreed upon by consensus to approve of a specific deploy.
@@ -22,3 +24,24 @@ impl FinalizedApprovals {
         self.0
     }
 }
+
+impl ToBytes for FinalizedApprovals {
+    fn write_bytes(&self, writer: &mut Vec<u8>) -> Result<(), bytesrepr::Error> {
+        self.0.write_bytes(writer)
+    }
+
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        self.0.to_bytes()
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.0.serialized_length()
+    }
+}
+
+impl FromBytes for FinalizedApprovals {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        BTreeSet::<Approval>::from_bytes(bytes)
+            .map(|(approvals, remainder)| (FinalizedApprovals(approvals), remainder))
+    }
+}
@@ -0,0 +1,99 @@
+use std::fmt::{self, Display, Formatter};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use casper_types::{TimeDiff, U512};
+
+use super::{DeployFootprint, DeployHash, DeployHeader};
+
+/// The status of a deploy held in the deploy buffer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PendingDeployStatus {
+    /// The deploy may be included in the next block proposed by this node.
+    Proposable,
+    /// The deploy is included in a proposed block which has not yet been finalized.
+    Held,
+    /// The deploy's timestamp is in the future, and it may not be proposed before then.
+    Scheduled,
+    /// The deploy has been included in a finalized block, or has been found to be unproposable,
+    /// and will not be proposed again.
+    Dead,
+}
+
+impl Display for PendingDeployStatus {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PendingDeployStatus::Proposable => write!(formatter, "proposable"),
+            PendingDeployStatus::Held => write!(formatter, "held"),
+            PendingDeployStatus::Scheduled => write!(formatter, "scheduled"),
+            PendingDeployStatus::Dead => write!(formatter, "dead"),
+        }
+    }
+}
+
+/// Information about how much block limit a deploy will consume.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JsonDeployFootprint {
+    /// The deploy header.
+    pub header: DeployHeader,
+    /// The estimated gas to be consumed by the deploy.
+    pub gas_estimate: U512,
+    /// The estimated serialized size of the deploy in bytes.
+    pub size_estimate: u64,
+    /// Whether the deploy is a native transfer.
+    pub is_transfer: bool,
+}
+
+impl From<DeployFootprint> for JsonDeployFootprint {
+    fn from(footprint: DeployFootprint) -> Self {
+        JsonDeployFootprint {
+            header: footprint.header,
+            gas_estimate: footprint.gas_estimate.value(),
+            size_estimate: footprint.size_estimate as u64,
+            is_transfer: footprint.is_transfer,
+        }
+    }
+}
+
+/// A deploy known to the deploy buffer.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PendingDeploy {
+    /// The deploy hash.
+    pub deploy_hash: DeployHash,
+    /// The deploy's footprint, if the deploy itself is known to the buffer rather than only its
+    /// inclusion in a block.
+    pub footprint: Option<JsonDeployFootprint>,
+    /// The time remaining until the deploy expires and is purged from the buffer.
+    pub ttl_remaining: TimeDiff,
+    /// The status of the deploy.
+    pub status: PendingDeployStatus,
+}
+
+impl Display for PendingDeploy {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "{} {}, expires in {}",
+            self.deploy_hash, self.status, self.ttl_remaining
+        )?;
+        if let Some(footprint) = &self.footprint {
+            write!(
+                formatter,
+                ", account {}, gas estimate {}, size estimate {}{}",
+                footprint.header.account(),
+                footprint.gas_estimate,
+                footprint.size_estimate,
+                if footprint.is_transfer {
+                    ", transfer"
+                } else {
+                    ""
+                }
+            )?;
+        }
+        Ok(())
+    }
+}
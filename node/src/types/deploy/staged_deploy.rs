@@ -0,0 +1,55 @@
+use std::fmt::{self, Display, Formatter};
+
+use datasize::DataSize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::Deploy;
+
+/// The status of a deploy collecting approvals in the deploy staging area.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, DataSize)]
+#[serde(rename_all = "snake_case")]
+pub enum StagedDeployStatus {
+    /// The weight of the deploy's approvals is below the deployment threshold of its account.
+    AwaitingApprovals,
+    /// The deploy's approvals have reached the deployment threshold of its account, and the deploy
+    /// has been accepted by the node.
+    Accepted,
+}
+
+impl Display for StagedDeployStatus {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            StagedDeployStatus::AwaitingApprovals => write!(formatter, "awaiting approvals"),
+            StagedDeployStatus::Accepted => write!(formatter, "accepted"),
+        }
+    }
+}
+
+/// A deploy held in the deploy staging area until its approvals reach the deployment threshold of
+/// its account.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, DataSize)]
+#[serde(deny_unknown_fields)]
+pub struct StagedDeploy {
+    /// The deploy, with all approvals collected so far.
+    pub deploy: Deploy,
+    /// The total weight of the associated keys of the deploy's account which approved the deploy.
+    pub approval_weight: u8,
+    /// The deployment threshold of the deploy's account.
+    pub deployment_threshold: u8,
+    /// The status of the deploy.
+    pub status: StagedDeployStatus,
+}
+
+impl Display for StagedDeploy {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "{} {}, approval weight {} of {}",
+            self.deploy.hash(),
+            self.status,
+            self.approval_weight,
+            self.deployment_threshold
+        )
+    }
+}
@@ -7,7 +7,9 @@ use serde::Serialize;
 use thiserror::Error;
 
 use casper_hashing::Digest;
-use casper_types::{bytesrepr, CLValueError, EraId, PublicKey, U512};
+use casper_types::{
+    bytesrepr, CLValueError, EraId, FinalitySignatureError, GlobalStateProofError, PublicKey, U512,
+};
 
 use crate::types::{
     block::EraReport, Block, BlockHash, Deploy, DeployConfigurationFailure, DeployHash,
@@ -149,3 +151,28 @@ pub(crate) enum BlockWithMetadataValidationError {
     #[error(transparent)]
     BlockHeaderWithMetadataValidationError(#[from] BlockHeaderWithMetadataValidationError),
 }
+
+/// An error that can arise when verifying the result of a global state query against a block
+/// header and its finality signatures.
+#[derive(Error, Debug)]
+pub enum GlobalStateQueryVerificationError {
+    /// A finality signature is not for the given block.
+    #[error(
+        "finality signature by {public_key} is for block {block_hash} in {era_id}, not the given \
+         block"
+    )]
+    SignatureForOtherBlock {
+        /// The public key of the signer.
+        public_key: Box<PublicKey>,
+        /// The hash of the block signed.
+        block_hash: BlockHash,
+        /// The era of the block signed.
+        era_id: EraId,
+    },
+    /// The finality signatures do not show the block to be finalized by the trusted validators.
+    #[error("block is not finalized: {0}")]
+    NotFinalized(#[from] FinalitySignatureError),
+    /// The Merkle proof of the query result is invalid.
+    #[error(transparent)]
+    InvalidProof(#[from] GlobalStateProofError),
+}
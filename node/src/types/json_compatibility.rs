@@ -2,6 +2,7 @@
 
 mod account;
 mod auction_state;
+mod call_trace;
 mod contracts;
 mod stored_value;
 
@@ -9,6 +10,7 @@ use casper_types::{contracts::NamedKeys, NamedKey};
 
 pub use account::Account;
 pub use auction_state::AuctionState;
+pub use call_trace::{CallFrame, CallTrace};
 pub use contracts::{Contract, ContractPackage};
 pub use stored_value::StoredValue;
 
@@ -0,0 +1,155 @@
+// TODO - remove once schemars stops causing warning.
+#![allow(clippy::field_reassign_with_default)]
+
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use casper_execution_engine::core::runtime::call_trace::{
+    CallFrame as ExecutionEngineCallFrame, CallTrace as ExecutionEngineCallTrace,
+};
+use casper_types::{
+    account::AccountHash, system::CallStackElement as ExecutionEngineCallStackElement,
+    ContractHash, ContractPackageHash, Phase as ExecutionEnginePhase, U512,
+};
+
+/// The phase in which a call frame was entered.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub enum Phase {
+    /// Committing the genesis or upgrade configurations.
+    System,
+    /// Executing the payment code of a deploy.
+    Payment,
+    /// Executing the session code of a deploy.
+    Session,
+    /// Finalizing payment at the end of a deploy.
+    FinalizePayment,
+}
+
+impl From<ExecutionEnginePhase> for Phase {
+    fn from(phase: ExecutionEnginePhase) -> Self {
+        match phase {
+            ExecutionEnginePhase::System => Phase::System,
+            ExecutionEnginePhase::Payment => Phase::Payment,
+            ExecutionEnginePhase::Session => Phase::Session,
+            ExecutionEnginePhase::FinalizePayment => Phase::FinalizePayment,
+        }
+    }
+}
+
+/// The code executing in a call frame.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub enum CallStackElement {
+    /// Session code supplied with the deploy.
+    Session {
+        /// The account hash of the caller.
+        account_hash: AccountHash,
+    },
+    /// Stored session code.
+    StoredSession {
+        /// The account hash of the caller.
+        account_hash: AccountHash,
+        /// The contract package hash.
+        contract_package_hash: ContractPackageHash,
+        /// The contract hash.
+        contract_hash: ContractHash,
+    },
+    /// A stored contract.
+    StoredContract {
+        /// The contract package hash.
+        contract_package_hash: ContractPackageHash,
+        /// The contract hash.
+        contract_hash: ContractHash,
+    },
+}
+
+impl From<ExecutionEngineCallStackElement> for CallStackElement {
+    fn from(call_stack_element: ExecutionEngineCallStackElement) -> Self {
+        match call_stack_element {
+            ExecutionEngineCallStackElement::Session { account_hash } => {
+                CallStackElement::Session { account_hash }
+            }
+            ExecutionEngineCallStackElement::StoredSession {
+                account_hash,
+                contract_package_hash,
+                contract_hash,
+            } => CallStackElement::StoredSession {
+                account_hash,
+                contract_package_hash,
+                contract_hash,
+            },
+            ExecutionEngineCallStackElement::StoredContract {
+                contract_package_hash,
+                contract_hash,
+            } => CallStackElement::StoredContract {
+                contract_package_hash,
+                contract_hash,
+            },
+        }
+    }
+}
+
+/// A call frame entered while executing a deploy.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CallFrame {
+    /// The depth of the frame, zero for the top-level code of each phase.
+    pub depth: u64,
+    /// The phase in which the frame was entered.
+    pub phase: Phase,
+    /// The code executing in the frame, if it could be resolved.
+    pub call_stack_element: Option<CallStackElement>,
+    /// The name of the entry point called.
+    pub entry_point: String,
+    /// The size in bytes of the serialized runtime args passed to the entry point.
+    pub args_size: u64,
+    /// The gas used by the frame, including that used by any frames it entered.
+    pub gas_used: U512,
+    /// The code passed to `revert` if the frame reverted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revert_code: Option<u32>,
+    /// The error the frame failed with, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The number of times each host function was invoked directly by the frame.
+    pub host_function_calls: BTreeMap<String, u64>,
+}
+
+impl From<ExecutionEngineCallFrame> for CallFrame {
+    fn from(call_frame: ExecutionEngineCallFrame) -> Self {
+        CallFrame {
+            depth: call_frame.depth as u64,
+            phase: call_frame.phase.into(),
+            call_stack_element: call_frame.call_stack_element.map(CallStackElement::from),
+            entry_point: call_frame.entry_point,
+            args_size: call_frame.args_size as u64,
+            gas_used: call_frame.gas_used.value(),
+            revert_code: call_frame.revert_code,
+            error: call_frame.error,
+            host_function_calls: call_frame.host_function_calls,
+        }
+    }
+}
+
+/// The call frames entered while executing a deploy, in the order they were entered.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CallTrace {
+    /// The call frames.
+    pub frames: Vec<CallFrame>,
+}
+
+impl From<ExecutionEngineCallTrace> for CallTrace {
+    fn from(call_trace: ExecutionEngineCallTrace) -> Self {
+        CallTrace {
+            frames: call_trace
+                .into_frames()
+                .into_iter()
+                .map(CallFrame::from)
+                .collect(),
+        }
+    }
+}
@@ -16,6 +16,9 @@ pub struct PeerEntry {
     pub node_id: String,
     /// Node address.
     pub address: String,
+    /// The peer's reputation score: zero for well-behaved peers, lower for misbehaving ones.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reputation: Option<i32>,
 }
 
 /// Map of peer IDs to network addresses.
@@ -28,6 +31,23 @@ impl PeersMap {
     pub fn into_inner(self) -> Vec<PeerEntry> {
         self.0
     }
+
+    /// Creates a map of peers including their reputation scores, where peers absent from
+    /// `reputations` have a score of zero.
+    pub(crate) fn with_reputations(
+        peers: BTreeMap<NodeId, String>,
+        reputations: &BTreeMap<NodeId, i32>,
+    ) -> Self {
+        let ret = peers
+            .into_iter()
+            .map(|(node_id, address)| PeerEntry {
+                reputation: Some(reputations.get(&node_id).copied().unwrap_or_default()),
+                node_id: node_id.to_string(),
+                address,
+            })
+            .collect();
+        PeersMap(ret)
+    }
 }
 
 impl From<BTreeMap<NodeId, String>> for PeersMap {
@@ -37,6 +57,7 @@ impl From<BTreeMap<NodeId, String>> for PeersMap {
             .map(|(node_id, address)| PeerEntry {
                 node_id: node_id.to_string(),
                 address,
+                reputation: None,
             })
             .collect();
         PeersMap(ret)
@@ -7,24 +7,43 @@ use std::{
 use datasize::DataSize;
 use itertools::Itertools;
 use num_rational::Ratio;
+use once_cell::sync::Lazy;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use casper_hashing::Digest;
-use casper_types::{crypto, EraId, ProtocolVersion};
+use casper_types::{
+    crypto, EraId, ProtocolVersion, SignedEraTransitionHeader, ValidatorTransitionError,
+    ValidatorTransitions,
+};
 use tracing::error;
 
 use crate::{
     components::fetcher::{FetchItem, Tag},
+    rpcs::docs::DocExample,
     types::{
         error::BlockHeaderWithMetadataValidationError, BlockHash, BlockHeader,
-        BlockHeaderWithMetadata, BlockSignatures, EraValidatorWeights,
+        BlockHeaderWithMetadata, BlockSignatures, EraValidatorWeights, JsonBlock, JsonBlockHeader,
+        JsonProof,
     },
     utils::{self, BlockSignatureError},
 };
 
 use super::{chainspec::ProtocolConfig, sync_leap_validation_metadata::SyncLeapValidationMetaData};
 
+static JSON_SYNC_LEAP: Lazy<JsonSyncLeap> = Lazy::new(|| {
+    let json_block = JsonBlock::doc_example();
+    JsonSyncLeap {
+        trusted_block_header: JsonBlockHeader::doc_example().clone(),
+        trusted_ancestor_headers: vec![],
+        signed_block_headers: vec![JsonSignedBlockHeader {
+            block_header: json_block.header.clone(),
+            proofs: json_block.proofs.clone(),
+        }],
+    }
+});
+
 #[derive(Error, Debug)]
 pub(crate) enum SyncLeapValidationError {
     #[error("No ancestors of the trusted block provided.")]
@@ -254,6 +273,123 @@ impl SyncLeap {
     }
 }
 
+/// A JSON-friendly representation of a block header along with its finality signatures.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct JsonSignedBlockHeader {
+    /// The block header.
+    pub block_header: JsonBlockHeader,
+    /// The finality signatures of the block.
+    pub proofs: Vec<JsonProof>,
+}
+
+/// A JSON-friendly representation of a `SyncLeap`: the headers and signatures required to prove
+/// that if a given trusted block hash is on the correct chain, then so is a later header.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct JsonSyncLeap {
+    /// The header of the trusted block.
+    pub trusted_block_header: JsonBlockHeader,
+    /// The headers of the trusted block's ancestors, in descending order of height, back to the
+    /// most recent switch block.
+    pub trusted_ancestor_headers: Vec<JsonBlockHeader>,
+    /// The headers of all switch blocks after the trusted block and before the highest block,
+    /// plus the highest block, in ascending order of height, with their finality signatures.
+    pub signed_block_headers: Vec<JsonSignedBlockHeader>,
+}
+
+impl JsonSyncLeap {
+    /// Follows the transitions of the validator set from the trusted block to the highest signed
+    /// block.
+    ///
+    /// All block hashes are computed from the contents of the headers, so the only inputs which
+    /// need to be trusted are `trusted_block_hash` and the chain's `fault_tolerance_fraction`.
+    pub fn walk_validator_transitions(
+        &self,
+        trusted_block_hash: BlockHash,
+        fault_tolerance_fraction: Ratio<u64>,
+    ) -> Result<ValidatorTransitions, ValidatorTransitionError> {
+        let sync_leap = SyncLeap::from(self.clone());
+        let mut signed_block_headers: Vec<_> = sync_leap.signed_block_headers.iter().collect();
+        signed_block_headers.sort_by_key(|signed_header| signed_header.block_header.height());
+        casper_types::walk_validator_transitions(
+            trusted_block_hash.inner().value(),
+            &sync_leap.trusted_block_header,
+            &sync_leap.trusted_ancestor_headers,
+            signed_block_headers
+                .into_iter()
+                .map(|signed_header| SignedEraTransitionHeader {
+                    header: &signed_header.block_header,
+                    signatures: &signed_header.block_signatures.proofs,
+                }),
+            fault_tolerance_fraction,
+        )
+    }
+}
+
+impl DocExample for JsonSyncLeap {
+    fn doc_example() -> &'static Self {
+        &JSON_SYNC_LEAP
+    }
+}
+
+impl From<SyncLeap> for JsonSyncLeap {
+    fn from(sync_leap: SyncLeap) -> Self {
+        JsonSyncLeap {
+            trusted_block_header: JsonBlockHeader::from(sync_leap.trusted_block_header),
+            trusted_ancestor_headers: sync_leap
+                .trusted_ancestor_headers
+                .into_iter()
+                .map(JsonBlockHeader::from)
+                .collect(),
+            signed_block_headers: sync_leap
+                .signed_block_headers
+                .into_iter()
+                .map(|signed_header| JsonSignedBlockHeader {
+                    block_header: JsonBlockHeader::from(signed_header.block_header),
+                    proofs: signed_header
+                        .block_signatures
+                        .proofs
+                        .into_iter()
+                        .map(JsonProof::from)
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<JsonSyncLeap> for SyncLeap {
+    fn from(json_sync_leap: JsonSyncLeap) -> Self {
+        SyncLeap {
+            trusted_ancestor_only: false,
+            trusted_block_header: BlockHeader::from(json_sync_leap.trusted_block_header),
+            trusted_ancestor_headers: json_sync_leap
+                .trusted_ancestor_headers
+                .into_iter()
+                .map(BlockHeader::from)
+                .collect(),
+            signed_block_headers: json_sync_leap
+                .signed_block_headers
+                .into_iter()
+                .map(|signed_header| {
+                    let block_header = BlockHeader::from(signed_header.block_header);
+                    let mut block_signatures =
+                        BlockSignatures::new(block_header.block_hash(), block_header.era_id());
+                    for proof in signed_header.proofs {
+                        let (public_key, signature) = proof.into();
+                        block_signatures.insert_proof(public_key, signature);
+                    }
+                    BlockHeaderWithMetadata {
+                        block_header,
+                        block_signatures,
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
 impl Display for SyncLeap {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(
@@ -452,12 +588,12 @@ mod tests {
 
     use casper_types::{
         crypto, testing::TestRng, EraId, ProtocolVersion, PublicKey, SecretKey, Signature,
-        Timestamp, U512,
+        Timestamp, ValidatorTransitionError, U512,
     };
     use num_rational::Ratio;
     use rand::Rng;
 
-    use super::SyncLeap;
+    use super::{JsonSyncLeap, SyncLeap};
     use crate::{
         components::fetcher::FetchItem,
         types::{
@@ -2355,4 +2491,46 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn should_walk_validator_transitions_of_json_sync_leap() {
+        // Chain
+        // 0   1   2   3   4   5   6   7   8   9   10   11
+        // S           S           S           S
+        let switch_blocks = [0, 3, 6, 9];
+        let mut rng = TestRng::new();
+
+        let query = 5;
+        let trusted_ancestor_headers = [4, 3];
+        let signed_block_headers = [6, 9, 11];
+        let add_proofs = true;
+        let sync_leap = make_test_sync_leap(
+            &mut rng,
+            &switch_blocks,
+            query,
+            &trusted_ancestor_headers,
+            &signed_block_headers,
+            add_proofs,
+        );
+        let trusted_block_hash = sync_leap.trusted_block_header.block_hash();
+        let highest_block_hash = sync_leap.highest_block_hash();
+        let highest_block_height = sync_leap.highest_block_height();
+        let json_sync_leap = JsonSyncLeap::from(sync_leap);
+
+        let transitions = json_sync_leap
+            .walk_validator_transitions(trusted_block_hash, Ratio::new(1, 3))
+            .expect("should follow validator transitions");
+        assert_eq!(transitions.era_validators.len(), 3);
+        assert_eq!(transitions.highest_block_height, highest_block_height);
+        assert_eq!(
+            transitions.highest_block_hash,
+            highest_block_hash.inner().value()
+        );
+
+        let other_block_hash = BlockHash::random(&mut rng);
+        assert_eq!(
+            json_sync_leap.walk_validator_transitions(other_block_hash, Ratio::new(1, 3)),
+            Err(ValidatorTransitionError::TrustedBlockHashMismatch)
+        );
+    }
 }
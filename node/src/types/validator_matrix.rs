@@ -3,6 +3,7 @@ use std::iter;
 use std::{
     collections::{BTreeMap, HashSet},
     fmt::{self, Debug, Formatter},
+    future::Future,
     sync::{Arc, RwLock, RwLockReadGuard},
 };
 
@@ -11,11 +12,16 @@ use itertools::Itertools;
 use num_rational::Ratio;
 use serde::Serialize;
 use static_assertions::const_assert;
-use tracing::info;
+use tracing::{error, info};
 
-use casper_types::{EraId, PublicKey, SecretKey, U512};
+#[cfg(test)]
+use casper_types::SecretKey;
+use casper_types::{EraId, PublicKey, U512};
 
 use super::{BlockHeader, FinalitySignature};
+#[cfg(test)]
+use crate::signer::LocalSigner;
+use crate::signer::{self, Signable, Signer};
 
 const MAX_VALIDATOR_MATRIX_ENTRIES: usize = 6;
 const_assert!(MAX_VALIDATOR_MATRIX_ENTRIES % 2 == 0);
@@ -47,7 +53,8 @@ pub(crate) struct ValidatorMatrix {
     chainspec_activation_era: EraId,
     #[data_size(skip)]
     finality_threshold_fraction: Ratio<u64>,
-    secret_signing_key: Arc<SecretKey>,
+    #[data_size(skip)]
+    signer: Arc<dyn Signer>,
     public_signing_key: PublicKey,
     auction_delay: u64,
     retrograde_latch: Option<EraId>,
@@ -58,7 +65,7 @@ impl ValidatorMatrix {
         finality_threshold_fraction: Ratio<u64>,
         chainspec_validators: Option<BTreeMap<PublicKey, U512>>,
         chainspec_activation_era: EraId,
-        secret_signing_key: Arc<SecretKey>,
+        signer: Arc<dyn Signer>,
         public_signing_key: PublicKey,
         auction_delay: u64,
     ) -> Self {
@@ -68,7 +75,7 @@ impl ValidatorMatrix {
             finality_threshold_fraction,
             chainspec_validators: chainspec_validators.map(Arc::new),
             chainspec_activation_era,
-            secret_signing_key,
+            signer,
             public_signing_key,
             auction_delay,
             retrograde_latch: None,
@@ -92,7 +99,7 @@ impl ValidatorMatrix {
             chainspec_activation_era: EraId::from(0),
             finality_threshold_fraction,
             public_signing_key,
-            secret_signing_key,
+            signer: Arc::new(LocalSigner::new(secret_signing_key)),
             auction_delay: 1,
             retrograde_latch: None,
         }
@@ -262,22 +269,37 @@ impl ValidatorMatrix {
             .any(|validator_weights| validator_weights.is_validator(public_key))
     }
 
+    /// Returns a future creating our finality signature for the given block, or `None` if we are
+    /// not a validator in its era.
+    ///
+    /// The future signs on a blocking thread if the signer may block, so it must not be awaited on
+    /// the reactor.
     pub(crate) fn create_finality_signature(
         &self,
         block_header: &BlockHeader,
-    ) -> Option<FinalitySignature> {
-        if self
+    ) -> Option<impl Future<Output = Option<FinalitySignature>> + Send + 'static> {
+        if !self
             .is_self_validator_in_era(block_header.era_id())
             .unwrap_or(false)
         {
-            return Some(FinalitySignature::create(
-                block_header.block_hash(),
-                block_header.era_id(),
-                &self.secret_signing_key,
-                self.public_signing_key.clone(),
-            ));
+            return None;
         }
-        None
+        let block_hash = block_header.block_hash();
+        let era_id = block_header.era_id();
+        let message = Signable::FinalitySignature(Box::new(block_header.clone()));
+        let signer = Arc::clone(&self.signer);
+        let public_key = self.public_signing_key.clone();
+        Some(async move {
+            match signer::sign_async(signer, message).await {
+                Ok(signature) => Some(FinalitySignature::new(
+                    block_hash, era_id, signature, public_key,
+                )),
+                Err(error) => {
+                    error!(%error, %block_hash, "failed to create finality signature");
+                    None
+                }
+            }
+        })
     }
 
     fn read_inner(&self) -> RwLockReadGuard<BTreeMap<EraId, EraValidatorWeights>> {
@@ -6,7 +6,7 @@ use core::mem::MaybeUninit;
 use casper_types::{
     account::AccountHash,
     api_error,
-    bytesrepr::{self, FromBytes},
+    bytesrepr::{self, FromBytes, ToBytes},
     contracts::{ContractVersion, NamedKeys},
     system::CallStackElement,
     ApiError, BlockTime, CLTyped, CLValue, ContractHash, ContractPackageHash, Key, Phase,
@@ -282,6 +282,20 @@ pub fn remove_key(name: &str) {
     unsafe { ext_ffi::casper_remove_key(name_ptr, name_size) }
 }
 
+/// Emits an event with the given `topic` and `value`, recorded in the deploy's execution result.
+///
+/// Events are only recorded for successful deploys, and only when emitted during the session
+/// phase.  The topic must be non-empty and at most [`casper_types::EVENT_TOPIC_MAX_LENGTH`] bytes
+/// long.
+pub fn emit_event<T: CLTyped + ToBytes>(topic: &str, value: T) {
+    let value = CLValue::from_t(value).unwrap_or_revert();
+    let (topic_ptr, topic_size, _bytes) = contract_api::to_ptr(topic);
+    let (value_ptr, value_size, _bytes2) = contract_api::to_ptr(value);
+    let result =
+        unsafe { ext_ffi::casper_emit_event(topic_ptr, topic_size, value_ptr, value_size) };
+    api_error::result_from(result).unwrap_or_revert()
+}
+
 /// Returns the set of [`AccountHash`] from the calling account's context `authorization_keys`.
 pub fn list_authorization_keys() -> BTreeSet<AccountHash> {
     let (total_authorization_keys, result_size) = {
@@ -805,4 +805,19 @@ extern "C" {
         contract_hash_ptr: *const u8,
         contract_hash_size: usize,
     ) -> i32;
+    /// Records an event emitted by the currently executing contract. Returns non-zero standard
+    /// error for a failure, otherwise a zero indicates success.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic_ptr` - pointer to serialized event topic.
+    /// * `topic_size` - size of event topic in serialized form.
+    /// * `value_ptr` - pointer to serialized event value, a `CLValue`.
+    /// * `value_size` - size of event value in serialized form.
+    pub fn casper_emit_event(
+        topic_ptr: *const u8,
+        topic_size: usize,
+        value_ptr: *const u8,
+        value_size: usize,
+    ) -> i32;
 }
@@ -0,0 +1,26 @@
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use alloc::string::String;
+
+use casper_contract::contract_api::runtime;
+use casper_types::ApiError;
+
+const ARG_TOPIC: &str = "topic";
+const ARG_VALUE: &str = "value";
+const ARG_REVERT: &str = "revert";
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let topic: String = runtime::get_named_arg(ARG_TOPIC);
+    let value: u64 = runtime::get_named_arg(ARG_VALUE);
+    let revert: bool = runtime::get_named_arg(ARG_REVERT);
+
+    runtime::emit_event(&topic, value);
+
+    if revert {
+        runtime::revert(ApiError::User(0));
+    }
+}
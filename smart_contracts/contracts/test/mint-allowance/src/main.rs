@@ -0,0 +1,108 @@
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use alloc::{string::String, vec};
+
+use casper_contract::{
+    contract_api::{runtime, storage, system},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    account::AccountHash,
+    contracts::{EntryPoint, EntryPointAccess, EntryPointType, EntryPoints, Parameter},
+    runtime_args,
+    system::mint,
+    ApiError, CLType, CLTyped, Key, RuntimeArgs, U512,
+};
+
+const ARG_ACTION: &str = "action";
+const ARG_VALUE: &str = "value";
+
+const ACTION_APPROVE: &str = "approve";
+const ACTION_ALLOWANCE: &str = "allowance";
+const ACTION_TRANSFER_FROM: &str = "transfer_from";
+const ACTION_INSTALL: &str = "install";
+
+const ALLOWANCE_KEY_NAME: &str = "allowance";
+const CONTRACT_HASH_KEY_NAME: &str = "mint_allowance_contract_hash";
+
+fn approve(spender: Key, amount: U512) {
+    let result: Result<(), mint::Error> = runtime::call_contract(
+        system::get_mint(),
+        mint::METHOD_APPROVE,
+        runtime_args! {
+            mint::ARG_SPENDER => spender,
+            mint::ARG_AMOUNT => amount,
+        },
+    );
+    result.unwrap_or_revert();
+}
+
+/// Approves `spender` from within a stored contract, which the mint must reject.
+#[no_mangle]
+pub extern "C" fn approve_from_contract() {
+    let spender: Key = runtime::get_named_arg(mint::ARG_SPENDER);
+    let amount: U512 = runtime::get_named_arg(ARG_VALUE);
+    approve(spender, amount);
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let action: String = runtime::get_named_arg(ARG_ACTION);
+    match action.as_str() {
+        ACTION_APPROVE => {
+            let spender: Key = runtime::get_named_arg(mint::ARG_SPENDER);
+            let amount: U512 = runtime::get_named_arg(ARG_VALUE);
+            approve(spender, amount);
+        }
+        ACTION_ALLOWANCE => {
+            let owner: AccountHash = runtime::get_named_arg(mint::ARG_OWNER);
+            let spender: Key = runtime::get_named_arg(mint::ARG_SPENDER);
+            let allowance: U512 = runtime::call_contract(
+                system::get_mint(),
+                mint::METHOD_ALLOWANCE,
+                runtime_args! {
+                    mint::ARG_OWNER => owner,
+                    mint::ARG_SPENDER => spender,
+                },
+            );
+            runtime::put_key(ALLOWANCE_KEY_NAME, storage::new_uref(allowance).into());
+        }
+        ACTION_TRANSFER_FROM => {
+            let owner: AccountHash = runtime::get_named_arg(mint::ARG_OWNER);
+            let amount: U512 = runtime::get_named_arg(ARG_VALUE);
+            // Transfer into a new purse so the owner can also be the spender.
+            let target = system::create_purse();
+            let result: Result<(), mint::Error> = runtime::call_contract(
+                system::get_mint(),
+                mint::METHOD_TRANSFER_FROM,
+                runtime_args! {
+                    mint::ARG_OWNER => owner,
+                    mint::ARG_TARGET => target,
+                    mint::ARG_AMOUNT => amount,
+                    mint::ARG_ID => <Option<u64>>::None,
+                },
+            );
+            result.unwrap_or_revert();
+        }
+        ACTION_INSTALL => {
+            let mut entry_points = EntryPoints::new();
+            entry_points.add_entry_point(EntryPoint::new(
+                "approve_from_contract",
+                vec![
+                    Parameter::new(mint::ARG_SPENDER, Key::cl_type()),
+                    Parameter::new(ARG_VALUE, U512::cl_type()),
+                ],
+                CLType::Unit,
+                EntryPointAccess::Public,
+                EntryPointType::Contract,
+            ));
+            let (contract_hash, _contract_version) =
+                storage::new_contract(entry_points, None, None, None);
+            runtime::put_key(CONTRACT_HASH_KEY_NAME, contract_hash.into());
+        }
+        _ => runtime::revert(ApiError::InvalidArgument),
+    }
+}
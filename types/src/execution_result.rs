@@ -30,7 +30,7 @@ use rand::{
 };
 #[cfg(feature = "json-schema")]
 use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 #[cfg(feature = "json-schema")]
 use crate::KEY_HASH_LENGTH;
@@ -38,7 +38,7 @@ use crate::{
     account::AccountHash,
     bytesrepr::{self, FromBytes, ToBytes, U8_SERIALIZED_LENGTH},
     system::auction::{Bid, EraInfo, UnbondingPurse, WithdrawPurse},
-    CLValue, DeployInfo, NamedKey, Transfer, TransferAddr, U128, U256, U512,
+    CLValue, DeployInfo, Key, NamedKey, Transfer, TransferAddr, U128, U256, U512,
 };
 
 #[derive(FromPrimitive, ToPrimitive, Debug)]
@@ -46,6 +46,7 @@ use crate::{
 enum ExecutionResultTag {
     Failure = 0,
     Success = 1,
+    SuccessWithEvents = 2,
 }
 
 impl TryFrom<u8> for ExecutionResultTag {
@@ -143,18 +144,25 @@ static EXECUTION_RESULT: Lazy<ExecutionResult> = Lazy::new(|| {
         TransferAddr::new([130; KEY_HASH_LENGTH]),
     ];
 
+    let events = vec![ContractEvent {
+        emitter: Key::Hash([7; KEY_HASH_LENGTH]).to_formatted_string(),
+        topic: "transfer".to_string(),
+        value: CLValue::from_t(1_000u64).unwrap(),
+    }];
+
     ExecutionResult::Success {
         effect,
         transfers,
         cost: U512::from(123_456),
+        events,
     }
 });
 
 /// The result of executing a single deploy.
-#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[derive(Clone, Eq, PartialEq, Debug)]
 #[cfg_attr(feature = "datasize", derive(DataSize))]
 #[cfg_attr(feature = "json-schema", derive(JsonSchema))]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(feature = "json-schema", schemars(deny_unknown_fields))]
 pub enum ExecutionResult {
     /// The result of a failed execution.
     Failure {
@@ -175,6 +183,9 @@ pub enum ExecutionResult {
         transfers: Vec<TransferAddr>,
         /// The cost of executing the deploy.
         cost: U512,
+        /// The events emitted by contracts while executing the deploy.
+        #[cfg_attr(feature = "json-schema", schemars(default))]
+        events: Vec<ContractEvent>,
     },
 }
 
@@ -194,11 +205,10 @@ impl ExecutionResult {
                 cost: _,
                 error_message: _,
             } => ExecutionResultTag::Failure,
-            ExecutionResult::Success {
-                effect: _,
-                transfers: _,
-                cost: _,
-            } => ExecutionResultTag::Success,
+            ExecutionResult::Success { events, .. } if events.is_empty() => {
+                ExecutionResultTag::Success
+            }
+            ExecutionResult::Success { .. } => ExecutionResultTag::SuccessWithEvents,
         }
     }
 }
@@ -242,15 +252,242 @@ impl Distribution<ExecutionResult> for Standard {
                 error_message: format!("Error message {}", rng.gen::<u64>()),
             }
         } else {
+            let event_count = rng.gen_range(0..3);
+            let mut events = vec![];
+            for _ in 0..event_count {
+                events.push(rng.gen())
+            }
             ExecutionResult::Success {
                 effect: execution_effect,
                 transfers,
                 cost: rng.gen::<u64>().into(),
+                events,
             }
         }
     }
 }
 
+/// Serde representation of an [`ExecutionResult`] in human-readable formats, where `events` is
+/// omitted if empty.
+#[derive(Serialize)]
+#[serde(rename = "ExecutionResult")]
+enum HumanReadableSerHelper<'a> {
+    Failure {
+        effect: &'a ExecutionEffect,
+        transfers: &'a Vec<TransferAddr>,
+        cost: &'a U512,
+        error_message: &'a String,
+    },
+    Success {
+        effect: &'a ExecutionEffect,
+        transfers: &'a Vec<TransferAddr>,
+        cost: &'a U512,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        events: &'a Vec<ContractEvent>,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "ExecutionResult", deny_unknown_fields)]
+enum HumanReadableDeserHelper {
+    Failure {
+        effect: ExecutionEffect,
+        transfers: Vec<TransferAddr>,
+        cost: U512,
+        error_message: String,
+    },
+    Success {
+        effect: ExecutionEffect,
+        transfers: Vec<TransferAddr>,
+        cost: U512,
+        #[serde(default)]
+        events: Vec<ContractEvent>,
+    },
+}
+
+/// Serde representation of an [`ExecutionResult`] in binary formats such as bincode, which can't
+/// omit fields.  Successful results without events keep the encoding they had before events were
+/// introduced, so previously stored results remain readable.
+#[derive(Serialize)]
+#[serde(rename = "ExecutionResult")]
+enum BinarySerHelper<'a> {
+    Failure {
+        effect: &'a ExecutionEffect,
+        transfers: &'a Vec<TransferAddr>,
+        cost: &'a U512,
+        error_message: &'a String,
+    },
+    Success {
+        effect: &'a ExecutionEffect,
+        transfers: &'a Vec<TransferAddr>,
+        cost: &'a U512,
+    },
+    SuccessWithEvents {
+        effect: &'a ExecutionEffect,
+        transfers: &'a Vec<TransferAddr>,
+        cost: &'a U512,
+        events: &'a Vec<ContractEvent>,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "ExecutionResult")]
+enum BinaryDeserHelper {
+    Failure {
+        effect: ExecutionEffect,
+        transfers: Vec<TransferAddr>,
+        cost: U512,
+        error_message: String,
+    },
+    Success {
+        effect: ExecutionEffect,
+        transfers: Vec<TransferAddr>,
+        cost: U512,
+    },
+    SuccessWithEvents {
+        effect: ExecutionEffect,
+        transfers: Vec<TransferAddr>,
+        cost: U512,
+        events: Vec<ContractEvent>,
+    },
+}
+
+impl Serialize for ExecutionResult {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            let helper = match self {
+                ExecutionResult::Failure {
+                    effect,
+                    transfers,
+                    cost,
+                    error_message,
+                } => HumanReadableSerHelper::Failure {
+                    effect,
+                    transfers,
+                    cost,
+                    error_message,
+                },
+                ExecutionResult::Success {
+                    effect,
+                    transfers,
+                    cost,
+                    events,
+                } => HumanReadableSerHelper::Success {
+                    effect,
+                    transfers,
+                    cost,
+                    events,
+                },
+            };
+            helper.serialize(serializer)
+        } else {
+            let helper = match self {
+                ExecutionResult::Failure {
+                    effect,
+                    transfers,
+                    cost,
+                    error_message,
+                } => BinarySerHelper::Failure {
+                    effect,
+                    transfers,
+                    cost,
+                    error_message,
+                },
+                ExecutionResult::Success {
+                    effect,
+                    transfers,
+                    cost,
+                    events,
+                } if events.is_empty() => BinarySerHelper::Success {
+                    effect,
+                    transfers,
+                    cost,
+                },
+                ExecutionResult::Success {
+                    effect,
+                    transfers,
+                    cost,
+                    events,
+                } => BinarySerHelper::SuccessWithEvents {
+                    effect,
+                    transfers,
+                    cost,
+                    events,
+                },
+            };
+            helper.serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ExecutionResult {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            let execution_result = match HumanReadableDeserHelper::deserialize(deserializer)? {
+                HumanReadableDeserHelper::Failure {
+                    effect,
+                    transfers,
+                    cost,
+                    error_message,
+                } => ExecutionResult::Failure {
+                    effect,
+                    transfers,
+                    cost,
+                    error_message,
+                },
+                HumanReadableDeserHelper::Success {
+                    effect,
+                    transfers,
+                    cost,
+                    events,
+                } => ExecutionResult::Success {
+                    effect,
+                    transfers,
+                    cost,
+                    events,
+                },
+            };
+            Ok(execution_result)
+        } else {
+            let execution_result = match BinaryDeserHelper::deserialize(deserializer)? {
+                BinaryDeserHelper::Failure {
+                    effect,
+                    transfers,
+                    cost,
+                    error_message,
+                } => ExecutionResult::Failure {
+                    effect,
+                    transfers,
+                    cost,
+                    error_message,
+                },
+                BinaryDeserHelper::Success {
+                    effect,
+                    transfers,
+                    cost,
+                } => ExecutionResult::Success {
+                    effect,
+                    transfers,
+                    cost,
+                    events: Vec::new(),
+                },
+                BinaryDeserHelper::SuccessWithEvents {
+                    effect,
+                    transfers,
+                    cost,
+                    events,
+                } => ExecutionResult::Success {
+                    effect,
+                    transfers,
+                    cost,
+                    events,
+                },
+            };
+            Ok(execution_result)
+        }
+    }
+}
+
 // TODO[goral09]: Add `write_bytes` impl.
 impl ToBytes for ExecutionResult {
     fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
@@ -273,10 +510,15 @@ impl ToBytes for ExecutionResult {
                 effect,
                 transfers,
                 cost,
+                events,
             } => {
                 buffer.extend(effect.to_bytes()?);
                 buffer.extend(transfers.to_bytes()?);
                 buffer.extend(cost.to_bytes()?);
+                // Results without events keep the legacy encoding.
+                if !events.is_empty() {
+                    buffer.extend(events.to_bytes()?);
+                }
             }
         }
         Ok(buffer)
@@ -300,10 +542,17 @@ impl ToBytes for ExecutionResult {
                     effect: execution_effect,
                     transfers,
                     cost,
+                    events,
                 } => {
+                    let events_length = if events.is_empty() {
+                        0
+                    } else {
+                        events.serialized_length()
+                    };
                     execution_effect.serialized_length()
                         + transfers.serialized_length()
                         + cost.serialized_length()
+                        + events_length
                 }
             }
     }
@@ -334,6 +583,20 @@ impl FromBytes for ExecutionResult {
                     effect: execution_effect,
                     transfers,
                     cost,
+                    events: Vec::new(),
+                };
+                Ok((execution_result, remainder))
+            }
+            ExecutionResultTag::SuccessWithEvents => {
+                let (execution_effect, remainder) = ExecutionEffect::from_bytes(remainder)?;
+                let (transfers, remainder) = Vec::<TransferAddr>::from_bytes(remainder)?;
+                let (cost, remainder) = U512::from_bytes(remainder)?;
+                let (events, remainder) = Vec::<ContractEvent>::from_bytes(remainder)?;
+                let execution_result = ExecutionResult::Success {
+                    effect: execution_effect,
+                    transfers,
+                    cost,
+                    events,
                 };
                 Ok((execution_result, remainder))
             }
@@ -341,6 +604,74 @@ impl FromBytes for ExecutionResult {
     }
 }
 
+/// The maximum length in bytes of a [`ContractEvent`] topic.
+pub const EVENT_TOPIC_MAX_LENGTH: usize = 64;
+
+/// A structured event emitted by a contract while executing a deploy.
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "datasize", derive(DataSize))]
+#[cfg_attr(feature = "json-schema", derive(JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct ContractEvent {
+    /// The formatted string of the `Key` of the account or contract which emitted the event.
+    pub emitter: String,
+    /// The topic of the event.
+    pub topic: String,
+    /// The value carried by the event.
+    pub value: CLValue,
+}
+
+impl ContractEvent {
+    /// Constructor for [`ContractEvent`].
+    pub fn new(emitter: Key, topic: String, value: CLValue) -> Self {
+        ContractEvent {
+            emitter: emitter.to_formatted_string(),
+            topic,
+            value,
+        }
+    }
+}
+
+impl Distribution<ContractEvent> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> ContractEvent {
+        ContractEvent {
+            emitter: rng.gen::<Key>().to_formatted_string(),
+            topic: format!("topic {}", rng.gen::<u8>()),
+            value: CLValue::from_t(rng.gen::<u64>()).unwrap(),
+        }
+    }
+}
+
+impl ToBytes for ContractEvent {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        buffer.extend(self.emitter.to_bytes()?);
+        buffer.extend(self.topic.to_bytes()?);
+        buffer.extend(self.value.to_bytes()?);
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.emitter.serialized_length()
+            + self.topic.serialized_length()
+            + self.value.serialized_length()
+    }
+}
+
+impl FromBytes for ContractEvent {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (emitter, remainder) = String::from_bytes(bytes)?;
+        let (topic, remainder) = String::from_bytes(remainder)?;
+        let (value, remainder) = CLValue::from_bytes(remainder)?;
+        let contract_event = ContractEvent {
+            emitter,
+            topic,
+            value,
+        };
+        Ok((contract_event, remainder))
+    }
+}
+
 /// The journal of execution transforms from a single deploy.
 #[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Default, Debug)]
 #[cfg_attr(feature = "datasize", derive(DataSize))]
@@ -798,4 +1129,91 @@ mod tests {
         let execution_result: ExecutionResult = rng.gen();
         bytesrepr::test_serialization_roundtrip(&execution_result);
     }
+
+    #[test]
+    fn bytesrepr_test_contract_event() {
+        let mut rng = get_rng();
+        let event: ContractEvent = rng.gen();
+        bytesrepr::test_serialization_roundtrip(&event);
+    }
+
+    #[test]
+    fn serde_roundtrip_execution_result() {
+        let mut rng = get_rng();
+        let execution_result: ExecutionResult = rng.gen();
+
+        let json = serde_json::to_string(&execution_result).unwrap();
+        let decoded: ExecutionResult = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, execution_result);
+
+        let bincode = bincode::serialize(&execution_result).unwrap();
+        let decoded: ExecutionResult = bincode::deserialize(&bincode).unwrap();
+        assert_eq!(decoded, execution_result);
+    }
+
+    #[test]
+    fn success_without_events_should_keep_legacy_serde_encoding() {
+        #[derive(Serialize)]
+        enum LegacyExecutionResult {
+            #[allow(dead_code)]
+            Failure,
+            Success {
+                effect: ExecutionEffect,
+                transfers: Vec<TransferAddr>,
+                cost: U512,
+            },
+        }
+
+        let mut rng = get_rng();
+        let cost = U512::from(rng.gen::<u64>());
+        let legacy = LegacyExecutionResult::Success {
+            effect: ExecutionEffect::new(vec![]),
+            transfers: vec![],
+            cost,
+        };
+        let execution_result = ExecutionResult::Success {
+            effect: ExecutionEffect::new(vec![]),
+            transfers: vec![],
+            cost,
+            events: vec![],
+        };
+
+        assert_eq!(
+            serde_json::to_string(&execution_result).unwrap(),
+            serde_json::to_string(&legacy).unwrap()
+        );
+        let legacy_bincode = bincode::serialize(&legacy).unwrap();
+        assert_eq!(
+            bincode::serialize(&execution_result).unwrap(),
+            legacy_bincode
+        );
+        let decoded: ExecutionResult = bincode::deserialize(&legacy_bincode).unwrap();
+        assert_eq!(decoded, execution_result);
+    }
+
+    #[test]
+    fn success_without_events_should_keep_legacy_encoding() {
+        let mut rng = get_rng();
+        let effect = ExecutionEffect::new(vec![]);
+        let cost = U512::from(rng.gen::<u64>());
+        let without_events = ExecutionResult::Success {
+            effect: effect.clone(),
+            transfers: vec![],
+            cost,
+            events: vec![],
+        };
+        let bytes = without_events.to_bytes().unwrap();
+        assert_eq!(bytes[0], ExecutionResultTag::Success as u8);
+        assert_eq!(bytes.len(), without_events.serialized_length());
+
+        let with_events = ExecutionResult::Success {
+            effect,
+            transfers: vec![],
+            cost,
+            events: vec![rng.gen()],
+        };
+        let bytes = with_events.to_bytes().unwrap();
+        assert_eq!(bytes[0], ExecutionResultTag::SuccessWithEvents as u8);
+        bytesrepr::test_serialization_roundtrip(&with_events);
+    }
 }
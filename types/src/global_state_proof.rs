@@ -0,0 +1,734 @@
+//! Verification of Merkle proofs of global state queries.
+//!
+//! A global state query made via the node's JSON-RPC server (e.g. `state_get_item` or
+//! `query_global_state`) returns the value found along with a hex-encoded `merkle_proof`.  The
+//! functionality here allows a client to check such a proof against a trusted state root hash
+//! without depending on the execution engine, and hence without trusting the node which served
+//! the query.
+
+use alloc::{string::String, vec, vec::Vec};
+use core::fmt::{self, Display, Formatter};
+
+use blake2::{
+    digest::{Update, VariableOutput},
+    VarBlake2b,
+};
+
+use crate::{
+    bytesrepr::{self, Bytes, FromBytes, ToBytes},
+    crypto,
+    key::BLAKE2B_DIGEST_LENGTH,
+    CLType, CLValue, Key, StoredValue,
+};
+
+/// The size of the chunks into which trie leaves larger than a single chunk are split for
+/// hashing.  This must match `ChunkWithProof::CHUNK_SIZE_BYTES` of the `casper-hashing` crate.
+const CHUNK_SIZE_BYTES: usize = 8 * 1024 * 1024;
+
+/// The number of children of a node of the global state trie.
+const RADIX: usize = 256;
+
+const TRIE_LEAF_TAG: u8 = 0;
+const TRIE_NODE_TAG: u8 = 1;
+const TRIE_EXTENSION_TAG: u8 = 2;
+
+const LEAF_POINTER_TAG: u8 = 0;
+const NODE_POINTER_TAG: u8 = 1;
+
+const PROOF_STEP_NODE_TAG: u8 = 0;
+const PROOF_STEP_EXTENSION_TAG: u8 = 1;
+
+const OPTION_NONE_TAG: u8 = 0;
+const OPTION_SOME_TAG: u8 = 1;
+
+/// Error verifying a Merkle proof of a global state query.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum GlobalStateProofError {
+    /// The proof was not valid hex.
+    Base16DecodeError(base16::DecodeError),
+    /// The proof could not be deserialized.
+    Deserialization(bytesrepr::Error),
+    /// The proof contained no entries.
+    EmptyProof,
+    /// An entry of the proof did not hash to the expected state root hash.
+    StateRootHashMismatch {
+        /// Index of the proof entry.
+        index: usize,
+        /// The trusted state root hash.
+        expected: [u8; BLAKE2B_DIGEST_LENGTH],
+        /// The state root hash computed from the proof entry.
+        computed: [u8; BLAKE2B_DIGEST_LENGTH],
+    },
+    /// An entry of the proof was for an unexpected key.
+    UnexpectedKey {
+        /// Index of the proof entry.
+        index: usize,
+        /// The key expected by following the query path.
+        expected: Key,
+        /// The key of the proof entry.
+        actual: Key,
+    },
+    /// The query path could not be followed from the value of an entry of the proof.
+    CannotFollowPath {
+        /// Index of the proof entry.
+        index: usize,
+    },
+    /// A name in the query path was not found among the named keys of the value of an entry of the
+    /// proof.
+    NameNotFound {
+        /// Index of the proof entry.
+        index: usize,
+        /// The missing name.
+        name: String,
+    },
+    /// The proof has too few or too many entries for the query path.
+    UnexpectedProofLength {
+        /// The number of proof entries.
+        actual: usize,
+    },
+    /// The value proven differs from the expected value.
+    ValueMismatch,
+}
+
+impl Display for GlobalStateProofError {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            GlobalStateProofError::Base16DecodeError(error) => {
+                write!(formatter, "Merkle proof is not valid hex: {}", error)
+            }
+            GlobalStateProofError::Deserialization(error) => {
+                write!(formatter, "could not deserialize Merkle proof: {}", error)
+            }
+            GlobalStateProofError::EmptyProof => write!(formatter, "Merkle proof is empty"),
+            GlobalStateProofError::StateRootHashMismatch {
+                index,
+                expected,
+                computed,
+            } => write!(
+                formatter,
+                "Merkle proof entry {} hashes to {}, expected state root hash {}",
+                index,
+                base16::encode_lower(computed),
+                base16::encode_lower(expected)
+            ),
+            GlobalStateProofError::UnexpectedKey {
+                index,
+                expected,
+                actual,
+            } => write!(
+                formatter,
+                "Merkle proof entry {} is for key {}, expected {}",
+                index, actual, expected
+            ),
+            GlobalStateProofError::CannotFollowPath { index } => write!(
+                formatter,
+                "cannot follow query path from Merkle proof entry {}",
+                index
+            ),
+            GlobalStateProofError::NameNotFound { index, name } => write!(
+                formatter,
+                "name {} not found in named keys of Merkle proof entry {}",
+                name, index
+            ),
+            GlobalStateProofError::UnexpectedProofLength { actual } => write!(
+                formatter,
+                "Merkle proof has {} entries, which does not match the query path",
+                actual
+            ),
+            GlobalStateProofError::ValueMismatch => write!(
+                formatter,
+                "value proven by Merkle proof does not match expected value"
+            ),
+        }
+    }
+}
+
+impl From<bytesrepr::Error> for GlobalStateProofError {
+    fn from(error: bytesrepr::Error) -> Self {
+        GlobalStateProofError::Deserialization(error)
+    }
+}
+
+#[cfg(any(feature = "std", test))]
+impl std::error::Error for GlobalStateProofError {}
+
+/// Hashes the serialized form of a trie leaf, splitting it into chunks if it is too large to fit in
+/// a single one.
+///
+/// This matches `Digest::hash_into_chunks_if_necessary` of the `casper-hashing` crate.
+fn hash_leaf_bytes(bytes: &[u8]) -> [u8; BLAKE2B_DIGEST_LENGTH] {
+    if bytes.len() <= CHUNK_SIZE_BYTES {
+        return crypto::blake2b(bytes);
+    }
+    let mut chunk_hashes = bytes.chunks(CHUNK_SIZE_BYTES).map(crypto::blake2b);
+    let leaf_count = chunk_hashes.len() as u64;
+    let raw_root = tree_fold(&mut chunk_hashes).expect("should have at least two chunks");
+    hash_merkle_root(leaf_count, raw_root)
+}
+
+fn hash_pair(
+    left: [u8; BLAKE2B_DIGEST_LENGTH],
+    right: [u8; BLAKE2B_DIGEST_LENGTH],
+) -> [u8; BLAKE2B_DIGEST_LENGTH] {
+    let mut result = [0; BLAKE2B_DIGEST_LENGTH];
+    let mut hasher = VarBlake2b::new(BLAKE2B_DIGEST_LENGTH).expect("should create hasher");
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize_variable(|slice| result.copy_from_slice(slice));
+    result
+}
+
+/// Hashes the raw Merkle root of the chunks of a leaf together with their count, prefixed by a
+/// chunk's worth of zeros.
+fn hash_merkle_root(
+    leaf_count: u64,
+    root: [u8; BLAKE2B_DIGEST_LENGTH],
+) -> [u8; BLAKE2B_DIGEST_LENGTH] {
+    const ZEROS: [u8; 1024] = [0; 1024];
+    let mut result = [0; BLAKE2B_DIGEST_LENGTH];
+    let mut hasher = VarBlake2b::new(BLAKE2B_DIGEST_LENGTH).expect("should create hasher");
+    for _ in 0..CHUNK_SIZE_BYTES / ZEROS.len() {
+        hasher.update(ZEROS);
+    }
+    hasher.update(leaf_count.to_le_bytes());
+    hasher.update(root);
+    hasher.finalize_variable(|slice| result.copy_from_slice(slice));
+    result
+}
+
+/// The result of folding part of the chunk hashes: `Ok` for a complete subtree, `Err` once the
+/// hashes have run out, holding whatever was folded so far.
+type FoldState = Result<[u8; BLAKE2B_DIGEST_LENGTH], Option<[u8; BLAKE2B_DIGEST_LENGTH]>>;
+
+/// Folds the hashes into a balanced tree of pairs, in the same order as itertools' `tree_fold1`
+/// used by `Digest::hash_merkle_tree` of the `casper-hashing` crate.
+fn tree_fold<I: Iterator<Item = [u8; BLAKE2B_DIGEST_LENGTH]>>(
+    hashes: &mut I,
+) -> Option<[u8; BLAKE2B_DIGEST_LENGTH]> {
+    fn fold_pair<I: Iterator<Item = [u8; BLAKE2B_DIGEST_LENGTH]>>(hashes: &mut I) -> FoldState {
+        let left = match hashes.next() {
+            Some(hash) => hash,
+            None => return Err(None),
+        };
+        match hashes.next() {
+            Some(right) => Ok(hash_pair(left, right)),
+            None => Err(Some(left)),
+        }
+    }
+
+    fn fold_subtree<I: Iterator<Item = [u8; BLAKE2B_DIGEST_LENGTH]>>(
+        stop: usize,
+        hashes: &mut I,
+    ) -> FoldState {
+        let mut folded = fold_pair(hashes)?;
+        for height in 0..stop {
+            let next = if height == 0 {
+                fold_pair(hashes)
+            } else {
+                fold_subtree(height, hashes)
+            };
+            match next {
+                Ok(right) => folded = hash_pair(folded, right),
+                Err(None) => return Err(Some(folded)),
+                Err(Some(right)) => return Err(Some(hash_pair(folded, right))),
+            }
+        }
+        Ok(folded)
+    }
+
+    match fold_subtree(usize::MAX, hashes) {
+        Err(folded) => folded,
+        Ok(_) => unreachable!("cannot fold a subtree of unbounded height"),
+    }
+}
+
+/// A pointer from a node of the global state trie to one of its children.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct Pointer {
+    tag: u8,
+    hash: [u8; BLAKE2B_DIGEST_LENGTH],
+}
+
+impl Pointer {
+    fn write_bytes(&self, writer: &mut Vec<u8>) {
+        writer.push(self.tag);
+        writer.extend_from_slice(&self.hash);
+    }
+}
+
+impl FromBytes for Pointer {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (tag, rem) = u8::from_bytes(bytes)?;
+        if tag != LEAF_POINTER_TAG && tag != NODE_POINTER_TAG {
+            return Err(bytesrepr::Error::Formatting);
+        }
+        let (hash, rem) = <[u8; BLAKE2B_DIGEST_LENGTH]>::from_bytes(rem)?;
+        Ok((Pointer { tag, hash }, rem))
+    }
+}
+
+/// A single step of a proof, describing a node of the global state trie on the path from the
+/// proven leaf to the root.
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum ProofStep {
+    /// A node with up to `RADIX` children, one of which is on the proof path.
+    Node {
+        hole_index: u8,
+        indexed_pointers_with_hole: Vec<(u8, Pointer)>,
+    },
+    /// An extension whose single child is on the proof path.
+    Extension { affix: Bytes },
+}
+
+impl ProofStep {
+    /// Returns the hash of the trie node described by this step, given the pointer to its child
+    /// on the proof path.
+    fn hash_with_child(
+        &self,
+        child: Pointer,
+    ) -> Result<[u8; BLAKE2B_DIGEST_LENGTH], bytesrepr::Error> {
+        let mut bytes = vec![];
+        match self {
+            ProofStep::Node {
+                hole_index,
+                indexed_pointers_with_hole,
+            } => {
+                let mut pointer_block = [None; RADIX];
+                for (index, pointer) in indexed_pointers_with_hole {
+                    pointer_block[*index as usize] = Some(*pointer);
+                }
+                pointer_block[*hole_index as usize] = Some(child);
+                bytes.push(TRIE_NODE_TAG);
+                for maybe_pointer in pointer_block.iter() {
+                    match maybe_pointer {
+                        None => bytes.push(OPTION_NONE_TAG),
+                        Some(pointer) => {
+                            bytes.push(OPTION_SOME_TAG);
+                            pointer.write_bytes(&mut bytes);
+                        }
+                    }
+                }
+            }
+            ProofStep::Extension { affix } => {
+                bytes.push(TRIE_EXTENSION_TAG);
+                affix.write_bytes(&mut bytes)?;
+                child.write_bytes(&mut bytes);
+            }
+        }
+        Ok(crypto::blake2b(bytes))
+    }
+}
+
+impl FromBytes for ProofStep {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (tag, rem) = u8::from_bytes(bytes)?;
+        match tag {
+            PROOF_STEP_NODE_TAG => {
+                let (hole_index, rem) = u8::from_bytes(rem)?;
+                let (indexed_pointers_with_hole, rem) = Vec::<(u8, Pointer)>::from_bytes(rem)?;
+                Ok((
+                    ProofStep::Node {
+                        hole_index,
+                        indexed_pointers_with_hole,
+                    },
+                    rem,
+                ))
+            }
+            PROOF_STEP_EXTENSION_TAG => {
+                let (affix, rem) = Bytes::from_bytes(rem)?;
+                Ok((ProofStep::Extension { affix }, rem))
+            }
+            _ => Err(bytesrepr::Error::Formatting),
+        }
+    }
+}
+
+/// A proof that a value is stored under a given key in the global state under some state root
+/// hash.
+///
+/// This has the same serialized form as the execution engine's
+/// `TrieMerkleProof<Key, StoredValue>`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct GlobalStateProof {
+    key: Key,
+    value: StoredValue,
+    steps: Vec<ProofStep>,
+}
+
+impl GlobalStateProof {
+    /// Returns the key whose value is proven.
+    pub fn key(&self) -> &Key {
+        &self.key
+    }
+
+    /// Returns the value proven to be stored under the key.
+    pub fn value(&self) -> &StoredValue {
+        &self.value
+    }
+
+    /// Computes the state root hash of the global state in which the value is stored under the
+    /// key.
+    pub fn compute_state_root_hash(&self) -> Result<[u8; BLAKE2B_DIGEST_LENGTH], bytesrepr::Error> {
+        let mut leaf_bytes = vec![TRIE_LEAF_TAG];
+        self.key.write_bytes(&mut leaf_bytes)?;
+        self.value.write_bytes(&mut leaf_bytes)?;
+        let mut hash = hash_leaf_bytes(&leaf_bytes);
+        for (index, step) in self.steps.iter().enumerate() {
+            let tag = if index == 0 {
+                LEAF_POINTER_TAG
+            } else {
+                NODE_POINTER_TAG
+            };
+            hash = step.hash_with_child(Pointer { tag, hash })?;
+        }
+        Ok(hash)
+    }
+
+    /// Returns the value as returned by a global state query, i.e. with the wrapper around values
+    /// stored in dictionaries removed.
+    fn queried_value(&self) -> Result<StoredValue, bytesrepr::Error> {
+        match (&self.key, &self.value) {
+            (Key::Dictionary(_), StoredValue::CLValue(cl_value)) => {
+                let (inner_cl_value, _) = CLValue::from_bytes(cl_value.inner_bytes())?;
+                Ok(StoredValue::CLValue(inner_cl_value))
+            }
+            (_, value) => Ok(value.clone()),
+        }
+    }
+
+    /// Returns the key to which a global state query navigates from the proven value, consuming
+    /// the next name of the query path if required.
+    fn next_key<'a, I: Iterator<Item = &'a String>>(
+        &self,
+        index: usize,
+        names: &mut I,
+    ) -> Result<Key, GlobalStateProofError> {
+        let named_keys = match &self.value {
+            StoredValue::Account(account) => account.named_keys(),
+            StoredValue::Contract(contract) => contract.named_keys(),
+            StoredValue::CLValue(cl_value) if *cl_value.cl_type() == CLType::Key => {
+                return cl_value
+                    .clone()
+                    .into_t::<Key>()
+                    .map_err(|_| GlobalStateProofError::CannotFollowPath { index });
+            }
+            _ => return Err(GlobalStateProofError::CannotFollowPath { index }),
+        };
+        let name = names
+            .next()
+            .ok_or(GlobalStateProofError::CannotFollowPath { index })?;
+        named_keys
+            .get(name)
+            .copied()
+            .ok_or_else(|| GlobalStateProofError::NameNotFound {
+                index,
+                name: name.clone(),
+            })
+    }
+}
+
+impl FromBytes for GlobalStateProof {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (key, rem) = Key::from_bytes(bytes)?;
+        let (value, rem) = StoredValue::from_bytes(rem)?;
+        let (steps, rem) = Vec::<ProofStep>::from_bytes(rem)?;
+        Ok((GlobalStateProof { key, value, steps }, rem))
+    }
+}
+
+/// Verifies that a global state query for `key` and `path` under `state_root_hash` yields
+/// `value`, given the hex-encoded `merkle_proof` returned along with the query result.
+///
+/// Only `state_root_hash` needs to be trusted; everything else may be supplied by an untrusted
+/// node.
+pub fn verify_global_state_query(
+    state_root_hash: &[u8; BLAKE2B_DIGEST_LENGTH],
+    key: &Key,
+    path: &[String],
+    value: &StoredValue,
+    merkle_proof: &str,
+) -> Result<(), GlobalStateProofError> {
+    let proof_bytes = base16::decode(merkle_proof.as_bytes())
+        .map_err(GlobalStateProofError::Base16DecodeError)?;
+    let proofs: Vec<GlobalStateProof> = bytesrepr::deserialize(proof_bytes)?;
+    verify_global_state_proofs(state_root_hash, key, path, value, &proofs)
+}
+
+/// Verifies that a global state query for `key` and `path` under `state_root_hash` yields
+/// `value`, given the deserialized proofs returned along with the query result.
+pub fn verify_global_state_proofs(
+    state_root_hash: &[u8; BLAKE2B_DIGEST_LENGTH],
+    key: &Key,
+    path: &[String],
+    value: &StoredValue,
+    proofs: &[GlobalStateProof],
+) -> Result<(), GlobalStateProofError> {
+    let last_proof = proofs.last().ok_or(GlobalStateProofError::EmptyProof)?;
+
+    let mut names = path.iter().peekable();
+    let mut expected_key = *key;
+    for (index, proof) in proofs.iter().enumerate() {
+        let computed = proof.compute_state_root_hash()?;
+        if computed != *state_root_hash {
+            return Err(GlobalStateProofError::StateRootHashMismatch {
+                index,
+                expected: *state_root_hash,
+                computed,
+            });
+        }
+        if proof.key != expected_key {
+            return Err(GlobalStateProofError::UnexpectedKey {
+                index,
+                expected: expected_key,
+                actual: proof.key,
+            });
+        }
+        // As for the query itself, the path is complete once all names have been consumed.
+        let is_last = index + 1 == proofs.len();
+        if names.peek().is_none() != is_last {
+            return Err(GlobalStateProofError::UnexpectedProofLength {
+                actual: proofs.len(),
+            });
+        }
+        if !is_last {
+            expected_key = proof.next_key(index, &mut names)?;
+        }
+    }
+
+    if last_proof.queried_value()? != *value {
+        return Err(GlobalStateProofError::ValueMismatch);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::{
+        account::{Account, AccountHash},
+        bytesrepr::ToBytes,
+        AccessRights, CLValue, Key, StoredValue, URef,
+    };
+
+    use super::*;
+
+    fn leaf_hash(key: &Key, value: &StoredValue) -> [u8; BLAKE2B_DIGEST_LENGTH] {
+        let mut bytes = vec![TRIE_LEAF_TAG];
+        bytes.extend(key.to_bytes().unwrap());
+        bytes.extend(value.to_bytes().unwrap());
+        hash_leaf_bytes(&bytes)
+    }
+
+    /// Returns proofs for two leaves held as the children at indices 1 and 2 of a single node.
+    fn two_leaf_proofs(
+        first: (Key, StoredValue),
+        second: (Key, StoredValue),
+    ) -> (
+        [u8; BLAKE2B_DIGEST_LENGTH],
+        GlobalStateProof,
+        GlobalStateProof,
+    ) {
+        let first_pointer = Pointer {
+            tag: LEAF_POINTER_TAG,
+            hash: leaf_hash(&first.0, &first.1),
+        };
+        let second_pointer = Pointer {
+            tag: LEAF_POINTER_TAG,
+            hash: leaf_hash(&second.0, &second.1),
+        };
+        let first_proof = GlobalStateProof {
+            key: first.0,
+            value: first.1,
+            steps: vec![ProofStep::Node {
+                hole_index: 1,
+                indexed_pointers_with_hole: vec![(2, second_pointer)],
+            }],
+        };
+        let second_proof = GlobalStateProof {
+            key: second.0,
+            value: second.1,
+            steps: vec![ProofStep::Node {
+                hole_index: 2,
+                indexed_pointers_with_hole: vec![(1, first_pointer)],
+            }],
+        };
+        let state_root_hash = first_proof.compute_state_root_hash().unwrap();
+        assert_eq!(
+            state_root_hash,
+            second_proof.compute_state_root_hash().unwrap()
+        );
+        (state_root_hash, first_proof, second_proof)
+    }
+
+    fn serialize(proofs: &[GlobalStateProof]) -> String {
+        let mut bytes = (proofs.len() as u32).to_bytes().unwrap();
+        for proof in proofs {
+            bytes.extend(proof.key.to_bytes().unwrap());
+            bytes.extend(proof.value.to_bytes().unwrap());
+            bytes.extend((proof.steps.len() as u32).to_bytes().unwrap());
+            for step in &proof.steps {
+                match step {
+                    ProofStep::Node {
+                        hole_index,
+                        indexed_pointers_with_hole,
+                    } => {
+                        bytes.push(PROOF_STEP_NODE_TAG);
+                        bytes.push(*hole_index);
+                        bytes.extend(
+                            (indexed_pointers_with_hole.len() as u32)
+                                .to_bytes()
+                                .unwrap(),
+                        );
+                        for (index, pointer) in indexed_pointers_with_hole {
+                            bytes.push(*index);
+                            pointer.write_bytes(&mut bytes);
+                        }
+                    }
+                    ProofStep::Extension { affix } => {
+                        bytes.push(PROOF_STEP_EXTENSION_TAG);
+                        bytes.extend(affix.to_bytes().unwrap());
+                    }
+                }
+            }
+        }
+        base16::encode_lower(&bytes)
+    }
+
+    #[test]
+    fn should_verify_query_following_named_key() {
+        let uref = URef::new([3; 32], AccessRights::READ_ADD_WRITE);
+        let mut named_keys = BTreeMap::new();
+        named_keys.insert("counter".to_string(), Key::URef(uref));
+        let account_hash = AccountHash::new([1; 32]);
+        let account = Account::create(account_hash, named_keys, uref);
+        let account_key = Key::Account(account_hash);
+        let counter_value = StoredValue::CLValue(CLValue::from_t(7_u64).unwrap());
+
+        let (state_root_hash, account_proof, counter_proof) = two_leaf_proofs(
+            (account_key, StoredValue::Account(account)),
+            (Key::URef(uref), counter_value.clone()),
+        );
+        let merkle_proof = serialize(&[account_proof.clone(), counter_proof.clone()]);
+        let path = vec!["counter".to_string()];
+
+        assert_eq!(
+            verify_global_state_query(
+                &state_root_hash,
+                &account_key,
+                &path,
+                &counter_value,
+                &merkle_proof
+            ),
+            Ok(())
+        );
+
+        // A different value is rejected.
+        let other_value = StoredValue::CLValue(CLValue::from_t(8_u64).unwrap());
+        assert_eq!(
+            verify_global_state_query(
+                &state_root_hash,
+                &account_key,
+                &path,
+                &other_value,
+                &merkle_proof
+            ),
+            Err(GlobalStateProofError::ValueMismatch)
+        );
+
+        // A different state root hash is rejected.
+        let other_root = crypto::blake2b([9; 32]);
+        assert!(matches!(
+            verify_global_state_query(
+                &other_root,
+                &account_key,
+                &path,
+                &counter_value,
+                &merkle_proof
+            ),
+            Err(GlobalStateProofError::StateRootHashMismatch { index: 0, .. })
+        ));
+
+        // A path not matching the proof is rejected.
+        assert!(matches!(
+            verify_global_state_proofs(
+                &state_root_hash,
+                &account_key,
+                &["other".to_string()],
+                &counter_value,
+                &[account_proof.clone(), counter_proof.clone()],
+            ),
+            Err(GlobalStateProofError::NameNotFound { index: 0, .. })
+        ));
+        assert_eq!(
+            verify_global_state_proofs(
+                &state_root_hash,
+                &account_key,
+                &[],
+                &counter_value,
+                &[account_proof, counter_proof.clone()],
+            ),
+            Err(GlobalStateProofError::UnexpectedProofLength { actual: 2 })
+        );
+
+        // A proof for a key other than the queried one is rejected.
+        assert!(matches!(
+            verify_global_state_proofs(
+                &state_root_hash,
+                &account_key,
+                &[],
+                &counter_value,
+                &[counter_proof],
+            ),
+            Err(GlobalStateProofError::UnexpectedKey { index: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn should_fold_chunk_hashes_into_balanced_tree() {
+        let hashes: Vec<_> = (0..5_u8).map(|byte| crypto::blake2b([byte])).collect();
+        let fold = |count: usize| tree_fold(&mut hashes.iter().copied().take(count));
+
+        assert_eq!(fold(0), None);
+        assert_eq!(fold(1), Some(hashes[0]));
+        assert_eq!(fold(2), Some(hash_pair(hashes[0], hashes[1])));
+        assert_eq!(
+            fold(3),
+            Some(hash_pair(hash_pair(hashes[0], hashes[1]), hashes[2]))
+        );
+        assert_eq!(
+            fold(5),
+            Some(hash_pair(
+                hash_pair(
+                    hash_pair(hashes[0], hashes[1]),
+                    hash_pair(hashes[2], hashes[3])
+                ),
+                hashes[4]
+            ))
+        );
+    }
+
+    #[test]
+    fn should_reject_malformed_proofs() {
+        let key = Key::Hash([1; 32]);
+        let value = StoredValue::CLValue(CLValue::from_t(1_u8).unwrap());
+        assert_eq!(
+            verify_global_state_query(&[0; BLAKE2B_DIGEST_LENGTH], &key, &[], &value, "zz"),
+            Err(GlobalStateProofError::Base16DecodeError(
+                base16::DecodeError::InvalidByte {
+                    index: 0,
+                    byte: b'z'
+                }
+            ))
+        );
+        assert_eq!(
+            verify_global_state_query(&[0; BLAKE2B_DIGEST_LENGTH], &key, &[], &value, "00000000"),
+            Err(GlobalStateProofError::EmptyProof)
+        );
+        assert!(matches!(
+            verify_global_state_query(&[0; BLAKE2B_DIGEST_LENGTH], &key, &[], &value, "01000000"),
+            Err(GlobalStateProofError::Deserialization(_))
+        ));
+    }
+}
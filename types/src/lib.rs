@@ -40,6 +40,7 @@ pub mod file_utils;
 mod gas;
 #[cfg(any(feature = "testing", feature = "gens", test))]
 pub mod gens;
+mod global_state_proof;
 mod json_pretty_printer;
 mod key;
 mod motes;
@@ -58,6 +59,7 @@ mod transfer;
 mod transfer_result;
 mod uint;
 mod uref;
+mod validator_transitions;
 
 pub use access_rights::{
     AccessRights, ContextAccessRights, GrantedAccess, ACCESS_RIGHTS_SERIALIZED_LENGTH,
@@ -77,9 +79,13 @@ pub use contracts::{
 pub use crypto::*;
 pub use deploy_info::DeployInfo;
 pub use execution_result::{
-    ExecutionEffect, ExecutionResult, OpKind, Operation, Transform, TransformEntry,
+    ContractEvent, ExecutionEffect, ExecutionResult, OpKind, Operation, Transform, TransformEntry,
+    EVENT_TOPIC_MAX_LENGTH,
 };
 pub use gas::Gas;
+pub use global_state_proof::{
+    verify_global_state_proofs, verify_global_state_query, GlobalStateProof, GlobalStateProofError,
+};
 pub use json_pretty_printer::json_pretty_print;
 #[doc(inline)]
 pub use key::{
@@ -106,6 +112,11 @@ pub use transfer_result::{TransferResult, TransferredTo};
 pub use uref::{
     FromStrError as URefFromStrError, URef, URefAddr, UREF_ADDR_LENGTH, UREF_SERIALIZED_LENGTH,
 };
+pub use validator_transitions::{
+    verify_finality_signatures, walk_validator_transitions, EraTransitionHeader, EraValidators,
+    FinalitySignatureError, SignedEraTransitionHeader, ValidatorTransitionError,
+    ValidatorTransitions,
+};
 
 pub use crate::{
     era_id::EraId,
@@ -10,6 +10,10 @@ pub const ARG_TO: &str = "to";
 pub const ARG_SOURCE: &str = "source";
 /// Named constant for `target`.
 pub const ARG_TARGET: &str = "target";
+/// Named constant for `owner`.
+pub const ARG_OWNER: &str = "owner";
+/// Named constant for `spender`.
+pub const ARG_SPENDER: &str = "spender";
 /// Named constant for `round_seigniorage_rate` used in installer.
 pub const ARG_ROUND_SEIGNIORAGE_RATE: &str = "round_seigniorage_rate";
 
@@ -27,6 +31,12 @@ pub const METHOD_TRANSFER: &str = "transfer";
 pub const METHOD_READ_BASE_ROUND_REWARD: &str = "read_base_round_reward";
 /// Named constant for method `mint_into_existing_purse`.
 pub const METHOD_MINT_INTO_EXISTING_PURSE: &str = "mint_into_existing_purse";
+/// Named constant for method `approve`.
+pub const METHOD_APPROVE: &str = "approve";
+/// Named constant for method `allowance`.
+pub const METHOD_ALLOWANCE: &str = "allowance";
+/// Named constant for method `transfer_from`.
+pub const METHOD_TRANSFER_FROM: &str = "transfer_from";
 
 /// Storage for mint contract hash.
 pub const HASH_KEY: &str = "mint_hash";
@@ -38,3 +48,5 @@ pub const BASE_ROUND_REWARD_KEY: &str = "mint_base_round_reward";
 pub const TOTAL_SUPPLY_KEY: &str = "total_supply";
 /// Storage for mint round seigniorage rate.
 pub const ROUND_SEIGNIORAGE_RATE_KEY: &str = "round_seigniorage_rate";
+/// Storage for the dictionary of allowances granted over accounts' main purses.
+pub const ALLOWANCES_KEY: &str = "allowances";
@@ -3,9 +3,10 @@ use alloc::boxed::Box;
 use crate::{
     contracts::Parameters,
     system::mint::{
-        ARG_AMOUNT, ARG_ID, ARG_PURSE, ARG_SOURCE, ARG_TARGET, ARG_TO, METHOD_BALANCE,
-        METHOD_CREATE, METHOD_MINT, METHOD_MINT_INTO_EXISTING_PURSE, METHOD_READ_BASE_ROUND_REWARD,
-        METHOD_REDUCE_TOTAL_SUPPLY, METHOD_TRANSFER,
+        ARG_AMOUNT, ARG_ID, ARG_OWNER, ARG_PURSE, ARG_SOURCE, ARG_SPENDER, ARG_TARGET, ARG_TO,
+        METHOD_ALLOWANCE, METHOD_APPROVE, METHOD_BALANCE, METHOD_CREATE, METHOD_MINT,
+        METHOD_MINT_INTO_EXISTING_PURSE, METHOD_READ_BASE_ROUND_REWARD, METHOD_REDUCE_TOTAL_SUPPLY,
+        METHOD_TRANSFER, METHOD_TRANSFER_FROM,
     },
     CLType, EntryPoint, EntryPointAccess, EntryPointType, EntryPoints, Parameter,
 };
@@ -98,5 +99,49 @@ pub fn mint_entry_points() -> EntryPoints {
     );
     entry_points.add_entry_point(entry_point);
 
+    let entry_point = EntryPoint::new(
+        METHOD_APPROVE,
+        vec![
+            Parameter::new(ARG_SPENDER, CLType::Key),
+            Parameter::new(ARG_AMOUNT, CLType::U512),
+        ],
+        CLType::Result {
+            ok: Box::new(CLType::Unit),
+            err: Box::new(CLType::U8),
+        },
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+    entry_points.add_entry_point(entry_point);
+
+    let entry_point = EntryPoint::new(
+        METHOD_ALLOWANCE,
+        vec![
+            Parameter::new(ARG_OWNER, CLType::ByteArray(32)),
+            Parameter::new(ARG_SPENDER, CLType::Key),
+        ],
+        CLType::U512,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+    entry_points.add_entry_point(entry_point);
+
+    let entry_point = EntryPoint::new(
+        METHOD_TRANSFER_FROM,
+        vec![
+            Parameter::new(ARG_OWNER, CLType::ByteArray(32)),
+            Parameter::new(ARG_TARGET, CLType::URef),
+            Parameter::new(ARG_AMOUNT, CLType::U512),
+            Parameter::new(ARG_ID, CLType::Option(Box::new(CLType::U64))),
+        ],
+        CLType::Result {
+            ok: Box::new(CLType::Unit),
+            err: Box::new(CLType::U8),
+        },
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+    entry_points.add_entry_point(entry_point);
+
     entry_points
 }
@@ -154,6 +154,20 @@ pub enum Error {
     /// assert_eq!(22, Error::DisabledUnrestrictedTransfers as u8);
     DisabledUnrestrictedTransfers = 22,
 
+    /// Tried to transfer more CSPR from an account's main purse than the spender is allowed to.
+    /// ```
+    /// # use casper_types::system::mint::Error;
+    /// assert_eq!(23, Error::InsufficientAllowance as u8);
+    /// ```
+    InsufficientAllowance = 23,
+
+    /// Tried to grant an allowance to a key which is neither an account nor a contract.
+    /// ```
+    /// # use casper_types::system::mint::Error;
+    /// assert_eq!(24, Error::InvalidSpender as u8);
+    /// ```
+    InvalidSpender = 24,
+
     #[cfg(test)]
     #[doc(hidden)]
     Sentinel,
@@ -209,6 +223,8 @@ impl TryFrom<u8> for Error {
             d if d == Error::DisabledUnrestrictedTransfers as u8 => {
                 Ok(Error::DisabledUnrestrictedTransfers)
             }
+            d if d == Error::InsufficientAllowance as u8 => Ok(Error::InsufficientAllowance),
+            d if d == Error::InvalidSpender as u8 => Ok(Error::InvalidSpender),
             _ => Err(TryFromU8ForError(())),
         }
     }
@@ -269,6 +285,8 @@ impl Display for Error {
             Error::DisabledUnrestrictedTransfers => {
                 formatter.write_str("Disabled unrestricted transfers")
             }
+            Error::InsufficientAllowance => formatter.write_str("Insufficient allowance"),
+            Error::InvalidSpender => formatter.write_str("Invalid spender"),
             #[cfg(test)]
             Error::Sentinel => formatter.write_str("Sentinel error"),
         }
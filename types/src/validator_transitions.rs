@@ -0,0 +1,615 @@
+//! Following the transitions of the validator set from a trusted block to the tip of the chain.
+//!
+//! Each switch block records the validators of the following era.  Given a trusted block hash,
+//! the validators of the trusted block's era can be established from the most recent switch block
+//! at or before the trusted block.  Each later switch block can then be accepted as finalized once
+//! it carries signatures from a quorum of the validators of its own era, which in turn establishes
+//! the validators of the next era.
+
+use alloc::{boxed::Box, collections::BTreeMap, vec::Vec};
+use core::fmt::{self, Display, Formatter};
+
+#[cfg(feature = "datasize")]
+use datasize::DataSize;
+use num_rational::Ratio;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    crypto::{self, PublicKey, Signature},
+    key::BLAKE2B_DIGEST_LENGTH,
+    EraId, U512,
+};
+
+/// The information about a block header required to follow validator-set transitions.
+pub trait EraTransitionHeader {
+    /// Returns the hash of the block.
+    ///
+    /// This must be computed from the contents of the header, so that the validators returned by
+    /// [`Self::next_era_validator_weights`] are covered by the finality signatures of the block.
+    fn block_hash(&self) -> [u8; BLAKE2B_DIGEST_LENGTH];
+
+    /// Returns the hash of the parent block.
+    fn parent_hash(&self) -> [u8; BLAKE2B_DIGEST_LENGTH];
+
+    /// Returns the era in which the block was created.
+    fn era_id(&self) -> EraId;
+
+    /// Returns the height of the block.
+    fn height(&self) -> u64;
+
+    /// Returns the validators of the following era and their weights if this is a switch block,
+    /// or `None` otherwise.
+    fn next_era_validator_weights(&self) -> Option<&BTreeMap<PublicKey, U512>>;
+}
+
+/// A block header along with finality signatures of the block.
+#[derive(Clone, Copy, Debug)]
+pub struct SignedEraTransitionHeader<'a, H> {
+    /// The block header.
+    pub header: &'a H,
+    /// The finality signatures of the block, keyed by the signing validator.
+    pub signatures: &'a BTreeMap<PublicKey, Signature>,
+}
+
+/// The validators of a single era.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "datasize", derive(DataSize))]
+pub struct EraValidators {
+    /// The era.
+    pub era_id: EraId,
+    /// The hash of the switch block of the previous era, which recorded the validators.
+    pub switch_block_hash: [u8; BLAKE2B_DIGEST_LENGTH],
+    /// The validators of the era and their weights.
+    pub validator_weights: BTreeMap<PublicKey, U512>,
+}
+
+/// The result of following validator-set transitions from a trusted block.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "datasize", derive(DataSize))]
+pub struct ValidatorTransitions {
+    /// The validators of each era from the trusted block's era onwards, in ascending era order.
+    pub era_validators: Vec<EraValidators>,
+    /// The hash of the highest block proven to be finalized.
+    pub highest_block_hash: [u8; BLAKE2B_DIGEST_LENGTH],
+    /// The height of the highest block proven to be finalized.
+    pub highest_block_height: u64,
+}
+
+/// Errors which can arise when following validator-set transitions.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum ValidatorTransitionError {
+    /// The hash of the trusted block header does not match the trusted block hash.
+    TrustedBlockHashMismatch,
+    /// A trusted ancestor header is not the parent of the preceding header.
+    AncestorNotParent {
+        /// The height of the ancestor header.
+        height: u64,
+    },
+    /// No switch block was found at or before the trusted block.
+    MissingSwitchBlock,
+    /// The signed block headers are not in ascending order of height.
+    HeadersNotAscending {
+        /// The height of the out-of-order header.
+        height: u64,
+    },
+    /// A signed block header is not in the era whose validators are known.
+    UnexpectedEra {
+        /// The height of the block.
+        height: u64,
+        /// The era whose validators are known.
+        expected: EraId,
+        /// The era of the block.
+        actual: EraId,
+    },
+    /// A block was signed by a public key which is not a validator of its era.
+    UnknownSigner {
+        /// The height of the block.
+        height: u64,
+        /// The public key of the signer.
+        public_key: Box<PublicKey>,
+    },
+    /// A finality signature is cryptographically invalid.
+    InvalidSignature {
+        /// The height of the block.
+        height: u64,
+        /// The public key of the signer.
+        public_key: Box<PublicKey>,
+        /// The verification error.
+        error: crypto::Error,
+    },
+    /// The weight of the signers of a block does not exceed the required quorum.
+    InsufficientSignatureWeight {
+        /// The height of the block.
+        height: u64,
+        /// The total weight of the signers.
+        signature_weight: Box<U512>,
+        /// The total weight of the validators of the era.
+        total_validator_weight: Box<U512>,
+    },
+}
+
+impl Display for ValidatorTransitionError {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidatorTransitionError::TrustedBlockHashMismatch => write!(
+                formatter,
+                "trusted block header does not match the trusted block hash"
+            ),
+            ValidatorTransitionError::AncestorNotParent { height } => write!(
+                formatter,
+                "ancestor header at height {} is not the parent of the preceding header",
+                height
+            ),
+            ValidatorTransitionError::MissingSwitchBlock => {
+                write!(formatter, "no switch block at or before the trusted block")
+            }
+            ValidatorTransitionError::HeadersNotAscending { height } => write!(
+                formatter,
+                "signed block header at height {} is out of order",
+                height
+            ),
+            ValidatorTransitionError::UnexpectedEra {
+                height,
+                expected,
+                actual,
+            } => write!(
+                formatter,
+                "block at height {} is in {} but only the validators of {} are known",
+                height, actual, expected
+            ),
+            ValidatorTransitionError::UnknownSigner { height, public_key } => write!(
+                formatter,
+                "block at height {} signed by non-validator {}",
+                height, public_key
+            ),
+            ValidatorTransitionError::InvalidSignature {
+                height,
+                public_key,
+                error,
+            } => write!(
+                formatter,
+                "invalid signature by {} of block at height {}: {}",
+                public_key, height, error
+            ),
+            ValidatorTransitionError::InsufficientSignatureWeight {
+                height,
+                signature_weight,
+                total_validator_weight,
+            } => write!(
+                formatter,
+                "insufficient signature weight for block at height {}: {} of {}",
+                height, signature_weight, total_validator_weight
+            ),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", test))]
+impl std::error::Error for ValidatorTransitionError {}
+
+/// Follows the transitions of the validator set from a trusted block to the highest signed block.
+///
+/// `trusted_ancestor_headers` must hold the ancestors of the trusted block in descending order of
+/// height, back to and including the most recent switch block, unless the trusted block is itself
+/// a switch block.  `signed_block_headers` must be in ascending order of height, and must include
+/// every switch block after the trusted block and before the highest block.
+///
+/// A signed block is accepted if the validators of its era who signed it have more than the
+/// quorum weight implied by `fault_tolerance_fraction`, and all the signatures are valid.
+///
+/// Changes to the validator set made by an emergency upgrade are not recorded in switch blocks and
+/// so are not followed.
+pub fn walk_validator_transitions<'a, H, I>(
+    trusted_block_hash: [u8; BLAKE2B_DIGEST_LENGTH],
+    trusted_block_header: &H,
+    trusted_ancestor_headers: &[H],
+    signed_block_headers: I,
+    fault_tolerance_fraction: Ratio<u64>,
+) -> Result<ValidatorTransitions, ValidatorTransitionError>
+where
+    H: EraTransitionHeader + 'a,
+    I: IntoIterator<Item = SignedEraTransitionHeader<'a, H>>,
+{
+    if trusted_block_header.block_hash() != trusted_block_hash {
+        return Err(ValidatorTransitionError::TrustedBlockHashMismatch);
+    }
+
+    let mut switch_block = trusted_block_header
+        .next_era_validator_weights()
+        .map(|validator_weights| (trusted_block_header, validator_weights));
+    let mut child = trusted_block_header;
+    for header in trusted_ancestor_headers {
+        if switch_block.is_some() {
+            break;
+        }
+        if child.parent_hash() != header.block_hash() {
+            return Err(ValidatorTransitionError::AncestorNotParent {
+                height: header.height(),
+            });
+        }
+        switch_block = header
+            .next_era_validator_weights()
+            .map(|validator_weights| (header, validator_weights));
+        child = header;
+    }
+    let (switch_block, validator_weights) =
+        switch_block.ok_or(ValidatorTransitionError::MissingSwitchBlock)?;
+
+    let mut current = EraValidators {
+        era_id: switch_block.era_id().successor(),
+        switch_block_hash: switch_block.block_hash(),
+        validator_weights: validator_weights.clone(),
+    };
+    let mut era_validators = Vec::new();
+    let mut highest_block_hash = trusted_block_hash;
+    let mut highest_block_height = trusted_block_header.height();
+
+    for SignedEraTransitionHeader { header, signatures } in signed_block_headers {
+        let height = header.height();
+        if height <= highest_block_height {
+            return Err(ValidatorTransitionError::HeadersNotAscending { height });
+        }
+        if header.era_id() != current.era_id {
+            return Err(ValidatorTransitionError::UnexpectedEra {
+                height,
+                expected: current.era_id,
+                actual: header.era_id(),
+            });
+        }
+        let block_hash = header.block_hash();
+        check_signatures(
+            height,
+            block_hash,
+            header.era_id(),
+            signatures,
+            &current.validator_weights,
+            fault_tolerance_fraction,
+        )?;
+        highest_block_hash = block_hash;
+        highest_block_height = height;
+
+        if let Some(validator_weights) = header.next_era_validator_weights() {
+            let next = EraValidators {
+                era_id: header.era_id().successor(),
+                switch_block_hash: block_hash,
+                validator_weights: validator_weights.clone(),
+            };
+            era_validators.push(core::mem::replace(&mut current, next));
+        }
+    }
+    era_validators.push(current);
+
+    Ok(ValidatorTransitions {
+        era_validators,
+        highest_block_hash,
+        highest_block_height,
+    })
+}
+
+/// Errors which can arise when verifying the finality signatures of a block.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum FinalitySignatureError {
+    /// A block was signed by a public key which is not a validator of its era.
+    UnknownSigner {
+        /// The public key of the signer.
+        public_key: Box<PublicKey>,
+    },
+    /// A finality signature is cryptographically invalid.
+    InvalidSignature {
+        /// The public key of the signer.
+        public_key: Box<PublicKey>,
+        /// The verification error.
+        error: crypto::Error,
+    },
+    /// The weight of the signers of a block does not exceed the required quorum.
+    InsufficientSignatureWeight {
+        /// The total weight of the signers.
+        signature_weight: Box<U512>,
+        /// The total weight of the validators of the era.
+        total_validator_weight: Box<U512>,
+    },
+}
+
+impl Display for FinalitySignatureError {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            FinalitySignatureError::UnknownSigner { public_key } => {
+                write!(formatter, "block signed by non-validator {}", public_key)
+            }
+            FinalitySignatureError::InvalidSignature { public_key, error } => {
+                write!(formatter, "invalid signature by {}: {}", public_key, error)
+            }
+            FinalitySignatureError::InsufficientSignatureWeight {
+                signature_weight,
+                total_validator_weight,
+            } => write!(
+                formatter,
+                "insufficient signature weight {} of {}",
+                signature_weight, total_validator_weight
+            ),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", test))]
+impl std::error::Error for FinalitySignatureError {}
+
+/// Verifies that a block is finalized, i.e. that it was signed by validators of its era with more
+/// than the quorum weight implied by `fault_tolerance_fraction`, and that all the signatures are
+/// valid.
+pub fn verify_finality_signatures(
+    block_hash: [u8; BLAKE2B_DIGEST_LENGTH],
+    era_id: EraId,
+    signatures: &BTreeMap<PublicKey, Signature>,
+    validator_weights: &BTreeMap<PublicKey, U512>,
+    fault_tolerance_fraction: Ratio<u64>,
+) -> Result<(), FinalitySignatureError> {
+    let mut signature_weight = U512::zero();
+    for public_key in signatures.keys() {
+        match validator_weights.get(public_key) {
+            Some(weight) => signature_weight += *weight,
+            None => {
+                return Err(FinalitySignatureError::UnknownSigner {
+                    public_key: Box::new(public_key.clone()),
+                })
+            }
+        }
+    }
+
+    // The quorum is the lowest weight such that any two sets of validators with that weight have
+    // at least one honest validator in common.
+    let quorum_fraction = (fault_tolerance_fraction + 1) / 2;
+    let total_validator_weight: U512 = validator_weights.values().copied().sum();
+    if signature_weight * U512::from(*quorum_fraction.denom())
+        <= total_validator_weight * U512::from(*quorum_fraction.numer())
+    {
+        return Err(FinalitySignatureError::InsufficientSignatureWeight {
+            signature_weight: Box::new(signature_weight),
+            total_validator_weight: Box::new(total_validator_weight),
+        });
+    }
+
+    // Defer cryptographic verification until last to avoid unnecessary computation.
+    let mut message = block_hash.to_vec();
+    message.extend_from_slice(&era_id.to_le_bytes());
+    for (public_key, signature) in signatures {
+        crypto::verify(&message, signature, public_key).map_err(|error| {
+            FinalitySignatureError::InvalidSignature {
+                public_key: Box::new(public_key.clone()),
+                error,
+            }
+        })?;
+    }
+    Ok(())
+}
+
+/// Verifies the finality signatures of the block at `height`.
+fn check_signatures(
+    height: u64,
+    block_hash: [u8; BLAKE2B_DIGEST_LENGTH],
+    era_id: EraId,
+    signatures: &BTreeMap<PublicKey, Signature>,
+    validator_weights: &BTreeMap<PublicKey, U512>,
+    fault_tolerance_fraction: Ratio<u64>,
+) -> Result<(), ValidatorTransitionError> {
+    verify_finality_signatures(
+        block_hash,
+        era_id,
+        signatures,
+        validator_weights,
+        fault_tolerance_fraction,
+    )
+    .map_err(|error| match error {
+        FinalitySignatureError::UnknownSigner { public_key } => {
+            ValidatorTransitionError::UnknownSigner { height, public_key }
+        }
+        FinalitySignatureError::InvalidSignature { public_key, error } => {
+            ValidatorTransitionError::InvalidSignature {
+                height,
+                public_key,
+                error,
+            }
+        }
+        FinalitySignatureError::InsufficientSignatureWeight {
+            signature_weight,
+            total_validator_weight,
+        } => ValidatorTransitionError::InsufficientSignatureWeight {
+            height,
+            signature_weight,
+            total_validator_weight,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{crypto::SecretKey, testing::TestRng};
+
+    #[derive(Clone)]
+    struct TestHeader {
+        hash: [u8; BLAKE2B_DIGEST_LENGTH],
+        parent_hash: [u8; BLAKE2B_DIGEST_LENGTH],
+        era_id: EraId,
+        height: u64,
+        next_era_validator_weights: Option<BTreeMap<PublicKey, U512>>,
+    }
+
+    impl EraTransitionHeader for TestHeader {
+        fn block_hash(&self) -> [u8; BLAKE2B_DIGEST_LENGTH] {
+            self.hash
+        }
+
+        fn parent_hash(&self) -> [u8; BLAKE2B_DIGEST_LENGTH] {
+            self.parent_hash
+        }
+
+        fn era_id(&self) -> EraId {
+            self.era_id
+        }
+
+        fn height(&self) -> u64 {
+            self.height
+        }
+
+        fn next_era_validator_weights(&self) -> Option<&BTreeMap<PublicKey, U512>> {
+            self.next_era_validator_weights.as_ref()
+        }
+    }
+
+    struct TestChain {
+        headers: Vec<TestHeader>,
+        validators: Vec<Vec<(SecretKey, PublicKey)>>,
+    }
+
+    impl TestChain {
+        /// Creates a chain of `era_count` eras of `era_length` blocks each, the last block of each
+        /// era being a switch block, with a fresh set of three equally-weighted validators per era.
+        fn new(rng: &mut TestRng, era_count: u64, era_length: u64) -> Self {
+            let validators: Vec<Vec<_>> = (0..=era_count)
+                .map(|_| {
+                    (0..3)
+                        .map(|_| {
+                            let secret_key = SecretKey::random(rng);
+                            let public_key = PublicKey::from(&secret_key);
+                            (secret_key, public_key)
+                        })
+                        .collect()
+                })
+                .collect();
+            let mut headers: Vec<TestHeader> = vec![];
+            for height in 0..era_count * era_length {
+                let era = height / era_length;
+                let next_era_validator_weights =
+                    (height % era_length == era_length - 1).then(|| {
+                        validators[era as usize + 1]
+                            .iter()
+                            .map(|(_, public_key)| (public_key.clone(), U512::from(10)))
+                            .collect()
+                    });
+                headers.push(TestHeader {
+                    hash: crypto::blake2b(height.to_le_bytes()),
+                    parent_hash: headers.last().map_or([0; 32], |parent| parent.hash),
+                    era_id: EraId::new(era),
+                    height,
+                    next_era_validator_weights,
+                });
+            }
+            TestChain {
+                headers,
+                validators,
+            }
+        }
+
+        fn sign(&self, height: u64, signer_count: usize) -> BTreeMap<PublicKey, Signature> {
+            let header = &self.headers[height as usize];
+            let mut message = header.hash.to_vec();
+            message.extend_from_slice(&header.era_id.to_le_bytes());
+            self.validators[header.era_id.value() as usize]
+                .iter()
+                .take(signer_count)
+                .map(|(secret_key, public_key)| {
+                    (
+                        public_key.clone(),
+                        crypto::sign(&message, secret_key, public_key),
+                    )
+                })
+                .collect()
+        }
+    }
+
+    fn walk(
+        chain: &TestChain,
+        trusted_height: u64,
+        signed: &[(u64, BTreeMap<PublicKey, Signature>)],
+    ) -> Result<ValidatorTransitions, ValidatorTransitionError> {
+        let trusted = &chain.headers[trusted_height as usize];
+        let ancestors: Vec<TestHeader> = chain.headers[..trusted_height as usize]
+            .iter()
+            .rev()
+            .cloned()
+            .collect();
+        let signed_block_headers =
+            signed
+                .iter()
+                .map(|(height, signatures)| SignedEraTransitionHeader {
+                    header: &chain.headers[*height as usize],
+                    signatures,
+                });
+        walk_validator_transitions(
+            trusted.hash,
+            trusted,
+            &ancestors,
+            signed_block_headers,
+            Ratio::new(1, 3),
+        )
+    }
+
+    #[test]
+    fn should_follow_validator_transitions_to_tip() {
+        let mut rng = TestRng::new();
+        let chain = TestChain::new(&mut rng, 4, 5);
+        let signed: Vec<_> = [9, 14, 17]
+            .iter()
+            .map(|height| (*height, chain.sign(*height, 3)))
+            .collect();
+
+        let transitions = walk(&chain, 6, &signed).expect("should follow transitions");
+
+        let eras: Vec<_> = transitions
+            .era_validators
+            .iter()
+            .map(|era_validators| era_validators.era_id.value())
+            .collect();
+        assert_eq!(eras, vec![1, 2, 3]);
+        assert_eq!(
+            transitions.era_validators[2].switch_block_hash,
+            chain.headers[14].hash
+        );
+        assert_eq!(transitions.highest_block_hash, chain.headers[17].hash);
+        assert_eq!(transitions.highest_block_height, 17);
+    }
+
+    #[test]
+    fn should_reject_invalid_transitions() {
+        let mut rng = TestRng::new();
+        let chain = TestChain::new(&mut rng, 4, 5);
+
+        // A single validator of three does not carry sufficient weight.
+        let signed = vec![(9, chain.sign(9, 1))];
+        assert!(matches!(
+            walk(&chain, 6, &signed),
+            Err(ValidatorTransitionError::InsufficientSignatureWeight { height: 9, .. })
+        ));
+
+        // The switch block of era 1 is missing, so the validators of era 2 are unknown.
+        let signed = vec![(14, chain.sign(14, 3))];
+        assert!(matches!(
+            walk(&chain, 6, &signed),
+            Err(ValidatorTransitionError::UnexpectedEra { height: 14, .. })
+        ));
+
+        // Signatures by the validators of the wrong era are rejected.
+        let mut signatures = chain.sign(9, 3);
+        signatures.extend(chain.sign(14, 1));
+        let signed = vec![(9, signatures)];
+        assert!(matches!(
+            walk(&chain, 6, &signed),
+            Err(ValidatorTransitionError::UnknownSigner { height: 9, .. })
+        ));
+
+        // A forged signature is rejected.
+        let mut signatures = chain.sign(9, 3);
+        let forged = chain.sign(8, 3);
+        let (public_key, _) = signatures.iter().next().unwrap();
+        let public_key = public_key.clone();
+        signatures.insert(public_key.clone(), forged[&public_key]);
+        let signed = vec![(9, signatures)];
+        assert!(matches!(
+            walk(&chain, 6, &signed),
+            Err(ValidatorTransitionError::InvalidSignature { height: 9, .. })
+        ));
+    }
+}
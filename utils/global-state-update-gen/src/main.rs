@@ -1,6 +1,7 @@
 mod admins;
 mod balances;
 mod generic;
+mod state_diff;
 mod system_contract_registry;
 mod utils;
 mod validators;
@@ -10,7 +11,7 @@ use clap::{crate_version, App, Arg, SubCommand};
 
 use crate::{
     balances::generate_balances_update, generic::generate_generic_update,
-    system_contract_registry::generate_system_contract_registry,
+    state_diff::generate_state_diff, system_contract_registry::generate_system_contract_registry,
     validators::generate_validators_update,
 };
 
@@ -184,6 +185,46 @@ fn main() {
                         .number_of_values(1),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("state-diff")
+                .about("Prints the differences between the global state under two state hashes")
+                .arg(
+                    Arg::with_name("data_dir")
+                        .short("d")
+                        .long("data-dir")
+                        .value_name("PATH")
+                        .help("Data storage directory containing the global state database file")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("hash")
+                        .short("s")
+                        .long("state-hash")
+                        .value_name("HEX_STRING")
+                        .help("The global state hash against which differences are reported")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("post_state_hash")
+                        .short("p")
+                        .long("post-state-hash")
+                        .value_name("HEX_STRING")
+                        .help("The global state hash whose differences are reported")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("key_tag")
+                        .short("k")
+                        .long("key-tag")
+                        .value_name("KEY_TAG")
+                        .help("Only report differences under keys of this type, e.g. 'balance'")
+                        .takes_value(true)
+                        .required(false),
+                ),
+        )
         .get_matches();
 
     match matches.subcommand() {
@@ -194,6 +235,7 @@ fn main() {
         }
         ("generic", Some(sub_matches)) => generate_generic_update(sub_matches),
         ("generate-admins", Some(sub_matches)) => generate_admins(sub_matches),
+        ("state-diff", Some(sub_matches)) => generate_state_diff(sub_matches),
         (subcommand, _) => {
             println!("Unknown subcommand: \"{}\"", subcommand);
         }
@@ -0,0 +1,53 @@
+use casper_engine_test_support::LmdbWasmTestBuilder;
+use casper_execution_engine::{
+    core::engine_state::{StateDiffRequest, StateDiffResult, TrieDiff},
+    shared::newtypes::CorrelationId,
+};
+use clap::ArgMatches;
+
+use crate::utils::{hash_from_str, key_tag_from_str, print_entry, print_removed_entry};
+
+/// The number of differences read from global state at a time.
+const PAGE_SIZE: usize = 1_000;
+
+/// Prints the differences between the global state under two state root hashes.
+///
+/// Added and modified values are printed as `[[entries]]` in the same format as the generated
+/// updates, so that the effects of an applied update can be compared against the update file.
+pub(crate) fn generate_state_diff(matches: &ArgMatches<'_>) {
+    let data_dir = matches.value_of("data_dir").unwrap_or(".");
+    let pre_state_hash = hash_from_str(matches.value_of("hash").unwrap());
+    let post_state_hash = hash_from_str(matches.value_of("post_state_hash").unwrap());
+    let key_tag = matches.value_of("key_tag").map(key_tag_from_str);
+
+    let builder = LmdbWasmTestBuilder::open_raw(data_dir, Default::default(), pre_state_hash);
+
+    let mut after = None;
+    loop {
+        let request =
+            StateDiffRequest::new(pre_state_hash, post_state_hash, key_tag, after, PAGE_SIZE);
+        let (diffs, next) = match builder
+            .get_engine_state()
+            .get_state_diff(CorrelationId::new(), request)
+            .expect("should compute state diff")
+        {
+            StateDiffResult::Success { diffs, next } => (diffs, next),
+            StateDiffResult::RootNotFound(state_hash) => {
+                panic!("state root hash {} not found in global state", state_hash)
+            }
+        };
+
+        for diff in diffs {
+            match diff {
+                TrieDiff::Added { key, value } => print_entry(&key, &value),
+                TrieDiff::Modified { key, new_value, .. } => print_entry(&key, &new_value),
+                TrieDiff::Removed { key, value } => print_removed_entry(&key, &value),
+            }
+        }
+
+        match next {
+            Some(key) => after = Some(key),
+            None => break,
+        }
+    }
+}
@@ -6,7 +6,7 @@ use std::{
 use casper_hashing::Digest;
 use casper_types::{
     bytesrepr::ToBytes, checksummed_hex, system::auction::SeigniorageRecipientsSnapshot,
-    AsymmetricType, Key, PublicKey, StoredValue, U512,
+    AsymmetricType, Key, KeyTag, PublicKey, StoredValue, U512,
 };
 
 /// Parses a Digest from a string. Panics if parsing fails.
@@ -34,6 +34,37 @@ pub(crate) fn print_entry(key: &Key, value: &StoredValue) {
     println!();
 }
 
+/// Prints a global state entry which is no longer present in a format ready for inclusion in a
+/// TOML file.
+pub(crate) fn print_removed_entry(key: &Key, value: &StoredValue) {
+    println!("[[removed]]");
+    println!("key = \"{}\"", key.to_formatted_string());
+    println!("value = \"{}\"", base64::encode(value.to_bytes().unwrap()));
+    println!();
+}
+
+/// Parses a KeyTag from its snake case name. Panics if parsing fails.
+pub(crate) fn key_tag_from_str(name: &str) -> KeyTag {
+    match name {
+        "account" => KeyTag::Account,
+        "hash" => KeyTag::Hash,
+        "uref" => KeyTag::URef,
+        "transfer" => KeyTag::Transfer,
+        "deploy_info" => KeyTag::DeployInfo,
+        "era_info" => KeyTag::EraInfo,
+        "balance" => KeyTag::Balance,
+        "bid" => KeyTag::Bid,
+        "withdraw" => KeyTag::Withdraw,
+        "dictionary" => KeyTag::Dictionary,
+        "system_contract_registry" => KeyTag::SystemContractRegistry,
+        "era_summary" => KeyTag::EraSummary,
+        "unbond" => KeyTag::Unbond,
+        "chainspec_registry" => KeyTag::ChainspecRegistry,
+        "checksum_registry" => KeyTag::ChecksumRegistry,
+        _ => panic!("unknown key tag: {}", name),
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub(crate) struct ValidatorInfo {
     pub public_key: PublicKey,